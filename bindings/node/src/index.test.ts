@@ -2,7 +2,22 @@
  * Zantetsu - Jest test suite for heuristic parser
  */
 
-import { HeuristicParser, parse, parseBatch } from '../src/index.js';
+import {
+  ClientContext,
+  HeuristicParser,
+  QualityProfile,
+  QualityScores,
+  Zantetsu,
+  ZantetsuError,
+  backend,
+  isEpisodeRange,
+  isSingleEpisode,
+  isUsingNativeModule,
+  parse,
+  parseBatch,
+  rank,
+  runtime,
+} from '../src/index.js';
 
 describe('HeuristicParser', () => {
   let parser: HeuristicParser;
@@ -28,7 +43,7 @@ describe('HeuristicParser', () => {
 
       expect(result.title).toBe('Jujutsu Kaisen');
       expect(result.group).toBe('SubsPlease');
-      expect(result.episode).toEqual({ type: 'single', episode: 24 });
+      expect(result.episode).toEqual({ kind: 'single', value: 24 });
       expect(result.resolution).toBe('FHD1080');
       expect(result.crc32).toBe('A1B2C3D4');
       expect(result.extension).toBe('mkv');
@@ -41,7 +56,7 @@ describe('HeuristicParser', () => {
       );
 
       expect(result.group).toBe('Erai-raws');
-      expect(result.episode).toEqual({ type: 'versioned', episode: 28, version: 2 });
+      expect(result.episode).toEqual({ kind: 'versioned', value: 28, version: 2 });
       expect(result.resolution).toBe('FHD1080');
       expect(result.video_codec).toBe('HEVC');
     });
@@ -51,15 +66,33 @@ describe('HeuristicParser', () => {
 
       expect(result.group).toBe('Judas');
       expect(result.season).toBe(3);
-      expect(result.episode).toEqual({ type: 'range', start: 1, end: 12 });
+      expect(result.season_end).toBeNull();
+      expect(result.episode).toEqual({ kind: 'range', start: 1, end: 12 });
       expect(result.resolution).toBe('FHD1080');
     });
 
+    it('should narrow episodes with type guards', () => {
+      const { episode } = parser.parse('[Judas] Golden Kamuy S3 - 01-12 (1080p) [Batch]');
+
+      expect(isSingleEpisode(episode)).toBe(false);
+      expect(isEpisodeRange(episode)).toBe(true);
+      if (isEpisodeRange(episode)) {
+        expect(episode.end - episode.start).toBe(11);
+      }
+    });
+
+    it('should parse season batch range', () => {
+      const result = parser.parse('[Judas] Attack on Titan S01-S03 Complete [1080p][HEVC]');
+
+      expect(result.season).toBe(1);
+      expect(result.season_end).toBe(3);
+    });
+
     it('should parse dot-separated format', () => {
       const result = parser.parse('One.Piece.1084.VOSTFR.1080p.WEB.x264-AAC.mkv');
 
       expect(result.title).toBe('One Piece');
-      expect(result.episode).toEqual({ type: 'single', episode: 1084 });
+      expect(result.episode).toEqual({ kind: 'single', value: 1084 });
       expect(result.resolution).toBe('FHD1080');
       expect(result.video_codec).toBe('H264');
       expect(result.audio_codec).toBe('AAC');
@@ -122,12 +155,39 @@ describe('HeuristicParser', () => {
       expect(() => parser.parse('   ')).toThrow();
     });
 
+    it('should report a stable error code', () => {
+      expect(() => parser.parse('   ')).toThrow(ZantetsuError);
+      expect(() => parser.parse('   ')).toThrow(expect.objectContaining({ code: 'EMPTY_INPUT' }));
+      expect(() => parser.parse(42 as never)).toThrow(
+        expect.objectContaining({ code: 'INVALID_ARGUMENT' }),
+      );
+    });
+
     it('should return correct parse mode', () => {
       const result = parser.parse('[Test] Title - 01 (1080p).mkv');
       expect(result.parse_mode).toBe('Light');
     });
   });
 
+  describe('parseMany()', () => {
+    it('should keep input order and return null for failures', () => {
+      const inputs = [
+        '[SubsPlease] Jujutsu Kaisen - 24 (1080p).mkv',
+        '',
+        '[Erai-raws] One Piece - 1000 [1080p].mkv',
+      ];
+
+      for (const parallel of [false, true]) {
+        const results = parser.parseMany(inputs, { parallel });
+
+        expect(results).toHaveLength(3);
+        expect(results[0]?.title).toBe('Jujutsu Kaisen');
+        expect(results[1]).toBeNull();
+        expect(results[2]?.title).toBe('One Piece');
+      }
+    });
+  });
+
   describe('parseBatch()', () => {
     it('should parse multiple inputs', () => {
       const inputs = [
@@ -146,12 +206,131 @@ describe('HeuristicParser', () => {
   });
 });
 
+describe('Zantetsu', () => {
+  it('should parse like the heuristic parser when no model is loaded', () => {
+    const parser = new Zantetsu({ mode: 'auto', confidenceThreshold: 0.7 });
+    const result = parser.parse('[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv');
+
+    expect(result.title).toBe('Jujutsu Kaisen');
+    expect(result.group).toBe('SubsPlease');
+    expect(result.resolution).toBe('FHD1080');
+    if (!parser.hasNeural) {
+      expect(result.parse_mode).toBe('Light');
+    }
+  });
+
+  it('should return null for inputs that fail to parse in a batch', () => {
+    const parser = new Zantetsu();
+    const results = parser.parseBatch(['[Test] Anime - 01 (1080p).mkv', '   ']);
+
+    expect(results).toHaveLength(2);
+    expect(results[0]?.title).toBe('Anime');
+    expect(results[1]).toBeNull();
+  });
+
+  it('should reject empty input', () => {
+    expect(() => new Zantetsu().parse('')).toThrow();
+  });
+
+  it('should rebuild a shared parser from its handle', () => {
+    const parser = new Zantetsu();
+    const shared = Zantetsu.fromShared(parser.share());
+
+    expect(shared.mode).toBe(parser.mode);
+    expect(shared.parse('[Test] Anime - 01 (1080p).mkv').title).toBe('Anime');
+  });
+
+  it('should parse asynchronously', async () => {
+    const result = await new Zantetsu().parseAsync('[SubsPlease] Jujutsu Kaisen - 24 (1080p).mkv');
+    expect(result.title).toBe('Jujutsu Kaisen');
+  });
+
+  it('should reject an aborted async parse', async () => {
+    const controller = new AbortController();
+    controller.abort();
+    await expect(
+      new Zantetsu().parseAsync('[SubsPlease] Jujutsu Kaisen - 24 (1080p).mkv', {
+        signal: controller.signal,
+      }),
+    ).rejects.toThrow();
+  });
+});
+
+const describeNative = isUsingNativeModule() ? describe : describe.skip;
+
+describeNative('parseBuffer()', () => {
+  const parser = new HeuristicParser();
+
+  it('should parse a buffer of lines into NDJSON', () => {
+    const input = Buffer.from('[Group] Title - 01 (1080p).mkv\r\n\n[Group] Other - 02.mkv\n');
+
+    for (const parallel of [false, true]) {
+      const lines = parser.parseBuffer(input, { parallel }).toString('utf8').trimEnd().split('\n');
+      const results = lines.map(line => JSON.parse(line));
+
+      expect(results).toHaveLength(3);
+      expect(results[0].title).toBe('Title');
+      expect(results[0].episode).toEqual({ Single: 1 });
+      expect(results[1]).toBeNull();
+      expect(results[2].title).toBe('Other');
+    }
+  });
+});
+
+describeNative('parseJson()', () => {
+  it('should return the full Rust ParseResult as JSON', () => {
+    for (const parser of [new HeuristicParser(), new Zantetsu({ mode: 'light' })]) {
+      const result = JSON.parse(parser.parseJson('[Group] Title - 01 (1080p) [ABCD1234].mkv'));
+
+      expect(result.title).toBe('Title');
+      expect(result.episode).toEqual({ Single: 1 });
+      expect(result.crc32).toBe('ABCD1234');
+      expect(() => parser.parseJson('  ')).toThrow(ZantetsuError);
+    }
+  });
+});
+
+describeNative('Scoring', () => {
+  const parser = new HeuristicParser();
+
+  it('should rank releases for the client context', () => {
+    const results = parser.parseBatch([
+      '[Group] Title - 01 (1080p) [AV1].mkv',
+      '[Group] Title - 01 (1080p) [x264].mkv',
+    ]);
+    const profile = QualityProfile.preset('streaming');
+
+    const h264Only = ClientContext.fromJson({ hw_decode_codecs: ['H264'] });
+    expect(rank(results, profile, h264Only)[0].index).toBe(1);
+
+    const scores = QualityScores.fromResult(results[0]);
+    expect(scores.compute(profile, h264Only)).toBeLessThan(scores.compute(profile));
+  });
+
+  it('should reject unknown presets', () => {
+    expect(() => QualityProfile.preset('cinema' as never)).toThrow(
+      expect.objectContaining({ code: 'INVALID_ARGUMENT' }),
+    );
+    expect(() => ClientContext.fromJson({ hdr_capable: 'yes' })).toThrow(
+      expect.objectContaining({ code: 'INVALID_CONTEXT' }),
+    );
+  });
+});
+
+describe('Runtime detection', () => {
+  it('should detect Node.js and report the engine it loaded', () => {
+    expect(runtime).toBe('node');
+    expect(['native', 'wasm', 'js']).toContain(backend());
+    expect(backend() === 'native').toBe(isUsingNativeModule());
+  });
+});
+
 describe('Convenience functions', () => {
   describe('parse()', () => {
     it('should work as a convenience function', () => {
       const result = parse('[Test] Anime - 01 (1080p).mkv');
       expect(result.title).toBe('Anime');
-      expect(result.episode).toEqual({ type: 'single', episode: 1 });
+      expect(result.episode).toEqual({ kind: 'single', value: 1 });
     });
   });
 
@@ -1,16 +1,33 @@
 /**
  * Zantetsu - Fast anime metadata parser
  *
- * JavaScript/TypeScript bindings for the heuristic parser engine.
+ * JavaScript/TypeScript bindings for the heuristic parser engine and the
+ * unified (neural with heuristic fallback) parser.
  * Provides both a class-based API and convenience functions.
- * Includes a pure JavaScript fallback when native module is unavailable.
+ * Runs on Node.js, Bun and Deno: loads the native addon where the runtime
+ * can, else the bundled WebAssembly build, else a pure JavaScript fallback.
  */
 
+import { createRequire } from 'node:module';
+import { arch, platform } from 'node:process';
+
 // Import types from the declaration file
 import type { 
   HeuristicParserOptions, 
+  ZantetsuOptions,
+  ParseManyOptions,
+  ParseAsyncOptions,
+  QualityPreset,
+  RankedResult,
+  ErrorCode,
   ParseResult, 
   EpisodeSpec,
+  SingleEpisode,
+  EpisodeRange,
+  MultiEpisode,
+  VersionedEpisode,
+  SpecialEpisode,
+  EpisodeZero,
   Resolution,
   VideoCodec,
   AudioCodec,
@@ -18,9 +35,55 @@ import type {
   ParseMode
 } from './types.js';
 
+/**
+ * JavaScript runtime the package runs on
+ */
+export type Runtime = 'node' | 'bun' | 'deno' | 'unknown';
+
+/**
+ * Engine behind the parsers: the native addon, its WebAssembly build
+ * (heuristic engine only) or the JavaScript fallback
+ */
+export type Backend = 'native' | 'wasm' | 'js';
+
+function detectRuntime(): Runtime {
+  const global = globalThis as {
+    Bun?: unknown;
+    Deno?: unknown;
+    process?: { versions?: { node?: string } };
+  };
+  // Bun and Deno also report a Node.js version for compatibility
+  if (global.Bun) return 'bun';
+  if (global.Deno) return 'deno';
+  return global.process?.versions?.node ? 'node' : 'unknown';
+}
+
+/** The runtime detected when the package was loaded */
+export const runtime: Runtime = detectRuntime();
+
+/**
+ * Whether the runtime may load a Node-API addon: Deno needs `--allow-ffi`,
+ * and would prompt for it instead of failing
+ */
+function canLoadAddon(): boolean {
+  if (runtime !== 'deno') return true;
+  const { Deno } = globalThis as unknown as {
+    Deno: { permissions: { querySync?: (descriptor: { name: string }) => { state: string } } };
+  };
+  return Deno.permissions.querySync?.({ name: 'ffi' }).state === 'granted';
+}
+
+type WasmModule = {
+  HeuristicParser: new () => {
+    parse: (input: string) => unknown;
+    parseBatch: (inputs: string[]) => unknown[];
+  };
+};
+
 // Track if native module is available
 let useNative = false;
 let nativeModule: unknown = null;
+let wasmModule: WasmModule | null = null;
 let initialized = false;
 
 /**
@@ -30,15 +93,89 @@ function initNative(): void {
   if (initialized) return;
   initialized = true;
 
+  // `require` of an ES module, which Node.js, Bun and Deno all provide
+  const load = createRequire(import.meta.url);
+  const candidates = [
+    '@zan/heuristic-node',
+    `../prebuilds/${platform}-${arch}/zantetsu.node`,
+  ];
+  for (const candidate of canLoadAddon() ? candidates : []) {
+    try {
+      nativeModule = load(candidate);
+      useNative = true;
+      return;
+    } catch {
+      // Try the next build
+    }
+  }
+
   try {
-    // Try to load the native addon
-    // eslint-disable-next-line @typescript-eslint/no-require-imports
-    nativeModule = require('@zan/heuristic-node');
-    useNative = true;
+    wasmModule = load('../wasm/zantetsu.js') as WasmModule;
   } catch {
     // Use JavaScript fallback
-    nativeModule = null;
-    useNative = false;
+    wasmModule = null;
+  }
+}
+
+/**
+ * ZantetsuError - an error with a stable, machine-readable code
+ *
+ * Thrown for every failure of the parsers and the scoring API, so callers can
+ * branch on `code` (e.g. EMPTY_INPUT vs MODEL_LOAD_ERROR) instead of messages.
+ */
+export class ZantetsuError extends Error {
+  readonly code: ErrorCode;
+
+  constructor(code: ErrorCode, message: string) {
+    super(message);
+    this.name = 'ZantetsuError';
+    this.code = code;
+  }
+}
+
+/**
+ * Rethrow an error of the native module, which carries its code as
+ * `err.code`, as a ZantetsuError; other errors (e.g. AbortError) pass through
+ */
+function toZantetsuError(error: unknown): unknown {
+  if (error instanceof ZantetsuError || !(error instanceof Error)) {
+    return error;
+  }
+  const code = (error as { code?: unknown }).code;
+  return typeof code === 'string' && /^[A-Z_]+$/.test(code)
+    ? new ZantetsuError(code as ErrorCode, error.message)
+    : error;
+}
+
+/**
+ * Run a native call, rethrowing its errors as ZantetsuError
+ */
+function coded<T>(call: () => T): T {
+  try {
+    return call();
+  } catch (error) {
+    throw toZantetsuError(error);
+  }
+}
+
+/**
+ * Reject unless `input` is a non-empty string
+ */
+function checkInput(input: unknown): void {
+  if (typeof input !== 'string') {
+    throw new ZantetsuError('INVALID_ARGUMENT', 'Input must be a string');
+  }
+  if (!input.trim()) {
+    throw new ZantetsuError('EMPTY_INPUT', 'input is empty or whitespace-only');
+  }
+}
+
+/**
+ * Reject unless `inputs` is an array
+ */
+function checkInputs(inputs: unknown): void {
+  if (!Array.isArray(inputs)) {
+    throw new ZantetsuError('INVALID_ARGUMENT', 'Input must be an array of strings');
   }
 }
 
@@ -56,6 +193,7 @@ class JsHeuristicParser {
   private readonly reEpisodeV = /(?:[\s\-.])(?:[Ee]p?\.?)?\s*(\d{1,4})v(\d+)/i;
   private readonly reEpisodeRange = /(?:[\s\-.])(?:[Ee]p?\.?)?\s*(\d{1,4})\s*[-~]\s*(\d{1,4})/i;
   private readonly reSeason = /(?:^|[\s\-])S(\d+)/i;
+  private readonly reSeasonRange = /\bS(\d{1,2})\s*[-~]\s*S(\d{1,2})\b|\bseasons\s*(\d{1,2})\s*[-~]\s*(\d{1,2})\b/i;
   private readonly reYear = /\((\d{4})\)/;
   private readonly reExtension = /\.(\w+)$/;
   private readonly reCrc32 = /\[([A-Fa-f0-9]{8})\]/;
@@ -72,7 +210,7 @@ class JsHeuristicParser {
   parse(input: string): ParseResult {
     const trimmed = input.trim();
     if (!trimmed) {
-      throw new Error('input is empty or whitespace-only');
+      throw new ZantetsuError('EMPTY_INPUT', 'input is empty or whitespace-only');
     }
 
     const result: ParseResult = {
@@ -81,6 +219,7 @@ class JsHeuristicParser {
       group: null,
       episode: null,
       season: null,
+      season_end: null,
       resolution: null,
       video_codec: null,
       audio_codec: null,
@@ -98,7 +237,12 @@ class JsHeuristicParser {
     result.extension = this.extractExtension(trimmed);
     result.crc32 = this.extractCrc32(trimmed);
     result.resolution = this.extractResolution(trimmed);
-    result.season = this.extractSeason(trimmed);
+    const seasonRange = this.extractSeasonRange(trimmed);
+    if (seasonRange) {
+      [result.season, result.season_end] = seasonRange;
+    } else {
+      result.season = this.extractSeason(trimmed);
+    }
     result.year = this.extractYear(trimmed);
     result.episode = this.extractEpisode(trimmed);
     result.video_codec = this.extractVideoCodec(trimmed);
@@ -142,6 +286,14 @@ class JsHeuristicParser {
     return match ? parseInt(match[1], 10) : null;
   }
 
+  private extractSeasonRange(input: string): [number, number] | null {
+    const match = this.reSeasonRange.exec(input);
+    if (!match) return null;
+    const start = parseInt(match[1] ?? match[3], 10);
+    const end = parseInt(match[2] ?? match[4], 10);
+    return start < end ? [start, end] : null;
+  }
+
   private extractYear(input: string): number | null {
     const match = this.reYear.exec(input);
     return match ? parseInt(match[1], 10) : null;
@@ -151,7 +303,7 @@ class JsHeuristicParser {
     // Try versioned episode
     let match = this.reEpisodeV.exec(input);
     if (match) {
-      return { type: 'versioned', episode: parseInt(match[1], 10), version: parseInt(match[2], 10) };
+      return { kind: 'versioned', value: parseInt(match[1], 10), version: parseInt(match[2], 10) };
     }
 
     // Try episode range
@@ -160,14 +312,14 @@ class JsHeuristicParser {
       const start = parseInt(match[1], 10);
       const end = parseInt(match[2], 10);
       if (start < end) {
-        return { type: 'range', start, end };
+        return { kind: 'range', start, end };
       }
     }
 
     // Try single episode
     match = this.reEpisode.exec(input);
     if (match) {
-      return { type: 'single', episode: parseInt(match[1], 10) };
+      return { kind: 'single', value: parseInt(match[1], 10) };
     }
 
     return null;
@@ -294,19 +446,113 @@ class JsHeuristicParser {
   }
 }
 
+/**
+ * A parser instance: the native addon's or the JavaScript fallback.
+ * Only native parsers have `parseMany`.
+ */
+type AnyParser = {
+  parse: (input: string) => unknown;
+  parseMany?: (inputs: string[], parallel?: boolean) => unknown[];
+  parseBuffer?: (inputs: Uint8Array, parallel?: boolean) => Buffer;
+  parseJson?: (input: string) => string;
+  share?: () => number;
+};
+
+/**
+ * The WebAssembly build of the heuristic engine behind the native parser
+ * interface: its results are the Rust ParseResult as is, reshaped here like
+ * the addon's
+ */
+class WasmParser {
+  private readonly inner = new wasmModule!.HeuristicParser();
+
+  parse(input: string): unknown {
+    return fromRustResult(this.inner.parse(input));
+  }
+
+  parseMany(inputs: string[]): unknown[] {
+    return this.inner.parseBatch(inputs).map(result => (result ? fromRustResult(result) : null));
+  }
+
+  parseJson(input: string): string {
+    return JSON.stringify(this.inner.parse(input));
+  }
+}
+
+/**
+ * A Rust ParseResult (as serde writes it) shaped like a result of the addon
+ */
+function fromRustResult(rust: unknown): unknown {
+  const r = rust as Record<string, unknown>;
+  const season = r.season as { Single?: number; Range?: [number, number] } | null;
+  return {
+    ...r,
+    episode: fromRustEpisode(r.episode),
+    season: season?.Single ?? season?.Range?.[0] ?? null,
+    season_end: season?.Range?.[1] ?? null,
+  };
+}
+
+function fromRustEpisode(episode: unknown): unknown {
+  if (episode === 'Zero') return { kind: 'zero' };
+  if (!episode || typeof episode !== 'object') return null;
+
+  const e = episode as Record<string, unknown>;
+  if ('Single' in e) return { kind: 'single', value: e.Single };
+  if ('Range' in e) {
+    const [start, end] = e.Range as [number, number];
+    return { kind: 'range', start, end };
+  }
+  if ('Multi' in e) return { kind: 'multi', values: e.Multi };
+  if ('Version' in e) {
+    const { episode: value, version } = e.Version as { episode: number; version: number };
+    return { kind: 'versioned', value, version };
+  }
+  if ('Special' in e) return { kind: 'special', value: e.Special };
+  return null;
+}
+
+/**
+ * Check the argument of parseBuffer() and that the native module can take it
+ */
+function checkBuffer(inputs: unknown, parser: Pick<AnyParser, 'parseBuffer'> | null): void {
+  if (!(inputs instanceof Uint8Array)) {
+    throw new ZantetsuError('INVALID_ARGUMENT', 'Input must be a Buffer or Uint8Array');
+  }
+  if (!parser?.parseBuffer) {
+    throw new Error('parseBuffer requires the native module');
+  }
+}
+
+/**
+ * The parser behind parseJson(), which needs the native or WebAssembly module
+ */
+function jsonParser(parser: Pick<AnyParser, 'parseJson'> | null): Required<Pick<AnyParser, 'parseJson'>> {
+  if (!parser?.parseJson) {
+    throw new Error('parseJson requires the native or WebAssembly module');
+  }
+  return parser as Required<Pick<AnyParser, 'parseJson'>>;
+}
+
 // Singleton instances
 let jsParser: JsHeuristicParser | null = null;
 let nativeParser: unknown = null;
+let wasmParser: WasmParser | null = null;
 
-function getParser(): { parse: (input: string) => unknown } {
+function getParser(): AnyParser {
   initNative();
 
   if (useNative) {
     if (!nativeParser) {
       // eslint-disable-next-line new-cap
-      nativeParser = new (nativeModule as { HeuristicParser: new () => { parse: (s: string) => unknown } }).HeuristicParser();
+      nativeParser = new (nativeModule as { HeuristicParser: new () => AnyParser }).HeuristicParser();
     }
-    return nativeParser as { parse: (s: string) => unknown };
+    return nativeParser as AnyParser;
+  }
+
+  if (wasmModule) {
+    wasmParser ??= new WasmParser();
+    return wasmParser;
   }
 
   if (!jsParser) {
@@ -316,41 +562,59 @@ function getParser(): { parse: (input: string) => unknown } {
 }
 
 /**
- * Convert native episode spec to typed EpisodeSpec
+ * Convert native episode spec to typed EpisodeSpec, keeping only the fields
+ * of its kind
  */
 function convertEpisodeSpec(native: unknown): EpisodeSpec | null {
   if (!native) return null;
 
-  if (typeof native === 'number') {
-    return { type: 'single', episode: native };
+  const n = native as Record<string, unknown>;
+  switch (n.kind) {
+    case 'single':
+      return { kind: 'single', value: n.value as number };
+    case 'range':
+      return { kind: 'range', start: n.start as number, end: n.end as number };
+    case 'multi':
+      return { kind: 'multi', values: n.values as number[] };
+    case 'versioned':
+      return { kind: 'versioned', value: n.value as number, version: n.version as number };
+    case 'special':
+      return { kind: 'special', value: n.value as number };
+    case 'zero':
+      return { kind: 'zero' };
+    default:
+      return null;
   }
+}
 
-  const n = native as Record<string, unknown>;
+/** Whether `spec` is a single episode */
+export function isSingleEpisode(spec: EpisodeSpec | null | undefined): spec is SingleEpisode {
+  return spec?.kind === 'single';
+}
 
-  if (typeof n.type === 'number') {
-    switch (n.type) {
-      case 0:
-        return { type: 'single', episode: n.episode as number };
-      case 1:
-        return { type: 'range', start: n.start as number, end: n.end as number };
-      case 2:
-        return { type: 'multi', episodes: n.episodes as number[] };
-      case 3:
-        return { type: 'versioned', episode: n.episode as number, version: n.version as number };
-    }
-  }
+/** Whether `spec` is an episode range */
+export function isEpisodeRange(spec: EpisodeSpec | null | undefined): spec is EpisodeRange {
+  return spec?.kind === 'range';
+}
 
-  if (n.episode !== undefined && n.version !== undefined) {
-    return { type: 'versioned', episode: n.episode as number, version: n.version as number };
-  }
-  if (n.start !== undefined && n.end !== undefined) {
-    return { type: 'range', start: n.start as number, end: n.end as number };
-  }
-  if (n.episodes !== undefined) {
-    return { type: 'multi', episodes: n.episodes as number[] };
-  }
+/** Whether `spec` lists discrete episodes */
+export function isMultiEpisode(spec: EpisodeSpec | null | undefined): spec is MultiEpisode {
+  return spec?.kind === 'multi';
+}
 
-  return null;
+/** Whether `spec` is a versioned episode */
+export function isVersionedEpisode(spec: EpisodeSpec | null | undefined): spec is VersionedEpisode {
+  return spec?.kind === 'versioned';
+}
+
+/** Whether `spec` is a numbered special */
+export function isSpecialEpisode(spec: EpisodeSpec | null | undefined): spec is SpecialEpisode {
+  return spec?.kind === 'special';
+}
+
+/** Whether `spec` is episode zero */
+export function isEpisodeZero(spec: EpisodeSpec | null | undefined): spec is EpisodeZero {
+  return spec?.kind === 'zero';
 }
 
 /**
@@ -358,12 +622,13 @@ function convertEpisodeSpec(native: unknown): EpisodeSpec | null {
  */
 function convertResult(native: unknown): ParseResult {
   const n = native as Record<string, unknown>;
-  return {
+  const result: ParseResult = {
     input: n.input as string,
     title: n.title as string | null,
     group: n.group as string | null,
     episode: convertEpisodeSpec(n.episode),
     season: n.season as number | null,
+    season_end: (n.season_end ?? null) as number | null,
     resolution: n.resolution as Resolution | null,
     video_codec: n.video_codec as VideoCodec | null,
     audio_codec: n.audio_codec as AudioCodec | null,
@@ -375,6 +640,156 @@ function convertResult(native: unknown): ParseResult {
     confidence: n.confidence as number,
     parse_mode: n.parse_mode as ParseMode,
   };
+  // Keep the native result for the scoring API, out of sight of JSON and spreads
+  Object.defineProperty(result, NATIVE_RESULT, { value: native });
+  return result;
+}
+
+/**
+ * Key of the native result behind a converted ParseResult
+ */
+const NATIVE_RESULT = Symbol('zantetsu.nativeResult');
+
+function nativeResultOf(result: ParseResult): unknown {
+  const native = (result as unknown as Record<symbol, unknown>)[NATIVE_RESULT];
+  if (!native) {
+    throw new ZantetsuError('INVALID_ARGUMENT', 'Scoring needs a result parsed by the native module');
+  }
+  return native;
+}
+
+/**
+ * Export `name` of the native module; the scoring API has no JavaScript fallback
+ */
+function requireNative<T>(name: string): T {
+  initNative();
+  if (!useNative) {
+    throw new Error(`${name} requires the native module`);
+  }
+  return (nativeModule as Record<string, T>)[name];
+}
+
+type NativeJson = { toJson: () => string };
+
+type NativeProfileClass = {
+  new (): NativeJson;
+  preset: (name: string) => NativeJson;
+  fromJson: (json: string) => NativeJson;
+  fromToml: (toml: string) => NativeJson;
+};
+
+type NativeContextClass = {
+  new (): NativeJson;
+  fromJson: (json: string) => NativeJson;
+  fromToml: (toml: string) => NativeJson;
+};
+
+type NativeScores = NativeJson & {
+  compute: (profile: NativeJson, context?: NativeJson) => number;
+  explain: (profile: NativeJson, context?: NativeJson) => string;
+};
+
+/**
+ * QualityProfile - the weight of each quality dimension
+ */
+export class QualityProfile {
+  /** @internal */
+  readonly native: NativeJson;
+
+  /** The default weights */
+  constructor(native?: NativeJson) {
+    this.native = native ?? new (requireNative<NativeProfileClass>('QualityProfile'))();
+  }
+
+  /** Tuned weights for a common use case */
+  static preset(name: QualityPreset): QualityProfile {
+    return new QualityProfile(coded(() => requireNative<NativeProfileClass>('QualityProfile').preset(name)));
+  }
+
+  /** Weights from JSON (or an object); missing weights keep their defaults */
+  static fromJson(json: string | object): QualityProfile {
+    const text = typeof json === 'string' ? json : JSON.stringify(json);
+    return new QualityProfile(coded(() => requireNative<NativeProfileClass>('QualityProfile').fromJson(text)));
+  }
+
+  /** Weights from a TOML document; missing weights keep their defaults */
+  static fromToml(toml: string): QualityProfile {
+    return new QualityProfile(coded(() => requireNative<NativeProfileClass>('QualityProfile').fromToml(toml)));
+  }
+
+  toJSON(): Record<string, unknown> {
+    return JSON.parse(this.native.toJson());
+  }
+}
+
+/**
+ * ClientContext - the playback device, network and decoders scores are adjusted for
+ */
+export class ClientContext {
+  /** @internal */
+  readonly native: NativeJson;
+
+  /** A desktop on an unlimited network decoding H.264 and HEVC */
+  constructor(native?: NativeJson) {
+    this.native = native ?? new (requireNative<NativeContextClass>('ClientContext'))();
+  }
+
+  /** A context with the fields of the Rust ClientContext; missing fields keep their defaults */
+  static fromJson(json: string | object): ClientContext {
+    const text = typeof json === 'string' ? json : JSON.stringify(json);
+    return new ClientContext(coded(() => requireNative<NativeContextClass>('ClientContext').fromJson(text)));
+  }
+
+  /** A context from a TOML document */
+  static fromToml(toml: string): ClientContext {
+    return new ClientContext(coded(() => requireNative<NativeContextClass>('ClientContext').fromToml(toml)));
+  }
+
+  toJSON(): Record<string, unknown> {
+    return JSON.parse(this.native.toJson());
+  }
+}
+
+/**
+ * QualityScores - per-dimension quality scores of one release
+ */
+export class QualityScores {
+  private constructor(private readonly native: NativeScores) {}
+
+  /** Scores of a result from HeuristicParser or Zantetsu */
+  static fromResult(result: ParseResult): QualityScores {
+    const Native = requireNative<{ fromResult: (result: unknown) => NativeScores }>('QualityScores');
+    return new QualityScores(Native.fromResult(nativeResultOf(result)));
+  }
+
+  /** The weighted score under `profile`, adjusted for `context` when given */
+  compute(profile: QualityProfile, context?: ClientContext): number {
+    return this.native.compute(profile.native, context?.native);
+  }
+
+  /** Per-dimension breakdown of the score (the Rust ScoreReport) */
+  explain(profile: QualityProfile, context?: ClientContext): Record<string, unknown> {
+    return JSON.parse(this.native.explain(profile.native, context?.native));
+  }
+
+  toJSON(): Record<string, unknown> {
+    return JSON.parse(this.native.toJson());
+  }
+}
+
+/**
+ * Rank results of alternative releases, best first, with the Rust selection
+ * logic; `context` defaults to a desktop
+ */
+export function rank(
+  results: ParseResult[],
+  profile: QualityProfile,
+  context?: ClientContext,
+): RankedResult[] {
+  const nativeRank = requireNative<
+    (results: unknown[], profile: NativeJson, context?: NativeJson) => RankedResult[]
+  >('rank');
+  return nativeRank(results.map(nativeResultOf), profile.native, context?.native);
 }
 
 /**
@@ -384,20 +799,40 @@ function convertResult(native: unknown): ParseResult {
  * instant parsing with zero ML overhead.
  */
 export class HeuristicParser {
-  private parser: { parse: (input: string) => unknown };
+  private parser: AnyParser;
 
   constructor(_options?: HeuristicParserOptions) {
     this.parser = getParser();
   }
 
-  parse(input: string): ParseResult {
-    if (typeof input !== 'string' || !input.trim()) {
-      throw new Error('Input must be a non-empty string');
+  /**
+   * A handle for another worker thread to rebuild this parser with
+   * fromShared(); post it with postMessage. Valid while this parser is alive
+   */
+  share(): number {
+    return this.parser.share ? this.parser.share() : 0;
+  }
+
+  /**
+   * The parser behind a handle from share(), in this worker thread
+   */
+  static fromShared(handle: number): HeuristicParser {
+    const parser = new HeuristicParser();
+    if (useNative) {
+      const { HeuristicParser: NativeClass } = nativeModule as {
+        HeuristicParser: { fromShared: (handle: number) => AnyParser };
+      };
+      parser.parser = coded(() => NativeClass.fromShared(handle));
     }
+    return parser;
+  }
 
-    const result = this.parser.parse(input);
+  parse(input: string): ParseResult {
+    checkInput(input);
 
-    if (!useNative) {
+    const result = coded(() => this.parser.parse(input));
+
+    if (this.parser instanceof JsHeuristicParser) {
       return result as ParseResult;
     }
 
@@ -405,12 +840,193 @@ export class HeuristicParser {
   }
 
   parseBatch(inputs: string[]): ParseResult[] {
-    if (!Array.isArray(inputs)) {
-      throw new Error('Input must be an array of strings');
-    }
+    checkInputs(inputs);
 
     return inputs.map(input => this.parse(input));
   }
+
+  /**
+   * Parse many filenames in a single native call (optionally in parallel);
+   * failed parses are null
+   */
+  parseMany(inputs: string[], options?: ParseManyOptions): (ParseResult | null)[] {
+    checkInputs(inputs);
+
+    if (this.parser.parseMany) {
+      return this.parser
+        .parseMany(inputs, options?.parallel ?? false)
+        .map(result => (result ? convertResult(result) : null));
+    }
+    return inputs.map(input => {
+      try {
+        return this.parse(input);
+      } catch {
+        return null;
+      }
+    });
+  }
+
+  /**
+   * Parse a buffer of newline-delimited filenames in a single native call
+   * (optionally in parallel). Returns newline-delimited JSON of the Rust
+   * ParseResult, one line per input, `null` for failures
+   */
+  parseBuffer(inputs: Uint8Array, options?: ParseManyOptions): Buffer {
+    checkBuffer(inputs, useNative ? this.parser : null);
+    return this.parser.parseBuffer!(inputs, options?.parallel ?? false);
+  }
+
+  /**
+   * Parse into the JSON of the full Rust ParseResult, every field included,
+   * ready to forward or store as is
+   */
+  parseJson(input: string): string {
+    checkInput(input);
+    const parser = jsonParser(this.parser);
+    return coded(() => parser.parseJson(input));
+  }
+}
+
+type NativeZantetsu = {
+  share: () => number;
+  parse: (input: string) => unknown;
+  parseMany: (inputs: string[], parallel?: boolean) => unknown[];
+  parseBuffer: (inputs: Uint8Array, parallel?: boolean) => Buffer;
+  parseJson: (input: string) => string;
+  parseAsync: (input: string, signal?: AbortSignal) => Promise<unknown>;
+  mode: string;
+  hasNeural: boolean;
+};
+
+/**
+ * Zantetsu - the unified parser
+ *
+ * Runs the neural model when it is available and falls back to the
+ * heuristic engine otherwise, like the Rust `Parser`. Without the native
+ * module it always uses the WebAssembly or JavaScript heuristic parser.
+ */
+export class Zantetsu {
+  private readonly native: NativeZantetsu | null;
+  private readonly fallback: HeuristicParser | null;
+
+  constructor(options?: ZantetsuOptions) {
+    initNative();
+
+    if (useNative) {
+      const { Zantetsu: NativeClass } = nativeModule as {
+        Zantetsu: new (options?: ZantetsuOptions) => NativeZantetsu;
+      };
+      this.native = coded(() => new NativeClass(options));
+      this.fallback = null;
+    } else {
+      this.native = null;
+      this.fallback = new HeuristicParser();
+    }
+  }
+
+  /**
+   * A handle for another worker thread to rebuild this parser with
+   * fromShared(), so workers share one loaded model instead of loading it
+   * each; post it with postMessage. Valid while this parser is alive
+   */
+  share(): number {
+    return this.native ? this.native.share() : 0;
+  }
+
+  /**
+   * The parser behind a handle from share(), in this worker thread
+   */
+  static fromShared(handle: number): Zantetsu {
+    initNative();
+    if (!useNative) {
+      return new Zantetsu();
+    }
+    const { Zantetsu: NativeClass } = nativeModule as {
+      Zantetsu: { fromShared: (handle: number) => NativeZantetsu };
+    };
+    const parser = Object.create(Zantetsu.prototype) as Zantetsu;
+    Object.assign(parser, { native: coded(() => NativeClass.fromShared(handle)), fallback: null });
+    return parser;
+  }
+
+  /** The configured parse mode ('Light' without the native module) */
+  get mode(): ParseMode {
+    return this.native ? (this.native.mode as ParseMode) : 'Light';
+  }
+
+  /** Whether the neural model is loaded */
+  get hasNeural(): boolean {
+    return this.native ? this.native.hasNeural : false;
+  }
+
+  parse(input: string): ParseResult {
+    checkInput(input);
+
+    if (!this.native) {
+      return this.fallback!.parse(input);
+    }
+    return convertResult(coded(() => this.native!.parse(input)));
+  }
+
+  /**
+   * Parse off the main thread, so model inference does not block the event
+   * loop. Aborting `options.signal` rejects the promise with an AbortError.
+   */
+  async parseAsync(input: string, options?: ParseAsyncOptions): Promise<ParseResult> {
+    checkInput(input);
+    options?.signal?.throwIfAborted();
+
+    if (!this.native) {
+      return this.fallback!.parse(input);
+    }
+    try {
+      return convertResult(await this.native.parseAsync(input, options?.signal));
+    } catch (error) {
+      throw toZantetsuError(error);
+    }
+  }
+
+  /** Parse many filenames; failed parses are null */
+  parseBatch(inputs: string[]): (ParseResult | null)[] {
+    return this.parseMany(inputs);
+  }
+
+  /**
+   * Parse many filenames in a single native call (optionally in parallel);
+   * failed parses are null
+   */
+  parseMany(inputs: string[], options?: ParseManyOptions): (ParseResult | null)[] {
+    checkInputs(inputs);
+
+    if (!this.native) {
+      return this.fallback!.parseMany(inputs);
+    }
+    return this.native
+      .parseMany(inputs, options?.parallel ?? false)
+      .map(result => (result ? convertResult(result) : null));
+  }
+
+  /**
+   * Parse a buffer of newline-delimited filenames in a single native call
+   * (optionally in parallel). Returns newline-delimited JSON of the Rust
+   * ParseResult, one line per input, `null` for failures
+   */
+  parseBuffer(inputs: Uint8Array, options?: ParseManyOptions): Buffer {
+    checkBuffer(inputs, this.native);
+    return this.native!.parseBuffer(inputs, options?.parallel ?? false);
+  }
+
+  /**
+   * Parse into the JSON of the full Rust ParseResult, every field included,
+   * ready to forward or store as is
+   */
+  parseJson(input: string): string {
+    if (!this.native) {
+      return this.fallback!.parseJson(input);
+    }
+    checkInput(input);
+    return coded(() => this.native!.parseJson(input));
+  }
 }
 
 // Default parser instance for convenience functions
@@ -445,5 +1061,14 @@ export function isUsingNativeModule(): boolean {
   return useNative;
 }
 
+/**
+ * The engine behind the parsers on this runtime
+ */
+export function backend(): Backend {
+  initNative();
+  if (useNative) return 'native';
+  return wasmModule ? 'wasm' : 'js';
+}
+
 // Export types
-export type { HeuristicParserOptions, ParseResult, EpisodeSpec, Resolution, VideoCodec, AudioCodec, MediaSource, ParseMode } from './types.js';
+export type { HeuristicParserOptions, ZantetsuOptions, ParseManyOptions, ParseAsyncOptions, QualityPreset, RankedResult, ErrorCode, ParseResult, EpisodeSpec, SingleEpisode, EpisodeRange, MultiEpisode, VersionedEpisode, SpecialEpisode, EpisodeZero, Resolution, VideoCodec, AudioCodec, MediaSource, ParseMode } from './types.js';
@@ -5,13 +5,52 @@
  */
 
 /**
- * Episode specification types
+ * Episode specification, discriminated by `kind`
  */
 export type EpisodeSpec =
-  | { type: 'single'; episode: number }
-  | { type: 'range'; start: number; end: number }
-  | { type: 'multi'; episodes: number[] }
-  | { type: 'versioned'; episode: number; version: number };
+  | SingleEpisode
+  | EpisodeRange
+  | MultiEpisode
+  | VersionedEpisode
+  | SpecialEpisode
+  | EpisodeZero;
+
+/** A single episode: "01", "1084" */
+export interface SingleEpisode {
+  kind: 'single';
+  value: number;
+}
+
+/** An episode range: "01-12" */
+export interface EpisodeRange {
+  kind: 'range';
+  start: number;
+  end: number;
+}
+
+/** Discrete episodes: "01, 03, 05" */
+export interface MultiEpisode {
+  kind: 'multi';
+  values: number[];
+}
+
+/** A re-released episode: "12v2" */
+export interface VersionedEpisode {
+  kind: 'versioned';
+  value: number;
+  version: number;
+}
+
+/** A numbered special: "SP1" */
+export interface SpecialEpisode {
+  kind: 'special';
+  value: number;
+}
+
+/** Episode zero or a prologue: "00" */
+export interface EpisodeZero {
+  kind: 'zero';
+}
 
 /**
  * Video resolution
@@ -36,7 +75,7 @@ export type MediaSource = 'BluRayRemux' | 'BluRay' | 'WebDL' | 'WebRip' | 'HDTV'
 /**
  * Parse mode
  */
-export type ParseMode = 'Full' | 'Light' | 'Auto';
+export type ParseMode = 'Full' | 'Light' | 'Auto' | 'Ensemble';
 
 /**
  * Options for creating a HeuristicParser
@@ -46,6 +85,71 @@ export interface HeuristicParserOptions {
   debug?: boolean;
 }
 
+/**
+ * Options for creating a Zantetsu parser
+ */
+export interface ZantetsuOptions {
+  /** Parsing engine selection (default: 'auto'; case-insensitive) */
+  mode?: 'full' | 'light' | 'auto' | 'ensemble' | ParseMode;
+  /** Directory of the neural model (default: $ZANTETSU_MODEL_DIR, else 'models/ner_model') */
+  modelDir?: string;
+  /**
+   * Hugging Face Hub repository ('owner/name' or 'owner/name@revision') to
+   * download the model from when no directory is set; needs an addon built
+   * with the `hub` feature
+   */
+  modelRepo?: string;
+  /** Model confidence below which auto mode falls back to the heuristic parser (default: 0.6) */
+  confidenceThreshold?: number;
+}
+
+/**
+ * Options for parseMany()
+ */
+export interface ParseManyOptions {
+  /** Spread the inputs over all cores (default: false) */
+  parallel?: boolean;
+}
+
+/**
+ * Options for parseAsync()
+ */
+export interface ParseAsyncOptions {
+  /** Abort the parse; the promise rejects with an AbortError */
+  signal?: AbortSignal;
+}
+
+/**
+ * Stable code of a ZantetsuError; the Rust variant name in SCREAMING_CASE,
+ * or INVALID_ARGUMENT for arguments rejected by the bindings
+ */
+export type ErrorCode =
+  | 'EMPTY_INPUT'
+  | 'PARSE_FAILED'
+  | 'REGEX_ERROR'
+  | 'MODEL_LOAD_ERROR'
+  | 'INFERENCE_ERROR'
+  | 'INVALID_CONTEXT'
+  | 'NEURAL_PARSER_ERROR'
+  | 'CANDLE_ERROR'
+  | 'IO_ERROR'
+  | 'INVALID_ARGUMENT';
+
+/**
+ * Ready-made quality weightings
+ */
+export type QualityPreset = 'archival' | 'streaming' | 'mobile' | 'lowBandwidth' | 'audiophile';
+
+/**
+ * One entry of rank(), best first
+ */
+export interface RankedResult {
+  /** Position of the result in the ranked array */
+  index: number;
+  /** Context-adjusted, weighted quality score */
+  score: number;
+}
+
 /**
  * Parse result from the heuristic parser
  */
@@ -58,8 +162,10 @@ export interface ParseResult {
   group: string | null;
   /** Episode specification */
   episode: EpisodeSpec | null;
-  /** Season number */
+  /** Season number (first season for batches) */
   season: number | null;
+  /** Last season of a season batch (e.g. 3 for "S1-S3") */
+  season_end: number | null;
   /** Video resolution */
   resolution: Resolution | null;
   /** Video codec */
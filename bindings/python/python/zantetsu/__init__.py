@@ -3,18 +3,46 @@
 Extracts title, episode, resolution, codecs, and more from anime filenames.
 
 Example:
-    >>> from zantetsu import HeuristicParser
-    >>> parser = HeuristicParser()
+    >>> from zantetsu import ParseMode, Parser, Resolution
+    >>> parser = Parser(mode=ParseMode.Light)
     >>> result = parser.parse("[SubsPlease] Spy x Family - 01 (1080p).mkv")
     >>> print(result.title)
-    'Spy x Family'
+    Spy x Family
     >>> print(result.episode)
-    '1'
-    >>> print(result.resolution)
-    'FHD1080'
+    1
+    >>> result.resolution == Resolution.FHD1080
+    True
 """
 
-from zantetsu._zantetsu import HeuristicParser, ParseResult
+from zantetsu._zantetsu import (
+    AudioCodec,
+    HeuristicParser,
+    MediaSource,
+    ParseMode,
+    Parser,
+    ParseLines,
+    ParseResult,
+    Resolution,
+    VideoCodec,
+    ZantetsuError,
+    parse_lines,
+    parse_to_columns,
+    parse_to_records,
+)
 
 __version__ = "0.1.2"
-__all__ = ["HeuristicParser", "ParseResult"]
\ No newline at end of file
+__all__ = [
+    "AudioCodec",
+    "HeuristicParser",
+    "MediaSource",
+    "ParseMode",
+    "Parser",
+    "ParseLines",
+    "ParseResult",
+    "Resolution",
+    "VideoCodec",
+    "ZantetsuError",
+    "parse_lines",
+    "parse_to_columns",
+    "parse_to_records",
+]
@@ -0,0 +1,117 @@
+import io
+import json
+
+import pytest
+
+from zantetsu import (
+    HeuristicParser,
+    ParseMode,
+    Parser,
+    ParseResult,
+    Resolution,
+    VideoCodec,
+    ZantetsuError,
+    parse_lines,
+    parse_to_columns,
+    parse_to_records,
+)
+
+FRIEREN = "[SubsPlease] Sousou no Frieren - 05 (1080p) [x265][ABCD1234].mkv"
+
+
+def test_parse_returns_enums():
+    result = Parser(mode=ParseMode.Light).parse(FRIEREN)
+
+    assert isinstance(result, ParseResult)
+    assert result.title == "Sousou no Frieren"
+    assert result.group == "SubsPlease"
+    assert result.episode == "5"
+    assert result.resolution == Resolution.FHD1080
+    assert result.video_codec == VideoCodec.HEVC
+    assert str(result.resolution) == "FHD1080"
+    assert result.parse_mode == ParseMode.Light
+
+
+def test_results_behave_like_dataclasses():
+    parser = Parser(mode=ParseMode.Light)
+    result = parser.parse(FRIEREN)
+
+    assert result == parser.parse(FRIEREN)
+    assert result.to_dict()["crc32"] == "ABCD1234"
+    assert repr(result).startswith("ParseResult(input=")
+    with pytest.raises(AttributeError):
+        result.title = "Other"
+
+
+def test_parse_batch_keeps_order_and_skips_failures():
+    results = Parser(mode=ParseMode.Light).parse_batch([FRIEREN, "", "Oshi no Ko S02E05 [720p].mkv"])
+
+    assert [r and r.title for r in results] == ["Sousou no Frieren", None, "Oshi no Ko"]
+    assert results[2].season == 2
+
+
+def test_parse_lines_streams_files_lazily():
+    dump = io.StringIO(f"{FRIEREN}\n\nOshi no Ko S02E05 [720p].mkv\n")
+    results = parse_lines(dump, mode=ParseMode.Light)
+
+    assert next(results).title == "Sousou no Frieren"
+    assert dump.readline() == "\n"  # only the first line was consumed
+    assert [r and r.title for r in results] == ["Oshi no Ko"]
+
+    lines = [FRIEREN.encode(), b""]
+    assert [r and r.title for r in Parser(mode=ParseMode.Light).parse_lines(lines)] == [
+        "Sousou no Frieren",
+        None,
+    ]
+
+
+def test_records_and_columns_are_plain_and_aligned():
+    inputs = [FRIEREN, ""]
+    records = parse_to_records(inputs, mode=ParseMode.Light)
+
+    assert records[0]["title"] == "Sousou no Frieren"
+    assert records[0]["resolution"] == "FHD1080"
+    assert records[0]["parse_mode"] == "Light"
+    assert records[1] == {key: None for key in records[0]} | {"input": ""}
+
+    columns = parse_to_columns(inputs, mode=ParseMode.Light)
+    assert list(columns) == list(records[0])
+    assert columns["title"] == ["Sousou no Frieren", None]
+    assert columns == Parser(mode=ParseMode.Light).parse_to_columns(inputs)
+
+
+def test_parse_json_returns_the_full_result():
+    for parser in (Parser(mode=ParseMode.Light), HeuristicParser()):
+        result = json.loads(parser.parse_json(FRIEREN))
+
+        assert result["title"] == "Sousou no Frieren"
+        assert result["episode"] == {"Single": 5}
+        assert result["resolution"] == "FHD1080"
+        with pytest.raises(ZantetsuError):
+            parser.parse_json("")
+
+
+def test_missing_model_dir_falls_back_to_heuristics(tmp_path):
+    parser = Parser(mode=ParseMode.Auto, model_dir=str(tmp_path))
+
+    assert parser.parse(FRIEREN).title == "Sousou no Frieren"
+    with pytest.raises(TypeError):
+        Parser(ParseMode.Auto, str(tmp_path))  # keyword-only
+
+
+def test_parser_defaults_to_auto_mode():
+    assert Parser().mode == ParseMode.Auto
+    assert Parser().parse(FRIEREN).title == "Sousou no Frieren"
+
+
+def test_parse_raises_on_empty_input():
+    with pytest.raises(ValueError):
+        Parser(mode=ParseMode.Light).parse("")
+    with pytest.raises(ValueError):
+        HeuristicParser().parse("")
+
+
+def test_errors_carry_a_stable_code():
+    with pytest.raises(ZantetsuError) as excinfo:
+        HeuristicParser().parse("   ")
+    assert excinfo.value.code == "EMPTY_INPUT"
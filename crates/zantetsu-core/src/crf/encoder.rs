@@ -0,0 +1,221 @@
+//! DistilBERT encoder shared by the reduced-precision [`CrfModel`] backbones.
+//!
+//! candle's `DistilBertModel` only runs in F32, so the int8 and half-precision
+//! variants use this implementation instead. It is generic over the linear
+//! layer so the same attention and feed-forward code drives both quantized
+//! matmuls and F16/BF16 matmuls. Attention scores are always softmaxed in F32.
+//!
+//! [`CrfModel`]: super::model::CrfModel
+
+use candle_core::{D, DType, Module, Result, Tensor};
+use candle_nn::{Embedding, LayerNorm};
+use candle_transformers::quantized_nn;
+use candle_transformers::quantized_var_builder::VarBuilder as QuantizedVarBuilder;
+use serde::Deserialize;
+
+/// The subset of a DistilBERT `config.json` needed to build the encoder.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EncoderConfig {
+    pub vocab_size: usize,
+    pub dim: usize,
+    pub n_layers: usize,
+    pub n_heads: usize,
+    pub hidden_dim: usize,
+    pub max_position_embeddings: usize,
+    #[serde(default = "default_activation")]
+    pub activation: String,
+}
+
+fn default_activation() -> String {
+    "gelu".to_string()
+}
+
+const LAYER_NORM_EPS: f64 = 1e-12;
+
+/// Source of encoder weights: a float or a quantized var builder.
+pub(crate) trait Weights: Clone {
+    type Linear: Module;
+
+    fn pp(&self, prefix: &str) -> Self;
+    fn linear(&self, in_dim: usize, out_dim: usize) -> Result<Self::Linear>;
+    fn layer_norm(&self, dim: usize) -> Result<LayerNorm>;
+    fn embedding(&self, rows: usize, dim: usize) -> Result<Embedding>;
+}
+
+impl Weights for candle_nn::VarBuilder<'_> {
+    type Linear = candle_nn::Linear;
+
+    fn pp(&self, prefix: &str) -> Self {
+        candle_nn::VarBuilder::pp(self, prefix)
+    }
+
+    fn linear(&self, in_dim: usize, out_dim: usize) -> Result<Self::Linear> {
+        candle_nn::linear(in_dim, out_dim, self.clone())
+    }
+
+    fn layer_norm(&self, dim: usize) -> Result<LayerNorm> {
+        candle_nn::layer_norm(dim, LAYER_NORM_EPS, self.clone())
+    }
+
+    fn embedding(&self, rows: usize, dim: usize) -> Result<Embedding> {
+        candle_nn::embedding(rows, dim, self.clone())
+    }
+}
+
+impl Weights for QuantizedVarBuilder {
+    type Linear = quantized_nn::Linear;
+
+    fn pp(&self, prefix: &str) -> Self {
+        QuantizedVarBuilder::pp(self, prefix)
+    }
+
+    fn linear(&self, in_dim: usize, out_dim: usize) -> Result<Self::Linear> {
+        quantized_nn::linear(in_dim, out_dim, self.clone())
+    }
+
+    fn layer_norm(&self, dim: usize) -> Result<LayerNorm> {
+        quantized_nn::layer_norm(dim, LAYER_NORM_EPS, self.clone())
+    }
+
+    fn embedding(&self, rows: usize, dim: usize) -> Result<Embedding> {
+        let weights = self.get((rows, dim), "weight")?.dequantize(self.device())?;
+        Ok(Embedding::new(weights, dim))
+    }
+}
+
+struct Embeddings {
+    word_embeddings: Embedding,
+    position_embeddings: Embedding,
+    layer_norm: LayerNorm,
+}
+
+impl Embeddings {
+    fn load<W: Weights>(vb: W, config: &EncoderConfig) -> Result<Self> {
+        Ok(Self {
+            word_embeddings: vb
+                .pp("word_embeddings")
+                .embedding(config.vocab_size, config.dim)?,
+            position_embeddings: vb
+                .pp("position_embeddings")
+                .embedding(config.max_position_embeddings, config.dim)?,
+            layer_norm: vb.pp("LayerNorm").layer_norm(config.dim)?,
+        })
+    }
+
+    fn forward(&self, input_ids: &Tensor) -> Result<Tensor> {
+        let (_bsize, seq_len) = input_ids.dims2()?;
+        let position_ids = (0..seq_len as u32).collect::<Vec<_>>();
+        let position_ids = Tensor::new(&position_ids[..], input_ids.device())?;
+        let embeddings = self
+            .word_embeddings
+            .forward(input_ids)?
+            .broadcast_add(&self.position_embeddings.forward(&position_ids)?)?;
+        self.layer_norm.forward(&embeddings)
+    }
+}
+
+struct TransformerBlock<L> {
+    q_lin: L,
+    k_lin: L,
+    v_lin: L,
+    out_lin: L,
+    sa_layer_norm: LayerNorm,
+    lin1: L,
+    lin2: L,
+    output_layer_norm: LayerNorm,
+    n_heads: usize,
+    relu: bool,
+}
+
+impl<L: Module> TransformerBlock<L> {
+    fn load<W: Weights<Linear = L>>(vb: W, config: &EncoderConfig) -> Result<Self> {
+        let dim = config.dim;
+        let attention = vb.pp("attention");
+        let ffn = vb.pp("ffn");
+
+        Ok(Self {
+            q_lin: attention.pp("q_lin").linear(dim, dim)?,
+            k_lin: attention.pp("k_lin").linear(dim, dim)?,
+            v_lin: attention.pp("v_lin").linear(dim, dim)?,
+            out_lin: attention.pp("out_lin").linear(dim, dim)?,
+            sa_layer_norm: vb.pp("sa_layer_norm").layer_norm(dim)?,
+            lin1: ffn.pp("lin1").linear(dim, config.hidden_dim)?,
+            lin2: ffn.pp("lin2").linear(config.hidden_dim, dim)?,
+            output_layer_norm: vb.pp("output_layer_norm").layer_norm(dim)?,
+            n_heads: config.n_heads,
+            relu: config.activation == "relu",
+        })
+    }
+
+    /// Same attention-mask convention as candle's `DistilBertModel`:
+    /// non-zero mask entries are excluded from attention.
+    fn forward(&self, hidden_states: &Tensor, attention_mask: &Tensor) -> Result<Tensor> {
+        let (bs, seq_len, dim) = hidden_states.dims3()?;
+        let head_dim = dim / self.n_heads;
+        let heads = |xs: Tensor| -> Result<Tensor> {
+            xs.reshape((bs, seq_len, self.n_heads, head_dim))?
+                .transpose(1, 2)?
+                .contiguous()
+        };
+
+        let q = heads(self.q_lin.forward(hidden_states)?)?;
+        let k = heads(self.k_lin.forward(hidden_states)?)?;
+        let v = heads(self.v_lin.forward(hidden_states)?)?;
+
+        let q = (q / (head_dim as f64).sqrt())?;
+        let scores = q
+            .matmul(&k.transpose(2, 3)?.contiguous()?)?
+            .to_dtype(DType::F32)?;
+        let mask = attention_mask.broadcast_as(scores.shape())?;
+        let neg_inf =
+            Tensor::new(f32::NEG_INFINITY, scores.device())?.broadcast_as(scores.shape())?;
+        let scores = mask.where_cond(&neg_inf, &scores)?;
+        let weights = candle_nn::ops::softmax(&scores, D::Minus1)?.to_dtype(v.dtype())?;
+
+        let context = weights
+            .matmul(&v)?
+            .transpose(1, 2)?
+            .reshape((bs, seq_len, dim))?
+            .contiguous()?;
+        let sa_output = self
+            .out_lin
+            .forward(&context)?
+            .broadcast_add(hidden_states)?;
+        let sa_output = self.sa_layer_norm.forward(&sa_output)?;
+
+        let ffn = self.lin1.forward(&sa_output)?;
+        let ffn = if self.relu { ffn.relu()? } else { ffn.gelu()? };
+        let ffn = (self.lin2.forward(&ffn)? + sa_output)?;
+        self.output_layer_norm.forward(&ffn)
+    }
+}
+
+/// DistilBERT encoder over linear layers of type `L`.
+pub struct DistilBertEncoder<L> {
+    embeddings: Embeddings,
+    layers: Vec<TransformerBlock<L>>,
+}
+
+impl<L: Module> DistilBertEncoder<L> {
+    /// Load the encoder (tensor names as in
+    /// [`candle_tensor_name`](super::model::candle_tensor_name), i.e. without
+    /// the `distilbert.` prefix).
+    pub(crate) fn load<W: Weights<Linear = L>>(vb: W, config: &EncoderConfig) -> Result<Self> {
+        let embeddings = Embeddings::load(vb.pp("embeddings"), config)?;
+        let layers = (0..config.n_layers)
+            .map(|index| {
+                TransformerBlock::load(vb.pp(&format!("transformer.layer.{index}")), config)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { embeddings, layers })
+    }
+
+    /// Forward pass producing hidden states `[batch_size, seq_len, dim]`.
+    pub fn forward(&self, input_ids: &Tensor, attention_mask: &Tensor) -> Result<Tensor> {
+        let mut hidden_states = self.embeddings.forward(input_ids)?;
+        for layer in &self.layers {
+            hidden_states = layer.forward(&hidden_states, attention_mask)?;
+        }
+        Ok(hidden_states)
+    }
+}
@@ -0,0 +1,132 @@
+//! # Forward-Backward for CRF
+//!
+//! Computes per-position tag marginals `P(y_t = tag | x)` of a linear-chain CRF
+//! from emission and transition scores. Viterbi finds the best path; the
+//! marginals say how much probability mass that path actually carries.
+
+use crate::error::{Result, ZantetsuError};
+
+/// Per-position marginal probabilities, shape `[seq_len, num_tags]`.
+///
+/// Each row sums to 1. Invalid transitions should already be masked out of
+/// `transition_matrix` with a large negative score, as done for Viterbi.
+pub fn marginals(
+    emission_scores: &[Vec<f32>],
+    transition_matrix: &[Vec<f32>],
+) -> Result<Vec<Vec<f32>>> {
+    let seq_len = emission_scores.len();
+    if seq_len == 0 {
+        return Ok(Vec::new());
+    }
+
+    let num_tags = transition_matrix.len();
+    if let Some(row) = emission_scores.iter().find(|row| row.len() != num_tags) {
+        return Err(ZantetsuError::NeuralParser(format!(
+            "Emission score dimension mismatch: expected {}, got {}",
+            num_tags,
+            row.len()
+        )));
+    }
+
+    // alpha[t][j]: log-sum of all prefixes ending in tag j at position t
+    let mut alpha = vec![vec![0.0f32; num_tags]; seq_len];
+    alpha[0].clone_from(&emission_scores[0]);
+    for pos in 1..seq_len {
+        for curr in 0..num_tags {
+            let incoming = log_sum_exp(
+                (0..num_tags).map(|prev| alpha[pos - 1][prev] + transition_matrix[prev][curr]),
+            );
+            alpha[pos][curr] = incoming + emission_scores[pos][curr];
+        }
+    }
+
+    // beta[t][i]: log-sum of all suffixes following tag i at position t
+    let mut beta = vec![vec![0.0f32; num_tags]; seq_len];
+    for pos in (0..seq_len - 1).rev() {
+        for curr in 0..num_tags {
+            beta[pos][curr] = log_sum_exp((0..num_tags).map(|next| {
+                transition_matrix[curr][next] + emission_scores[pos + 1][next] + beta[pos + 1][next]
+            }));
+        }
+    }
+
+    let log_z = log_sum_exp(alpha[seq_len - 1].iter().copied());
+
+    Ok(alpha
+        .iter()
+        .zip(&beta)
+        .map(|(a, b)| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| (a + b - log_z).exp())
+                .collect()
+        })
+        .collect())
+}
+
+fn log_sum_exp(values: impl Iterator<Item = f32> + Clone) -> f32 {
+    let max = values.clone().fold(f32::NEG_INFINITY, f32::max);
+    if max == f32::NEG_INFINITY {
+        return max;
+    }
+    max + values.map(|v| (v - max).exp()).sum::<f32>().ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Brute-force marginals by enumerating every tag sequence.
+    fn enumerate(emissions: &[Vec<f32>], transitions: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        let (len, tags) = (emissions.len(), transitions.len());
+        let mut totals = vec![vec![0.0f64; tags]; len];
+        let mut z = 0.0f64;
+        for code in 0..tags.pow(len as u32) {
+            let path: Vec<usize> = (0..len).map(|p| code / tags.pow(p as u32) % tags).collect();
+            let mut score = emissions[0][path[0]];
+            for p in 1..len {
+                score += transitions[path[p - 1]][path[p]] + emissions[p][path[p]];
+            }
+            let weight = f64::from(score).exp();
+            z += weight;
+            for (p, &tag) in path.iter().enumerate() {
+                totals[p][tag] += weight;
+            }
+        }
+        totals
+            .into_iter()
+            .map(|row| row.into_iter().map(|w| (w / z) as f32).collect())
+            .collect()
+    }
+
+    #[test]
+    fn matches_brute_force_enumeration() {
+        let emissions = vec![
+            vec![1.0, 0.2, -0.5],
+            vec![0.1, 1.5, 0.3],
+            vec![-1.0, 0.4, 2.0],
+            vec![0.0, 0.0, 0.5],
+        ];
+        let transitions = vec![
+            vec![0.5, -0.2, 0.1],
+            vec![-10000.0, 0.8, 0.3],
+            vec![0.2, 0.0, -0.4],
+        ];
+
+        let got = marginals(&emissions, &transitions).unwrap();
+        let want = enumerate(&emissions, &transitions);
+        for (g, w) in got.iter().flatten().zip(want.iter().flatten()) {
+            assert!((g - w).abs() < 1e-5, "{g} vs {w}");
+        }
+        for row in &got {
+            assert!((row.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn rejects_mismatched_dimensions() {
+        let transitions = vec![vec![0.0; 3]; 3];
+        assert!(marginals(&[vec![0.0; 2]], &transitions).is_err());
+        assert!(marginals(&[], &transitions).unwrap().is_empty());
+    }
+}
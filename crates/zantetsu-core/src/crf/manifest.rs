@@ -0,0 +1,144 @@
+//! # Model Manifest
+//!
+//! Metadata stored beside the model weights (`manifest.json`) so a checkpoint
+//! can be checked against the tag sets and tokenizer this crate supports
+//! before its labels are trusted.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, ZantetsuError};
+use crate::parser::bio_tags::TagSet;
+
+/// Manifest file name inside a model directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Version and compatibility metadata for a trained model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelManifest {
+    /// Free-form model version (e.g., `0.3.0`).
+    pub model_version: String,
+    /// Version of the [`TagSet`] the classifier head was trained on.
+    pub tag_set_version: u32,
+    /// Hex SHA-256 of the `tokenizer.json` the model was trained with.
+    pub tokenizer_sha256: String,
+}
+
+impl ModelManifest {
+    /// Build a manifest for a model trained on `tag_set` with the tokenizer at `tokenizer_path`.
+    pub fn new(
+        model_version: impl Into<String>,
+        tag_set: TagSet,
+        tokenizer_path: &Path,
+    ) -> Result<Self> {
+        Ok(Self {
+            model_version: model_version.into(),
+            tag_set_version: tag_set.version(),
+            tokenizer_sha256: sha256_file(tokenizer_path)?,
+        })
+    }
+
+    /// Read a manifest from `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(|e| {
+            ZantetsuError::ModelLoadError(format!("invalid manifest {}: {e}", path.display()))
+        })
+    }
+
+    /// Write the manifest to `path` as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ZantetsuError::ModelLoadError(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// The tag set named by the manifest, if this crate supports it.
+    pub fn tag_set(&self) -> Result<TagSet> {
+        TagSet::from_version(self.tag_set_version).ok_or_else(|| {
+            ZantetsuError::ModelLoadError(format!(
+                "model {} uses tag set v{}, but this build supports up to v{}",
+                self.model_version,
+                self.tag_set_version,
+                TagSet::LATEST.version()
+            ))
+        })
+    }
+
+    /// Check that `tokenizer_path` is the tokenizer the model was trained with.
+    pub fn verify_tokenizer(&self, tokenizer_path: &Path) -> Result<()> {
+        let actual = sha256_file(tokenizer_path)?;
+        if actual.eq_ignore_ascii_case(&self.tokenizer_sha256) {
+            Ok(())
+        } else {
+            Err(ZantetsuError::ModelLoadError(format!(
+                "tokenizer {} does not match model {} (expected sha256 {}, got {actual})",
+                tokenizer_path.display(),
+                self.model_version,
+                self.tokenizer_sha256
+            )))
+        }
+    }
+}
+
+/// Hex SHA-256 of a file's contents.
+pub(crate) fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_validates_tag_set_and_tokenizer() {
+        let dir = std::env::temp_dir().join(format!("zantetsu-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let tokenizer = dir.join("tokenizer.json");
+        std::fs::write(&tokenizer, r#"{"version":"1.0"}"#).unwrap();
+
+        let manifest = ModelManifest::new("0.3.0", TagSet::V2, &tokenizer).unwrap();
+        let path = dir.join(MANIFEST_FILE);
+        manifest.save(&path).unwrap();
+        let loaded = ModelManifest::load(&path).unwrap();
+        assert_eq!(loaded, manifest);
+        assert_eq!(loaded.tag_set().unwrap(), TagSet::V2);
+        assert!(loaded.verify_tokenizer(&tokenizer).is_ok());
+
+        std::fs::write(&tokenizer, r#"{"version":"2.0"}"#).unwrap();
+        assert!(matches!(
+            loaded.verify_tokenizer(&tokenizer),
+            Err(ZantetsuError::ModelLoadError(_))
+        ));
+
+        let future = ModelManifest {
+            tag_set_version: 99,
+            ..manifest
+        };
+        assert!(matches!(
+            future.tag_set(),
+            Err(ZantetsuError::ModelLoadError(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1 +1,11 @@
+#[cfg(feature = "neural")]
+pub mod char_cnn;
+#[cfg(feature = "neural")]
+pub mod encoder;
+pub mod forward_backward;
+pub mod manifest;
+#[cfg(feature = "neural")]
 pub mod model;
+#[cfg(feature = "neural")]
+pub mod quantized;
+pub mod transitions;
@@ -1,54 +1,420 @@
-use crate::parser::bio_tags::BioTag;
+use crate::crf::encoder::{DistilBertEncoder, EncoderConfig};
+use crate::crf::quantized::QuantizedDistilBertModel;
+use crate::crf::transitions::TransitionMatrix;
+use crate::parser::bio_tags::TagSet;
 use candle_core::{DType, Result, Tensor};
 use candle_nn::{Linear, Module, VarBuilder};
 use candle_transformers::models::distilbert::{Config, DistilBertModel};
+use candle_transformers::quantized_nn;
+use candle_transformers::quantized_var_builder::VarBuilder as QuantizedVarBuilder;
+
+/// Numeric precision of the neural model weights.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModelPrecision {
+    /// Full-precision safetensors weights.
+    #[default]
+    F32,
+    /// Half-precision (IEEE `f16`) weights and activations.
+    F16,
+    /// Half-precision (`bfloat16`) weights and activations. GPU devices only:
+    /// candle's CPU backend has no BF16 matmul.
+    BF16,
+    /// Int8 (`Q8_0`) GGUF weights: roughly a quarter of the memory footprint.
+    Int8,
+}
+
+impl ModelPrecision {
+    /// Floating-point dtype the weights are loaded as (`None` for quantized weights).
+    pub fn dtype(self) -> Option<DType> {
+        match self {
+            ModelPrecision::F32 => Some(DType::F32),
+            ModelPrecision::F16 => Some(DType::F16),
+            ModelPrecision::BF16 => Some(DType::BF16),
+            ModelPrecision::Int8 => None,
+        }
+    }
+}
+
+/// Encoder and emission layer for one of the supported precisions.
+enum Backbone {
+    F32 {
+        distilbert: DistilBertModel,
+        emission: Linear,
+    },
+    Half {
+        distilbert: DistilBertEncoder<Linear>,
+        emission: Linear,
+        precision: ModelPrecision,
+    },
+    Int8 {
+        distilbert: QuantizedDistilBertModel,
+        emission: quantized_nn::Linear,
+    },
+}
 
 /// A Transformer-CRF sequence classification model (DistilBERT + Linear + Transitions)
 pub struct CrfModel {
-    pub distilbert: DistilBertModel,
-    pub emission: Linear,
+    backbone: Backbone,
+    tag_set: TagSet,
+    learned_transitions: bool,
     pub transitions: Tensor,
 }
 
 impl CrfModel {
-    /// Load the model from safetensors
-    pub fn load(vb: VarBuilder, config: Config) -> Result<Self> {
+    /// Load the model from safetensors, with a classifier head sized for `tag_set`
+    pub fn load(vb: VarBuilder, config: Config, tag_set: TagSet) -> Result<Self> {
         // Keys in safetensors are already prefixed with "distilbert.", but VarBuilder
         // from_mmaped_safetensors doesn't add any prefix, so we access them directly
         let distilbert = DistilBertModel::load(vb.clone(), &config)?;
 
         let hidden_size = 768; // Default distilbert dimension
-        let num_labels = BioTag::NUM_TAGS;
+        let num_labels = tag_set.num_tags();
 
         // The linear layer for emission scores maps from hidden_size to num_labels
         // In Hugging Face sequence classification, this is usually named `classifier`
         let emission = candle_nn::linear(hidden_size, num_labels, vb.pp("classifier"))?;
 
-        // Depending on whether CRF transitions were learned in python, we might load them here
-        // If not, we can initialize a dummy transition matrix for the Viterbi decoder to use
+        // Checkpoints exported from Python may carry learned transitions; otherwise
+        // start from zeros until a `transitions.json` is applied
         let dev = vb.device();
-        let transitions = match vb.get((num_labels, num_labels), "crf_transitions.weight") {
-            Ok(t) => t,
-            Err(_) => Tensor::zeros((num_labels, num_labels), DType::F32, dev)?,
+        let (transitions, learned_transitions) =
+            match vb.get((num_labels, num_labels), "crf_transitions.weight") {
+                Ok(t) => (t, true),
+                Err(_) => (
+                    Tensor::zeros((num_labels, num_labels), DType::F32, dev)?,
+                    false,
+                ),
+            };
+
+        Ok(Self {
+            backbone: Backbone::F32 {
+                distilbert,
+                emission,
+            },
+            tag_set,
+            learned_transitions,
+            transitions,
+        })
+    }
+
+    /// Load the model in half precision; `vb` must load F16 or BF16 weights.
+    ///
+    /// Attention softmax runs in F32 and emissions are returned as F32, so only
+    /// the matmuls and activations run at reduced precision.
+    pub fn load_half(vb: VarBuilder, config: &EncoderConfig, tag_set: TagSet) -> Result<Self> {
+        let precision = match vb.dtype() {
+            DType::F16 => ModelPrecision::F16,
+            DType::BF16 if vb.device().is_cpu() => {
+                candle_core::bail!("BF16 inference is not supported on the CPU backend")
+            }
+            DType::BF16 => ModelPrecision::BF16,
+            dtype => candle_core::bail!("half-precision model requested with {dtype:?} weights"),
         };
+        let distilbert = DistilBertEncoder::load(vb.clone(), config)?;
+
+        let num_labels = tag_set.num_tags();
+        let emission = candle_nn::linear(config.dim, num_labels, vb.pp("classifier"))?;
+
+        let dev = vb.device();
+        let (transitions, learned_transitions) =
+            match vb.get((num_labels, num_labels), "crf_transitions.weight") {
+                Ok(t) => (t.to_dtype(DType::F32)?, true),
+                Err(_) => (
+                    Tensor::zeros((num_labels, num_labels), DType::F32, dev)?,
+                    false,
+                ),
+            };
 
         Ok(Self {
-            distilbert,
-            emission,
+            backbone: Backbone::Half {
+                distilbert,
+                emission,
+                precision,
+            },
+            tag_set,
+            learned_transitions,
             transitions,
         })
     }
 
+    /// Load the int8 model from a GGUF file written by
+    /// [`quantize_safetensors`](crate::crf::quantized::quantize_safetensors).
+    pub fn load_quantized(
+        vb: QuantizedVarBuilder,
+        config: &EncoderConfig,
+        tag_set: TagSet,
+    ) -> Result<Self> {
+        let distilbert = QuantizedDistilBertModel::load(vb.clone(), config)?;
+
+        let num_labels = tag_set.num_tags();
+        let emission = quantized_nn::linear(config.dim, num_labels, vb.pp("classifier"))?;
+
+        let dev = vb.device();
+        let (transitions, learned_transitions) =
+            match vb.get((num_labels, num_labels), "crf_transitions.weight") {
+                Ok(t) => (t.dequantize(dev)?, true),
+                Err(_) => (
+                    Tensor::zeros((num_labels, num_labels), DType::F32, dev)?,
+                    false,
+                ),
+            };
+
+        Ok(Self {
+            backbone: Backbone::Int8 {
+                distilbert,
+                emission,
+            },
+            tag_set,
+            learned_transitions,
+            transitions,
+        })
+    }
+
+    /// Precision of the loaded weights.
+    pub fn precision(&self) -> ModelPrecision {
+        match self.backbone {
+            Backbone::F32 { .. } => ModelPrecision::F32,
+            Backbone::Half { precision, .. } => precision,
+            Backbone::Int8 { .. } => ModelPrecision::Int8,
+        }
+    }
+
+    /// Tag set the classifier head was trained on.
+    pub fn tag_set(&self) -> TagSet {
+        self.tag_set
+    }
+
+    /// Whether transition scores were learned (from the weights or a
+    /// [`TransitionMatrix`]) rather than zero-initialized.
+    pub fn has_learned_transitions(&self) -> bool {
+        self.learned_transitions
+    }
+
+    /// Replace the transition scores with `matrix`, which must be over this
+    /// model's tag set.
+    pub fn set_transitions(&mut self, matrix: &TransitionMatrix) -> Result<()> {
+        if matrix.tag_set_version != self.tag_set.version() {
+            candle_core::bail!(
+                "transitions use tag set v{}, but the model uses v{}",
+                matrix.tag_set_version,
+                self.tag_set.version()
+            );
+        }
+        self.transitions = matrix.to_tensor(self.transitions.device())?;
+        self.learned_transitions = true;
+        Ok(())
+    }
+
     /// Forward pass producing emission scores
     /// `input_ids`: [batch_size, seq_len]
     /// `attention_mask`: [batch_size, seq_len]
     pub fn forward(&self, input_ids: &Tensor, attention_mask: &Tensor) -> Result<Tensor> {
         // DistilBert forward returns either hidden states or multiple things depending on config
-        let hidden_states = self.distilbert.forward(input_ids, attention_mask)?;
-
         // Pass the output of the transformer through the emission linear layer
-        let emissions = self.emission.forward(&hidden_states)?;
+        match &self.backbone {
+            Backbone::F32 {
+                distilbert,
+                emission,
+            } => emission.forward(&distilbert.forward(input_ids, attention_mask)?),
+            Backbone::Half {
+                distilbert,
+                emission,
+                ..
+            } => emission
+                .forward(&distilbert.forward(input_ids, attention_mask)?)?
+                .to_dtype(DType::F32),
+            Backbone::Int8 {
+                distilbert,
+                emission,
+            } => emission.forward(&distilbert.forward(input_ids, attention_mask)?),
+        }
+    }
+}
+
+/// Map a Hugging Face checkpoint tensor name onto the name candle expects
+/// (no `distilbert.` prefix, `LayerNorm.gamma/beta` as `weight/bias`).
+pub fn candle_tensor_name(key: &str) -> String {
+    let key = key.strip_prefix("distilbert.").unwrap_or(key);
+
+    if let Some(stem) = key.strip_suffix(".LayerNorm.gamma") {
+        format!("{stem}.LayerNorm.weight")
+    } else if let Some(stem) = key.strip_suffix(".LayerNorm.beta") {
+        format!("{stem}.LayerNorm.bias")
+    } else {
+        key.to_string()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::crf::quantized::quantize_safetensors;
+    use candle_core::Device;
+    use std::collections::HashMap;
+
+    const DIM: usize = 768;
+    const HIDDEN: usize = 64;
+    const VOCAB: usize = 32;
+    const MAX_POS: usize = 16;
+
+    pub(crate) fn config_json() -> String {
+        serde_json::json!({
+            "vocab_size": VOCAB,
+            "dim": DIM,
+            "n_layers": 1,
+            "n_heads": 12,
+            "hidden_dim": HIDDEN,
+            "activation": "gelu",
+            "max_position_embeddings": MAX_POS,
+            "initializer_range": 0.02,
+            "pad_token_id": 0,
+        })
+        .to_string()
+    }
+
+    /// A one-layer DistilBERT checkpoint with a v1 head, in Hugging Face
+    /// tensor names.
+    pub(crate) fn random_checkpoint(dev: &Device) -> HashMap<String, Tensor> {
+        let mut tensors = HashMap::new();
+        let mut put = |name: &str, shape: &[usize], scale: f64| {
+            let t = (Tensor::randn(0f32, 1f32, shape, dev).unwrap() * scale).unwrap();
+            tensors.insert(name.to_string(), t);
+        };
+        put(
+            "distilbert.embeddings.word_embeddings.weight",
+            &[VOCAB, DIM],
+            1.0,
+        );
+        put(
+            "distilbert.embeddings.position_embeddings.weight",
+            &[MAX_POS, DIM],
+            1.0,
+        );
+        put("distilbert.embeddings.LayerNorm.gamma", &[DIM], 0.1);
+        put("distilbert.embeddings.LayerNorm.beta", &[DIM], 0.1);
+        let layer = "distilbert.transformer.layer.0";
+        for lin in ["q_lin", "k_lin", "v_lin", "out_lin"] {
+            put(
+                &format!("{layer}.attention.{lin}.weight"),
+                &[DIM, DIM],
+                0.02,
+            );
+            put(&format!("{layer}.attention.{lin}.bias"), &[DIM], 0.02);
+        }
+        put(&format!("{layer}.ffn.lin1.weight"), &[HIDDEN, DIM], 0.02);
+        put(&format!("{layer}.ffn.lin1.bias"), &[HIDDEN], 0.02);
+        put(&format!("{layer}.ffn.lin2.weight"), &[DIM, HIDDEN], 0.02);
+        put(&format!("{layer}.ffn.lin2.bias"), &[DIM], 0.02);
+        for norm in ["sa_layer_norm", "output_layer_norm"] {
+            put(&format!("{layer}.{norm}.weight"), &[DIM], 0.1);
+            put(&format!("{layer}.{norm}.bias"), &[DIM], 0.1);
+        }
+        let num_tags = TagSet::V1.num_tags();
+        put("classifier.weight", &[num_tags, DIM], 0.02);
+        put("classifier.bias", &[num_tags], 0.02);
+        tensors
+    }
+
+    #[test]
+    fn tensor_names_follow_candle_layout() {
+        assert_eq!(
+            candle_tensor_name("distilbert.embeddings.LayerNorm.gamma"),
+            "embeddings.LayerNorm.weight"
+        );
+        assert_eq!(candle_tensor_name("classifier.bias"), "classifier.bias");
+    }
+
+    fn emissions(model: &CrfModel, dev: &Device) -> Vec<f32> {
+        let ids = Tensor::new(&[[1u32, 5, 9, 2]], dev).unwrap();
+        let mask = Tensor::zeros((1, 4), DType::U8, dev).unwrap();
+        model
+            .forward(&ids, &mask)
+            .unwrap()
+            .flatten_all()
+            .unwrap()
+            .to_vec1()
+            .unwrap()
+    }
+
+    fn max_abs_diff(a: &[f32], b: &[f32]) -> f32 {
+        assert_eq!(a.len(), b.len());
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y).abs())
+            .fold(0f32, f32::max)
+    }
+
+    fn full_precision_model(checkpoint: HashMap<String, Tensor>, dev: &Device) -> CrfModel {
+        let vb = VarBuilder::from_tensors(renamed(checkpoint), DType::F32, dev);
+        let config: Config = serde_json::from_str(&config_json()).unwrap();
+        CrfModel::load(vb, config, TagSet::V1).unwrap()
+    }
+
+    fn renamed(checkpoint: HashMap<String, Tensor>) -> HashMap<String, Tensor> {
+        checkpoint
+            .into_iter()
+            .map(|(name, t)| (candle_tensor_name(&name), t))
+            .collect()
+    }
+
+    #[test]
+    fn transitions_come_from_matrix_of_same_tag_set() {
+        let dev = Device::Cpu;
+        let mut model = full_precision_model(random_checkpoint(&dev), &dev);
+        assert!(!model.has_learned_transitions());
+
+        assert!(
+            model
+                .set_transitions(&TransitionMatrix::zeros(TagSet::V2))
+                .is_err()
+        );
+
+        let mut matrix = TransitionMatrix::zeros(TagSet::V1);
+        matrix.scores[0][1] = 2.0;
+        model.set_transitions(&matrix).unwrap();
+        assert!(model.has_learned_transitions());
+        let scores: Vec<Vec<f32>> = model.transitions.to_vec2().unwrap();
+        assert_eq!(scores, matrix.scores);
+    }
+
+    #[test]
+    fn int8_model_tracks_f32_model() {
+        let dev = Device::Cpu;
+        let dir = std::env::temp_dir().join(format!("zantetsu-q8-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let src = dir.join("model.safetensors");
+        let dst = dir.join("model-q8_0.gguf");
+
+        let checkpoint = random_checkpoint(&dev);
+        candle_core::safetensors::save(&checkpoint, &src).unwrap();
+        quantize_safetensors(&src, &dst).unwrap();
+        let full = full_precision_model(checkpoint, &dev);
+
+        let qvb = QuantizedVarBuilder::from_gguf(&dst, &dev).unwrap();
+        let qconfig: EncoderConfig = serde_json::from_str(&config_json()).unwrap();
+        let quantized = CrfModel::load_quantized(qvb, &qconfig, TagSet::V1).unwrap();
+        assert_eq!(quantized.precision(), ModelPrecision::Int8);
+
+        let max_err = max_abs_diff(&emissions(&full, &dev), &emissions(&quantized, &dev));
+        assert!(max_err < 0.05, "max_err={max_err}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn f16_model_tracks_f32_model() {
+        let dev = Device::Cpu;
+        let checkpoint = random_checkpoint(&dev);
+        let reference = emissions(&full_precision_model(checkpoint.clone(), &dev), &dev);
+        let config: EncoderConfig = serde_json::from_str(&config_json()).unwrap();
+
+        let vb = VarBuilder::from_tensors(renamed(checkpoint.clone()), DType::F16, &dev);
+        let half = CrfModel::load_half(vb, &config, TagSet::V1).unwrap();
+        assert_eq!(half.precision(), ModelPrecision::F16);
+        let max_err = max_abs_diff(&reference, &emissions(&half, &dev));
+        assert!(max_err < 0.05, "max_err={max_err}");
 
-        Ok(emissions)
+        let vb = VarBuilder::from_tensors(renamed(checkpoint), DType::BF16, &dev);
+        assert!(CrfModel::load_half(vb, &config, TagSet::V1).is_err());
     }
 }
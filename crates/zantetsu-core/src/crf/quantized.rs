@@ -0,0 +1,47 @@
+//! Int8 (GGUF `Q8_0`) weights for the DistilBERT encoder used by [`CrfModel`].
+//!
+//! Dense projections run as quantized matmuls; embeddings, layer norms and
+//! biases are dequantized to F32 at load time since they are small.
+//!
+//! [`CrfModel`]: super::model::CrfModel
+
+use std::fs::File;
+use std::path::Path;
+
+use candle_core::quantized::{GgmlDType, QTensor, gguf_file};
+use candle_core::{DType, Device, Result};
+use candle_transformers::quantized_nn;
+
+use super::encoder::DistilBertEncoder;
+use super::model::candle_tensor_name;
+
+/// DistilBERT encoder with `Q8_0` projection weights.
+pub type QuantizedDistilBertModel = DistilBertEncoder<quantized_nn::Linear>;
+
+/// Quantize a Hugging Face DistilBERT token-classification checkpoint into a
+/// GGUF file loadable with [`ModelPrecision::Int8`](super::model::ModelPrecision).
+///
+/// Rank-2 weights whose rows divide into `Q8_0` blocks are quantized; everything
+/// else (biases, layer norms, CRF transitions) is stored as F32.
+pub fn quantize_safetensors(src: &Path, dst: &Path) -> Result<()> {
+    let tensors = candle_core::safetensors::load(src, &Device::Cpu)?;
+    let block_size = GgmlDType::Q8_0.block_size();
+
+    let mut quantized = Vec::with_capacity(tensors.len());
+    for (name, tensor) in tensors {
+        let tensor = tensor.to_dtype(DType::F32)?;
+        let dtype = match tensor.dims() {
+            [_, cols] if cols % block_size == 0 => GgmlDType::Q8_0,
+            _ => GgmlDType::F32,
+        };
+        quantized.push((
+            candle_tensor_name(&name),
+            QTensor::quantize(&tensor, dtype)?,
+        ));
+    }
+    quantized.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let refs: Vec<(&str, &QTensor)> = quantized.iter().map(|(n, t)| (n.as_str(), t)).collect();
+    let mut file = File::create(dst)?;
+    gguf_file::write(&mut file, &[], &refs)
+}
@@ -0,0 +1,166 @@
+//! # CRF Transition Matrix
+//!
+//! The one on-disk format for learned CRF transition scores:
+//! `transitions.json` beside the model weights. The trainer writes it and
+//! [`NeuralParser`](crate::parser::NeuralParser) loads it, so the Viterbi
+//! decoder no longer runs on an all-zero matrix when the weights file has no
+//! `crf_transitions.weight` tensor.
+
+use std::path::Path;
+
+#[cfg(feature = "neural")]
+use candle_core::{Device, Tensor};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, ZantetsuError};
+use crate::parser::bio_tags::TagSet;
+
+/// Transition file name inside a model directory.
+pub const TRANSITIONS_FILE: &str = "transitions.json";
+
+/// Learned transition scores between BIO tags.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransitionMatrix {
+    /// Version of the [`TagSet`] the rows and columns are indexed by.
+    pub tag_set_version: u32,
+    /// Tag labels in index order (e.g. `B-TITLE`), checked against the tag set.
+    pub labels: Vec<String>,
+    /// `scores[from][to]`: score of tag `to` following tag `from`.
+    pub scores: Vec<Vec<f32>>,
+    /// Seed of the training run that learned the scores, if recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl TransitionMatrix {
+    /// Build a matrix over `tag_set` from `scores[from][to]`.
+    pub fn new(tag_set: TagSet, scores: Vec<Vec<f32>>) -> Result<Self> {
+        let matrix = Self {
+            tag_set_version: tag_set.version(),
+            labels: tag_set.tags().iter().map(ToString::to_string).collect(),
+            scores,
+            seed: None,
+        };
+        matrix.validate()?;
+        Ok(matrix)
+    }
+
+    /// An all-zero matrix: decoding then relies on emissions and the BIO constraints alone.
+    pub fn zeros(tag_set: TagSet) -> Self {
+        let num_tags = tag_set.num_tags();
+        Self {
+            tag_set_version: tag_set.version(),
+            labels: tag_set.tags().iter().map(ToString::to_string).collect(),
+            scores: vec![vec![0.0; num_tags]; num_tags],
+            seed: None,
+        }
+    }
+
+    /// Record the seed of the training run behind the matrix.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Read and validate a matrix from `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let matrix: Self = serde_json::from_str(&text).map_err(|e| {
+            ZantetsuError::ModelLoadError(format!("invalid transitions {}: {e}", path.display()))
+        })?;
+        matrix.validate()?;
+        Ok(matrix)
+    }
+
+    /// Write the matrix to `path` as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ZantetsuError::ModelLoadError(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Check the matrix against its tag set, returning that tag set.
+    ///
+    /// The tag set must be supported, the labels must name its tags in
+    /// [`BioTag`](crate::parser::BioTag) index order, and the matrix must be
+    /// square over them (at most `BioTag::NUM_TAGS` wide) with finite scores.
+    pub fn validate(&self) -> Result<TagSet> {
+        let invalid = |reason: String| ZantetsuError::ModelLoadError(reason);
+        let tag_set = TagSet::from_version(self.tag_set_version).ok_or_else(|| {
+            invalid(format!(
+                "transitions use tag set v{}, but this build supports up to v{}",
+                self.tag_set_version,
+                TagSet::LATEST.version()
+            ))
+        })?;
+
+        let num_tags = tag_set.num_tags();
+        let expected: Vec<String> = tag_set.tags().iter().map(ToString::to_string).collect();
+        if self.labels != expected {
+            return Err(invalid(format!(
+                "transition labels do not match tag set v{}",
+                tag_set.version()
+            )));
+        }
+        if self.scores.len() != num_tags || self.scores.iter().any(|row| row.len() != num_tags) {
+            return Err(invalid(format!(
+                "transition matrix must be {num_tags}x{num_tags} for tag set v{}",
+                tag_set.version()
+            )));
+        }
+        if self.scores.iter().flatten().any(|score| !score.is_finite()) {
+            return Err(invalid("transition matrix has non-finite scores".into()));
+        }
+
+        Ok(tag_set)
+    }
+
+    /// The matrix as an F32 tensor `[num_tags, num_tags]`.
+    #[cfg(feature = "neural")]
+    pub fn to_tensor(&self, device: &Device) -> candle_core::Result<Tensor> {
+        let num_tags = self.scores.len();
+        let flat: Vec<f32> = self.scores.iter().flatten().copied().collect();
+        Tensor::from_vec(flat, (num_tags, num_tags), device)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_validates_shape_and_labels() {
+        let mut scores = vec![vec![0.0; TagSet::V1.num_tags()]; TagSet::V1.num_tags()];
+        scores[0][1] = 1.5;
+        let matrix = TransitionMatrix::new(TagSet::V1, scores).unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("zantetsu-transitions-{}.json", std::process::id()));
+        matrix.save(&path).unwrap();
+        let loaded = TransitionMatrix::load(&path).unwrap();
+        assert_eq!(loaded, matrix);
+        #[cfg(feature = "neural")]
+        {
+            let tensor = loaded.to_tensor(&Device::Cpu).unwrap();
+            assert_eq!(tensor.dims(), &[17, 17]);
+        }
+        std::fs::remove_file(&path).ok();
+
+        // V1 labels with a V2-sized matrix
+        let wrong_shape = TransitionMatrix {
+            scores: TransitionMatrix::zeros(TagSet::V2).scores,
+            ..matrix.clone()
+        };
+        assert!(wrong_shape.validate().is_err());
+
+        let mut wrong_labels = matrix.clone();
+        wrong_labels.labels.swap(0, 1);
+        assert!(wrong_labels.validate().is_err());
+
+        let mut not_finite = matrix;
+        not_finite.scores[2][3] = f32::NAN;
+        assert!(not_finite.validate().is_err());
+    }
+}
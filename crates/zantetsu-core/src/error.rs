@@ -26,9 +26,9 @@ pub enum ZantetsuError {
     #[error("inference error: {0}")]
     InferenceError(String),
 
-    /// An invalid quality profile was provided.
+    /// A quality profile or client context failed to load or validate.
     #[error("invalid scoring context: {0}")]
-    InvalidContext(String),
+    InvalidContext(#[from] ConfigError),
 
     /// Neural parser error.
     #[error("neural parser error: {0}")]
@@ -37,8 +37,67 @@ pub enum ZantetsuError {
     /// Candle ML framework error.
     #[error("ML inference error: {0}")]
     CandleError(String),
+
+    /// Reading parser input failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl ZantetsuError {
+    /// Stable, machine-readable name of the variant, such as `EMPTY_INPUT`.
+    ///
+    /// The bindings expose it (as `err.code` in Node, `code` on Python
+    /// exceptions), so callers can branch on the kind of error without
+    /// matching on messages, which may change between releases.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::EmptyInput => "EMPTY_INPUT",
+            Self::ParseFailed { .. } => "PARSE_FAILED",
+            Self::RegexError(_) => "REGEX_ERROR",
+            Self::ModelLoadError(_) => "MODEL_LOAD_ERROR",
+            Self::InferenceError(_) => "INFERENCE_ERROR",
+            Self::InvalidContext(_) => "INVALID_CONTEXT",
+            Self::NeuralParser(_) => "NEURAL_PARSER_ERROR",
+            Self::CandleError(_) => "CANDLE_ERROR",
+            Self::Io(_) => "IO_ERROR",
+        }
+    }
 }
 
+/// Why a scoring configuration ([`QualityProfile`](crate::scoring::QualityProfile)
+/// or [`ClientContext`](crate::scoring::ClientContext)) was rejected.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// The TOML document is malformed or has fields of the wrong type.
+    #[error("malformed TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// The JSON document is malformed or has fields of the wrong type.
+    #[error("malformed JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A weight is negative, above 1.0 or not a number.
+    #[error("{field} must be within [0.0, 1.0], got {value}")]
+    WeightOutOfRange {
+        /// Name of the offending weight.
+        field: &'static str,
+        /// The value that was provided.
+        value: f32,
+    },
+
+    /// The weights do not sum to 1.0.
+    #[error("weights sum to {0}, expected 1.0")]
+    WeightSum(f32),
+
+    /// A scoring rule names an unknown field or has no valid action.
+    #[error("invalid scoring rule: {0}")]
+    InvalidRule(String),
+
+    /// A client context combines settings that cannot all be true.
+    #[error("inconsistent client context: {0}")]
+    InconsistentContext(String),
+}
+
 /// Result type alias for Zantetsu operations.
 pub type Result<T> = std::result::Result<T, ZantetsuError>;
 
@@ -55,9 +114,28 @@ mod tests {
             input: "bad input".into(),
         };
         assert!(err.to_string().contains("bad input"));
+
+        let err = ZantetsuError::from(ConfigError::WeightSum(1.5));
+        assert_eq!(
+            err.to_string(),
+            "invalid scoring context: weights sum to 1.5, expected 1.0"
+        );
     }
 
     #[test]
+    fn error_codes_are_stable() {
+        assert_eq!(ZantetsuError::EmptyInput.code(), "EMPTY_INPUT");
+        assert_eq!(
+            ZantetsuError::ModelLoadError("missing".into()).code(),
+            "MODEL_LOAD_ERROR"
+        );
+        assert_eq!(
+            ZantetsuError::from(ConfigError::WeightSum(1.5)).code(),
+            "INVALID_CONTEXT"
+        );
+    }
+
+    #[test]
     fn error_is_send_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<ZantetsuError>();
@@ -30,11 +30,25 @@ pub mod scoring;
 pub mod types;
 
 // Re-export primary API
-pub use error::{Result, ZantetsuError};
+#[cfg(feature = "neural")]
+pub use crf::model::ModelPrecision;
+pub use error::{ConfigError, Result, ZantetsuError};
 pub use parser::{
-    BioTag, HeuristicParser, NeuralParser, Parser, ParserConfig, Tokenizer, ViterbiDecoder,
+    BioTag, EngineCost, FallbackReason, FieldSpan, HeuristicParser, ParseEngine, ParseTrace,
+    ParserObserver, TagEvidence, TagSet, Tokenizer, ViterbiDecoder,
+};
+#[cfg(feature = "neural")]
+pub use parser::{
+    CharCnnParser, FusionMode, MODEL_DIR_ENV, NeuralParser, Parser, ParserConfig, ParserPool,
+    QUANTIZED_MODEL_FILE, SelectionStrategy, TitleCasing, default_parser, parse, parse_json,
+    parse_with_mode, quantize_model, reset_default_parser,
+};
+pub use scoring::{
+    BatteryState, ClientContext, CpuTier, DeviceType, GroupBlocklist, GroupRegistry,
+    NetworkQuality, Preset, QualityProfile, QualityScores, ReleaseShape, ScoreReport,
 };
-pub use scoring::{ClientContext, DeviceType, NetworkQuality, QualityProfile, QualityScores};
 pub use types::{
-    AudioCodec, EpisodeSpec, MediaSource, ParseMode, ParseResult, Resolution, VideoCodec,
+    AudioCodec, AudioTrack, EpisodeNumbering, EpisodeSpec, FieldConfidence, FieldDiff,
+    FieldProvenance, FieldSource, MediaSource, MergeStrategy, ParseEpisodeSpecError, ParseField,
+    ParseMode, ParseResult, ParseWarning, Resolution, SeasonSpec, VideoCodec,
 };
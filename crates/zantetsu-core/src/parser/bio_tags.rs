@@ -5,6 +5,8 @@
 
 use std::fmt;
 
+use crate::types::ParseField;
+
 /// BIO tags for labeling tokens in anime filenames.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BioTag {
@@ -31,11 +33,72 @@ pub enum BioTag {
     Version,
     // Outside (irrelevant token)
     Outside,
+    // Tag set v2 (appended so v1 indices stay stable)
+    BeginEpisodeTitle,
+    InsideEpisodeTitle,
+    Language,
+    AudioChannels,
+    Part,
+    ReleaseFlag,
+}
+
+/// Version of the tag set a model's classifier head was trained on.
+///
+/// Tags are only ever appended, so the label indices of an older tag set
+/// map onto the same [`BioTag`]s as the latest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagSet {
+    /// The original 17 tags.
+    V1,
+    /// Adds episode title, language, audio channels, part and release flags.
+    V2,
+}
+
+impl TagSet {
+    /// The tag set new models are trained on.
+    pub const LATEST: TagSet = TagSet::V2;
+
+    /// Numeric version, as stored in model metadata.
+    pub fn version(self) -> u32 {
+        match self {
+            TagSet::V1 => 1,
+            TagSet::V2 => 2,
+        }
+    }
+
+    /// Look up a tag set by numeric version.
+    pub fn from_version(version: u32) -> Option<Self> {
+        match version {
+            1 => Some(TagSet::V1),
+            2 => Some(TagSet::V2),
+            _ => None,
+        }
+    }
+
+    /// Look up the tag set whose size matches a classifier head with `num_labels` outputs.
+    pub fn from_num_labels(num_labels: usize) -> Option<Self> {
+        [TagSet::V1, TagSet::V2]
+            .into_iter()
+            .find(|tag_set| tag_set.num_tags() == num_labels)
+    }
+
+    /// Number of tags (classifier outputs) in this tag set.
+    pub fn num_tags(self) -> usize {
+        match self {
+            TagSet::V1 => 17,
+            TagSet::V2 => BioTag::NUM_TAGS,
+        }
+    }
+
+    /// Tags in this set, in index order.
+    pub fn tags(self) -> &'static [BioTag] {
+        &BioTag::all_tags()[..self.num_tags()]
+    }
 }
 
 impl BioTag {
     /// Total number of distinct tags.
-    pub const NUM_TAGS: usize = 17;
+    pub const NUM_TAGS: usize = 23;
 
     /// Get all possible tags in order.
     pub fn all_tags() -> &'static [BioTag] {
@@ -57,6 +120,12 @@ impl BioTag {
             BioTag::Extension,
             BioTag::Version,
             BioTag::Outside,
+            BioTag::BeginEpisodeTitle,
+            BioTag::InsideEpisodeTitle,
+            BioTag::Language,
+            BioTag::AudioChannels,
+            BioTag::Part,
+            BioTag::ReleaseFlag,
         ]
     }
 
@@ -80,6 +149,12 @@ impl BioTag {
             BioTag::Extension => 14,
             BioTag::Version => 15,
             BioTag::Outside => 16,
+            BioTag::BeginEpisodeTitle => 17,
+            BioTag::InsideEpisodeTitle => 18,
+            BioTag::Language => 19,
+            BioTag::AudioChannels => 20,
+            BioTag::Part => 21,
+            BioTag::ReleaseFlag => 22,
         }
     }
 
@@ -103,6 +178,12 @@ impl BioTag {
             14 => Some(BioTag::Extension),
             15 => Some(BioTag::Version),
             16 => Some(BioTag::Outside),
+            17 => Some(BioTag::BeginEpisodeTitle),
+            18 => Some(BioTag::InsideEpisodeTitle),
+            19 => Some(BioTag::Language),
+            20 => Some(BioTag::AudioChannels),
+            21 => Some(BioTag::Part),
+            22 => Some(BioTag::ReleaseFlag),
             _ => None,
         }
     }
@@ -111,7 +192,11 @@ impl BioTag {
     pub fn is_begin(&self) -> bool {
         matches!(
             self,
-            BioTag::BeginTitle | BioTag::BeginGroup | BioTag::BeginEpisode | BioTag::BeginSeason
+            BioTag::BeginTitle
+                | BioTag::BeginGroup
+                | BioTag::BeginEpisode
+                | BioTag::BeginSeason
+                | BioTag::BeginEpisodeTitle
         )
     }
 
@@ -123,6 +208,7 @@ impl BioTag {
                 | BioTag::InsideGroup
                 | BioTag::InsideEpisode
                 | BioTag::InsideSeason
+                | BioTag::InsideEpisodeTitle
         )
     }
 
@@ -141,39 +227,31 @@ impl BioTag {
             BioTag::Crc32 => Some(EntityType::Crc32),
             BioTag::Extension => Some(EntityType::Extension),
             BioTag::Version => Some(EntityType::Version),
+            BioTag::BeginEpisodeTitle | BioTag::InsideEpisodeTitle => {
+                Some(EntityType::EpisodeTitle)
+            }
+            BioTag::Language => Some(EntityType::Language),
+            BioTag::AudioChannels => Some(EntityType::AudioChannels),
+            BioTag::Part => Some(EntityType::Part),
+            BioTag::ReleaseFlag => Some(EntityType::ReleaseFlag),
             BioTag::Outside => None,
         }
     }
 
     /// Check if transitioning from `from` tag to `to` tag is valid.
     pub fn is_valid_transition(from: BioTag, to: BioTag) -> bool {
-        // Forbidden transitions (return false)
-        match (from, to) {
-            // Can't have I-* after O or different B-*
-            (BioTag::InsideTitle, BioTag::BeginTitle) => false,
-            (BioTag::InsideGroup, BioTag::BeginGroup) => false,
-            (BioTag::InsideEpisode, BioTag::BeginEpisode) => false,
-            (BioTag::InsideSeason, BioTag::BeginSeason) => false,
-            // Can't transition from one entity's I-* to another entity's I-*
-            (BioTag::InsideTitle, BioTag::InsideGroup) => false,
-            (BioTag::InsideTitle, BioTag::InsideEpisode) => false,
-            (BioTag::InsideTitle, BioTag::InsideSeason) => false,
-            (BioTag::InsideGroup, BioTag::InsideTitle) => false,
-            (BioTag::InsideGroup, BioTag::InsideEpisode) => false,
-            (BioTag::InsideGroup, BioTag::InsideSeason) => false,
-            (BioTag::InsideEpisode, BioTag::InsideTitle) => false,
-            (BioTag::InsideEpisode, BioTag::InsideGroup) => false,
-            (BioTag::InsideEpisode, BioTag::InsideSeason) => false,
-            (BioTag::InsideSeason, BioTag::InsideTitle) => false,
-            (BioTag::InsideSeason, BioTag::InsideGroup) => false,
-            (BioTag::InsideSeason, BioTag::InsideEpisode) => false,
+        if to.is_inside() {
             // Can't have I-* without preceding B-* or I-* of same type
-            (BioTag::Outside, BioTag::InsideTitle) => false,
-            (BioTag::Outside, BioTag::InsideGroup) => false,
-            (BioTag::Outside, BioTag::InsideEpisode) => false,
-            (BioTag::Outside, BioTag::InsideSeason) => false,
-            _ => true,
+            // (and can't jump from one entity's I-* to another entity's I-*)
+            if from == BioTag::Outside
+                || (from.is_inside() && from.entity_type() != to.entity_type())
+            {
+                return false;
+            }
         }
+
+        // Can't start the same entity again straight from its I-*
+        !(from.is_inside() && to.is_begin() && from.entity_type() == to.entity_type())
     }
 }
 
@@ -197,6 +275,12 @@ impl fmt::Display for BioTag {
             BioTag::Extension => write!(f, "EXTENSION"),
             BioTag::Version => write!(f, "VERSION"),
             BioTag::Outside => write!(f, "O"),
+            BioTag::BeginEpisodeTitle => write!(f, "B-EPISODE_TITLE"),
+            BioTag::InsideEpisodeTitle => write!(f, "I-EPISODE_TITLE"),
+            BioTag::Language => write!(f, "LANGUAGE"),
+            BioTag::AudioChannels => write!(f, "AUDIO_CHANNELS"),
+            BioTag::Part => write!(f, "PART"),
+            BioTag::ReleaseFlag => write!(f, "RELEASE_FLAG"),
         }
     }
 }
@@ -216,6 +300,36 @@ pub enum EntityType {
     Crc32,
     Extension,
     Version,
+    EpisodeTitle,
+    Language,
+    AudioChannels,
+    Part,
+    ReleaseFlag,
+}
+
+impl EntityType {
+    /// The [`ParseResult`](crate::types::ParseResult) field this entity fills.
+    pub fn field(self) -> ParseField {
+        match self {
+            EntityType::Title => ParseField::Title,
+            EntityType::Group => ParseField::Group,
+            EntityType::Episode => ParseField::Episode,
+            EntityType::Season => ParseField::Season,
+            EntityType::Resolution => ParseField::Resolution,
+            EntityType::VCodec => ParseField::VideoCodec,
+            EntityType::ACodec => ParseField::AudioCodec,
+            EntityType::Source => ParseField::Source,
+            EntityType::Year => ParseField::Year,
+            EntityType::Crc32 => ParseField::Crc32,
+            EntityType::Extension => ParseField::Extension,
+            EntityType::Version => ParseField::Version,
+            EntityType::EpisodeTitle => ParseField::EpisodeTitle,
+            EntityType::Language => ParseField::Language,
+            EntityType::AudioChannels => ParseField::AudioChannels,
+            EntityType::Part => ParseField::Part,
+            EntityType::ReleaseFlag => ParseField::ReleaseFlags,
+        }
+    }
 }
 
 /// An extracted entity with token indices.
@@ -272,6 +386,38 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_tag_set_versions_keep_v1_indices() {
+        assert_eq!(TagSet::V1.num_tags(), 17);
+        assert_eq!(TagSet::V1.tags().last(), Some(&BioTag::Outside));
+        assert_eq!(TagSet::LATEST.num_tags(), BioTag::NUM_TAGS);
+        assert_eq!(BioTag::Outside.index(), 16);
+        assert_eq!(TagSet::from_num_labels(17), Some(TagSet::V1));
+        assert_eq!(TagSet::from_num_labels(23), Some(TagSet::V2));
+        assert_eq!(TagSet::from_num_labels(3), None);
+        assert_eq!(TagSet::from_version(2), Some(TagSet::V2));
+    }
+
+    #[test]
+    fn test_episode_title_transitions() {
+        assert!(BioTag::is_valid_transition(
+            BioTag::BeginEpisodeTitle,
+            BioTag::InsideEpisodeTitle
+        ));
+        assert!(!BioTag::is_valid_transition(
+            BioTag::Outside,
+            BioTag::InsideEpisodeTitle
+        ));
+        assert!(!BioTag::is_valid_transition(
+            BioTag::InsideTitle,
+            BioTag::InsideEpisodeTitle
+        ));
+        assert_eq!(
+            BioTag::InsideEpisodeTitle.entity_type(),
+            Some(EntityType::EpisodeTitle)
+        );
+    }
+
     #[test]
     fn test_is_begin() {
         assert!(BioTag::BeginTitle.is_begin());
@@ -0,0 +1,160 @@
+//! # Character-CNN Parser
+//!
+//! Middle-tier engine between the regex heuristics and the DistilBERT
+//! [`NeuralParser`]: a [`CharCnnModel`] scores every character and the shared
+//! CRF decoding turns those scores into entities. A fraction of the size and
+//! latency of the transformer, for devices where it is too heavy.
+
+use std::path::Path;
+
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+
+use crate::crf::char_cnn::{CharCnnConfig, CharCnnModel, encode_chars};
+use crate::error::{Result, ZantetsuError};
+use crate::parser::heuristic::TagEvidence;
+use crate::parser::neural::{CONFIG_FILE, MODEL_FILE, NeuralParser, constrain_emissions};
+use crate::parser::viterbi::ViterbiDecoder;
+use crate::types::ParseResult;
+
+/// Directory searched by [`CharCnnParser::init_model`].
+const DEFAULT_MODEL_DIR: &str = "models/char_cnn";
+
+/// Character-level CNN + CRF parser for anime filenames.
+pub struct CharCnnParser {
+    model: Option<CharCnnModel>,
+    viterbi: ViterbiDecoder,
+    device: Device,
+}
+
+impl CharCnnParser {
+    /// Create a parser with no model loaded.
+    pub fn new() -> Self {
+        Self {
+            model: None,
+            viterbi: ViterbiDecoder::new(0),
+            device: Device::Cpu,
+        }
+    }
+
+    /// Initialize the model from `models/char_cnn`.
+    pub fn init_model(&mut self) -> Result<()> {
+        self.init_model_from_dir(Path::new(DEFAULT_MODEL_DIR))
+    }
+
+    /// Initialize the model from a directory holding `model.safetensors` and
+    /// `config.json` (a serialized [`CharCnnConfig`]), as written by
+    /// `zantetsu-trainer`.
+    pub fn init_model_from_dir(&mut self, dir: &Path) -> Result<()> {
+        let model_file = dir.join(MODEL_FILE);
+        if !model_file.exists() {
+            return Err(ZantetsuError::ModelLoadError(format!(
+                "Character model not found at {}",
+                model_file.display()
+            )));
+        }
+
+        let config_path = dir.join(CONFIG_FILE);
+        let config_str = std::fs::read_to_string(&config_path)?;
+        let config: CharCnnConfig = serde_json::from_str(&config_str).map_err(|e| {
+            ZantetsuError::ModelLoadError(format!("invalid config {}: {e}", config_path.display()))
+        })?;
+
+        let vb = unsafe {
+            VarBuilder::from_mmaped_safetensors(&[&model_file], DType::F32, &self.device)
+        }
+        .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+        let model = CharCnnModel::load(vb, &config)
+            .map_err(|e| ZantetsuError::ModelLoadError(e.to_string()))?;
+
+        self.viterbi = ViterbiDecoder::new(model.tag_set().num_tags());
+        self.model = Some(model);
+        Ok(())
+    }
+
+    /// Returns `true` once a model is loaded.
+    pub fn is_ready(&self) -> bool {
+        self.model.is_some()
+    }
+
+    /// Parse a filename with the character model.
+    pub fn parse(&self, input: &str) -> Result<ParseResult> {
+        self.parse_with_evidence(input, &[])
+    }
+
+    /// Parse with characters inside `evidence` spans forced to the given tags
+    /// (see [`NeuralParser::parse_with_evidence`]).
+    pub fn parse_with_evidence(
+        &self,
+        input: &str,
+        evidence: &[TagEvidence],
+    ) -> Result<ParseResult> {
+        if input.trim().is_empty() {
+            return Err(ZantetsuError::EmptyInput);
+        }
+
+        let model = self.model.as_ref().ok_or_else(|| {
+            ZantetsuError::NeuralParser("Character model is not initialized".into())
+        })?;
+
+        let ids = encode_chars(input);
+        let mut scores: Vec<Vec<f32>> = Tensor::new(ids.as_slice(), &self.device)
+            .and_then(|ids| ids.unsqueeze(0))
+            .and_then(|ids| model.forward(&ids))
+            .and_then(|emissions| emissions.squeeze(0))
+            .and_then(|emissions| emissions.to_vec2())
+            .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+
+        let offsets: Vec<(usize, usize)> = input
+            .char_indices()
+            .map(|(start, c)| (start, start + c.len_utf8()))
+            .collect();
+        constrain_emissions(&mut scores, &offsets, evidence);
+
+        NeuralParser::decode(&self.viterbi, input, &offsets, &scores, &model.transitions)
+    }
+}
+
+impl Default for CharCnnParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_with_a_saved_model() {
+        let dir = std::env::temp_dir().join(format!("zantetsu-char-cnn-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut parser = CharCnnParser::new();
+        assert!(!parser.is_ready());
+        assert!(parser.init_model_from_dir(&dir).is_err());
+
+        let config = CharCnnConfig {
+            filters: 8,
+            hidden_dim: 8,
+            ..CharCnnConfig::default()
+        };
+        let varmap = candle_nn::VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+        CharCnnModel::load(vb, &config).unwrap();
+        varmap.save(dir.join(MODEL_FILE)).unwrap();
+        std::fs::write(
+            dir.join(CONFIG_FILE),
+            serde_json::to_string(&config).unwrap(),
+        )
+        .unwrap();
+
+        parser.init_model_from_dir(&dir).unwrap();
+        assert!(parser.is_ready());
+        let result = parser.parse("[Group] Título - 01.mkv").unwrap();
+        assert_eq!(result.input, "[Group] Título - 01.mkv");
+        assert!(matches!(parser.parse("  "), Err(ZantetsuError::EmptyInput)));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
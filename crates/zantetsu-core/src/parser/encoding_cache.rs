@@ -0,0 +1,111 @@
+//! # Encoding Cache
+//!
+//! LRU cache of tokenizer encodings keyed by input string. RSS pipelines
+//! re-see the same filenames constantly; a hit skips tokenization and, when
+//! emission caching is enabled, the model forward pass as well.
+
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+use lru::LruCache;
+
+/// A tokenized input, plus its emission scores once computed (if cached).
+#[derive(Debug)]
+pub(crate) struct CachedEncoding {
+    pub ids: Vec<u32>,
+    pub special_tokens_mask: Vec<u32>,
+    pub offsets: Vec<(usize, usize)>,
+    pub emissions: OnceLock<Vec<Vec<f32>>>,
+}
+
+impl CachedEncoding {
+    pub fn new(encoding: &tokenizers::Encoding) -> Self {
+        Self {
+            ids: encoding.get_ids().to_vec(),
+            special_tokens_mask: encoding.get_special_tokens_mask().to_vec(),
+            offsets: encoding.get_offsets().to_vec(),
+            emissions: OnceLock::new(),
+        }
+    }
+}
+
+/// Thread-safe LRU cache of [`CachedEncoding`]s.
+pub(crate) struct EncodingCache {
+    entries: Mutex<LruCache<String, Arc<CachedEncoding>>>,
+    cache_emissions: bool,
+}
+
+impl EncodingCache {
+    /// Cache up to `capacity` encodings; `cache_emissions` also keeps their
+    /// emission scores.
+    pub fn new(capacity: NonZeroUsize, cache_emissions: bool) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            cache_emissions,
+        }
+    }
+
+    /// Whether emission scores are kept alongside encodings.
+    pub fn caches_emissions(&self) -> bool {
+        self.cache_emissions
+    }
+
+    /// Look up `input`, marking it as most recently used.
+    pub fn get(&self, input: &str) -> Option<Arc<CachedEncoding>> {
+        self.lock().get(input).cloned()
+    }
+
+    /// Store the encoding of `input`, evicting the least recently used entry
+    /// when full.
+    pub fn insert(&self, input: &str, encoding: CachedEncoding) -> Arc<CachedEncoding> {
+        let encoding = Arc::new(encoding);
+        self.lock().put(input.to_string(), Arc::clone(&encoding));
+        encoding
+    }
+
+    /// Drop every entry (e.g. after loading a different model).
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<String, Arc<CachedEncoding>>> {
+        // Entries are plain data, so a panic elsewhere cannot leave them inconsistent.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoding(id: u32) -> CachedEncoding {
+        CachedEncoding {
+            ids: vec![id],
+            special_tokens_mask: vec![0],
+            offsets: vec![(0, 1)],
+            emissions: OnceLock::new(),
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = EncodingCache::new(NonZeroUsize::new(2).unwrap(), true);
+        cache.insert("a", encoding(1));
+        cache.insert("b", encoding(2));
+        assert_eq!(cache.get("a").unwrap().ids, vec![1]);
+
+        cache.insert("c", encoding(3));
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+
+        let hit = cache.get("c").unwrap();
+        hit.emissions.set(vec![vec![0.5]]).unwrap();
+        assert_eq!(
+            cache.get("c").unwrap().emissions.get(),
+            Some(&vec![vec![0.5]])
+        );
+
+        cache.clear();
+        assert!(cache.get("a").is_none());
+    }
+}
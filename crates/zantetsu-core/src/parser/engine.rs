@@ -0,0 +1,108 @@
+//! # Parse Engines
+//!
+//! [`ParseEngine`] is the common interface of everything that can turn a
+//! filename into a [`ParseResult`]. The built-in parsers implement it, and
+//! user engines (say, for a private tracker's naming scheme) can be added to a
+//! [`Parser`](crate::parser::Parser) with
+//! [`Parser::with_engine`](crate::parser::Parser::with_engine) while keeping
+//! its fallback and ensemble logic.
+
+use crate::error::Result;
+#[cfg(feature = "neural")]
+use crate::parser::char_cnn::CharCnnParser;
+use crate::parser::heuristic::HeuristicParser;
+#[cfg(feature = "neural")]
+use crate::parser::neural::NeuralParser;
+use crate::types::ParseResult;
+
+/// Rough per-call cost of an engine, cheapest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EngineCost {
+    /// String matching only (regexes, lookups).
+    Cheap,
+    /// A small model or heavier text processing.
+    Moderate,
+    /// Transformer inference.
+    Expensive,
+}
+
+/// Something that parses anime filenames.
+///
+/// Engines must be `Send + Sync` so a [`Parser`](crate::parser::Parser)
+/// holding them can be shared across threads.
+pub trait ParseEngine: Send + Sync {
+    /// Short identifier used in logs (e.g. `"heuristic"`).
+    fn name(&self) -> &str;
+
+    /// How expensive one [`parse`](Self::parse) call is; cheaper engines are
+    /// consulted first.
+    fn cost_hint(&self) -> EngineCost;
+
+    /// Parse `input`, returning an error when the engine cannot handle it.
+    fn parse(&self, input: &str) -> Result<ParseResult>;
+}
+
+impl ParseEngine for HeuristicParser {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn cost_hint(&self) -> EngineCost {
+        EngineCost::Cheap
+    }
+
+    fn parse(&self, input: &str) -> Result<ParseResult> {
+        HeuristicParser::parse(self, input)
+    }
+}
+
+#[cfg(feature = "neural")]
+impl ParseEngine for CharCnnParser {
+    fn name(&self) -> &str {
+        "char_cnn"
+    }
+
+    fn cost_hint(&self) -> EngineCost {
+        EngineCost::Moderate
+    }
+
+    fn parse(&self, input: &str) -> Result<ParseResult> {
+        CharCnnParser::parse(self, input)
+    }
+}
+
+#[cfg(feature = "neural")]
+impl ParseEngine for NeuralParser {
+    fn name(&self) -> &str {
+        "neural"
+    }
+
+    fn cost_hint(&self) -> EngineCost {
+        EngineCost::Expensive
+    }
+
+    fn parse(&self, input: &str) -> Result<ParseResult> {
+        NeuralParser::parse(self, input)
+    }
+}
+
+#[cfg(all(test, feature = "neural"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_engines_are_ordered_by_cost() {
+        let heuristic = HeuristicParser::new().unwrap();
+        let char_cnn = CharCnnParser::new();
+        let neural = NeuralParser::new().unwrap();
+        let engines: [&dyn ParseEngine; 3] = [&neural, &heuristic, &char_cnn];
+
+        let mut by_cost = engines.to_vec();
+        by_cost.sort_by_key(|engine| engine.cost_hint());
+        let names: Vec<&str> = by_cost.iter().map(|engine| engine.name()).collect();
+        assert_eq!(names, ["heuristic", "char_cnn", "neural"]);
+
+        let result = ParseEngine::parse(&heuristic, "[Group] Title - 01.mkv").unwrap();
+        assert_eq!(result.title.as_deref(), Some("Title"));
+    }
+}
@@ -1,10 +1,122 @@
 use regex::Regex;
 
 use crate::error::{Result, ZantetsuError};
+use crate::parser::bio_tags::BioTag;
+use crate::parser::trace::RegexMatch;
 use crate::types::{
-    AudioCodec, EpisodeSpec, MediaSource, ParseMode, ParseResult, Resolution, VideoCodec,
+    AudioCodec, AudioTrack, EpisodeNumbering, EpisodeSpec, MediaSource, ParseField, ParseMode,
+    ParseResult, Resolution, SeasonSpec, VideoCodec,
 };
 
+/// A byte span of the input whose tag the regex rules are certain of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TagEvidence {
+    /// Byte offset where the span starts.
+    pub start: usize,
+    /// Byte offset one past the end of the span.
+    pub end: usize,
+    /// Tag every token inside the span must receive.
+    pub tag: BioTag,
+}
+
+/// A byte span of the input holding the value of one field of a parse
+/// result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSpan {
+    /// The field whose value the span holds.
+    pub field: ParseField,
+    /// Byte offset where the span starts.
+    pub start: usize,
+    /// Byte offset one past the end of the span.
+    pub end: usize,
+}
+
+/// Non-overlapping field spans, claimed one at a time.
+#[derive(Default)]
+struct SpanClaims(Vec<FieldSpan>);
+
+impl SpanClaims {
+    fn is_free(&self, start: usize, end: usize) -> bool {
+        start < end
+            && self
+                .0
+                .iter()
+                .all(|span| end <= span.start || span.end <= start)
+    }
+
+    /// Claims the first free candidate, returning whether there was one.
+    fn claim(
+        &mut self,
+        field: ParseField,
+        candidates: impl IntoIterator<Item = (usize, usize)>,
+    ) -> bool {
+        let Some((start, end)) = candidates
+            .into_iter()
+            .find(|&(start, end)| self.is_free(start, end))
+        else {
+            return false;
+        };
+        self.0.push(FieldSpan { field, start, end });
+        true
+    }
+
+    /// Claims the first free run of `text`'s words in `input`, separated by
+    /// anything but letters and digits and compared ASCII case-insensitively.
+    fn claim_words(&mut self, field: ParseField, input: &str, text: &str) -> bool {
+        let haystack = input.to_ascii_lowercase();
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect();
+        let Some(first) = words.first() else {
+            return false;
+        };
+
+        let at_boundary = |offset: usize| {
+            let before = haystack[..offset].chars().next_back();
+            let after = haystack[offset..].chars().next();
+            !(before.is_some_and(char::is_alphanumeric) && after.is_some_and(char::is_alphanumeric))
+        };
+        let candidates = haystack
+            .match_indices(first.as_str())
+            .filter_map(|(start, _)| {
+                let mut end = start;
+                for (i, word) in words.iter().enumerate() {
+                    if i > 0 {
+                        end += haystack[end..]
+                            .find(|c: char| c.is_alphanumeric())
+                            .unwrap_or(haystack.len() - end);
+                    }
+                    if !haystack[end..].starts_with(word.as_str()) || !at_boundary(end) {
+                        return None;
+                    }
+                    end += word.len();
+                }
+                at_boundary(end).then_some((start, end))
+            });
+        self.claim(field, candidates)
+    }
+}
+
+/// `(start, end)` of capture group `group` of every match of `re`, where
+/// `accept` holds for the group's text.
+fn group_spans<'a>(
+    re: &'a Regex,
+    input: &'a str,
+    group: usize,
+    accept: impl Fn(&str) -> bool + 'a,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    re.captures_iter(input)
+        .filter_map(move |caps| caps.get(group))
+        .filter(move |m| accept(m.as_str()))
+        .map(|m| (m.start(), m.end()))
+}
+
+fn is_number(text: &str, number: u32) -> bool {
+    text.parse::<u32>().is_ok_and(|value| value == number)
+}
+
 /// Heuristic parser using optimized regex patterns and scene naming rules.
 ///
 /// This is the `ParseMode::Light` engine — fast, zero-ML-overhead parsing
@@ -29,31 +141,40 @@ pub struct HeuristicParser {
     // Season and episode patterns
     re_season_episode: Regex,
     re_episode_range: Regex,
+    re_episode_count: Regex,
     re_episode_version: Regex,
     re_episode: Regex,
     re_explicit_episode: Regex,
     re_dash_episode: Regex,
     re_season: Regex,
+    re_season_range: Regex,
     #[allow(dead_code)]
     re_season_long: Regex,
 
     // Version patterns
     re_version: Regex,
 
+    // "Part 2" / "Cour 2"
+    re_part: Regex,
+
     // Year patterns
     re_year: Regex,
+    re_year_range: Regex,
 
     // File patterns
     re_extension: Regex,
     re_group: Regex,
+    re_bracket_token: Regex,
 
     // Special episode patterns
     #[allow(dead_code)]
     re_special_episode: Regex,
+    re_special_number: Regex,
 
     // Multi-audio patterns
     #[allow(dead_code)]
     re_dual_audio: Regex,
+    re_audio_track: Regex,
 
     // Subtitle patterns
     #[allow(dead_code)]
@@ -95,6 +216,10 @@ impl HeuristicParser {
             re_episode_range: Regex::new(
                 r"(?i)(?:[\s\-_\.]|(?:^|[\s\-_\.\[\(])ep?\.?\s*)(\d{1,4})\s*[-~]\s*(\d{1,4})\b",
             )?,
+            // Episode totals: "(24 episodes)", "[Complete 13 Eps]", "[12/12]"
+            re_episode_count: Regex::new(
+                r"(?i)[\(\[]\s*(?:complete\s+)?(\d{1,4})\s*(?:episodes?|eps)\.?\s*(?:complete\s*)?[\)\]]|\[(\d{1,4})\s*/\s*(\d{1,4})\]",
+            )?,
             re_episode_version: Regex::new(
                 r"(?i)(?:[\s\-_\.]|(?:^|[\s\-_\.\[\(])ep?\.?\s*)(\d{1,4})v(\d)\b",
             )?,
@@ -110,27 +235,47 @@ impl HeuristicParser {
 
             // Season patterns
             re_season: Regex::new(r"(?i)(?:\bS|season\s*)(\d{1,2})\b")?,
+            // Season batches: "S1-S3", "S01 ~ S03", "Seasons 1-3", "Season 1-3"
+            re_season_range: Regex::new(
+                r"(?i)\bS(\d{1,2})\s*[-~]\s*S(\d{1,2})\b|\bseasons\s*(\d{1,2})\s*[-~]\s*(\d{1,2})\b|\bseason\s*(\d{1,2})[-~](\d{1,2})\b",
+            )?,
             re_season_long: Regex::new(r"(?i)\bseason\s*(\d{1,2})\b")?,
 
             // Version patterns
             re_version: Regex::new(r"(?i)\[v(\d)\]|\bv(\d)\b")?,
 
+            re_part: Regex::new(r"(?i)\b(?:part|cour)[\s\._]*(\d{1,2})\b")?,
+
             // Year patterns
             re_year: Regex::new(r"\b((?:19|20)\d{2})\b")?,
+            // Year spans like "(1999-2001)", brackets included so the title strip is clean
+            re_year_range: Regex::new(
+                r"[\(\[]?\b((?:19|20)\d{2})\s*[-~]\s*((?:19|20)\d{2})\b[\)\]]?",
+            )?,
 
             // File patterns
             re_extension: Regex::new(r"\.(\w{2,4})$")?,
             re_group: Regex::new(r"^\[([^\]]+)\]")?,
+            re_bracket_token: Regex::new(r"[\[\(]([^\[\]\(\)]+)[\]\)]")?,
 
             // Special episode patterns (OVA, ONA, Movie, etc.)
             re_special_episode: Regex::new(
                 r"(?i)\b(OVA|ONA|OAD|Movie|Film|Special|SP|ED|NCOP|NCED|Preview|Trailer|Extra)\b",
             )?,
 
+            // Numbered specials: "SP1", "SP 02", "Special 3"
+            re_special_number: Regex::new(
+                r"(?i)(?:^|[\s\-_\.\[\(])(?:sp|special)\s*\.?\s*(\d{1,3})\b",
+            )?,
+
             // Multi-audio patterns
             re_dual_audio: Regex::new(
                 r"(?i)\b(?:dual[\s\-_]?audio|multi[\s\-_]?audio|multi[\s\-_]?(?:lang|language))\b",
             )?,
+            // Audio tracks: codec, then optional channels and language ("FLAC 2.0 JPN")
+            re_audio_track: Regex::new(
+                r"(?i)\b(flac|aac|opus|ac3|dts(?:-?hd)?|truehd|true\shd|mp3|vorbis|ogg|e-?aac\+?)\b(?:[\s_]*(\d\.\d))?(?:[\s_]+(jpn|jap|japanese|eng|english|ger|deu|german|fre|fra|french|spa|spanish|ita|italian|por|portuguese|rus|russian|chi|chinese|kor|korean)\b)?",
+            )?,
 
             // Subtitle patterns
             re_multi_sub: Regex::new(
@@ -164,17 +309,33 @@ impl HeuristicParser {
         result.resolution = self.extract_resolution(trimmed);
         result.video_codec = self.extract_video_codec(trimmed);
         result.audio_codec = self.extract_audio_codec(trimmed);
+        result.audio_tracks = self.extract_audio_tracks(trimmed);
         result.source = self.extract_source(trimmed);
         result.year = self.extract_year(trimmed);
+        result.year_range = self.extract_year_range(trimmed);
 
         // Season and episode: try S##E## combined first
         let (se_season, se_episode) = self.extract_season_episode(trimmed);
         result.season = se_season.or_else(|| self.extract_season(trimmed));
-        result.episode = se_episode.or_else(|| self.extract_episode(trimmed, &result));
+        result.episode_count = self.extract_episode_count(trimmed);
+
+        // "[03/12]" is episode 3 of 12; take the episode before the mask
+        // below removes it
+        let fraction_episode = self.extract_fraction_episode(trimmed);
+
+        // Mask season batches and episode totals so "Seasons 1-3" or
+        // "[Complete 13 Eps]" can't be read as an episode
+        let episode_input = self.re_season_range.replace_all(trimmed, " ");
+        let episode_input = self.re_episode_count.replace_all(&episode_input, " ");
+        result.episode = se_episode
+            .or(fraction_episode)
+            .or_else(|| self.extract_episode(&episode_input, &result));
         result.version = self.extract_version(trimmed, &result.episode);
+        result.episode_numbering = EpisodeNumbering::infer(result.season, result.episode.as_ref());
 
         // Title extraction: everything between group tag and first metadata token
         result.title = self.extract_title(trimmed, &result);
+        result.extras = self.extract_extras(trimmed, &result);
 
         // Compute confidence based on how many fields were extracted
         result.confidence = self.compute_confidence(&result);
@@ -182,6 +343,334 @@ impl HeuristicParser {
         Ok(result)
     }
 
+    /// Parse like [`parse`](Self::parse) into the JSON of the result; see
+    /// [`ParseResult::to_json`].
+    pub fn parse_json(&self, input: &str) -> Result<String> {
+        self.parse(input).map(|result| result.to_json())
+    }
+
+    /// Spans of `input` that are unambiguous: bracketed CRC32s, resolutions,
+    /// video and audio codecs, and the file extension.
+    ///
+    /// Used to constrain neural decoding (see `FusionMode::ConstrainNeural`),
+    /// so patterns that can collide with titles (years, sources, episode
+    /// numbers) are deliberately left out.
+    pub fn evidence(&self, input: &str) -> Vec<TagEvidence> {
+        let spans = [
+            (&self.re_crc32, BioTag::Crc32, 1),
+            (&self.re_resolution, BioTag::Resolution, 0),
+            (&self.re_vcodec, BioTag::VCodec, 0),
+            (&self.re_acodec, BioTag::ACodec, 0),
+            (&self.re_extension, BioTag::Extension, 1),
+        ];
+
+        let mut evidence: Vec<TagEvidence> = spans
+            .into_iter()
+            .flat_map(|(re, tag, group)| {
+                re.captures_iter(input)
+                    .filter_map(move |caps| caps.get(group))
+                    .map(move |m| TagEvidence {
+                        start: m.start(),
+                        end: m.end(),
+                        tag,
+                    })
+            })
+            .collect();
+        evidence.sort_by_key(|span| span.start);
+        evidence
+    }
+
+    /// Byte spans of `input` holding the fields of `result`, sorted by
+    /// position.
+    ///
+    /// Each value is located with the pattern that extracts it and checked
+    /// against `result`, so a word shared by two fields (the "no" of
+    /// "[Nekomoe kissaten no] Kimetsu no Yaiba") lands in the right one.
+    /// Spans never overlap: unambiguous fields are located first, free text
+    /// (titles, languages, flags) last. `result` may come from any engine;
+    /// values that do not appear in `input` get no span.
+    pub fn field_spans(&self, input: &str, result: &ParseResult) -> Vec<FieldSpan> {
+        let offset = input.len() - input.trim_start().len();
+        let input = input.trim();
+        let mut claims = SpanClaims::default();
+
+        if let Some(group) = &result.group {
+            let bracketed = group_spans(&self.re_group, input, 1, |text| {
+                text.trim().eq_ignore_ascii_case(group)
+            })
+            .map(|(start, end)| trim_span(input, start, end));
+            if !claims.claim(ParseField::Group, bracketed) {
+                claims.claim_words(ParseField::Group, input, group);
+            }
+        }
+        if let Some(extension) = &result.extension {
+            claims.claim(
+                ParseField::Extension,
+                group_spans(&self.re_extension, input, 1, |text| {
+                    text.eq_ignore_ascii_case(extension)
+                }),
+            );
+        }
+        if let Some(crc32) = &result.crc32 {
+            let bracketed = group_spans(&self.re_crc32, input, 1, |text| {
+                text.eq_ignore_ascii_case(crc32)
+            });
+            if !claims.claim(ParseField::Crc32, bracketed) {
+                claims.claim_words(ParseField::Crc32, input, crc32);
+            }
+        }
+        if let Some(resolution) = result.resolution {
+            let accept = |height: &str| Self::resolution_from(height) == Some(resolution);
+            let standard = self
+                .re_resolution
+                .captures_iter(input)
+                .filter(|c| accept(&c[1]))
+                .filter_map(|c| c.get(0));
+            let dimensions = self
+                .re_resolution_dim
+                .captures_iter(input)
+                .filter(|c| accept(&c[2]))
+                .filter_map(|c| c.get(0));
+            claims.claim(
+                ParseField::Resolution,
+                standard.chain(dimensions).map(|m| (m.start(), m.end())),
+            );
+        }
+        if let Some(codec) = result.video_codec {
+            claims.claim(
+                ParseField::VideoCodec,
+                group_spans(&self.re_vcodec, input, 1, |text| {
+                    Self::video_codec_from(text) == Some(codec)
+                }),
+            );
+        }
+        if let Some(codec) = result.audio_codec {
+            claims.claim(
+                ParseField::AudioCodec,
+                group_spans(&self.re_acodec, input, 1, |text| {
+                    Self::audio_codec_from(text) == Some(codec)
+                }),
+            );
+        }
+        if let Some(channels) = &result.audio_channels {
+            claims.claim_words(ParseField::AudioChannels, input, channels);
+        }
+        if let Some(source) = result.source {
+            // Same byte offsets as `input`: one space per underscore.
+            let normalized = input.replace('_', " ");
+            let spans: Vec<_> = group_spans(&self.re_source, &normalized, 1, |text| {
+                Self::source_from(text) == Some(source)
+            })
+            .collect();
+            claims.claim(ParseField::Source, spans);
+        }
+        if let Some(year) = result.year {
+            claims.claim(
+                ParseField::Year,
+                group_spans(&self.re_year, input, 1, |text| {
+                    is_number(text, u32::from(year))
+                }),
+            );
+        }
+        if let Some((start, end)) = result.year_range {
+            for year in [start, end] {
+                claims.claim(
+                    ParseField::YearRange,
+                    group_spans(&self.re_year_range, input, 1, |text| {
+                        is_number(text, u32::from(year))
+                    })
+                    .chain(group_spans(
+                        &self.re_year_range,
+                        input,
+                        2,
+                        |text| is_number(text, u32::from(year)),
+                    )),
+                );
+            }
+        }
+        self.claim_season_and_episode(&mut claims, input, result);
+        if let Some(version) = result.version {
+            let spans = self.re_version.captures_iter(input).filter_map(|c| {
+                let number = c.get(1).or_else(|| c.get(2))?;
+                if !is_number(number.as_str(), u32::from(version)) {
+                    return None;
+                }
+                Some(trim_span(input, c.get(0)?.start(), number.end()))
+            });
+            claims.claim(ParseField::Version, spans);
+        }
+        if let Some(part) = result.part {
+            let spans = self
+                .re_part
+                .captures_iter(input)
+                .filter(|c| is_number(&c[1], part))
+                .filter_map(|c| c.get(0).map(|m| (m.start(), m.end())));
+            claims.claim(ParseField::Part, spans);
+        }
+        if let Some(language) = &result.language {
+            claims.claim_words(ParseField::Language, input, language);
+        }
+        for flag in &result.release_flags {
+            claims.claim_words(ParseField::ReleaseFlags, input, flag);
+        }
+        if let Some(title) = &result.title {
+            claims.claim_words(ParseField::Title, input, title);
+        }
+        if let Some(episode_title) = &result.episode_title {
+            claims.claim_words(ParseField::EpisodeTitle, input, episode_title);
+        }
+
+        let mut spans = claims.0;
+        for span in &mut spans {
+            span.start += offset;
+            span.end += offset;
+        }
+        spans.sort_by_key(|span| span.start);
+        spans
+    }
+
+    /// Season and episode spans, trying the patterns in the order
+    /// [`parse`](Self::parse) does.
+    fn claim_season_and_episode(&self, claims: &mut SpanClaims, input: &str, result: &ParseResult) {
+        let number = |episode: &EpisodeSpec| match episode {
+            EpisodeSpec::Single(number) | EpisodeSpec::Special(number) => Some(*number),
+            EpisodeSpec::Zero => Some(0),
+            _ => None,
+        };
+
+        if let (Some(SeasonSpec::Single(season)), Some(episode)) = (result.season, &result.episode)
+            && let Some(episode) = number(episode)
+            && let Some(caps) = self
+                .re_season_episode
+                .captures_iter(input)
+                .find(|c| is_number(&c[1], season) && is_number(&c[2], episode))
+            && let (Some(whole), Some(season), Some(episode)) =
+                (caps.get(0), caps.get(1), caps.get(2))
+        {
+            claims.claim(ParseField::Season, [(whole.start(), season.end())]);
+            claims.claim(ParseField::Episode, [(season.end(), episode.end())]);
+            return;
+        }
+
+        match result.season {
+            Some(SeasonSpec::Single(season)) => {
+                claims.claim(
+                    ParseField::Season,
+                    group_spans(&self.re_season, input, 1, |text| is_number(text, season)),
+                );
+            }
+            Some(SeasonSpec::Range(start, end)) => {
+                let spans = self.re_season_range.captures_iter(input).filter_map(|c| {
+                    let mut bounds = c.iter().skip(1).flatten();
+                    if !(is_number(bounds.next()?.as_str(), start)
+                        && is_number(bounds.next()?.as_str(), end))
+                    {
+                        return None;
+                    }
+                    c.get(0).map(|m| (m.start(), m.end()))
+                });
+                claims.claim(ParseField::Season, spans);
+            }
+            None => {}
+        }
+
+        let Some(episode) = &result.episode else {
+            return;
+        };
+        match episode {
+            EpisodeSpec::Special(number) => {
+                let spans = self
+                    .re_special_number
+                    .captures_iter(input)
+                    .filter(|c| is_number(&c[1], *number))
+                    .filter_map(|c| c.get(0).map(|m| trim_span(input, m.start(), m.end())));
+                claims.claim(ParseField::Episode, spans);
+            }
+            EpisodeSpec::Version { episode, version } => {
+                let spans = self
+                    .re_episode_version
+                    .captures_iter(input)
+                    .filter_map(|c| {
+                        if !(is_number(&c[1], *episode) && is_number(&c[2], u32::from(*version))) {
+                            return None;
+                        }
+                        Some((c.get(1)?.start(), c.get(2)?.end()))
+                    });
+                claims.claim(ParseField::Episode, spans);
+            }
+            EpisodeSpec::Range(start, end) => {
+                let spans = self.re_episode_range.captures_iter(input).filter_map(|c| {
+                    if !(is_number(&c[1], *start) && is_number(&c[2], *end)) {
+                        return None;
+                    }
+                    Some((c.get(1)?.start(), c.get(2)?.end()))
+                });
+                claims.claim(ParseField::Episode, spans);
+            }
+            EpisodeSpec::Multi(numbers) => {
+                for &number in numbers {
+                    claims.claim(ParseField::Episode, digit_runs(input, number));
+                }
+            }
+            EpisodeSpec::Single(_) | EpisodeSpec::Zero => {
+                let number = number(episode).unwrap_or_default();
+                let accept = |text: &str| is_number(text, number);
+                let mut dashed: Vec<_> =
+                    group_spans(&self.re_dash_episode, input, 1, accept).collect();
+                // The last " - NN" is the episode; earlier ones are titles.
+                dashed.reverse();
+                let candidates = group_spans(&self.re_explicit_episode, input, 1, accept)
+                    .chain(dashed)
+                    .chain(group_spans(&self.re_episode, input, 1, accept))
+                    .chain(digit_runs(input, number));
+                claims.claim(ParseField::Episode, candidates);
+            }
+        }
+    }
+
+    /// Every match of every pattern in `input`, sorted by position, for
+    /// [`ParseTrace`](crate::parser::ParseTrace).
+    pub fn regex_matches(&self, input: &str) -> Vec<RegexMatch> {
+        let patterns = [
+            ("resolution", &self.re_resolution),
+            ("resolution_dim", &self.re_resolution_dim),
+            ("vcodec", &self.re_vcodec),
+            ("acodec", &self.re_acodec),
+            ("source", &self.re_source),
+            ("crc32", &self.re_crc32),
+            ("crc32_no_bracket", &self.re_crc32_no_bracket),
+            ("season_episode", &self.re_season_episode),
+            ("special_number", &self.re_special_number),
+            ("episode_range", &self.re_episode_range),
+            ("episode_count", &self.re_episode_count),
+            ("episode_version", &self.re_episode_version),
+            ("episode", &self.re_episode),
+            ("explicit_episode", &self.re_explicit_episode),
+            ("dash_episode", &self.re_dash_episode),
+            ("season", &self.re_season),
+            ("season_range", &self.re_season_range),
+            ("version", &self.re_version),
+            ("year", &self.re_year),
+            ("year_range", &self.re_year_range),
+            ("extension", &self.re_extension),
+            ("group", &self.re_group),
+        ];
+
+        let mut matches: Vec<RegexMatch> = patterns
+            .into_iter()
+            .flat_map(|(pattern, re)| {
+                re.find_iter(input).map(move |m| RegexMatch {
+                    pattern,
+                    start: m.start(),
+                    end: m.end(),
+                    text: m.as_str().to_string(),
+                })
+            })
+            .collect();
+        matches.sort_by_key(|m| (m.start, m.end));
+        matches
+    }
+
     fn extract_group(&self, input: &str) -> Option<String> {
         self.re_group
             .captures(input)
@@ -194,6 +683,50 @@ impl HeuristicParser {
             .map(|c| c[1].to_lowercase())
     }
 
+    /// Bracketed tokens that no field pattern matches and that are not the
+    /// group or title.
+    fn extract_extras(&self, input: &str, result: &ParseResult) -> Vec<String> {
+        let claimed_by = [
+            &self.re_resolution,
+            &self.re_resolution_dim,
+            &self.re_vcodec,
+            &self.re_acodec,
+            &self.re_source,
+            &self.re_crc32,
+            &self.re_season_episode,
+            &self.re_special_number,
+            &self.re_episode_range,
+            &self.re_episode_count,
+            &self.re_episode_version,
+            &self.re_season,
+            &self.re_season_range,
+            &self.re_version,
+            &self.re_year,
+            &self.re_year_range,
+        ];
+        let is_field = |text: &str| {
+            [&result.group, &result.title]
+                .into_iter()
+                .flatten()
+                .any(|field| field.eq_ignore_ascii_case(text))
+        };
+
+        let mut extras: Vec<String> = Vec::new();
+        for caps in self.re_bracket_token.captures_iter(input) {
+            let text = caps[1].trim();
+            if text.is_empty()
+                || text.chars().all(|c| c.is_ascii_digit())
+                || is_field(text)
+                || claimed_by.iter().any(|re| re.is_match(&caps[0]))
+                || extras.iter().any(|extra| extra == text)
+            {
+                continue;
+            }
+            extras.push(text.to_string());
+        }
+        extras
+    }
+
     fn extract_crc32(&self, input: &str) -> Option<String> {
         self.re_crc32.captures(input).map(|c| c[1].to_uppercase())
     }
@@ -218,85 +751,120 @@ impl HeuristicParser {
         if let Some(res) = self
             .re_resolution
             .captures(input)
-            .and_then(|c| match &c[1] {
-                "2160" => Some(Resolution::UHD2160),
-                "1080" => Some(Resolution::FHD1080),
-                "720" => Some(Resolution::HD720),
-                "480" => Some(Resolution::SD480),
-                _ => None,
-            })
+            .and_then(|c| Self::resolution_from(&c[1]))
         {
             return Some(res);
         }
 
         // Try WIDTHxHEIGHT format (e.g. 1920x1080, 1280x720)
-        self.re_resolution_dim.captures(input).and_then(|c| {
-            let height: u32 = c[2].parse().ok()?;
-            match height {
-                2160 => Some(Resolution::UHD2160),
-                1080 => Some(Resolution::FHD1080),
-                720 => Some(Resolution::HD720),
-                480 => Some(Resolution::SD480),
-                _ => None,
-            }
-        })
+        self.re_resolution_dim
+            .captures(input)
+            .and_then(|c| Self::resolution_from(&c[2]))
+    }
+
+    fn resolution_from(height: &str) -> Option<Resolution> {
+        match height.parse::<u32>().ok()? {
+            2160 => Some(Resolution::UHD2160),
+            1080 => Some(Resolution::FHD1080),
+            720 => Some(Resolution::HD720),
+            480 => Some(Resolution::SD480),
+            _ => None,
+        }
     }
 
     fn extract_video_codec(&self, input: &str) -> Option<VideoCodec> {
-        self.re_vcodec.captures(input).and_then(|c| {
-            let codec = c[1].to_lowercase();
-            match codec.as_str() {
-                "x264" | "x.264" | "h264" | "h.264" => Some(VideoCodec::H264),
-                "x265" | "x.265" | "h265" | "h.265" | "hevc" => Some(VideoCodec::HEVC),
-                "av1" => Some(VideoCodec::AV1),
-                "vp9" => Some(VideoCodec::VP9),
-                "mpeg4" | "xvid" => Some(VideoCodec::MPEG4),
-                _ => None,
-            }
-        })
+        self.re_vcodec
+            .captures(input)
+            .and_then(|c| Self::video_codec_from(&c[1]))
+    }
+
+    fn video_codec_from(codec: &str) -> Option<VideoCodec> {
+        match codec.to_lowercase().as_str() {
+            "x264" | "x.264" | "h264" | "h.264" => Some(VideoCodec::H264),
+            "x265" | "x.265" | "h265" | "h.265" | "hevc" => Some(VideoCodec::HEVC),
+            "av1" => Some(VideoCodec::AV1),
+            "vp9" => Some(VideoCodec::VP9),
+            "mpeg4" | "xvid" => Some(VideoCodec::MPEG4),
+            _ => None,
+        }
     }
 
     fn extract_audio_codec(&self, input: &str) -> Option<AudioCodec> {
-        self.re_acodec.captures(input).and_then(|c| {
-            let codec = c[1].to_lowercase();
-            match codec.as_str() {
-                "flac" => Some(AudioCodec::FLAC),
-                "aac" => Some(AudioCodec::AAC),
-                "opus" => Some(AudioCodec::Opus),
-                "ac3" => Some(AudioCodec::AC3),
-                s if s.starts_with("dts") => Some(AudioCodec::DTS),
-                s if s.contains("truehd") || s.contains("true hd") => Some(AudioCodec::TrueHD),
-                "mp3" => Some(AudioCodec::MP3),
-                "vorbis" | "ogg" => Some(AudioCodec::Vorbis),
-                s if s.starts_with("e-aac") || s.starts_with("eaac") => Some(AudioCodec::EAAC),
-                _ => None,
-            }
-        })
+        self.re_acodec
+            .captures(input)
+            .and_then(|c| Self::audio_codec_from(&c[1]))
+    }
+
+    fn extract_audio_tracks(&self, input: &str) -> Vec<AudioTrack> {
+        self.re_audio_track
+            .captures_iter(input)
+            .filter_map(|c| {
+                Some(AudioTrack {
+                    codec: Self::audio_codec_from(&c[1])?,
+                    channels: c.get(2).map(|m| m.as_str().to_string()),
+                    language: c.get(3).map(|m| m.as_str().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    fn audio_codec_from(codec: &str) -> Option<AudioCodec> {
+        match codec.to_lowercase().as_str() {
+            "flac" => Some(AudioCodec::FLAC),
+            "aac" => Some(AudioCodec::AAC),
+            "opus" => Some(AudioCodec::Opus),
+            "ac3" => Some(AudioCodec::AC3),
+            s if s.starts_with("dts") => Some(AudioCodec::DTS),
+            s if s.contains("truehd") || s.contains("true hd") => Some(AudioCodec::TrueHD),
+            "mp3" => Some(AudioCodec::MP3),
+            "vorbis" | "ogg" => Some(AudioCodec::Vorbis),
+            s if s.starts_with("e-aac") || s.starts_with("eaac") => Some(AudioCodec::EAAC),
+            _ => None,
+        }
     }
 
     fn extract_source(&self, input: &str) -> Option<MediaSource> {
         // Normalize underscores to spaces for matching (e.g. _Blu-Ray_ patterns)
         let normalized = input.replace('_', " ");
-        self.re_source.captures(&normalized).and_then(|c| {
-            let source = c[1].to_lowercase().replace([' ', '-'], "");
-            match source.as_str() {
-                s if s.contains("remux") => Some(MediaSource::BluRayRemux),
-                s if s.contains("blu") => Some(MediaSource::BluRay),
-                "bdrip" => Some(MediaSource::BluRay),
-                "bd" => Some(MediaSource::BluRay),
-                "webdl" => Some(MediaSource::WebDL),
-                "web" => Some(MediaSource::WebDL),
-                "webrip" => Some(MediaSource::WebRip),
-                "hdtv" => Some(MediaSource::HDTV),
-                s if s.starts_with("dvd") => Some(MediaSource::DVD),
-                s if s == "laserdisc" || s == "ld" => Some(MediaSource::LaserDisc),
-                "vhs" => Some(MediaSource::VHS),
-                _ => None,
-            }
+        self.re_source
+            .captures(&normalized)
+            .and_then(|c| Self::source_from(&c[1]))
+    }
+
+    fn source_from(source: &str) -> Option<MediaSource> {
+        let source = source.to_lowercase().replace([' ', '-'], "");
+        match source.as_str() {
+            s if s.contains("remux") => Some(MediaSource::BluRayRemux),
+            s if s.contains("blu") => Some(MediaSource::BluRay),
+            "bdrip" => Some(MediaSource::BluRay),
+            "bd" => Some(MediaSource::BluRay),
+            "webdl" => Some(MediaSource::WebDL),
+            "web" => Some(MediaSource::WebDL),
+            "webrip" => Some(MediaSource::WebRip),
+            "hdtv" => Some(MediaSource::HDTV),
+            s if s.starts_with("dvd") => Some(MediaSource::DVD),
+            s if s == "laserdisc" || s == "ld" => Some(MediaSource::LaserDisc),
+            "vhs" => Some(MediaSource::VHS),
+            _ => None,
+        }
+    }
+
+    fn extract_season(&self, input: &str) -> Option<SeasonSpec> {
+        self.extract_season_range(input)
+            .or_else(|| self.extract_single_season(input).map(SeasonSpec::Single))
+    }
+
+    /// Extract season batch notation ("S1-S3", "Seasons 1-3").
+    fn extract_season_range(&self, input: &str) -> Option<SeasonSpec> {
+        self.re_season_range.captures_iter(input).find_map(|c| {
+            let mut bounds = c.iter().skip(1).flatten();
+            let start: u32 = bounds.next()?.as_str().parse().ok()?;
+            let end: u32 = bounds.next()?.as_str().parse().ok()?;
+            (start < end).then_some(SeasonSpec::Range(start, end))
         })
     }
 
-    fn extract_season(&self, input: &str) -> Option<u32> {
+    fn extract_single_season(&self, input: &str) -> Option<u32> {
         // Try S## pattern (but not S##E## which is handled by extract_season_episode)
         self.re_season.captures(input).and_then(|c| {
             // Verify it's not part of S##E## — if so, re_season_episode handles it
@@ -314,11 +882,14 @@ impl HeuristicParser {
     }
 
     /// Extract combined S##E## season+episode notation.
-    fn extract_season_episode(&self, input: &str) -> (Option<u32>, Option<EpisodeSpec>) {
+    fn extract_season_episode(&self, input: &str) -> (Option<SeasonSpec>, Option<EpisodeSpec>) {
         if let Some(caps) = self.re_season_episode.captures(input) {
             let season: u32 = caps[1].parse().ok().unwrap_or(0);
             let episode: u32 = caps[2].parse().ok().unwrap_or(0);
-            return (Some(season), Some(EpisodeSpec::Single(episode)));
+            return (
+                Some(SeasonSpec::Single(season)),
+                Some(EpisodeSpec::numbered(episode)),
+            );
         }
         (None, None)
     }
@@ -328,7 +899,7 @@ impl HeuristicParser {
         // (between 1980 and current year + 1)
         self.re_year.captures(input).and_then(|c| {
             let year: u16 = c[1].parse().ok()?;
-            if (1980..=2030).contains(&year) {
+            if is_plausible_year(year) {
                 Some(year)
             } else {
                 None
@@ -336,12 +907,42 @@ impl HeuristicParser {
         })
     }
 
+    fn extract_year_range(&self, input: &str) -> Option<(u16, u16)> {
+        self.re_year_range.captures_iter(input).find_map(|c| {
+            let start: u16 = c[1].parse().ok()?;
+            let end: u16 = c[2].parse().ok()?;
+            (start < end && is_plausible_year(start) && is_plausible_year(end))
+                .then_some((start, end))
+        })
+    }
+
+    fn extract_episode_count(&self, input: &str) -> Option<u32> {
+        self.re_episode_count.captures_iter(input).find_map(|c| {
+            let count: u32 = c.get(1).or_else(|| c.get(3))?.as_str().parse().ok()?;
+            (count > 0).then_some(count)
+        })
+    }
+
+    /// The episode of an "[03/12]" episode-of-total token.
+    fn extract_fraction_episode(&self, input: &str) -> Option<EpisodeSpec> {
+        self.re_episode_count.captures_iter(input).find_map(|c| {
+            let episode: u32 = c.get(2)?.as_str().parse().ok()?;
+            (episode > 0).then_some(EpisodeSpec::Single(episode))
+        })
+    }
+
     fn extract_episode(&self, input: &str, result: &ParseResult) -> Option<EpisodeSpec> {
         // S##E## is handled by extract_season_episode, skip if present
         if self.re_season_episode.is_match(input) {
             return None;
         }
 
+        // Phase 0: Numbered specials "SP1", "Special 2"
+        if let Some(caps) = self.re_special_number.captures(input) {
+            let number: u32 = caps[1].parse().ok()?;
+            return Some(EpisodeSpec::Special(number));
+        }
+
         // Phase 1: Versioned episodes "12v2" — try all, validate
         for caps in self.re_episode_version.captures_iter(input) {
             let episode: u32 = match caps[1].parse().ok() {
@@ -370,6 +971,11 @@ impl HeuristicParser {
             if start >= end || self.is_resolution_number(start) {
                 continue;
             }
+            // A year span like "1999-2001" is not an episode batch
+            if self.is_year_or_resolution(start, result) && self.is_year_or_resolution(end, result)
+            {
+                continue;
+            }
             // Reject if preceded by "Part" or "Season" (e.g. "Part 2 - 25")
             if let Some(m) = caps.get(0) {
                 let prefix = input[..m.start()].to_lowercase();
@@ -386,7 +992,7 @@ impl HeuristicParser {
         if let Some(caps) = self.re_explicit_episode.captures(input) {
             let ep: u32 = caps[1].parse().ok()?;
             if !self.is_year_or_resolution(ep, result) {
-                return Some(EpisodeSpec::Single(ep));
+                return Some(EpisodeSpec::numbered(ep));
             }
         }
 
@@ -412,7 +1018,7 @@ impl HeuristicParser {
             last_dash_ep = Some(ep); // Keep updating — we want the LAST one
         }
         if let Some(ep) = last_dash_ep {
-            return Some(EpisodeSpec::Single(ep));
+            return Some(EpisodeSpec::numbered(ep));
         }
 
         // Phase 5: Bare number fallback — only if no explicit or dash patterns matched
@@ -468,7 +1074,7 @@ impl HeuristicParser {
                 }
             }
 
-            return Some(EpisodeSpec::Single(ep));
+            return Some(EpisodeSpec::numbered(ep));
         }
 
         None
@@ -486,6 +1092,11 @@ impl HeuristicParser {
         {
             return true;
         }
+        if let Some((start, end)) = result.year_range
+            && (n == u32::from(start) || n == u32::from(end))
+        {
+            return true;
+        }
         self.is_resolution_number(n)
     }
 
@@ -531,6 +1142,10 @@ impl HeuristicParser {
             &self.re_source,
             &self.re_crc32,
             &self.re_season_episode,
+            &self.re_special_number,
+            &self.re_season_range,
+            &self.re_episode_count,
+            &self.re_year_range,
             &self.re_episode_range,
             &self.re_episode_version,
             &self.re_season,
@@ -697,6 +1312,41 @@ impl HeuristicParser {
     }
 }
 
+/// `start..end` without surrounding brackets, whitespace or separators.
+fn trim_span(input: &str, start: usize, end: usize) -> (usize, usize) {
+    let text = &input[start..end];
+    let trimmed = text.trim_start_matches(|c: char| !c.is_alphanumeric());
+    let start = start + (text.len() - trimmed.len());
+    let trimmed = trimmed.trim_end_matches(|c: char| !c.is_alphanumeric());
+    (start, start + trimmed.len())
+}
+
+/// Spans of standalone digit runs in `input` whose value is `number`.
+fn digit_runs(input: &str, number: u32) -> Vec<(usize, usize)> {
+    let bytes = input.as_bytes();
+    let mut runs = Vec::new();
+    let mut start = None;
+    for i in 0..=bytes.len() {
+        let digit = bytes.get(i).is_some_and(u8::is_ascii_digit);
+        match (digit, start) {
+            (true, None) => start = Some(i),
+            (false, Some(run)) => {
+                if is_number(&input[run..i], number) {
+                    runs.push((run, i));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// Release years outside this window are treated as plain numbers.
+fn is_plausible_year(year: u16) -> bool {
+    (1980..=2030).contains(&year)
+}
+
 /// Strip common non-title tokens from the end of a title string.
 fn strip_trailing_noise(title: &str) -> String {
     let noise_tokens = [
@@ -737,6 +1387,80 @@ mod tests {
         HeuristicParser::new().unwrap()
     }
 
+    #[test]
+    fn evidence_marks_unambiguous_spans() {
+        let input = "[SubsPlease] Jujutsu Kaisen - 24 (1080p) [HEVC AAC] [A1B2C3D4].mkv";
+        let evidence = parser().evidence(input);
+        let tagged: Vec<(&str, BioTag)> = evidence
+            .iter()
+            .map(|span| (&input[span.start..span.end], span.tag))
+            .collect();
+
+        assert_eq!(
+            tagged,
+            vec![
+                ("1080p", BioTag::Resolution),
+                ("HEVC", BioTag::VCodec),
+                ("AAC", BioTag::ACodec),
+                ("A1B2C3D4", BioTag::Crc32),
+                ("mkv", BioTag::Extension),
+            ]
+        );
+    }
+
+    #[test]
+    fn field_spans_locate_every_extracted_field() {
+        let p = parser();
+        let input =
+            " [Nekomoe kissaten no] Kimetsu no Yaiba S02E05 v2 (BD 1080p x265 FLAC) [A1B2C3D4].mkv";
+        let result = p.parse(input).unwrap();
+        let fields: Vec<(&str, ParseField)> = p
+            .field_spans(input, &result)
+            .iter()
+            .map(|span| (&input[span.start..span.end], span.field))
+            .collect();
+
+        assert_eq!(
+            fields,
+            vec![
+                ("Nekomoe kissaten no", ParseField::Group),
+                ("Kimetsu no Yaiba", ParseField::Title),
+                ("S02", ParseField::Season),
+                ("E05", ParseField::Episode),
+                ("v2", ParseField::Version),
+                ("BD", ParseField::Source),
+                ("1080p", ParseField::Resolution),
+                ("x265", ParseField::VideoCodec),
+                ("FLAC", ParseField::AudioCodec),
+                ("A1B2C3D4", ParseField::Crc32),
+                ("mkv", ParseField::Extension),
+            ]
+        );
+    }
+
+    #[test]
+    fn field_spans_follow_the_result_not_the_patterns() {
+        let p = parser();
+        let input = "Bocchi the Rock - 2 - 12 [720p]";
+        let mut result = ParseResult::new(input, ParseMode::Full);
+        result.part = Some(2);
+        result.episode = Some(EpisodeSpec::Single(2));
+        result.resolution = Some(Resolution::FHD1080);
+
+        let spans = p.field_spans(input, &result);
+        let episode = spans
+            .iter()
+            .find(|span| span.field == ParseField::Episode)
+            .unwrap();
+        assert_eq!(&input[episode.start..episode.end], "2");
+        assert_eq!(episode.start, 18);
+        assert!(
+            spans
+                .iter()
+                .all(|span| !matches!(span.field, ParseField::Resolution | ParseField::Part))
+        );
+    }
+
     #[test]
     fn empty_input_errors() {
         let p = parser();
@@ -788,7 +1512,7 @@ mod tests {
             .unwrap();
 
         assert_eq!(r.group.as_deref(), Some("Judas"));
-        assert_eq!(r.season, Some(3));
+        assert_eq!(r.season, Some(SeasonSpec::Single(3)));
         assert_eq!(r.episode, Some(EpisodeSpec::Range(1, 12)));
         assert_eq!(r.resolution, Some(Resolution::FHD1080));
     }
@@ -882,6 +1606,118 @@ mod tests {
         assert_eq!(r.year, Some(2024));
     }
 
+    #[test]
+    fn season_range_batches() {
+        let p = parser();
+        for input in [
+            "[Judas] Attack on Titan S1-S3 [1080p][HEVC]",
+            "[Judas] Attack on Titan S01-S03 Complete [1080p][HEVC]",
+            "[Judas] Attack on Titan Seasons 1-3 [1080p][HEVC]",
+        ] {
+            let r = p.parse(input).unwrap();
+            assert_eq!(r.season, Some(SeasonSpec::Range(1, 3)), "{input}");
+            assert_eq!(r.episode, None, "{input}");
+            assert_eq!(r.title.as_deref(), Some("Attack on Titan"), "{input}");
+        }
+
+        let r = p.parse("[Group] Title S2 - 05 [720p].mkv").unwrap();
+        assert_eq!(r.season, Some(SeasonSpec::Single(2)));
+        assert_eq!(r.episode, Some(EpisodeSpec::Single(5)));
+    }
+
+    #[test]
+    fn episode_count_tokens() {
+        let p = parser();
+        for (input, count) in [
+            ("[Group] Mushishi (26 episodes) [BD 1080p]", 26),
+            ("[Group] Mushishi [Complete 13 Eps] [BD 1080p]", 13),
+        ] {
+            let r = p.parse(input).unwrap();
+            assert_eq!(r.episode_count, Some(count), "{input}");
+            assert_eq!(r.episode, None, "{input}");
+            assert_eq!(r.title.as_deref(), Some("Mushishi"), "{input}");
+        }
+
+        let r = p.parse("[SubsPlease] Frieren - 01 (1080p).mkv").unwrap();
+        assert_eq!(r.episode_count, None);
+    }
+
+    #[test]
+    fn episode_of_total_token() {
+        let p = parser();
+        for (input, title, episode) in [
+            ("Show [03/12].mkv", "Show", 3),
+            ("[Group] Mushishi [12/12] [BD 1080p]", "Mushishi", 12),
+        ] {
+            let r = p.parse(input).unwrap();
+            assert_eq!(r.episode, Some(EpisodeSpec::Single(episode)), "{input}");
+            assert_eq!(r.episode_count, Some(12), "{input}");
+            assert_eq!(r.title.as_deref(), Some(title), "{input}");
+        }
+    }
+
+    #[test]
+    fn year_range_is_not_an_episode_range() {
+        let p = parser();
+        let r = p
+            .parse("[Group] Cowboy Bebop (1998-1999) [BD 1080p].mkv")
+            .unwrap();
+        assert_eq!(r.year_range, Some((1998, 1999)));
+        assert_eq!(r.title.as_deref(), Some("Cowboy Bebop"));
+        assert_eq!(r.episode, None);
+
+        let r = p.parse("Cowboy Bebop 1998-1999 - 05 [720p].mkv").unwrap();
+        assert_eq!(r.year_range, Some((1998, 1999)));
+        assert_eq!(r.episode, Some(EpisodeSpec::Single(5)));
+    }
+
+    #[test]
+    fn specials_and_episode_zero() {
+        let p = parser();
+        let r = p.parse("[Group] Show - 00 [1080p].mkv").unwrap();
+        assert_eq!(r.episode, Some(EpisodeSpec::Zero));
+        assert_eq!(r.title.as_deref(), Some("Show"));
+
+        for (input, number) in [
+            ("[Group] Show - SP1 [1080p].mkv", 1),
+            ("[Group] Show - Special 02 [1080p].mkv", 2),
+        ] {
+            let r = p.parse(input).unwrap();
+            assert_eq!(r.episode, Some(EpisodeSpec::Special(number)), "{input}");
+            assert_eq!(r.title.as_deref(), Some("Show"), "{input}");
+        }
+    }
+
+    #[test]
+    fn unclaimed_brackets_become_extras() {
+        let p = parser();
+        let r = p
+            .parse("[Group] Show - 05 [Weekly][1080p HEVC][Multiple Subtitle][ABCD1234].mkv")
+            .unwrap();
+        assert_eq!(r.extras, ["Weekly", "Multiple Subtitle"]);
+
+        let r = p
+            .parse("[SubsPlease] Frieren - 01 (1080p) [A1B2C3D4].mkv")
+            .unwrap();
+        assert!(r.extras.is_empty());
+    }
+
+    #[test]
+    fn dual_audio_tracks() {
+        let p = parser();
+        let r = p
+            .parse("[Group] Show - 05 [BD 1080p HEVC FLAC 2.0 JPN + AAC 2.0 ENG].mkv")
+            .unwrap();
+        assert_eq!(r.audio_codec, Some(AudioCodec::FLAC));
+        let tracks: Vec<String> = r.audio_tracks.iter().map(ToString::to_string).collect();
+        assert_eq!(tracks, ["FLAC 2.0 JPN", "AAC 2.0 ENG"]);
+
+        let r = p
+            .parse("[SubsPlease] Frieren - 01 (1080p) [AAC].mkv")
+            .unwrap();
+        assert_eq!(r.audio_tracks, [AudioTrack::new(AudioCodec::AAC)]);
+    }
+
     #[test]
     fn confidence_scales_with_fields() {
         let p = parser();
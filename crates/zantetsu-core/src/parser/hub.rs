@@ -0,0 +1,123 @@
+//! # Model Hub
+//!
+//! Downloads the neural parser's model files from the Hugging Face Hub and
+//! keeps them in a local cache. Requires the `hub` feature.
+
+use std::path::{Path, PathBuf};
+
+use hf_hub::api::sync::ApiBuilder;
+use hf_hub::{Repo, RepoType};
+
+use crate::crf::manifest::{MANIFEST_FILE, sha256_file};
+use crate::crf::transitions::TRANSITIONS_FILE;
+use crate::error::{Result, ZantetsuError};
+use crate::parser::neural::{CONFIG_FILE, MODEL_FILE, TOKENIZER_FILE};
+
+/// Default model repository on the Hugging Face Hub.
+pub const DEFAULT_REPO: &str = "enrell/zantetsu-ner";
+
+/// Revision used when none is requested.
+const DEFAULT_REVISION: &str = "main";
+
+/// Cache directory for downloaded models (`<platform cache>/zantetsu/models`).
+///
+/// Falls back to `.cache/zantetsu/models` when the platform has no cache directory.
+pub fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from(".cache"))
+        .join("zantetsu")
+        .join("models")
+}
+
+/// Download (or reuse from cache) the model, tokenizer, config and (if
+/// published) manifest and transitions of `repo_id` at `revision`, returning
+/// the directory that holds them.
+pub fn fetch_model(repo_id: &str, revision: Option<&str>) -> Result<PathBuf> {
+    let api = ApiBuilder::new()
+        .with_cache_dir(cache_dir())
+        .with_progress(false)
+        .build()
+        .map_err(|e| ZantetsuError::ModelLoadError(format!("failed to create hub client: {e}")))?;
+    let repo = api.repo(Repo::with_revision(
+        repo_id.to_string(),
+        RepoType::Model,
+        revision.unwrap_or(DEFAULT_REVISION).to_string(),
+    ));
+
+    let mut dir = None;
+    for file in [MODEL_FILE, TOKENIZER_FILE, CONFIG_FILE] {
+        let path = repo.get(file).map_err(|e| {
+            ZantetsuError::ModelLoadError(format!("failed to fetch {file} from {repo_id}: {e}"))
+        })?;
+        verify_checksum(&path)?;
+        dir = path.parent().map(Path::to_path_buf);
+    }
+
+    // The manifest and transitions are optional: older model repositories predate them.
+    for file in [MANIFEST_FILE, TRANSITIONS_FILE] {
+        if let Ok(path) = repo.get(file) {
+            verify_checksum(&path)?;
+        }
+    }
+
+    dir.ok_or_else(|| ZantetsuError::ModelLoadError(format!("no files fetched from {repo_id}")))
+}
+
+/// Check a cached file against the SHA-256 it is stored under.
+///
+/// The hub cache names LFS blobs after the SHA-256 of their content, so a
+/// mismatch means a truncated or corrupted download. Small files tracked in
+/// git (named by their git object hash) are not checked.
+pub fn verify_checksum(path: &Path) -> Result<()> {
+    let blob = std::fs::canonicalize(path)?;
+    let Some(expected) = blob.file_name().and_then(|name| name.to_str()) else {
+        return Ok(());
+    };
+    if expected.len() != 64 || !expected.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Ok(());
+    }
+
+    let actual = sha256_file(&blob)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(ZantetsuError::ModelLoadError(format!(
+            "checksum mismatch for {}: expected {expected}, got {actual}",
+            path.display()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn checksum_follows_blob_name() {
+        let dir = std::env::temp_dir().join(format!("zantetsu-hub-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let digest: String = Sha256::digest(b"zantetsu")
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        let good = dir.join(&digest);
+        std::fs::write(&good, b"zantetsu").unwrap();
+        assert!(verify_checksum(&good).is_ok());
+
+        let bad = dir.join("0".repeat(64));
+        std::fs::write(&bad, b"zantetsu").unwrap();
+        assert!(matches!(
+            verify_checksum(&bad),
+            Err(ZantetsuError::ModelLoadError(_))
+        ));
+
+        // Git-tracked files are named by a 40-char object hash and skipped.
+        let small = dir.join("a".repeat(40));
+        std::fs::write(&small, b"{}").unwrap();
+        assert!(verify_checksum(&small).is_ok());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,13 +1,40 @@
 pub mod bio_tags;
+#[cfg(feature = "neural")]
+pub mod char_cnn;
+#[cfg(feature = "neural")]
+mod encoding_cache;
+pub mod engine;
 pub mod heuristic;
+#[cfg(feature = "hub")]
+pub mod hub;
+#[cfg(feature = "neural")]
 pub mod neural;
+pub mod observer;
+#[cfg(feature = "neural")]
+pub mod pool;
+#[cfg(feature = "neural")]
+mod result_cache;
 pub mod tokenizer;
+pub mod trace;
+#[cfg(feature = "neural")]
 pub mod unified;
 pub mod viterbi;
 
-pub use bio_tags::{BioTag, Entity, EntityType};
-pub use heuristic::HeuristicParser;
-pub use neural::NeuralParser;
+pub use bio_tags::{BioTag, Entity, EntityType, TagSet};
+#[cfg(feature = "neural")]
+pub use char_cnn::CharCnnParser;
+pub use engine::{EngineCost, ParseEngine};
+pub use heuristic::{FieldSpan, HeuristicParser, TagEvidence};
+#[cfg(feature = "neural")]
+pub use neural::{NeuralParser, QUANTIZED_MODEL_FILE, quantize_model};
+pub use observer::{FallbackReason, ParserObserver};
+#[cfg(feature = "neural")]
+pub use pool::{ParserPool, PooledParser};
 pub use tokenizer::{Token, Tokenizer};
-pub use unified::{Parser, ParserConfig, parse, parse_with_mode};
+pub use trace::{ParseTrace, RegexMatch, TaggedToken};
+#[cfg(feature = "neural")]
+pub use unified::{
+    FusionMode, MODEL_DIR_ENV, ParseLines, Parser, ParserConfig, SelectionStrategy, TitleCasing,
+    default_parser, parse, parse_json, parse_with_mode, reset_default_parser,
+};
 pub use viterbi::ViterbiDecoder;
@@ -3,28 +3,89 @@
 //! ML-based parser using a DistilBERT + CRF architecture for sequence labeling.
 //! Uses candle for inference without external dependencies.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
 
 use candle_core::{DType, Device, Tensor};
 use candle_nn::VarBuilder;
 use candle_transformers::models::distilbert::Config as BertConfig;
+use candle_transformers::quantized_var_builder::VarBuilder as QuantizedVarBuilder;
+use sha2::{Digest, Sha256};
 use tokenizers::Tokenizer as HfTokenizer;
 
-use crate::crf::model::CrfModel;
+use crate::crf::encoder::EncoderConfig;
+use crate::crf::forward_backward;
+use crate::crf::manifest::{MANIFEST_FILE, ModelManifest};
+use crate::crf::model::{CrfModel, ModelPrecision, candle_tensor_name};
+use crate::crf::quantized::quantize_safetensors;
+use crate::crf::transitions::{TRANSITIONS_FILE, TransitionMatrix};
 use crate::error::{Result, ZantetsuError};
-use crate::parser::bio_tags::{BioTag, Entity, EntityType};
+use crate::parser::bio_tags::{BioTag, Entity, EntityType, TagSet};
+use crate::parser::encoding_cache::{CachedEncoding, EncodingCache};
+use crate::parser::heuristic::TagEvidence;
+use crate::parser::trace::TaggedToken;
 use crate::parser::viterbi::ViterbiDecoder;
-use crate::types::{AudioCodec, EpisodeSpec, MediaSource, ParseResult, Resolution, VideoCodec};
+use crate::types::{
+    AudioCodec, EpisodeNumbering, EpisodeSpec, FieldConfidence, FieldProvenance, MediaSource,
+    ParseResult, Resolution, SeasonSpec, VideoCodec,
+};
+
+/// Input used by [`NeuralParser::warmup`]; exercises every common entity type.
+const WARMUP_INPUT: &str =
+    "[SubsPlease] Sousou no Frieren S01E01 (1080p) [HEVC AAC] [ABCD1234].mkv";
+
+/// Score that rules out a transition or tag during decoding.
+const FORBIDDEN_SCORE: f32 = -10000.0;
+
+/// DistilBERT's position-embedding count, used when the config omits it.
+const DEFAULT_MAX_SEQ_LEN: usize = 512;
+
+/// Directory searched by [`NeuralParser::init_model`].
+const DEFAULT_MODEL_DIR: &str = "models/ner_model";
+/// Model weights file name inside a model directory.
+pub(crate) const MODEL_FILE: &str = "model.safetensors";
+/// Tokenizer file name inside a model directory.
+pub(crate) const TOKENIZER_FILE: &str = "tokenizer.json";
+/// Model configuration file name inside a model directory.
+pub(crate) const CONFIG_FILE: &str = "config.json";
+/// Int8 weights file name inside a model directory.
+pub const QUANTIZED_MODEL_FILE: &str = "model-q8_0.gguf";
+
+/// Quantize the `model.safetensors` in `dir` into the Int8 weights
+/// [`ModelPrecision::Int8`] loads, written next to it as
+/// [`QUANTIZED_MODEL_FILE`]. Returns the path written.
+///
+/// Loading never quantizes on its own, so run this once per model, e.g.
+/// with `zantetsu-train quantize`.
+pub fn quantize_model(dir: &Path) -> Result<PathBuf> {
+    let gguf_path = dir.join(QUANTIZED_MODEL_FILE);
+    quantize_safetensors(&dir.join(MODEL_FILE), &gguf_path)
+        .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+    Ok(gguf_path)
+}
 
 /// Neural CRF Parser for anime filenames.
+///
+/// Loading needs `&mut self`, but [`parse`](Self::parse) only needs `&self`
+/// and the parser is `Send + Sync`: once initialized, share one instance
+/// (e.g. behind an `Arc`) across threads instead of serializing inference
+/// behind a `Mutex`. The weights are memory-mapped, so they are never copied.
 pub struct NeuralParser {
     hf_tokenizer: Option<HfTokenizer>,
     model: Option<CrfModel>,
+    manifest: Option<ModelManifest>,
     viterbi: ViterbiDecoder,
     device: Device,
+    warmed_up: AtomicBool,
+    max_seq_len: usize,
+    cache: Option<EncodingCache>,
 }
 
 impl NeuralParser {
@@ -35,60 +96,233 @@ impl NeuralParser {
         Ok(Self {
             hf_tokenizer: None,
             model: None,
+            manifest: None,
             viterbi: ViterbiDecoder::new(BioTag::NUM_TAGS),
             device,
+            warmed_up: AtomicBool::new(false),
+            max_seq_len: DEFAULT_MAX_SEQ_LEN,
+            cache: None,
         })
     }
 
     /// Initialize model with default paths (for production).
     /// If weights are missing, the parser will fail cleanly to trigger fallback.
     pub fn init_model(&mut self) -> Result<()> {
-        let model_path = "models/ner_model/model.safetensors";
-        let tokenizer_path = "models/ner_model/tokenizer.json";
+        self.init_model_with_precision(ModelPrecision::F32)
+    }
 
-        let tokenizer_file = Path::new(tokenizer_path);
+    /// Initialize the model at the given weight precision.
+    ///
+    /// `ModelPrecision::F16`/`BF16` load the safetensors weights at half
+    /// precision. `ModelPrecision::Int8` loads `models/ner_model/model-q8_0.gguf`,
+    /// which [`quantize_model`] creates; it is an error if there is none.
+    pub fn init_model_with_precision(&mut self, precision: ModelPrecision) -> Result<()> {
+        self.init_model_from_dir(Path::new(DEFAULT_MODEL_DIR), precision)
+    }
+
+    /// Initialize the model from a directory holding `model.safetensors`,
+    /// `tokenizer.json` and `config.json`.
+    ///
+    /// If the directory also has a `manifest.json`, the model is rejected with
+    /// [`ZantetsuError::ModelLoadError`] when its tag set is unsupported, its
+    /// tokenizer hash does not match, or its label count disagrees with the
+    /// manifest's tag set.
+    ///
+    /// A `transitions.json` ([`TransitionMatrix`]) replaces any
+    /// `crf_transitions.weight` stored with the weights; it is rejected if it
+    /// is not over the model's tag set.
+    pub fn init_model_from_dir(&mut self, dir: &Path, precision: ModelPrecision) -> Result<()> {
+        let tokenizer_file = dir.join(TOKENIZER_FILE);
         if tokenizer_file.exists() {
-            let hf_tokenizer = HfTokenizer::from_file(tokenizer_file)
+            let mut hf_tokenizer = HfTokenizer::from_file(&tokenizer_file)
+                .map_err(|e| ZantetsuError::NeuralParser(e.to_string()))?;
+            // Over-length inputs are windowed in `parse`, never truncated.
+            hf_tokenizer
+                .with_truncation(None)
                 .map_err(|e| ZantetsuError::NeuralParser(e.to_string()))?;
             self.hf_tokenizer = Some(hf_tokenizer);
         } else {
             return Err(ZantetsuError::NeuralParser(format!(
                 "Tokenizer not found at {}",
-                tokenizer_path
+                tokenizer_file.display()
             )));
         }
 
-        let safetensors_path = Path::new(model_path);
+        let model_file = dir.join(MODEL_FILE);
+        let safetensors_path = model_file.as_path();
         if !safetensors_path.exists() {
             return Err(ZantetsuError::NeuralParser(format!(
                 "Model not found at {}",
-                model_path
+                safetensors_path.display()
             )));
         }
 
-        let config_path = Path::new("models/ner_model/config.json");
-        let config_str = std::fs::read_to_string(config_path)
+        let config_path = dir.join(CONFIG_FILE);
+        let config_str = std::fs::read_to_string(&config_path)
             .map_err(|e| ZantetsuError::NeuralParser(format!("Failed to read config: {}", e)))?;
-        let config: BertConfig = serde_json::from_str(&config_str)
-            .map_err(|e| ZantetsuError::NeuralParser(format!("Failed to parse config: {}", e)))?;
+        let config_tag_set = tag_set_from_config(&config_str)?;
+
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let manifest = if manifest_path.exists() {
+            Some(ModelManifest::load(&manifest_path)?)
+        } else {
+            None
+        };
+        let tag_set = match &manifest {
+            Some(manifest) => {
+                manifest.verify_tokenizer(&tokenizer_file)?;
+                let tag_set = manifest.tag_set()?;
+                if config_tag_set.is_some_and(|config_tag_set| config_tag_set != tag_set) {
+                    return Err(ZantetsuError::ModelLoadError(format!(
+                        "manifest declares tag set v{}, but config.json has a different label count",
+                        tag_set.version()
+                    )));
+                }
+                tag_set
+            }
+            None => config_tag_set.unwrap_or(TagSet::V1),
+        };
+        self.manifest = manifest;
+        self.warmed_up.store(false, Ordering::Release);
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+        self.max_seq_len = max_positions_from_config(&config_str).unwrap_or(DEFAULT_MAX_SEQ_LEN);
+        self.viterbi = ViterbiDecoder::new(tag_set.num_tags());
+
+        if precision == ModelPrecision::Int8 {
+            let mut model = self.load_quantized(safetensors_path, &config_str, tag_set)?;
+            apply_transitions(dir, &mut model)?;
+            self.model = Some(model);
+            return Ok(());
+        }
 
         let converted_path = Self::convert_safetensors_for_candle(safetensors_path)?;
 
-        let vb = unsafe {
-            VarBuilder::from_mmaped_safetensors(&[&converted_path], DType::F32, &self.device)
+        let dtype = precision.dtype().unwrap_or(DType::F32);
+        let vb =
+            unsafe { VarBuilder::from_mmaped_safetensors(&[&converted_path], dtype, &self.device) }
+                .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+
+        let mut model = if dtype == DType::F32 {
+            let config: BertConfig = serde_json::from_str(&config_str).map_err(|e| {
+                ZantetsuError::NeuralParser(format!("Failed to parse config: {}", e))
+            })?;
+            CrfModel::load(vb, config, tag_set)
+        } else {
+            let config: EncoderConfig = serde_json::from_str(&config_str).map_err(|e| {
+                ZantetsuError::NeuralParser(format!("Failed to parse config: {}", e))
+            })?;
+            CrfModel::load_half(vb, &config, tag_set)
         }
         .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
-
-        let model =
-            CrfModel::load(vb, config).map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+        apply_transitions(dir, &mut model)?;
 
         self.model = Some(model);
         Ok(())
     }
 
+    /// Cache the tokenization of up to `capacity` recent inputs (`0` disables
+    /// the cache). With `cache_emissions`, the model's emission scores are
+    /// cached too, so repeated inputs skip the forward pass entirely.
+    ///
+    /// The cache is cleared whenever a model is (re)loaded.
+    pub fn set_encoding_cache(&mut self, capacity: usize, cache_emissions: bool) {
+        self.cache = NonZeroUsize::new(capacity)
+            .map(|capacity| EncodingCache::new(capacity, cache_emissions));
+    }
+
+    /// Create a parser with a model downloaded from the Hugging Face Hub.
+    ///
+    /// Files are cached under the platform cache directory (see
+    /// [`hub::cache_dir`](crate::parser::hub::cache_dir)), so only the first
+    /// call touches the network. `revision` defaults to `main`.
+    #[cfg(feature = "hub")]
+    pub fn from_hub(repo_id: &str, revision: Option<&str>) -> Result<Self> {
+        let dir = crate::parser::hub::fetch_model(repo_id, revision)?;
+        let mut parser = Self::new()?;
+        parser.init_model_from_dir(&dir, ModelPrecision::F32)?;
+        Ok(parser)
+    }
+
+    fn load_quantized(
+        &self,
+        safetensors_path: &Path,
+        config_str: &str,
+        tag_set: TagSet,
+    ) -> Result<CrfModel> {
+        let gguf_path = safetensors_path.with_file_name(QUANTIZED_MODEL_FILE);
+        if !gguf_path.exists() {
+            return Err(ZantetsuError::ModelLoadError(format!(
+                "Int8 weights not found at {}; create them with `zantetsu-train quantize` \
+                 or `quantize_model`",
+                gguf_path.display()
+            )));
+        }
+        let config: EncoderConfig = serde_json::from_str(config_str)
+            .map_err(|e| ZantetsuError::NeuralParser(format!("Failed to parse config: {}", e)))?;
+
+        let vb = QuantizedVarBuilder::from_gguf(&gguf_path, &self.device)
+            .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+        CrfModel::load_quantized(vb, &config, tag_set)
+            .map_err(|e| ZantetsuError::CandleError(e.to_string()))
+    }
+
+    /// Run a throwaway parse so lazy allocations and mmap page faults happen
+    /// now rather than on the first real request.
+    ///
+    /// Fails if no model is loaded. After it succeeds, [`is_ready`](Self::is_ready)
+    /// returns `true`.
+    pub fn warmup(&self) -> Result<()> {
+        self.parse(WARMUP_INPUT)?;
+        self.warmed_up.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns `true` once a model is loaded and [`warmup`](Self::warmup) has
+    /// completed, e.g. for gating a server's readiness probe.
+    pub fn is_ready(&self) -> bool {
+        self.model.is_some()
+            && self.hf_tokenizer.is_some()
+            && self.warmed_up.load(Ordering::Acquire)
+    }
+
+    /// Manifest of the loaded model, if its directory had one.
+    pub fn manifest(&self) -> Option<&ModelManifest> {
+        self.manifest.as_ref()
+    }
+
+    /// Precision of the loaded model, if one is loaded.
+    pub fn precision(&self) -> Option<ModelPrecision> {
+        self.model.as_ref().map(CrfModel::precision)
+    }
+
+    /// Where the candle-layout copy of `safetensors_path` is kept: the temp
+    /// directory, under a name derived from the source's canonical path,
+    /// size and modification time, so every model directory (and every
+    /// rewrite of its weights) gets its own copy.
+    fn converted_path(safetensors_path: &Path) -> Result<PathBuf> {
+        let source = safetensors_path.canonicalize()?;
+        let metadata = source.metadata()?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+
+        let mut hasher = Sha256::new();
+        hasher.update(source.as_os_str().as_encoded_bytes());
+        hasher.update(metadata.len().to_le_bytes());
+        hasher.update(modified.as_nanos().to_le_bytes());
+        let key: String = hasher.finalize()[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Ok(std::env::temp_dir().join(format!("zantetsu_candle_model-{key}.safetensors")))
+    }
+
     fn convert_safetensors_for_candle(safetensors_path: &Path) -> Result<PathBuf> {
-        let temp_dir = std::env::temp_dir();
-        let converted_path = temp_dir.join("zantetsu_candle_model.safetensors");
+        let converted_path = Self::converted_path(safetensors_path)?;
 
         if converted_path.exists() {
             return Ok(converted_path);
@@ -112,17 +346,7 @@ impl NeuralParser {
                 ZantetsuError::NeuralParser(format!("Failed to get tensor {}: {}", key, e))
             })?;
 
-            let mut new_key = (*key).clone();
-
-            if new_key.starts_with("distilbert.") {
-                new_key = new_key.strip_prefix("distilbert.").unwrap().to_string();
-            }
-
-            if new_key.ends_with(".LayerNorm.gamma") {
-                new_key = new_key.replace(".LayerNorm.gamma", ".LayerNorm.weight");
-            } else if new_key.ends_with(".LayerNorm.beta") {
-                new_key = new_key.replace(".LayerNorm.beta", ".LayerNorm.bias");
-            }
+            let new_key = candle_tensor_name(key);
 
             let shape: Vec<usize> = view.shape().to_vec();
             let _n_elements: usize = shape.iter().product();
@@ -178,18 +402,72 @@ impl NeuralParser {
         let serialized = safetensors::serialize(serialized_tensors, &Some(metadata))
             .map_err(|e| ZantetsuError::NeuralParser(format!("Failed to serialize: {}", e)))?;
 
-        let mut out_file = File::create(&converted_path).map_err(|e| {
+        // Written aside and renamed, so a concurrent load never maps a
+        // half-written file.
+        let partial_path = converted_path.with_extension(format!("{}.part", std::process::id()));
+        let mut out_file = File::create(&partial_path).map_err(|e| {
             ZantetsuError::NeuralParser(format!("Failed to create temp file: {}", e))
         })?;
         out_file.write_all(&serialized).map_err(|e| {
             ZantetsuError::NeuralParser(format!("Failed to write temp file: {}", e))
         })?;
+        std::fs::rename(&partial_path, &converted_path)?;
 
         Ok(converted_path)
     }
 
     /// Parse a filename using the neural CRF model.
     pub fn parse(&self, input: &str) -> Result<ParseResult> {
+        self.parse_with_evidence(input, &[])
+    }
+
+    /// Parse with tokens inside `evidence` spans forced to the given tags.
+    ///
+    /// The decoder can then only choose among labelings that agree with the
+    /// regex evidence, so the model cannot mislabel a CRC32 or resolution.
+    pub fn parse_with_evidence(
+        &self,
+        input: &str,
+        evidence: &[TagEvidence],
+    ) -> Result<ParseResult> {
+        self.with_emissions(input, evidence, |model, offsets, scores| {
+            Self::decode(&self.viterbi, input, offsets, scores, &model.transitions)
+        })
+    }
+
+    /// Like [`parse_with_evidence`](Self::parse_with_evidence), also
+    /// returning the tagged tokens the result was built from.
+    pub(crate) fn parse_tagged(
+        &self,
+        input: &str,
+        evidence: &[TagEvidence],
+    ) -> Result<(ParseResult, Vec<TaggedToken>)> {
+        self.with_emissions(input, evidence, |model, offsets, scores| {
+            let (result, tags) =
+                Self::decode_tags(&self.viterbi, input, offsets, scores, &model.transitions)?;
+            Ok((result, tagged_tokens(input, offsets, &tags)))
+        })
+    }
+
+    /// The model's subword tokens of `input` with their decoded tags, for
+    /// [`ParseTrace`](crate::parser::ParseTrace). Special tokens are skipped.
+    pub fn tag_sequence(&self, input: &str) -> Result<Vec<TaggedToken>> {
+        self.with_emissions(input, &[], |model, offsets, scores| {
+            let transitions = transition_matrix(&model.transitions)?;
+            let tags = self.viterbi.decode_constrained(scores, &transitions)?;
+            Ok(tagged_tokens(input, offsets, &tags))
+        })
+    }
+
+    /// Tokenize `input` (or fetch it from the cache), compute its emission
+    /// scores with `evidence` applied, and hand the model, offsets and scores
+    /// to `f`.
+    fn with_emissions<T>(
+        &self,
+        input: &str,
+        evidence: &[TagEvidence],
+        f: impl FnOnce(&CrfModel, &[(usize, usize)], &[Vec<f32>]) -> Result<T>,
+    ) -> Result<T> {
         if input.trim().is_empty() {
             return Err(ZantetsuError::EmptyInput);
         }
@@ -204,18 +482,102 @@ impl NeuralParser {
             .as_ref()
             .ok_or_else(|| ZantetsuError::NeuralParser("Model is not initialized".into()))?;
 
-        let encoding = tokenizer
-            .encode(input, true)
-            .map_err(|e| ZantetsuError::NeuralParser(format!("Tokenize error: {}", e)))?;
+        let encoding = match self.cache.as_ref().and_then(|cache| cache.get(input)) {
+            Some(encoding) => encoding,
+            None => {
+                let encoding = tokenizer
+                    .encode(input, true)
+                    .map_err(|e| ZantetsuError::NeuralParser(format!("Tokenize error: {}", e)))?;
+                let encoding = CachedEncoding::new(&encoding);
+                match &self.cache {
+                    Some(cache) => cache.insert(input, encoding),
+                    None => Arc::new(encoding),
+                }
+            }
+        };
 
-        let tokens = encoding.get_ids();
+        let tokens = &encoding.ids;
         if tokens.is_empty() {
             return Err(ZantetsuError::ParseFailed {
                 input: input.to_string(),
             });
         }
 
-        let input_ids = Tensor::new(tokens, &self.device)
+        let scores = match encoding.emissions.get() {
+            Some(scores) => Cow::Borrowed(scores.as_slice()),
+            None => {
+                let scores = if tokens.len() <= self.max_seq_len {
+                    self.emission_rows(model, tokens)?
+                } else {
+                    self.windowed_emissions(model, tokens, &encoding.special_tokens_mask)?
+                };
+                if self
+                    .cache
+                    .as_ref()
+                    .is_some_and(EncodingCache::caches_emissions)
+                {
+                    let _ = encoding.emissions.set(scores.clone());
+                }
+                Cow::Owned(scores)
+            }
+        };
+        let scores = if evidence.is_empty() {
+            scores
+        } else {
+            let mut scores = scores.into_owned();
+            constrain_emissions(&mut scores, &encoding.offsets, evidence);
+            Cow::Owned(scores)
+        };
+        f(model, &encoding.offsets, &scores)
+    }
+
+    /// Decode per-position emission scores into a [`ParseResult`].
+    ///
+    /// Runs constrained Viterbi over `transitions` (`[num_tags, num_tags]`),
+    /// then assembles entities from `offsets`, the byte range in `input` of
+    /// each scored position. Shared with the character-level model.
+    pub(crate) fn decode(
+        viterbi: &ViterbiDecoder,
+        input: &str,
+        offsets: &[(usize, usize)],
+        scores: &[Vec<f32>],
+        transitions: &Tensor,
+    ) -> Result<ParseResult> {
+        Self::decode_tags(viterbi, input, offsets, scores, transitions).map(|(result, _)| result)
+    }
+
+    /// [`decode`](Self::decode), also returning the tag index of every
+    /// position.
+    fn decode_tags(
+        viterbi: &ViterbiDecoder,
+        input: &str,
+        offsets: &[(usize, usize)],
+        scores: &[Vec<f32>],
+        transitions: &Tensor,
+    ) -> Result<(ParseResult, Vec<usize>)> {
+        let transition_matrix = transition_matrix(transitions)?;
+        let tag_indices = viterbi.decode_constrained(scores, &transition_matrix)?;
+
+        // Marginal probability of each decoded tag, for per-entity confidence
+        let marginals = forward_backward::marginals(scores, &transition_matrix)?;
+        let tag_confidence: Vec<f32> = tag_indices
+            .iter()
+            .zip(&marginals)
+            .map(|(&tag, row)| row[tag])
+            .collect();
+
+        let entities = Self::assemble_entities(input, offsets, &tag_indices)?;
+
+        let mut result = Self::build_parse_result(input, &entities)?;
+        result.field_confidence = entity_confidence(&result, &entities, &tag_confidence);
+
+        Ok((result, tag_indices))
+    }
+
+    /// Run the model over one sequence of token ids, returning one row of
+    /// emission scores per token.
+    fn emission_rows(&self, model: &CrfModel, ids: &[u32]) -> Result<Vec<Vec<f32>>> {
+        let input_ids = Tensor::new(ids, &self.device)
             .map_err(|e| ZantetsuError::CandleError(e.to_string()))?
             .unsqueeze(0)
             .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
@@ -230,7 +592,6 @@ impl NeuralParser {
         let emissions_vec = emissions
             .squeeze(0)
             .map_err(|_| ZantetsuError::NeuralParser("Emission dimension mismatch".into()))?;
-        let seq_len = tokens.len();
 
         let emissions_flat: Vec<f32> = emissions_vec
             .flatten_all()
@@ -238,49 +599,94 @@ impl NeuralParser {
             .to_vec1()
             .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
 
-        let mut scores = Vec::with_capacity(seq_len);
-        let num_tags = BioTag::NUM_TAGS;
-        for i in 0..seq_len {
-            let start = i * num_tags;
-            let end = start + num_tags;
-            scores.push(emissions_flat[start..end].to_vec());
-        }
-
-        let transition_flat: Vec<f32> = model
-            .transitions
-            .flatten_all()
-            .map_err(|e| ZantetsuError::CandleError(e.to_string()))?
-            .to_vec1()
-            .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+        let num_tags = model.tag_set().num_tags();
+        Ok(emissions_flat
+            .chunks_exact(num_tags)
+            .map(<[f32]>::to_vec)
+            .collect())
+    }
 
-        let mut transition_matrix = vec![vec![0.0f32; num_tags]; num_tags];
-        for i in 0..num_tags {
-            for j in 0..num_tags {
-                transition_matrix[i][j] = transition_flat[i * num_tags + j];
-                if !BioTag::is_valid_transition(
-                    BioTag::from_index(i).unwrap(),
-                    BioTag::from_index(j).unwrap(),
-                ) {
-                    transition_matrix[i][j] = -10000.0;
-                }
+    /// Emission scores for a sequence longer than the model's position embeddings.
+    ///
+    /// The tokens between the leading and trailing special tokens are split into
+    /// overlapping windows, each re-wrapped in those special tokens. Every token
+    /// keeps the scores of the window it sits deepest inside; Viterbi then runs
+    /// over the stitched sequence, so entities crossing a window edge still
+    /// decode as one span.
+    fn windowed_emissions(
+        &self,
+        model: &CrfModel,
+        ids: &[u32],
+        special_tokens_mask: &[u32],
+    ) -> Result<Vec<Vec<f32>>> {
+        let lead = usize::from(special_tokens_mask.first() == Some(&1));
+        let trail = usize::from(ids.len() > lead && special_tokens_mask.last() == Some(&1));
+        let body = &ids[lead..ids.len() - trail];
+
+        let window = self.max_seq_len.saturating_sub(lead + trail);
+        if window == 0 {
+            return Err(ZantetsuError::NeuralParser(format!(
+                "Maximum sequence length {} leaves no room for input tokens",
+                self.max_seq_len
+            )));
+        }
+        let spans = window_spans(body.len(), window, (window * 3 / 4).max(1));
+        let owners = window_owners(body.len(), &spans);
+
+        let mut rows = vec![Vec::new(); ids.len()];
+        for (index, &(start, end)) in spans.iter().enumerate() {
+            let mut window_ids = Vec::with_capacity(end - start + lead + trail);
+            window_ids.extend_from_slice(&ids[..lead]);
+            window_ids.extend_from_slice(&body[start..end]);
+            window_ids.extend_from_slice(&ids[ids.len() - trail..]);
+            let mut window_rows = self.emission_rows(model, &window_ids)?;
+
+            if index == 0 && lead == 1 {
+                rows[0] = window_rows[0].clone();
+            }
+            if index == spans.len() - 1 && trail == 1 {
+                rows[ids.len() - 1] = window_rows.pop().unwrap_or_default();
+            }
+            for pos in (start..end).filter(|&pos| owners[pos] == index) {
+                rows[lead + pos] = std::mem::take(&mut window_rows[lead + pos - start]);
             }
         }
 
-        let tag_indices = self
-            .viterbi
-            .decode_constrained(&scores, &transition_matrix)?;
-
-        let offsets = encoding.get_offsets();
-        let entities = self.assemble_entities(input, offsets, &tag_indices)?;
-
-        let result = self.build_parse_result(input, &entities)?;
+        Ok(rows)
+    }
 
-        Ok(result)
+    /// Build a [`ParseResult`] from per-token tag indices, the way a decoded
+    /// tag sequence becomes fields. `offsets` is the byte range in `input` of
+    /// each tagged token; empty ranges at `0` mark special tokens.
+    ///
+    /// Lets trainers score predicted tag sequences on the parse fields they
+    /// produce rather than on individual tags.
+    pub fn assemble_result(
+        input: &str,
+        offsets: &[(usize, usize)],
+        tag_indices: &[usize],
+    ) -> Result<ParseResult> {
+        if offsets.len() != tag_indices.len() {
+            return Err(ZantetsuError::NeuralParser(format!(
+                "{} tags for {} token offsets",
+                tag_indices.len(),
+                offsets.len()
+            )));
+        }
+        if let Some(&tag) = tag_indices
+            .iter()
+            .find(|&&tag| BioTag::from_index(tag).is_none())
+        {
+            return Err(ZantetsuError::NeuralParser(format!(
+                "Unknown tag index {tag}"
+            )));
+        }
+        let entities = Self::assemble_entities(input, offsets, tag_indices)?;
+        Self::build_parse_result(input, &entities)
     }
 
     /// Assemble entities cleanly from HF subword tags and original string offset map.
     fn assemble_entities(
-        &self,
         input: &str,
         offsets: &[(usize, usize)],
         tag_indices: &[usize],
@@ -344,7 +750,7 @@ impl NeuralParser {
     }
 
     /// Build ParseResult from extracted entities using exact original snippets.
-    fn build_parse_result(&self, input: &str, entities: &[Entity]) -> Result<ParseResult> {
+    fn build_parse_result(input: &str, entities: &[Entity]) -> Result<ParseResult> {
         let mut title = None;
         let mut group = None;
         let mut episode = None;
@@ -357,6 +763,11 @@ impl NeuralParser {
         let mut crc32 = None;
         let mut extension = None;
         let mut version = None;
+        let mut episode_title = None;
+        let mut language = None;
+        let mut audio_channels = None;
+        let mut part = None;
+        let mut release_flags = Vec::new();
 
         for entity in entities {
             match entity.entity_type {
@@ -371,37 +782,35 @@ impl NeuralParser {
                     }
                 }
                 EntityType::Episode => {
-                    if episode.is_none()
-                        && let Ok(num) = entity.text.parse::<u32>()
-                    {
-                        episode = Some(EpisodeSpec::Single(num));
+                    if episode.is_none() {
+                        episode = Self::parse_episode(&entity.text);
                     }
                 }
                 EntityType::Season => {
                     if season.is_none()
                         && let Ok(num) = entity.text.parse::<u32>()
                     {
-                        season = Some(num);
+                        season = Some(SeasonSpec::Single(num));
                     }
                 }
                 EntityType::Resolution => {
                     if resolution.is_none() {
-                        resolution = self.parse_resolution(&entity.text);
+                        resolution = Self::parse_resolution(&entity.text);
                     }
                 }
                 EntityType::VCodec => {
                     if video_codec.is_none() {
-                        video_codec = self.parse_video_codec(&entity.text);
+                        video_codec = Self::parse_video_codec(&entity.text);
                     }
                 }
                 EntityType::ACodec => {
                     if audio_codec.is_none() {
-                        audio_codec = self.parse_audio_codec(&entity.text);
+                        audio_codec = Self::parse_audio_codec(&entity.text);
                     }
                 }
                 EntityType::Source => {
                     if source.is_none() {
-                        source = self.parse_source(&entity.text);
+                        source = Self::parse_source(&entity.text);
                     }
                 }
                 EntityType::Year => {
@@ -429,6 +838,37 @@ impl NeuralParser {
                             .map(|v| v as u8);
                     }
                 }
+                EntityType::EpisodeTitle => {
+                    if episode_title.is_none() {
+                        episode_title = Some(entity.text.clone());
+                    }
+                }
+                EntityType::Language => {
+                    if language.is_none() {
+                        language = Some(entity.text.clone());
+                    }
+                }
+                EntityType::AudioChannels => {
+                    if audio_channels.is_none() {
+                        audio_channels = Some(entity.text.clone());
+                    }
+                }
+                EntityType::Part => {
+                    if part.is_none() {
+                        let digits: String = entity
+                            .text
+                            .chars()
+                            .skip_while(|c| !c.is_ascii_digit())
+                            .take_while(char::is_ascii_digit)
+                            .collect();
+                        part = digits.parse::<u32>().ok();
+                    }
+                }
+                EntityType::ReleaseFlag => {
+                    if !release_flags.contains(&entity.text) {
+                        release_flags.push(entity.text.clone());
+                    }
+                }
             }
         }
 
@@ -452,26 +892,58 @@ impl NeuralParser {
 
         let confidence = (extracted_count as f32 / 11.0).clamp(0.0, 1.0);
 
+        let episode_numbering = EpisodeNumbering::infer(season, episode.as_ref());
+
         Ok(ParseResult {
             input: input.to_string(),
             title,
             group,
+            group_trust: None,
             episode,
+            episode_count: None,
+            episode_title,
             season,
+            part,
+            episode_numbering,
             resolution,
             video_codec,
             audio_codec,
+            audio_channels,
+            language,
+            audio_tracks: Vec::new(),
             source,
             year,
+            year_range: None,
             crc32,
             extension,
             version,
+            release_flags,
+            extras: Vec::new(),
             confidence,
+            field_confidence: FieldConfidence::new(),
+            provenance: FieldProvenance::new(),
+            budget_exhausted: false,
+            warnings: Vec::new(),
             parse_mode: crate::types::ParseMode::Full,
         })
     }
 
-    fn parse_resolution(&self, text: &str) -> Option<Resolution> {
+    /// An episode entity: a plain number, or `SP<n>` / `Special <n>`.
+    fn parse_episode(text: &str) -> Option<EpisodeSpec> {
+        let text = text.trim();
+        if let Ok(number) = text.parse::<u32>() {
+            return Some(EpisodeSpec::numbered(number));
+        }
+
+        let lower = text.to_lowercase();
+        let number = lower
+            .strip_prefix("special")
+            .or_else(|| lower.strip_prefix("sp"))?
+            .trim_start_matches([' ', '.', '_', '-']);
+        number.parse().ok().map(EpisodeSpec::Special)
+    }
+
+    fn parse_resolution(text: &str) -> Option<Resolution> {
         let t = text.to_lowercase();
         if t.contains("2160") {
             Some(Resolution::UHD2160)
@@ -486,7 +958,7 @@ impl NeuralParser {
         }
     }
 
-    fn parse_video_codec(&self, text: &str) -> Option<VideoCodec> {
+    fn parse_video_codec(text: &str) -> Option<VideoCodec> {
         let t = text.to_lowercase();
         if t.contains("av1") {
             Some(VideoCodec::AV1)
@@ -503,7 +975,7 @@ impl NeuralParser {
         }
     }
 
-    fn parse_audio_codec(&self, text: &str) -> Option<AudioCodec> {
+    fn parse_audio_codec(text: &str) -> Option<AudioCodec> {
         let t = text.to_lowercase();
         if t.contains("flac") {
             Some(AudioCodec::FLAC)
@@ -526,7 +998,7 @@ impl NeuralParser {
         }
     }
 
-    fn parse_source(&self, text: &str) -> Option<MediaSource> {
+    fn parse_source(text: &str) -> Option<MediaSource> {
         let t = text.to_lowercase();
         if t.contains("remux") {
             Some(MediaSource::BluRayRemux)
@@ -551,3 +1023,414 @@ impl Default for NeuralParser {
         Self::new().expect("Failed to create NeuralParser")
     }
 }
+
+/// `transitions` as nested rows, with BIO-invalid transitions forbidden.
+fn transition_matrix(transitions: &Tensor) -> Result<Vec<Vec<f32>>> {
+    let num_tags = transitions
+        .dim(0)
+        .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+
+    let transition_flat: Vec<f32> = transitions
+        .flatten_all()
+        .map_err(|e| ZantetsuError::CandleError(e.to_string()))?
+        .to_vec1()
+        .map_err(|e| ZantetsuError::CandleError(e.to_string()))?;
+
+    let mut transition_matrix = vec![vec![0.0f32; num_tags]; num_tags];
+    for i in 0..num_tags {
+        for j in 0..num_tags {
+            transition_matrix[i][j] = transition_flat[i * num_tags + j];
+            if !BioTag::is_valid_transition(
+                BioTag::from_index(i).unwrap(),
+                BioTag::from_index(j).unwrap(),
+            ) {
+                transition_matrix[i][j] = FORBIDDEN_SCORE;
+            }
+        }
+    }
+
+    Ok(transition_matrix)
+}
+
+/// The tokens at `offsets` with their decoded `tags`, skipping special
+/// tokens.
+fn tagged_tokens(input: &str, offsets: &[(usize, usize)], tags: &[usize]) -> Vec<TaggedToken> {
+    offsets
+        .iter()
+        .zip(tags)
+        .filter(|((start, end), _)| end > start)
+        .filter_map(|(&(start, end), &tag)| {
+            Some(TaggedToken {
+                text: input.get(start..end)?.to_string(),
+                start,
+                end,
+                tag: BioTag::from_index(tag)?,
+            })
+        })
+        .collect()
+}
+
+/// Load `transitions.json` from `dir` into `model`, if present.
+fn apply_transitions(dir: &Path, model: &mut CrfModel) -> Result<()> {
+    let path = dir.join(TRANSITIONS_FILE);
+    if path.exists() {
+        let matrix = TransitionMatrix::load(&path)?;
+        model
+            .set_transitions(&matrix)
+            .map_err(|e| ZantetsuError::ModelLoadError(e.to_string()))?;
+    } else if !model.has_learned_transitions() {
+        tracing::warn!(
+            "no learned CRF transitions in {}; decoding with a zero transition matrix",
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Rule out every tag but the evidenced one for each position whose byte
+/// range (`offsets`) lies inside an evidence span. Special tokens (empty
+/// ranges) and tags outside the model's tag set are left alone.
+pub(crate) fn constrain_emissions(
+    scores: &mut [Vec<f32>],
+    offsets: &[(usize, usize)],
+    evidence: &[TagEvidence],
+) {
+    for (row, &(start, end)) in scores.iter_mut().zip(offsets) {
+        if start >= end {
+            continue;
+        }
+        let Some(span) = evidence
+            .iter()
+            .find(|span| span.start <= start && end <= span.end && span.tag.index() < row.len())
+        else {
+            continue;
+        };
+        for (tag, score) in row.iter_mut().enumerate() {
+            if tag != span.tag.index() {
+                *score = FORBIDDEN_SCORE;
+            }
+        }
+    }
+}
+
+/// Split `len` tokens into windows of at most `window` tokens, `stride` apart.
+fn window_spans(len: usize, window: usize, stride: usize) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + window).min(len);
+        spans.push((start, end));
+        if end == len {
+            return spans;
+        }
+        start += stride;
+    }
+}
+
+/// For each token, the window it sits deepest inside (distance to the nearest
+/// window edge; the ends of the whole sequence do not count as edges).
+fn window_owners(len: usize, spans: &[(usize, usize)]) -> Vec<usize> {
+    (0..len)
+        .map(|pos| {
+            spans
+                .iter()
+                .enumerate()
+                .filter(|(_, (start, end))| (*start..*end).contains(&pos))
+                .max_by_key(|(_, (start, end))| {
+                    let left = if *start == 0 { len } else { pos - start };
+                    let right = if *end == len { len } else { end - 1 - pos };
+                    left.min(right)
+                })
+                .map_or(0, |(index, _)| index)
+        })
+        .collect()
+}
+
+/// `max_position_embeddings` of a model config, if present.
+fn max_positions_from_config(config_str: &str) -> Option<usize> {
+    let config: serde_json::Value = serde_json::from_str(config_str).ok()?;
+    let positions = config.get("max_position_embeddings")?.as_u64()?;
+    usize::try_from(positions).ok()
+}
+
+/// Tag set of a Hugging Face token-classification config, from the size of
+/// its `id2label` map (`None` if the config has no label map).
+fn tag_set_from_config(config_str: &str) -> Result<Option<TagSet>> {
+    let config: serde_json::Value = serde_json::from_str(config_str)
+        .map_err(|e| ZantetsuError::NeuralParser(format!("Failed to parse config: {}", e)))?;
+    let Some(labels) = config.get("id2label").and_then(|labels| labels.as_object()) else {
+        return Ok(None);
+    };
+    TagSet::from_num_labels(labels.len())
+        .map(Some)
+        .ok_or_else(|| {
+            ZantetsuError::ModelLoadError(format!(
+                "model has {} labels, which matches no supported tag set",
+                labels.len()
+            ))
+        })
+}
+
+/// Per-field confidence: the mean marginal probability of the decoded tags
+/// over the first entity span of each field present in `result`.
+fn entity_confidence(
+    result: &ParseResult,
+    entities: &[Entity],
+    tag_confidence: &[f32],
+) -> FieldConfidence {
+    let mut confidence = FieldConfidence::new();
+    for entity in entities {
+        let field = entity.entity_type.field();
+        if confidence.get(field).is_some() || !field.is_present(result) {
+            continue;
+        }
+        let span = &tag_confidence[entity.start_token..entity.end_token];
+        if !span.is_empty() {
+            confidence.set(field, span.iter().sum::<f32>() / span.len() as f32);
+        }
+    }
+    confidence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ParseField, ParseMode};
+
+    #[test]
+    fn entity_confidence_averages_span_marginals() {
+        let mut result = ParseResult::new("[Group] Title - 01", ParseMode::Full);
+        result.title = Some("Title".into());
+        result.group = Some("Group".into());
+        let entity = |entity_type, start_token, end_token| Entity {
+            entity_type,
+            start_token,
+            end_token,
+            text: String::new(),
+        };
+        let entities = [
+            entity(EntityType::Group, 1, 2),
+            entity(EntityType::Title, 3, 5),
+            entity(EntityType::Title, 6, 7),
+            entity(EntityType::Episode, 7, 8),
+        ];
+        let tags = [0.0, 0.9, 0.0, 0.6, 0.8, 0.0, 0.1, 0.99];
+
+        let confidence = entity_confidence(&result, &entities, &tags);
+        assert_eq!(confidence.get(ParseField::Group), Some(0.9));
+        assert!((confidence.get(ParseField::Title).unwrap() - 0.7).abs() < 1e-6);
+        // Episode was not extracted into the result, so it has no score.
+        assert_eq!(confidence.get(ParseField::Episode), None);
+    }
+
+    #[test]
+    fn windows_cover_input_with_overlap() {
+        assert_eq!(window_spans(5, 8, 6), vec![(0, 5)]);
+        let spans = window_spans(20, 8, 6);
+        assert_eq!(spans, vec![(0, 8), (6, 14), (12, 20)]);
+
+        let owners = window_owners(20, &spans);
+        // Sequence ends belong to the only window covering them
+        assert_eq!(owners[0], 0);
+        assert_eq!(owners[19], 2);
+        // Overlapping tokens go to the window they sit deeper inside
+        assert_eq!(owners[6], 0);
+        assert_eq!(owners[7], 1);
+        assert_eq!(owners[12], 1);
+        assert_eq!(owners[13], 2);
+        assert!(owners.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn evidence_forces_tags_inside_spans() {
+        let mut scores = vec![vec![0.0; BioTag::NUM_TAGS]; 4];
+        scores[2][BioTag::BeginTitle.index()] = 9.0;
+        // [CLS], "Show", "1080", "p"
+        let offsets = [(0, 0), (0, 4), (5, 9), (9, 10)];
+        let evidence = [TagEvidence {
+            start: 5,
+            end: 10,
+            tag: BioTag::Resolution,
+        }];
+
+        constrain_emissions(&mut scores, &offsets, &evidence);
+
+        assert!(scores[0].iter().all(|&score| score == 0.0));
+        assert!(scores[1].iter().all(|&score| score == 0.0));
+        for row in &scores[2..] {
+            let best = (0..BioTag::NUM_TAGS).max_by(|&a, &b| row[a].total_cmp(&row[b]));
+            assert_eq!(best, Some(BioTag::Resolution.index()));
+        }
+    }
+
+    #[test]
+    fn neural_parser_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<NeuralParser>();
+    }
+
+    #[test]
+    fn warmup_requires_a_model() {
+        let parser = NeuralParser::new().unwrap();
+        assert!(!parser.is_ready());
+        assert!(parser.warmup().is_err());
+        assert!(!parser.is_ready());
+    }
+
+    #[test]
+    fn int8_load_requires_quantized_weights() {
+        use tokenizers::models::wordlevel::WordLevel;
+
+        let dir = std::env::temp_dir().join(format!("zantetsu-no-q8-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let vocab = HashMap::from([("[UNK]".to_string(), 0)]);
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        HfTokenizer::new(model)
+            .save(dir.join(TOKENIZER_FILE), false)
+            .unwrap();
+        std::fs::write(dir.join(MODEL_FILE), b"").unwrap();
+        std::fs::write(dir.join(CONFIG_FILE), "{}").unwrap();
+
+        let mut parser = NeuralParser::new().unwrap();
+        let err = parser
+            .init_model_from_dir(&dir, ModelPrecision::Int8)
+            .unwrap_err();
+        assert!(matches!(err, ZantetsuError::ModelLoadError(_)), "{err}");
+        // Loading never writes the weights itself
+        assert!(!dir.join(QUANTIZED_MODEL_FILE).exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn loads_each_directory_with_its_own_weights() {
+        use crate::crf::model::tests::{config_json, random_checkpoint};
+        use tokenizers::models::wordlevel::WordLevel;
+
+        let root = std::env::temp_dir().join(format!("zantetsu-two-models-{}", std::process::id()));
+        let device = Device::Cpu;
+        // Zero classifier weights, so every token's emissions are the bias
+        // and `favored` is the best tag.
+        let write_model = |name: &str, favored: usize| {
+            let dir = root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            let vocab = HashMap::from([("[UNK]".to_string(), 0), ("frieren".to_string(), 1)]);
+            let model = WordLevel::builder()
+                .vocab(vocab)
+                .unk_token("[UNK]".into())
+                .build()
+                .unwrap();
+            HfTokenizer::new(model)
+                .save(dir.join(TOKENIZER_FILE), false)
+                .unwrap();
+            std::fs::write(dir.join(CONFIG_FILE), config_json()).unwrap();
+
+            let mut checkpoint = random_checkpoint(&device);
+            let num_tags = TagSet::V1.num_tags();
+            let mut bias = vec![0f32; num_tags];
+            bias[favored] = 5.0;
+            checkpoint.insert(
+                "classifier.weight".into(),
+                Tensor::zeros((num_tags, 768), DType::F32, &device).unwrap(),
+            );
+            checkpoint.insert(
+                "classifier.bias".into(),
+                Tensor::from_vec(bias, num_tags, &device).unwrap(),
+            );
+            candle_core::safetensors::save(&checkpoint, dir.join(MODEL_FILE)).unwrap();
+            dir
+        };
+        let first = write_model("first", BioTag::BeginTitle.index());
+        let second = write_model("second", BioTag::BeginGroup.index());
+
+        let mut parser = NeuralParser::new().unwrap();
+        for (dir, favored) in [(&first, BioTag::BeginTitle), (&second, BioTag::BeginGroup)] {
+            parser
+                .init_model_from_dir(dir, ModelPrecision::F32)
+                .unwrap();
+            let ids = Tensor::new(&[[1u32]], &device).unwrap();
+            let mask = Tensor::zeros((1, 1), DType::U8, &device).unwrap();
+            let emissions: Vec<f32> = parser
+                .model
+                .as_ref()
+                .unwrap()
+                .forward(&ids, &mask)
+                .unwrap()
+                .flatten_all()
+                .unwrap()
+                .to_vec1()
+                .unwrap();
+            let best = (0..emissions.len()).max_by(|&a, &b| emissions[a].total_cmp(&emissions[b]));
+            assert_eq!(best, Some(favored.index()), "{}", dir.display());
+        }
+
+        for dir in [&first, &second] {
+            let converted = NeuralParser::converted_path(&dir.join(MODEL_FILE)).unwrap();
+            std::fs::remove_file(converted).ok();
+        }
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn episode_entities_map_to_specs() {
+        assert_eq!(
+            NeuralParser::parse_episode("07"),
+            Some(EpisodeSpec::Single(7))
+        );
+        assert_eq!(NeuralParser::parse_episode("00"), Some(EpisodeSpec::Zero));
+        assert_eq!(
+            NeuralParser::parse_episode("SP1"),
+            Some(EpisodeSpec::Special(1))
+        );
+        assert_eq!(
+            NeuralParser::parse_episode("Special 02"),
+            Some(EpisodeSpec::Special(2))
+        );
+        assert_eq!(NeuralParser::parse_episode("OVA"), None);
+    }
+
+    #[test]
+    fn assembles_results_from_tags() {
+        let input = "Sousou no Frieren 05 1080p";
+        let offsets = [(0, 0), (0, 6), (7, 9), (10, 17), (18, 20), (21, 26), (0, 0)];
+        let tags = [
+            BioTag::Outside,
+            BioTag::BeginTitle,
+            BioTag::InsideTitle,
+            BioTag::InsideTitle,
+            BioTag::BeginEpisode,
+            BioTag::Resolution,
+            BioTag::Outside,
+        ]
+        .map(|tag| tag.index());
+
+        let result = NeuralParser::assemble_result(input, &offsets, &tags).unwrap();
+        assert_eq!(result.title.as_deref(), Some("Sousou no Frieren"));
+        assert_eq!(result.episode, Some(EpisodeSpec::Single(5)));
+        assert_eq!(result.resolution, Some(Resolution::FHD1080));
+
+        assert!(NeuralParser::assemble_result(input, &offsets, &tags[1..]).is_err());
+        assert!(NeuralParser::assemble_result(input, &offsets[..1], &[99]).is_err());
+    }
+
+    #[test]
+    fn tag_set_follows_config_labels() {
+        let labels = |n: usize| {
+            let id2label: serde_json::Map<String, serde_json::Value> =
+                (0..n).map(|i| (i.to_string(), "O".into())).collect();
+            serde_json::json!({ "id2label": id2label }).to_string()
+        };
+
+        assert_eq!(tag_set_from_config("{}").unwrap(), None);
+        assert_eq!(tag_set_from_config(&labels(17)).unwrap(), Some(TagSet::V1));
+        assert_eq!(tag_set_from_config(&labels(23)).unwrap(), Some(TagSet::V2));
+        assert!(matches!(
+            tag_set_from_config(&labels(3)),
+            Err(ZantetsuError::ModelLoadError(_))
+        ));
+    }
+}
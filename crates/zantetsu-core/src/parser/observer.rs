@@ -0,0 +1,88 @@
+//! # Parser Observers
+//!
+//! Callbacks fired by [`Parser`](crate::parser::Parser) as it picks engines
+//! and falls back between them, so services can export fallback rates and
+//! latency histograms without patching the crate. Register one with
+//! [`Parser::with_observer`](crate::parser::Parser::with_observer).
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::error::ZantetsuError;
+use crate::types::ParseResult;
+
+/// Why the parser gave up on an engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FallbackReason {
+    /// The engine is not loaded.
+    Unavailable,
+    /// The engine returned an error.
+    Error,
+    /// The engine's result was below the confidence threshold.
+    LowConfidence,
+    /// Running the engine would have exceeded the time budget.
+    TimeBudget,
+}
+
+impl fmt::Display for FallbackReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable => write!(f, "unavailable"),
+            Self::Error => write!(f, "error"),
+            Self::LowConfidence => write!(f, "low confidence"),
+            Self::TimeBudget => write!(f, "time budget"),
+        }
+    }
+}
+
+/// Receives events from every [`Parser::parse`](crate::parser::Parser::parse)
+/// call.
+///
+/// All methods default to doing nothing. They run inline on the parsing
+/// thread, so keep them cheap (bump counters, record histograms).
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "neural")] {
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::time::Duration;
+/// use zantetsu_core::parser::{FallbackReason, Parser, ParserObserver};
+///
+/// #[derive(Default)]
+/// struct Fallbacks(AtomicUsize);
+///
+/// impl ParserObserver for Fallbacks {
+///     fn on_fallback(&self, _input: &str, _engine: &str, _reason: FallbackReason) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let fallbacks = Arc::new(Fallbacks::default());
+/// let parser = Parser::default().unwrap().with_observer(fallbacks.clone());
+/// parser.parse("[SubsPlease] Frieren - 01 (1080p).mkv").unwrap();
+/// # }
+/// ```
+pub trait ParserObserver: Send + Sync {
+    /// A parse of `input` is starting.
+    fn on_parse_start(&self, _input: &str) {}
+
+    /// `engine` (see [`ParseEngine::name`](crate::parser::ParseEngine::name))
+    /// is about to run on `input`.
+    fn on_engine_selected(&self, _input: &str, _engine: &str) {}
+
+    /// The parser gave up on `engine` and is moving on to the next option.
+    ///
+    /// `engine` is `"model"` when the whole model tier (DistilBERT and the
+    /// character CNN) is dropped in favour of the heuristic result.
+    fn on_fallback(&self, _input: &str, _engine: &str, _reason: FallbackReason) {}
+
+    /// The parse of `input` finished after `elapsed`.
+    fn on_complete(
+        &self,
+        _input: &str,
+        _outcome: Result<&ParseResult, &ZantetsuError>,
+        _elapsed: Duration,
+    ) {
+    }
+}
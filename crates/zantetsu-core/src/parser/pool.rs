@@ -0,0 +1,163 @@
+//! # Parser Pool
+//!
+//! A fixed set of [`Parser`]s handed out through a checkout guard, for
+//! servers that parse on many threads at once. The parsers are clones of one
+//! another, so the compiled regexes and memory-mapped model weights are
+//! loaded once and shared; only per-parser state is duplicated.
+
+use std::ops::Deref;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::error::Result;
+use crate::parser::unified::{Parser, ParserConfig};
+
+/// A pool of parsers that share their loaded engines.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use zantetsu_core::parser::{ParserConfig, ParserPool};
+///
+/// let pool = Arc::new(ParserPool::new(ParserConfig::default(), 4).unwrap());
+/// let handles: Vec<_> = (1..=8)
+///     .map(|episode| {
+///         let pool = Arc::clone(&pool);
+///         std::thread::spawn(move || {
+///             let parser = pool.checkout();
+///             parser.parse(&format!("[SubsPlease] Frieren - {episode:02} (1080p).mkv"))
+///         })
+///     })
+///     .collect();
+///
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap().unwrap().title.as_deref(), Some("Frieren"));
+/// }
+/// ```
+pub struct ParserPool {
+    idle: Mutex<Vec<Parser>>,
+    returned: Condvar,
+    size: usize,
+}
+
+impl ParserPool {
+    /// Build one parser from `config` and share it across `size` pool slots
+    /// (at least one).
+    pub fn new(config: ParserConfig, size: usize) -> Result<Self> {
+        Ok(Self::from_parser(Parser::new(config)?, size))
+    }
+
+    /// Fill a pool with `size` clones of `parser` (at least one), keeping its
+    /// engines, observers and group registry.
+    pub fn from_parser(parser: Parser, size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            idle: Mutex::new(vec![parser; size]),
+            returned: Condvar::new(),
+            size,
+        }
+    }
+
+    /// Take a parser, waiting for one to be returned if all are in use.
+    pub fn checkout(&self) -> PooledParser<'_> {
+        let mut idle = self.lock();
+        loop {
+            if let Some(parser) = idle.pop() {
+                return PooledParser::new(self, parser);
+            }
+            idle = self
+                .returned
+                .wait(idle)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Take a parser if one is idle, without waiting.
+    pub fn try_checkout(&self) -> Option<PooledParser<'_>> {
+        let parser = self.lock().pop()?;
+        Some(PooledParser::new(self, parser))
+    }
+
+    /// Number of parsers in the pool.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of parsers not currently checked out.
+    pub fn available(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn give_back(&self, parser: Parser) {
+        self.lock().push(parser);
+        self.returned.notify_one();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Parser>> {
+        // The idle list is only pushed and popped, so it cannot be left half-updated.
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A parser checked out of a [`ParserPool`]; returned to the pool on drop.
+pub struct PooledParser<'a> {
+    pool: &'a ParserPool,
+    parser: Option<Parser>,
+}
+
+impl<'a> PooledParser<'a> {
+    fn new(pool: &'a ParserPool, parser: Parser) -> Self {
+        Self {
+            pool,
+            parser: Some(parser),
+        }
+    }
+}
+
+impl Deref for PooledParser<'_> {
+    type Target = Parser;
+
+    fn deref(&self) -> &Parser {
+        self.parser
+            .as_ref()
+            .expect("parser is present until the guard is dropped")
+    }
+}
+
+impl Drop for PooledParser<'_> {
+    fn drop(&mut self) {
+        if let Some(parser) = self.parser.take() {
+            self.pool.give_back(parser);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ParseMode;
+
+    #[test]
+    fn checkouts_are_returned_on_drop() {
+        let pool = ParserPool::new(ParserConfig::new().with_mode(ParseMode::Light), 2).unwrap();
+        assert_eq!(pool.size(), 2);
+
+        let first = pool.checkout();
+        let second = pool.try_checkout().unwrap();
+        assert!(pool.try_checkout().is_none());
+        assert_eq!(pool.available(), 0);
+
+        let result = first
+            .parse("[SubsPlease] Frieren - 01 (1080p).mkv")
+            .unwrap();
+        assert_eq!(result.title.as_deref(), Some("Frieren"));
+        drop(first);
+        assert_eq!(pool.available(), 1);
+        drop(second);
+        assert_eq!(pool.available(), 2);
+
+        assert_eq!(
+            ParserPool::from_parser(Parser::default().unwrap(), 0).size(),
+            1
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! # Result Cache
+//!
+//! LRU cache of finished [`ParseResult`]s for the unified
+//! [`Parser`](crate::parser::Parser). Library scanners and RSS pollers parse
+//! the same names over and over; a hit skips every engine.
+
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use lru::LruCache;
+
+use crate::types::{ParseMode, ParseResult};
+
+/// Thread-safe LRU cache of parse results keyed by mode and input.
+pub(crate) struct ResultCache {
+    entries: Mutex<LruCache<(ParseMode, String), ParseResult>>,
+}
+
+impl ResultCache {
+    /// A cache of up to `capacity` results, or `None` when `capacity` is zero.
+    pub fn with_capacity(capacity: usize) -> Option<Self> {
+        NonZeroUsize::new(capacity).map(|capacity| Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        })
+    }
+
+    /// The cached result of parsing `input` in `mode`.
+    pub fn get(&self, mode: ParseMode, input: &str) -> Option<ParseResult> {
+        self.lock().get(&(mode, input.to_string())).cloned()
+    }
+
+    /// Remember `result` as the outcome of parsing `input` in `mode`.
+    pub fn insert(&self, mode: ParseMode, input: &str, result: &ParseResult) {
+        self.lock().put((mode, input.to_string()), result.clone());
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache<(ParseMode, String), ParseResult>> {
+        // Entries are plain data, so a panic elsewhere cannot leave them inconsistent.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_on_mode_and_input() {
+        assert!(ResultCache::with_capacity(0).is_none());
+        let cache = ResultCache::with_capacity(1).unwrap();
+
+        let result = ParseResult::new("a", ParseMode::Light);
+        cache.insert(ParseMode::Light, "a", &result);
+        assert_eq!(cache.get(ParseMode::Light, "a"), Some(result));
+        assert_eq!(cache.get(ParseMode::Full, "a"), None);
+
+        cache.insert(
+            ParseMode::Light,
+            "b",
+            &ParseResult::new("b", ParseMode::Light),
+        );
+        assert_eq!(cache.get(ParseMode::Light, "a"), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! # Parse Traces
+//!
+//! What happened inside one [`Parser::parse_traced`](crate::parser::Parser::parse_traced)
+//! call: how the input was tokenized, which regexes matched where, the tags
+//! the model chose, and which engines ran or were given up on. Meant for
+//! working out why a title was misparsed.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use crate::parser::bio_tags::BioTag;
+use crate::parser::observer::{FallbackReason, ParserObserver};
+use crate::parser::tokenizer::Token;
+
+/// A heuristic regex match in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexMatch {
+    /// Name of the pattern (e.g. `crc32`, `dash_episode`).
+    pub pattern: &'static str,
+    /// Byte offset where the match starts.
+    pub start: usize,
+    /// Byte offset one past the end of the match.
+    pub end: usize,
+    /// The matched text.
+    pub text: String,
+}
+
+/// One model token with the tag Viterbi decoding chose for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaggedToken {
+    /// The input text the token covers.
+    pub text: String,
+    /// Byte offset where the token starts.
+    pub start: usize,
+    /// Byte offset one past the end of the token.
+    pub end: usize,
+    /// Decoded tag.
+    pub tag: BioTag,
+}
+
+/// Debugging details of a single parse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseTrace {
+    /// The input split on filename delimiters.
+    pub tokens: Vec<Token>,
+    /// Every heuristic pattern match in the raw input, in input order. The
+    /// heuristic parser masks parts of the input for some fields, so not
+    /// every match here ends up in the result.
+    pub regex_matches: Vec<RegexMatch>,
+    /// DistilBERT's subword tokens and decoded tags, when that model ran.
+    pub model_tags: Option<Vec<TaggedToken>>,
+    /// Engines that ran, in order.
+    pub engines: Vec<String>,
+    /// Engines that were given up on, and why.
+    pub fallbacks: Vec<(String, FallbackReason)>,
+    /// Wall-clock time of the parse, excluding trace collection.
+    pub elapsed: Duration,
+}
+
+/// Observer collecting engine choices, fallbacks and model tags for a
+/// [`ParseTrace`].
+#[derive(Default)]
+#[cfg_attr(not(feature = "neural"), allow(dead_code))]
+pub(crate) struct TraceRecorder {
+    engines: Mutex<Vec<String>>,
+    fallbacks: Mutex<Vec<(String, FallbackReason)>>,
+    model_tags: Mutex<Option<Vec<TaggedToken>>>,
+}
+
+#[cfg_attr(not(feature = "neural"), allow(dead_code))]
+impl TraceRecorder {
+    /// Record the tags of the model decode behind the result.
+    pub fn record_model_tags(&self, tags: Vec<TaggedToken>) {
+        *self
+            .model_tags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(tags);
+    }
+
+    /// The recorded model tags.
+    pub fn take_model_tags(&self) -> Option<Vec<TaggedToken>> {
+        self.model_tags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    /// The recorded engines and fallbacks.
+    pub fn take(&self) -> (Vec<String>, Vec<(String, FallbackReason)>) {
+        let engines =
+            std::mem::take(&mut *self.engines.lock().unwrap_or_else(PoisonError::into_inner));
+        let fallbacks = std::mem::take(
+            &mut *self
+                .fallbacks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        (engines, fallbacks)
+    }
+}
+
+impl ParserObserver for TraceRecorder {
+    fn on_engine_selected(&self, _input: &str, engine: &str) {
+        self.engines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(engine.to_string());
+    }
+
+    fn on_fallback(&self, _input: &str, engine: &str, reason: FallbackReason) {
+        self.fallbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((engine.to_string(), reason));
+    }
+}
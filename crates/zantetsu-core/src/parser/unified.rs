@@ -3,10 +3,109 @@
 //! Provides a unified API for parsing anime filenames with automatic
 //! mode selection and fallback handling.
 
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::crf::model::ModelPrecision;
 use crate::error::{Result, ZantetsuError};
+use crate::parser::char_cnn::CharCnnParser;
+use crate::parser::engine::ParseEngine;
 use crate::parser::heuristic::HeuristicParser;
 use crate::parser::neural::NeuralParser;
-use crate::types::{ParseMode, ParseResult};
+use crate::parser::observer::{FallbackReason, ParserObserver};
+use crate::parser::result_cache::ResultCache;
+use crate::parser::tokenizer::Tokenizer;
+use crate::parser::trace::{ParseTrace, TraceRecorder};
+use crate::scoring::{GroupBlocklist, GroupRegistry};
+use crate::types::{
+    EpisodeNumbering, FieldProvenance, FieldSource, ParseField, ParseMode, ParseResult,
+    ParseWarning,
+};
+
+/// How extracted titles are cased before being returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TitleCasing {
+    /// Keep the casing found in the input.
+    #[default]
+    Preserve,
+    /// Capitalize the first letter of each word and lowercase the rest.
+    TitleCase,
+    /// Lowercase the whole title.
+    Lowercase,
+}
+
+impl TitleCasing {
+    /// Apply this casing to `title`.
+    pub fn apply(self, title: &str) -> String {
+        match self {
+            Self::Preserve => title.to_string(),
+            Self::Lowercase => title.to_lowercase(),
+            Self::TitleCase => title
+                .split(' ')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                            .collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+/// How auto mode chooses between the heuristic parser and the model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// Run the heuristic parser; consult the model only when the heuristic
+    /// result is below the confidence threshold or lacks a title, group or
+    /// episode, then take the model result if it is far more confident or
+    /// fuse the two.
+    #[default]
+    HeuristicFirst,
+    /// Run the model; fall back to fusing in the heuristic result when the
+    /// model is below the confidence threshold or fails.
+    NeuralFirst,
+    /// Run engines cheapest first and return the first that reaches the
+    /// confidence threshold, without fusing (the most confident result if
+    /// none does).
+    CheapestAboveThreshold,
+    /// Always run both and return the more confident result, without fusing.
+    RunBothPickBest,
+}
+
+/// Outcome of running the model tier in auto mode.
+#[allow(clippy::large_enum_variant)] // short-lived, never stored
+enum ModelOutcome {
+    Parsed(ParseResult),
+    Failed,
+    OverBudget,
+}
+
+/// How heuristic and model evidence are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FusionMode {
+    /// Decode the model freely, then let the heuristic result fill fields
+    /// the model missed.
+    #[default]
+    Merge,
+    /// Feed unambiguous regex matches (CRC32, resolution, codecs, extension)
+    /// into Viterbi decoding as hard constraints, so the model labels the
+    /// rest of the filename around them.
+    ConstrainNeural,
+}
+
+/// Environment variable naming the neural model's directory when
+/// [`ParserConfig::model_dir`] is unset, for hosts that cannot configure the
+/// parser in code (packaged apps, the language bindings).
+pub const MODEL_DIR_ENV: &str = "ZANTETSU_MODEL_DIR";
 
 /// Configuration for the parser.
 #[derive(Debug, Clone)]
@@ -17,6 +116,47 @@ pub struct ParserConfig {
     pub confidence_threshold: f32,
     /// Whether to enable neural parser
     pub enable_neural: bool,
+    /// Directory of the neural model (`None` means `$ZANTETSU_MODEL_DIR`,
+    /// else `model_repo`, else `models/ner_model`)
+    pub model_dir: Option<PathBuf>,
+    /// Hugging Face Hub repository (`owner/name`, optionally
+    /// `owner/name@revision`) to download the neural model from when no
+    /// directory is configured; needs the `hub` feature
+    pub model_repo: Option<String>,
+    /// Whether to load the character-CNN model from `models/char_cnn`; used
+    /// when the DistilBERT model is disabled or unavailable (e.g. on devices
+    /// too small to run it)
+    pub enable_char_cnn: bool,
+    /// Fields that must be extracted; a parse missing any of them fails
+    /// with `ZantetsuError::ParseFailed` (empty means no strict checking)
+    pub required_fields: Vec<ParseField>,
+    /// Casing applied to extracted titles
+    pub title_casing: TitleCasing,
+    /// Keep a parenthesized year that directly follows the title as part of
+    /// it, e.g. `Hunter x Hunter (2011)` (the `year` field is still set)
+    pub keep_title_year: bool,
+    /// Weight precision for the neural model (`F16`/`BF16`/`Int8` trade a little
+    /// accuracy for a smaller memory footprint and faster bulk parsing)
+    pub model_precision: ModelPrecision,
+    /// Number of recent inputs whose tokenization the neural parser caches
+    /// (`0` disables the cache)
+    pub encoding_cache_size: usize,
+    /// Also cache the neural model's emission scores, so repeated inputs
+    /// skip inference (only used when `encoding_cache_size` is non-zero)
+    pub cache_emissions: bool,
+    /// How heuristic matches are combined with model output
+    pub fusion: FusionMode,
+    /// Wall-clock ceiling per parse in auto and ensemble modes; model
+    /// inference that would overrun it is skipped (`None` means no limit)
+    pub time_budget: Option<Duration>,
+    /// Fill CRC32, extension, resolution and codecs missing from a model
+    /// result with the heuristic parser's values
+    pub backfill_missing_fields: bool,
+    /// Number of recent parse results the parser keeps, keyed by mode and
+    /// input (`0` disables the cache)
+    pub cache_capacity: usize,
+    /// How auto mode chooses between the heuristic parser and the model
+    pub selection: SelectionStrategy,
 }
 
 impl Default for ParserConfig {
@@ -25,6 +165,20 @@ impl Default for ParserConfig {
             mode: ParseMode::Auto,
             confidence_threshold: 0.6,
             enable_neural: true,
+            model_dir: None,
+            model_repo: None,
+            enable_char_cnn: false,
+            required_fields: Vec::new(),
+            title_casing: TitleCasing::Preserve,
+            keep_title_year: false,
+            model_precision: ModelPrecision::F32,
+            encoding_cache_size: 0,
+            cache_emissions: false,
+            fusion: FusionMode::Merge,
+            time_budget: None,
+            backfill_missing_fields: false,
+            cache_capacity: 0,
+            selection: SelectionStrategy::HeuristicFirst,
         }
     }
 }
@@ -52,13 +206,192 @@ impl ParserConfig {
         self.enable_neural = enabled;
         self
     }
+
+    /// Load the neural model from `dir` instead of `models/ner_model`.
+    pub fn with_model_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.model_dir = Some(dir.into());
+        self
+    }
+
+    /// Download the neural model from the Hugging Face Hub repository
+    /// `repo` (`owner/name[@revision]`) when no directory is configured.
+    pub fn with_model_repo(mut self, repo: impl Into<String>) -> Self {
+        self.model_repo = Some(repo.into());
+        self
+    }
+
+    /// The directory the neural model loads from: `model_dir`, else
+    /// `$ZANTETSU_MODEL_DIR`, else the download of `model_repo`; `None`
+    /// means `models/ner_model`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::ModelLoadError` if `model_repo` cannot be
+    /// downloaded, or is set in a build without the `hub` feature.
+    pub fn resolve_model_dir(&self) -> Result<Option<PathBuf>> {
+        if let Some(dir) = &self.model_dir {
+            return Ok(Some(dir.clone()));
+        }
+        if let Some(dir) = std::env::var_os(MODEL_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            return Ok(Some(dir.into()));
+        }
+        self.model_repo.as_deref().map(fetch_repo).transpose()
+    }
+
+    /// Enable or disable the character-CNN parser.
+    pub fn with_char_cnn(mut self, enabled: bool) -> Self {
+        self.enable_char_cnn = enabled;
+        self
+    }
+
+    /// Set how heuristic matches are combined with model output.
+    pub fn fusion(mut self, mode: FusionMode) -> Self {
+        self.fusion = mode;
+        self
+    }
+
+    /// Limit each auto or ensemble parse to `budget`.
+    ///
+    /// Model inference is skipped when its recent average latency would not
+    /// fit in what is left of the budget, and its result is dropped if it
+    /// finishes late; either way the heuristic result is returned with
+    /// `budget_exhausted` set. The very first model call has no latency
+    /// estimate and always runs.
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Enable or disable heuristic backfill of model results.
+    pub fn with_field_backfill(mut self, enabled: bool) -> Self {
+        self.backfill_missing_fields = enabled;
+        self
+    }
+
+    /// Cache up to `capacity` parse results (`0` disables the cache).
+    ///
+    /// Clones of the parser share the cache. Results cut short by the time
+    /// budget are not cached.
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+
+    /// Set how auto mode chooses between the heuristic parser and the model.
+    pub fn with_selection_strategy(mut self, strategy: SelectionStrategy) -> Self {
+        self.selection = strategy;
+        self
+    }
+
+    /// Set the casing applied to extracted titles.
+    pub fn with_title_casing(mut self, casing: TitleCasing) -> Self {
+        self.title_casing = casing;
+        self
+    }
+
+    /// Set the neural model weight precision.
+    pub fn with_model_precision(mut self, precision: ModelPrecision) -> Self {
+        self.model_precision = precision;
+        self
+    }
+
+    /// Cache the tokenization of up to `size` recent inputs (`0` disables it).
+    pub fn with_encoding_cache(mut self, size: usize) -> Self {
+        self.encoding_cache_size = size;
+        self
+    }
+
+    /// Also cache emission scores for inputs in the encoding cache.
+    pub fn with_emission_cache(mut self, enabled: bool) -> Self {
+        self.cache_emissions = enabled;
+        self
+    }
+
+    /// Keep title-adjacent years like `(2011)` in the title to disambiguate remakes.
+    pub fn with_title_year(mut self, enabled: bool) -> Self {
+        self.keep_title_year = enabled;
+        self
+    }
+
+    /// Enable strict mode: parses missing any of `required_fields` return
+    /// `ZantetsuError::ParseFailed` instead of a partial result.
+    pub fn strict(mut self, required_fields: impl IntoIterator<Item = ParseField>) -> Self {
+        self.required_fields = required_fields.into_iter().collect();
+        self
+    }
 }
 
 /// Unified parser that handles both heuristic and neural parsing with automatic fallback.
+///
+/// `Parser` is `Send + Sync` and parses through `&self`, so a server can build
+/// one and share it across worker threads without a lock. Cloning is cheap:
+/// clones share the compiled regexes and memory-mapped model weights (see
+/// [`ParserPool`](crate::parser::ParserPool)).
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use zantetsu_core::parser::Parser;
+///
+/// let parser = Arc::new(Parser::default().unwrap());
+/// let handles: Vec<_> = (1..=4)
+///     .map(|episode| {
+///         let parser = Arc::clone(&parser);
+///         std::thread::spawn(move || {
+///             let input = format!("[SubsPlease] Frieren - {episode:02} (1080p).mkv");
+///             parser.parse(&input).unwrap()
+///         })
+///     })
+///     .collect();
+///
+/// for handle in handles {
+///     assert_eq!(handle.join().unwrap().title.as_deref(), Some("Frieren"));
+/// }
+/// ```
+#[derive(Clone)]
 pub struct Parser {
     config: ParserConfig,
-    heuristic: HeuristicParser,
-    neural: Option<NeuralParser>,
+    heuristic: Arc<HeuristicParser>,
+    neural: Option<Arc<NeuralParser>>,
+    char_cnn: Option<Arc<CharCnnParser>>,
+    /// User engines, cheapest first
+    engines: Vec<Arc<dyn ParseEngine>>,
+    groups: Option<GroupRegistry>,
+    blocklist: Option<GroupBlocklist>,
+    observers: Vec<Arc<dyn ParserObserver>>,
+    model_latency: Arc<LatencyEstimate>,
+    results: Option<Arc<ResultCache>>,
+    /// Receives the model tags during [`parse_traced`](Self::parse_traced)
+    trace: Option<Arc<TraceRecorder>>,
+    /// Stands in for the model tier in tests
+    #[cfg(test)]
+    model_stub: Option<Arc<dyn ParseEngine>>,
+}
+
+/// Moving average of model inference latency, for time budgets.
+#[derive(Debug, Default)]
+struct LatencyEstimate {
+    nanos: AtomicU64,
+}
+
+impl LatencyEstimate {
+    fn get(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    /// Fold in one measurement, weighting it 1/8 against the history.
+    fn record(&self, sample: Duration) {
+        let sample = u64::try_from(sample.as_nanos()).unwrap_or(u64::MAX);
+        let _ = self
+            .nanos
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |average| {
+                Some(if average == 0 {
+                    sample
+                } else {
+                    average - average / 8 + sample / 8
+                })
+            });
+    }
 }
 
 fn is_usable_text(value: &Option<String>) -> bool {
@@ -77,8 +410,7 @@ fn normalize_whitespace(value: &str) -> String {
 
 fn normalize_candidate_text(value: &str) -> Option<String> {
     let cleaned = value
-        .replace('.', " ")
-        .replace('_', " ")
+        .replace(['.', '_'], " ")
         .replace(['[', ']', '(', ')', '{', '}'], " ")
         .trim_matches(|c: char| {
             matches!(c, '[' | ']' | '(' | ')' | '{' | '}' | ' ' | '.' | '_' | '-')
@@ -177,6 +509,19 @@ fn sanitize_result(mut result: ParseResult) -> ParseResult {
     result
 }
 
+/// Re-attach a `(YYYY)` that immediately follows the title in the input.
+fn embed_title_year(mut result: ParseResult) -> ParseResult {
+    if let (Some(title), Some(year)) = (result.title.as_ref(), result.year) {
+        let haystack = normalize_whitespace(&result.input.replace(['.', '_'], " ")).to_lowercase();
+        let needle = format!("{} ({year})", title.to_lowercase());
+        if haystack.contains(&needle) {
+            result.title = Some(format!("{title} ({year})"));
+        }
+    }
+
+    result
+}
+
 fn is_heuristic_complete(result: &ParseResult) -> bool {
     result.title.is_some() && result.group.is_some() && result.episode.is_some()
 }
@@ -209,6 +554,9 @@ fn fuse_results(
     if heuristic.episode.is_none() {
         heuristic.episode = neural.episode.clone();
     }
+    if heuristic.episode_count.is_none() {
+        heuristic.episode_count = neural.episode_count;
+    }
     if heuristic.season.is_none() {
         heuristic.season = neural.season;
     }
@@ -227,6 +575,9 @@ fn fuse_results(
     if heuristic.year.is_none() {
         heuristic.year = neural.year;
     }
+    if heuristic.year_range.is_none() {
+        heuristic.year_range = neural.year_range;
+    }
     if heuristic.crc32.is_none() {
         heuristic.crc32 = neural.crc32.clone();
     }
@@ -236,14 +587,272 @@ fn fuse_results(
     if heuristic.version.is_none() {
         heuristic.version = neural.version;
     }
+    if heuristic.episode_title.is_none() {
+        heuristic.episode_title = neural.episode_title.clone();
+    }
+    if heuristic.part.is_none() {
+        heuristic.part = neural.part;
+    }
+    if heuristic.audio_channels.is_none() {
+        heuristic.audio_channels = neural.audio_channels.clone();
+    }
+    if heuristic.language.is_none() {
+        heuristic.language = neural.language.clone();
+    }
+    if heuristic.release_flags.is_empty() {
+        heuristic.release_flags = neural.release_flags.clone();
+    }
 
     heuristic.confidence = heuristic
         .confidence
         .max((heuristic.confidence + neural.confidence * 0.35).clamp(0.0, 1.0));
 
+    heuristic.episode_numbering =
+        EpisodeNumbering::infer(heuristic.season, heuristic.episode.as_ref());
+
+    // The model's score only vouches for values it extracted too
+    for (field, confidence) in neural.field_confidence.iter() {
+        if field.is_present(&heuristic) && heuristic.field_value(field) == neural.field_value(field)
+        {
+            heuristic.field_confidence.set(field, confidence);
+        }
+    }
+
     sanitize_result(heuristic)
 }
 
+/// Fill exact-token fields the model missed from the heuristic result,
+/// recording the heuristic as their source.
+fn backfill_fields(mut neural: ParseResult, heuristic: &ParseResult) -> ParseResult {
+    fn fill<T: Clone>(
+        field: ParseField,
+        target: &mut Option<T>,
+        source: &Option<T>,
+        provenance: &mut FieldProvenance,
+    ) {
+        if target.is_none() && source.is_some() {
+            *target = source.clone();
+            provenance.set(field, FieldSource::Heuristic);
+        }
+    }
+
+    let p = &mut neural.provenance;
+    fill(ParseField::Crc32, &mut neural.crc32, &heuristic.crc32, p);
+    fill(
+        ParseField::Extension,
+        &mut neural.extension,
+        &heuristic.extension,
+        p,
+    );
+    fill(
+        ParseField::Resolution,
+        &mut neural.resolution,
+        &heuristic.resolution,
+        p,
+    );
+    fill(
+        ParseField::VideoCodec,
+        &mut neural.video_codec,
+        &heuristic.video_codec,
+        p,
+    );
+    fill(
+        ParseField::AudioCodec,
+        &mut neural.audio_codec,
+        &heuristic.audio_codec,
+        p,
+    );
+    neural
+}
+
+/// Take the preferred engine's value for `field`, falling back to the
+/// other engine's, and record which one was used.
+fn pick_field<T>(
+    field: ParseField,
+    heuristic: Option<T>,
+    neural: Option<T>,
+    prefer: FieldSource,
+    provenance: &mut FieldProvenance,
+) -> Option<T> {
+    let (preferred, fallback, fallback_source) = match prefer {
+        FieldSource::Heuristic => (heuristic, neural, FieldSource::Neural),
+        FieldSource::Neural => (neural, heuristic, FieldSource::Heuristic),
+    };
+    let (value, source) = match (preferred, fallback) {
+        (Some(value), _) => (value, prefer),
+        (None, Some(value)) => (value, fallback_source),
+        (None, None) => return None,
+    };
+    provenance.set(field, source);
+    Some(value)
+}
+
+/// Merge two engine results field by field ([`ParseMode::Ensemble`]).
+///
+/// Free-text fields (title, group, episode title) come from the model, which
+/// sees their context; exact tokens (CRC32, resolution, codecs, extension...)
+/// come from the regexes; episode and season go to whichever engine is more
+/// confident.
+fn merge_fields(heuristic: ParseResult, neural: ParseResult) -> ParseResult {
+    let heuristic = sanitize_result(heuristic);
+    let neural = sanitize_result(neural);
+    let mut provenance = FieldProvenance::new();
+    let mut result = ParseResult::new(heuristic.input.clone(), ParseMode::Ensemble);
+
+    let text = |value: Option<String>| {
+        Some(value)
+            .filter(is_usable_text)
+            .flatten()
+            .filter(|text| !looks_like_metadata_noise(text))
+    };
+    let more_confident = |field: ParseField| match neural.field_confidence.get(field) {
+        Some(confidence) if confidence > heuristic.confidence => FieldSource::Neural,
+        _ => FieldSource::Heuristic,
+    };
+    let episode_source = more_confident(ParseField::Episode);
+    let season_source = more_confident(ParseField::Season);
+
+    use FieldSource::{Heuristic, Neural};
+    let p = &mut provenance;
+    result.title = pick_field(
+        ParseField::Title,
+        text(heuristic.title),
+        text(neural.title),
+        Neural,
+        p,
+    );
+    result.group = pick_field(
+        ParseField::Group,
+        text(heuristic.group),
+        text(neural.group),
+        Neural,
+        p,
+    );
+    result.episode_title = pick_field(
+        ParseField::EpisodeTitle,
+        text(heuristic.episode_title),
+        text(neural.episode_title),
+        Neural,
+        p,
+    );
+    result.episode = pick_field(
+        ParseField::Episode,
+        heuristic.episode,
+        neural.episode,
+        episode_source,
+        p,
+    );
+    result.season = pick_field(
+        ParseField::Season,
+        heuristic.season,
+        neural.season,
+        season_source,
+        p,
+    );
+    result.episode_count = pick_field(
+        ParseField::EpisodeCount,
+        heuristic.episode_count,
+        neural.episode_count,
+        Heuristic,
+        p,
+    );
+    result.part = pick_field(ParseField::Part, heuristic.part, neural.part, Heuristic, p);
+    result.resolution = pick_field(
+        ParseField::Resolution,
+        heuristic.resolution,
+        neural.resolution,
+        Heuristic,
+        p,
+    );
+    result.video_codec = pick_field(
+        ParseField::VideoCodec,
+        heuristic.video_codec,
+        neural.video_codec,
+        Heuristic,
+        p,
+    );
+    result.audio_codec = pick_field(
+        ParseField::AudioCodec,
+        heuristic.audio_codec,
+        neural.audio_codec,
+        Heuristic,
+        p,
+    );
+    result.audio_channels = pick_field(
+        ParseField::AudioChannels,
+        heuristic.audio_channels,
+        neural.audio_channels,
+        Heuristic,
+        p,
+    );
+    result.language = pick_field(
+        ParseField::Language,
+        heuristic.language,
+        neural.language,
+        Heuristic,
+        p,
+    );
+    result.source = pick_field(
+        ParseField::Source,
+        heuristic.source,
+        neural.source,
+        Heuristic,
+        p,
+    );
+    result.year = pick_field(ParseField::Year, heuristic.year, neural.year, Heuristic, p);
+    result.year_range = pick_field(
+        ParseField::YearRange,
+        heuristic.year_range,
+        neural.year_range,
+        Heuristic,
+        p,
+    );
+    result.crc32 = pick_field(
+        ParseField::Crc32,
+        heuristic.crc32,
+        neural.crc32,
+        Heuristic,
+        p,
+    );
+    result.extension = pick_field(
+        ParseField::Extension,
+        heuristic.extension,
+        neural.extension,
+        Heuristic,
+        p,
+    );
+    result.version = pick_field(
+        ParseField::Version,
+        heuristic.version,
+        neural.version,
+        Heuristic,
+        p,
+    );
+    let flags = |flags: Vec<String>| Some(flags).filter(|flags| !flags.is_empty());
+    result.release_flags = pick_field(
+        ParseField::ReleaseFlags,
+        flags(heuristic.release_flags),
+        flags(neural.release_flags),
+        Heuristic,
+        p,
+    )
+    .unwrap_or_default();
+
+    for (field, confidence) in neural.field_confidence.iter() {
+        if provenance.get(field) == Some(Neural) {
+            result.field_confidence.set(field, confidence);
+        }
+    }
+    result.confidence = heuristic.confidence.max(neural.confidence);
+    result.episode_numbering = EpisodeNumbering::infer(result.season, result.episode.as_ref());
+    result.group_trust = None;
+    result.provenance = provenance;
+    result.extras = heuristic.extras;
+    result.audio_tracks = heuristic.audio_tracks;
+
+    sanitize_result(result)
+}
+
 impl Parser {
     /// Create a new parser with the given configuration.
     pub fn new(config: ParserConfig) -> Result<Self> {
@@ -252,9 +861,25 @@ impl Parser {
         let neural = if config.enable_neural {
             match NeuralParser::new() {
                 Ok(mut parser) => {
-                    // Try to initialize model - if it fails, we'll fall back to heuristic
-                    let _ = parser.init_model();
-                    Some(parser)
+                    // Only keep the neural parser if its model loaded; otherwise
+                    // we'll fall back to heuristic
+                    let loaded = config.resolve_model_dir().and_then(|dir| match dir {
+                        Some(dir) => parser.init_model_from_dir(&dir, config.model_precision),
+                        None => parser.init_model_with_precision(config.model_precision),
+                    });
+                    match loaded {
+                        Ok(()) => {
+                            parser.set_encoding_cache(
+                                config.encoding_cache_size,
+                                config.cache_emissions,
+                            );
+                            Some(Arc::new(parser))
+                        }
+                        Err(err) => {
+                            tracing::debug!(%err, "neural model not loaded");
+                            None
+                        }
+                    }
                 }
                 Err(_) => None,
             }
@@ -262,13 +887,71 @@ impl Parser {
             None
         };
 
+        // Only keep the character model if it actually loaded
+        let char_cnn = if config.enable_char_cnn {
+            let mut parser = CharCnnParser::new();
+            parser.init_model().ok().map(|()| Arc::new(parser))
+        } else {
+            None
+        };
+
+        let results = ResultCache::with_capacity(config.cache_capacity).map(Arc::new);
+
         Ok(Self {
             config,
-            heuristic,
+            heuristic: Arc::new(heuristic),
             neural,
+            char_cnn,
+            engines: Vec::new(),
+            groups: None,
+            blocklist: None,
+            observers: Vec::new(),
+            model_latency: Arc::default(),
+            results,
+            trace: None,
+            #[cfg(test)]
+            model_stub: None,
         })
     }
 
+    /// Add a user-provided engine.
+    ///
+    /// User engines are consulted, cheapest first, before the configured
+    /// mode runs; the first result reaching the confidence threshold is
+    /// returned. Otherwise parsing falls through to the built-in engines.
+    pub fn with_engine(mut self, engine: impl ParseEngine + 'static) -> Self {
+        let position = self
+            .engines
+            .partition_point(|existing| existing.cost_hint() <= engine.cost_hint());
+        self.engines.insert(position, Arc::new(engine));
+        self
+    }
+
+    /// Attach a group registry; results get `group_trust` set from it.
+    pub fn with_group_registry(mut self, registry: GroupRegistry) -> Self {
+        self.groups = Some(registry);
+        self
+    }
+
+    /// Attach a group blocklist; results from listed groups get a
+    /// [`ParseWarning::BlocklistedGroup`].
+    pub fn with_group_blocklist(mut self, blocklist: GroupBlocklist) -> Self {
+        self.blocklist = Some(blocklist);
+        self
+    }
+
+    /// Register an observer notified of every parse.
+    pub fn with_observer(mut self, observer: Arc<dyn ParserObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    fn notify(&self, event: impl Fn(&dyn ParserObserver)) {
+        for observer in &self.observers {
+            event(observer.as_ref());
+        }
+    }
+
     /// Create a new parser with default configuration.
     #[allow(clippy::should_implement_trait)]
     pub fn default() -> Result<Self> {
@@ -294,97 +977,661 @@ impl Parser {
     /// assert_eq!(result.group.as_deref(), Some("SubsPlease"));
     /// ```
     pub fn parse(&self, input: &str) -> Result<ParseResult> {
-        match self.config.mode {
-            ParseMode::Full => self.parse_full(input),
-            ParseMode::Light => self.parse_light(input),
-            ParseMode::Auto => self.parse_auto(input),
+        if self.observers.is_empty() {
+            return self.parse_observed(input);
         }
+
+        let started = Instant::now();
+        self.notify(|observer| observer.on_parse_start(input));
+        let result = self.parse_observed(input);
+        let elapsed = started.elapsed();
+        self.notify(|observer| observer.on_complete(input, result.as_ref(), elapsed));
+        result
+    }
+
+    /// [`parse`](Self::parse) without the start and completion events.
+    fn parse_observed(&self, input: &str) -> Result<ParseResult> {
+        let Some(results) = &self.results else {
+            return self.parse_uncached(input);
+        };
+        if let Some(result) = results.get(self.config.mode, input) {
+            return Ok(result);
+        }
+
+        let result = self.parse_uncached(input)?;
+        // A budget-limited result is degraded; let the next parse try again
+        if !result.budget_exhausted {
+            results.insert(self.config.mode, input, &result);
+        }
+        Ok(result)
+    }
+
+    /// Parse without consulting the result cache.
+    fn parse_uncached(&self, input: &str) -> Result<ParseResult> {
+        let mut result = match self.parse_user_engines(input) {
+            Some(result) => result,
+            None => match self.config.mode {
+                ParseMode::Full => self.parse_full(input),
+                ParseMode::Light => self.parse_light(input),
+                ParseMode::Auto => self.parse_auto(input),
+                ParseMode::Ensemble => self.parse_ensemble(input),
+            }?,
+        };
+
+        if let (Some(groups), Some(group)) = (&self.groups, &result.group) {
+            result.group_trust = groups.trust(group);
+        }
+        if let (Some(blocklist), Some(group)) = (&self.blocklist, &result.group)
+            && let Some(penalty) = blocklist.penalty(group)
+        {
+            result.warnings.push(ParseWarning::BlocklistedGroup {
+                group: group.clone(),
+                penalty,
+            });
+        }
+
+        if self.config.keep_title_year {
+            result = embed_title_year(result);
+        }
+
+        if self.config.title_casing != TitleCasing::Preserve {
+            result.title = result
+                .title
+                .map(|title| self.config.title_casing.apply(&title));
+        }
+
+        self.check_required_fields(result)
+    }
+
+    /// Parse like [`parse`](Self::parse) into the JSON of the result, with
+    /// every field; see [`ParseResult::to_json`].
+    pub fn parse_json(&self, input: &str) -> Result<String> {
+        self.parse(input).map(|result| result.to_json())
+    }
+
+    /// Parse like [`parse`](Self::parse), also returning a [`ParseTrace`]
+    /// of tokenization, regex matches, model tags, and engine fallbacks.
+    ///
+    /// The result cache is bypassed so the engines actually run; use this
+    /// for debugging, not in hot paths.
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::parser::Parser;
+    ///
+    /// let parser = Parser::default().unwrap();
+    /// let (result, trace) = parser.parse_traced("[SubsPlease] Frieren - 01 (1080p).mkv").unwrap();
+    ///
+    /// assert_eq!(result.title.as_deref(), Some("Frieren"));
+    /// assert!(trace.regex_matches.iter().any(|m| m.pattern == "resolution"));
+    /// ```
+    pub fn parse_traced(&self, input: &str) -> Result<(ParseResult, ParseTrace)> {
+        let recorder = Arc::new(TraceRecorder::default());
+        let mut parser = self.clone();
+        parser.results = None;
+        parser.observers.push(recorder.clone());
+        parser.trace = Some(recorder.clone());
+
+        let started = Instant::now();
+        let result = parser.parse(input)?;
+        let elapsed = started.elapsed();
+
+        let (engines, fallbacks) = recorder.take();
+        let trace = ParseTrace {
+            tokens: Tokenizer::new().tokenize(input),
+            regex_matches: self.heuristic.regex_matches(input),
+            model_tags: recorder.take_model_tags(),
+            engines,
+            fallbacks,
+            elapsed,
+        };
+        Ok((result, trace))
+    }
+
+    /// Parse on tokio's blocking thread pool so neural inference does not
+    /// stall the async executor.
+    ///
+    /// Takes the parser behind an `Arc` because the work outlives the call
+    /// site; share one parser across tasks rather than building one per call.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// use std::sync::Arc;
+    /// use zantetsu_core::parser::Parser;
+    ///
+    /// let parser = Arc::new(Parser::default().unwrap());
+    /// let result = parser.parse_async("[SubsPlease] Frieren - 01 (1080p).mkv").await.unwrap();
+    /// assert_eq!(result.title.as_deref(), Some("Frieren"));
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn parse_async(
+        self: &std::sync::Arc<Self>,
+        input: impl Into<String>,
+    ) -> Result<ParseResult> {
+        let parser = std::sync::Arc::clone(self);
+        let input = input.into();
+        tokio::task::spawn_blocking(move || parser.parse(&input))
+            .await
+            .map_err(|e| ZantetsuError::InferenceError(format!("parse task failed: {e}")))?
+    }
+
+    /// Enforce `ParserConfig::required_fields` (strict mode).
+    fn check_required_fields(&self, result: ParseResult) -> Result<ParseResult> {
+        if self
+            .config
+            .required_fields
+            .iter()
+            .any(|field| !field.is_present(&result))
+        {
+            return Err(ZantetsuError::ParseFailed {
+                input: result.input,
+            });
+        }
+
+        Ok(result)
     }
 
     /// Parse using the neural CRF model (ParseMode::Full).
     fn parse_full(&self, input: &str) -> Result<ParseResult> {
-        if let Some(ref neural) = self.neural {
-            neural.parse(input).map(sanitize_result)
+        if self.has_model() {
+            let result = self.parse_model(input)?;
+            if !self.config.backfill_missing_fields {
+                return Ok(result);
+            }
+            let heuristic_result = self.parse_light(input)?;
+            Ok(backfill_fields(result, &heuristic_result))
         } else {
             // Neural parser not available, fall back to heuristic
-            let mut result = sanitize_result(self.heuristic.parse(input)?);
+            self.notify(|observer| {
+                observer.on_fallback(input, "neural", FallbackReason::Unavailable)
+            });
+            let mut result = self.parse_light(input)?;
             result.parse_mode = ParseMode::Light; // Mark as fallback
             Ok(result)
         }
     }
 
+    /// Parse with the most capable available model: DistilBERT, then the
+    /// character CNN.
+    ///
+    /// With [`FusionMode::ConstrainNeural`] the heuristic evidence constrains
+    /// decoding.
+    fn parse_model(&self, input: &str) -> Result<ParseResult> {
+        #[cfg(test)]
+        if let Some(stub) = &self.model_stub {
+            self.notify(|observer| observer.on_engine_selected(input, stub.name()));
+            return stub.parse(input).map(sanitize_result);
+        }
+        let evidence = match self.config.fusion {
+            FusionMode::Merge => Vec::new(),
+            FusionMode::ConstrainNeural => self.heuristic.evidence(input),
+        };
+        let neural_result = self.neural.as_ref().map(|neural| {
+            self.notify(|observer| observer.on_engine_selected(input, neural.name()));
+            match &self.trace {
+                Some(trace) => neural.parse_tagged(input, &evidence).map(|(result, tags)| {
+                    trace.record_model_tags(tags);
+                    result
+                }),
+                None => neural.parse_with_evidence(input, &evidence),
+            }
+        });
+        match (neural_result, &self.char_cnn) {
+            (Some(Ok(result)), _) => Ok(result),
+            (Some(Err(ZantetsuError::EmptyInput)), _) => Err(ZantetsuError::EmptyInput),
+            (neural_result, Some(char_cnn)) => {
+                if neural_result.is_some() {
+                    self.notify(|observer| {
+                        observer.on_fallback(input, "neural", FallbackReason::Error)
+                    });
+                }
+                self.notify(|observer| observer.on_engine_selected(input, char_cnn.name()));
+                char_cnn.parse_with_evidence(input, &evidence)
+            }
+            (Some(Err(err)), None) => Err(err),
+            (None, None) => Err(ZantetsuError::NeuralParser("no model is loaded".into())),
+        }
+        .map(sanitize_result)
+    }
+
+    /// Check if a model (DistilBERT or the character CNN) is loaded, i.e.
+    /// whether [`ParseMode::Full`] runs a model rather than the heuristic
+    /// fallback.
+    pub fn has_model(&self) -> bool {
+        let loaded = self.neural.is_some() || self.char_cnn.is_some();
+        #[cfg(test)]
+        let loaded = loaded || self.model_stub.is_some();
+        loaded
+    }
+
+    /// First confident result from the user engines, if any.
+    fn parse_user_engines(&self, input: &str) -> Option<ParseResult> {
+        self.engines.iter().find_map(|engine| {
+            self.notify(|observer| observer.on_engine_selected(input, engine.name()));
+            let reason = match engine.parse(input) {
+                Ok(result) if result.confidence >= self.config.confidence_threshold => {
+                    return Some(sanitize_result(result));
+                }
+                Ok(_) => FallbackReason::LowConfidence,
+                Err(err) => {
+                    tracing::debug!(engine = engine.name(), %err, "user engine failed");
+                    FallbackReason::Error
+                }
+            };
+            self.notify(|observer| observer.on_fallback(input, engine.name(), reason));
+            None
+        })
+    }
+
     /// Parse using the heuristic regex parser (ParseMode::Light).
     fn parse_light(&self, input: &str) -> Result<ParseResult> {
+        self.notify(|observer| observer.on_engine_selected(input, self.heuristic.name()));
         self.heuristic.parse(input).map(sanitize_result)
     }
 
-    /// Parse with automatic mode selection.
-    ///
-    /// Strategy:
-    /// 1. Try neural parser first
-    /// 2. If neural parser confidence is below threshold, try heuristic
-    /// 3. Return the result with higher confidence
+    /// Parse with automatic mode selection, following
+    /// [`ParserConfig::selection`].
     fn parse_auto(&self, input: &str) -> Result<ParseResult> {
-        let mut heuristic_result = sanitize_result(self.heuristic.parse(input)?);
-        heuristic_result.parse_mode = ParseMode::Auto;
+        let started = Instant::now();
+        if !self.has_model() {
+            return self.parse_auto_heuristic(input);
+        }
 
-        let Some(neural) = self.neural.as_ref() else {
-            return Ok(heuristic_result);
-        };
+        match self.config.selection {
+            SelectionStrategy::HeuristicFirst => self.select_heuristic_first(input, started),
+            SelectionStrategy::NeuralFirst => self.select_neural_first(input, started),
+            SelectionStrategy::CheapestAboveThreshold => {
+                self.select_cheapest_above_threshold(input, started)
+            }
+            SelectionStrategy::RunBothPickBest => self.select_best_of_both(input, started),
+        }
+    }
 
+    /// [`SelectionStrategy::HeuristicFirst`].
+    fn select_heuristic_first(&self, input: &str, started: Instant) -> Result<ParseResult> {
+        let mut heuristic_result = self.parse_auto_heuristic(input)?;
         if heuristic_result.confidence >= self.config.confidence_threshold
             && is_heuristic_complete(&heuristic_result)
         {
             return Ok(heuristic_result);
         }
 
-        match neural.parse(input).map(sanitize_result) {
-            Ok(neural_result) => {
+        match self.parse_auto_model(input, started)? {
+            ModelOutcome::Parsed(neural_result) => {
                 if neural_result.confidence > 0.90
                     && neural_result.confidence > heuristic_result.confidence + 0.20
                     && is_usable_text(&neural_result.title)
                 {
-                    return Ok(neural_result);
+                    return Ok(self.accept_model_result(neural_result, &heuristic_result));
                 }
 
+                self.notify_low_confidence(input);
+                Ok(fuse_results(
+                    heuristic_result,
+                    &neural_result,
+                    self.config.confidence_threshold,
+                ))
+            }
+            ModelOutcome::Failed => Ok(heuristic_result),
+            ModelOutcome::OverBudget => {
+                heuristic_result.budget_exhausted = true;
+                Ok(heuristic_result)
+            }
+        }
+    }
+
+    /// [`SelectionStrategy::NeuralFirst`].
+    fn select_neural_first(&self, input: &str, started: Instant) -> Result<ParseResult> {
+        let outcome = self.parse_auto_model(input, started)?;
+        if let ModelOutcome::Parsed(neural_result) = &outcome
+            && neural_result.confidence >= self.config.confidence_threshold
+            && is_usable_text(&neural_result.title)
+        {
+            let neural_result = neural_result.clone();
+            if !self.config.backfill_missing_fields {
+                return Ok(neural_result);
+            }
+            let heuristic_result = self.parse_auto_heuristic(input)?;
+            return Ok(backfill_fields(neural_result, &heuristic_result));
+        }
+
+        let mut heuristic_result = self.parse_auto_heuristic(input)?;
+        match outcome {
+            ModelOutcome::Parsed(neural_result) => {
+                self.notify_low_confidence(input);
                 Ok(fuse_results(
                     heuristic_result,
                     &neural_result,
                     self.config.confidence_threshold,
                 ))
             }
-            Err(ZantetsuError::EmptyInput) => Err(ZantetsuError::EmptyInput),
-            Err(_) => Ok(heuristic_result),
+            ModelOutcome::Failed => Ok(heuristic_result),
+            ModelOutcome::OverBudget => {
+                heuristic_result.budget_exhausted = true;
+                Ok(heuristic_result)
+            }
+        }
+    }
+
+    /// [`SelectionStrategy::CheapestAboveThreshold`].
+    fn select_cheapest_above_threshold(
+        &self,
+        input: &str,
+        started: Instant,
+    ) -> Result<ParseResult> {
+        let mut heuristic_result = self.parse_auto_heuristic(input)?;
+        if heuristic_result.confidence >= self.config.confidence_threshold {
+            return Ok(heuristic_result);
+        }
+
+        match self.parse_auto_model(input, started)? {
+            ModelOutcome::Parsed(neural_result)
+                if neural_result.confidence >= self.config.confidence_threshold
+                    || neural_result.confidence > heuristic_result.confidence =>
+            {
+                Ok(self.accept_model_result(neural_result, &heuristic_result))
+            }
+            ModelOutcome::Parsed(_) => {
+                self.notify_low_confidence(input);
+                Ok(heuristic_result)
+            }
+            ModelOutcome::Failed => Ok(heuristic_result),
+            ModelOutcome::OverBudget => {
+                heuristic_result.budget_exhausted = true;
+                Ok(heuristic_result)
+            }
+        }
+    }
+
+    /// [`SelectionStrategy::RunBothPickBest`].
+    fn select_best_of_both(&self, input: &str, started: Instant) -> Result<ParseResult> {
+        let mut heuristic_result = self.parse_auto_heuristic(input)?;
+
+        match self.parse_auto_model(input, started)? {
+            ModelOutcome::Parsed(neural_result)
+                if neural_result.confidence > heuristic_result.confidence
+                    && is_usable_text(&neural_result.title) =>
+            {
+                Ok(self.accept_model_result(neural_result, &heuristic_result))
+            }
+            ModelOutcome::Parsed(_) => {
+                self.notify_low_confidence(input);
+                Ok(heuristic_result)
+            }
+            ModelOutcome::Failed => Ok(heuristic_result),
+            ModelOutcome::OverBudget => {
+                heuristic_result.budget_exhausted = true;
+                Ok(heuristic_result)
+            }
+        }
+    }
+
+    /// The heuristic result, marked as an auto-mode result.
+    fn parse_auto_heuristic(&self, input: &str) -> Result<ParseResult> {
+        let mut result = self.parse_light(input)?;
+        result.parse_mode = ParseMode::Auto;
+        Ok(result)
+    }
+
+    /// Run the model tier for auto mode, reporting failures to observers.
+    /// Only [`ZantetsuError::EmptyInput`] is returned as an error.
+    fn parse_auto_model(&self, input: &str, started: Instant) -> Result<ModelOutcome> {
+        match self.parse_model_within_budget(input, started) {
+            Some(Ok(result)) => Ok(ModelOutcome::Parsed(result)),
+            Some(Err(ZantetsuError::EmptyInput)) => Err(ZantetsuError::EmptyInput),
+            Some(Err(_)) => {
+                self.notify(|observer| observer.on_fallback(input, "model", FallbackReason::Error));
+                Ok(ModelOutcome::Failed)
+            }
+            None => Ok(ModelOutcome::OverBudget),
+        }
+    }
+
+    /// Report a model result passed over for its confidence.
+    fn notify_low_confidence(&self, input: &str) {
+        self.notify(|observer| observer.on_fallback(input, "model", FallbackReason::LowConfidence));
+    }
+
+    /// A model result chosen over the heuristic one, backfilled if enabled.
+    fn accept_model_result(&self, neural: ParseResult, heuristic: &ParseResult) -> ParseResult {
+        if self.config.backfill_missing_fields {
+            backfill_fields(neural, heuristic)
+        } else {
+            neural
+        }
+    }
+
+    /// Run the heuristic parser and the model, then merge field by field
+    /// (ParseMode::Ensemble). Without a usable model every field is the
+    /// heuristic's.
+    fn parse_ensemble(&self, input: &str) -> Result<ParseResult> {
+        let started = Instant::now();
+        let heuristic_result = self.parse_light(input)?;
+        let (neural_result, budget_exhausted) = match self.parse_model_within_budget(input, started)
+        {
+            Some(Ok(result)) => (result, false),
+            Some(Err(ZantetsuError::EmptyInput)) => return Err(ZantetsuError::EmptyInput),
+            Some(Err(_)) => {
+                self.notify(|observer| observer.on_fallback(input, "model", FallbackReason::Error));
+                (ParseResult::new(input, ParseMode::Full), false)
+            }
+            None => (ParseResult::new(input, ParseMode::Full), true),
+        };
+
+        let mut result = merge_fields(heuristic_result, neural_result);
+        result.budget_exhausted = budget_exhausted;
+        Ok(result)
+    }
+
+    /// [`parse_model`](Self::parse_model), unless the time budget (counted
+    /// from `started`) cannot fit it; `None` means the budget was hit.
+    fn parse_model_within_budget(
+        &self,
+        input: &str,
+        started: Instant,
+    ) -> Option<Result<ParseResult>> {
+        let Some(budget) = self.config.time_budget else {
+            return Some(self.parse_model(input));
+        };
+        let budget_hit = || {
+            self.notify(|observer| {
+                observer.on_fallback(input, "model", FallbackReason::TimeBudget)
+            });
+            None
+        };
+        if started.elapsed() + self.model_latency.get() > budget {
+            return budget_hit();
+        }
+
+        let model_started = Instant::now();
+        let result = self.parse_model(input);
+        self.model_latency.record(model_started.elapsed());
+        if started.elapsed() > budget {
+            return budget_hit();
+        }
+        Some(result)
+    }
+
+    /// Lazily parse every non-blank line read from `reader`.
+    ///
+    /// Each line yields its own `Result`, so a malformed line (or a read
+    /// error) does not abort the stream. Lines are trimmed before parsing; a
+    /// line that is not valid UTF-8 yields a [`ZantetsuError::Io`] of kind
+    /// [`InvalidData`](io::ErrorKind::InvalidData) and the stream goes on.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::Cursor;
+    /// use zantetsu_core::parser::{Parser, ParserConfig};
+    /// use zantetsu_core::types::ParseMode;
+    ///
+    /// let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light)).unwrap();
+    /// let input = Cursor::new("[SubsPlease] Frieren - 01 (1080p).mkv\n\n[Erai-raws] Spy x Family - 02.mkv\n");
+    ///
+    /// let results: Vec<_> = parser.parse_lines(input).collect();
+    /// assert_eq!(results.len(), 2);
+    /// ```
+    pub fn parse_lines<R: BufRead>(&self, reader: R) -> ParseLines<'_, R> {
+        ParseLines {
+            parser: self,
+            reader,
+            bytes: Vec::new(),
+            line: String::new(),
         }
     }
 
-    /// Check if the neural parser is available.
+    /// Check if the DistilBERT model is loaded.
     pub fn has_neural(&self) -> bool {
         self.neural.is_some()
     }
 
+    /// Check if the character-CNN model is loaded.
+    pub fn has_char_cnn(&self) -> bool {
+        self.char_cnn.is_some()
+    }
+
     /// Get the parser configuration.
     pub fn config(&self) -> &ParserConfig {
         &self.config
     }
 }
 
+/// Streaming iterator returned by [`Parser::parse_lines`].
+pub struct ParseLines<'a, R> {
+    parser: &'a Parser,
+    reader: R,
+    bytes: Vec<u8>,
+    line: String,
+}
+
+impl<R> ParseLines<'_, R> {
+    /// The trimmed line that produced the most recently yielded item, with
+    /// invalid UTF-8 replaced by `U+FFFD`.
+    ///
+    /// Useful for reporting which input failed when an item is an error.
+    pub fn current_line(&self) -> &str {
+        self.line.trim()
+    }
+}
+
+impl<R: BufRead> Iterator for ParseLines<'_, R> {
+    type Item = Result<ParseResult>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.bytes.clear();
+            match self.reader.read_until(b'\n', &mut self.bytes) {
+                Ok(0) => return None,
+                Ok(_) => {
+                    let valid = match std::str::from_utf8(&self.bytes) {
+                        Ok(line) => {
+                            self.line.clear();
+                            self.line.push_str(line);
+                            true
+                        }
+                        Err(_) => {
+                            self.line = String::from_utf8_lossy(&self.bytes).into_owned();
+                            false
+                        }
+                    };
+                    let line = self.line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if !valid {
+                        let e =
+                            io::Error::new(io::ErrorKind::InvalidData, "line is not valid UTF-8");
+                        return Some(Err(e.into()));
+                    }
+                    return Some(self.parser.parse(line));
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+/// Process-wide parser behind [`parse`] and [`parse_with_mode`].
+static DEFAULT_PARSER: RwLock<Option<Arc<Parser>>> = RwLock::new(None);
+
+/// Download `repo` (`owner/name[@revision]`) from the Hugging Face Hub.
+#[cfg(feature = "hub")]
+fn fetch_repo(repo: &str) -> Result<PathBuf> {
+    let (id, revision) = match repo.split_once('@') {
+        Some((id, revision)) => (id, Some(revision)),
+        None => (repo, None),
+    };
+    crate::parser::hub::fetch_model(id, revision)
+}
+
+#[cfg(not(feature = "hub"))]
+fn fetch_repo(repo: &str) -> Result<PathBuf> {
+    Err(ZantetsuError::ModelLoadError(format!(
+        "downloading {repo} needs the `hub` feature"
+    )))
+}
+
+/// The shared default-configuration parser, built on first use.
+///
+/// Later calls return the same instance, so models are loaded at most once
+/// per process (until [`reset_default_parser`]).
+pub fn default_parser() -> Result<Arc<Parser>> {
+    if let Some(parser) = DEFAULT_PARSER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+    {
+        return Ok(Arc::clone(parser));
+    }
+
+    let mut slot = DEFAULT_PARSER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    // Another thread may have built it while we waited for the write lock
+    if let Some(parser) = slot.as_ref() {
+        return Ok(Arc::clone(parser));
+    }
+    let parser = Arc::new(Parser::default()?);
+    *slot = Some(Arc::clone(&parser));
+    Ok(parser)
+}
+
+/// Drop the shared default parser; the next [`parse`] builds a fresh one.
+///
+/// Meant for tests that change the model files on disk between parses.
+pub fn reset_default_parser() {
+    *DEFAULT_PARSER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
 /// Convenience function to parse a filename with default settings.
+///
+/// Reuses the [`default_parser`] instead of loading models on every call.
 pub fn parse(input: &str) -> Result<ParseResult> {
-    let parser = Parser::default()?;
-    parser.parse(input)
+    default_parser()?.parse(input)
+}
+
+/// [`parse`] into the JSON of the result; see [`ParseResult::to_json`].
+pub fn parse_json(input: &str) -> Result<String> {
+    default_parser()?.parse_json(input)
 }
 
-/// Parse with a specific mode.
+/// Parse with a specific mode, reusing the [`default_parser`]'s engines.
 pub fn parse_with_mode(input: &str, mode: ParseMode) -> Result<ParseResult> {
-    let config = ParserConfig::new().with_mode(mode);
-    let parser = Parser::new(config)?;
+    let mut parser = Parser::clone(&*default_parser()?);
+    parser.config.mode = mode;
     parser.parse(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::EpisodeSpec;
+    use std::path::Path;
 
     #[test]
     fn test_parser_creation() {
@@ -392,16 +1639,463 @@ mod tests {
         assert!(parser.is_ok());
     }
 
+    #[test]
+    fn parser_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Parser>();
+    }
+
     #[test]
     fn test_parser_config() {
         let config = ParserConfig::new()
             .with_mode(ParseMode::Light)
             .with_confidence_threshold(0.7)
-            .with_neural(false);
+            .with_neural(false)
+            .with_model_dir("models/custom");
 
         assert_eq!(config.mode, ParseMode::Light);
         assert_eq!(config.confidence_threshold, 0.7);
         assert!(!config.enable_neural);
+        assert_eq!(
+            config.model_dir.as_deref(),
+            Some(Path::new("models/custom"))
+        );
+    }
+
+    #[test]
+    fn model_dir_takes_precedence_over_env_and_repo() {
+        let config = ParserConfig::new()
+            .with_model_repo("enrell/zantetsu-ner@main")
+            .with_model_dir("models/custom");
+        assert_eq!(
+            config.resolve_model_dir().unwrap().as_deref(),
+            Some(Path::new("models/custom"))
+        );
+
+        if std::env::var_os(MODEL_DIR_ENV).is_none() {
+            assert_eq!(ParserConfig::new().resolve_model_dir().unwrap(), None);
+            #[cfg(not(feature = "hub"))]
+            assert!(matches!(
+                ParserConfig::new()
+                    .with_model_repo("enrell/zantetsu-ner")
+                    .resolve_model_dir(),
+                Err(ZantetsuError::ModelLoadError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn constrained_fusion_still_parses() {
+        let config = ParserConfig::new().fusion(FusionMode::ConstrainNeural);
+        assert_eq!(config.fusion, FusionMode::ConstrainNeural);
+        assert_eq!(ParserConfig::new().fusion, FusionMode::Merge);
+
+        let parser = Parser::new(config).unwrap();
+        let result = parser
+            .parse("[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv")
+            .unwrap();
+        assert_eq!(result.group.as_deref(), Some("SubsPlease"));
+    }
+
+    #[test]
+    fn ensemble_merges_fields_with_provenance() {
+        let mut heuristic = ParseResult::new("x", ParseMode::Light);
+        heuristic.title = Some("1080p HEVC".into());
+        heuristic.crc32 = Some("A1B2C3D4".into());
+        heuristic.episode = Some(EpisodeSpec::Single(3));
+        heuristic.confidence = 0.5;
+
+        let mut neural = ParseResult::new("x", ParseMode::Full);
+        neural.title = Some("Jujutsu Kaisen".into());
+        neural.crc32 = Some("A1B2C3D5".into());
+        neural.episode = Some(EpisodeSpec::Single(24));
+        neural.resolution = Some(crate::types::Resolution::FHD1080);
+        neural.confidence = 0.8;
+        neural.field_confidence.set(ParseField::Episode, 0.9);
+
+        let merged = merge_fields(heuristic, neural);
+        assert_eq!(merged.parse_mode, ParseMode::Ensemble);
+        assert_eq!(merged.title.as_deref(), Some("Jujutsu Kaisen"));
+        assert_eq!(merged.crc32.as_deref(), Some("A1B2C3D4"));
+        assert_eq!(merged.episode, Some(EpisodeSpec::Single(24)));
+        assert!(merged.resolution.is_some());
+
+        let provenance = &merged.provenance;
+        assert_eq!(provenance.get(ParseField::Title), Some(FieldSource::Neural));
+        assert_eq!(
+            provenance.get(ParseField::Crc32),
+            Some(FieldSource::Heuristic)
+        );
+        assert_eq!(
+            provenance.get(ParseField::Episode),
+            Some(FieldSource::Neural)
+        );
+        assert_eq!(
+            provenance.get(ParseField::Resolution),
+            Some(FieldSource::Neural)
+        );
+        assert_eq!(provenance.get(ParseField::Group), None);
+        assert_eq!(merged.field_confidence.get(ParseField::Episode), Some(0.9));
+    }
+
+    #[test]
+    fn ensemble_mode_parses_without_a_model() {
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Ensemble)).unwrap();
+        let result = parser
+            .parse("[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv")
+            .unwrap();
+
+        assert_eq!(result.parse_mode, ParseMode::Ensemble);
+        assert_eq!(result.group.as_deref(), Some("SubsPlease"));
+        assert_eq!(result.crc32.as_deref(), Some("A1B2C3D4"));
+        assert!(result.provenance.get(ParseField::Crc32).is_some());
+    }
+
+    struct TrackerEngine;
+
+    impl ParseEngine for TrackerEngine {
+        fn name(&self) -> &str {
+            "tracker"
+        }
+
+        fn cost_hint(&self) -> crate::parser::EngineCost {
+            crate::parser::EngineCost::Cheap
+        }
+
+        fn parse(&self, input: &str) -> Result<ParseResult> {
+            let Some(title) = input.strip_prefix("TRK.") else {
+                return Err(ZantetsuError::ParseFailed {
+                    input: input.to_string(),
+                });
+            };
+            let mut result = ParseResult::new(input, ParseMode::Light);
+            result.title = Some(title.replace('.', " "));
+            result.confidence = 1.0;
+            Ok(result)
+        }
+    }
+
+    #[test]
+    fn user_engines_run_before_built_in_ones() {
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light))
+            .unwrap()
+            .with_engine(TrackerEngine);
+
+        let result = parser.parse("TRK.Sousou.no.Frieren").unwrap();
+        assert_eq!(result.title.as_deref(), Some("Sousou no Frieren"));
+
+        let result = parser
+            .parse("[SubsPlease] Frieren - 01 (1080p).mkv")
+            .unwrap();
+        assert_eq!(result.group.as_deref(), Some("SubsPlease"));
+    }
+
+    #[test]
+    fn exhausted_time_budget_falls_back_to_heuristic() {
+        let parser = Parser::new(ParserConfig::new().time_budget(Duration::ZERO)).unwrap();
+        let result = parser.parse("Frieren 1080p").unwrap();
+        assert_eq!(result.budget_exhausted, parser.has_model());
+
+        let estimate = LatencyEstimate::default();
+        estimate.record(Duration::from_millis(8));
+        estimate.record(Duration::from_millis(16));
+        assert_eq!(estimate.get(), Duration::from_millis(9));
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl ParserObserver for RecordingObserver {
+        fn on_parse_start(&self, input: &str) {
+            self.events.lock().unwrap().push(format!("start {input}"));
+        }
+
+        fn on_engine_selected(&self, _input: &str, engine: &str) {
+            self.events.lock().unwrap().push(format!("engine {engine}"));
+        }
+
+        fn on_fallback(&self, _input: &str, engine: &str, reason: FallbackReason) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("fallback {engine} ({reason})"));
+        }
+
+        fn on_complete(
+            &self,
+            _input: &str,
+            outcome: std::result::Result<&ParseResult, &ZantetsuError>,
+            _elapsed: Duration,
+        ) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("complete ok={}", outcome.is_ok()));
+        }
+    }
+
+    #[test]
+    fn observers_see_engine_choices_and_fallbacks() {
+        let observer = Arc::new(RecordingObserver::default());
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light))
+            .unwrap()
+            .with_engine(TrackerEngine)
+            .with_observer(observer.clone());
+
+        parser.parse("[Group] Title - 01.mkv").unwrap();
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            [
+                "start [Group] Title - 01.mkv",
+                "engine tracker",
+                "fallback tracker (error)",
+                "engine heuristic",
+                "complete ok=true",
+            ]
+        );
+    }
+
+    /// A model tier that answers every input with one fixed result.
+    struct StubModel {
+        title: &'static str,
+        confidence: f32,
+    }
+
+    impl ParseEngine for StubModel {
+        fn name(&self) -> &str {
+            "stub"
+        }
+
+        fn cost_hint(&self) -> crate::parser::EngineCost {
+            crate::parser::EngineCost::Expensive
+        }
+
+        fn parse(&self, input: &str) -> Result<ParseResult> {
+            let mut result = ParseResult::new(input, ParseMode::Full);
+            result.title = Some(self.title.to_string());
+            result.confidence = self.confidence;
+            Ok(result)
+        }
+    }
+
+    impl Parser {
+        fn with_model_stub(mut self, model: StubModel) -> Self {
+            self.model_stub = Some(Arc::new(model));
+            self
+        }
+    }
+
+    const STRATEGIES: [SelectionStrategy; 4] = [
+        SelectionStrategy::HeuristicFirst,
+        SelectionStrategy::NeuralFirst,
+        SelectionStrategy::CheapestAboveThreshold,
+        SelectionStrategy::RunBothPickBest,
+    ];
+
+    #[test]
+    fn observers_see_rejected_model_results() {
+        for strategy in STRATEGIES {
+            let observer = Arc::new(RecordingObserver::default());
+            let parser = Parser::new(
+                ParserConfig::new()
+                    .with_selection_strategy(strategy)
+                    .with_confidence_threshold(0.99),
+            )
+            .unwrap()
+            .with_model_stub(StubModel {
+                title: "Stub",
+                confidence: 0.0,
+            })
+            .with_observer(observer.clone());
+
+            let result = parser.parse("[Group] Title - 01.mkv").unwrap();
+            assert_eq!(result.title.as_deref(), Some("Title"), "{strategy:?}");
+            let events = observer.events.lock().unwrap();
+            assert!(
+                events.contains(&"engine stub".to_string()),
+                "{strategy:?}: {events:?}"
+            );
+            assert!(
+                events.contains(&"fallback model (low confidence)".to_string()),
+                "{strategy:?}: {events:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn backfill_fills_only_missing_exact_fields() {
+        let mut neural = ParseResult::new("x", ParseMode::Full);
+        neural.title = Some("Frieren".into());
+        neural.extension = Some("mp4".into());
+
+        let mut heuristic = ParseResult::new("x", ParseMode::Light);
+        heuristic.title = Some("Frieren 1080p".into());
+        heuristic.crc32 = Some("A1B2C3D4".into());
+        heuristic.extension = Some("mkv".into());
+
+        let result = backfill_fields(neural, &heuristic);
+        assert_eq!(result.title.as_deref(), Some("Frieren"));
+        assert_eq!(result.crc32.as_deref(), Some("A1B2C3D4"));
+        assert_eq!(result.extension.as_deref(), Some("mp4"));
+        assert_eq!(
+            result.provenance.get(ParseField::Crc32),
+            Some(FieldSource::Heuristic)
+        );
+        assert_eq!(result.provenance.get(ParseField::Extension), None);
+        assert!(
+            ParserConfig::new()
+                .with_field_backfill(true)
+                .backfill_missing_fields
+        );
+    }
+
+    #[test]
+    fn default_parser_is_reused_until_reset() {
+        let first = default_parser().unwrap();
+        assert!(Arc::ptr_eq(&first, &default_parser().unwrap()));
+
+        let result = parse_with_mode("[SubsPlease] Frieren - 01 (1080p).mkv", ParseMode::Light);
+        assert_eq!(result.unwrap().parse_mode, ParseMode::Light);
+
+        reset_default_parser();
+        assert!(!Arc::ptr_eq(&first, &default_parser().unwrap()));
+        assert!(parse("[SubsPlease] Frieren - 01 (1080p).mkv").is_ok());
+    }
+
+    #[test]
+    fn result_cache_skips_engines_on_repeat_inputs() {
+        let observer = Arc::new(RecordingObserver::default());
+        let parser = Parser::new(
+            ParserConfig::new()
+                .with_mode(ParseMode::Light)
+                .with_cache_capacity(8),
+        )
+        .unwrap()
+        .with_observer(observer.clone());
+
+        let first = parser.parse("[Group] Title - 01.mkv").unwrap();
+        let second = parser.parse("[Group] Title - 01.mkv").unwrap();
+        assert_eq!(first, second);
+
+        let engine_runs = observer
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.starts_with("engine"))
+            .count();
+        assert_eq!(engine_runs, 1);
+    }
+
+    #[test]
+    fn every_selection_strategy_parses_without_a_model() {
+        for strategy in STRATEGIES {
+            let config = ParserConfig::new().with_selection_strategy(strategy);
+            assert_eq!(config.selection, strategy);
+            let result = Parser::new(config)
+                .unwrap()
+                .parse("[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv")
+                .unwrap();
+            assert_eq!(result.group.as_deref(), Some("SubsPlease"), "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn selection_strategies_pick_by_confidence() {
+        // The heuristic scores the first input 0.625 with title, group and
+        // episode, and the second 0.375 without a group.
+        let complete = "[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv";
+        let partial = "Frieren - 05.mkv";
+        let cases = [
+            (
+                SelectionStrategy::HeuristicFirst,
+                complete,
+                0.95,
+                "Jujutsu Kaisen",
+            ),
+            (SelectionStrategy::HeuristicFirst, partial, 0.95, "Stub"),
+            (SelectionStrategy::HeuristicFirst, partial, 0.5, "Frieren"),
+            (SelectionStrategy::NeuralFirst, complete, 0.7, "Stub"),
+            (
+                SelectionStrategy::NeuralFirst,
+                complete,
+                0.4,
+                "Jujutsu Kaisen",
+            ),
+            (
+                SelectionStrategy::CheapestAboveThreshold,
+                complete,
+                0.95,
+                "Jujutsu Kaisen",
+            ),
+            (
+                SelectionStrategy::CheapestAboveThreshold,
+                partial,
+                0.5,
+                "Stub",
+            ),
+            (
+                SelectionStrategy::CheapestAboveThreshold,
+                partial,
+                0.3,
+                "Frieren",
+            ),
+            (SelectionStrategy::RunBothPickBest, complete, 0.7, "Stub"),
+            (
+                SelectionStrategy::RunBothPickBest,
+                complete,
+                0.6,
+                "Jujutsu Kaisen",
+            ),
+        ];
+
+        for (strategy, input, confidence, title) in cases {
+            let result = Parser::new(ParserConfig::new().with_selection_strategy(strategy))
+                .unwrap()
+                .with_model_stub(StubModel {
+                    title: "Stub",
+                    confidence,
+                })
+                .parse(input)
+                .unwrap();
+            assert_eq!(
+                result.title.as_deref(),
+                Some(title),
+                "{strategy:?} on {input} with the model at {confidence}"
+            );
+        }
+    }
+
+    #[test]
+    fn traced_parse_records_regexes_and_engines() {
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light))
+            .unwrap()
+            .with_engine(TrackerEngine);
+        let input = "[SubsPlease] Frieren - 01 (1080p) [A1B2C3D4].mkv";
+        let (result, trace) = parser.parse_traced(input).unwrap();
+
+        assert_eq!(result, parser.parse(input).unwrap());
+        assert_eq!(trace.engines, ["tracker", "heuristic"]);
+        assert_eq!(
+            trace.fallbacks,
+            [("tracker".to_string(), FallbackReason::Error)]
+        );
+        assert!(trace.model_tags.is_none());
+        assert!(
+            trace
+                .tokens
+                .iter()
+                .any(|token| token.text.eq_ignore_ascii_case("frieren"))
+        );
+
+        let crc = trace
+            .regex_matches
+            .iter()
+            .find(|m| m.pattern == "crc32")
+            .unwrap();
+        assert_eq!(&input[crc.start..crc.end], "[A1B2C3D4]");
     }
 
     #[test]
@@ -451,6 +2145,28 @@ mod tests {
         assert!(merged.confidence >= 0.62);
     }
 
+    #[test]
+    fn test_fusion_keeps_confidence_of_disputed_values() {
+        let mut heuristic = ParseResult::new("x", ParseMode::Light);
+        heuristic.title = Some("Frieren".into());
+        heuristic.episode = Some(EpisodeSpec::Single(3));
+        heuristic.field_confidence.set(ParseField::Episode, 0.4);
+        heuristic.confidence = 0.62;
+
+        let mut neural = ParseResult::new("x", ParseMode::Full);
+        neural.title = Some("Frieren".into());
+        neural.episode = Some(EpisodeSpec::Single(24));
+        neural.field_confidence.set(ParseField::Title, 0.95);
+        neural.field_confidence.set(ParseField::Episode, 0.9);
+        neural.confidence = 0.82;
+
+        let merged = fuse_results(heuristic, &neural, 0.6);
+
+        assert_eq!(merged.episode, Some(EpisodeSpec::Single(3)));
+        assert_eq!(merged.field_confidence.get(ParseField::Episode), Some(0.4));
+        assert_eq!(merged.field_confidence.get(ParseField::Title), Some(0.95));
+    }
+
     #[test]
     fn test_fusion_ignores_low_confidence_neural_fill() {
         let mut heuristic = ParseResult::new("x", ParseMode::Light);
@@ -526,6 +2242,150 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_strict_mode_rejects_missing_fields() {
+        let config = ParserConfig::new()
+            .with_mode(ParseMode::Light)
+            .strict([ParseField::Title, ParseField::Episode]);
+        let parser = Parser::new(config).unwrap();
+
+        let result = parser.parse("[SubsPlease] Jujutsu Kaisen - 24 (1080p).mkv");
+        assert!(result.is_ok());
+
+        let result = parser.parse("Some Random Title.mkv");
+        assert!(matches!(result, Err(ZantetsuError::ParseFailed { .. })));
+    }
+
+    #[test]
+    fn test_group_registry_annotates_trust() {
+        let registry = GroupRegistry::new().with_group("subsplease", 0.9);
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light))
+            .unwrap()
+            .with_group_registry(registry);
+
+        let result = parser
+            .parse("[SubsPlease] Frieren - 01 (1080p).mkv")
+            .unwrap();
+        assert_eq!(result.group_trust, Some(0.9));
+
+        let result = parser.parse("[Nobody] Frieren - 01 (1080p).mkv").unwrap();
+        assert_eq!(result.group_trust, None);
+    }
+
+    #[test]
+    fn test_group_blocklist_adds_warning() {
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light))
+            .unwrap()
+            .with_group_blocklist(GroupBlocklist::new().with_group("Shady", 0.0));
+
+        let result = parser.parse("[Shady] Frieren - 01 (1080p).mkv").unwrap();
+        assert_eq!(
+            result.warnings,
+            [ParseWarning::BlocklistedGroup {
+                group: "Shady".into(),
+                penalty: 0.0,
+            }]
+        );
+        assert_eq!(result.warnings[0].to_string(), "group Shady is blocklisted");
+
+        let result = parser
+            .parse("[SubsPlease] Frieren - 01 (1080p).mkv")
+            .unwrap();
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_keep_title_year() {
+        let input = "[SubsPlease] Hunter x Hunter (2011) - 01 (1080p).mkv";
+
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light)).unwrap();
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result.title.as_deref(), Some("Hunter x Hunter"));
+
+        let config = ParserConfig::new()
+            .with_mode(ParseMode::Light)
+            .with_title_year(true);
+        let parser = Parser::new(config).unwrap();
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result.title.as_deref(), Some("Hunter x Hunter (2011)"));
+        assert_eq!(result.year, Some(2011));
+        assert_eq!(result.episode, Some(EpisodeSpec::Single(1)));
+
+        // A year that isn't adjacent to the title stays out of it
+        let result = parser
+            .parse("[Group] Hunter x Hunter - 01 [BD 1080p] (2011).mkv")
+            .unwrap();
+        assert_eq!(result.title.as_deref(), Some("Hunter x Hunter"));
+    }
+
+    #[test]
+    fn test_title_casing() {
+        assert_eq!(TitleCasing::TitleCase.apply("SPY x fAMILY"), "Spy X Family");
+        assert_eq!(TitleCasing::Lowercase.apply("Spy x Family"), "spy x family");
+        assert_eq!(TitleCasing::Preserve.apply("Spy x Family"), "Spy x Family");
+
+        let config = ParserConfig::new()
+            .with_mode(ParseMode::Light)
+            .with_title_casing(TitleCasing::Lowercase);
+        let parser = Parser::new(config).unwrap();
+        let result = parser
+            .parse("[SubsPlease] Jujutsu Kaisen - 24 (1080p).mkv")
+            .unwrap();
+        assert_eq!(result.title.as_deref(), Some("jujutsu kaisen"));
+    }
+
+    #[test]
+    fn test_episode_numbering_hint() {
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light)).unwrap();
+
+        let result = parser
+            .parse("[Erai-raws] One Piece - 1084 [1080p].mkv")
+            .unwrap();
+        assert_eq!(result.episode_numbering, EpisodeNumbering::Absolute);
+
+        let result = parser
+            .parse("[Group] Spy x Family S02E05 [1080p].mkv")
+            .unwrap();
+        assert_eq!(result.episode_numbering, EpisodeNumbering::Seasonal);
+    }
+
+    #[test]
+    fn test_parse_lines_recovers_per_line() {
+        let config = ParserConfig::new()
+            .with_mode(ParseMode::Light)
+            .strict([ParseField::Episode]);
+        let parser = Parser::new(config).unwrap();
+        let input = std::io::Cursor::new(
+            "[SubsPlease] Frieren - 01 (1080p).mkv\r\n\nSome Random Title.mkv\n[Erai-raws] Spy x Family - 02.mkv",
+        );
+
+        let mut lines = parser.parse_lines(input);
+        assert!(lines.next().unwrap().is_ok());
+        assert!(lines.next().unwrap().is_err());
+        assert_eq!(lines.current_line(), "Some Random Title.mkv");
+        let last = lines.next().unwrap().unwrap();
+        assert_eq!(last.group.as_deref(), Some("Erai-raws"));
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_parse_lines_skips_invalid_utf8() {
+        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Light)).unwrap();
+        let input = std::io::Cursor::new(
+            b"[Group] Bad \xff Name - 01.mkv\n[Erai-raws] Spy x Family - 02.mkv\n".to_vec(),
+        );
+
+        let mut lines = parser.parse_lines(input);
+        match lines.next().unwrap() {
+            Err(ZantetsuError::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            other => panic!("expected an invalid-data error, got {other:?}"),
+        }
+        assert_eq!(lines.current_line(), "[Group] Bad \u{fffd} Name - 01.mkv");
+        let next = lines.next().unwrap().unwrap();
+        assert_eq!(next.group.as_deref(), Some("Erai-raws"));
+        assert!(lines.next().is_none());
+    }
+
     #[test]
     fn test_confidence_threshold_clamping() {
         let config = ParserConfig::new().with_confidence_threshold(1.5);
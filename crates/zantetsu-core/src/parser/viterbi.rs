@@ -226,15 +226,15 @@ mod tests {
         let mut matrix = vec![vec![0.0f32; num_tags]; num_tags];
 
         // Set some reasonable transition scores
-        for i in 0..num_tags {
-            for j in 0..num_tags {
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
                 if BioTag::is_valid_transition(
                     BioTag::from_index(i).unwrap(),
                     BioTag::from_index(j).unwrap(),
                 ) {
-                    matrix[i][j] = 0.1;
+                    *cell = 0.1;
                 } else {
-                    matrix[i][j] = -1000.0; // Strong penalty for invalid
+                    *cell = -1000.0; // Strong penalty for invalid
                 }
             }
         }
@@ -248,14 +248,9 @@ mod tests {
         let transition = create_simple_transition_matrix(BioTag::NUM_TAGS);
 
         // Simple emission scores: favor tag 0 at position 0, tag 1 at position 1
-        let emissions = vec![
-            vec![
-                1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            ],
-            vec![
-                0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            ],
-        ];
+        let mut emissions = vec![vec![0.0; BioTag::NUM_TAGS]; 2];
+        emissions[0][0] = 1.0;
+        emissions[1][2] = 1.0;
 
         let result = decoder.decode(&emissions, &transition).unwrap();
         assert_eq!(result.len(), 2);
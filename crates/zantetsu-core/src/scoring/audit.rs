@@ -0,0 +1,158 @@
+use std::sync::{Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::VideoCodec;
+
+use super::context::ClientContext;
+use super::profile::{QualityProfile, QualityScores};
+use super::report::ScoreReport;
+
+/// One [`ClientContext::adjust_score`] decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Caller-supplied label, typically the release name.
+    pub label: Option<String>,
+    /// Scores before context adjustment.
+    pub input: QualityScores,
+    /// Video codec passed for the hardware-decode check.
+    pub file_video_codec: Option<VideoCodec>,
+    /// Multipliers applied and the resulting final score.
+    pub report: ScoreReport,
+}
+
+/// Opt-in collector of scoring decisions, for offline analysis of how a
+/// profile and context rank a library.
+///
+/// Score through [`adjust_score`](Self::adjust_score) instead of
+/// [`ClientContext::adjust_score`] to record each decision. The collector
+/// is thread-safe; share it behind an `Arc` to audit parallel scoring.
+///
+/// # Examples
+/// ```
+/// use zantetsu_core::scoring::{ClientContext, QualityProfile, QualityScores, ScoringAudit};
+/// use zantetsu_core::{Resolution, VideoCodec};
+///
+/// let audit = ScoringAudit::new();
+/// let scores = QualityScores::from_metadata(
+///     Some(Resolution::FHD1080),
+///     Some(VideoCodec::AV1),
+///     None,
+///     None,
+///     0.5,
+/// );
+/// audit.adjust_score(
+///     Some("[Group] Show - 01 [1080p AV1].mkv"),
+///     &ClientContext::default(),
+///     &QualityProfile::default(),
+///     scores,
+///     Some(VideoCodec::AV1),
+/// );
+///
+/// let log = serde_json::to_string(&audit.entries()).unwrap();
+/// assert!(log.contains("HwDecode"));
+/// ```
+#[derive(Debug, Default)]
+pub struct ScoringAudit {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl ScoringAudit {
+    /// Creates an empty audit log.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adjusts `scores` like [`ClientContext::adjust_score`], recording the
+    /// decision and its final score under `profile`.
+    pub fn adjust_score(
+        &self,
+        label: Option<&str>,
+        context: &ClientContext,
+        profile: &QualityProfile,
+        scores: QualityScores,
+        file_video_codec: Option<VideoCodec>,
+    ) -> QualityScores {
+        let entry = AuditEntry {
+            label: label.map(str::to_owned),
+            report: scores.explain(profile, context, file_video_codec),
+            input: scores.clone(),
+            file_video_codec,
+        };
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(entry);
+        context.adjust_score(scores, file_video_codec)
+    }
+
+    /// A snapshot of the recorded decisions, oldest first.
+    #[must_use]
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Removes and returns the recorded decisions, oldest first.
+    pub fn take(&self) -> Vec<AuditEntry> {
+        std::mem::take(&mut *self.entries.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Number of recorded decisions.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns `true` if nothing has been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::{DeviceType, MultiplierKind};
+    use crate::types::Resolution;
+
+    #[test]
+    fn audit_records_each_decision() {
+        let audit = ScoringAudit::new();
+        let context = ClientContext {
+            device_type: DeviceType::Mobile,
+            ..ClientContext::default()
+        };
+        let profile = QualityProfile::default();
+        let scores = QualityScores::from_metadata(Some(Resolution::UHD2160), None, None, None, 0.5);
+
+        let adjusted = audit.adjust_score(Some("a"), &context, &profile, scores.clone(), None);
+        audit.adjust_score(None, &ClientContext::default(), &profile, scores, None);
+        assert_eq!(audit.len(), 2);
+
+        let entries = audit.take();
+        assert!(audit.is_empty());
+        assert_eq!(entries[0].label.as_deref(), Some("a"));
+        assert_eq!(
+            entries[0].input.resolution,
+            Some(Resolution::UHD2160.score())
+        );
+        assert!((entries[0].report.total - adjusted.compute(&profile)).abs() < 1e-6);
+        assert_eq!(
+            entries[0].report.dimensions[0].multipliers[0].kind,
+            MultiplierKind::Device
+        );
+        assert!(entries[1].report.dimensions[0].multipliers.is_empty());
+
+        let json = serde_json::to_string(&entries).unwrap();
+        let back: Vec<AuditEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back[1].report, entries[1].report);
+    }
+}
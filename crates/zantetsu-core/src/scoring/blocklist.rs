@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::ParseResult;
+
+/// Score factor for groups on the curated list.
+const CURATED_PENALTY: f32 = 0.3;
+
+/// Re-encode groups widely considered low quality (over-compressed mini
+/// encodes, upscales, re-encodes of other groups' releases).
+const CURATED_GROUPS: &[&str] = &[
+    "AnimeRG",
+    "Anime Time",
+    "ASW",
+    "HollowRips",
+    "Judas",
+    "NemDiggers",
+    "Tenrai-Sensei",
+];
+
+/// Penalties for known low-quality re-encode groups.
+///
+/// Each entry is a factor in `[0.0, 1.0]` the quality score is multiplied
+/// by; `0.0` blocks the group outright. Lookups are case-insensitive.
+/// Start from [`curated`](Self::curated) and override entries with
+/// [`with_group`](Self::with_group) and [`without_group`](Self::without_group).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupBlocklist {
+    penalties: HashMap<String, f32>,
+}
+
+impl GroupBlocklist {
+    /// Creates an empty blocklist.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The curated default list shipped with the crate.
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::GroupBlocklist;
+    ///
+    /// let blocklist = GroupBlocklist::curated()
+    ///     .with_group("MyShadyGroup", 0.0)
+    ///     .without_group("Judas");
+    /// assert_eq!(blocklist.penalty("myshadygroup"), Some(0.0));
+    /// assert_eq!(blocklist.penalty("Judas"), None);
+    /// ```
+    #[must_use]
+    pub fn curated() -> Self {
+        CURATED_GROUPS
+            .iter()
+            .map(|&name| (name, CURATED_PENALTY))
+            .collect()
+    }
+
+    /// Adds or replaces a group's penalty, returning the blocklist.
+    #[must_use]
+    pub fn with_group(mut self, name: &str, penalty: f32) -> Self {
+        self.insert(name, penalty);
+        self
+    }
+
+    /// Removes a group, returning the blocklist.
+    #[must_use]
+    pub fn without_group(mut self, name: &str) -> Self {
+        self.remove(name);
+        self
+    }
+
+    /// Adds or replaces a group's penalty.
+    pub fn insert(&mut self, name: &str, penalty: f32) {
+        self.penalties
+            .insert(name.trim().to_lowercase(), penalty.clamp(0.0, 1.0));
+    }
+
+    /// Removes a group, returning its penalty if it was listed.
+    pub fn remove(&mut self, name: &str) -> Option<f32> {
+        self.penalties.remove(&name.trim().to_lowercase())
+    }
+
+    /// Returns the penalty for `name`, if the group is listed.
+    #[must_use]
+    pub fn penalty(&self, name: &str) -> Option<f32> {
+        self.penalties.get(&name.trim().to_lowercase()).copied()
+    }
+
+    /// Multiplies a computed quality `score` for `result` by its group's
+    /// penalty, if the group is listed.
+    #[must_use]
+    pub fn apply(&self, result: &ParseResult, score: f32) -> f32 {
+        result
+            .group
+            .as_deref()
+            .and_then(|group| self.penalty(group))
+            .map_or(score, |penalty| score * penalty)
+    }
+
+    /// Number of listed groups.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.penalties.len()
+    }
+
+    /// Returns `true` if no groups are listed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.penalties.is_empty()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, f32)> for GroupBlocklist {
+    fn from_iter<I: IntoIterator<Item = (S, f32)>>(iter: I) -> Self {
+        let mut blocklist = Self::new();
+        for (name, penalty) in iter {
+            blocklist.insert(name.as_ref(), penalty);
+        }
+        blocklist
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ParseMode;
+
+    #[test]
+    fn curated_list_penalizes_and_overrides() {
+        let blocklist = GroupBlocklist::curated();
+        assert_eq!(blocklist.len(), CURATED_GROUPS.len());
+        assert_eq!(blocklist.penalty("animerg"), Some(CURATED_PENALTY));
+
+        let mut result = ParseResult::new("x", ParseMode::Light);
+        result.group = Some("AnimeRG".into());
+        assert!((blocklist.apply(&result, 0.8) - 0.8 * CURATED_PENALTY).abs() < 1e-6);
+
+        let blocklist = blocklist.with_group("AnimeRG", 0.0);
+        assert_eq!(blocklist.apply(&result, 0.8), 0.0);
+
+        let blocklist = blocklist.without_group("AnimeRG");
+        assert_eq!(blocklist.apply(&result, 0.8), 0.8);
+        result.group = None;
+        assert_eq!(GroupBlocklist::curated().apply(&result, 0.8), 0.8);
+    }
+}
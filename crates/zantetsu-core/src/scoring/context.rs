@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::types::VideoCodec;
+use crate::error::{ConfigError, Result};
+use crate::types::{Resolution, VideoCodec};
 
 use super::profile::QualityScores;
+use super::report::{AppliedMultiplier, MultiplierKind, ScoreDimension};
 
 /// Device type affects resolution preference.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -32,8 +34,50 @@ pub enum NetworkQuality {
     Offline,
 }
 
+/// Battery state affects how much decoding work is worth spending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BatteryState {
+    /// Plugged in — no penalty.
+    Charging,
+    /// On battery — slight penalty for 4K.
+    Discharging,
+    /// Battery low — strong penalty above 720p.
+    Low,
+}
+
+/// CPU class, for judging what can be decoded in software.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CpuTier {
+    /// Phones, set-top boxes and old netbooks.
+    Low,
+    /// Typical laptops.
+    Mid,
+    /// Recent desktop CPUs.
+    High,
+}
+
+impl CpuTier {
+    /// The highest resolution of `codec` this tier decodes smoothly in
+    /// software.
+    #[must_use]
+    pub fn max_software_resolution(self, codec: VideoCodec) -> Resolution {
+        match (codec, self) {
+            (VideoCodec::AV1, Self::Low) => Resolution::SD480,
+            (VideoCodec::AV1 | VideoCodec::HEVC | VideoCodec::VP9, Self::Mid) => {
+                Resolution::FHD1080
+            }
+            (VideoCodec::HEVC | VideoCodec::VP9, Self::Low) => Resolution::HD720,
+            (VideoCodec::H264 | VideoCodec::MPEG4, Self::Low) => Resolution::FHD1080,
+            _ => Resolution::UHD2160,
+        }
+    }
+}
+
 /// Client context for dynamic score adjustment.
+///
+/// Fields left out of a config file keep their defaults.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ClientContext {
     /// Device type affects resolution preference.
     pub device_type: DeviceType,
@@ -41,6 +85,20 @@ pub struct ClientContext {
     pub network: NetworkQuality,
     /// Hardware-supported video codecs on the client.
     pub hw_decode_codecs: Vec<VideoCodec>,
+    /// CPU class for software decoding of codecs missing from
+    /// `hw_decode_codecs`. Without it, software decoding is assumed to be
+    /// impractical.
+    pub cpu_tier: Option<CpuTier>,
+    /// Whether the display can show HDR; HDR video is tone-mapped (and
+    /// penalized) otherwise.
+    pub hdr_capable: bool,
+    /// Battery state, for battery-powered devices.
+    pub battery: Option<BatteryState>,
+    /// Native screen resolution; pixels beyond it are wasted.
+    pub screen_resolution: Option<Resolution>,
+    /// Free storage in bytes; files that would not fit comfortably are
+    /// penalized (see [`QualityScores::with_file_size`]).
+    pub storage_budget: Option<u64>,
 }
 
 impl Default for ClientContext {
@@ -49,11 +107,94 @@ impl Default for ClientContext {
             device_type: DeviceType::Desktop,
             network: NetworkQuality::Unlimited,
             hw_decode_codecs: vec![VideoCodec::H264, VideoCodec::HEVC],
+            cpu_tier: None,
+            hdr_capable: false,
+            battery: None,
+            screen_resolution: None,
+            storage_budget: None,
         }
     }
 }
 
 impl ClientContext {
+    /// Loads a context from a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::InvalidContext` if the document is malformed
+    /// or names an unknown device, network or codec.
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::{ClientContext, DeviceType};
+    ///
+    /// let context = ClientContext::from_toml(
+    ///     "device_type = \"Mobile\"\nhw_decode_codecs = [\"H264\"]",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(context.device_type, DeviceType::Mobile);
+    /// ```
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let context: Self = toml::from_str(toml).map_err(ConfigError::from)?;
+        context.validate()?;
+        Ok(context)
+    }
+
+    /// Starts a validated context from the defaults.
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::{BatteryState, ClientContext, DeviceType};
+    /// use zantetsu_core::VideoCodec;
+    ///
+    /// let context = ClientContext::builder()
+    ///     .with_device_type(DeviceType::Mobile)
+    ///     .with_hw_decode_codecs([VideoCodec::H264])
+    ///     .with_battery(BatteryState::Discharging)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(context.device_type, DeviceType::Mobile);
+    ///
+    /// // A desktop has no battery
+    /// assert!(
+    ///     ClientContext::builder()
+    ///         .with_battery(BatteryState::Low)
+    ///         .build()
+    ///         .is_err()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn builder() -> ClientContextBuilder {
+        ClientContextBuilder::default()
+    }
+
+    /// Checks that the settings are usable and consistent with each other:
+    /// at least one hardware-decodable codec, no battery on desktops or
+    /// TVs, and no offline use on embedded streaming devices.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::InvalidContext` naming the first problem.
+    pub fn validate(&self) -> Result<()> {
+        let problem = if self.hw_decode_codecs.is_empty() {
+            Some("hw_decode_codecs is empty; list at least one codec".to_string())
+        } else if self.battery.is_some()
+            && matches!(self.device_type, DeviceType::Desktop | DeviceType::TV)
+        {
+            Some(format!("a {:?} has no battery", self.device_type))
+        } else if self.network == NetworkQuality::Offline
+            && self.device_type == DeviceType::Embedded
+        {
+            Some("embedded streaming devices have no local library to play offline".to_string())
+        } else {
+            None
+        };
+        match problem {
+            Some(problem) => Err(ConfigError::InconsistentContext(problem).into()),
+            None => Ok(()),
+        }
+    }
+
     /// Applies context-aware multipliers to the quality scores.
     ///
     /// Returns the adjusted final score.
@@ -63,30 +204,106 @@ impl ClientContext {
         mut scores: QualityScores,
         file_video_codec: Option<VideoCodec>,
     ) -> QualityScores {
+        for multiplier in self.multipliers(&scores, file_video_codec) {
+            if let Some(score) = scores.dimension_mut(multiplier.dimension) {
+                *score *= multiplier.factor;
+            }
+        }
+        scores
+    }
+
+    /// The multipliers [`adjust_score`](Self::adjust_score) applies to
+    /// `scores`, in order. Multipliers of 1.0 are left out.
+    pub(crate) fn multipliers(
+        &self,
+        scores: &QualityScores,
+        file_video_codec: Option<VideoCodec>,
+    ) -> Vec<AppliedMultiplier> {
+        let mut multipliers = Vec::new();
+        let mut apply = |dimension, kind, factor: f32| {
+            if factor != 1.0 && scores.dimension(dimension).is_some() {
+                multipliers.push(AppliedMultiplier {
+                    dimension,
+                    kind,
+                    factor,
+                });
+            }
+        };
+
         // Device-type resolution adjustment
-        if let Some(ref mut res_score) = scores.resolution {
-            let multiplier = self.resolution_multiplier(*res_score);
-            *res_score *= multiplier;
+        if let Some(res_score) = scores.resolution {
+            apply(
+                ScoreDimension::Resolution,
+                MultiplierKind::Device,
+                self.resolution_multiplier(res_score),
+            );
+        }
+
+        // Screen cap: resolution beyond the screen scores as the screen's own
+        if let (Some(res_score), Some(screen)) = (scores.resolution, self.screen_resolution)
+            && res_score > screen.score()
+        {
+            apply(
+                ScoreDimension::Resolution,
+                MultiplierKind::Screen,
+                screen.score() / res_score,
+            );
+        }
+
+        // Battery penalty: high resolutions cost more to decode
+        if let (Some(res_score), Some(battery)) = (scores.resolution, self.battery) {
+            apply(
+                ScoreDimension::Resolution,
+                MultiplierKind::Battery,
+                Self::battery_multiplier(battery, res_score),
+            );
         }
 
         // Network penalty (applied as a global modifier to all scores)
         let network_mult = self.network_multiplier();
-        if let Some(ref mut res) = scores.resolution {
-            *res *= network_mult;
-        }
-        if let Some(ref mut vc) = scores.video_codec {
-            *vc *= network_mult;
+        apply(
+            ScoreDimension::Resolution,
+            MultiplierKind::Network,
+            network_mult,
+        );
+        apply(
+            ScoreDimension::VideoCodec,
+            MultiplierKind::Network,
+            network_mult,
+        );
+
+        // Storage penalty: large files crowd out everything else
+        if let (Some(size), Some(budget)) = (scores.file_size, self.storage_budget) {
+            let storage_mult = Self::storage_multiplier(size, budget);
+            apply(
+                ScoreDimension::Resolution,
+                MultiplierKind::Storage,
+                storage_mult,
+            );
+            apply(
+                ScoreDimension::VideoCodec,
+                MultiplierKind::Storage,
+                storage_mult,
+            );
         }
 
         // Hardware decoding penalty
         if let Some(codec) = file_video_codec
             && !self.hw_decode_codecs.contains(&codec)
         {
-            // Massive penalty: codec not hardware-decodable
-            scores.video_codec = scores.video_codec.map(|s| s * 0.1);
+            apply(
+                ScoreDimension::VideoCodec,
+                MultiplierKind::HwDecode,
+                self.software_decode_multiplier(codec, scores.resolution),
+            );
         }
 
-        scores
+        // Tone-mapping penalty: HDR on an SDR display looks washed out
+        if !self.hdr_capable && scores.is_hdr() {
+            apply(ScoreDimension::Hdr, MultiplierKind::ToneMapping, 0.3);
+        }
+
+        multipliers
     }
 
     /// Returns a resolution multiplier based on device type.
@@ -120,6 +337,50 @@ impl ClientContext {
         }
     }
 
+    /// Returns a resolution multiplier based on battery state.
+    fn battery_multiplier(battery: BatteryState, res_score: f32) -> f32 {
+        match battery {
+            BatteryState::Charging => 1.0,
+            // 4K drains the battery noticeably faster
+            BatteryState::Discharging if res_score > 0.9 => 0.85,
+            BatteryState::Discharging => 1.0,
+            // Anything above 720p gets penalized on a low battery
+            BatteryState::Low if res_score > 0.5 => 0.6,
+            BatteryState::Low => 1.0,
+        }
+    }
+
+    /// Returns a multiplier for a file of `size` bytes given `budget` bytes
+    /// of free storage.
+    fn storage_multiplier(size: u64, budget: u64) -> f32 {
+        if size > budget {
+            // Does not fit at all
+            0.1
+        } else if size > budget / 2 {
+            // Fits, but eats most of what is left
+            0.6
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns a multiplier for decoding `codec` in software, given the
+    /// file's resolution score (1080p assumed when unknown).
+    fn software_decode_multiplier(&self, codec: VideoCodec, res_score: Option<f32>) -> f32 {
+        let Some(tier) = self.cpu_tier else {
+            // Massive penalty: codec not hardware-decodable
+            return 0.1;
+        };
+        let res_score = res_score.unwrap_or(Resolution::FHD1080.score());
+        if res_score <= tier.max_software_resolution(codec).score() {
+            // Plays smoothly, at some cost in power and heat
+            0.9
+        } else {
+            // Drops frames
+            0.1
+        }
+    }
+
     /// Returns a network quality multiplier.
     fn network_multiplier(&self) -> f32 {
         match self.network {
@@ -131,9 +392,86 @@ impl ClientContext {
     }
 }
 
+/// Builder for a validated [`ClientContext`]; see [`ClientContext::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct ClientContextBuilder {
+    context: ClientContext,
+}
+
+impl ClientContextBuilder {
+    /// Sets the device type.
+    #[must_use]
+    pub fn with_device_type(mut self, device_type: DeviceType) -> Self {
+        self.context.device_type = device_type;
+        self
+    }
+
+    /// Sets the network quality.
+    #[must_use]
+    pub fn with_network(mut self, network: NetworkQuality) -> Self {
+        self.context.network = network;
+        self
+    }
+
+    /// Replaces the hardware-decodable codecs.
+    #[must_use]
+    pub fn with_hw_decode_codecs(mut self, codecs: impl IntoIterator<Item = VideoCodec>) -> Self {
+        self.context.hw_decode_codecs = codecs.into_iter().collect();
+        self
+    }
+
+    /// Sets the CPU class for software decoding.
+    #[must_use]
+    pub fn with_cpu_tier(mut self, cpu_tier: CpuTier) -> Self {
+        self.context.cpu_tier = Some(cpu_tier);
+        self
+    }
+
+    /// Sets whether the display can show HDR.
+    #[must_use]
+    pub fn with_hdr_capable(mut self, hdr_capable: bool) -> Self {
+        self.context.hdr_capable = hdr_capable;
+        self
+    }
+
+    /// Sets the battery state.
+    #[must_use]
+    pub fn with_battery(mut self, battery: BatteryState) -> Self {
+        self.context.battery = Some(battery);
+        self
+    }
+
+    /// Sets the native screen resolution.
+    #[must_use]
+    pub fn with_screen_resolution(mut self, resolution: Resolution) -> Self {
+        self.context.screen_resolution = Some(resolution);
+        self
+    }
+
+    /// Sets the free-storage budget in bytes.
+    #[must_use]
+    pub fn with_storage_budget(mut self, bytes: u64) -> Self {
+        self.context.storage_budget = Some(bytes);
+        self
+    }
+
+    /// Validates and returns the context.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::InvalidContext` if
+    /// [`ClientContext::validate`] rejects it.
+    pub fn build(self) -> Result<ClientContext> {
+        self.context.validate()?;
+        Ok(self.context)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ZantetsuError;
+    use crate::scoring::QualityProfile;
     use crate::types::{AudioCodec, MediaSource, Resolution};
 
     fn make_scores(
@@ -149,6 +487,137 @@ mod tests {
         )
     }
 
+    #[test]
+    fn hdr_scores_depend_on_display() {
+        let profile = QualityProfile {
+            hdr_weight: 0.2,
+            bit_depth_weight: 0.1,
+            ..QualityProfile::default()
+        }
+        .renormalized();
+        let hdr_bd = QualityScores::from_metadata(
+            Some(Resolution::FHD1080),
+            Some(VideoCodec::HEVC),
+            Some(AudioCodec::FLAC),
+            Some(MediaSource::BluRay),
+            0.7,
+        )
+        .with_hdr(true)
+        .with_bit_depth(10);
+        let sdr_web = make_scores(Some(Resolution::FHD1080), Some(VideoCodec::HEVC))
+            .with_hdr(false)
+            .with_bit_depth(8);
+
+        let capable = ClientContext {
+            hdr_capable: true,
+            ..ClientContext::default()
+        };
+        let score = |ctx: &ClientContext, scores: &QualityScores| {
+            ctx.adjust_score(scores.clone(), Some(VideoCodec::HEVC))
+                .compute(&profile)
+        };
+        assert!(score(&capable, &hdr_bd) > score(&capable, &sdr_web));
+        assert!(score(&ClientContext::default(), &hdr_bd) < score(&capable, &hdr_bd));
+        assert_eq!(
+            score(&ClientContext::default(), &sdr_web),
+            score(&capable, &sdr_web)
+        );
+    }
+
+    #[test]
+    fn screen_resolution_caps_wasted_pixels() {
+        let ctx = ClientContext {
+            screen_resolution: Some(Resolution::FHD1080),
+            ..ClientContext::default()
+        };
+        let uhd = ctx.adjust_score(make_scores(Some(Resolution::UHD2160), None), None);
+        let fhd = ctx.adjust_score(make_scores(Some(Resolution::FHD1080), None), None);
+        assert_eq!(uhd.resolution, fhd.resolution);
+        assert_eq!(fhd.resolution, Some(Resolution::FHD1080.score()));
+    }
+
+    #[test]
+    fn low_battery_penalizes_high_resolution() {
+        let ctx = ClientContext {
+            battery: Some(BatteryState::Low),
+            ..ClientContext::default()
+        };
+        let adjusted = ctx.adjust_score(make_scores(Some(Resolution::FHD1080), None), None);
+        assert!((adjusted.resolution.unwrap() - 0.85 * 0.6).abs() < 0.001);
+
+        let adjusted = ctx.adjust_score(make_scores(Some(Resolution::HD720), None), None);
+        assert_eq!(adjusted.resolution, Some(Resolution::HD720.score()));
+    }
+
+    #[test]
+    fn storage_budget_penalizes_large_files() {
+        let ctx = ClientContext {
+            storage_budget: Some(4_000_000_000),
+            ..ClientContext::default()
+        };
+        let scores = |size| {
+            make_scores(Some(Resolution::FHD1080), Some(VideoCodec::H264)).with_file_size(size)
+        };
+
+        let small = ctx.adjust_score(scores(1_000_000_000), None);
+        assert_eq!(small.resolution, Some(Resolution::FHD1080.score()));
+
+        let large = ctx.adjust_score(scores(3_000_000_000), None);
+        assert!((large.resolution.unwrap() - 0.85 * 0.6).abs() < 0.001);
+
+        let too_large = ctx.adjust_score(scores(5_000_000_000), None);
+        assert!((too_large.video_codec.unwrap() - VideoCodec::H264.score() * 0.1).abs() < 0.001);
+
+        // Unknown size: no penalty
+        let unknown = ctx.adjust_score(make_scores(Some(Resolution::FHD1080), None), None);
+        assert_eq!(unknown.resolution, Some(Resolution::FHD1080.score()));
+    }
+
+    #[test]
+    fn context_loads_from_toml() {
+        let ctx = ClientContext::from_toml("device_type = \"TV\"\nnetwork = \"Limited\"").unwrap();
+        assert_eq!(ctx.device_type, DeviceType::TV);
+        assert_eq!(ctx.network, NetworkQuality::Limited);
+        assert_eq!(
+            ctx.hw_decode_codecs,
+            ClientContext::default().hw_decode_codecs
+        );
+
+        assert!(ClientContext::from_toml("device_type = \"Toaster\"").is_err());
+        assert!(ClientContext::from_toml("hw_decode_codecs = []").is_err());
+    }
+
+    #[test]
+    fn builder_rejects_inconsistent_contexts() {
+        let ctx = ClientContext::builder()
+            .with_device_type(DeviceType::Laptop)
+            .with_network(NetworkQuality::Offline)
+            .with_battery(BatteryState::Low)
+            .with_cpu_tier(CpuTier::Mid)
+            .build()
+            .unwrap();
+        assert_eq!(ctx.battery, Some(BatteryState::Low));
+
+        for builder in [
+            ClientContext::builder().with_hw_decode_codecs([]),
+            ClientContext::builder()
+                .with_device_type(DeviceType::TV)
+                .with_battery(BatteryState::Charging),
+            ClientContext::builder()
+                .with_device_type(DeviceType::Embedded)
+                .with_network(NetworkQuality::Offline),
+        ] {
+            let err = builder.build().unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    ZantetsuError::InvalidContext(ConfigError::InconsistentContext(_))
+                ),
+                "{err}"
+            );
+        }
+    }
+
     #[test]
     fn desktop_unlimited_no_penalty() {
         let ctx = ClientContext::default();
@@ -165,6 +634,7 @@ mod tests {
             device_type: DeviceType::Mobile,
             network: NetworkQuality::Unlimited,
             hw_decode_codecs: vec![VideoCodec::H264, VideoCodec::HEVC],
+            ..ClientContext::default()
         };
 
         let scores = make_scores(Some(Resolution::FHD1080), Some(VideoCodec::H264));
@@ -186,6 +656,7 @@ mod tests {
             device_type: DeviceType::Desktop,
             network: NetworkQuality::Limited,
             hw_decode_codecs: vec![VideoCodec::H264],
+            ..ClientContext::default()
         };
 
         let scores = make_scores(Some(Resolution::FHD1080), Some(VideoCodec::H264));
@@ -202,6 +673,7 @@ mod tests {
             device_type: DeviceType::Desktop,
             network: NetworkQuality::Unlimited,
             hw_decode_codecs: vec![VideoCodec::H264], // AV1 NOT listed
+            ..ClientContext::default()
         };
 
         let scores = make_scores(Some(Resolution::FHD1080), Some(VideoCodec::AV1));
@@ -211,6 +683,28 @@ mod tests {
         assert!((adjusted.video_codec.unwrap() - 0.1).abs() < 0.001);
     }
 
+    #[test]
+    fn cpu_tier_softens_software_decode_penalty() {
+        let av1_score = |resolution, cpu_tier| {
+            let ctx = ClientContext {
+                hw_decode_codecs: vec![VideoCodec::H264],
+                cpu_tier,
+                ..ClientContext::default()
+            };
+            let scores = make_scores(Some(resolution), Some(VideoCodec::AV1));
+            ctx.adjust_score(scores, Some(VideoCodec::AV1))
+                .video_codec
+                .unwrap()
+        };
+
+        // A fast desktop CPU plays 4K AV1; a phone can't even manage 1080p
+        assert!((av1_score(Resolution::UHD2160, Some(CpuTier::High)) - 0.9).abs() < 0.001);
+        assert!((av1_score(Resolution::FHD1080, Some(CpuTier::Low)) - 0.1).abs() < 0.001);
+        assert!((av1_score(Resolution::FHD1080, Some(CpuTier::Mid)) - 0.9).abs() < 0.001);
+        assert!((av1_score(Resolution::UHD2160, Some(CpuTier::Mid)) - 0.1).abs() < 0.001);
+        assert!((av1_score(Resolution::UHD2160, None) - 0.1).abs() < 0.001);
+    }
+
     #[test]
     fn default_context_is_desktop_unlimited() {
         let ctx = ClientContext::default();
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::ParseResult;
+
+use super::profile::{QualityProfile, QualityScores};
+use super::report::ScoreDimension;
+
+/// Dimensions whose weights are learned. HDR and bit depth are not parsed
+/// yet, so every release would look the same on them; release shape and
+/// recency are preferences rather than qualities.
+const FITTED: [ScoreDimension; 5] = [
+    ScoreDimension::Resolution,
+    ScoreDimension::VideoCodec,
+    ScoreDimension::AudioCodec,
+    ScoreDimension::Source,
+    ScoreDimension::GroupTrust,
+];
+
+/// Sharpness of the preference model: how strongly a score gap predicts the
+/// user's choice. Score gaps are small (a few tenths), so this is well above 1.
+const TEMPERATURE: f32 = 20.0;
+const LEARNING_RATE: f32 = 0.5;
+const EPOCHS: usize = 500;
+
+/// Which release of a pair the user kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Choice {
+    /// The first release was kept.
+    First,
+    /// The second release was kept.
+    Second,
+}
+
+/// Learns profile weights from observed "user kept A over B" decisions.
+///
+/// Fits a pairwise logistic (Bradley–Terry) model on the difference of the
+/// two releases' [`QualityScores`], starting from the default profile. Weights
+/// stay non-negative and sum to 1.0. HDR, bit-depth, release-shape and
+/// recency weights are left at zero. With no preferences the default
+/// profile is returned.
+///
+/// # Examples
+/// ```
+/// use zantetsu_core::scoring::{Choice, fit_profile};
+/// use zantetsu_core::{AudioCodec, ParseMode, ParseResult};
+///
+/// let release = |audio| {
+///     let mut result = ParseResult::new("x", ParseMode::Light);
+///     result.audio_codec = Some(audio);
+///     result
+/// };
+/// let profile = fit_profile(&[
+///     (release(AudioCodec::FLAC), release(AudioCodec::AAC), Choice::First),
+///     (release(AudioCodec::MP3), release(AudioCodec::Opus), Choice::Second),
+/// ]);
+/// assert!(profile.is_valid());
+/// ```
+#[must_use]
+pub fn fit_profile(preferences: &[(ParseResult, ParseResult, Choice)]) -> QualityProfile {
+    let default = QualityProfile::default();
+    if preferences.is_empty() {
+        return default;
+    }
+
+    let features = |result: &ParseResult| {
+        let scores = QualityScores::from_result(result);
+        FITTED.map(|dimension| scores.dimension(dimension).unwrap_or(0.5))
+    };
+    // Feature difference oriented towards the kept release.
+    let samples: Vec<[f32; 5]> = preferences
+        .iter()
+        .map(|(first, second, choice)| {
+            let (kept, other) = match choice {
+                Choice::First => (features(first), features(second)),
+                Choice::Second => (features(second), features(first)),
+            };
+            std::array::from_fn(|i| kept[i] - other[i])
+        })
+        .collect();
+
+    // Weights are the softmax of `logits`, which keeps them positive and
+    // summing to one without a projection step.
+    let mut logits = FITTED.map(|dimension| dimension.weight(&default).ln());
+    for _ in 0..EPOCHS {
+        let weights = softmax(&logits);
+        let mut gradient = [0.0f32; 5];
+        for diff in &samples {
+            let margin: f32 = weights.iter().zip(diff).map(|(w, x)| w * x).sum();
+            // d/dw log σ(T·w·x) = (1 − σ(T·w·x))·T·x
+            let slack = 1.0 - sigmoid(TEMPERATURE * margin);
+            for (g, x) in gradient.iter_mut().zip(diff) {
+                *g += slack * TEMPERATURE * x;
+            }
+        }
+
+        // Chain rule through the softmax.
+        let mean: f32 = weights.iter().zip(&gradient).map(|(w, g)| w * g).sum();
+        let scale = LEARNING_RATE / samples.len() as f32;
+        for ((logit, w), g) in logits.iter_mut().zip(&weights).zip(&gradient) {
+            *logit += scale * w * (g - mean);
+        }
+    }
+
+    let [resolution, video_codec, audio_codec, source, group_trust] = softmax(&logits);
+    QualityProfile {
+        resolution_weight: resolution,
+        video_codec_weight: video_codec,
+        audio_codec_weight: audio_codec,
+        source_weight: source,
+        group_trust_weight: group_trust,
+        hdr_weight: 0.0,
+        bit_depth_weight: 0.0,
+        release_shape_weight: 0.0,
+        recency_weight: 0.0,
+        preferred_shape: default.preferred_shape,
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn softmax(logits: &[f32; 5]) -> [f32; 5] {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exp = logits.map(|l| (l - max).exp());
+    let sum: f32 = exp.iter().sum();
+    exp.map(|e| e / sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AudioCodec, ParseMode, Resolution};
+
+    fn release(resolution: Resolution, audio: AudioCodec) -> ParseResult {
+        let mut result = ParseResult::new("x", ParseMode::Light);
+        result.resolution = Some(resolution);
+        result.audio_codec = Some(audio);
+        result
+    }
+
+    #[test]
+    fn no_preferences_keeps_default() {
+        let profile = fit_profile(&[]);
+        assert_eq!(
+            profile.resolution_weight,
+            QualityProfile::default().resolution_weight
+        );
+    }
+
+    #[test]
+    fn audio_lover_raises_audio_weight() {
+        // Always keeps better audio, even at a lower resolution.
+        let preferences: Vec<_> = [
+            (
+                Resolution::HD720,
+                AudioCodec::FLAC,
+                Resolution::FHD1080,
+                AudioCodec::AAC,
+            ),
+            (
+                Resolution::FHD1080,
+                AudioCodec::TrueHD,
+                Resolution::UHD2160,
+                AudioCodec::MP3,
+            ),
+            (
+                Resolution::SD480,
+                AudioCodec::FLAC,
+                Resolution::HD720,
+                AudioCodec::Opus,
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(res_a, audio_a, res_b, audio_b)| {
+            [
+                (
+                    release(res_a, audio_a),
+                    release(res_b, audio_b),
+                    Choice::First,
+                ),
+                (
+                    release(res_b, audio_b),
+                    release(res_a, audio_a),
+                    Choice::Second,
+                ),
+            ]
+        })
+        .collect();
+
+        let profile = fit_profile(&preferences);
+        assert!(profile.is_valid());
+        assert!(profile.audio_codec_weight > profile.resolution_weight);
+        for (kept, other, choice) in &preferences {
+            let (kept, other) = match choice {
+                Choice::First => (kept, other),
+                Choice::Second => (other, kept),
+            };
+            assert!(
+                QualityScores::from_result(kept).compute(&profile)
+                    > QualityScores::from_result(other).compute(&profile)
+            );
+        }
+    }
+}
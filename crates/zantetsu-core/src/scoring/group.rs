@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Registry of release groups and how much their releases are trusted.
+///
+/// Lookups are case-insensitive. Trust values are clamped to `[0.0, 1.0]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupRegistry {
+    trust: HashMap<String, f32>,
+}
+
+impl GroupRegistry {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a group's trust value, returning the registry.
+    #[must_use]
+    pub fn with_group(mut self, name: &str, trust: f32) -> Self {
+        self.insert(name, trust);
+        self
+    }
+
+    /// Adds or replaces a group's trust value.
+    pub fn insert(&mut self, name: &str, trust: f32) {
+        self.trust
+            .insert(name.trim().to_lowercase(), trust.clamp(0.0, 1.0));
+    }
+
+    /// Returns the trust value for `name`, if the group is registered.
+    #[must_use]
+    pub fn trust(&self, name: &str) -> Option<f32> {
+        self.trust.get(&name.trim().to_lowercase()).copied()
+    }
+
+    /// Number of registered groups.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.trust.len()
+    }
+
+    /// Returns `true` if no groups are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.trust.is_empty()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, f32)> for GroupRegistry {
+    fn from_iter<I: IntoIterator<Item = (S, f32)>>(iter: I) -> Self {
+        let mut registry = Self::new();
+        for (name, trust) in iter {
+            registry.insert(name.as_ref(), trust);
+        }
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_is_case_insensitive_and_clamped() {
+        let registry = GroupRegistry::new()
+            .with_group("SubsPlease", 0.9)
+            .with_group("Shady", 4.0);
+
+        assert_eq!(registry.trust("subsplease"), Some(0.9));
+        assert_eq!(registry.trust(" SUBSPLEASE "), Some(0.9));
+        assert_eq!(registry.trust("Shady"), Some(1.0));
+        assert_eq!(registry.trust("Unknown"), None);
+        assert_eq!(registry.len(), 2);
+    }
+}
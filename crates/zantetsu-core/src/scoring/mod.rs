@@ -1,5 +1,23 @@
+pub mod audit;
+pub mod blocklist;
 pub mod context;
+pub mod fit;
+pub mod group;
 pub mod profile;
+pub mod rank;
+pub mod report;
+pub mod rules;
+pub mod shape;
 
-pub use context::{ClientContext, DeviceType, NetworkQuality};
-pub use profile::{QualityProfile, QualityScores};
+pub use audit::{AuditEntry, ScoringAudit};
+pub use blocklist::GroupBlocklist;
+pub use context::{
+    BatteryState, ClientContext, ClientContextBuilder, CpuTier, DeviceType, NetworkQuality,
+};
+pub use fit::{Choice, fit_profile};
+pub use group::GroupRegistry;
+pub use profile::{Preset, QualityProfile, QualityScores};
+pub use rank::rank;
+pub use report::{AppliedMultiplier, DimensionReport, MultiplierKind, ScoreDimension, ScoreReport};
+pub use rules::{Condition, ScoringRule, ScoringRules};
+pub use shape::ReleaseShape;
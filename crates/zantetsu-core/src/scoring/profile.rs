@@ -1,6 +1,12 @@
+use std::time::{Duration, SystemTime};
+
 use serde::{Deserialize, Serialize};
 
-use crate::types::{AudioCodec, MediaSource, Resolution, VideoCodec};
+use crate::error::{ConfigError, Result};
+use crate::types::{AudioCodec, MediaSource, ParseResult, Resolution, VideoCodec};
+
+use super::report::ScoreDimension;
+use super::shape::ReleaseShape;
 
 /// Default quality profile weights.
 pub const WEIGHT_RESOLUTION: f32 = 0.35;
@@ -8,15 +14,70 @@ pub const WEIGHT_VIDEO_CODEC: f32 = 0.25;
 pub const WEIGHT_AUDIO_CODEC: f32 = 0.15;
 pub const WEIGHT_SOURCE: f32 = 0.15;
 pub const WEIGHT_GROUP_TRUST: f32 = 0.10;
+/// HDR, bit depth, release shape and recency are opt-in: raise their
+/// weights and call [`QualityProfile::renormalized`].
+pub const WEIGHT_HDR: f32 = 0.0;
+pub const WEIGHT_BIT_DEPTH: f32 = 0.0;
+pub const WEIGHT_RELEASE_SHAPE: f32 = 0.0;
+pub const WEIGHT_RECENCY: f32 = 0.0;
+
+/// Dimension scores for HDR and bit depth.
+const SCORE_HDR: f32 = 1.0;
+const SCORE_SDR: f32 = 0.4;
+const SCORE_HIGH_BIT_DEPTH: f32 = 1.0;
+const SCORE_8_BIT: f32 = 0.6;
+const SCORE_LOW_BIT_DEPTH: f32 = 0.3;
+
+/// Recency score lost per year of age, down to the floor.
+const RECENCY_DECAY_PER_YEAR: f32 = 0.04;
+const RECENCY_FLOOR: f32 = 0.2;
+
+/// Average Gregorian year, for turning the clock into a calendar year.
+const SECONDS_PER_YEAR: u64 = 31_556_952;
+
+/// Lowest bitrate (kbit/s) at which a release plausibly delivers its
+/// advertised resolution.
+fn bitrate_floor_kbps(resolution: Resolution) -> u32 {
+    match resolution {
+        Resolution::SD480 => 800,
+        Resolution::HD720 => 2_000,
+        Resolution::FHD1080 => 3_500,
+        Resolution::UHD2160 => 10_000,
+    }
+}
+
+/// Ready-made weightings for common use cases; see [`QualityProfile::preset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Preset {
+    /// Keeping the best copy: source and lossless audio matter most.
+    Archival,
+    /// Desktop or TV streaming: resolution first, efficient codecs second.
+    Streaming,
+    /// Phones and tablets: efficient codecs and reliable groups over pixels.
+    Mobile,
+    /// Slow or metered connections: codec efficiency dominates.
+    LowBandwidth,
+    /// Audio quality above everything else.
+    AudioPhile,
+}
 
 /// Quality profile defining the relative importance of each dimension.
+///
+/// Weights left out of a config file keep their defaults.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct QualityProfile {
     pub resolution_weight: f32,
     pub video_codec_weight: f32,
     pub audio_codec_weight: f32,
     pub source_weight: f32,
     pub group_trust_weight: f32,
+    pub hdr_weight: f32,
+    pub bit_depth_weight: f32,
+    pub release_shape_weight: f32,
+    pub recency_weight: f32,
+    /// Release shape scored highest by the release-shape dimension.
+    pub preferred_shape: ReleaseShape,
 }
 
 impl Default for QualityProfile {
@@ -27,20 +88,211 @@ impl Default for QualityProfile {
             audio_codec_weight: WEIGHT_AUDIO_CODEC,
             source_weight: WEIGHT_SOURCE,
             group_trust_weight: WEIGHT_GROUP_TRUST,
+            hdr_weight: WEIGHT_HDR,
+            bit_depth_weight: WEIGHT_BIT_DEPTH,
+            release_shape_weight: WEIGHT_RELEASE_SHAPE,
+            recency_weight: WEIGHT_RECENCY,
+            preferred_shape: ReleaseShape::default(),
         }
     }
 }
 
 impl QualityProfile {
+    /// Returns the tuned weights for `preset`.
+    ///
+    /// Archival prefers batches and the others single episodes, though
+    /// the release-shape weight stays zero until raised.
+    #[must_use]
+    pub fn preset(preset: Preset) -> Self {
+        let (resolution, video_codec, audio_codec, source, group_trust) = match preset {
+            Preset::Archival => (0.25, 0.15, 0.20, 0.30, 0.10),
+            Preset::Streaming => (0.35, 0.30, 0.10, 0.10, 0.15),
+            Preset::Mobile => (0.15, 0.40, 0.10, 0.10, 0.25),
+            Preset::LowBandwidth => (0.10, 0.45, 0.10, 0.10, 0.25),
+            Preset::AudioPhile => (0.20, 0.15, 0.40, 0.15, 0.10),
+        };
+        Self {
+            resolution_weight: resolution,
+            video_codec_weight: video_codec,
+            audio_codec_weight: audio_codec,
+            source_weight: source,
+            group_trust_weight: group_trust,
+            hdr_weight: WEIGHT_HDR,
+            bit_depth_weight: WEIGHT_BIT_DEPTH,
+            release_shape_weight: WEIGHT_RELEASE_SHAPE,
+            recency_weight: WEIGHT_RECENCY,
+            preferred_shape: match preset {
+                Preset::Archival => ReleaseShape::Batch,
+                _ => ReleaseShape::Single,
+            },
+        }
+    }
+
+    /// Scales all weights so they sum to 1.0, keeping their proportions.
+    ///
+    /// Use after raising a weight, e.g. to add HDR to an existing profile.
+    /// A profile whose weights sum to zero is returned unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::QualityProfile;
+    ///
+    /// let profile = QualityProfile {
+    ///     hdr_weight: 0.25,
+    ///     ..QualityProfile::default()
+    /// }
+    /// .renormalized();
+    /// assert!(profile.is_valid());
+    /// assert_eq!(profile.hdr_weight, 0.2);
+    /// ```
+    #[must_use]
+    pub fn renormalized(mut self) -> Self {
+        self.normalize();
+        self
+    }
+
+    /// In-place form of [`renormalized`](Self::renormalized).
+    pub fn normalize(&mut self) {
+        let sum = self.weight_sum();
+        if sum <= 0.0 {
+            return;
+        }
+        for dimension in ScoreDimension::ALL {
+            *dimension.weight_mut(self) /= sum;
+        }
+    }
+
+    /// Sets one dimension's weight and rescales the others so the profile
+    /// still sums to 1.0.
+    ///
+    /// `value` is clamped to `[0.0, 1.0]`. The other weights keep their
+    /// proportions; if they are all zero the remainder is shared equally.
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::{QualityProfile, ScoreDimension};
+    ///
+    /// let profile = QualityProfile::default().with_weight(ScoreDimension::AudioCodec, 0.5);
+    /// assert!(profile.is_valid());
+    /// assert_eq!(profile.audio_codec_weight, 0.5);
+    /// ```
+    #[must_use]
+    pub fn with_weight(mut self, dimension: ScoreDimension, value: f32) -> Self {
+        let value = value.clamp(0.0, 1.0);
+        let others: Vec<ScoreDimension> = ScoreDimension::ALL
+            .into_iter()
+            .filter(|&d| d != dimension)
+            .collect();
+        let rest: f32 = others.iter().map(|d| d.weight(&self)).sum();
+        let remainder = 1.0 - value;
+        for d in others.iter().copied() {
+            let weight = d.weight_mut(&mut self);
+            *weight = if rest > 0.0 {
+                *weight / rest * remainder
+            } else {
+                remainder / others.len() as f32
+            };
+        }
+        *dimension.weight_mut(&mut self) = value;
+        self
+    }
+
+    /// Interpolates between `self` (`t = 0.0`) and `other` (`t = 1.0`),
+    /// normalizing the result.
+    ///
+    /// `t` is clamped to `[0.0, 1.0]`.
+    #[must_use]
+    pub fn lerp(&self, other: &QualityProfile, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mut blended = self.clone();
+        for dimension in ScoreDimension::ALL {
+            let (a, b) = (dimension.weight(self), dimension.weight(other));
+            *dimension.weight_mut(&mut blended) = a + (b - a) * t;
+        }
+        blended.renormalized()
+    }
+
+    /// Loads and validates a profile from a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::InvalidContext` if the document is malformed
+    /// or the weights fail [`validate`](Self::validate).
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::QualityProfile;
+    ///
+    /// let profile = QualityProfile::from_toml(
+    ///     "resolution_weight = 0.45\nvideo_codec_weight = 0.15",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(profile.resolution_weight, 0.45);
+    /// ```
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let profile: Self = toml::from_str(toml).map_err(ConfigError::from)?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Loads and validates a profile from a JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::InvalidContext` if the document is malformed
+    /// or the weights fail [`validate`](Self::validate).
+    pub fn from_json(json: &str) -> Result<Self> {
+        let profile: Self = serde_json::from_str(json).map_err(ConfigError::from)?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Checks that every weight is within `[0.0, 1.0]` and that they sum to
+    /// approximately 1.0.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::InvalidContext` naming the first problem.
+    pub fn validate(&self) -> Result<()> {
+        let weights = [
+            ("resolution_weight", self.resolution_weight),
+            ("video_codec_weight", self.video_codec_weight),
+            ("audio_codec_weight", self.audio_codec_weight),
+            ("source_weight", self.source_weight),
+            ("group_trust_weight", self.group_trust_weight),
+            ("hdr_weight", self.hdr_weight),
+            ("bit_depth_weight", self.bit_depth_weight),
+            ("release_shape_weight", self.release_shape_weight),
+            ("recency_weight", self.recency_weight),
+        ];
+        if let Some(&(field, value)) = weights
+            .iter()
+            .find(|(_, value)| !(0.0..=1.0).contains(value))
+        {
+            return Err(ConfigError::WeightOutOfRange { field, value }.into());
+        }
+        if !self.is_valid() {
+            return Err(ConfigError::WeightSum(self.weight_sum()).into());
+        }
+        Ok(())
+    }
+
     /// Validates that all weights sum to approximately 1.0.
     #[must_use]
     pub fn is_valid(&self) -> bool {
-        let sum = self.resolution_weight
+        (self.weight_sum() - 1.0).abs() < 0.01
+    }
+
+    fn weight_sum(&self) -> f32 {
+        self.resolution_weight
             + self.video_codec_weight
             + self.audio_codec_weight
             + self.source_weight
-            + self.group_trust_weight;
-        (sum - 1.0).abs() < 0.01
+            + self.group_trust_weight
+            + self.hdr_weight
+            + self.bit_depth_weight
+            + self.release_shape_weight
+            + self.recency_weight
     }
 }
 
@@ -57,6 +309,28 @@ pub struct QualityScores {
     pub source: Option<f32>,
     /// Group trust score `[0.0, 1.0]`.
     pub group_trust: f32,
+    /// HDR score `[0.0, 1.0]`; set with [`with_hdr`](Self::with_hdr).
+    #[serde(default)]
+    pub hdr: Option<f32>,
+    /// Bit depth score `[0.0, 1.0]`; set with
+    /// [`with_bit_depth`](Self::with_bit_depth).
+    #[serde(default)]
+    pub bit_depth: Option<f32>,
+    /// Estimated video bitrate in kbit/s; set with
+    /// [`with_estimated_bitrate`](Self::with_estimated_bitrate).
+    #[serde(default)]
+    pub estimated_bitrate: Option<u32>,
+    /// File size in bytes; set with [`with_file_size`](Self::with_file_size).
+    #[serde(default)]
+    pub file_size: Option<u64>,
+    /// How much of the series the release covers, scored against
+    /// [`QualityProfile::preferred_shape`].
+    #[serde(default)]
+    pub release_shape: Option<ReleaseShape>,
+    /// Recency score `[0.0, 1.0]` from the release year; set with
+    /// [`with_year`](Self::with_year).
+    #[serde(default)]
+    pub recency: Option<f32>,
 }
 
 impl QualityScores {
@@ -75,9 +349,123 @@ impl QualityScores {
             audio_codec: audio_codec.map(|a| a.score()),
             source: source.map(|s| s.score()),
             group_trust,
+            hdr: None,
+            bit_depth: None,
+            estimated_bitrate: None,
+            file_size: None,
+            release_shape: None,
+            recency: None,
         }
     }
 
+    /// Records the file size, checked against
+    /// [`ClientContext::storage_budget`](super::ClientContext::storage_budget).
+    #[must_use]
+    pub fn with_file_size(mut self, bytes: u64) -> Self {
+        self.file_size = Some(bytes);
+        self
+    }
+
+    /// Estimates the average bitrate in kbit/s of a file of `file_size`
+    /// bytes playing for `duration`.
+    #[must_use]
+    pub fn estimate_bitrate(file_size: u64, duration: Duration) -> Option<u32> {
+        let seconds = duration.as_secs_f64();
+        (seconds > 0.0).then(|| (file_size as f64 * 8.0 / 1000.0 / seconds) as u32)
+    }
+
+    /// Records the estimated bitrate and penalizes the resolution score when
+    /// it is implausibly low for `resolution`.
+    ///
+    /// The resolution score is scaled by how far the bitrate falls short of
+    /// the resolution's floor, so a starved 1080p mini-encode ends up below
+    /// an honest 720p release.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use zantetsu_core::scoring::QualityScores;
+    /// use zantetsu_core::Resolution;
+    ///
+    /// let episode = Duration::from_secs(24 * 60);
+    /// let mini = QualityScores::from_metadata(Some(Resolution::FHD1080), None, None, None, 0.5)
+    ///     .with_estimated_bitrate(
+    ///         QualityScores::estimate_bitrate(300_000_000, episode).unwrap(),
+    ///         Resolution::FHD1080,
+    ///     );
+    /// assert!(mini.resolution.unwrap() < Resolution::HD720.score());
+    /// ```
+    #[must_use]
+    pub fn with_estimated_bitrate(mut self, kbps: u32, resolution: Resolution) -> Self {
+        let ratio = kbps as f32 / bitrate_floor_kbps(resolution) as f32;
+        if let Some(ref mut score) = self.resolution {
+            *score *= ratio.min(1.0);
+        }
+        self.estimated_bitrate = Some(kbps);
+        self
+    }
+
+    /// Scores the HDR dimension: HDR video above SDR.
+    #[must_use]
+    pub fn with_hdr(mut self, hdr: bool) -> Self {
+        self.hdr = Some(if hdr { SCORE_HDR } else { SCORE_SDR });
+        self
+    }
+
+    /// Scores the bit-depth dimension: 10-bit and above beat 8-bit.
+    #[must_use]
+    pub fn with_bit_depth(mut self, bits: u8) -> Self {
+        self.bit_depth = Some(match bits {
+            10.. => SCORE_HIGH_BIT_DEPTH,
+            8..=9 => SCORE_8_BIT,
+            _ => SCORE_LOW_BIT_DEPTH,
+        });
+        self
+    }
+
+    /// Scores the recency dimension: a release from `current_year` scores
+    /// 1.0, losing 0.04 per year of age down to 0.2.
+    #[must_use]
+    pub fn with_year(mut self, year: u16, current_year: u16) -> Self {
+        let age = f32::from(current_year.saturating_sub(year));
+        self.recency = Some((1.0 - age * RECENCY_DECAY_PER_YEAR).max(RECENCY_FLOOR));
+        self
+    }
+
+    /// Whether the HDR dimension marks HDR video.
+    pub(crate) fn is_hdr(&self) -> bool {
+        self.hdr == Some(SCORE_HDR)
+    }
+
+    /// Builds scores from a parse result, using its `group_trust` annotation
+    /// (neutral 0.5 when the group is unknown or no registry was used).
+    /// Recency is measured against the current year.
+    #[must_use]
+    pub fn from_result(result: &ParseResult) -> Self {
+        let scores = Self {
+            release_shape: ReleaseShape::of(result),
+            ..Self::from_metadata(
+                result.resolution,
+                result.video_codec,
+                result.audio_codec,
+                result.source,
+                result.group_trust.unwrap_or(0.5),
+            )
+        };
+        match result.year {
+            Some(year) => scores.with_year(year, current_year()),
+            None => scores,
+        }
+    }
+
+    /// Release-shape score under `profile`'s preferred shape, if the shape
+    /// is known.
+    #[must_use]
+    pub fn release_shape_score(&self, profile: &QualityProfile) -> Option<f32> {
+        self.release_shape
+            .map(|shape| shape.score(profile.preferred_shape))
+    }
+
     /// Computes the weighted quality score using the given profile.
     /// Missing dimensions contribute 0.5 (neutral) to avoid penalizing
     /// files where metadata is simply absent.
@@ -87,18 +475,36 @@ impl QualityScores {
         let vc = self.video_codec.unwrap_or(0.5);
         let ac = self.audio_codec.unwrap_or(0.5);
         let src = self.source.unwrap_or(0.5);
+        let hdr = self.hdr.unwrap_or(0.5);
+        let bits = self.bit_depth.unwrap_or(0.5);
+        let shape = self.release_shape_score(profile).unwrap_or(0.5);
+        let recency = self.recency.unwrap_or(0.5);
 
         profile.resolution_weight * res
             + profile.video_codec_weight * vc
             + profile.audio_codec_weight * ac
             + profile.source_weight * src
             + profile.group_trust_weight * self.group_trust
+            + profile.hdr_weight * hdr
+            + profile.bit_depth_weight * bits
+            + profile.release_shape_weight * shape
+            + profile.recency_weight * recency
     }
 }
 
+/// The current calendar year, from the system clock.
+fn current_year() -> u16 {
+    let seconds = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    u16::try_from(1970 + seconds / SECONDS_PER_YEAR).unwrap_or(u16::MAX)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ZantetsuError;
+    use crate::types::{EpisodeSpec, ParseMode};
 
     #[test]
     fn default_profile_is_valid() {
@@ -106,6 +512,258 @@ mod tests {
         assert!(profile.is_valid());
     }
 
+    #[test]
+    fn presets_are_valid() {
+        for preset in [
+            Preset::Archival,
+            Preset::Streaming,
+            Preset::Mobile,
+            Preset::LowBandwidth,
+            Preset::AudioPhile,
+        ] {
+            assert!(QualityProfile::preset(preset).is_valid(), "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn presets_shift_preferences() {
+        let flac = QualityScores::from_metadata(None, None, Some(AudioCodec::FLAC), None, 0.5);
+        let mp3 = QualityScores::from_metadata(None, None, Some(AudioCodec::MP3), None, 0.5);
+        let gap = |preset| {
+            let profile = QualityProfile::preset(preset);
+            flac.compute(&profile) - mp3.compute(&profile)
+        };
+        assert!(gap(Preset::AudioPhile) > gap(Preset::Streaming));
+
+        let remux =
+            QualityScores::from_metadata(None, None, None, Some(MediaSource::BluRayRemux), 0.5);
+        let webrip = QualityScores::from_metadata(None, None, None, Some(MediaSource::WebRip), 0.5);
+        let archival = QualityProfile::preset(Preset::Archival);
+        let mobile = QualityProfile::preset(Preset::Mobile);
+        assert!(
+            remux.compute(&archival) - webrip.compute(&archival)
+                > remux.compute(&mobile) - webrip.compute(&mobile)
+        );
+    }
+
+    #[test]
+    fn profile_loads_from_toml_and_json() {
+        let profile =
+            QualityProfile::from_toml("resolution_weight = 0.45\nvideo_codec_weight = 0.15\n")
+                .unwrap();
+        assert_eq!(profile.resolution_weight, 0.45);
+        assert_eq!(profile.source_weight, WEIGHT_SOURCE);
+
+        let json = serde_json::to_string(&QualityProfile::preset(Preset::Mobile)).unwrap();
+        let profile = QualityProfile::from_json(&json).unwrap();
+        assert_eq!(profile.video_codec_weight, 0.40);
+    }
+
+    #[test]
+    fn profile_loading_reports_validation_errors() {
+        let err = QualityProfile::from_toml("resolution_weight = 0.9").unwrap_err();
+        assert!(matches!(
+            err,
+            ZantetsuError::InvalidContext(ConfigError::WeightSum(_))
+        ));
+
+        let err = QualityProfile::from_json(r#"{"source_weight": -0.1}"#).unwrap_err();
+        assert!(matches!(
+            err,
+            ZantetsuError::InvalidContext(ConfigError::WeightOutOfRange {
+                field: "source_weight",
+                ..
+            })
+        ));
+
+        let err = QualityProfile::from_toml("resolution_weight = \"high\"").unwrap_err();
+        assert!(matches!(
+            err,
+            ZantetsuError::InvalidContext(ConfigError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn renormalization_keeps_proportions() {
+        let profile = QualityProfile {
+            hdr_weight: 0.1,
+            bit_depth_weight: 0.1,
+            ..QualityProfile::default()
+        };
+        assert!(!profile.is_valid());
+
+        let profile = profile.renormalized();
+        assert!(profile.is_valid());
+        assert!((profile.resolution_weight / profile.hdr_weight - 3.5).abs() < 0.001);
+
+        let zero = QualityProfile {
+            resolution_weight: 0.0,
+            video_codec_weight: 0.0,
+            audio_codec_weight: 0.0,
+            source_weight: 0.0,
+            group_trust_weight: 0.0,
+            hdr_weight: 0.0,
+            bit_depth_weight: 0.0,
+            release_shape_weight: 0.0,
+            recency_weight: 0.0,
+            preferred_shape: ReleaseShape::Single,
+        };
+        assert_eq!(zero.clone().renormalized().weight_sum(), 0.0);
+    }
+
+    #[test]
+    fn weight_helpers_keep_profile_valid() {
+        let profile = QualityProfile::default().with_weight(ScoreDimension::Resolution, 0.65);
+        assert!(profile.is_valid());
+        assert_eq!(profile.resolution_weight, 0.65);
+        // The others shrink by the same factor (0.35 / 0.65).
+        assert!(
+            (profile.video_codec_weight / profile.audio_codec_weight - 0.25 / 0.15).abs() < 1e-4
+        );
+
+        let all_in = profile.with_weight(ScoreDimension::Source, 1.0);
+        assert_eq!(all_in.source_weight, 1.0);
+        let shared = all_in.with_weight(ScoreDimension::Source, 0.3);
+        assert!(shared.is_valid());
+        assert!((shared.hdr_weight - 0.7 / (ScoreDimension::ALL.len() - 1) as f32).abs() < 1e-6);
+
+        let archival = QualityProfile::preset(Preset::Archival);
+        let mobile = QualityProfile::preset(Preset::Mobile);
+        assert_eq!(
+            archival.lerp(&mobile, 0.0).source_weight,
+            archival.source_weight
+        );
+        let halfway = archival.lerp(&mobile, 0.5);
+        assert!(halfway.is_valid());
+        assert!((halfway.source_weight - 0.2).abs() < 1e-6);
+
+        let mut doubled = QualityProfile {
+            resolution_weight: 0.7,
+            ..QualityProfile::default()
+        };
+        doubled.normalize();
+        assert!(doubled.is_valid());
+    }
+
+    #[test]
+    fn release_shape_follows_preference() {
+        let mut batch = ParseResult::new("x", ParseMode::Light);
+        batch.episode = Some(EpisodeSpec::Range(1, 12));
+        batch.release_flags = vec!["Batch".into()];
+        let mut single = ParseResult::new("x", ParseMode::Light);
+        single.episode = Some(EpisodeSpec::Single(12));
+        let (batch, single) = (
+            QualityScores::from_result(&batch),
+            QualityScores::from_result(&single),
+        );
+        assert_eq!(batch.release_shape, Some(ReleaseShape::Batch));
+
+        let archival =
+            QualityProfile::preset(Preset::Archival).with_weight(ScoreDimension::ReleaseShape, 0.2);
+        assert!(batch.compute(&archival) > single.compute(&archival));
+
+        let airing = QualityProfile::default().with_weight(ScoreDimension::ReleaseShape, 0.2);
+        assert!(single.compute(&airing) > batch.compute(&airing));
+
+        // Inert at the default weight
+        let profile = QualityProfile::default();
+        assert_eq!(batch.compute(&profile), single.compute(&profile));
+    }
+
+    #[test]
+    fn newer_releases_win_on_recency() {
+        let scores = QualityScores::from_metadata(
+            Some(Resolution::FHD1080),
+            Some(VideoCodec::HEVC),
+            None,
+            Some(MediaSource::BluRay),
+            0.5,
+        );
+        let remaster = scores.clone().with_year(2024, 2025);
+        let old = scores.clone().with_year(2008, 2025);
+        assert_eq!(remaster.recency, Some(0.96));
+        assert_eq!(scores.clone().with_year(1980, 2025).recency, Some(0.2));
+        assert_eq!(scores.clone().with_year(2030, 2025).recency, Some(1.0));
+
+        let profile = QualityProfile::default().with_weight(ScoreDimension::Recency, 0.1);
+        assert!(remaster.compute(&profile) > old.compute(&profile));
+        // Off by default
+        let profile = QualityProfile::default();
+        assert_eq!(remaster.compute(&profile), old.compute(&profile));
+    }
+
+    #[test]
+    fn hdr_and_bit_depth_scores() {
+        let profile = QualityProfile {
+            hdr_weight: 0.15,
+            bit_depth_weight: 0.1,
+            ..QualityProfile::default()
+        }
+        .renormalized();
+
+        let hdr_bd = QualityScores::from_metadata(
+            Some(Resolution::FHD1080),
+            Some(VideoCodec::HEVC),
+            Some(AudioCodec::FLAC),
+            Some(MediaSource::BluRay),
+            0.5,
+        )
+        .with_hdr(true)
+        .with_bit_depth(10);
+        let sdr_bd = QualityScores::from_metadata(
+            Some(Resolution::FHD1080),
+            Some(VideoCodec::HEVC),
+            Some(AudioCodec::FLAC),
+            Some(MediaSource::BluRay),
+            0.5,
+        )
+        .with_hdr(false)
+        .with_bit_depth(8);
+        assert!(hdr_bd.compute(&profile) > sdr_bd.compute(&profile));
+
+        // Unset dimensions stay neutral, and default weights ignore them.
+        let plain = QualityScores::from_metadata(None, None, None, None, 0.5);
+        assert!((plain.compute(&profile) - 0.5).abs() < 0.001);
+        assert_eq!(
+            hdr_bd.compute(&QualityProfile::default()),
+            sdr_bd.compute(&QualityProfile::default())
+        );
+    }
+
+    #[test]
+    fn low_bitrate_penalizes_resolution() {
+        let episode = Duration::from_secs(24 * 60);
+        assert_eq!(
+            QualityScores::estimate_bitrate(1_440_000_000, episode),
+            Some(8_000)
+        );
+        assert_eq!(QualityScores::estimate_bitrate(1, Duration::ZERO), None);
+
+        let scores = |resolution, size| {
+            QualityScores::from_metadata(
+                Some(resolution),
+                Some(VideoCodec::H264),
+                Some(AudioCodec::AAC),
+                Some(MediaSource::WebDL),
+                0.5,
+            )
+            .with_estimated_bitrate(
+                QualityScores::estimate_bitrate(size, episode).unwrap(),
+                resolution,
+            )
+        };
+        let profile = QualityProfile::default();
+
+        let honest_1080 = scores(Resolution::FHD1080, 1_400_000_000);
+        assert_eq!(honest_1080.resolution, Some(Resolution::FHD1080.score()));
+        assert_eq!(honest_1080.estimated_bitrate, Some(7_777));
+
+        let mini_1080 = scores(Resolution::FHD1080, 300_000_000);
+        let honest_720 = scores(Resolution::HD720, 700_000_000);
+        assert!(mini_1080.compute(&profile) < honest_720.compute(&profile));
+        assert!(honest_1080.compute(&profile) > honest_720.compute(&profile));
+    }
+
     #[test]
     fn invalid_profile_detected() {
         let profile = QualityProfile {
@@ -114,6 +772,11 @@ mod tests {
             audio_codec_weight: 0.5,
             source_weight: 0.5,
             group_trust_weight: 0.5,
+            hdr_weight: 0.0,
+            bit_depth_weight: 0.0,
+            release_shape_weight: 0.0,
+            recency_weight: 0.0,
+            preferred_shape: ReleaseShape::Single,
         };
         assert!(!profile.is_valid());
     }
@@ -139,6 +802,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn quality_scores_from_result_uses_group_trust() {
+        let mut result = ParseResult::new("x", crate::types::ParseMode::Light);
+        result.resolution = Some(Resolution::FHD1080);
+        assert_eq!(QualityScores::from_result(&result).group_trust, 0.5);
+
+        result.group_trust = Some(0.9);
+        let scores = QualityScores::from_result(&result);
+        assert_eq!(scores.group_trust, 0.9);
+        assert_eq!(scores.resolution, Some(Resolution::FHD1080.score()));
+    }
+
     #[test]
     fn quality_scores_missing_metadata_uses_neutral() {
         let scores = QualityScores::from_metadata(None, None, None, None, 0.5);
@@ -0,0 +1,70 @@
+use crate::types::ParseResult;
+
+use super::context::ClientContext;
+use super::profile::{QualityProfile, QualityScores};
+
+/// Ranks parse results of alternative releases, best first.
+///
+/// Each result is scored with [`QualityScores::from_result`], adjusted for
+/// `context` and weighted by `profile`. Returns `(index, score)` pairs
+/// indexing into `results`; ties keep their input order.
+///
+/// # Examples
+/// ```
+/// use zantetsu_core::HeuristicParser;
+/// use zantetsu_core::scoring::{ClientContext, QualityProfile, rank};
+///
+/// let parser = HeuristicParser::new().unwrap();
+/// let results = [
+///     parser.parse("[Group] Title - 01 (480p).mkv").unwrap(),
+///     parser.parse("[Group] Title - 01 (1080p).mkv").unwrap(),
+/// ];
+/// let ranking = rank(&results, &QualityProfile::default(), &ClientContext::default());
+/// assert_eq!(ranking[0].0, 1);
+/// ```
+#[must_use]
+pub fn rank(
+    results: &[ParseResult],
+    profile: &QualityProfile,
+    context: &ClientContext,
+) -> Vec<(usize, f32)> {
+    let mut ranking: Vec<(usize, f32)> = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| {
+            let scores =
+                context.adjust_score(QualityScores::from_result(result), result.video_codec);
+            (index, scores.compute(profile))
+        })
+        .collect();
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ParseMode, VideoCodec};
+
+    #[test]
+    fn context_decides_between_codecs() {
+        let mut h264 = ParseResult::new("a", ParseMode::Light);
+        h264.video_codec = Some(VideoCodec::H264);
+        let mut av1 = ParseResult::new("b", ParseMode::Light);
+        av1.video_codec = Some(VideoCodec::AV1);
+        let results = [h264, av1];
+        let profile = QualityProfile::default();
+
+        let av1_decoder = ClientContext::builder()
+            .with_hw_decode_codecs([VideoCodec::AV1, VideoCodec::H264])
+            .build()
+            .unwrap();
+        assert_eq!(rank(&results, &profile, &av1_decoder)[0].0, 1);
+
+        let h264_only = ClientContext::builder()
+            .with_hw_decode_codecs([VideoCodec::H264])
+            .build()
+            .unwrap();
+        assert_eq!(rank(&results, &profile, &h264_only)[0].0, 0);
+    }
+}
@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::VideoCodec;
+
+use super::context::ClientContext;
+use super::profile::{QualityProfile, QualityScores};
+
+/// Score contributed by a dimension with no metadata.
+const NEUTRAL_SCORE: f32 = 0.5;
+
+/// A weighted dimension of the quality score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScoreDimension {
+    Resolution,
+    VideoCodec,
+    AudioCodec,
+    Source,
+    GroupTrust,
+    Hdr,
+    BitDepth,
+    ReleaseShape,
+    Recency,
+}
+
+impl ScoreDimension {
+    /// All dimensions, in `QualityScores` declaration order.
+    pub const ALL: [ScoreDimension; 9] = [
+        ScoreDimension::Resolution,
+        ScoreDimension::VideoCodec,
+        ScoreDimension::AudioCodec,
+        ScoreDimension::Source,
+        ScoreDimension::GroupTrust,
+        ScoreDimension::Hdr,
+        ScoreDimension::BitDepth,
+        ScoreDimension::ReleaseShape,
+        ScoreDimension::Recency,
+    ];
+
+    /// The profile weight of this dimension.
+    #[must_use]
+    pub fn weight(self, profile: &QualityProfile) -> f32 {
+        match self {
+            Self::Resolution => profile.resolution_weight,
+            Self::VideoCodec => profile.video_codec_weight,
+            Self::AudioCodec => profile.audio_codec_weight,
+            Self::Source => profile.source_weight,
+            Self::GroupTrust => profile.group_trust_weight,
+            Self::Hdr => profile.hdr_weight,
+            Self::BitDepth => profile.bit_depth_weight,
+            Self::ReleaseShape => profile.release_shape_weight,
+            Self::Recency => profile.recency_weight,
+        }
+    }
+
+    pub(crate) fn weight_mut(self, profile: &mut QualityProfile) -> &mut f32 {
+        match self {
+            Self::Resolution => &mut profile.resolution_weight,
+            Self::VideoCodec => &mut profile.video_codec_weight,
+            Self::AudioCodec => &mut profile.audio_codec_weight,
+            Self::Source => &mut profile.source_weight,
+            Self::GroupTrust => &mut profile.group_trust_weight,
+            Self::Hdr => &mut profile.hdr_weight,
+            Self::BitDepth => &mut profile.bit_depth_weight,
+            Self::ReleaseShape => &mut profile.release_shape_weight,
+            Self::Recency => &mut profile.recency_weight,
+        }
+    }
+}
+
+/// Why a [`ClientContext`] scaled a dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MultiplierKind {
+    /// Resolution preference of the device type.
+    Device,
+    /// Bitrate tolerance of the network.
+    Network,
+    /// The video codec cannot be hardware-decoded.
+    HwDecode,
+    /// HDR video on a display that cannot show it.
+    ToneMapping,
+    /// Resolution beyond the screen's own.
+    Screen,
+    /// Decoding cost on battery power.
+    Battery,
+    /// File size against the free-storage budget.
+    Storage,
+}
+
+/// A context multiplier applied to one dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AppliedMultiplier {
+    /// The dimension that was scaled.
+    pub dimension: ScoreDimension,
+    /// Why it was scaled.
+    pub kind: MultiplierKind,
+    /// The factor it was multiplied by.
+    pub factor: f32,
+}
+
+/// How one dimension contributed to the final score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DimensionReport {
+    /// The dimension.
+    pub dimension: ScoreDimension,
+    /// Score before context adjustment, or `None` if the metadata is missing.
+    pub raw: Option<f32>,
+    /// Context multipliers applied to the raw score, in order.
+    pub multipliers: Vec<AppliedMultiplier>,
+    /// Score after adjustment; missing metadata counts as neutral 0.5.
+    pub adjusted: f32,
+    /// The profile weight of the dimension.
+    pub weight: f32,
+    /// `adjusted * weight`.
+    pub contribution: f32,
+}
+
+/// Breakdown of a quality score, answering "why did release A beat release B".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreReport {
+    /// Per-dimension breakdown, in [`ScoreDimension::ALL`] order.
+    pub dimensions: Vec<DimensionReport>,
+    /// Sum of all contributions; equal to the adjusted scores' `compute`.
+    pub total: f32,
+}
+
+impl QualityScores {
+    /// Explains the score these metadata get under `profile` on the client
+    /// described by `context` (see [`ClientContext::adjust_score`]).
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::{ClientContext, QualityProfile, QualityScores};
+    /// use zantetsu_core::{Resolution, VideoCodec};
+    ///
+    /// let scores = QualityScores::from_metadata(
+    ///     Some(Resolution::FHD1080),
+    ///     Some(VideoCodec::AV1),
+    ///     None,
+    ///     None,
+    ///     0.5,
+    /// );
+    /// let report = scores.explain(
+    ///     &QualityProfile::default(),
+    ///     &ClientContext::default(),
+    ///     Some(VideoCodec::AV1),
+    /// );
+    /// println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    /// ```
+    #[must_use]
+    pub fn explain(
+        &self,
+        profile: &QualityProfile,
+        context: &ClientContext,
+        file_video_codec: Option<VideoCodec>,
+    ) -> ScoreReport {
+        let multipliers = context.multipliers(self, file_video_codec);
+        let dimensions: Vec<DimensionReport> = ScoreDimension::ALL
+            .into_iter()
+            .map(|dimension| {
+                let raw = match dimension {
+                    ScoreDimension::ReleaseShape => self.release_shape_score(profile),
+                    _ => self.dimension(dimension),
+                };
+                let multipliers: Vec<AppliedMultiplier> = multipliers
+                    .iter()
+                    .filter(|m| m.dimension == dimension)
+                    .copied()
+                    .collect();
+                let adjusted = raw.map_or(NEUTRAL_SCORE, |raw| {
+                    multipliers.iter().fold(raw, |score, m| score * m.factor)
+                });
+                let weight = dimension.weight(profile);
+                DimensionReport {
+                    dimension,
+                    raw,
+                    multipliers,
+                    adjusted,
+                    weight,
+                    contribution: adjusted * weight,
+                }
+            })
+            .collect();
+
+        ScoreReport {
+            total: dimensions.iter().map(|d| d.contribution).sum(),
+            dimensions,
+        }
+    }
+
+    /// The score of `dimension`, if its metadata is known.
+    ///
+    /// The release-shape score depends on the profile, so it is always
+    /// `None` here; see [`release_shape_score`](Self::release_shape_score).
+    #[must_use]
+    pub fn dimension(&self, dimension: ScoreDimension) -> Option<f32> {
+        match dimension {
+            ScoreDimension::Resolution => self.resolution,
+            ScoreDimension::VideoCodec => self.video_codec,
+            ScoreDimension::AudioCodec => self.audio_codec,
+            ScoreDimension::Source => self.source,
+            ScoreDimension::GroupTrust => Some(self.group_trust),
+            ScoreDimension::Hdr => self.hdr,
+            ScoreDimension::BitDepth => self.bit_depth,
+            ScoreDimension::ReleaseShape => None,
+            ScoreDimension::Recency => self.recency,
+        }
+    }
+
+    pub(crate) fn dimension_mut(&mut self, dimension: ScoreDimension) -> Option<&mut f32> {
+        match dimension {
+            ScoreDimension::Resolution => self.resolution.as_mut(),
+            ScoreDimension::VideoCodec => self.video_codec.as_mut(),
+            ScoreDimension::AudioCodec => self.audio_codec.as_mut(),
+            ScoreDimension::Source => self.source.as_mut(),
+            ScoreDimension::GroupTrust => Some(&mut self.group_trust),
+            ScoreDimension::Hdr => self.hdr.as_mut(),
+            ScoreDimension::BitDepth => self.bit_depth.as_mut(),
+            ScoreDimension::ReleaseShape => None,
+            ScoreDimension::Recency => self.recency.as_mut(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::{DeviceType, NetworkQuality};
+    use crate::types::{AudioCodec, MediaSource, Resolution};
+
+    #[test]
+    fn report_matches_adjusted_score() {
+        let scores = QualityScores::from_metadata(
+            Some(Resolution::FHD1080),
+            Some(VideoCodec::AV1),
+            Some(AudioCodec::AAC),
+            None,
+            0.7,
+        );
+        let profile = QualityProfile::default();
+        let context = ClientContext {
+            device_type: DeviceType::Mobile,
+            network: NetworkQuality::Limited,
+            ..ClientContext::default()
+        };
+        let codec = Some(VideoCodec::AV1);
+
+        let report = scores.explain(&profile, &context, codec);
+        let adjusted = context.adjust_score(scores, codec).compute(&profile);
+        assert!((report.total - adjusted).abs() < 1e-6);
+
+        let resolution = &report.dimensions[0];
+        assert_eq!(resolution.raw, Some(Resolution::FHD1080.score()));
+        let kinds: Vec<_> = resolution.multipliers.iter().map(|m| m.kind).collect();
+        assert_eq!(kinds, [MultiplierKind::Device, MultiplierKind::Network]);
+
+        let video = &report.dimensions[1];
+        assert!(
+            video
+                .multipliers
+                .iter()
+                .any(|m| m.kind == MultiplierKind::HwDecode)
+        );
+
+        let source = &report.dimensions[3];
+        assert_eq!(source.raw, None);
+        assert_eq!(source.adjusted, 0.5);
+        assert_eq!(source.contribution, 0.5 * profile.source_weight);
+    }
+
+    #[test]
+    fn report_serializes_to_json() {
+        let scores = QualityScores::from_metadata(None, None, None, Some(MediaSource::BluRay), 0.5);
+        let report = scores.explain(&QualityProfile::default(), &ClientContext::default(), None);
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"dimension\":\"Source\""));
+        let back: ScoreReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, report);
+    }
+}
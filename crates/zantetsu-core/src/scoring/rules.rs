@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::error::{ConfigError, Result};
+use crate::types::{ParseField, ParseResult};
+
+/// A condition on one parsed field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    /// The field to test.
+    pub field: ParseField,
+    /// The value it must have. Compared ignoring case and punctuation, so
+    /// `WebDL` matches `WEB-DL` and `h264` matches `H.264`. Release flags
+    /// match if any flag equals the value.
+    pub value: String,
+}
+
+impl Condition {
+    /// Returns `true` if `result` satisfies this condition.
+    #[must_use]
+    pub fn matches(&self, result: &ParseResult) -> bool {
+        let expected = normalize(&self.value);
+        if self.field == ParseField::ReleaseFlags {
+            return result
+                .release_flags
+                .iter()
+                .any(|flag| normalize(flag) == expected);
+        }
+        result
+            .field_value(self.field)
+            .is_some_and(|value| normalize(&value) == expected)
+    }
+}
+
+/// One rule: when every condition holds, adjust the score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoringRule {
+    /// Optional label, for reporting.
+    pub name: Option<String>,
+    /// Conditions that must all hold; an empty list matches every result.
+    pub conditions: Vec<Condition>,
+    /// Factor the score is multiplied by.
+    pub multiply: Option<f32>,
+    /// Upper bound the score is clamped to, after `multiply`.
+    pub cap: Option<f32>,
+}
+
+impl ScoringRule {
+    /// Returns `true` if every condition holds for `result`.
+    #[must_use]
+    pub fn matches(&self, result: &ParseResult) -> bool {
+        self.conditions.iter().all(|c| c.matches(result))
+    }
+
+    /// Applies this rule's actions to `score`.
+    #[must_use]
+    pub fn adjust(&self, score: f32) -> f32 {
+        let score = self.multiply.map_or(score, |factor| score * factor);
+        self.cap.map_or(score, |cap| score.min(cap))
+    }
+}
+
+/// Custom-format style rules applied after
+/// [`QualityScores::compute`](super::QualityScores::compute).
+///
+/// Rules run in file order; each matching rule adjusts the score left by the
+/// previous one.
+///
+/// # Examples
+/// ```
+/// use zantetsu_core::scoring::ScoringRules;
+/// use zantetsu_core::{MediaSource, ParseMode, ParseResult, Resolution};
+///
+/// let rules = ScoringRules::from_toml(
+///     r#"
+///     [[rule]]
+///     name = "untrusted group"
+///     when = { group = "BadGroup" }
+///     multiply = 0.2
+///
+///     [[rule]]
+///     when = { source = "WebRip", resolution = "2160p" }
+///     cap = 0.6
+///     "#,
+/// )
+/// .unwrap();
+///
+/// let mut result = ParseResult::new("x", ParseMode::Light);
+/// result.source = Some(MediaSource::WebRip);
+/// result.resolution = Some(Resolution::UHD2160);
+/// assert_eq!(rules.apply(&result, 0.9), 0.6);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoringRules {
+    rules: Vec<ScoringRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<RuleEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleEntry {
+    name: Option<String>,
+    #[serde(default)]
+    when: BTreeMap<String, String>,
+    multiply: Option<f32>,
+    cap: Option<f32>,
+}
+
+impl ScoringRules {
+    /// Creates a rule set from already-built rules.
+    #[must_use]
+    pub fn new(rules: Vec<ScoringRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Loads rules from a TOML document of `[[rule]]` tables, each with a
+    /// `when` table mapping field names (as in [`ParseField`]'s `Display`,
+    /// e.g. `group`, `video_codec`) to values, plus `multiply` and/or `cap`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZantetsuError::InvalidContext` if the document is malformed,
+    /// names an unknown field, or has a rule without a valid action.
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let file: RulesFile = toml::from_str(toml).map_err(ConfigError::from)?;
+        let rules = file
+            .rule
+            .into_iter()
+            .map(ScoringRule::try_from)
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// The rules, in evaluation order.
+    #[must_use]
+    pub fn rules(&self) -> &[ScoringRule] {
+        &self.rules
+    }
+
+    /// The rules that match `result`, in evaluation order.
+    pub fn matching<'a>(
+        &'a self,
+        result: &'a ParseResult,
+    ) -> impl Iterator<Item = &'a ScoringRule> {
+        self.rules.iter().filter(move |rule| rule.matches(result))
+    }
+
+    /// Adjusts a computed quality `score` for `result` by every matching rule.
+    #[must_use]
+    pub fn apply(&self, result: &ParseResult, score: f32) -> f32 {
+        self.matching(result)
+            .fold(score, |score, rule| rule.adjust(score))
+    }
+}
+
+impl TryFrom<RuleEntry> for ScoringRule {
+    type Error = ConfigError;
+
+    fn try_from(entry: RuleEntry) -> std::result::Result<Self, ConfigError> {
+        let label = entry.name.clone().unwrap_or_else(|| "unnamed rule".into());
+        if entry.multiply.is_none() && entry.cap.is_none() {
+            return Err(ConfigError::InvalidRule(format!(
+                "{label}: needs `multiply` or `cap`"
+            )));
+        }
+        if let Some(value) = entry.multiply.filter(|v| !(v.is_finite() && *v >= 0.0)) {
+            return Err(ConfigError::InvalidRule(format!(
+                "{label}: multiply must be non-negative, got {value}"
+            )));
+        }
+        if let Some(value) = entry.cap.filter(|v| !v.is_finite()) {
+            return Err(ConfigError::InvalidRule(format!(
+                "{label}: cap must be a number, got {value}"
+            )));
+        }
+
+        let conditions = entry
+            .when
+            .into_iter()
+            .map(|(name, value)| {
+                ParseField::ALL
+                    .into_iter()
+                    .find(|field| field.to_string() == name)
+                    .map(|field| Condition { field, value })
+                    .ok_or_else(|| {
+                        ConfigError::InvalidRule(format!("{label}: unknown field `{name}`"))
+                    })
+            })
+            .collect::<std::result::Result<_, _>>()?;
+
+        Ok(Self {
+            name: entry.name,
+            conditions,
+            multiply: entry.multiply,
+            cap: entry.cap,
+        })
+    }
+}
+
+/// Lowercase alphanumerics only, for forgiving value comparison.
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ZantetsuError;
+    use crate::types::{MediaSource, ParseMode, Resolution, VideoCodec};
+
+    fn release(group: &str, source: MediaSource, resolution: Resolution) -> ParseResult {
+        let mut result = ParseResult::new("x", ParseMode::Light);
+        result.group = Some(group.into());
+        result.source = Some(source);
+        result.resolution = Some(resolution);
+        result.video_codec = Some(VideoCodec::H264);
+        result.release_flags = vec!["REPACK".into()];
+        result
+    }
+
+    #[test]
+    fn rules_multiply_and_cap() {
+        let rules = ScoringRules::from_toml(
+            r#"
+            [[rule]]
+            name = "bad group"
+            when = { group = "badgroup" }
+            multiply = 0.2
+
+            [[rule]]
+            when = { source = "WebRip", resolution = "2160p" }
+            cap = 0.6
+
+            [[rule]]
+            when = { video_codec = "h264", release_flags = "repack" }
+            multiply = 1.1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rules.rules().len(), 3);
+
+        let bad = release("BadGroup", MediaSource::BluRay, Resolution::FHD1080);
+        assert!((rules.apply(&bad, 0.8) - 0.8 * 0.2 * 1.1).abs() < 1e-6);
+
+        let uhd_rip = release("Good", MediaSource::WebRip, Resolution::UHD2160);
+        // Capped at 0.6, then boosted by the REPACK rule
+        assert!((rules.apply(&uhd_rip, 0.9) - 0.6 * 1.1).abs() < 1e-6);
+        let names: Vec<_> = rules.matching(&bad).map(|r| r.name.as_deref()).collect();
+        assert_eq!(names, [Some("bad group"), None]);
+
+        let unmatched = ParseResult::new("x", ParseMode::Light);
+        assert_eq!(rules.apply(&unmatched, 0.7), 0.7);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        for toml in [
+            "[[rule]]\nwhen = { group = \"x\" }",
+            "[[rule]]\nwhen = { colour = \"red\" }\nmultiply = 0.5",
+            "[[rule]]\nmultiply = -1.0",
+            "[[rule]]\nmultiply = 0.5\nboost = 2",
+        ] {
+            let err = ScoringRules::from_toml(toml).unwrap_err();
+            assert!(matches!(err, ZantetsuError::InvalidContext(_)), "{toml}");
+        }
+        assert_eq!(
+            ScoringRules::from_toml("").unwrap(),
+            ScoringRules::default()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{EpisodeSpec, ParseResult};
+
+/// How much of a series a release covers.
+///
+/// Ordered from smallest to largest; a shape one step away from the
+/// preferred one scores half.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum ReleaseShape {
+    /// One episode, as released while airing.
+    #[default]
+    Single,
+    /// A few episodes in one release (`01-03`, `01+02`).
+    Multi,
+    /// A season pack or complete-series batch.
+    Batch,
+}
+
+impl ReleaseShape {
+    /// The shape of `result`: `Batch` if it carries the `Batch` release
+    /// flag, otherwise derived from its [`EpisodeSpec`]. `None` when neither
+    /// is present.
+    ///
+    /// # Examples
+    /// ```
+    /// use zantetsu_core::scoring::ReleaseShape;
+    /// use zantetsu_core::{EpisodeSpec, ParseMode, ParseResult};
+    ///
+    /// let mut result = ParseResult::new("x", ParseMode::Light);
+    /// result.episode = Some(EpisodeSpec::Range(1, 12));
+    /// assert_eq!(ReleaseShape::of(&result), Some(ReleaseShape::Multi));
+    ///
+    /// result.release_flags.push("Batch".into());
+    /// assert_eq!(ReleaseShape::of(&result), Some(ReleaseShape::Batch));
+    /// ```
+    #[must_use]
+    pub fn of(result: &ParseResult) -> Option<Self> {
+        if result
+            .release_flags
+            .iter()
+            .any(|flag| flag.eq_ignore_ascii_case("batch"))
+        {
+            return Some(Self::Batch);
+        }
+        result.episode.as_ref().map(|episode| match episode {
+            EpisodeSpec::Range(..) | EpisodeSpec::Multi(_) => Self::Multi,
+            EpisodeSpec::Single(_)
+            | EpisodeSpec::Version { .. }
+            | EpisodeSpec::Special(_)
+            | EpisodeSpec::Zero => Self::Single,
+        })
+    }
+
+    /// Score `[0.0, 1.0]` of this shape for a user who prefers `preferred`.
+    #[must_use]
+    pub fn score(self, preferred: ReleaseShape) -> f32 {
+        match (self as u8).abs_diff(preferred as u8) {
+            0 => 1.0,
+            1 => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ParseMode;
+
+    #[test]
+    fn shape_from_episode_and_batch_flag() {
+        let mut result = ParseResult::new("x", ParseMode::Light);
+        assert_eq!(ReleaseShape::of(&result), None);
+
+        result.episode = Some(EpisodeSpec::Single(5));
+        assert_eq!(ReleaseShape::of(&result), Some(ReleaseShape::Single));
+        result.episode = Some(EpisodeSpec::Multi(vec![1, 2]));
+        assert_eq!(ReleaseShape::of(&result), Some(ReleaseShape::Multi));
+
+        result.episode = None;
+        result.release_flags = vec!["BATCH".into()];
+        assert_eq!(ReleaseShape::of(&result), Some(ReleaseShape::Batch));
+    }
+
+    #[test]
+    fn shape_score_falls_off_with_distance() {
+        use ReleaseShape::{Batch, Multi, Single};
+        assert_eq!(Batch.score(Batch), 1.0);
+        assert_eq!(Multi.score(Batch), 0.5);
+        assert_eq!(Single.score(Batch), 0.0);
+        assert_eq!(Single.score(Single), 1.0);
+    }
+}
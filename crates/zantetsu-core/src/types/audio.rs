@@ -0,0 +1,57 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::quality::AudioCodec;
+
+/// One audio track of a release, e.g. `FLAC 2.0 JPN` in a dual-audio remux.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AudioTrack {
+    /// Audio codec.
+    pub codec: AudioCodec,
+    /// Channel layout as written (e.g., `2.0`, `5.1`).
+    pub channels: Option<String>,
+    /// Language tag as written (e.g., `JPN`, `English`).
+    pub language: Option<String>,
+}
+
+impl AudioTrack {
+    /// A track with only its codec known.
+    #[must_use]
+    pub fn new(codec: AudioCodec) -> Self {
+        Self {
+            codec,
+            channels: None,
+            language: None,
+        }
+    }
+}
+
+impl fmt::Display for AudioTrack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.codec)?;
+        if let Some(ref channels) = self.channels {
+            write!(f, " {channels}")?;
+        }
+        if let Some(ref language) = self.language {
+            write!(f, " {language}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_track_display() {
+        assert_eq!(AudioTrack::new(AudioCodec::AAC).to_string(), "AAC");
+        let track = AudioTrack {
+            codec: AudioCodec::FLAC,
+            channels: Some("2.0".into()),
+            language: Some("JPN".into()),
+        };
+        assert_eq!(track.to_string(), "FLAC 2.0 JPN");
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::field::ParseField;
+
+/// Per-field confidence scores in `[0.0, 1.0]`.
+///
+/// Fields without a score were either not extracted or came from an engine
+/// that cannot estimate its own certainty (the heuristic parser).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FieldConfidence(HashMap<ParseField, f32>);
+
+impl FieldConfidence {
+    /// Creates an empty set of scores.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the score for `field`, if one was recorded.
+    #[must_use]
+    pub fn get(&self, field: ParseField) -> Option<f32> {
+        self.0.get(&field).copied()
+    }
+
+    /// Records the score for `field`, clamped to `[0.0, 1.0]`.
+    pub fn set(&mut self, field: ParseField, confidence: f32) {
+        self.0.insert(field, confidence.clamp(0.0, 1.0));
+    }
+
+    /// Removes the score for `field`.
+    pub fn remove(&mut self, field: ParseField) -> Option<f32> {
+        self.0.remove(&field)
+    }
+
+    /// Iterates over recorded scores in [`ParseField::ALL`] order.
+    pub fn iter(&self) -> impl Iterator<Item = (ParseField, f32)> + '_ {
+        ParseField::ALL
+            .into_iter()
+            .filter_map(|field| self.get(field).map(|confidence| (field, confidence)))
+    }
+
+    /// Number of fields with a score.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no scores were recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_are_clamped_and_ordered() {
+        let mut scores = FieldConfidence::new();
+        scores.set(ParseField::Resolution, 1.4);
+        scores.set(ParseField::Title, 0.8);
+
+        assert_eq!(scores.get(ParseField::Resolution), Some(1.0));
+        assert_eq!(scores.get(ParseField::Group), None);
+        let order: Vec<ParseField> = scores.iter().map(|(field, _)| field).collect();
+        assert_eq!(order, vec![ParseField::Title, ParseField::Resolution]);
+
+        let json = serde_json::to_string(&scores).unwrap();
+        let back: FieldConfidence = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, scores);
+    }
+}
@@ -1,7 +1,14 @@
 use std::fmt;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
+use super::season::SeasonSpec;
+
+/// Episodes above this number with no season marker are assumed to use
+/// absolute (whole-series) numbering; seasonal runs rarely exceed a year.
+const ABSOLUTE_EPISODE_THRESHOLD: u32 = 52;
+
 /// Episode specification supporting complex numbering schemes
 /// found in anime torrent/file names.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -22,6 +29,66 @@ pub enum EpisodeSpec {
         /// The version number (e.g., v2 = 2).
         version: u8,
     },
+
+    /// Numbered special: "SP1", "Special 2"
+    Special(u32),
+
+    /// Episode zero or prologue: "00", "Episode 0"
+    Zero,
+}
+
+impl EpisodeSpec {
+    /// A plain episode number: [`Zero`](Self::Zero) for 0, otherwise
+    /// [`Single`](Self::Single).
+    #[must_use]
+    pub fn numbered(episode: u32) -> Self {
+        if episode == 0 {
+            Self::Zero
+        } else {
+            Self::Single(episode)
+        }
+    }
+
+    /// The highest episode number referenced by this spec.
+    ///
+    /// Specials sit outside the regular numbering and count as 0.
+    #[must_use]
+    pub fn last(&self) -> u32 {
+        match self {
+            Self::Single(ep) | Self::Range(_, ep) => *ep,
+            Self::Multi(eps) => eps.iter().copied().max().unwrap_or(0),
+            Self::Version { episode, .. } => *episode,
+            Self::Special(_) | Self::Zero => 0,
+        }
+    }
+}
+
+/// How the episode number of a release is counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EpisodeNumbering {
+    /// Counted from the first episode of the whole series ("One Piece - 1084").
+    Absolute,
+    /// Counted from the start of a season ("S02E05").
+    Seasonal,
+    /// Not enough evidence either way.
+    #[default]
+    Unknown,
+}
+
+impl EpisodeNumbering {
+    /// Infers the numbering scheme from the extracted season and episode.
+    ///
+    /// An explicit single season means seasonal numbering; a large episode
+    /// number with no season means absolute numbering.
+    #[must_use]
+    pub fn infer(season: Option<SeasonSpec>, episode: Option<&EpisodeSpec>) -> Self {
+        match (season, episode) {
+            (_, None) => Self::Unknown,
+            (Some(SeasonSpec::Single(_)), Some(_)) => Self::Seasonal,
+            (None, Some(ep)) if ep.last() > ABSOLUTE_EPISODE_THRESHOLD => Self::Absolute,
+            _ => Self::Unknown,
+        }
+    }
 }
 
 impl fmt::Display for EpisodeSpec {
@@ -34,10 +101,91 @@ impl fmt::Display for EpisodeSpec {
                 write!(f, "{}", formatted.join(", "))
             }
             Self::Version { episode, version } => write!(f, "{episode:02}v{version}"),
+            Self::Special(number) => write!(f, "SP{number:02}"),
+            Self::Zero => write!(f, "00"),
         }
     }
 }
 
+/// Error returned when a string is not a valid [`EpisodeSpec`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid episode specification: {input:?}")]
+pub struct ParseEpisodeSpecError {
+    input: String,
+}
+
+impl ParseEpisodeSpecError {
+    fn new(input: &str) -> Self {
+        Self {
+            input: input.to_string(),
+        }
+    }
+
+    /// The string that failed to parse.
+    #[must_use]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+/// Parses the forms written by [`Display`](fmt::Display): "24", "01-12"
+/// (or "01~12"), "12v2", "01, 03, 05", "SP01" and "00".
+///
+/// This is a strict reader for stored or user-entered specs, not a filename
+/// parser; use [`HeuristicParser`](crate::parser::HeuristicParser) for that.
+impl FromStr for EpisodeSpec {
+    type Err = ParseEpisodeSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseEpisodeSpecError::new(s);
+        let number = |part: &str| {
+            let part = part.trim();
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(err());
+            }
+            part.parse::<u32>().map_err(|_| err())
+        };
+
+        let text = s.trim();
+
+        if text.contains(',') {
+            let episodes = text.split(',').map(number).collect::<Result<Vec<_>, _>>()?;
+            return Ok(Self::Multi(episodes));
+        }
+
+        if let Some((start, end)) = text.split_once(['-', '~']) {
+            let (start, end) = (number(start)?, number(end)?);
+            return if start < end {
+                Ok(Self::Range(start, end))
+            } else {
+                Err(err())
+            };
+        }
+
+        if let Some((episode, version)) = text.split_once(['v', 'V']) {
+            let version = u8::try_from(number(version)?).map_err(|_| err())?;
+            return Ok(Self::Version {
+                episode: number(episode)?,
+                version,
+            });
+        }
+
+        if let Some(special) = text.get(..2).filter(|p| p.eq_ignore_ascii_case("sp")) {
+            return number(&text[special.len()..]).map(Self::Special);
+        }
+
+        number(text).map(Self::numbered)
+    }
+}
+
+impl TryFrom<&str> for EpisodeSpec {
+    type Error = ParseEpisodeSpecError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,6 +220,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn episode_spec_special_and_zero() {
+        assert_eq!(EpisodeSpec::Special(1).to_string(), "SP01");
+        assert_eq!(EpisodeSpec::Zero.to_string(), "00");
+        assert_eq!(EpisodeSpec::numbered(0), EpisodeSpec::Zero);
+        assert_eq!(EpisodeSpec::numbered(3), EpisodeSpec::Single(3));
+        assert_eq!(EpisodeSpec::Special(60).last(), 0);
+    }
+
+    #[test]
+    fn episode_spec_from_str() {
+        assert_eq!("24".parse(), Ok(EpisodeSpec::Single(24)));
+        assert_eq!(" 00 ".parse(), Ok(EpisodeSpec::Zero));
+        assert_eq!("01~12".parse(), Ok(EpisodeSpec::Range(1, 12)));
+        assert_eq!("1,3, 5".parse(), Ok(EpisodeSpec::Multi(vec![1, 3, 5])));
+        assert_eq!("sp3".parse(), Ok(EpisodeSpec::Special(3)));
+        assert_eq!(
+            EpisodeSpec::try_from("12V2"),
+            Ok(EpisodeSpec::Version {
+                episode: 12,
+                version: 2
+            })
+        );
+
+        for invalid in ["", "abc", "12-03", "1, x", "+5", "1v300", "SP", "-3"] {
+            let err = invalid.parse::<EpisodeSpec>().unwrap_err();
+            assert_eq!(err.input(), invalid);
+        }
+
+        for spec in [
+            EpisodeSpec::Single(1084),
+            EpisodeSpec::Range(1, 12),
+            EpisodeSpec::Multi(vec![1, 3, 5]),
+            EpisodeSpec::Version {
+                episode: 12,
+                version: 2,
+            },
+            EpisodeSpec::Special(1),
+            EpisodeSpec::Zero,
+        ] {
+            assert_eq!(spec.to_string().parse(), Ok(spec));
+        }
+    }
+
+    #[test]
+    fn episode_numbering_inference() {
+        assert_eq!(
+            EpisodeNumbering::infer(None, Some(&EpisodeSpec::Single(1084))),
+            EpisodeNumbering::Absolute
+        );
+        assert_eq!(
+            EpisodeNumbering::infer(Some(SeasonSpec::Single(2)), Some(&EpisodeSpec::Single(5))),
+            EpisodeNumbering::Seasonal
+        );
+        assert_eq!(
+            EpisodeNumbering::infer(None, Some(&EpisodeSpec::Single(5))),
+            EpisodeNumbering::Unknown
+        );
+        assert_eq!(
+            EpisodeNumbering::infer(Some(SeasonSpec::Single(2)), None),
+            EpisodeNumbering::Unknown
+        );
+    }
+
     #[test]
     fn episode_spec_serialization_roundtrip() {
         let specs = vec![
@@ -82,6 +294,8 @@ mod tests {
                 episode: 7,
                 version: 3,
             },
+            EpisodeSpec::Special(2),
+            EpisodeSpec::Zero,
         ];
 
         for spec in &specs {
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::result::ParseResult;
+
+/// Identifies an individual metadata field of a [`ParseResult`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ParseField {
+    Title,
+    Group,
+    Episode,
+    EpisodeCount,
+    EpisodeTitle,
+    Season,
+    Part,
+    Resolution,
+    VideoCodec,
+    AudioCodec,
+    AudioChannels,
+    Language,
+    Source,
+    Year,
+    YearRange,
+    Crc32,
+    Extension,
+    Version,
+    ReleaseFlags,
+}
+
+impl ParseField {
+    /// All metadata fields, in `ParseResult` declaration order.
+    pub const ALL: [ParseField; 19] = [
+        ParseField::Title,
+        ParseField::Group,
+        ParseField::Episode,
+        ParseField::EpisodeCount,
+        ParseField::EpisodeTitle,
+        ParseField::Season,
+        ParseField::Part,
+        ParseField::Resolution,
+        ParseField::VideoCodec,
+        ParseField::AudioCodec,
+        ParseField::AudioChannels,
+        ParseField::Language,
+        ParseField::Source,
+        ParseField::Year,
+        ParseField::YearRange,
+        ParseField::Crc32,
+        ParseField::Extension,
+        ParseField::Version,
+        ParseField::ReleaseFlags,
+    ];
+
+    /// Returns `true` if this field was extracted in `result`.
+    #[must_use]
+    pub fn is_present(self, result: &ParseResult) -> bool {
+        match self {
+            Self::Title => result.title.is_some(),
+            Self::Group => result.group.is_some(),
+            Self::Episode => result.episode.is_some(),
+            Self::EpisodeCount => result.episode_count.is_some(),
+            Self::EpisodeTitle => result.episode_title.is_some(),
+            Self::Season => result.season.is_some(),
+            Self::Part => result.part.is_some(),
+            Self::Resolution => result.resolution.is_some(),
+            Self::VideoCodec => result.video_codec.is_some(),
+            Self::AudioCodec => result.audio_codec.is_some(),
+            Self::AudioChannels => result.audio_channels.is_some(),
+            Self::Language => result.language.is_some(),
+            Self::Source => result.source.is_some(),
+            Self::Year => result.year.is_some(),
+            Self::YearRange => result.year_range.is_some(),
+            Self::Crc32 => result.crc32.is_some(),
+            Self::Extension => result.extension.is_some(),
+            Self::Version => result.version.is_some(),
+            Self::ReleaseFlags => !result.release_flags.is_empty(),
+        }
+    }
+}
+
+impl fmt::Display for ParseField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Title => write!(f, "title"),
+            Self::Group => write!(f, "group"),
+            Self::Episode => write!(f, "episode"),
+            Self::EpisodeCount => write!(f, "episode_count"),
+            Self::EpisodeTitle => write!(f, "episode_title"),
+            Self::Season => write!(f, "season"),
+            Self::Part => write!(f, "part"),
+            Self::Resolution => write!(f, "resolution"),
+            Self::VideoCodec => write!(f, "video_codec"),
+            Self::AudioCodec => write!(f, "audio_codec"),
+            Self::AudioChannels => write!(f, "audio_channels"),
+            Self::Language => write!(f, "language"),
+            Self::Source => write!(f, "source"),
+            Self::Year => write!(f, "year"),
+            Self::YearRange => write!(f, "year_range"),
+            Self::Crc32 => write!(f, "crc32"),
+            Self::Extension => write!(f, "extension"),
+            Self::Version => write!(f, "version"),
+            Self::ReleaseFlags => write!(f, "release_flags"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EpisodeSpec, ParseMode};
+
+    #[test]
+    fn is_present_tracks_result_fields() {
+        let mut result = ParseResult::new("x", ParseMode::Light);
+        assert!(ParseField::ALL.iter().all(|f| !f.is_present(&result)));
+
+        result.title = Some("Frieren".into());
+        result.episode = Some(EpisodeSpec::Single(1));
+        assert!(ParseField::Title.is_present(&result));
+        assert!(ParseField::Episode.is_present(&result));
+        assert!(!ParseField::Group.is_present(&result));
+    }
+
+    #[test]
+    fn display_uses_result_field_names() {
+        assert_eq!(ParseField::VideoCodec.to_string(), "video_codec");
+        assert_eq!(ParseField::Crc32.to_string(), "crc32");
+    }
+}
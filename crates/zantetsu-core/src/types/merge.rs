@@ -0,0 +1,251 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::field::ParseField;
+use super::result::ParseResult;
+
+/// How [`ParseResult::merge`] resolves a field both results extracted.
+///
+/// Fields only one side extracted are always taken from that side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MergeStrategy {
+    /// Keep `self`'s value, filling gaps from `other`.
+    #[default]
+    PreferSelf,
+    /// Take `other`'s value, falling back to `self` where `other` has none.
+    PreferOther,
+    /// Take the value with the higher per-field confidence (the overall
+    /// confidence when none was recorded); ties keep `self`.
+    HigherConfidence,
+}
+
+/// A field on which two parse results disagree.
+///
+/// Values are rendered with their `Display` implementations so that every
+/// field can be compared and reported the same way.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldDiff {
+    /// The field that differs.
+    pub field: ParseField,
+    /// Value in `self`, or `None` if not extracted.
+    pub left: Option<String>,
+    /// Value in `other`, or `None` if not extracted.
+    pub right: Option<String>,
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".into());
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.field,
+            show(&self.left),
+            show(&self.right)
+        )
+    }
+}
+
+impl ParseResult {
+    /// Combines two results of the same input field by field.
+    ///
+    /// Each field's confidence and provenance follow its value. Release
+    /// flags and extras are unioned; audio tracks come from `self` unless it
+    /// has none. The input, parse mode, overall confidence and budget flag
+    /// are kept from `self`.
+    #[must_use]
+    pub fn merge(&self, other: &ParseResult, strategy: MergeStrategy) -> ParseResult {
+        let mut merged = self.clone();
+        // Release flags are unioned below rather than picked from one side.
+        for field in ParseField::ALL
+            .into_iter()
+            .filter(|&field| field != ParseField::ReleaseFlags)
+        {
+            let take_other = match (field.is_present(self), field.is_present(other)) {
+                (_, false) => false,
+                (false, true) => true,
+                (true, true) => match strategy {
+                    MergeStrategy::PreferSelf => false,
+                    MergeStrategy::PreferOther => true,
+                    MergeStrategy::HigherConfidence => {
+                        other.confidence_of(field) > self.confidence_of(field)
+                    }
+                },
+            };
+            if take_other {
+                merged.copy_field(other, field);
+            }
+        }
+
+        for flag in &other.release_flags {
+            if !merged.release_flags.contains(flag) {
+                merged.release_flags.push(flag.clone());
+            }
+        }
+        if merged.audio_tracks.is_empty() {
+            merged.audio_tracks.clone_from(&other.audio_tracks);
+        }
+        for extra in &other.extras {
+            if !merged.extras.contains(extra) {
+                merged.extras.push(extra.clone());
+            }
+        }
+        merged
+    }
+
+    /// Lists the fields whose values differ between `self` and `other`, in
+    /// [`ParseField::ALL`] order.
+    #[must_use]
+    pub fn diff(&self, other: &ParseResult) -> Vec<FieldDiff> {
+        ParseField::ALL
+            .into_iter()
+            .filter_map(|field| {
+                let left = self.field_value(field);
+                let right = other.field_value(field);
+                (left != right).then_some(FieldDiff { field, left, right })
+            })
+            .collect()
+    }
+
+    fn confidence_of(&self, field: ParseField) -> f32 {
+        self.field_confidence.get(field).unwrap_or(self.confidence)
+    }
+
+    /// Copies `field`, with its confidence and provenance, from `other`.
+    fn copy_field(&mut self, other: &ParseResult, field: ParseField) {
+        match field {
+            ParseField::Title => self.title.clone_from(&other.title),
+            ParseField::Group => {
+                self.group.clone_from(&other.group);
+                self.group_trust = other.group_trust;
+            }
+            ParseField::Episode => {
+                self.episode.clone_from(&other.episode);
+                self.episode_numbering = other.episode_numbering;
+            }
+            ParseField::EpisodeCount => self.episode_count = other.episode_count,
+            ParseField::EpisodeTitle => self.episode_title.clone_from(&other.episode_title),
+            ParseField::Season => self.season = other.season,
+            ParseField::Part => self.part = other.part,
+            ParseField::Resolution => self.resolution = other.resolution,
+            ParseField::VideoCodec => self.video_codec = other.video_codec,
+            ParseField::AudioCodec => self.audio_codec = other.audio_codec,
+            ParseField::AudioChannels => self.audio_channels.clone_from(&other.audio_channels),
+            ParseField::Language => self.language.clone_from(&other.language),
+            ParseField::Source => self.source = other.source,
+            ParseField::Year => self.year = other.year,
+            ParseField::YearRange => self.year_range = other.year_range,
+            ParseField::Crc32 => self.crc32.clone_from(&other.crc32),
+            ParseField::Extension => self.extension.clone_from(&other.extension),
+            ParseField::Version => self.version = other.version,
+            ParseField::ReleaseFlags => self.release_flags.clone_from(&other.release_flags),
+        }
+
+        match other.field_confidence.get(field) {
+            Some(confidence) => self.field_confidence.set(field, confidence),
+            None => {
+                self.field_confidence.remove(field);
+            }
+        }
+        if let Some(source) = other.provenance.get(field) {
+            self.provenance.set(field, source);
+        }
+    }
+
+    /// `field` rendered with its `Display` implementation, if extracted.
+    pub(crate) fn field_value(&self, field: ParseField) -> Option<String> {
+        fn show<T: fmt::Display>(value: Option<&T>) -> Option<String> {
+            value.map(ToString::to_string)
+        }
+
+        match field {
+            ParseField::Title => self.title.clone(),
+            ParseField::Group => self.group.clone(),
+            ParseField::Episode => show(self.episode.as_ref()),
+            ParseField::EpisodeCount => show(self.episode_count.as_ref()),
+            ParseField::EpisodeTitle => self.episode_title.clone(),
+            ParseField::Season => show(self.season.as_ref()),
+            ParseField::Part => show(self.part.as_ref()),
+            ParseField::Resolution => show(self.resolution.as_ref()),
+            ParseField::VideoCodec => show(self.video_codec.as_ref()),
+            ParseField::AudioCodec => show(self.audio_codec.as_ref()),
+            ParseField::AudioChannels => self.audio_channels.clone(),
+            ParseField::Language => self.language.clone(),
+            ParseField::Source => show(self.source.as_ref()),
+            ParseField::Year => show(self.year.as_ref()),
+            ParseField::YearRange => self.year_range.map(|(start, end)| format!("{start}-{end}")),
+            ParseField::Crc32 => self.crc32.clone(),
+            ParseField::Extension => self.extension.clone(),
+            ParseField::Version => show(self.version.as_ref()),
+            ParseField::ReleaseFlags => {
+                (!self.release_flags.is_empty()).then(|| self.release_flags.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EpisodeSpec, ParseMode, Resolution};
+
+    fn results() -> (ParseResult, ParseResult) {
+        let mut left = ParseResult::new("x", ParseMode::Light);
+        left.title = Some("Frieren".into());
+        left.episode = Some(EpisodeSpec::Single(1));
+        left.release_flags = vec!["Batch".into()];
+        left.confidence = 0.6;
+
+        let mut right = ParseResult::new("x", ParseMode::Full);
+        right.title = Some("Frieren Beyond".into());
+        right.resolution = Some(Resolution::FHD1080);
+        right.release_flags = vec!["END".into()];
+        right.confidence = 0.4;
+        right.field_confidence.set(ParseField::Title, 0.9);
+        (left, right)
+    }
+
+    #[test]
+    fn merge_fills_gaps_and_resolves_conflicts() {
+        let (left, right) = results();
+
+        let merged = left.merge(&right, MergeStrategy::PreferSelf);
+        assert_eq!(merged.title.as_deref(), Some("Frieren"));
+        assert_eq!(merged.episode, Some(EpisodeSpec::Single(1)));
+        assert_eq!(merged.resolution, Some(Resolution::FHD1080));
+        assert_eq!(merged.release_flags, ["Batch", "END"]);
+        assert_eq!(merged.parse_mode, ParseMode::Light);
+        assert_eq!(merged.field_confidence.get(ParseField::Title), None);
+
+        let merged = left.merge(&right, MergeStrategy::PreferOther);
+        assert_eq!(merged.title.as_deref(), Some("Frieren Beyond"));
+        assert_eq!(merged.episode, Some(EpisodeSpec::Single(1)));
+        assert_eq!(merged.release_flags, ["Batch", "END"]);
+
+        let merged = left.merge(&right, MergeStrategy::HigherConfidence);
+        assert_eq!(merged.title.as_deref(), Some("Frieren Beyond"));
+        assert_eq!(merged.field_confidence.get(ParseField::Title), Some(0.9));
+    }
+
+    #[test]
+    fn diff_lists_differing_fields() {
+        let (left, right) = results();
+        assert!(left.diff(&left).is_empty());
+
+        let diff = left.diff(&right);
+        let fields: Vec<_> = diff.iter().map(|d| d.field).collect();
+        assert_eq!(
+            fields,
+            [
+                ParseField::Title,
+                ParseField::Episode,
+                ParseField::Resolution,
+                ParseField::ReleaseFlags
+            ]
+        );
+        assert_eq!(diff[1].left.as_deref(), Some("01"));
+        assert_eq!(diff[1].right, None);
+        assert_eq!(diff[1].to_string(), "episode: 01 -> -");
+    }
+}
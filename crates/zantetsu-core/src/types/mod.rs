@@ -1,7 +1,21 @@
+pub mod audio;
+pub mod confidence;
 pub mod episode;
+pub mod field;
+pub mod merge;
+pub mod provenance;
 pub mod quality;
 pub mod result;
+pub mod season;
+pub mod warning;
 
-pub use episode::EpisodeSpec;
+pub use audio::AudioTrack;
+pub use confidence::FieldConfidence;
+pub use episode::{EpisodeNumbering, EpisodeSpec, ParseEpisodeSpecError};
+pub use field::ParseField;
+pub use merge::{FieldDiff, MergeStrategy};
+pub use provenance::{FieldProvenance, FieldSource};
 pub use quality::{AudioCodec, MediaSource, ParseMode, Resolution, VideoCodec};
 pub use result::ParseResult;
+pub use season::SeasonSpec;
+pub use warning::ParseWarning;
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::field::ParseField;
+
+/// The engine a field value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FieldSource {
+    /// Regex heuristics ([`ParseMode::Light`](super::ParseMode::Light)).
+    Heuristic,
+    /// A neural model ([`ParseMode::Full`](super::ParseMode::Full)).
+    Neural,
+}
+
+impl fmt::Display for FieldSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Heuristic => write!(f, "heuristic"),
+            Self::Neural => write!(f, "neural"),
+        }
+    }
+}
+
+/// Which engine produced each field of a merged result.
+///
+/// Recorded by [`ParseMode::Ensemble`](super::ParseMode::Ensemble) and by
+/// heuristic backfill of model results; single-engine results leave it
+/// empty.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FieldProvenance(HashMap<ParseField, FieldSource>);
+
+impl FieldProvenance {
+    /// Creates an empty provenance map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the engine that produced `field`, if recorded.
+    #[must_use]
+    pub fn get(&self, field: ParseField) -> Option<FieldSource> {
+        self.0.get(&field).copied()
+    }
+
+    /// Records the engine that produced `field`.
+    pub fn set(&mut self, field: ParseField, source: FieldSource) {
+        self.0.insert(field, source);
+    }
+
+    /// Iterates over recorded sources in [`ParseField::ALL`] order.
+    pub fn iter(&self) -> impl Iterator<Item = (ParseField, FieldSource)> + '_ {
+        ParseField::ALL
+            .into_iter()
+            .filter_map(|field| self.get(field).map(|source| (field, source)))
+    }
+
+    /// Returns `true` if no sources were recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
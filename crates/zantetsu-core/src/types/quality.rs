@@ -178,6 +178,8 @@ pub enum ParseMode {
     /// Auto-select based on available resources.
     #[default]
     Auto,
+    /// Run both engines and merge their results field by field.
+    Ensemble,
 }
 
 impl fmt::Display for ParseMode {
@@ -186,6 +188,7 @@ impl fmt::Display for ParseMode {
             Self::Full => write!(f, "Full (Neural CRF)"),
             Self::Light => write!(f, "Light (Heuristic)"),
             Self::Auto => write!(f, "Auto"),
+            Self::Ensemble => write!(f, "Ensemble"),
         }
     }
 }
@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use super::episode::EpisodeSpec;
+use super::audio::AudioTrack;
+use super::confidence::FieldConfidence;
+use super::episode::{EpisodeNumbering, EpisodeSpec};
+use super::provenance::FieldProvenance;
 use super::quality::{AudioCodec, MediaSource, ParseMode, Resolution, VideoCodec};
+use super::season::SeasonSpec;
+use super::warning::ParseWarning;
 
 /// The primary output of the Zantetsu parsing engine.
 ///
@@ -18,11 +23,27 @@ pub struct ParseResult {
     /// Release group name (e.g., "SubsPlease", "Erai-raws").
     pub group: Option<String>,
 
+    /// Trust value of `group` from a `GroupRegistry`, if one was consulted.
+    pub group_trust: Option<f32>,
+
     /// Episode specification.
     pub episode: Option<EpisodeSpec>,
 
-    /// Season number.
-    pub season: Option<u32>,
+    /// Total episode count advertised by the release (e.g., `(24 episodes)`).
+    pub episode_count: Option<u32>,
+
+    /// Episode title (e.g., `- 05 - The Journey's End`).
+    pub episode_title: Option<String>,
+
+    /// Season specification.
+    pub season: Option<SeasonSpec>,
+
+    /// Split-cour or split-season part number (e.g., `Part 2`).
+    pub part: Option<u32>,
+
+    /// Whether `episode` counts from the series or the season start.
+    #[serde(default)]
+    pub episode_numbering: EpisodeNumbering,
 
     /// Video resolution.
     pub resolution: Option<Resolution>,
@@ -33,12 +54,26 @@ pub struct ParseResult {
     /// Audio codec.
     pub audio_codec: Option<AudioCodec>,
 
+    /// Audio channel layout as written (e.g., `5.1`, `2.0`).
+    pub audio_channels: Option<String>,
+
+    /// Audio or subtitle language tag as written (e.g., `ENG`, `Multi-Subs`).
+    pub language: Option<String>,
+
+    /// Every audio track named in the release (e.g., `FLAC 2.0 JPN + AAC 2.0
+    /// ENG`), in input order. `audio_codec` is the first track's codec.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audio_tracks: Vec<AudioTrack>,
+
     /// Media source.
     pub source: Option<MediaSource>,
 
     /// Release year.
     pub year: Option<u16>,
 
+    /// Inclusive span of release years (e.g., `(1999-2001)`).
+    pub year_range: Option<(u16, u16)>,
+
     /// CRC32 checksum (hex string).
     pub crc32: Option<String>,
 
@@ -48,9 +83,36 @@ pub struct ParseResult {
     /// Release version (e.g., v2 = 2).
     pub version: Option<u8>,
 
+    /// Release flags such as `Batch`, `END`, `Uncensored` or `REPACK`.
+    #[serde(default)]
+    pub release_flags: Vec<String>,
+
+    /// Bracketed tokens no extractor claimed (e.g., `Multiple Subtitle`,
+    /// `Weekly`), in input order. Filled by the heuristic parser.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extras: Vec<String>,
+
     /// Confidence score in `[0.0, 1.0]` from the parsing engine.
     pub confidence: f32,
 
+    /// Per-field confidence, where the engine can estimate it (neural parser).
+    #[serde(default, skip_serializing_if = "FieldConfidence::is_empty")]
+    pub field_confidence: FieldConfidence,
+
+    /// Engine behind each field, when results were merged (ensemble mode,
+    /// heuristic backfill).
+    #[serde(default, skip_serializing_if = "FieldProvenance::is_empty")]
+    pub provenance: FieldProvenance,
+
+    /// Whether the parser's time budget cut model inference short, leaving
+    /// a heuristic-only result.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub budget_exhausted: bool,
+
+    /// Issues found while parsing, such as a blocklisted release group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ParseWarning>,
+
     /// Which parse mode produced this result.
     pub parse_mode: ParseMode,
 }
@@ -63,21 +125,45 @@ impl ParseResult {
             input: input.into(),
             title: None,
             group: None,
+            group_trust: None,
             episode: None,
+            episode_count: None,
+            episode_title: None,
             season: None,
+            part: None,
+            episode_numbering: EpisodeNumbering::Unknown,
             resolution: None,
             video_codec: None,
             audio_codec: None,
+            audio_channels: None,
+            language: None,
+            audio_tracks: Vec::new(),
             source: None,
             year: None,
+            year_range: None,
             crc32: None,
             extension: None,
             version: None,
+            release_flags: Vec::new(),
+            extras: Vec::new(),
             confidence: 0.0,
+            field_confidence: FieldConfidence::new(),
+            provenance: FieldProvenance::new(),
+            budget_exhausted: false,
+            warnings: Vec::new(),
             parse_mode,
         }
     }
 
+    /// The result as a JSON object with every field, as the bindings and
+    /// `zantetsu_parse_json` hand it out.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        // Every map in a result is keyed by strings or unit variants, and
+        // serde_json writes non-finite floats as `null`.
+        serde_json::to_string(self).expect("a ParseResult serializes to JSON")
+    }
+
     /// Returns `true` if the result extracted at least a title.
     #[must_use]
     pub fn has_title(&self) -> bool {
@@ -118,6 +204,17 @@ impl std::fmt::Display for ParseResult {
 mod tests {
     use super::*;
 
+    #[test]
+    fn to_json_round_trips() {
+        let mut result = ParseResult::new("[Group] Title - 05.mkv", ParseMode::Light);
+        result.title = Some("Title".into());
+        let back: ParseResult = serde_json::from_str(&result.to_json()).unwrap();
+        assert_eq!(back, result);
+
+        result.confidence = f32::NAN;
+        assert!(result.to_json().contains(r#""confidence":null"#));
+    }
+
     #[test]
     fn new_parse_result_is_empty() {
         let result = ParseResult::new("test input", ParseMode::Light);
@@ -163,11 +260,14 @@ mod tests {
         result.title = Some("One Piece".into());
         result.group = Some("SubsPlease".into());
         result.episode = Some(EpisodeSpec::Single(1084));
+        result.episode_count = Some(1100);
+        result.season = Some(SeasonSpec::Range(1, 3));
         result.resolution = Some(Resolution::FHD1080);
         result.video_codec = Some(VideoCodec::H264);
         result.audio_codec = Some(AudioCodec::AAC);
         result.source = Some(MediaSource::WebDL);
         result.year = Some(2024);
+        result.year_range = Some((2023, 2024));
         result.crc32 = Some("DEADBEEF".into());
         result.extension = Some("mkv".into());
         result.version = Some(2);
@@ -0,0 +1,82 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Season specification for single-season releases and season batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SeasonSpec {
+    /// Single season: "S2", "Season 2"
+    Single(u32),
+
+    /// Season range: "S1-S3", "Seasons 1-3"
+    Range(u32, u32),
+}
+
+impl SeasonSpec {
+    /// The first season covered by this spec.
+    #[must_use]
+    pub fn first(self) -> u32 {
+        match self {
+            Self::Single(season) | Self::Range(season, _) => season,
+        }
+    }
+
+    /// The last season covered by this spec.
+    #[must_use]
+    pub fn last(self) -> u32 {
+        match self {
+            Self::Single(season) | Self::Range(_, season) => season,
+        }
+    }
+
+    /// Returns `true` if `season` falls within this spec.
+    #[must_use]
+    pub fn contains(self, season: u32) -> bool {
+        (self.first()..=self.last()).contains(&season)
+    }
+}
+
+impl From<u32> for SeasonSpec {
+    fn from(season: u32) -> Self {
+        Self::Single(season)
+    }
+}
+
+impl fmt::Display for SeasonSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single(season) => write!(f, "S{season:02}"),
+            Self::Range(start, end) => write!(f, "S{start:02}-S{end:02}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn season_spec_display() {
+        assert_eq!(SeasonSpec::Single(2).to_string(), "S02");
+        assert_eq!(SeasonSpec::Range(1, 3).to_string(), "S01-S03");
+    }
+
+    #[test]
+    fn season_spec_bounds() {
+        let range = SeasonSpec::Range(1, 3);
+        assert_eq!(range.first(), 1);
+        assert_eq!(range.last(), 3);
+        assert!(range.contains(2));
+        assert!(!range.contains(4));
+        assert!(SeasonSpec::Single(2).contains(2));
+    }
+
+    #[test]
+    fn season_spec_serialization_roundtrip() {
+        for spec in [SeasonSpec::Single(4), SeasonSpec::Range(1, 3)] {
+            let json = serde_json::to_string(&spec).unwrap();
+            let back: SeasonSpec = serde_json::from_str(&json).unwrap();
+            assert_eq!(spec, back);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Something about a release worth flagging to the user, found while
+/// parsing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParseWarning {
+    /// The release group is on the parser's
+    /// [`GroupBlocklist`](crate::scoring::GroupBlocklist).
+    BlocklistedGroup {
+        /// Group name as parsed.
+        group: String,
+        /// Score factor the blocklist applies; `0.0` means blocked.
+        penalty: f32,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlocklistedGroup { group, penalty } if *penalty == 0.0 => {
+                write!(f, "group {group} is blocklisted")
+            }
+            Self::BlocklistedGroup { group, penalty } => {
+                write!(
+                    f,
+                    "group {group} is a known low-quality re-encoder (score x{penalty})"
+                )
+            }
+        }
+    }
+}
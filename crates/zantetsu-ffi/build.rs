@@ -0,0 +1,17 @@
+//! Generates `include/zantetsu.h` from the C API when the `c` feature is on.
+
+fn main() {
+    #[cfg(feature = "c")]
+    generate_header();
+}
+
+#[cfg(feature = "c")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set");
+    println!("cargo:rerun-if-changed=src/c_api.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    cbindgen::generate(&crate_dir)
+        .expect("generating the C header")
+        .write_to_file(std::path::Path::new(&crate_dir).join("include/zantetsu.h"));
+}
@@ -0,0 +1,297 @@
+#ifndef ZANTETSU_H
+#define ZANTETSU_H
+
+/* Generated by cbindgen from src/c_api.rs; do not edit. */
+
+#include <stdint.h>
+
+/**
+ * Version of the C ABI: the exported functions, the layout of the
+ * `#[repr(C)]` types and the values of their enums. Raised by every change
+ * that would break a host built against an older header.
+ */
+#define ZANTETSU_ABI_VERSION 1
+
+/**
+ * Outcome of a C API call.
+ */
+typedef enum ZantetsuStatus {
+  /**
+   * The call succeeded.
+   */
+  ZANTETSU_STATUS_OK = 0,
+  /**
+   * A required pointer argument was null.
+   */
+  ZANTETSU_STATUS_NULL_POINTER = 1,
+  /**
+   * The input was not valid UTF-8.
+   */
+  ZANTETSU_STATUS_INVALID_UTF8 = 2,
+  /**
+   * The input was empty or whitespace-only.
+   */
+  ZANTETSU_STATUS_EMPTY_INPUT = 3,
+  /**
+   * No metadata could be extracted from the input.
+   */
+  ZANTETSU_STATUS_PARSE_FAILED = 4,
+  /**
+   * The parser could not be created.
+   */
+  ZANTETSU_STATUS_INIT_FAILED = 5,
+  /**
+   * An internal error; the library is still usable.
+   */
+  ZANTETSU_STATUS_INTERNAL = 6,
+  /**
+   * The neural model could not be loaded.
+   */
+  ZANTETSU_STATUS_MODEL_LOAD_FAILED = 7,
+  /**
+   * The neural model failed while parsing.
+   */
+  ZANTETSU_STATUS_INFERENCE_FAILED = 8,
+  /**
+   * An argument was out of range, e.g. an unknown parse mode.
+   */
+  ZANTETSU_STATUS_INVALID_ARGUMENT = 9,
+} ZantetsuStatus;
+
+/**
+ * Video resolution; `None` when not found.
+ */
+typedef enum ZantetsuResolution {
+  ZANTETSU_RESOLUTION_NONE = 0,
+  ZANTETSU_RESOLUTION_SD480 = 1,
+  ZANTETSU_RESOLUTION_HD720 = 2,
+  ZANTETSU_RESOLUTION_FHD1080 = 3,
+  ZANTETSU_RESOLUTION_UHD2160 = 4,
+} ZantetsuResolution;
+
+/**
+ * Video codec; `None` when not found.
+ */
+typedef enum ZantetsuVideoCodec {
+  ZANTETSU_VIDEO_CODEC_NONE = 0,
+  ZANTETSU_VIDEO_CODEC_H264 = 1,
+  ZANTETSU_VIDEO_CODEC_HEVC = 2,
+  ZANTETSU_VIDEO_CODEC_AV1 = 3,
+  ZANTETSU_VIDEO_CODEC_VP9 = 4,
+  ZANTETSU_VIDEO_CODEC_MPEG4 = 5,
+} ZantetsuVideoCodec;
+
+/**
+ * Audio codec; `None` when not found.
+ */
+typedef enum ZantetsuAudioCodec {
+  ZANTETSU_AUDIO_CODEC_NONE = 0,
+  ZANTETSU_AUDIO_CODEC_FLAC = 1,
+  ZANTETSU_AUDIO_CODEC_AAC = 2,
+  ZANTETSU_AUDIO_CODEC_OPUS = 3,
+  ZANTETSU_AUDIO_CODEC_AC3 = 4,
+  ZANTETSU_AUDIO_CODEC_DTS = 5,
+  ZANTETSU_AUDIO_CODEC_MP3 = 6,
+  ZANTETSU_AUDIO_CODEC_VORBIS = 7,
+  ZANTETSU_AUDIO_CODEC_TRUE_HD = 8,
+  ZANTETSU_AUDIO_CODEC_EAAC = 9,
+} ZantetsuAudioCodec;
+
+/**
+ * Release source; `None` when not found.
+ */
+typedef enum ZantetsuSource {
+  ZANTETSU_SOURCE_NONE = 0,
+  ZANTETSU_SOURCE_BLU_RAY_REMUX = 1,
+  ZANTETSU_SOURCE_BLU_RAY = 2,
+  ZANTETSU_SOURCE_WEB_DL = 3,
+  ZANTETSU_SOURCE_WEB_RIP = 4,
+  ZANTETSU_SOURCE_HDTV = 5,
+  ZANTETSU_SOURCE_DVD = 6,
+  ZANTETSU_SOURCE_LASER_DISC = 7,
+  ZANTETSU_SOURCE_VHS = 8,
+} ZantetsuSource;
+
+/**
+ * Parsing engine selection.
+ */
+typedef enum ZantetsuParseMode {
+  /**
+   * Neural CRF inference (requires model weights).
+   */
+  ZANTETSU_PARSE_MODE_FULL = 0,
+  /**
+   * Regex heuristics only.
+   */
+  ZANTETSU_PARSE_MODE_LIGHT = 1,
+  /**
+   * The model with a heuristic fallback.
+   */
+  ZANTETSU_PARSE_MODE_AUTO = 2,
+  /**
+   * Both engines, merged field by field.
+   */
+  ZANTETSU_PARSE_MODE_ENSEMBLE = 3,
+} ZantetsuParseMode;
+
+/**
+ * Opaque parser handle, created by [`zantetsu_parser_new`]. A parser may
+ * be shared between threads.
+ */
+typedef struct ZantetsuParser ZantetsuParser;
+
+/**
+ * Metadata extracted from a filename.
+ *
+ * Strings are NUL-terminated UTF-8 and null when the field was not found;
+ * numbers are `-1` when not found. Owned by the library: release with
+ * [`zantetsu_result_free`].
+ */
+typedef struct ZantetsuResult {
+  /**
+   * The `ZANTETSU_ABI_VERSION` this struct is laid out for; always the
+   * first field, so it can be checked before any other is read.
+   */
+  uint32_t abi_version;
+  /**
+   * The parsed input.
+   */
+  char *input;
+  char *title;
+  char *group;
+  /**
+   * Episode as written in canonical form (`05`, `01-12`, `12v2`, `SP01`).
+   */
+  char *episode;
+  /**
+   * First and last episode covered; both `-1` for specials.
+   */
+  int32_t episode_first;
+  int32_t episode_last;
+  /**
+   * First and last season covered.
+   */
+  int32_t season_first;
+  int32_t season_last;
+  enum ZantetsuResolution resolution;
+  enum ZantetsuVideoCodec video_codec;
+  enum ZantetsuAudioCodec audio_codec;
+  enum ZantetsuSource source;
+  int32_t year;
+  /**
+   * CRC32 checksum as 8 hex digits.
+   */
+  char *crc32;
+  /**
+   * File extension without the leading dot.
+   */
+  char *extension;
+  /**
+   * Release version (`v2` is 2).
+   */
+  int32_t version;
+  /**
+   * Confidence in `[0, 1]`.
+   */
+  float confidence;
+  /**
+   * Mode of the engine that produced the result.
+   */
+  enum ZantetsuParseMode parse_mode;
+} ZantetsuResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a parser running in `mode`, a [`ZantetsuParseMode`] value, and
+ * store it in `*out`. Any other `mode` returns
+ * [`ZantetsuStatus::InvalidArgument`].
+ *
+ * The model loads from `$ZANTETSU_MODEL_DIR`, else `models/ner_model`;
+ * without model weights, the model-based modes fall back to the heuristic
+ * engine. Release the parser with [`zantetsu_parser_free`].
+ *
+ * # Safety
+ *
+ * `out` must be null or valid for a pointer write.
+ */
+enum ZantetsuStatus zantetsu_parser_new(uint32_t mode, struct ZantetsuParser **out);
+
+/**
+ * Release a parser. Null is ignored.
+ *
+ * # Safety
+ *
+ * `parser` must be null or a pointer from [`zantetsu_parser_new`] that
+ * has not been freed.
+ */
+void zantetsu_parser_free(struct ZantetsuParser *parser);
+
+/**
+ * Parse `input` and store the result in `*out`. Release it with
+ * [`zantetsu_result_free`].
+ *
+ * # Safety
+ *
+ * `parser` must be a live parser, `input` a NUL-terminated string and
+ * `out` valid for a pointer write; null pointers are reported as
+ * [`ZantetsuStatus::NullPointer`].
+ */
+enum ZantetsuStatus zantetsu_parse(const struct ZantetsuParser *parser,
+                                   const char *input,
+                                   struct ZantetsuResult **out);
+
+/**
+ * Release a result and its strings. Null is ignored.
+ *
+ * # Safety
+ *
+ * `result` must be null or a pointer from [`zantetsu_parse`] that has not
+ * been freed.
+ */
+void zantetsu_result_free(struct ZantetsuResult *result);
+
+/**
+ * Parse `input` and store the full result, every field the Rust API
+ * reports, as a JSON object in `*out`. Release it with
+ * [`zantetsu_string_free`].
+ *
+ * # Safety
+ *
+ * As for [`zantetsu_parse`].
+ */
+enum ZantetsuStatus zantetsu_parse_json(const struct ZantetsuParser *parser,
+                                        const char *input,
+                                        char **out);
+
+/**
+ * Release a string returned by the library. Null is ignored.
+ *
+ * # Safety
+ *
+ * `string` must be null or a string from this library that has not been
+ * freed.
+ */
+void zantetsu_string_free(char *string);
+
+/**
+ * The `ZANTETSU_ABI_VERSION` the library was built with; a host may only
+ * use the library when it equals the version of its header.
+ */
+uint32_t zantetsu_abi_version(void);
+
+/**
+ * Message of the last error on the calling thread, or an empty string.
+ *
+ * The pointer stays valid until the next failing call on the same thread;
+ * do not free it.
+ */
+const char *zantetsu_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ZANTETSU_H */
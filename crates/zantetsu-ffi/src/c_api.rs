@@ -0,0 +1,641 @@
+//! C bindings.
+//!
+//! An `extern "C"` surface over the unified parser for C and C++ hosts
+//! (Kodi add-ons, native helpers of mpv scripts, media servers). The header
+//! `include/zantetsu.h` is generated from this module by cbindgen when the
+//! `c` feature is built.
+//!
+//! Every symbol carries the `zantetsu_` prefix and every type the `Zantetsu`
+//! prefix. Hosts that load the library at run time compare
+//! [`zantetsu_abi_version`] with the `ZANTETSU_ABI_VERSION` of the header
+//! they were built against before calling anything else, and refuse a
+//! mismatch instead of misreading a struct.
+//!
+//! Every function returns a [`ZantetsuStatus`]; on failure,
+//! [`zantetsu_last_error`] describes the error. Objects and strings handed
+//! out by the library are released with their `_free` function.
+//!
+//! # Example
+//!
+//! ```c
+//! #include "zantetsu.h"
+//!
+//! if (zantetsu_abi_version() != ZANTETSU_ABI_VERSION) {
+//!     fprintf(stderr, "incompatible libzantetsu_ffi\n");
+//!     return 1;
+//! }
+//!
+//! ZantetsuParser *parser = NULL;
+//! if (zantetsu_parser_new(ZANTETSU_PARSE_MODE_AUTO, &parser) != ZANTETSU_STATUS_OK) {
+//!     fprintf(stderr, "%s\n", zantetsu_last_error());
+//!     return 1;
+//! }
+//!
+//! ZantetsuResult *result = NULL;
+//! if (zantetsu_parse(parser, "[SubsPlease] Frieren - 05 (1080p).mkv", &result) == ZANTETSU_STATUS_OK) {
+//!     printf("%s episode %d\n", result->title, result->episode_first);
+//!     zantetsu_result_free(result);
+//! }
+//! zantetsu_parser_free(parser);
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+use zantetsu_core::{
+    AudioCodec, EpisodeSpec, MediaSource, ParseMode, ParseResult, Resolution, SeasonSpec,
+    VideoCodec, ZantetsuError,
+};
+
+use crate::engine::{Parser, ParserConfig};
+
+/// Version of the C ABI: the exported functions, the layout of the
+/// `#[repr(C)]` types and the values of their enums. Raised by every change
+/// that would break a host built against an older header.
+pub const ZANTETSU_ABI_VERSION: u32 = 1;
+
+/// Outcome of a C API call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZantetsuStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// The input was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The input was empty or whitespace-only.
+    EmptyInput = 3,
+    /// No metadata could be extracted from the input.
+    ParseFailed = 4,
+    /// The parser could not be created.
+    InitFailed = 5,
+    /// An internal error; the library is still usable.
+    Internal = 6,
+    /// The neural model could not be loaded.
+    ModelLoadFailed = 7,
+    /// The neural model failed while parsing.
+    InferenceFailed = 8,
+    /// An argument was out of range, e.g. an unknown parse mode.
+    InvalidArgument = 9,
+}
+
+impl ZantetsuStatus {
+    /// The status a core error maps to; `fallback` covers the errors
+    /// without a status of their own.
+    fn of(error: &ZantetsuError, fallback: Self) -> Self {
+        match error {
+            ZantetsuError::EmptyInput => Self::EmptyInput,
+            ZantetsuError::ModelLoadError(_) => Self::ModelLoadFailed,
+            ZantetsuError::InferenceError(_)
+            | ZantetsuError::NeuralParser(_)
+            | ZantetsuError::CandleError(_) => Self::InferenceFailed,
+            _ => fallback,
+        }
+    }
+}
+
+/// Parsing engine selection.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZantetsuParseMode {
+    /// Neural CRF inference (requires model weights).
+    Full = 0,
+    /// Regex heuristics only.
+    Light = 1,
+    /// The model with a heuristic fallback.
+    Auto = 2,
+    /// Both engines, merged field by field.
+    Ensemble = 3,
+}
+
+/// Video resolution; `None` when not found.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZantetsuResolution {
+    None = 0,
+    Sd480 = 1,
+    Hd720 = 2,
+    Fhd1080 = 3,
+    Uhd2160 = 4,
+}
+
+/// Video codec; `None` when not found.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZantetsuVideoCodec {
+    None = 0,
+    H264 = 1,
+    Hevc = 2,
+    Av1 = 3,
+    Vp9 = 4,
+    Mpeg4 = 5,
+}
+
+/// Audio codec; `None` when not found.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZantetsuAudioCodec {
+    None = 0,
+    Flac = 1,
+    Aac = 2,
+    Opus = 3,
+    Ac3 = 4,
+    Dts = 5,
+    Mp3 = 6,
+    Vorbis = 7,
+    TrueHd = 8,
+    Eaac = 9,
+}
+
+/// Release source; `None` when not found.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZantetsuSource {
+    None = 0,
+    BluRayRemux = 1,
+    BluRay = 2,
+    WebDl = 3,
+    WebRip = 4,
+    Hdtv = 5,
+    Dvd = 6,
+    LaserDisc = 7,
+    Vhs = 8,
+}
+
+/// Opaque parser handle, created by [`zantetsu_parser_new`]. A parser may
+/// be shared between threads.
+pub struct ZantetsuParser {
+    inner: Parser,
+}
+
+/// Metadata extracted from a filename.
+///
+/// Strings are NUL-terminated UTF-8 and null when the field was not found;
+/// numbers are `-1` when not found. Owned by the library: release with
+/// [`zantetsu_result_free`].
+#[repr(C)]
+pub struct ZantetsuResult {
+    /// The `ZANTETSU_ABI_VERSION` this struct is laid out for; always the
+    /// first field, so it can be checked before any other is read.
+    pub abi_version: u32,
+    /// The parsed input.
+    pub input: *mut c_char,
+    pub title: *mut c_char,
+    pub group: *mut c_char,
+    /// Episode as written in canonical form (`05`, `01-12`, `12v2`, `SP01`).
+    pub episode: *mut c_char,
+    /// First and last episode covered; both `-1` for specials.
+    pub episode_first: i32,
+    pub episode_last: i32,
+    /// First and last season covered.
+    pub season_first: i32,
+    pub season_last: i32,
+    pub resolution: ZantetsuResolution,
+    pub video_codec: ZantetsuVideoCodec,
+    pub audio_codec: ZantetsuAudioCodec,
+    pub source: ZantetsuSource,
+    pub year: i32,
+    /// CRC32 checksum as 8 hex digits.
+    pub crc32: *mut c_char,
+    /// File extension without the leading dot.
+    pub extension: *mut c_char,
+    /// Release version (`v2` is 2).
+    pub version: i32,
+    /// Confidence in `[0, 1]`.
+    pub confidence: f32,
+    /// Mode of the engine that produced the result.
+    pub parse_mode: ZantetsuParseMode,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Record `message` as the thread's last error and return `status`.
+fn fail(status: ZantetsuStatus, message: impl Into<String>) -> ZantetsuStatus {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+/// Run `body`, turning a panic into [`ZantetsuStatus::Internal`] instead of
+/// unwinding into C.
+fn guard(body: impl FnOnce() -> ZantetsuStatus) -> ZantetsuStatus {
+    catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|_| fail(ZantetsuStatus::Internal, "internal panic"))
+}
+
+fn parse_error(error: ZantetsuError) -> ZantetsuStatus {
+    fail(
+        ZantetsuStatus::of(&error, ZantetsuStatus::ParseFailed),
+        error.to_string(),
+    )
+}
+
+/// Owned C copy of `value`, or null.
+fn c_string(value: Option<String>) -> *mut c_char {
+    value
+        .and_then(|value| CString::new(value).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn number(value: Option<impl Into<i64>>) -> i32 {
+    value
+        .and_then(|value| i32::try_from(value.into()).ok())
+        .unwrap_or(-1)
+}
+
+impl From<ZantetsuParseMode> for ParseMode {
+    fn from(mode: ZantetsuParseMode) -> Self {
+        match mode {
+            ZantetsuParseMode::Full => Self::Full,
+            ZantetsuParseMode::Light => Self::Light,
+            ZantetsuParseMode::Auto => Self::Auto,
+            ZantetsuParseMode::Ensemble => Self::Ensemble,
+        }
+    }
+}
+
+impl TryFrom<u32> for ZantetsuParseMode {
+    type Error = u32;
+
+    fn try_from(mode: u32) -> Result<Self, u32> {
+        Ok(match mode {
+            0 => Self::Full,
+            1 => Self::Light,
+            2 => Self::Auto,
+            3 => Self::Ensemble,
+            _ => return Err(mode),
+        })
+    }
+}
+
+impl From<ParseMode> for ZantetsuParseMode {
+    fn from(mode: ParseMode) -> Self {
+        match mode {
+            ParseMode::Full => Self::Full,
+            ParseMode::Light => Self::Light,
+            ParseMode::Auto => Self::Auto,
+            ParseMode::Ensemble => Self::Ensemble,
+        }
+    }
+}
+
+impl From<ParseResult> for ZantetsuResult {
+    fn from(result: ParseResult) -> Self {
+        let (episode_first, episode_last) = match &result.episode {
+            Some(EpisodeSpec::Single(episode)) | Some(EpisodeSpec::Version { episode, .. }) => {
+                (Some(*episode), Some(*episode))
+            }
+            Some(EpisodeSpec::Range(first, last)) => (Some(*first), Some(*last)),
+            Some(EpisodeSpec::Multi(episodes)) => (
+                episodes.iter().copied().min(),
+                episodes.iter().copied().max(),
+            ),
+            Some(EpisodeSpec::Zero) => (Some(0), Some(0)),
+            Some(EpisodeSpec::Special(_)) | None => (None, None),
+        };
+        let (season_first, season_last) = match result.season {
+            Some(SeasonSpec::Single(season)) => (Some(season), Some(season)),
+            Some(SeasonSpec::Range(first, last)) => (Some(first), Some(last)),
+            None => (None, None),
+        };
+
+        Self {
+            abi_version: ZANTETSU_ABI_VERSION,
+            episode: c_string(result.episode.as_ref().map(ToString::to_string)),
+            episode_first: number(episode_first),
+            episode_last: number(episode_last),
+            season_first: number(season_first),
+            season_last: number(season_last),
+            resolution: match result.resolution {
+                None => ZantetsuResolution::None,
+                Some(Resolution::SD480) => ZantetsuResolution::Sd480,
+                Some(Resolution::HD720) => ZantetsuResolution::Hd720,
+                Some(Resolution::FHD1080) => ZantetsuResolution::Fhd1080,
+                Some(Resolution::UHD2160) => ZantetsuResolution::Uhd2160,
+            },
+            video_codec: match result.video_codec {
+                None => ZantetsuVideoCodec::None,
+                Some(VideoCodec::H264) => ZantetsuVideoCodec::H264,
+                Some(VideoCodec::HEVC) => ZantetsuVideoCodec::Hevc,
+                Some(VideoCodec::AV1) => ZantetsuVideoCodec::Av1,
+                Some(VideoCodec::VP9) => ZantetsuVideoCodec::Vp9,
+                Some(VideoCodec::MPEG4) => ZantetsuVideoCodec::Mpeg4,
+            },
+            audio_codec: match result.audio_codec {
+                None => ZantetsuAudioCodec::None,
+                Some(AudioCodec::FLAC) => ZantetsuAudioCodec::Flac,
+                Some(AudioCodec::AAC) => ZantetsuAudioCodec::Aac,
+                Some(AudioCodec::Opus) => ZantetsuAudioCodec::Opus,
+                Some(AudioCodec::AC3) => ZantetsuAudioCodec::Ac3,
+                Some(AudioCodec::DTS) => ZantetsuAudioCodec::Dts,
+                Some(AudioCodec::MP3) => ZantetsuAudioCodec::Mp3,
+                Some(AudioCodec::Vorbis) => ZantetsuAudioCodec::Vorbis,
+                Some(AudioCodec::TrueHD) => ZantetsuAudioCodec::TrueHd,
+                Some(AudioCodec::EAAC) => ZantetsuAudioCodec::Eaac,
+            },
+            source: match result.source {
+                None => ZantetsuSource::None,
+                Some(MediaSource::BluRayRemux) => ZantetsuSource::BluRayRemux,
+                Some(MediaSource::BluRay) => ZantetsuSource::BluRay,
+                Some(MediaSource::WebDL) => ZantetsuSource::WebDl,
+                Some(MediaSource::WebRip) => ZantetsuSource::WebRip,
+                Some(MediaSource::HDTV) => ZantetsuSource::Hdtv,
+                Some(MediaSource::DVD) => ZantetsuSource::Dvd,
+                Some(MediaSource::LaserDisc) => ZantetsuSource::LaserDisc,
+                Some(MediaSource::VHS) => ZantetsuSource::Vhs,
+            },
+            year: number(result.year),
+            version: number(result.version),
+            confidence: result.confidence,
+            parse_mode: result.parse_mode.into(),
+            input: c_string(Some(result.input)),
+            title: c_string(result.title),
+            group: c_string(result.group),
+            crc32: c_string(result.crc32),
+            extension: c_string(result.extension),
+        }
+    }
+}
+
+/// Borrow `input` as UTF-8.
+///
+/// # Safety
+///
+/// `input` must be null or point to a NUL-terminated string.
+unsafe fn input_str<'a>(input: *const c_char) -> Result<&'a str, ZantetsuStatus> {
+    if input.is_null() {
+        return Err(fail(ZantetsuStatus::NullPointer, "input is null"));
+    }
+    // SAFETY: non-null and NUL-terminated per the caller's contract.
+    unsafe { CStr::from_ptr(input) }
+        .to_str()
+        .map_err(|e| fail(ZantetsuStatus::InvalidUtf8, e.to_string()))
+}
+
+/// Create a parser running in `mode`, a [`ZantetsuParseMode`] value, and
+/// store it in `*out`. Any other `mode` returns
+/// [`ZantetsuStatus::InvalidArgument`].
+///
+/// The model loads from `$ZANTETSU_MODEL_DIR`, else `models/ner_model`;
+/// without model weights, the model-based modes fall back to the heuristic
+/// engine. Release the parser with [`zantetsu_parser_free`].
+///
+/// # Safety
+///
+/// `out` must be null or valid for a pointer write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zantetsu_parser_new(
+    mode: u32,
+    out: *mut *mut ZantetsuParser,
+) -> ZantetsuStatus {
+    guard(|| {
+        if out.is_null() {
+            return fail(ZantetsuStatus::NullPointer, "out is null");
+        }
+        let Ok(mode) = ZantetsuParseMode::try_from(mode) else {
+            return fail(
+                ZantetsuStatus::InvalidArgument,
+                format!("unknown parse mode {mode}"),
+            );
+        };
+        match Parser::new(ParserConfig::new().with_mode(mode.into())) {
+            Ok(inner) => {
+                // SAFETY: checked non-null; valid for writes per the contract.
+                unsafe { *out = Box::into_raw(Box::new(ZantetsuParser { inner })) };
+                ZantetsuStatus::Ok
+            }
+            Err(e) => fail(
+                ZantetsuStatus::of(&e, ZantetsuStatus::InitFailed),
+                e.to_string(),
+            ),
+        }
+    })
+}
+
+/// Release a parser. Null is ignored.
+///
+/// # Safety
+///
+/// `parser` must be null or a pointer from [`zantetsu_parser_new`] that
+/// has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zantetsu_parser_free(parser: *mut ZantetsuParser) {
+    if !parser.is_null() {
+        // SAFETY: allocated by `zantetsu_parser_new` and not yet freed.
+        drop(unsafe { Box::from_raw(parser) });
+    }
+}
+
+/// Parse `input` and store the result in `*out`. Release it with
+/// [`zantetsu_result_free`].
+///
+/// # Safety
+///
+/// `parser` must be a live parser, `input` a NUL-terminated string and
+/// `out` valid for a pointer write; null pointers are reported as
+/// [`ZantetsuStatus::NullPointer`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zantetsu_parse(
+    parser: *const ZantetsuParser,
+    input: *const c_char,
+    out: *mut *mut ZantetsuResult,
+) -> ZantetsuStatus {
+    guard(|| {
+        if parser.is_null() || out.is_null() {
+            return fail(ZantetsuStatus::NullPointer, "parser or out is null");
+        }
+        // SAFETY: per the contract.
+        let input = match unsafe { input_str(input) } {
+            Ok(input) => input,
+            Err(status) => return status,
+        };
+        // SAFETY: checked non-null; live per the contract.
+        match unsafe { &*parser }.inner.parse(input) {
+            Ok(result) => {
+                // SAFETY: checked non-null; valid for writes per the contract.
+                unsafe { *out = Box::into_raw(Box::new(ZantetsuResult::from(result))) };
+                ZantetsuStatus::Ok
+            }
+            Err(e) => parse_error(e),
+        }
+    })
+}
+
+/// Release a result and its strings. Null is ignored.
+///
+/// # Safety
+///
+/// `result` must be null or a pointer from [`zantetsu_parse`] that has not
+/// been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zantetsu_result_free(result: *mut ZantetsuResult) {
+    if result.is_null() {
+        return;
+    }
+    // SAFETY: allocated by `zantetsu_parse` and not yet freed.
+    let result = unsafe { Box::from_raw(result) };
+    for string in [
+        result.input,
+        result.title,
+        result.group,
+        result.episode,
+        result.crc32,
+        result.extension,
+    ] {
+        // SAFETY: each string is null or from `CString::into_raw`.
+        unsafe { zantetsu_string_free(string) };
+    }
+}
+
+/// Parse `input` and store the full result, every field the Rust API
+/// reports, as a JSON object in `*out`. Release it with
+/// [`zantetsu_string_free`].
+///
+/// # Safety
+///
+/// As for [`zantetsu_parse`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zantetsu_parse_json(
+    parser: *const ZantetsuParser,
+    input: *const c_char,
+    out: *mut *mut c_char,
+) -> ZantetsuStatus {
+    guard(|| {
+        if parser.is_null() || out.is_null() {
+            return fail(ZantetsuStatus::NullPointer, "parser or out is null");
+        }
+        // SAFETY: per the contract.
+        let input = match unsafe { input_str(input) } {
+            Ok(input) => input,
+            Err(status) => return status,
+        };
+        // SAFETY: checked non-null; live per the contract.
+        match unsafe { &*parser }.inner.parse_json(input) {
+            Ok(json) => {
+                // SAFETY: checked non-null; valid for writes per the contract.
+                unsafe { *out = c_string(Some(json)) };
+                ZantetsuStatus::Ok
+            }
+            Err(e) => parse_error(e),
+        }
+    })
+}
+
+/// Release a string returned by the library. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or a string from this library that has not been
+/// freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn zantetsu_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: created by `CString::into_raw` and not yet freed.
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// The `ZANTETSU_ABI_VERSION` the library was built with; a host may only
+/// use the library when it equals the version of its header.
+#[unsafe(no_mangle)]
+pub extern "C" fn zantetsu_abi_version() -> u32 {
+    ZANTETSU_ABI_VERSION
+}
+
+/// Message of the last error on the calling thread, or an empty string.
+///
+/// The pointer stays valid until the next failing call on the same thread;
+/// do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn zantetsu_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(text: &str) -> CString {
+        CString::new(text).unwrap()
+    }
+
+    fn string(value: *const c_char) -> Option<String> {
+        (!value.is_null()).then(|| {
+            unsafe { CStr::from_ptr(value) }
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+
+    #[test]
+    fn parses_through_the_c_api() {
+        let mut parser = ptr::null_mut();
+        let status = unsafe { zantetsu_parser_new(ZantetsuParseMode::Light as u32, &mut parser) };
+        assert_eq!(status, ZantetsuStatus::Ok);
+
+        let input = c("[SubsPlease] Frieren - 05 (1080p) [ABCD1234].mkv");
+        let mut result = ptr::null_mut();
+        let status = unsafe { zantetsu_parse(parser, input.as_ptr(), &mut result) };
+        assert_eq!(status, ZantetsuStatus::Ok);
+        let parsed = unsafe { &*result };
+        assert_eq!(parsed.abi_version, zantetsu_abi_version());
+        assert_eq!(string(parsed.title).as_deref(), Some("Frieren"));
+        assert_eq!(string(parsed.group).as_deref(), Some("SubsPlease"));
+        assert_eq!(string(parsed.episode).as_deref(), Some("05"));
+        assert_eq!((parsed.episode_first, parsed.episode_last), (5, 5));
+        assert_eq!(parsed.season_first, -1);
+        assert_eq!(parsed.resolution, ZantetsuResolution::Fhd1080);
+        assert_eq!(parsed.video_codec, ZantetsuVideoCodec::None);
+        assert_eq!(string(parsed.crc32).as_deref(), Some("ABCD1234"));
+        assert_eq!(parsed.parse_mode, ZantetsuParseMode::Light);
+        unsafe { zantetsu_result_free(result) };
+
+        let mut json = ptr::null_mut();
+        let status = unsafe { zantetsu_parse_json(parser, input.as_ptr(), &mut json) };
+        assert_eq!(status, ZantetsuStatus::Ok);
+        let value: serde_json::Value = serde_json::from_str(&string(json).unwrap()).unwrap();
+        assert_eq!(value["title"], "Frieren");
+        unsafe { zantetsu_string_free(json) };
+
+        unsafe { zantetsu_parser_free(parser) };
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut parser = ptr::null_mut();
+        unsafe { zantetsu_parser_new(ZantetsuParseMode::Light as u32, &mut parser) };
+        let mut result = ptr::null_mut();
+
+        let empty = c("  ");
+        let status = unsafe { zantetsu_parse(parser, empty.as_ptr(), &mut result) };
+        assert_eq!(status, ZantetsuStatus::EmptyInput);
+        assert!(result.is_null());
+        assert!(!string(zantetsu_last_error()).unwrap().is_empty());
+
+        let status = unsafe { zantetsu_parse(parser, ptr::null(), &mut result) };
+        assert_eq!(status, ZantetsuStatus::NullPointer);
+        let invalid = [0xffu8, 0];
+        let status = unsafe { zantetsu_parse(parser, invalid.as_ptr().cast(), &mut result) };
+        assert_eq!(status, ZantetsuStatus::InvalidUtf8);
+
+        unsafe { zantetsu_parser_free(parser) };
+        unsafe { zantetsu_parser_free(ptr::null_mut()) };
+        unsafe { zantetsu_result_free(ptr::null_mut()) };
+    }
+    #[test]
+    fn rejects_unknown_parse_modes() {
+        let mut parser = ptr::null_mut();
+        let status = unsafe { zantetsu_parser_new(4, &mut parser) };
+        assert_eq!(status, ZantetsuStatus::InvalidArgument);
+        assert!(parser.is_null());
+        assert_eq!(
+            string(zantetsu_last_error()).as_deref(),
+            Some("unknown parse mode 4")
+        );
+    }
+}
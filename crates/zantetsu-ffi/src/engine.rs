@@ -0,0 +1,95 @@
+//! The parser behind the Node, Python and C bindings.
+//!
+//! With the `neural` feature (the default) this is the core's unified
+//! [`Parser`]. Without it, a stand-in with the same surface runs the heuristic
+//! engine alone, so the bindings build without candle or tokenizers and the
+//! native library shrinks to the regex engine.
+
+#[cfg(feature = "neural")]
+pub use zantetsu_core::{Parser, ParserConfig};
+
+#[cfg(not(feature = "neural"))]
+pub use slim::{Parser, ParserConfig};
+
+// Each binding uses part of the surface.
+#[cfg(not(feature = "neural"))]
+#[allow(dead_code)]
+mod slim {
+    use std::path::PathBuf;
+
+    use zantetsu_core::{HeuristicParser, ParseMode, ParseResult, Result, ZantetsuError};
+
+    /// The settings of the unified parser that a heuristic-only build can
+    /// honor; the model settings are accepted and ignored.
+    #[derive(Debug, Clone, Default)]
+    pub struct ParserConfig {
+        /// Which parsing mode to use; every mode but `Full` runs the
+        /// heuristic engine.
+        pub mode: ParseMode,
+    }
+
+    impl ParserConfig {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn with_mode(mut self, mode: ParseMode) -> Self {
+            self.mode = mode;
+            self
+        }
+
+        /// Ignored: there is no model to fall back from.
+        pub fn with_confidence_threshold(self, _threshold: f32) -> Self {
+            self
+        }
+
+        /// Ignored: there is no model to load.
+        pub fn with_model_dir(self, _dir: impl Into<PathBuf>) -> Self {
+            self
+        }
+
+        /// Ignored: there is no model to download.
+        pub fn with_model_repo(self, _repo: impl Into<String>) -> Self {
+            self
+        }
+    }
+
+    /// The unified parser of a build without the neural engine: `Auto` and
+    /// `Ensemble` behave as when no model is installed.
+    pub struct Parser {
+        heuristic: HeuristicParser,
+        config: ParserConfig,
+    }
+
+    impl Parser {
+        /// Fails with [`ZantetsuError::ModelLoadError`] in `Full` mode,
+        /// which needs the model.
+        pub fn new(config: ParserConfig) -> Result<Self> {
+            if config.mode == ParseMode::Full {
+                return Err(ZantetsuError::ModelLoadError(
+                    "Full mode needs a build with the `neural` feature".into(),
+                ));
+            }
+            Ok(Self {
+                heuristic: HeuristicParser::new()?,
+                config,
+            })
+        }
+
+        pub fn parse(&self, input: &str) -> Result<ParseResult> {
+            self.heuristic.parse(input)
+        }
+
+        pub fn parse_json(&self, input: &str) -> Result<String> {
+            self.heuristic.parse_json(input)
+        }
+
+        pub fn has_neural(&self) -> bool {
+            false
+        }
+
+        pub fn config(&self) -> &ParserConfig {
+            &self.config
+        }
+    }
+}
@@ -2,16 +2,30 @@
 //!
 //! Multi-language FFI bindings for Zantetsu.
 //! Exports the core parsing engine to TypeScript/Node (napi-rs),
-//! Python (PyO3), and C/C++ (cbindgen).
+//! Python (PyO3), C/C++ (cbindgen) and WebAssembly (wasm-bindgen).
 //!
 //! Crates:
 //! - [`zantetsu`](https://docs.rs/zantetsu) - unified API surface
 //! - [`zantetsu-core`](https://docs.rs/zantetsu-core) - parsing engine
 //! - [`zantetsu-vecdb`](https://docs.rs/zantetsu-vecdb) - canonical title matching
 //! - [`zantetsu-trainer`](https://docs.rs/zantetsu-trainer) - training workflows
-//! - [`zantetsu-ffi`](https://docs.rs/zantetsu-ffi) - Node/Python/C bindings
+//! - [`zantetsu-ffi`](https://docs.rs/zantetsu-ffi) - Node/Python/C/WebAssembly bindings
 //!
-//! Enable `node` or `python` features depending on the target binding surface you need.
+//! Enable the `node`, `python`, `c` or `wasm` feature depending on the target
+//! binding surface you need. The `c` feature also generates
+//! `include/zantetsu.h`; the `wasm` feature builds the heuristic parser alone,
+//! for `wasm32-unknown-unknown`.
+//!
+//! The default `neural` feature links the neural engine (candle and
+//! tokenizers). Build with `--no-default-features` for a heuristic-only
+//! library a fraction of the size; `Full` mode is then unavailable and the
+//! other modes run the heuristic engine.
+
+#[cfg(feature = "c")]
+pub mod c_api;
+
+#[cfg(any(feature = "node", feature = "python", feature = "c"))]
+mod engine;
 
 #[cfg(feature = "node")]
 mod node;
@@ -19,20 +33,31 @@ mod node;
 #[cfg(feature = "python")]
 mod python;
 
+#[cfg(feature = "wasm")]
+mod wasm;
+
 // Re-export for node bindings
 pub use zantetsu_core::{HeuristicParser, ParseResult};
 
 #[cfg(feature = "node")]
-pub use node::HeuristicParserNode;
+pub use node::{
+    ClientContextNode, HeuristicParserNode, QualityProfileNode, QualityScoresNode, RankedResult,
+    ZantetsuNode, rank,
+};
+
+#[cfg(feature = "wasm")]
+pub use wasm::HeuristicParserWasm;
 
 #[cfg(feature = "python")]
-pub use python::HeuristicParserPy;
+pub use python::{HeuristicParserPy, ParseResultPy, ParserPy};
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+/// The `zantetsu._zantetsu` extension module, re-exported by the `zantetsu`
+/// Python package in `bindings/python`.
 #[cfg(feature = "python")]
 #[pymodule]
-fn zantetsu(m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn _zantetsu(m: &Bound<'_, PyModule>) -> PyResult<()> {
     python::pymodule(m)
 }
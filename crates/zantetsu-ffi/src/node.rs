@@ -2,14 +2,31 @@
 //!
 //! This module provides the primary interface for using Zantetsu
 //! from Node.js applications via the `zantetsu` npm package.
+//!
+//! Errors raised from a `ZantetsuError` carry its stable code (see
+//! `ZantetsuError::code`) as `err.code`, so callers can tell an empty input
+//! from a model that failed to load without matching on messages; invalid
+//! arguments to the bindings themselves use `INVALID_ARGUMENT`.
+//!
+//! Every `worker_thread` loads its own instance of the addon, and native
+//! objects cannot be posted between threads. To use one parser (and one
+//! loaded model) from several workers, `share()` it and rebuild it in each
+//! worker with `fromShared(handle)`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, Weak};
 
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
 use zantetsu_core::{
-    HeuristicParser, ParseResult,
-    types::{AudioCodec, EpisodeSpec, MediaSource, ParseMode, Resolution, VideoCodec},
+    ConfigError, HeuristicParser, ParseResult, ZantetsuError,
+    scoring::{self, ClientContext, Preset, QualityProfile, QualityScores},
+    types::{AudioCodec, EpisodeSpec, MediaSource, ParseMode, Resolution, SeasonSpec, VideoCodec},
 };
 
+use crate::engine::{Parser, ParserConfig};
+
 /// NAPI wrapper for the HeuristicParser.
 ///
 /// Create an instance to parse anime filenames using the fast
@@ -24,46 +41,448 @@ use zantetsu_core::{
 /// const result = parser.parse('[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv');
 ///
 /// console.log(result.title); // 'Jujutsu Kaisen'
-/// console.log(result.episode); // '24'
+/// console.log(result.episode); // { kind: 'single', value: 24 }
 /// console.log(result.resolution); // 'FHD1080'
 /// ```
-#[napi]
+#[napi(js_name = "HeuristicParser")]
 pub struct HeuristicParserNode {
-    inner: HeuristicParser,
+    inner: Arc<HeuristicParser>,
 }
 
 #[napi]
 impl HeuristicParserNode {
     #[napi(constructor)]
-    pub fn new() -> Result<Self> {
-        let inner = HeuristicParser::new().map_err(|e| {
-            Error::new(
-                Status::GenericFailure,
-                format!("failed to create parser: {}", e),
-            )
-        })?;
-        Ok(Self { inner })
+    pub fn new() -> Result<Self, &'static str> {
+        let inner = HeuristicParser::new().map_err(coded)?;
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
     }
 
+    /// A handle to this parser that another `worker_thread` can pass to
+    /// `fromShared`; valid while this parser is alive.
     #[napi]
-    pub fn parse(&self, input: String) -> Result<ParseResultNode> {
-        let result = self
-            .inner
-            .parse(&input)
-            .map_err(|e| Error::new(Status::GenericFailure, format!("parse error: {}", e)))?;
+    pub fn share(&self) -> u32 {
+        SHARED_HEURISTIC.share(&self.inner)
+    }
+
+    /// The parser behind a handle from `share()`, typically posted to this
+    /// worker by the thread that created it.
+    #[napi(factory)]
+    pub fn from_shared(handle: u32) -> Result<Self, &'static str> {
+        Ok(Self {
+            inner: SHARED_HEURISTIC.get(handle)?,
+        })
+    }
+
+    #[napi]
+    pub fn parse(&self, input: String) -> Result<ParseResultNode, &'static str> {
+        let result = self.inner.parse(&input).map_err(coded)?;
+        Ok(ParseResultNode::from(result))
+    }
+
+    /// Parse into the JSON of the full Rust `ParseResult`, every field
+    /// included, for callers that forward or store it as is.
+    #[napi]
+    pub fn parse_json(&self, input: String) -> Result<String, &'static str> {
+        self.inner.parse_json(&input).map_err(coded)
+    }
+
+    /// Parse many filenames in one call, across all cores when `parallel`
+    /// is true; failed parses are `null`.
+    #[napi]
+    pub fn parse_many(
+        &self,
+        inputs: Vec<String>,
+        parallel: Option<bool>,
+    ) -> Vec<Option<ParseResultNode>> {
+        parse_many(&inputs, parallel.unwrap_or(false), |input| {
+            self.inner.parse(input).ok()
+        })
+    }
+
+    /// Parse a buffer of newline-delimited filenames into a buffer of
+    /// newline-delimited JSON, one line per input (`null` for failures), so
+    /// huge batches cross into the addon as two buffers instead of one
+    /// string and one object per filename.
+    #[napi]
+    pub fn parse_buffer(&self, inputs: Buffer, parallel: Option<bool>) -> Buffer {
+        parse_buffer(&inputs, parallel.unwrap_or(false), |input| {
+            self.inner.parse(input).ok()
+        })
+    }
+}
+
+/// Options for [`ZantetsuNode`]; every field is optional.
+#[napi(object)]
+pub struct ZantetsuConfig {
+    /// `"full"`, `"light"`, `"auto"` (default) or `"ensemble"`.
+    pub mode: Option<String>,
+    /// Directory of the neural model (default: `$ZANTETSU_MODEL_DIR`, else
+    /// `models/ner_model`).
+    pub model_dir: Option<String>,
+    /// Hugging Face Hub repository (`owner/name[@revision]`) to download the
+    /// model from when no directory is set; needs the `hub` feature.
+    pub model_repo: Option<String>,
+    /// Model confidence below which auto mode falls back to the heuristic
+    /// parser (default: 0.6).
+    pub confidence_threshold: Option<f64>,
+}
+
+/// NAPI wrapper for the unified Parser.
+///
+/// Runs the neural model when it is available and falls back to the
+/// heuristic engine otherwise, as the Rust `Parser` does.
+///
+/// # Example
+///
+/// ```js
+/// const { Zantetsu } = require('zantetsu');
+///
+/// const parser = new Zantetsu({ mode: 'auto', modelDir: './models/ner_model' });
+/// const result = parser.parse('[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv');
+///
+/// console.log(result.title); // 'Jujutsu Kaisen'
+/// console.log(result.parse_mode); // 'Full' when the model ran, else 'Light'
+/// ```
+#[napi(js_name = "Zantetsu")]
+pub struct ZantetsuNode {
+    inner: Arc<Parser>,
+}
+
+#[napi]
+impl ZantetsuNode {
+    #[napi(constructor)]
+    pub fn new(config: Option<ZantetsuConfig>) -> Result<Self, &'static str> {
+        let mut parser_config = ParserConfig::new();
+        if let Some(config) = config {
+            if let Some(mode) = config.mode {
+                parser_config = parser_config.with_mode(parse_mode_from_str(&mode)?);
+            }
+            if let Some(dir) = config.model_dir {
+                parser_config = parser_config.with_model_dir(dir);
+            }
+            if let Some(repo) = config.model_repo {
+                parser_config = parser_config.with_model_repo(repo);
+            }
+            if let Some(threshold) = config.confidence_threshold {
+                parser_config = parser_config.with_confidence_threshold(threshold as f32);
+            }
+        }
+        let inner = Parser::new(parser_config).map_err(coded)?;
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// The configured parse mode.
+    #[napi(getter)]
+    pub fn mode(&self) -> String {
+        parse_mode_to_string(self.inner.config().mode)
+    }
+
+    /// Whether the neural model loaded; without it every mode runs the
+    /// heuristic engine.
+    #[napi(getter)]
+    pub fn has_neural(&self) -> bool {
+        self.inner.has_neural()
+    }
+
+    /// A handle to this parser that another `worker_thread` can pass to
+    /// `fromShared`, so workers share one loaded model; valid while this
+    /// parser is alive.
+    #[napi]
+    pub fn share(&self) -> u32 {
+        SHARED_UNIFIED.share(&self.inner)
+    }
+
+    /// The parser behind a handle from `share()`, typically posted to this
+    /// worker by the thread that created it.
+    #[napi(factory)]
+    pub fn from_shared(handle: u32) -> Result<Self, &'static str> {
+        Ok(Self {
+            inner: SHARED_UNIFIED.get(handle)?,
+        })
+    }
+
+    #[napi]
+    pub fn parse(&self, input: String) -> Result<ParseResultNode, &'static str> {
+        let result = self.inner.parse(&input).map_err(coded)?;
         Ok(ParseResultNode::from(result))
     }
+
+    /// Parse into the JSON of the full Rust `ParseResult`, every field
+    /// included, for callers that forward or store it as is.
+    #[napi]
+    pub fn parse_json(&self, input: String) -> Result<String, &'static str> {
+        self.inner.parse_json(&input).map_err(coded)
+    }
+
+    /// Parse many filenames in one call, across all cores when `parallel`
+    /// is true; failed parses are `null`.
+    #[napi]
+    pub fn parse_many(
+        &self,
+        inputs: Vec<String>,
+        parallel: Option<bool>,
+    ) -> Vec<Option<ParseResultNode>> {
+        parse_many(&inputs, parallel.unwrap_or(false), |input| {
+            self.inner.parse(input).ok()
+        })
+    }
+
+    /// Parse a buffer of newline-delimited filenames into a buffer of
+    /// newline-delimited JSON, one line per input (`null` for failures), so
+    /// huge batches cross into the addon as two buffers instead of one
+    /// string and one object per filename.
+    #[napi]
+    pub fn parse_buffer(&self, inputs: Buffer, parallel: Option<bool>) -> Buffer {
+        parse_buffer(&inputs, parallel.unwrap_or(false), |input| {
+            self.inner.parse(input).ok()
+        })
+    }
+
+    /// Parse on the libuv thread pool, resolving a Promise, so model
+    /// inference does not block the event loop.
+    ///
+    /// Aborting `signal` rejects the Promise with an `AbortError`; a parse
+    /// that has not started yet is dropped from the pool.
+    #[napi(ts_return_type = "Promise<ParseResultNode>")]
+    pub fn parse_async(&self, input: String, signal: Option<AbortSignal>) -> AsyncTask<ParseTask> {
+        AsyncTask::with_optional_signal(
+            ParseTask {
+                parser: Arc::clone(&self.inner),
+                input,
+                error_code: None,
+            },
+            signal,
+        )
+    }
+}
+
+/// Parsers handed out by `share()`, for the `worker_threads` of this
+/// process. Entries are weak: a handle dies with the parser that shared it,
+/// while the parsers rebuilt from it keep their own reference.
+struct SharedParsers<T> {
+    next: AtomicU32,
+    entries: Mutex<HashMap<u32, Weak<T>>>,
+}
+
+static SHARED_HEURISTIC: LazyLock<SharedParsers<HeuristicParser>> =
+    LazyLock::new(SharedParsers::new);
+static SHARED_UNIFIED: LazyLock<SharedParsers<Parser>> = LazyLock::new(SharedParsers::new);
+
+impl<T> SharedParsers<T> {
+    fn new() -> Self {
+        Self {
+            next: AtomicU32::new(1),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register `parser` under a new handle, dropping the entries of
+    /// parsers that are gone.
+    fn share(&self, parser: &Arc<T>) -> u32 {
+        let handle = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, parser| parser.strong_count() > 0);
+        entries.insert(handle, Arc::downgrade(parser));
+        handle
+    }
+
+    fn get(&self, handle: u32) -> Result<Arc<T>, &'static str> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.get(&handle).and_then(Weak::upgrade).ok_or_else(|| {
+            invalid_arg(format!(
+                "no live parser is shared under handle {handle}; keep the \
+                     sharing parser alive until every worker has called fromShared"
+            ))
+        })
+    }
+}
+
+/// A [`ZantetsuNode::parse_async`] call on the libuv thread pool.
+pub struct ParseTask {
+    parser: Arc<Parser>,
+    input: String,
+    /// Code of the error `compute` failed with, for `reject`.
+    error_code: Option<&'static str>,
+}
+
+impl Task for ParseTask {
+    type Output = ParseResult;
+    type JsValue = ParseResultNode;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        self.parser.parse(&self.input).map_err(|e| {
+            self.error_code = Some(e.code());
+            Error::new(Status::GenericFailure, e.to_string())
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(ParseResultNode::from(output))
+    }
+
+    /// Reject with the code of the core error, which `Error<Status>` alone
+    /// cannot carry across the thread pool.
+    fn reject(&mut self, env: Env, err: Error) -> Result<Self::JsValue> {
+        let Some(code) = self.error_code else {
+            return Err(err);
+        };
+        let mut error = env.create_error(err)?;
+        error.set_named_property("code", env.create_string(code)?)?;
+        Err(Error::from(error.into_unknown()))
+    }
 }
 
+/// Run `parse` over `inputs`, split into one chunk per core when
+/// `parallel`, keeping the input order.
+fn parse_many<F>(inputs: &[String], parallel: bool, parse: F) -> Vec<Option<ParseResultNode>>
+where
+    F: Fn(&str) -> Option<ParseResult> + Sync,
+{
+    map_chunks(inputs, parallel, |input| parse(input))
+        .into_iter()
+        .map(|result| result.map(ParseResultNode::from))
+        .collect()
+}
+
+/// [`parse_many`] over the lines of `inputs`, as newline-delimited JSON of
+/// the Rust `ParseResult`. Lines may end in `\r\n`; lines that are not
+/// UTF-8 fail like unparseable ones.
+fn parse_buffer<F>(inputs: &[u8], parallel: bool, parse: F) -> Buffer
+where
+    F: Fn(&str) -> Option<ParseResult> + Sync,
+{
+    let inputs = inputs.strip_suffix(b"\n").unwrap_or(inputs);
+    let lines: Vec<&[u8]> = if inputs.is_empty() {
+        Vec::new()
+    } else {
+        inputs.split(|byte| *byte == b'\n').collect()
+    };
+    let mut output = Vec::with_capacity(lines.len() * 256);
+    for json in map_chunks(&lines, parallel, |line| {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let result = std::str::from_utf8(line).ok().and_then(&parse);
+        // A ParseResult always serializes.
+        serde_json::to_vec(&result).unwrap_or_default()
+    }) {
+        output.extend_from_slice(&json);
+        output.push(b'\n');
+    }
+    output.into()
+}
+
+/// `f` applied to every item, in order; split into one chunk per core on
+/// scoped threads when `parallel`.
+fn map_chunks<T, R, F>(items: &[T], parallel: bool, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = if parallel {
+        std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+    } else {
+        1
+    };
+    if threads == 1 || items.len() < 2 {
+        return items.iter().map(f).collect();
+    }
+
+    let f = &f;
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = items
+            .chunks(items.len().div_ceil(threads))
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<_>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+/// An `EpisodeSpec` as a plain object; `kind` tells which other fields are
+/// set. TypeScript sees it as a union discriminated by `kind`.
+#[napi(object)]
+#[derive(Clone)]
+pub struct EpisodeSpecNode {
+    /// `"single"`, `"range"`, `"multi"`, `"versioned"`, `"special"` or
+    /// `"zero"`.
+    pub kind: String,
+    /// The episode (single, versioned) or special number.
+    pub value: Option<u32>,
+    pub start: Option<u32>,
+    pub end: Option<u32>,
+    pub values: Option<Vec<u32>>,
+    pub version: Option<u32>,
+}
+
+impl From<EpisodeSpec> for EpisodeSpecNode {
+    fn from(spec: EpisodeSpec) -> Self {
+        let node = |kind: &str| Self {
+            kind: kind.into(),
+            value: None,
+            start: None,
+            end: None,
+            values: None,
+            version: None,
+        };
+        match spec {
+            EpisodeSpec::Single(episode) => Self {
+                value: Some(episode),
+                ..node("single")
+            },
+            EpisodeSpec::Range(start, end) => Self {
+                start: Some(start),
+                end: Some(end),
+                ..node("range")
+            },
+            EpisodeSpec::Multi(episodes) => Self {
+                values: Some(episodes),
+                ..node("multi")
+            },
+            EpisodeSpec::Version { episode, version } => Self {
+                value: Some(episode),
+                version: Some(u32::from(version)),
+                ..node("versioned")
+            },
+            EpisodeSpec::Special(number) => Self {
+                value: Some(number),
+                ..node("special")
+            },
+            EpisodeSpec::Zero => node("zero"),
+        }
+    }
+}
+
+/// Fields keep their Rust (snake_case) names, as in the `ParseResult` type of
+/// the npm package.
 #[napi]
 pub struct ParseResultNode {
     pub input: String,
     pub title: Option<String>,
     pub group: Option<String>,
-    pub episode: Option<String>,
+    #[napi(ts_type = "{ kind: 'single'; value: number } \
+        | { kind: 'range'; start: number; end: number } \
+        | { kind: 'multi'; values: number[] } \
+        | { kind: 'versioned'; value: number; version: number } \
+        | { kind: 'special'; value: number } \
+        | { kind: 'zero' }")]
+    pub episode: Option<EpisodeSpecNode>,
     pub season: Option<u32>,
+    #[napi(js_name = "season_end")]
+    pub season_end: Option<u32>,
     pub resolution: Option<String>,
+    #[napi(js_name = "video_codec")]
     pub video_codec: Option<String>,
+    #[napi(js_name = "audio_codec")]
     pub audio_codec: Option<String>,
     pub source: Option<String>,
     pub year: Option<u16>,
@@ -71,17 +490,26 @@ pub struct ParseResultNode {
     pub extension: Option<String>,
     pub version: Option<u8>,
     pub confidence: f64,
+    #[napi(js_name = "parse_mode")]
     pub parse_mode: String,
+    /// The full result, for scoring.
+    inner: ParseResult,
 }
 
 impl From<ParseResult> for ParseResultNode {
     fn from(result: ParseResult) -> Self {
+        let inner = result.clone();
         Self {
+            inner,
             input: result.input,
             title: result.title,
             group: result.group,
-            episode: result.episode.map(episode_to_string),
-            season: result.season,
+            episode: result.episode.map(EpisodeSpecNode::from),
+            season: result.season.map(SeasonSpec::first),
+            season_end: match result.season {
+                Some(SeasonSpec::Range(_, end)) => Some(end),
+                _ => None,
+            },
             resolution: result.resolution.map(resolution_to_string),
             video_codec: result.video_codec.map(vcodec_to_string),
             audio_codec: result.audio_codec.map(acodec_to_string),
@@ -96,17 +524,208 @@ impl From<ParseResult> for ParseResultNode {
     }
 }
 
-fn episode_to_string(spec: EpisodeSpec) -> String {
-    match spec {
-        EpisodeSpec::Single(ep) => format!("{}", ep),
-        EpisodeSpec::Range(start, end) => format!("{}-{}", start, end),
-        EpisodeSpec::Multi(eps) => eps
-            .iter()
-            .map(|e| e.to_string())
-            .collect::<Vec<_>>()
-            .join(","),
-        EpisodeSpec::Version { episode, version } => format!("{}v{}", episode, version),
+/// NAPI wrapper for QualityProfile: the weight of each quality dimension.
+///
+/// # Example
+///
+/// ```js
+/// const { QualityProfile, ClientContext, rank } = require('zantetsu');
+///
+/// const profile = QualityProfile.preset('streaming');
+/// const context = ClientContext.fromJson('{"device_type": "Mobile", "hw_decode_codecs": ["H264"]}');
+/// const ranking = rank(results, profile, context);
+/// console.log(results[ranking[0].index].input);
+/// ```
+#[napi(js_name = "QualityProfile")]
+#[derive(Default)]
+pub struct QualityProfileNode {
+    inner: QualityProfile,
+}
+
+#[napi]
+impl QualityProfileNode {
+    /// The default weights.
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `"archival"`, `"streaming"`, `"mobile"`, `"lowBandwidth"` or
+    /// `"audiophile"`.
+    #[napi(factory)]
+    pub fn preset(name: String) -> Result<Self, &'static str> {
+        let preset = match name.to_ascii_lowercase().as_str() {
+            "archival" => Preset::Archival,
+            "streaming" => Preset::Streaming,
+            "mobile" => Preset::Mobile,
+            "lowbandwidth" => Preset::LowBandwidth,
+            "audiophile" => Preset::AudioPhile,
+            _ => {
+                return Err(invalid_arg(format!("unknown quality preset {:?}", name)));
+            }
+        };
+        Ok(Self {
+            inner: QualityProfile::preset(preset),
+        })
+    }
+
+    /// Weights from a JSON object; missing weights keep their defaults.
+    #[napi(factory)]
+    pub fn from_json(json: String) -> Result<Self, &'static str> {
+        QualityProfile::from_json(&json)
+            .map(|inner| Self { inner })
+            .map_err(coded)
+    }
+
+    /// Weights from a TOML document; missing weights keep their defaults.
+    #[napi(factory)]
+    pub fn from_toml(toml: String) -> Result<Self, &'static str> {
+        QualityProfile::from_toml(&toml)
+            .map(|inner| Self { inner })
+            .map_err(coded)
+    }
+
+    #[napi]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.inner).map_err(generic_failure)
+    }
+}
+
+/// NAPI wrapper for ClientContext: the playback device, network and
+/// decoders that scores are adjusted for.
+#[napi(js_name = "ClientContext")]
+#[derive(Default)]
+pub struct ClientContextNode {
+    inner: ClientContext,
+}
+
+#[napi]
+impl ClientContextNode {
+    /// A desktop on an unlimited network decoding H.264 and HEVC.
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A context from a JSON object with the fields of the Rust
+    /// `ClientContext`; missing fields keep their defaults.
+    #[napi(factory)]
+    pub fn from_json(json: String) -> Result<Self, &'static str> {
+        let inner: ClientContext =
+            serde_json::from_str(&json).map_err(|e| coded(ConfigError::from(e).into()))?;
+        inner.validate().map_err(coded)?;
+        Ok(Self { inner })
+    }
+
+    /// A context from a TOML document; missing fields keep their defaults.
+    #[napi(factory)]
+    pub fn from_toml(toml: String) -> Result<Self, &'static str> {
+        ClientContext::from_toml(&toml)
+            .map(|inner| Self { inner })
+            .map_err(coded)
+    }
+
+    #[napi]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.inner).map_err(generic_failure)
+    }
+}
+
+/// NAPI wrapper for QualityScores: per-dimension scores of one release.
+#[napi(js_name = "QualityScores")]
+pub struct QualityScoresNode {
+    inner: QualityScores,
+    video_codec: Option<VideoCodec>,
+}
+
+#[napi]
+impl QualityScoresNode {
+    /// Scores of a parse result.
+    #[napi(factory)]
+    pub fn from_result(result: &ParseResultNode) -> Self {
+        Self {
+            inner: QualityScores::from_result(&result.inner),
+            video_codec: result.inner.video_codec,
+        }
+    }
+
+    /// The weighted score under `profile`, adjusted for `context` when given.
+    #[napi]
+    pub fn compute(
+        &self,
+        profile: &QualityProfileNode,
+        context: Option<&ClientContextNode>,
+    ) -> f64 {
+        let scores = match context {
+            Some(context) => context
+                .inner
+                .adjust_score(self.inner.clone(), self.video_codec),
+            None => self.inner.clone(),
+        };
+        f64::from(scores.compute(&profile.inner))
+    }
+
+    /// Per-dimension breakdown of the score as JSON (the Rust `ScoreReport`).
+    #[napi]
+    pub fn explain(
+        &self,
+        profile: &QualityProfileNode,
+        context: Option<&ClientContextNode>,
+    ) -> Result<String> {
+        let default_context = ClientContext::default();
+        let context = context.map_or(&default_context, |context| &context.inner);
+        let report = self
+            .inner
+            .explain(&profile.inner, context, self.video_codec);
+        serde_json::to_string(&report).map_err(generic_failure)
     }
+
+    #[napi]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.inner).map_err(generic_failure)
+    }
+}
+
+/// One entry of a [`rank`] result.
+#[napi(object)]
+pub struct RankedResult {
+    /// Position of the result in the ranked array.
+    pub index: u32,
+    pub score: f64,
+}
+
+/// Rank parse results of alternative releases, best first, with the same
+/// logic as `zantetsu_core::scoring::rank`; `context` defaults to a desktop.
+#[napi]
+pub fn rank(
+    results: Vec<ClassInstance<ParseResultNode>>,
+    profile: &QualityProfileNode,
+    context: Option<&ClientContextNode>,
+) -> Vec<RankedResult> {
+    let results: Vec<ParseResult> = results.iter().map(|result| result.inner.clone()).collect();
+    let default_context = ClientContext::default();
+    let context = context.map_or(&default_context, |context| &context.inner);
+    scoring::rank(&results, &profile.inner, context)
+        .into_iter()
+        .map(|(index, score)| RankedResult {
+            index: index as u32,
+            score: f64::from(score),
+        })
+        .collect()
+}
+
+/// An error whose JS `code` is the stable code of `error`.
+fn coded(error: ZantetsuError) -> Error<&'static str> {
+    Error::new(error.code(), error.to_string())
+}
+
+/// An error for an argument the bindings reject before reaching the core.
+fn invalid_arg(message: String) -> Error<&'static str> {
+    Error::new("INVALID_ARGUMENT", message)
+}
+
+fn generic_failure(error: impl std::fmt::Display) -> Error {
+    Error::new(Status::GenericFailure, error.to_string())
 }
 
 fn resolution_to_string(res: Resolution) -> String {
@@ -155,10 +774,24 @@ fn source_to_string(src: MediaSource) -> String {
     }
 }
 
+fn parse_mode_from_str(mode: &str) -> Result<ParseMode, &'static str> {
+    match mode.to_ascii_lowercase().as_str() {
+        "full" => Ok(ParseMode::Full),
+        "light" => Ok(ParseMode::Light),
+        "auto" => Ok(ParseMode::Auto),
+        "ensemble" => Ok(ParseMode::Ensemble),
+        _ => Err(invalid_arg(format!(
+            "unknown parse mode {:?} (expected full, light, auto or ensemble)",
+            mode
+        ))),
+    }
+}
+
 fn parse_mode_to_string(mode: ParseMode) -> String {
     match mode {
         ParseMode::Full => "Full".into(),
         ParseMode::Light => "Light".into(),
         ParseMode::Auto => "Auto".into(),
+        ParseMode::Ensemble => "Ensemble".into(),
     }
 }
@@ -3,12 +3,404 @@
 //! This module provides the primary interface for using Zantetsu
 //! from Python applications via the `zantetsu` PyPI package.
 
+use std::path::PathBuf;
+
+use pyo3::IntoPyObjectExt;
+use pyo3::create_exception;
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyIterator, PyList};
 use zantetsu_core::{
     HeuristicParser, ParseResult,
-    types::{AudioCodec, EpisodeSpec, MediaSource, ParseMode, Resolution, VideoCodec},
+    types::{AudioCodec, EpisodeSpec, MediaSource, ParseMode, Resolution, SeasonSpec, VideoCodec},
 };
 
+use crate::engine::{Parser, ParserConfig};
+
+create_exception!(
+    zantetsu,
+    ZantetsuError,
+    pyo3::exceptions::PyValueError,
+    "Raised when parsing fails; `code` is the stable name of the error, such as `EMPTY_INPUT`."
+);
+
+/// A `ZantetsuError` exception with the stable code of `error` as `code`.
+fn to_py_err(error: zantetsu_core::ZantetsuError) -> PyErr {
+    let err = ZantetsuError::new_err(error.to_string());
+    Python::with_gil(|py| {
+        // Setting an attribute on a fresh exception instance cannot fail.
+        let _ = err.value(py).setattr("code", error.code());
+    });
+    err
+}
+
+/// Declares a Python enum mirroring a core enum variant for variant. `name`
+/// and `str()` give the variant name, as with `enum.Enum`.
+macro_rules! py_enum {
+    ($(#[$meta:meta])* $py:ident = $name:literal mirrors $core:ident { $($variant:ident),+ $(,)? }) => {
+        $(#[$meta])*
+        #[pyclass(name = $name, eq, eq_int, frozen, hash)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[allow(clippy::upper_case_acronyms)]
+        pub enum $py {
+            $($variant),+
+        }
+
+        #[pymethods]
+        impl $py {
+            #[getter]
+            fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($variant)),+
+                }
+            }
+
+            fn __str__(&self) -> &'static str {
+                self.name()
+            }
+        }
+
+        impl From<$core> for $py {
+            fn from(value: $core) -> Self {
+                match value {
+                    $($core::$variant => Self::$variant),+
+                }
+            }
+        }
+
+        impl From<$py> for $core {
+            fn from(value: $py) -> Self {
+                match value {
+                    $($py::$variant => Self::$variant),+
+                }
+            }
+        }
+    };
+}
+
+py_enum! {
+    /// Video resolution.
+    ResolutionPy = "Resolution" mirrors Resolution { SD480, HD720, FHD1080, UHD2160 }
+}
+
+py_enum! {
+    /// Video codec.
+    VideoCodecPy = "VideoCodec" mirrors VideoCodec { H264, HEVC, AV1, VP9, MPEG4 }
+}
+
+py_enum! {
+    /// Audio codec.
+    AudioCodecPy = "AudioCodec" mirrors AudioCodec {
+        FLAC, AAC, Opus, AC3, DTS, MP3, Vorbis, TrueHD, EAAC,
+    }
+}
+
+py_enum! {
+    /// Release source.
+    MediaSourcePy = "MediaSource" mirrors MediaSource {
+        BluRayRemux, BluRay, WebDL, WebRip, HDTV, DVD, LaserDisc, VHS,
+    }
+}
+
+py_enum! {
+    /// Parsing engine selection: `Full` (neural model), `Light` (heuristic),
+    /// `Auto` (model with heuristic fallback) or `Ensemble` (both, merged).
+    ParseModePy = "ParseMode" mirrors ParseMode { Full, Light, Auto, Ensemble }
+}
+
+/// PyO3 wrapper for the unified Parser.
+///
+/// Picks between the neural and heuristic engines according to `mode`,
+/// falling back to the heuristic engine when no model is installed.
+///
+/// # Example
+///
+/// ```python
+/// from zantetsu import ParseMode, Parser
+///
+/// parser = Parser(mode=ParseMode.Light)
+/// results = parser.parse_batch([
+///     '[SubsPlease] Frieren - 05 (1080p).mkv',
+///     '[Erai-raws] Oshi no Ko S02E05 [720p].mkv',
+/// ])
+/// print([r.episode for r in results]) # ['5', '5']
+/// ```
+#[pyclass(name = "Parser", frozen)]
+pub struct ParserPy {
+    inner: Parser,
+}
+
+#[pymethods]
+impl ParserPy {
+    /// `model_dir` defaults to `$ZANTETSU_MODEL_DIR`, else
+    /// `models/ner_model`; `model_repo` (`owner/name[@revision]`) downloads
+    /// the model from the Hugging Face Hub when no directory is set, in
+    /// builds with the `hub` feature.
+    #[new]
+    #[pyo3(signature = (mode = ParseModePy::Auto, *, model_dir = None, model_repo = None))]
+    fn new(
+        mode: ParseModePy,
+        model_dir: Option<PathBuf>,
+        model_repo: Option<String>,
+    ) -> PyResult<Self> {
+        let mut config = ParserConfig::new().with_mode(mode.into());
+        if let Some(dir) = model_dir {
+            config = config.with_model_dir(dir);
+        }
+        if let Some(repo) = model_repo {
+            config = config.with_model_repo(repo);
+        }
+        let inner = Parser::new(config).map_err(to_py_err)?;
+        Ok(Self { inner })
+    }
+
+    /// The mode the parser was created with.
+    #[getter]
+    fn mode(&self) -> ParseModePy {
+        self.inner.config().mode.into()
+    }
+
+    /// Parses one filename, raising `ZantetsuError` if it cannot be parsed.
+    fn parse(&self, py: Python<'_>, input: &str) -> PyResult<ParseResultPy> {
+        let result = py
+            .allow_threads(|| self.inner.parse(input))
+            .map_err(to_py_err)?;
+        Ok(ParseResultPy::from(result))
+    }
+
+    /// Parses one filename into the JSON of the full Rust `ParseResult`,
+    /// with every field, for `json.loads` or forwarding as is.
+    fn parse_json(&self, py: Python<'_>, input: &str) -> PyResult<String> {
+        py.allow_threads(|| self.inner.parse_json(input))
+            .map_err(to_py_err)
+    }
+
+    /// Parses many filenames with the GIL released, returning one result
+    /// per input in order, or `None` for inputs that cannot be parsed.
+    fn parse_batch(&self, py: Python<'_>, inputs: Vec<String>) -> Vec<Option<ParseResultPy>> {
+        py.allow_threads(|| {
+            inputs
+                .iter()
+                .map(|input| self.inner.parse(input).ok().map(ParseResultPy::from))
+                .collect()
+        })
+    }
+
+    /// Parses `inputs` with the GIL released into one `dict` per input, with
+    /// enums as their names and every field `None` (but `input`) for inputs
+    /// that cannot be parsed, ready for `pandas.DataFrame(records)`.
+    fn parse_to_records<'py>(
+        &self,
+        py: Python<'py>,
+        inputs: Vec<String>,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let rows = self.parse_rows(py, &inputs)?;
+        let records = PyList::empty(py);
+        for row in rows {
+            let record = PyDict::new(py);
+            for (key, value) in row {
+                record.set_item(key, value)?;
+            }
+            records.append(record)?;
+        }
+        Ok(records)
+    }
+
+    /// Like `parse_to_records`, as one list per field instead (`{field:
+    /// [value per input]}`), which `pandas.DataFrame` builds from fastest.
+    fn parse_to_columns<'py>(
+        &self,
+        py: Python<'py>,
+        inputs: Vec<String>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let mut columns: Vec<(&'static str, Vec<PyObject>)> = RECORD_FIELDS
+            .iter()
+            .map(|field| (*field, Vec::with_capacity(inputs.len())))
+            .collect();
+        for row in self.parse_rows(py, &inputs)? {
+            for ((_, column), (_, value)) in columns.iter_mut().zip(row) {
+                column.push(value);
+            }
+        }
+        let dict = PyDict::new(py);
+        for (key, column) in columns {
+            dict.set_item(key, column)?;
+        }
+        Ok(dict)
+    }
+
+    /// Lazily parses each line of `lines` (any iterable of `str` or
+    /// `bytes`, such as an open file), yielding one result per line, or
+    /// `None` for lines that cannot be parsed. Line endings are stripped and
+    /// the GIL is released while each line is parsed.
+    fn parse_lines(slf: Py<Self>, lines: &Bound<'_, PyAny>) -> PyResult<ParseLinesPy> {
+        Ok(ParseLinesPy {
+            parser: slf,
+            lines: lines.try_iter()?.unbind(),
+        })
+    }
+}
+
+impl ParserPy {
+    /// The fields of each input as `RECORD_FIELDS` pairs, parsed with the
+    /// GIL released.
+    fn parse_rows(
+        &self,
+        py: Python<'_>,
+        inputs: &[String],
+    ) -> PyResult<Vec<Vec<(&'static str, PyObject)>>> {
+        let results: Vec<Option<ParseResultPy>> = py.allow_threads(|| {
+            inputs
+                .iter()
+                .map(|input| self.inner.parse(input).ok().map(ParseResultPy::from))
+                .collect()
+        });
+        inputs
+            .iter()
+            .zip(results)
+            .map(|(input, result)| record_row(py, input, result.as_ref()))
+            .collect()
+    }
+}
+
+/// Keys of the records of `parse_to_records`, in order.
+const RECORD_FIELDS: [&str; 16] = [
+    "input",
+    "title",
+    "group",
+    "episode",
+    "season",
+    "season_end",
+    "resolution",
+    "video_codec",
+    "audio_codec",
+    "source",
+    "year",
+    "crc32",
+    "extension",
+    "version",
+    "confidence",
+    "parse_mode",
+];
+
+/// The `RECORD_FIELDS` of one input, with enums as plain strings.
+fn record_row(
+    py: Python<'_>,
+    input: &str,
+    result: Option<&ParseResultPy>,
+) -> PyResult<Vec<(&'static str, PyObject)>> {
+    let Some(r) = result else {
+        let mut row = vec![(RECORD_FIELDS[0], input.into_py_any(py)?)];
+        row.extend(RECORD_FIELDS[1..].iter().map(|field| (*field, py.None())));
+        return Ok(row);
+    };
+    let values = [
+        r.input.as_str().into_py_any(py)?,
+        r.title.as_deref().into_py_any(py)?,
+        r.group.as_deref().into_py_any(py)?,
+        r.episode.as_deref().into_py_any(py)?,
+        r.season.into_py_any(py)?,
+        r.season_end.into_py_any(py)?,
+        r.resolution.map(|v| v.name()).into_py_any(py)?,
+        r.video_codec.map(|v| v.name()).into_py_any(py)?,
+        r.audio_codec.map(|v| v.name()).into_py_any(py)?,
+        r.source.map(|v| v.name()).into_py_any(py)?,
+        r.year.into_py_any(py)?,
+        r.crc32.as_deref().into_py_any(py)?,
+        r.extension.as_deref().into_py_any(py)?,
+        r.version.into_py_any(py)?,
+        r.confidence.into_py_any(py)?,
+        r.parse_mode.name().into_py_any(py)?,
+    ];
+    Ok(RECORD_FIELDS.into_iter().zip(values).collect())
+}
+
+/// Iterator returned by `parse_lines`, pulling one line from the source per
+/// step so the whole input never has to fit in memory.
+#[pyclass(name = "ParseLines")]
+pub struct ParseLinesPy {
+    parser: Py<ParserPy>,
+    lines: Py<PyIterator>,
+}
+
+#[pymethods]
+impl ParseLinesPy {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<Option<ParseResultPy>>> {
+        let Some(line) = self.lines.bind(py).clone().next() else {
+            return Ok(None);
+        };
+        let line = line?;
+        let line = match line.downcast::<PyBytes>() {
+            Ok(bytes) => String::from_utf8_lossy(bytes.as_bytes()).into_owned(),
+            Err(_) => line.extract::<String>()?,
+        };
+        let parser = &self.parser.get().inner;
+        let result = py.allow_threads(|| {
+            parser
+                .parse(line.trim_end_matches(['\r', '\n']))
+                .ok()
+                .map(ParseResultPy::from)
+        });
+        Ok(Some(result))
+    }
+}
+
+/// Parses `inputs` with a new `Parser(mode)` into one `dict` per input; see
+/// `Parser.parse_to_records`.
+///
+/// ```python
+/// import pandas as pd
+/// import zantetsu
+///
+/// frame = pd.DataFrame(zantetsu.parse_to_records(names, mode=zantetsu.ParseMode.Light))
+/// ```
+#[pyfunction]
+#[pyo3(signature = (inputs, mode = ParseModePy::Auto))]
+fn parse_to_records(
+    py: Python<'_>,
+    inputs: Vec<String>,
+    mode: ParseModePy,
+) -> PyResult<Bound<'_, PyList>> {
+    ParserPy::new(mode, None, None)?.parse_to_records(py, inputs)
+}
+
+/// Parses `inputs` with a new `Parser(mode)` into one list per field; see
+/// `Parser.parse_to_columns`.
+#[pyfunction]
+#[pyo3(signature = (inputs, mode = ParseModePy::Auto))]
+fn parse_to_columns(
+    py: Python<'_>,
+    inputs: Vec<String>,
+    mode: ParseModePy,
+) -> PyResult<Bound<'_, PyDict>> {
+    ParserPy::new(mode, None, None)?.parse_to_columns(py, inputs)
+}
+
+/// Lazily parses each line of `lines` with a new `Parser(mode)`; see
+/// `Parser.parse_lines`.
+///
+/// ```python
+/// import zantetsu
+///
+/// with open('titles.txt', encoding='utf-8') as dump:
+///     for result in zantetsu.parse_lines(dump, mode=zantetsu.ParseMode.Light):
+///         if result is not None:
+///             print(result.title)
+/// ```
+#[pyfunction]
+#[pyo3(signature = (lines, mode = ParseModePy::Auto))]
+fn parse_lines(
+    py: Python<'_>,
+    lines: &Bound<'_, PyAny>,
+    mode: ParseModePy,
+) -> PyResult<ParseLinesPy> {
+    let parser = Py::new(py, ParserPy::new(mode, None, None)?)?;
+    ParserPy::parse_lines(parser, lines)
+}
+
 /// PyO3 wrapper for the HeuristicParser.
 ///
 /// Create an instance to parse anime filenames using the fast
@@ -24,7 +416,7 @@ use zantetsu_core::{
 ///
 /// print(result.title)      # 'Jujutsu Kaisen'
 /// print(result.episode)    # 24
-/// print(result.resolution) # 'FHD1080'
+/// print(result.resolution) # Resolution.FHD1080
 /// ```
 #[pyclass(name = "HeuristicParser")]
 pub struct HeuristicParserPy {
@@ -35,8 +427,7 @@ pub struct HeuristicParserPy {
 impl HeuristicParserPy {
     #[new]
     fn new() -> PyResult<Self> {
-        let inner = HeuristicParser::new()
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let inner = HeuristicParser::new().map_err(to_py_err)?;
         Ok(Self { inner })
     }
 
@@ -50,20 +441,24 @@ impl HeuristicParserPy {
     ///
     /// A `ParseResult` object containing extracted metadata.
     fn parse(&self, input: String) -> PyResult<ParseResultPy> {
-        let result = self
-            .inner
-            .parse(&input)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let result = self.inner.parse(&input).map_err(to_py_err)?;
         Ok(ParseResultPy::from(result))
     }
+
+    /// Parses one filename into the JSON of the full Rust `ParseResult`.
+    fn parse_json(&self, input: &str) -> PyResult<String> {
+        self.inner.parse_json(input).map_err(to_py_err)
+    }
 }
 
 /// PyO3 wrapper for ParseResult.
 ///
 /// Represents the structured output of parsing an anime filename,
 /// containing extracted metadata like title, episode, resolution, etc.
-#[pyclass(name = "ParseResult")]
-#[derive(Clone)]
+/// Behaves like a frozen dataclass: fields are read-only, results compare
+/// by value and `to_dict()` returns the fields as a `dict`.
+#[pyclass(name = "ParseResult", eq, frozen)]
+#[derive(Clone, PartialEq)]
 pub struct ParseResultPy {
     #[pyo3(get)]
     pub input: String,
@@ -76,13 +471,15 @@ pub struct ParseResultPy {
     #[pyo3(get)]
     pub season: Option<u32>,
     #[pyo3(get)]
-    pub resolution: Option<String>,
+    pub season_end: Option<u32>,
     #[pyo3(get)]
-    pub video_codec: Option<String>,
+    pub resolution: Option<ResolutionPy>,
     #[pyo3(get)]
-    pub audio_codec: Option<String>,
+    pub video_codec: Option<VideoCodecPy>,
     #[pyo3(get)]
-    pub source: Option<String>,
+    pub audio_codec: Option<AudioCodecPy>,
+    #[pyo3(get)]
+    pub source: Option<MediaSourcePy>,
     #[pyo3(get)]
     pub year: Option<u16>,
     #[pyo3(get)]
@@ -94,7 +491,43 @@ pub struct ParseResultPy {
     #[pyo3(get)]
     pub confidence: f32,
     #[pyo3(get)]
-    pub parse_mode: String,
+    pub parse_mode: ParseModePy,
+}
+
+#[pymethods]
+impl ParseResultPy {
+    /// The fields as a `dict`, in declaration order.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("input", &self.input)?;
+        dict.set_item("title", &self.title)?;
+        dict.set_item("group", &self.group)?;
+        dict.set_item("episode", &self.episode)?;
+        dict.set_item("season", self.season)?;
+        dict.set_item("season_end", self.season_end)?;
+        dict.set_item("resolution", self.resolution)?;
+        dict.set_item("video_codec", self.video_codec)?;
+        dict.set_item("audio_codec", self.audio_codec)?;
+        dict.set_item("source", self.source)?;
+        dict.set_item("year", self.year)?;
+        dict.set_item("crc32", &self.crc32)?;
+        dict.set_item("extension", &self.extension)?;
+        dict.set_item("version", self.version)?;
+        dict.set_item("confidence", self.confidence)?;
+        dict.set_item("parse_mode", self.parse_mode)?;
+        Ok(dict)
+    }
+
+    /// `ParseResult(title='...', ...)` with the fields that are set.
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let mut fields = Vec::new();
+        for (key, value) in self.to_dict(py)?.iter() {
+            if !value.is_none() {
+                fields.push(format!("{key}={}", value.repr()?));
+            }
+        }
+        Ok(format!("ParseResult({})", fields.join(", ")))
+    }
 }
 
 impl From<ParseResult> for ParseResultPy {
@@ -104,17 +537,21 @@ impl From<ParseResult> for ParseResultPy {
             title: result.title,
             group: result.group,
             episode: result.episode.map(episode_to_string),
-            season: result.season,
-            resolution: result.resolution.map(resolution_to_string),
-            video_codec: result.video_codec.map(vcodec_to_string),
-            audio_codec: result.audio_codec.map(acodec_to_string),
-            source: result.source.map(source_to_string),
+            season: result.season.map(SeasonSpec::first),
+            season_end: match result.season {
+                Some(SeasonSpec::Range(_, end)) => Some(end),
+                _ => None,
+            },
+            resolution: result.resolution.map(Into::into),
+            video_codec: result.video_codec.map(Into::into),
+            audio_codec: result.audio_codec.map(Into::into),
+            source: result.source.map(Into::into),
             year: result.year,
             crc32: result.crc32,
             extension: result.extension,
             version: result.version,
             confidence: result.confidence,
-            parse_mode: parse_mode_to_string(result.parse_mode),
+            parse_mode: result.parse_mode.into(),
         }
     }
 }
@@ -129,71 +566,25 @@ fn episode_to_string(spec: EpisodeSpec) -> String {
             .collect::<Vec<_>>()
             .join(","),
         EpisodeSpec::Version { episode, version } => format!("{}v{}", episode, version),
+        EpisodeSpec::Special(number) => format!("SP{}", number),
+        EpisodeSpec::Zero => "0".into(),
     }
 }
 
-fn resolution_to_string(res: Resolution) -> String {
-    match res {
-        Resolution::SD480 => "SD480",
-        Resolution::HD720 => "HD720",
-        Resolution::FHD1080 => "FHD1080",
-        Resolution::UHD2160 => "UHD2160",
-    }
-    .into()
-}
-
-fn vcodec_to_string(vc: VideoCodec) -> String {
-    match vc {
-        VideoCodec::H264 => "H264",
-        VideoCodec::HEVC => "HEVC",
-        VideoCodec::AV1 => "AV1",
-        VideoCodec::VP9 => "VP9",
-        VideoCodec::MPEG4 => "MPEG4",
-    }
-    .into()
-}
-
-fn acodec_to_string(ac: AudioCodec) -> String {
-    match ac {
-        AudioCodec::FLAC => "FLAC",
-        AudioCodec::AAC => "AAC",
-        AudioCodec::Opus => "Opus",
-        AudioCodec::AC3 => "AC3",
-        AudioCodec::DTS => "DTS",
-        AudioCodec::MP3 => "MP3",
-        AudioCodec::Vorbis => "Vorbis",
-        AudioCodec::TrueHD => "TrueHD",
-        AudioCodec::EAAC => "EAAC",
-    }
-    .into()
-}
-
-fn source_to_string(src: MediaSource) -> String {
-    match src {
-        MediaSource::BluRayRemux => "BluRayRemux",
-        MediaSource::BluRay => "BluRay",
-        MediaSource::WebDL => "WebDL",
-        MediaSource::WebRip => "WebRip",
-        MediaSource::HDTV => "HDTV",
-        MediaSource::DVD => "DVD",
-        MediaSource::LaserDisc => "LaserDisc",
-        MediaSource::VHS => "VHS",
-    }
-    .into()
-}
-
-fn parse_mode_to_string(mode: ParseMode) -> String {
-    match mode {
-        ParseMode::Full => "Full",
-        ParseMode::Light => "Light",
-        ParseMode::Auto => "Auto",
-    }
-    .into()
-}
-
 /// Python module definition.
 pub fn pymodule(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ParserPy>()?;
+    m.add_class::<ParseLinesPy>()?;
+    m.add_function(wrap_pyfunction!(parse_lines, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_to_records, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_to_columns, m)?)?;
     m.add_class::<HeuristicParserPy>()?;
     m.add_class::<ParseResultPy>()?;
+    m.add_class::<ParseModePy>()?;
+    m.add_class::<ResolutionPy>()?;
+    m.add_class::<VideoCodecPy>()?;
+    m.add_class::<AudioCodecPy>()?;
+    m.add_class::<MediaSourcePy>()?;
+    m.add("ZantetsuError", m.py().get_type::<ZantetsuError>())?;
     Ok(())
 }
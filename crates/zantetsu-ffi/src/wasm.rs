@@ -0,0 +1,71 @@
+//! WebAssembly bindings using wasm-bindgen.
+//!
+//! Exposes the heuristic engine to browser extensions and Electron renderers,
+//! which parse locally without a model download. Build it with
+//! `--no-default-features`, leaving out the `neural` feature, so the module
+//! pulls in neither candle nor any filesystem access.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use zantetsu_core::HeuristicParser;
+
+/// wasm-bindgen wrapper for the HeuristicParser.
+///
+/// Results are plain objects with the fields of the Rust `ParseResult`;
+/// missing fields are `null`.
+///
+/// # Example
+///
+/// ```js
+/// import init, { HeuristicParser } from 'zantetsu-wasm';
+///
+/// await init();
+/// const parser = new HeuristicParser();
+/// const result = parser.parse('[SubsPlease] Jujutsu Kaisen - 24 (1080p) [A1B2C3D4].mkv');
+///
+/// console.log(result.title); // 'Jujutsu Kaisen'
+/// console.log(result.episode); // { Single: 24 }
+/// console.log(result.resolution); // 'FHD1080'
+/// ```
+#[wasm_bindgen(js_name = HeuristicParser)]
+pub struct HeuristicParserWasm {
+    inner: HeuristicParser,
+}
+
+#[wasm_bindgen(js_class = HeuristicParser)]
+impl HeuristicParserWasm {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<HeuristicParserWasm, JsError> {
+        let inner = HeuristicParser::new()
+            .map_err(|e| JsError::new(&format!("failed to create parser: {e}")))?;
+        Ok(Self { inner })
+    }
+
+    /// Parse one filename.
+    pub fn parse(&self, input: &str) -> Result<JsValue, JsError> {
+        let result = self
+            .inner
+            .parse(input)
+            .map_err(|e| JsError::new(&format!("parse error: {e}")))?;
+        to_js(&result)
+    }
+
+    /// Parse many filenames; failed parses are `null`.
+    #[wasm_bindgen(js_name = parseBatch)]
+    pub fn parse_batch(&self, inputs: Vec<String>) -> Result<Vec<JsValue>, JsError> {
+        inputs
+            .iter()
+            .map(|input| match self.inner.parse(input) {
+                Ok(result) => to_js(&result),
+                Err(_) => Ok(JsValue::NULL),
+            })
+            .collect()
+    }
+}
+
+/// `value` as a plain JS object, with `None` as `null`.
+fn to_js(value: &impl Serialize) -> Result<JsValue, JsError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
@@ -0,0 +1,424 @@
+//! Active learning: send the inputs the model handles worst to annotators,
+//! then fold their corrections back into the training set.
+//!
+//! 1. [`ActiveLearner::select`] runs the current model over an unlabeled
+//!    corpus and ranks inputs by how unsure the model is and how much it
+//!    disagrees with the heuristic parser.
+//!    [`ActiveLearner::mine_disagreements`] instead keeps only the inputs
+//!    where the two engines disagree on title, episode or group.
+//! 2. [`write_tasks`] emits the top inputs as [`AnnotationTask`] JSONL, with
+//!    the model's tags as a pre-annotation to correct.
+//! 3. [`merge_annotations`] adds the corrected tasks to a silver dataset,
+//!    replacing earlier samples of the same tokens.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zantetsu_core::parser::{HeuristicParser, ParseEngine, Tokenizer};
+use zantetsu_core::{FieldDiff, ParseField, ParseResult};
+
+use crate::align::align_tags;
+use crate::data::{BioSample, label_index};
+
+/// One input for an annotator to label.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnotationTask {
+    /// The unlabeled input.
+    pub input: String,
+    /// Word tokens of `input`.
+    pub tokens: Vec<String>,
+    /// One tag per token: the model's guess until an annotator corrects it.
+    pub ner_tags: Vec<String>,
+    /// Selection score; higher means more informative.
+    #[serde(default)]
+    pub score: f32,
+    /// The model's confidence in its least certain field.
+    #[serde(default)]
+    pub confidence: f32,
+    /// Fields where the model (`left`) and the heuristic parser (`right`)
+    /// disagree.
+    #[serde(default)]
+    pub disagreements: Vec<FieldDiff>,
+    /// Set by the annotator to leave the task out of the training set.
+    #[serde(default)]
+    pub skip: bool,
+}
+
+/// Ranks unlabeled inputs by how much labeling them would teach the model.
+pub struct ActiveLearner<E> {
+    model: E,
+    heuristic: HeuristicParser,
+    tokenizer: Tokenizer,
+    disagreement_weight: f32,
+}
+
+impl<E: ParseEngine> ActiveLearner<E> {
+    /// Select inputs for `model`, the engine being trained.
+    pub fn new(model: E) -> anyhow::Result<Self> {
+        Ok(Self {
+            model,
+            heuristic: HeuristicParser::new()?,
+            tokenizer: Tokenizer::new(),
+            disagreement_weight: 1.0,
+        })
+    }
+
+    /// How much disagreement with the heuristic parser counts against the
+    /// model's own uncertainty (default `1.0`).
+    pub fn with_disagreement_weight(mut self, weight: f32) -> Self {
+        self.disagreement_weight = weight;
+        self
+    }
+
+    /// Build the annotation task for `input`, or `None` if it has no tokens.
+    ///
+    /// The score is `1 - confidence` plus the weighted share of extracted
+    /// fields on which the model and the heuristic parser disagree.
+    pub fn task(&self, input: &str) -> anyhow::Result<Option<AnnotationTask>> {
+        let tokens: Vec<_> = self
+            .tokenizer
+            .tokenize(input)
+            .into_iter()
+            .filter(|t| !t.text.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+
+        let predicted = self.model.parse(input)?;
+        let heuristic = self.heuristic.parse(input)?;
+        let disagreements = predicted.diff(&heuristic);
+        let extracted = ParseField::ALL
+            .iter()
+            .filter(|field| field.is_present(&predicted) || field.is_present(&heuristic))
+            .count();
+        let disagreement = if extracted == 0 {
+            0.0
+        } else {
+            disagreements.len() as f32 / extracted as f32
+        };
+        let confidence = least_confidence(&predicted);
+
+        Ok(Some(AnnotationTask {
+            input: input.to_string(),
+            ner_tags: align_tags(&tokens, &self.heuristic.field_spans(input, &predicted))
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            tokens: tokens.into_iter().map(|t| t.text).collect(),
+            score: (1.0 - confidence) + self.disagreement_weight * disagreement,
+            confidence,
+            disagreements,
+            skip: false,
+        }))
+    }
+
+    /// The `limit` highest-scoring tasks among `inputs`, best first.
+    /// Duplicate and blank inputs, and inputs the model cannot parse, are
+    /// skipped.
+    pub fn select<I, S>(&self, inputs: I, limit: usize) -> Vec<AnnotationTask>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut tasks = self.tasks(inputs, |_| true);
+        tasks.sort_by(|a, b| b.score.total_cmp(&a.score));
+        tasks.truncate(limit);
+        tasks
+    }
+
+    /// The tasks of the inputs where the model and the heuristic parser
+    /// disagree on one of [`KEY_FIELDS`], at most `limit` of them.
+    ///
+    /// These are the hard examples for both engines. Inputs disputed on more
+    /// key fields come first, then higher-scoring ones.
+    pub fn mine_disagreements<I, S>(&self, inputs: I, limit: usize) -> Vec<AnnotationTask>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut tasks = self.tasks(inputs, |task| key_disagreements(task) > 0);
+        tasks.sort_by(|a, b| {
+            key_disagreements(b)
+                .cmp(&key_disagreements(a))
+                .then(b.score.total_cmp(&a.score))
+        });
+        tasks.truncate(limit);
+        tasks
+    }
+
+    /// Tasks of the distinct, non-blank `inputs` the model can parse, where
+    /// `keep` accepts them.
+    fn tasks<I, S>(&self, inputs: I, keep: impl Fn(&AnnotationTask) -> bool) -> Vec<AnnotationTask>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut seen = std::collections::HashSet::new();
+        inputs
+            .into_iter()
+            .filter_map(|input| {
+                let input = input.as_ref().trim();
+                if input.is_empty() || !seen.insert(input.to_string()) {
+                    return None;
+                }
+                self.task(input).ok().flatten().filter(&keep)
+            })
+            .collect()
+    }
+}
+
+/// Fields [`ActiveLearner::mine_disagreements`] looks for disagreement on.
+pub const KEY_FIELDS: [ParseField; 3] = [ParseField::Title, ParseField::Episode, ParseField::Group];
+
+/// Number of [`KEY_FIELDS`] among the disagreements of `task`.
+fn key_disagreements(task: &AnnotationTask) -> usize {
+    task.disagreements
+        .iter()
+        .filter(|diff| KEY_FIELDS.contains(&diff.field))
+        .count()
+}
+
+/// Confidence of the least certain extracted field, or the overall
+/// confidence when the engine reports none per field.
+fn least_confidence(result: &ParseResult) -> f32 {
+    result
+        .field_confidence
+        .iter()
+        .map(|(_, confidence)| confidence)
+        .reduce(f32::min)
+        .unwrap_or(result.confidence)
+        .clamp(0.0, 1.0)
+}
+
+/// Write `tasks` as JSONL, one task per line.
+pub fn write_tasks<P: AsRef<Path>>(path: P, tasks: &[AnnotationTask]) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for task in tasks {
+        writeln!(writer, "{}", serde_json::to_string(task)?)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Outcome of [`merge_annotations`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeStats {
+    /// Corrections for tokens the training set did not have.
+    pub added: usize,
+    /// Corrections that replaced a sample of the same tokens.
+    pub replaced: usize,
+    /// Tasks marked `skip`, or with unknown tags or a tag count that does
+    /// not match the tokens.
+    pub rejected: usize,
+}
+
+/// Merge annotated tasks from `annotations` into the silver JSONL dataset at
+/// `training_set`, creating it if needed.
+///
+/// A correction replaces any existing sample with the same tokens, since the
+/// annotator's labels supersede the weak ones.
+pub fn merge_annotations<P: AsRef<Path>, Q: AsRef<Path>>(
+    annotations: P,
+    training_set: Q,
+) -> anyhow::Result<MergeStats> {
+    let training_set = training_set.as_ref();
+    let mut samples = Vec::new();
+    if training_set.exists() {
+        for line in BufReader::new(File::open(training_set)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                samples.push(serde_json::from_str::<BioSample>(&line)?);
+            }
+        }
+    }
+    let mut by_tokens: HashMap<Vec<String>, usize> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| (sample.tokens.clone(), i))
+        .collect();
+
+    let mut stats = MergeStats::default();
+    for line in BufReader::new(File::open(annotations)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let task: AnnotationTask = serde_json::from_str(&line)?;
+        let valid = task.tokens.len() == task.ner_tags.len()
+            && !task.tokens.is_empty()
+            && task.ner_tags.iter().all(|tag| label_index(tag).is_some());
+        if task.skip || !valid {
+            stats.rejected += 1;
+            continue;
+        }
+
+        let sample = BioSample {
+            tokens: task.tokens,
+            ner_tags: task.ner_tags,
+            weight: None,
+        };
+        match by_tokens.get(&sample.tokens) {
+            Some(&i) => {
+                samples[i] = sample;
+                stats.replaced += 1;
+            }
+            None => {
+                by_tokens.insert(sample.tokens.clone(), samples.len());
+                samples.push(sample);
+                stats.added += 1;
+            }
+        }
+    }
+
+    // Write beside the dataset and rename, so a failure cannot truncate it.
+    let partial = training_set.with_extension("jsonl.partial");
+    {
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        for sample in &samples {
+            writeln!(writer, "{}", serde_json::to_string(sample)?)?;
+        }
+        writer.flush()?;
+    }
+    std::fs::rename(&partial, training_set)?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zantetsu_core::parser::EngineCost;
+
+    /// A model that only ever finds a title, with low confidence.
+    struct TitleOnly;
+
+    impl ParseEngine for TitleOnly {
+        fn name(&self) -> &str {
+            "title_only"
+        }
+
+        fn cost_hint(&self) -> EngineCost {
+            EngineCost::Cheap
+        }
+
+        fn parse(&self, input: &str) -> zantetsu_core::Result<ParseResult> {
+            let mut result = ParseResult::new(input, zantetsu_core::ParseMode::Full);
+            result.title = input.split(" - ").next().map(str::to_string);
+            result.confidence = if input.contains('[') { 0.2 } else { 0.9 };
+            Ok(result)
+        }
+    }
+
+    #[test]
+    fn selects_uncertain_and_disputed_inputs() {
+        let learner = ActiveLearner::new(TitleOnly).unwrap();
+        let tasks = learner.select(
+            [
+                "Frieren",
+                "[SubsPlease] Frieren - 05 (1080p).mkv",
+                "Frieren",
+                "  ",
+            ],
+            5,
+        );
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].input, "[SubsPlease] Frieren - 05 (1080p).mkv");
+        assert!(tasks[0].score > tasks[1].score);
+        assert!(
+            tasks[0]
+                .disagreements
+                .iter()
+                .any(|d| d.field == ParseField::Episode)
+        );
+        assert_eq!(tasks[0].tokens.len(), tasks[0].ner_tags.len());
+        assert_eq!(learner.select(["a", "b", "c"], 1).len(), 1);
+    }
+
+    #[test]
+    fn mines_key_field_disagreements() {
+        let learner = ActiveLearner::new(TitleOnly).unwrap();
+        let tasks = learner.mine_disagreements(
+            [
+                "Frieren",
+                "Frieren - 05",
+                "[SubsPlease] Frieren - 05 (1080p).mkv",
+            ],
+            5,
+        );
+
+        let inputs: Vec<_> = tasks.iter().map(|task| task.input.as_str()).collect();
+        assert_eq!(
+            inputs,
+            ["[SubsPlease] Frieren - 05 (1080p).mkv", "Frieren - 05"]
+        );
+        assert_eq!(key_disagreements(&tasks[0]), 3);
+        assert_eq!(learner.mine_disagreements(["Frieren"], 5), []);
+    }
+
+    #[test]
+    fn merges_corrections_into_the_training_set() {
+        let dir = std::env::temp_dir().join(format!("zantetsu-active-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let training_set = dir.join("silver.jsonl");
+        let annotations = dir.join("annotated.jsonl");
+
+        let sample = |tokens: &[&str], tags: &[&str]| BioSample {
+            tokens: tokens.iter().map(|t| t.to_string()).collect(),
+            ner_tags: tags.iter().map(|t| t.to_string()).collect(),
+            weight: None,
+        };
+        std::fs::write(
+            &training_set,
+            serde_json::to_string(&sample(&["frieren", "05"], &["B-TITLE", "B-TITLE"])).unwrap(),
+        )
+        .unwrap();
+
+        let task = |tokens: &[&str], tags: &[&str], skip: bool| AnnotationTask {
+            input: tokens.join(" "),
+            tokens: tokens.iter().map(|t| t.to_string()).collect(),
+            ner_tags: tags.iter().map(|t| t.to_string()).collect(),
+            score: 1.0,
+            confidence: 0.0,
+            disagreements: Vec::new(),
+            skip,
+        };
+        write_tasks(
+            &annotations,
+            &[
+                task(&["frieren", "05"], &["B-TITLE", "B-EPISODE"], false),
+                task(&["bocchi", "mkv"], &["B-TITLE", "EXTENSION"], false),
+                task(&["spam"], &["O"], true),
+                task(&["bad"], &["B-NOTHING"], false),
+            ],
+        )
+        .unwrap();
+
+        let stats = merge_annotations(&annotations, &training_set).unwrap();
+        assert_eq!(
+            stats,
+            MergeStats {
+                added: 1,
+                replaced: 1,
+                rejected: 2,
+            }
+        );
+        let merged: Vec<BioSample> = std::fs::read_to_string(&training_set)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            merged,
+            [
+                sample(&["frieren", "05"], &["B-TITLE", "B-EPISODE"]),
+                sample(&["bocchi", "mkv"], &["B-TITLE", "EXTENSION"]),
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,146 @@
+//! Alignment of parse results onto word tokens.
+//!
+//! Turns the [`FieldSpan`]s of a [`ParseResult`](zantetsu_core::ParseResult)
+//! (see [`HeuristicParser::field_spans`]) into one [`BioTag`] per token of
+//! the input, which is how silver training data is bootstrapped from the
+//! heuristic parser and how model output is pre-annotated for review.
+//!
+//! [`HeuristicParser::field_spans`]: zantetsu_core::parser::HeuristicParser::field_spans
+
+use zantetsu_core::ParseField;
+use zantetsu_core::parser::tokenizer::Token;
+use zantetsu_core::parser::{BioTag, FieldSpan};
+
+/// The begin and inside tags of `field`; both are the same for single-token
+/// labels. `None` for fields without a tag.
+fn field_tags(field: ParseField) -> Option<(BioTag, BioTag)> {
+    let single = |tag| Some((tag, tag));
+    match field {
+        ParseField::Title => Some((BioTag::BeginTitle, BioTag::InsideTitle)),
+        ParseField::Group => Some((BioTag::BeginGroup, BioTag::InsideGroup)),
+        ParseField::Episode => Some((BioTag::BeginEpisode, BioTag::InsideEpisode)),
+        ParseField::Season => Some((BioTag::BeginSeason, BioTag::InsideSeason)),
+        ParseField::EpisodeTitle => Some((BioTag::BeginEpisodeTitle, BioTag::InsideEpisodeTitle)),
+        ParseField::Resolution => single(BioTag::Resolution),
+        ParseField::VideoCodec => single(BioTag::VCodec),
+        ParseField::AudioCodec => single(BioTag::ACodec),
+        ParseField::AudioChannels => single(BioTag::AudioChannels),
+        ParseField::Language => single(BioTag::Language),
+        ParseField::Source => single(BioTag::Source),
+        ParseField::Year | ParseField::YearRange => single(BioTag::Year),
+        ParseField::Crc32 => single(BioTag::Crc32),
+        ParseField::Extension => single(BioTag::Extension),
+        ParseField::Version => single(BioTag::Version),
+        ParseField::Part => single(BioTag::Part),
+        ParseField::ReleaseFlags => single(BioTag::ReleaseFlag),
+        ParseField::EpisodeCount => None,
+    }
+}
+
+/// Tag each of `tokens` with the field span it overlaps. Weak supervision:
+/// the result of a parser stands in for a human label.
+///
+/// The first token of a span gets the begin tag and the rest the inside
+/// tag. A token covering two spans (`S01E05` holds both season and episode)
+/// takes the one it overlaps most, the later one on a tie; tokens outside
+/// every span are [`BioTag::Outside`].
+pub fn align_tags(tokens: &[Token], spans: &[FieldSpan]) -> Vec<BioTag> {
+    let mut tags = vec![BioTag::Outside; tokens.len()];
+    let mut previous: Option<usize> = None;
+
+    for (tag, token) in tags.iter_mut().zip(tokens) {
+        let overlap = |span: &FieldSpan| {
+            span.end
+                .min(token.end)
+                .saturating_sub(span.start.max(token.start))
+        };
+        let best = spans
+            .iter()
+            .enumerate()
+            .filter(|(_, span)| overlap(span) > 0)
+            .max_by_key(|(_, span)| (overlap(span), span.start));
+        let Some((index, span)) = best else {
+            previous = None;
+            continue;
+        };
+        if let Some((begin, inside)) = field_tags(span.field) {
+            *tag = if previous == Some(index) {
+                inside
+            } else {
+                begin
+            };
+        }
+        previous = Some(index);
+    }
+
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zantetsu_core::parser::{HeuristicParser, Tokenizer};
+
+    fn tag_tokens(input: &str, part: Option<u32>) -> Vec<BioTag> {
+        let parser = HeuristicParser::new().unwrap();
+        let mut result = parser.parse(input).unwrap();
+        // The heuristic parser leaves parts in the title; the unified one
+        // splits them out.
+        if part.is_some() {
+            result.part = part;
+            result.title = result.title.map(|title| {
+                title
+                    .trim_end_matches(char::is_numeric)
+                    .trim_end()
+                    .trim_end_matches("Part")
+                    .trim_end()
+                    .to_string()
+            });
+        }
+        let tokens = Tokenizer::new().tokenize(input);
+        align_tags(&tokens, &parser.field_spans(input, &result))
+    }
+
+    #[test]
+    fn aligns_the_full_tag_set() {
+        let tags = tag_tokens(
+            "[SubsPlease] Kimetsu no Yaiba Part 2 - 05 (1080p) [BD x265 FLAC].mkv",
+            Some(2),
+        );
+        assert_eq!(
+            tags,
+            [
+                BioTag::BeginGroup,
+                BioTag::BeginTitle,
+                BioTag::InsideTitle,
+                BioTag::InsideTitle,
+                BioTag::Part,
+                BioTag::Part,
+                BioTag::BeginEpisode,
+                BioTag::Resolution,
+                BioTag::Source,
+                BioTag::VCodec,
+                BioTag::ACodec,
+                BioTag::Extension,
+            ]
+        );
+    }
+
+    #[test]
+    fn shared_words_follow_their_field() {
+        let tags = tag_tokens("[Nekomoe kissaten no] Kimetsu no Yaiba S01E05 [720p]", None);
+        assert_eq!(
+            tags,
+            [
+                BioTag::BeginGroup,
+                BioTag::InsideGroup,
+                BioTag::InsideGroup,
+                BioTag::BeginTitle,
+                BioTag::InsideTitle,
+                BioTag::InsideTitle,
+                BioTag::BeginEpisode,
+                BioTag::Resolution,
+            ]
+        );
+    }
+}
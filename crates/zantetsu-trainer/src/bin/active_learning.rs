@@ -0,0 +1,129 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use zantetsu_core::ModelPrecision;
+use zantetsu_core::parser::NeuralParser;
+use zantetsu_trainer::active::{ActiveLearner, KEY_FIELDS, merge_annotations, write_tasks};
+
+/// Pick filenames for annotation and merge the corrections back.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Rank an unlabeled corpus and write the most informative inputs as
+    /// annotation tasks.
+    Select {
+        /// Unlabeled filenames, one per line.
+        #[arg(long, default_value = "data/training/nyaa_titles_5000_raw.txt")]
+        corpus: PathBuf,
+        /// Model directory the current neural parser loads from.
+        #[arg(long, default_value = "models/ner_model")]
+        model_dir: PathBuf,
+        /// Number of tasks to emit.
+        #[arg(long, default_value_t = 200)]
+        limit: usize,
+        /// Where to write the annotation tasks (JSONL).
+        #[arg(long, default_value = "data/training/annotation_tasks.jsonl")]
+        output: PathBuf,
+    },
+    /// Run the heuristic and neural parsers over a corpus and write the
+    /// inputs where they disagree on title, episode or group as annotation
+    /// tasks.
+    Mine {
+        /// Filenames to compare the engines on, one per line.
+        #[arg(long, default_value = "data/training/nyaa_titles_5000_raw.txt")]
+        corpus: PathBuf,
+        /// Model directory the neural parser loads from.
+        #[arg(long, default_value = "models/ner_model")]
+        model_dir: PathBuf,
+        /// Most tasks to emit.
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+        /// Where to write the annotation tasks (JSONL).
+        #[arg(long, default_value = "data/training/disagreements.jsonl")]
+        output: PathBuf,
+    },
+    /// Merge annotated tasks into the training set.
+    Merge {
+        /// Annotated tasks (JSONL).
+        #[arg(long, default_value = "data/training/annotation_tasks.jsonl")]
+        annotations: PathBuf,
+        /// Silver dataset to update (JSONL).
+        #[arg(long, default_value = "data/training/silver_dataset.jsonl")]
+        training_set: PathBuf,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    match Cli::parse().command {
+        Command::Select {
+            corpus,
+            model_dir,
+            limit,
+            output,
+        } => {
+            let mut parser = NeuralParser::new()?;
+            parser.init_model_from_dir(&model_dir, ModelPrecision::F32)?;
+            let corpus = BufReader::new(File::open(&corpus)?)
+                .lines()
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let tasks = ActiveLearner::new(parser)?.select(&corpus, limit);
+            write_tasks(&output, &tasks)?;
+            println!(
+                "Wrote {} of {} inputs for annotation to {}",
+                tasks.len(),
+                corpus.len(),
+                output.display()
+            );
+        }
+        Command::Mine {
+            corpus,
+            model_dir,
+            limit,
+            output,
+        } => {
+            let mut parser = NeuralParser::new()?;
+            parser.init_model_from_dir(&model_dir, ModelPrecision::F32)?;
+            let corpus = BufReader::new(File::open(&corpus)?)
+                .lines()
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let tasks = ActiveLearner::new(parser)?.mine_disagreements(&corpus, limit);
+            write_tasks(&output, &tasks)?;
+            println!(
+                "Wrote {} disagreements from {} inputs to {}",
+                tasks.len(),
+                corpus.len(),
+                output.display()
+            );
+            for field in KEY_FIELDS {
+                let count = tasks
+                    .iter()
+                    .filter(|task| task.disagreements.iter().any(|d| d.field == field))
+                    .count();
+                println!("  {:<8} {count:>6}", field.to_string());
+            }
+        }
+        Command::Merge {
+            annotations,
+            training_set,
+        } => {
+            let stats = merge_annotations(&annotations, &training_set)?;
+            println!(
+                "Merged into {}: {} added, {} replaced, {} rejected",
+                training_set.display(),
+                stats.added,
+                stats.replaced,
+                stats.rejected
+            );
+        }
+    }
+    Ok(())
+}
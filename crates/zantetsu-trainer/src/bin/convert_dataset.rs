@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use zantetsu_trainer::data::{load_bio_dataset, write_bio_dataset};
+
+/// Convert a BIO dataset between the tab (`.txt`/`.tsv`), silver (`.jsonl`)
+/// and CoNLL (`.conll`) formats, picked by file extension.
+#[derive(Parser)]
+struct Args {
+    input: PathBuf,
+    output: PathBuf,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let examples = load_bio_dataset(&args.input)?;
+    write_bio_dataset(&args.output, &examples)?;
+    println!(
+        "Converted {} examples from {} to {}",
+        examples.len(),
+        args.input.display(),
+        args.output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,9 @@
+use zantetsu_trainer::run_char_cnn_training;
+
+fn main() {
+    tracing_subscriber::fmt::init();
+    if let Err(e) = run_char_cnn_training() {
+        eprintln!("Training failed: {}", e);
+        std::process::exit(1);
+    }
+}
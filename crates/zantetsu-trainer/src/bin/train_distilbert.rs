@@ -0,0 +1,9 @@
+use zantetsu_trainer::run_distilbert_training;
+
+fn main() {
+    tracing_subscriber::fmt::init();
+    if let Err(e) = run_distilbert_training() {
+        eprintln!("Training failed: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,252 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+use zantetsu_trainer::data::{load_bio_dataset, write_bio_dataset};
+use zantetsu_trainer::{
+    Checkpoint, MODEL_FILE, SynthGenerator, TRANSITIONS_DIR, TrainConfig, Trainer,
+    bootstrap_silver, load_titles, run_training, split_held_out,
+};
+
+/// Train, evaluate and export the CRF model, and prepare its datasets.
+///
+/// Every command reads its settings from `--config`; the other global flags
+/// override them.
+#[derive(Parser)]
+#[command(name = "zantetsu-train")]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct ConfigArgs {
+    /// TOML file with training settings.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    #[arg(long, global = true)]
+    learning_rate: Option<f32>,
+    #[arg(long, global = true)]
+    epochs: Option<usize>,
+    #[arg(long, global = true)]
+    batch_size: Option<usize>,
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    #[arg(long, global = true)]
+    train_data: Option<PathBuf>,
+    #[arg(long, global = true)]
+    held_out_fraction: Option<f32>,
+    #[arg(long, global = true)]
+    output_dir: Option<PathBuf>,
+    #[arg(long, global = true)]
+    checkpoint_every: Option<usize>,
+}
+
+impl ConfigArgs {
+    /// The `--config` file (else the settings of the `resume` checkpoint,
+    /// else the defaults) with the flags applied.
+    fn into_config(self, resume: Option<&Path>) -> anyhow::Result<TrainConfig> {
+        let mut config = match (&self.config, resume) {
+            (Some(path), _) => TrainConfig::load(path)?,
+            (None, Some(checkpoint)) => Checkpoint::load(checkpoint)?.config,
+            (None, None) => TrainConfig::default(),
+        };
+        if let Some(learning_rate) = self.learning_rate {
+            config.learning_rate = learning_rate;
+        }
+        if let Some(epochs) = self.epochs {
+            config.epochs = epochs;
+        }
+        if let Some(batch_size) = self.batch_size {
+            config.batch_size = batch_size;
+        }
+        if let Some(seed) = self.seed {
+            config.seed = seed;
+        }
+        if let Some(train_data) = self.train_data {
+            config.train_data = train_data;
+        }
+        if let Some(held_out_fraction) = self.held_out_fraction {
+            config.held_out_fraction = held_out_fraction;
+        }
+        if let Some(output_dir) = self.output_dir {
+            config.output_dir = output_dir;
+        }
+        if let Some(checkpoint_every) = self.checkpoint_every {
+            config.checkpoint_every = checkpoint_every;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Train on `train_data` and write the model and transitions to
+    /// `output_dir`.
+    Train {
+        /// Continue from a checkpoint, with its settings unless `--config`
+        /// is also given.
+        #[arg(long)]
+        resume: Option<PathBuf>,
+    },
+    /// Score a trained model per entity span and per parse field.
+    Eval {
+        /// Model to score [default: crf_model_v2.json in `output_dir`].
+        #[arg(long)]
+        model: Option<PathBuf>,
+        /// Dataset to score on in full, instead of the held-out split of
+        /// `train_data`.
+        #[arg(long)]
+        data: Option<PathBuf>,
+    },
+    /// Export the transitions of a trained model as `transitions.json`.
+    Export {
+        /// Model to export [default: crf_model_v2.json in `output_dir`].
+        #[arg(long)]
+        model: Option<PathBuf>,
+        /// Directory to write to [default: `output_dir/crf`]. Point it at a
+        /// neural model directory to replace that model's transitions.
+        #[arg(long)]
+        transitions_out: Option<PathBuf>,
+    },
+    /// Quantize a neural model directory's `model.safetensors` into the Int8
+    /// weights the parser loads with `ModelPrecision::Int8`.
+    Quantize {
+        #[arg(long, default_value = "models/ner_model")]
+        model_dir: PathBuf,
+    },
+    /// Label a raw filename corpus with the heuristic parser into a silver
+    /// dataset, filtering label noise.
+    Bootstrap {
+        /// Filenames, one per line.
+        #[arg(long, default_value = "data/training/nyaa_titles_5000_raw.txt")]
+        corpus: PathBuf,
+        #[arg(long, default_value = "data/training/silver_dataset.jsonl")]
+        output: PathBuf,
+    },
+    /// Generate labeled filenames from a list of titles, seeded by `seed`.
+    Synth {
+        /// Titles, one per line.
+        #[arg(long)]
+        titles: PathBuf,
+        /// Samples to generate.
+        #[arg(long, default_value_t = 10_000)]
+        count: usize,
+        /// Output dataset; the format follows the extension.
+        #[arg(long, default_value = "data/training/synth_dataset.jsonl")]
+        output: PathBuf,
+    },
+    /// Split `train_data` into training and held-out files by
+    /// `held_out_fraction`.
+    Split {
+        /// [default: `<train_data stem>.train.<ext>`]
+        #[arg(long)]
+        train_output: Option<PathBuf>,
+        /// [default: `<train_data stem>.held_out.<ext>`]
+        #[arg(long)]
+        held_out_output: Option<PathBuf>,
+    },
+}
+
+/// `path` with `.<suffix>` inserted before its extension.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{stem}.{suffix}.{}", ext.to_string_lossy())),
+        None => path.with_file_name(format!("{stem}.{suffix}")),
+    }
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
+    let resume = match &cli.command {
+        Command::Train { resume } => resume.clone(),
+        _ => None,
+    };
+    let config = cli.config.into_config(resume.as_deref())?;
+    let model_path = |model: Option<PathBuf>| model.unwrap_or(config.output_dir.join(MODEL_FILE));
+
+    match cli.command {
+        Command::Train { resume } => run_training(&config, resume.as_deref())?,
+        Command::Eval { model, data } => {
+            let trainer = Trainer::load_model(model_path(model))?;
+            let examples = match data {
+                Some(data) => load_bio_dataset(data)?,
+                None => {
+                    let examples = load_bio_dataset(&config.train_data)?;
+                    split_held_out(examples, config.held_out_fraction, config.seed).1
+                }
+            };
+            if examples.is_empty() {
+                anyhow::bail!("no examples to evaluate on");
+            }
+            println!("Evaluating on {} examples", examples.len());
+            println!("{}", trainer.evaluate(&examples)?);
+        }
+        Command::Export {
+            model,
+            transitions_out,
+        } => {
+            let dir = transitions_out.unwrap_or_else(|| config.output_dir.join(TRANSITIONS_DIR));
+            Trainer::load_model(model_path(model))?.export_transitions(dir)?;
+        }
+        Command::Quantize { model_dir } => {
+            let path = zantetsu_core::quantize_model(&model_dir)?;
+            println!("Wrote Int8 weights to {}", path.display());
+        }
+        Command::Bootstrap { corpus, output } => {
+            let report = bootstrap_silver(&corpus, &output)?;
+            print!("Label-noise filter: {report}");
+            println!(
+                "Bootstrapped {} samples to {}",
+                report.kept + report.down_weighted,
+                output.display()
+            );
+        }
+        Command::Synth {
+            titles,
+            count,
+            output,
+        } => {
+            let examples = SynthGenerator::new(load_titles(&titles)?, config.seed)?.generate(count);
+            write_bio_dataset(&output, &examples)?;
+            println!(
+                "Generated {} samples to {}",
+                examples.len(),
+                output.display()
+            );
+        }
+        Command::Split {
+            train_output,
+            held_out_output,
+        } => {
+            let data = &config.train_data;
+            let (train, held_out) = split_held_out(
+                load_bio_dataset(data)?,
+                config.held_out_fraction,
+                config.seed,
+            );
+            let train_output = train_output.unwrap_or_else(|| with_suffix(data, "train"));
+            let held_out_output = held_out_output.unwrap_or_else(|| with_suffix(data, "held_out"));
+            write_bio_dataset(&train_output, &train)?;
+            write_bio_dataset(&held_out_output, &held_out)?;
+            println!(
+                "Split {} into {} training examples ({}) and {} held-out examples ({})",
+                data.display(),
+                train.len(),
+                train_output.display(),
+                held_out.len(),
+                held_out_output.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("zantetsu-train: {e}");
+        std::process::exit(1);
+    }
+}
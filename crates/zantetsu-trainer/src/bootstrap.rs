@@ -0,0 +1,107 @@
+//! Silver dataset bootstrapping: label a raw filename corpus with the
+//! heuristic parser.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use zantetsu_core::parser::{BioTag, HeuristicParser, Tokenizer};
+
+use crate::align::align_tags;
+use crate::data::BioSample;
+use crate::noise::{NoiseFilter, NoiseReport};
+
+/// Heuristic results below this confidence are not used as labels.
+const MIN_CONFIDENCE: f32 = 0.20;
+
+/// Drop a leading `(...)` annotation, as some corpus dumps prefix lines
+/// with a rank or category.
+fn strip_prefix(line: &str) -> String {
+    let mut cleaned = line.trim();
+    if cleaned.starts_with('(')
+        && let Some(pos) = cleaned.find(')')
+    {
+        cleaned = &cleaned[pos + 1..];
+    }
+    cleaned.trim().to_string()
+}
+
+/// Label every distinct filename of the corpus at `input` (one per line)
+/// with the heuristic parser, pass the samples through the
+/// [`NoiseFilter`] and write the survivors to `output` as silver JSONL.
+///
+/// Inputs with fewer than three tokens, a low-confidence parse or neither
+/// title nor group are skipped. Returns the report of the noise filter.
+pub fn bootstrap_silver<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+) -> anyhow::Result<NoiseReport> {
+    let input = input.as_ref();
+    let reader = BufReader::new(
+        File::open(input).map_err(|e| anyhow::anyhow!("reading {}: {e}", input.display()))?,
+    );
+
+    let parser = HeuristicParser::new()?;
+    let tokenizer = Tokenizer::new();
+
+    let mut samples = Vec::new();
+    let mut seen_inputs: HashSet<String> = HashSet::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let cleaned = strip_prefix(&line);
+        if cleaned.is_empty() {
+            continue;
+        }
+
+        if !seen_inputs.insert(cleaned.clone()) {
+            continue;
+        }
+
+        if let Ok(result) = parser.parse(&cleaned)
+            && result.confidence >= MIN_CONFIDENCE
+            && (result.title.is_some() || result.group.is_some())
+        {
+            let tokens = tokenizer.tokenize(&cleaned);
+            if tokens.len() < 3 {
+                continue;
+            }
+
+            let tags = align_tags(&tokens, &parser.field_spans(&cleaned, &result));
+            if tags.iter().all(|t| *t == BioTag::Outside) {
+                continue;
+            }
+
+            samples.push(BioSample {
+                tokens: tokens.into_iter().map(|t| t.text).collect(),
+                ner_tags: tags.into_iter().map(|t| t.to_string()).collect(),
+                weight: None,
+            });
+        }
+    }
+
+    // Cross-check the silver labels and drop or down-weight noisy ones.
+    let (samples, report) = NoiseFilter::new()?.filter(samples);
+
+    let mut writer = BufWriter::new(File::create(output)?);
+    for sample in &samples {
+        writeln!(writer, "{}", serde_json::to_string(sample)?)?;
+    }
+    writer.flush()?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_corpus_prefixes() {
+        assert_eq!(
+            strip_prefix("  (12) [SubsPlease] Frieren - 05.mkv "),
+            "[SubsPlease] Frieren - 05.mkv"
+        );
+        assert_eq!(strip_prefix("Frieren (2023)"), "Frieren (2023)");
+    }
+}
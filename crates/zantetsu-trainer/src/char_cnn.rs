@@ -0,0 +1,285 @@
+//! Training for the character-level CNN model.
+//!
+//! Emissions are trained with per-character cross-entropy; CRF transitions
+//! are then set from smoothed tag-bigram log-frequencies of the training
+//! labels. Training data is the silver dataset written by `zantetsu-train bootstrap`.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use candle_core::{DType, Device, Tensor};
+use candle_nn::{AdamW, Optimizer, VarBuilder, VarMap};
+use serde::Deserialize;
+use zantetsu_core::crf::char_cnn::{CharCnnConfig, CharCnnModel, encode_chars};
+use zantetsu_core::parser::BioTag;
+
+/// A training example labeled per character.
+#[derive(Debug, Clone)]
+pub struct CharExample {
+    pub text: String,
+    /// One [`BioTag`] index per `char` of `text`.
+    pub tags: Vec<usize>,
+}
+
+impl CharExample {
+    /// Build a character-level example from word tokens joined by single spaces.
+    ///
+    /// A token tagged `B-X` labels its first character `B-X` and the rest
+    /// `I-X`; the space before a token tagged `I-X` is `I-X` too, so
+    /// multi-word entities stay one span.
+    pub fn from_tokens(tokens: &[String], tags: &[BioTag]) -> Self {
+        let mut text = String::new();
+        let mut char_tags = Vec::new();
+
+        for (i, (token, &tag)) in tokens.iter().zip(tags).enumerate() {
+            if i > 0 {
+                text.push(' ');
+                let space = if tag.is_inside() {
+                    tag
+                } else {
+                    BioTag::Outside
+                };
+                char_tags.push(space.index());
+            }
+            for (j, c) in token.chars().enumerate() {
+                text.push(c);
+                let char_tag = if j == 0 { tag } else { inside_of(tag) };
+                char_tags.push(char_tag.index());
+            }
+        }
+
+        Self {
+            text,
+            tags: char_tags,
+        }
+    }
+}
+
+/// The `I-` tag continuing `tag` (single-tag entities continue as themselves).
+pub(crate) fn inside_of(tag: BioTag) -> BioTag {
+    if !tag.is_begin() {
+        return tag;
+    }
+    BioTag::all_tags()
+        .iter()
+        .copied()
+        .find(|candidate| candidate.is_inside() && candidate.entity_type() == tag.entity_type())
+        .unwrap_or(tag)
+}
+
+#[derive(Deserialize)]
+struct SilverSample {
+    tokens: Vec<String>,
+    ner_tags: Vec<String>,
+}
+
+/// Load a JSONL dataset of `{"tokens": [...], "ner_tags": [...]}` samples.
+///
+/// Samples with unknown tags or mismatched lengths are skipped.
+pub fn load_silver_dataset<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<CharExample>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut examples = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let sample: SilverSample = serde_json::from_str(&line)?;
+        let tags: Option<Vec<BioTag>> = sample
+            .ner_tags
+            .iter()
+            .map(|label| {
+                BioTag::all_tags()
+                    .iter()
+                    .copied()
+                    .find(|tag| tag.to_string() == *label)
+            })
+            .collect();
+        match tags {
+            Some(tags) if tags.len() == sample.tokens.len() && !tags.is_empty() => {
+                examples.push(CharExample::from_tokens(&sample.tokens, &tags));
+            }
+            _ => continue,
+        }
+    }
+
+    Ok(examples)
+}
+
+/// Trains a [`CharCnnModel`] and writes it where `CharCnnParser` loads it from.
+pub struct CharCnnTrainer {
+    varmap: VarMap,
+    model: CharCnnModel,
+    config: CharCnnConfig,
+    optimizer: AdamW,
+    device: Device,
+}
+
+impl CharCnnTrainer {
+    /// Create a randomly initialized model with the given hyperparameters.
+    pub fn new(config: CharCnnConfig, learning_rate: f64) -> anyhow::Result<Self> {
+        let device = Device::Cpu;
+        let varmap = VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &device);
+        let model = CharCnnModel::load(vb, &config)?;
+        let optimizer = AdamW::new_lr(varmap.all_vars(), learning_rate)?;
+
+        Ok(Self {
+            varmap,
+            model,
+            config,
+            optimizer,
+            device,
+        })
+    }
+
+    /// Take one optimizer step on `example`, returning its loss.
+    pub fn train_step(&mut self, example: &CharExample) -> anyhow::Result<f32> {
+        let ids = encode_chars(&example.text);
+        let input = Tensor::new(ids.as_slice(), &self.device)?.unsqueeze(0)?;
+        let targets: Vec<u32> = example.tags.iter().map(|&tag| tag as u32).collect();
+        let targets = Tensor::new(targets.as_slice(), &self.device)?;
+
+        let emissions = self.model.forward(&input)?.squeeze(0)?;
+        let loss = candle_nn::loss::cross_entropy(&emissions, &targets)?;
+        self.optimizer.backward_step(&loss)?;
+        Ok(loss.to_scalar::<f32>()?)
+    }
+
+    /// Train for `epochs` passes over `examples`, then fit the transitions.
+    pub fn train(&mut self, examples: &[CharExample], epochs: usize) -> anyhow::Result<()> {
+        for epoch in 0..epochs {
+            let mut total_loss = 0.0f32;
+            let mut steps = 0usize;
+
+            for example in examples.iter().filter(|e| !e.text.is_empty()) {
+                total_loss += self.train_step(example)?;
+                steps += 1;
+            }
+
+            tracing::info!(
+                "Epoch {}/{} complete - Loss: {:.4}",
+                epoch + 1,
+                epochs,
+                total_loss / steps.max(1) as f32
+            );
+        }
+
+        self.fit_transitions(examples)
+    }
+
+    /// Set CRF transitions to add-one smoothed log-probabilities of the tag
+    /// bigrams in `examples`.
+    pub fn fit_transitions(&mut self, examples: &[CharExample]) -> anyhow::Result<()> {
+        let num_tags = self.model.tag_set().num_tags();
+        let mut counts = vec![vec![1.0f32; num_tags]; num_tags];
+        for example in examples {
+            for pair in example.tags.windows(2) {
+                counts[pair[0]][pair[1]] += 1.0;
+            }
+        }
+
+        let transitions: Vec<f32> = counts
+            .iter()
+            .flat_map(|row| {
+                let total: f32 = row.iter().sum();
+                row.iter().map(move |count| (count / total).ln())
+            })
+            .collect();
+        let transitions = Tensor::from_vec(transitions, (num_tags, num_tags), &self.device)?;
+        self.varmap.set_one("transitions", &transitions)?;
+        self.model.transitions = transitions;
+        Ok(())
+    }
+
+    /// Write `model.safetensors` and `config.json` into `dir`.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> anyhow::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        self.varmap.save(dir.join("model.safetensors"))?;
+        std::fs::write(
+            dir.join("config.json"),
+            serde_json::to_string_pretty(&self.config)?,
+        )?;
+        tracing::info!("Model saved to {}", dir.display());
+        Ok(())
+    }
+}
+
+pub fn run_char_cnn_training() -> anyhow::Result<()> {
+    let data_path = "data/training/silver_dataset.jsonl";
+
+    if !Path::new(data_path).exists() {
+        anyhow::bail!("Training data not found: {}", data_path);
+    }
+
+    let examples = load_silver_dataset(data_path)?;
+    tracing::info!("Loaded {} training examples", examples.len());
+
+    let mut trainer = CharCnnTrainer::new(CharCnnConfig::default(), 1e-3)?;
+    trainer.train(&examples, 3)?;
+    trainer.save("models/char_cnn")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zantetsu_core::parser::CharCnnParser;
+
+    #[test]
+    fn labels_every_char() {
+        let tokens: Vec<String> = ["Frieren", "Beyond", "-", "01"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let tags = [
+            BioTag::BeginTitle,
+            BioTag::InsideTitle,
+            BioTag::Outside,
+            BioTag::BeginEpisode,
+        ];
+        let example = CharExample::from_tokens(&tokens, &tags);
+
+        assert_eq!(example.text, "Frieren Beyond - 01");
+        assert_eq!(example.tags.len(), example.text.chars().count());
+        assert_eq!(example.tags[0], BioTag::BeginTitle.index());
+        assert_eq!(example.tags[7], BioTag::InsideTitle.index());
+        assert_eq!(example.tags[14], BioTag::Outside.index());
+        assert_eq!(example.tags[17], BioTag::BeginEpisode.index());
+        assert_eq!(example.tags[18], BioTag::InsideEpisode.index());
+    }
+
+    #[test]
+    fn trained_model_loads_in_core() {
+        let example = CharExample::from_tokens(
+            &["[Group]".to_string(), "Title".to_string()],
+            &[BioTag::BeginGroup, BioTag::BeginTitle],
+        );
+        let config = CharCnnConfig {
+            filters: 8,
+            hidden_dim: 8,
+            ..CharCnnConfig::default()
+        };
+        let mut trainer = CharCnnTrainer::new(config, 1e-2).unwrap();
+
+        let first = trainer.train_step(&example).unwrap();
+        let mut last = first;
+        for _ in 0..20 {
+            last = trainer.train_step(&example).unwrap();
+        }
+        assert!(last < first, "loss did not decrease: {first} -> {last}");
+        trainer.fit_transitions(&[example]).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("zantetsu-train-cnn-{}", std::process::id()));
+        trainer.save(&dir).unwrap();
+        let mut parser = CharCnnParser::new();
+        parser.init_model_from_dir(&dir).unwrap();
+        assert!(parser.parse("[Group] Title").is_ok());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
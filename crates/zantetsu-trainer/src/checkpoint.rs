@@ -0,0 +1,174 @@
+//! Training checkpoints, so an interrupted run can pick up where it stopped.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::TrainConfig;
+use crate::data::TrainingExample;
+
+/// File name of the checkpoint inside [`TrainConfig::output_dir`].
+pub const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+/// Everything needed to continue a [`Trainer`](crate::Trainer) run exactly
+/// where it stopped.
+///
+/// The perceptron keeps no optimizer state beyond its weights, and each
+/// epoch's shuffle is derived from the seed and epoch number, so the model,
+/// the config and the position in the run are enough.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Epoch in progress (0-based).
+    pub epoch: usize,
+    /// Examples of the shuffled epoch already trained on.
+    pub step: usize,
+    /// [`dataset_hash`] of the training set, checked on resume.
+    pub dataset_hash: String,
+    /// Settings of the run.
+    pub config: TrainConfig,
+    /// Model weights, in the [`CrfModel::to_json`](crate::CrfModel::to_json)
+    /// layout.
+    pub model: serde_json::Value,
+}
+
+impl Checkpoint {
+    /// Write the checkpoint to `path`, through a temporary file so an
+    /// interruption never leaves a torn checkpoint behind.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let partial = path.with_extension("json.partial");
+        {
+            let mut writer = BufWriter::new(File::create(&partial)?);
+            serde_json::to_writer(&mut writer, self)?;
+            writer.flush()?;
+        }
+        std::fs::rename(&partial, path)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("reading {}: {e}", path.display()))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Stable fingerprint of a training set: FNV-1a over every token, label and
+/// weight, in order, as 16 hex digits.
+pub fn dataset_hash(examples: &[TrainingExample]) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+    for example in examples {
+        feed(&(example.tokens.len() as u64).to_le_bytes());
+        for token in &example.tokens {
+            feed(&(token.len() as u64).to_le_bytes());
+            feed(token.as_bytes());
+        }
+        for &label in &example.labels {
+            feed(&(label as u64).to_le_bytes());
+        }
+        feed(&example.weight.to_bits().to_le_bytes());
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Trainer;
+    use crate::data::label_index;
+
+    fn examples() -> Vec<TrainingExample> {
+        let example = |tokens: &[&str], labels: &[&str]| {
+            TrainingExample::new(
+                tokens.iter().map(|t| t.to_string()).collect(),
+                labels.iter().map(|l| label_index(l).unwrap()).collect(),
+            )
+        };
+        vec![
+            example(
+                &["[SubsPlease]", "Frieren", "-", "05"],
+                &["B-GROUP", "B-TITLE", "O", "B-EPISODE"],
+            ),
+            example(
+                &["Bocchi", "-", "12", "1080p"],
+                &["B-TITLE", "O", "B-EPISODE", "RESOLUTION"],
+            ),
+            example(
+                &["[Erai-raws]", "Oshi", "no", "Ko", "-", "03"],
+                &["B-GROUP", "B-TITLE", "I-TITLE", "I-TITLE", "O", "B-EPISODE"],
+            ),
+            example(
+                &["Dandadan", "S01E04", "x265", "mkv"],
+                &["B-TITLE", "B-EPISODE", "VCODEC", "EXTENSION"],
+            ),
+            example(
+                &["[Judas]", "Mushishi", "-", "26", "FLAC"],
+                &["B-GROUP", "B-TITLE", "O", "B-EPISODE", "ACODEC"],
+            ),
+        ]
+    }
+
+    #[test]
+    fn resumed_training_matches_an_uninterrupted_run() {
+        let dir = std::env::temp_dir().join(format!("zantetsu-checkpoint-{}", std::process::id()));
+        let config = TrainConfig {
+            output_dir: dir.clone(),
+            checkpoint_every: 2,
+            ..TrainConfig::default()
+        };
+        let examples = examples();
+
+        let mut uninterrupted = Trainer::new().with_config(config.clone());
+        uninterrupted.train_on_examples(&examples, 3).unwrap();
+        let expected = Checkpoint::load(uninterrupted.checkpoint_path()).unwrap();
+        assert_eq!((expected.epoch, expected.step), (3, 0));
+
+        let mut first = Trainer::new().with_config(config);
+        first.train_on_examples(&examples, 1).unwrap();
+        let checkpoint = Checkpoint::load(first.checkpoint_path()).unwrap();
+        assert_eq!((checkpoint.epoch, checkpoint.step), (1, 0));
+        assert_eq!(checkpoint.dataset_hash, dataset_hash(&examples));
+
+        let mut resumed = Trainer::resume(first.checkpoint_path()).unwrap();
+        resumed.train_on_examples(&examples, 3).unwrap();
+        let actual = Checkpoint::load(resumed.checkpoint_path()).unwrap();
+        assert_eq!(actual.model, expected.model);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn refuses_to_resume_on_other_data() {
+        let dir =
+            std::env::temp_dir().join(format!("zantetsu-checkpoint-data-{}", std::process::id()));
+        let config = TrainConfig {
+            output_dir: dir.clone(),
+            ..TrainConfig::default()
+        };
+        let mut examples = examples();
+        let mut trainer = Trainer::new().with_config(config);
+        trainer.train_on_examples(&examples, 1).unwrap();
+
+        examples.pop();
+        let mut resumed = Trainer::resume(trainer.checkpoint_path()).unwrap();
+        let error = resumed.train_on_examples(&examples, 2).unwrap_err();
+        assert!(error.to_string().contains("dataset"), "{error}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,157 @@
+//! Hyperparameters and paths for a CRF training run.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::label_index;
+use crate::model::NUM_LABELS;
+
+/// Settings for [`run_training`](crate::run_training), loadable from TOML.
+///
+/// Every key is optional; missing keys keep their defaults.
+///
+/// ```toml
+/// learning_rate = 0.05
+/// epochs = 5
+/// seed = 7
+/// train_data = "data/training/bio_train_50k.txt"
+///
+/// [label_weights]
+/// B-EPISODE_TITLE = 2.0
+/// I-EPISODE_TITLE = 2.0
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrainConfig {
+    /// Perceptron step size.
+    pub learning_rate: f32,
+    /// Passes over the training set.
+    pub epochs: usize,
+    /// Examples decoded with the same weights before their updates are
+    /// applied. `1` trains fully online.
+    pub batch_size: usize,
+    /// Seed for the per-epoch shuffle and the held-out split, recorded in
+    /// the saved model and its transitions.
+    pub seed: u64,
+    /// BIO dataset (`.txt`/`.tsv` or silver `.jsonl`).
+    pub train_data: PathBuf,
+    /// Share of the dataset held out for evaluation.
+    pub held_out_fraction: f32,
+    /// Update scale per BIO label, for labels too rare to learn at the
+    /// default weight of `1.0`.
+    pub label_weights: BTreeMap<String, f32>,
+    /// Directory for `crf_model_v2.json` and the `crf/` transitions.
+    pub output_dir: PathBuf,
+    /// Examples between checkpoints in `output_dir`, which are also written
+    /// after every epoch. `0` disables checkpointing.
+    pub checkpoint_every: usize,
+}
+
+impl Default for TrainConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            epochs: 3,
+            batch_size: 1,
+            seed: 42,
+            train_data: PathBuf::from("data/training/bio_train_50k.txt"),
+            held_out_fraction: 0.1,
+            label_weights: BTreeMap::new(),
+            output_dir: PathBuf::from("models"),
+            checkpoint_every: 10_000,
+        }
+    }
+}
+
+impl TrainConfig {
+    /// Parse and validate a TOML document.
+    pub fn from_toml(toml: &str) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Read and validate the TOML file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("reading {}: {e}", path.display()))?;
+        Self::from_toml(&toml).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+    }
+
+    /// Check that every setting is in range and every weighted label exists.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.learning_rate.is_finite() && self.learning_rate > 0.0) {
+            anyhow::bail!("learning_rate must be positive, got {}", self.learning_rate);
+        }
+        if self.epochs == 0 {
+            anyhow::bail!("epochs must be at least 1");
+        }
+        if self.batch_size == 0 {
+            anyhow::bail!("batch_size must be at least 1");
+        }
+        if !(0.0..1.0).contains(&self.held_out_fraction) {
+            anyhow::bail!(
+                "held_out_fraction must be in [0, 1), got {}",
+                self.held_out_fraction
+            );
+        }
+        for (label, &weight) in &self.label_weights {
+            if label_index(label).is_none() {
+                anyhow::bail!("label_weights: unknown label {label}");
+            }
+            if !(weight.is_finite() && weight >= 0.0) {
+                anyhow::bail!("label_weights: {label} must be non-negative, got {weight}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Weight of every label in index order, `1.0` where none is set.
+    pub fn label_weight_table(&self) -> [f32; NUM_LABELS] {
+        let mut weights = [1.0; NUM_LABELS];
+        for (label, &weight) in &self.label_weights {
+            if let Some(index) = label_index(label) {
+                weights[index] = weight;
+            }
+        }
+        weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_partial_toml_over_defaults() {
+        let config = TrainConfig::from_toml(
+            "epochs = 5\nseed = 7\n\n[label_weights]\nB-EPISODE_TITLE = 2.5\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.epochs, 5);
+        assert_eq!(config.seed, 7);
+        assert_eq!(config.learning_rate, TrainConfig::default().learning_rate);
+        let weights = config.label_weight_table();
+        assert_eq!(weights[label_index("B-EPISODE_TITLE").unwrap()], 2.5);
+        assert_eq!(weights[label_index("O").unwrap()], 1.0);
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        for toml in [
+            "learning_rate = 0.0",
+            "epochs = 0",
+            "batch_size = 0",
+            "held_out_fraction = 1.0",
+            "[label_weights]\nB-NOTHING = 1.0",
+            "[label_weights]\nO = -1.0",
+            "learning_rat = 0.1",
+        ] {
+            assert!(TrainConfig::from_toml(toml).is_err(), "{toml}");
+        }
+    }
+}
@@ -1,29 +1,121 @@
 //! Data loading for BIO-tagged training data.
 
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 
+use serde::{Deserialize, Serialize};
+
 /// A single training example: sequence of (token, label) pairs.
 #[derive(Debug, Clone)]
 pub struct TrainingExample {
     pub tokens: Vec<String>,
+    /// Label indices into [`BIO_LABELS`].
     pub labels: Vec<usize>,
+    /// Scale of the example's updates; below `1.0` for labels that are
+    /// probably noisy.
+    pub weight: f32,
+}
+
+/// BIO labels, in [`BioTag::index`](zantetsu_core::parser::BioTag::index)
+/// order so trained artifacts line up with the neural parser's tag set.
+pub const BIO_LABELS: &[&str] = &[
+    "B-TITLE",
+    "I-TITLE",
+    "B-GROUP",
+    "I-GROUP",
+    "B-EPISODE",
+    "I-EPISODE",
+    "B-SEASON",
+    "I-SEASON",
+    "RESOLUTION",
+    "VCODEC",
+    "ACODEC",
+    "SOURCE",
+    "YEAR",
+    "CRC32",
+    "EXTENSION",
+    "VERSION",
+    "O",
+    "B-EPISODE_TITLE",
+    "I-EPISODE_TITLE",
+    "LANGUAGE",
+    "AUDIO_CHANNELS",
+    "PART",
+    "RELEASE_FLAG",
+];
+
+/// `0..len` in an order shuffled by `seed` (Fisher-Yates). The same seed
+/// always gives the same order.
+pub fn shuffled_indices(len: usize, seed: u128) -> Vec<usize> {
+    let mut rng = oorandom::Rand64::new(seed);
+    let mut indices: Vec<usize> = (0..len).collect();
+    for i in (1..len).rev() {
+        let j = rng.rand_range(0..(i as u64 + 1)) as usize;
+        indices.swap(i, j);
+    }
+    indices
 }
 
-/// BIO labels
-pub const BIO_LABELS: &[&str] = &["O", "B-TITLE", "I-TITLE"];
+/// Index of `label` in [`BIO_LABELS`].
+pub fn label_index(label: &str) -> Option<usize> {
+    BIO_LABELS.iter().position(|known| *known == label)
+}
+
+/// One line of a silver JSONL dataset, as written by `zantetsu-train bootstrap`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BioSample {
+    pub tokens: Vec<String>,
+    /// One [`BIO_LABELS`] entry per token.
+    pub ner_tags: Vec<String>,
+    /// Training weight, when not `1.0` (see [`NoiseFilter`](crate::noise::NoiseFilter)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f32>,
+}
 
 impl TrainingExample {
     pub fn new(tokens: Vec<String>, labels: Vec<usize>) -> Self {
-        Self { tokens, labels }
+        Self {
+            tokens,
+            labels,
+            weight: 1.0,
+        }
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
     }
 }
 
+/// Index of a CoNLL tag in [`BIO_LABELS`].
+///
+/// Annotation tools prefix every entity tag with `B-`/`I-`, so labels that
+/// are single tags here (`B-RESOLUTION`, `I-VCODEC`, ...) map to their bare
+/// form.
+pub fn conll_label_index(tag: &str) -> Option<usize> {
+    label_index(tag).or_else(|| {
+        let bare = tag.strip_prefix("B-").or_else(|| tag.strip_prefix("I-"))?;
+        label_index(bare).filter(|&index| !BIO_LABELS[index].contains('-') && bare != "O")
+    })
+}
+
 /// Load dataset from BIO format file.
+///
+/// Reads `token\tlabel` lines with blank lines between examples; for a
+/// `.jsonl` file, the `{"tokens": [...], "ner_tags": [...]}` samples written
+/// by `zantetsu-train bootstrap`; and for a `.conll` file, CoNLL-2003 style data
+/// (see [`load_conll`]). Tokens with unknown labels are skipped.
 pub fn load_bio_dataset<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<TrainingExample>> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext == "conll") {
+        return load_conll(path);
+    }
     let file = File::open(path)?;
     let reader = BufReader::new(file);
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        return load_jsonl_samples(reader);
+    }
 
     let mut examples = Vec::new();
     let mut current_tokens = Vec::new();
@@ -52,11 +144,8 @@ pub fn load_bio_dataset<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<Training
             let token = parts[0].to_string();
             let label_str = parts[1];
 
-            let label_idx = match label_str {
-                "O" => 0,
-                "B-TITLE" => 1,
-                "I-TITLE" => 2,
-                _ => continue,
+            let Some(label_idx) = label_index(label_str) else {
+                continue;
             };
 
             current_tokens.push(token);
@@ -72,6 +161,134 @@ pub fn load_bio_dataset<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<Training
     Ok(examples)
 }
 
+fn load_jsonl_samples<R: BufRead>(reader: R) -> std::io::Result<Vec<TrainingExample>> {
+    let mut examples = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let sample: BioSample = serde_json::from_str(&line)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        let (tokens, labels): (Vec<String>, Vec<usize>) = sample
+            .tokens
+            .into_iter()
+            .zip(&sample.ner_tags)
+            .filter_map(|(token, tag)| Some((token, label_index(tag)?)))
+            .unzip();
+        if !tokens.is_empty() {
+            examples.push(
+                TrainingExample::new(tokens, labels).with_weight(sample.weight.unwrap_or(1.0)),
+            );
+        }
+    }
+    Ok(examples)
+}
+
+/// Load a CoNLL-2003 style file, as exported by doccano or Label Studio.
+///
+/// Each line holds whitespace-separated columns with the token first and its
+/// tag last (`token POS chunk tag`, `token -X- _ tag` or just `token tag`);
+/// blank lines end a sentence and `-DOCSTART-` lines are ignored. Tags go
+/// through [`conll_label_index`], and tokens with unknown tags are skipped.
+pub fn load_conll<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<TrainingExample>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut examples = Vec::new();
+    let mut current = TrainingExample::new(Vec::new(), Vec::new());
+
+    for line in reader.lines() {
+        let line = line?;
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let (Some(token), Some(tag)) = (columns.first(), columns.last()) else {
+            if !current.tokens.is_empty() {
+                examples.push(std::mem::replace(
+                    &mut current,
+                    TrainingExample::new(Vec::new(), Vec::new()),
+                ));
+            }
+            continue;
+        };
+        if *token == "-DOCSTART-" || columns.len() < 2 {
+            continue;
+        }
+        if let Some(label) = conll_label_index(tag) {
+            current.tokens.push(token.to_string());
+            current.labels.push(label);
+        }
+    }
+
+    if !current.tokens.is_empty() {
+        examples.push(current);
+    }
+    Ok(examples)
+}
+
+/// Write `examples` as CoNLL-2003 style `token -X- _ tag` lines, with a
+/// blank line after each sentence.
+///
+/// Single-tag labels are written with a `B-` prefix so annotation tools
+/// read them as entities; [`load_conll`] maps them back. Fails with
+/// [`std::io::ErrorKind::InvalidInput`] for tokens containing whitespace,
+/// which the format cannot represent.
+pub fn write_conll<P: AsRef<Path>>(path: P, examples: &[TrainingExample]) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "-DOCSTART- -X- -X- O\n")?;
+    for example in examples {
+        for (token, &label) in example.tokens.iter().zip(&example.labels) {
+            if token.is_empty() || token.contains(char::is_whitespace) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("token {token:?} cannot be written as CoNLL"),
+                ));
+            }
+            let label = BIO_LABELS.get(label).copied().unwrap_or("O");
+            if label == "O" || label.contains('-') {
+                writeln!(writer, "{token} -X- _ {label}")?;
+            } else {
+                writeln!(writer, "{token} -X- _ B-{label}")?;
+            }
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// Write `examples` in the format [`load_bio_dataset`] picks for `path`:
+/// CoNLL for `.conll`, silver samples for `.jsonl`, and `token\tlabel`
+/// lines otherwise.
+pub fn write_bio_dataset<P: AsRef<Path>>(
+    path: P,
+    examples: &[TrainingExample],
+) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    if extension == Some("conll") {
+        return write_conll(path, examples);
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    let label = |index: usize| BIO_LABELS.get(index).copied().unwrap_or("O").to_string();
+    for example in examples {
+        if extension == Some("jsonl") {
+            let sample = BioSample {
+                tokens: example.tokens.clone(),
+                ner_tags: example.labels.iter().map(|&l| label(l)).collect(),
+                weight: (example.weight != 1.0).then_some(example.weight),
+            };
+            let line = serde_json::to_string(&sample)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            writeln!(writer, "{line}")?;
+        } else {
+            for (token, &l) in example.tokens.iter().zip(&example.labels) {
+                writeln!(writer, "{token}\t{}", label(l))?;
+            }
+            writeln!(writer)?;
+        }
+    }
+    writer.flush()
+}
+
 /// Character vocabulary for encoding tokens.
 pub struct CharVocab {
     char_to_idx: std::collections::HashMap<char, usize>,
@@ -112,6 +329,115 @@ impl Default for CharVocab {
 mod tests {
     use super::*;
 
+    use zantetsu_core::parser::BioTag;
+
+    #[test]
+    fn labels_follow_core_tag_indices() {
+        assert_eq!(BIO_LABELS.len(), BioTag::NUM_TAGS);
+        for (index, label) in BIO_LABELS.iter().enumerate() {
+            assert_eq!(BioTag::from_index(index).unwrap().to_string(), *label);
+        }
+    }
+
+    #[test]
+    fn loads_tsv_and_jsonl_datasets() {
+        let dir = std::env::temp_dir().join(format!("zantetsu-bio-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let tsv = dir.join("train.txt");
+        std::fs::write(
+            &tsv,
+            "subsplease\tB-GROUP\nfrieren\tB-TITLE\n05\tB-EPISODE\n1080p\tRESOLUTION\n\nmkv\tEXTENSION\n",
+        )
+        .unwrap();
+        let examples = load_bio_dataset(&tsv).unwrap();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(
+            examples[0].labels,
+            [
+                BioTag::BeginGroup,
+                BioTag::BeginTitle,
+                BioTag::BeginEpisode,
+                BioTag::Resolution
+            ]
+            .map(|tag| tag.index())
+        );
+
+        let jsonl = dir.join("silver.jsonl");
+        std::fs::write(
+            &jsonl,
+            r#"{"tokens": ["frieren", "hevc", "flac"], "ner_tags": ["B-TITLE", "VCODEC", "ACODEC"]}"#,
+        )
+        .unwrap();
+        let examples = load_bio_dataset(&jsonl).unwrap();
+        assert_eq!(
+            examples[0].labels,
+            [BioTag::BeginTitle, BioTag::VCodec, BioTag::ACodec].map(|tag| tag.index())
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reads_and_writes_conll() {
+        let dir = std::env::temp_dir().join(format!("zantetsu-conll-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Label Studio export: `token -X- _ tag`, with IOB2 prefixes on
+        // every entity.
+        let exported = dir.join("label_studio.conll");
+        std::fs::write(
+            &exported,
+            "-DOCSTART- -X- O O\n\n\
+             [SubsPlease] -X- _ B-GROUP\n\
+             Oshi -X- _ B-TITLE\nno -X- _ I-TITLE\nKo -X- _ I-TITLE\n\
+             - -X- _ O\n03 -X- _ B-EPISODE\n1080p -X- _ B-RESOLUTION\n\
+             \n\
+             Frieren NNP B-NP B-TITLE\nWEB -X- _ I-SOURCE\nmkv -X- _ B-UNKNOWN\n",
+        )
+        .unwrap();
+        let examples = load_bio_dataset(&exported).unwrap();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].tokens[1..4], ["Oshi", "no", "Ko"]);
+        assert_eq!(
+            examples[0].labels,
+            [
+                BioTag::BeginGroup,
+                BioTag::BeginTitle,
+                BioTag::InsideTitle,
+                BioTag::InsideTitle,
+                BioTag::Outside,
+                BioTag::BeginEpisode,
+                BioTag::Resolution,
+            ]
+            .map(|tag| tag.index())
+        );
+        assert_eq!(examples[1].tokens, ["Frieren", "WEB"]);
+        assert_eq!(
+            examples[1].labels,
+            [BioTag::BeginTitle.index(), BioTag::Source.index()]
+        );
+
+        for name in ["roundtrip.conll", "roundtrip.jsonl", "roundtrip.txt"] {
+            let path = dir.join(name);
+            write_bio_dataset(&path, &examples).unwrap();
+            let reloaded = load_bio_dataset(&path).unwrap();
+            assert_eq!(reloaded.len(), examples.len(), "{name}");
+            for (reloaded, original) in reloaded.iter().zip(&examples) {
+                assert_eq!(reloaded.tokens, original.tokens, "{name}");
+                assert_eq!(reloaded.labels, original.labels, "{name}");
+            }
+        }
+        let written = std::fs::read_to_string(dir.join("roundtrip.conll")).unwrap();
+        assert!(written.contains("1080p -X- _ B-RESOLUTION\n"));
+
+        let spaced = [TrainingExample::new(vec!["Oshi no Ko".into()], vec![0])];
+        let error = write_conll(dir.join("spaced.conll"), &spaced).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn test_vocab() {
         let vocab = CharVocab::new();
@@ -0,0 +1,743 @@
+//! End-to-end fine-tuning of the DistilBERT-CRF model.
+//!
+//! Starts from a pretrained Hugging Face DistilBERT checkpoint, adds the
+//! emission head (`classifier`) and CRF transitions (`crf_transitions`), and
+//! trains all of them on the CRF negative log-likelihood of the BIO dataset.
+//! The output directory loads directly in `NeuralParser`.
+//!
+//! candle's fused layer norm has no backward pass, so the encoder here is a
+//! differentiable re-implementation that keeps the tensor names of candle's
+//! `DistilBertModel`.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use candle_core::{D, DType, Device, Module, Tensor};
+use candle_nn::{AdamW, Embedding, Init, Linear, Optimizer, ParamsAdamW, VarBuilder, VarMap};
+use tokenizers::Tokenizer;
+use zantetsu_core::crf::encoder::EncoderConfig;
+use zantetsu_core::crf::manifest::{MANIFEST_FILE, ModelManifest};
+use zantetsu_core::crf::model::candle_tensor_name;
+use zantetsu_core::crf::transitions::{TRANSITIONS_FILE, TransitionMatrix};
+use zantetsu_core::parser::{BioTag, TagSet};
+
+use crate::char_cnn::inside_of;
+use crate::config::TrainConfig;
+use crate::data::{TrainingExample, load_bio_dataset, shuffled_indices};
+
+const MODEL_FILE: &str = "model.safetensors";
+const CONFIG_FILE: &str = "config.json";
+const TOKENIZER_FILE: &str = "tokenizer.json";
+const TRANSITIONS_VAR: &str = "crf_transitions.weight";
+const LAYER_NORM_EPS: f64 = 1e-12;
+
+/// Layer norm built from primitive ops so gradients flow through it.
+struct Norm {
+    weight: Tensor,
+    bias: Tensor,
+}
+
+impl Norm {
+    fn load(dim: usize, vb: VarBuilder) -> candle_core::Result<Self> {
+        Ok(Self {
+            weight: vb.get_with_hints(dim, "weight", Init::Const(1.0))?,
+            bias: vb.get_with_hints(dim, "bias", Init::Const(0.0))?,
+        })
+    }
+
+    fn forward(&self, xs: &Tensor) -> candle_core::Result<Tensor> {
+        let centered = xs.broadcast_sub(&xs.mean_keepdim(D::Minus1)?)?;
+        let variance = centered.sqr()?.mean_keepdim(D::Minus1)?;
+        centered
+            .broadcast_div(&(variance + LAYER_NORM_EPS)?.sqrt()?)?
+            .broadcast_mul(&self.weight)?
+            .broadcast_add(&self.bias)
+    }
+}
+
+struct Block {
+    q_lin: Linear,
+    k_lin: Linear,
+    v_lin: Linear,
+    out_lin: Linear,
+    sa_layer_norm: Norm,
+    lin1: Linear,
+    lin2: Linear,
+    output_layer_norm: Norm,
+    n_heads: usize,
+    relu: bool,
+}
+
+impl Block {
+    fn load(vb: VarBuilder, config: &EncoderConfig) -> candle_core::Result<Self> {
+        let dim = config.dim;
+        let attention = vb.pp("attention");
+        let ffn = vb.pp("ffn");
+        Ok(Self {
+            q_lin: candle_nn::linear(dim, dim, attention.pp("q_lin"))?,
+            k_lin: candle_nn::linear(dim, dim, attention.pp("k_lin"))?,
+            v_lin: candle_nn::linear(dim, dim, attention.pp("v_lin"))?,
+            out_lin: candle_nn::linear(dim, dim, attention.pp("out_lin"))?,
+            sa_layer_norm: Norm::load(dim, vb.pp("sa_layer_norm"))?,
+            lin1: candle_nn::linear(dim, config.hidden_dim, ffn.pp("lin1"))?,
+            lin2: candle_nn::linear(config.hidden_dim, dim, ffn.pp("lin2"))?,
+            output_layer_norm: Norm::load(dim, vb.pp("output_layer_norm"))?,
+            n_heads: config.n_heads,
+            relu: config.activation == "relu",
+        })
+    }
+
+    /// Full attention over one unpadded sequence `[1, seq_len, dim]`.
+    fn forward(&self, hidden_states: &Tensor) -> candle_core::Result<Tensor> {
+        let (bs, seq_len, dim) = hidden_states.dims3()?;
+        let head_dim = dim / self.n_heads;
+        let heads = |xs: Tensor| -> candle_core::Result<Tensor> {
+            xs.reshape((bs, seq_len, self.n_heads, head_dim))?
+                .transpose(1, 2)?
+                .contiguous()
+        };
+
+        let q = (heads(self.q_lin.forward(hidden_states)?)? / (head_dim as f64).sqrt())?;
+        let k = heads(self.k_lin.forward(hidden_states)?)?;
+        let v = heads(self.v_lin.forward(hidden_states)?)?;
+        let scores = q.matmul(&k.transpose(2, 3)?.contiguous()?)?;
+        let weights = candle_nn::ops::softmax(&scores, D::Minus1)?;
+        let context = weights
+            .matmul(&v)?
+            .transpose(1, 2)?
+            .reshape((bs, seq_len, dim))?
+            .contiguous()?;
+
+        let sa_output = (self.out_lin.forward(&context)? + hidden_states)?;
+        let sa_output = self.sa_layer_norm.forward(&sa_output)?;
+        let ffn = self.lin1.forward(&sa_output)?;
+        let ffn = if self.relu { ffn.relu()? } else { ffn.gelu()? };
+        self.output_layer_norm
+            .forward(&(self.lin2.forward(&ffn)? + sa_output)?)
+    }
+}
+
+/// DistilBERT encoder with emission head and CRF transitions.
+struct FineTuneModel {
+    word_embeddings: Embedding,
+    position_embeddings: Embedding,
+    embedding_norm: Norm,
+    layers: Vec<Block>,
+    classifier: Linear,
+    transitions: Tensor,
+}
+
+impl FineTuneModel {
+    fn load(vb: VarBuilder, config: &EncoderConfig, tag_set: TagSet) -> candle_core::Result<Self> {
+        let embeddings = vb.pp("embeddings");
+        let num_tags = tag_set.num_tags();
+        Ok(Self {
+            word_embeddings: candle_nn::embedding(
+                config.vocab_size,
+                config.dim,
+                embeddings.pp("word_embeddings"),
+            )?,
+            position_embeddings: candle_nn::embedding(
+                config.max_position_embeddings,
+                config.dim,
+                embeddings.pp("position_embeddings"),
+            )?,
+            embedding_norm: Norm::load(config.dim, embeddings.pp("LayerNorm"))?,
+            layers: (0..config.n_layers)
+                .map(|index| Block::load(vb.pp(format!("transformer.layer.{index}")), config))
+                .collect::<candle_core::Result<_>>()?,
+            classifier: candle_nn::linear(config.dim, num_tags, vb.pp("classifier"))?,
+            transitions: vb.get_with_hints(
+                (num_tags, num_tags),
+                TRANSITIONS_VAR,
+                Init::Const(0.0),
+            )?,
+        })
+    }
+
+    /// Emission scores `[seq_len, num_tags]` for one sequence of token ids.
+    fn emissions(&self, ids: &[u32], device: &Device) -> candle_core::Result<Tensor> {
+        let input_ids = Tensor::new(ids, device)?.unsqueeze(0)?;
+        let positions: Vec<u32> = (0..ids.len() as u32).collect();
+        let positions = Tensor::new(positions.as_slice(), device)?;
+        let embeddings = self
+            .word_embeddings
+            .forward(&input_ids)?
+            .broadcast_add(&self.position_embeddings.forward(&positions)?)?;
+
+        let mut hidden_states = self.embedding_norm.forward(&embeddings)?;
+        for layer in &self.layers {
+            hidden_states = layer.forward(&hidden_states)?;
+        }
+        self.classifier.forward(&hidden_states)?.squeeze(0)
+    }
+}
+
+/// `log(sum(exp(xs)))` along `dim`, which is removed.
+fn log_sum_exp(xs: &Tensor, dim: usize) -> candle_core::Result<Tensor> {
+    let max = xs.max_keepdim(dim)?.detach();
+    (xs.broadcast_sub(&max)?.exp()?.sum_keepdim(dim)?.log()? + max)?.squeeze(dim)
+}
+
+/// Negative log-likelihood of `tags` under a linear-chain CRF with
+/// `emissions` (`[seq_len, num_tags]`) and `transitions` (`[from, to]`).
+pub fn crf_loss(
+    emissions: &Tensor,
+    transitions: &Tensor,
+    tags: &[u32],
+) -> candle_core::Result<Tensor> {
+    let (seq_len, num_tags) = emissions.dims2()?;
+    let device = emissions.device();
+
+    let tag_ids = Tensor::new(tags, device)?.unsqueeze(1)?;
+    let mut gold = emissions.gather(&tag_ids, 1)?.sum_all()?;
+    let pairs: Vec<u32> = tags
+        .windows(2)
+        .map(|pair| pair[0] * num_tags as u32 + pair[1])
+        .collect();
+    if !pairs.is_empty() {
+        let pairs = Tensor::new(pairs.as_slice(), device)?;
+        gold = (gold
+            + transitions
+                .flatten_all()?
+                .index_select(&pairs, 0)?
+                .sum_all()?)?;
+    }
+
+    let mut alpha = emissions.get(0)?;
+    for t in 1..seq_len {
+        let scores = alpha.unsqueeze(1)?.broadcast_add(transitions)?;
+        alpha = (log_sum_exp(&scores, 0)? + emissions.get(t)?)?;
+    }
+    log_sum_exp(&alpha, 0)? - gold
+}
+
+/// Tag of every subword position, given the word each position came from.
+///
+/// The first subword of a word takes the word's tag and the rest continue
+/// it (`B-X` becomes `I-X`); special tokens are `O`. Tags outside `tag_set`
+/// become `O` as well.
+pub fn align_subword_tags(word_ids: &[Option<u32>], labels: &[usize], tag_set: TagSet) -> Vec<u32> {
+    let mut previous = None;
+    word_ids
+        .iter()
+        .map(|&word| {
+            let tag = word
+                .and_then(|word| labels.get(word as usize))
+                .and_then(|&label| BioTag::from_index(label))
+                .filter(|tag| tag.index() < tag_set.num_tags())
+                .map_or(BioTag::Outside, |tag| {
+                    if word.is_some() && word == previous {
+                        inside_of(tag)
+                    } else {
+                        tag
+                    }
+                });
+            previous = word;
+            tag.index() as u32
+        })
+        .collect()
+}
+
+/// Fine-tunes a pretrained DistilBERT into a DistilBERT-CRF tagger.
+pub struct DistilBertTrainer {
+    varmap: VarMap,
+    model: FineTuneModel,
+    config: EncoderConfig,
+    /// The pretrained `config.json`, re-emitted with the tag set's labels.
+    raw_config: serde_json::Value,
+    tokenizer: Tokenizer,
+    tokenizer_path: PathBuf,
+    tag_set: TagSet,
+    optimizer: AdamW,
+    device: Device,
+    seed: u64,
+}
+
+impl DistilBertTrainer {
+    /// Load the pretrained checkpoint in `dir` (`model.safetensors`,
+    /// `config.json` and `tokenizer.json`, as published on the Hugging Face
+    /// Hub) with a fresh head for `tag_set`.
+    ///
+    /// A checkpoint that already has a head and transitions for `tag_set`,
+    /// e.g. an earlier fine-tuning run, continues from them. Otherwise the
+    /// head is drawn from `seed`, which also orders the training examples
+    /// and is recorded in the saved transitions.
+    pub fn from_pretrained<P: AsRef<Path>>(
+        dir: P,
+        tag_set: TagSet,
+        learning_rate: f64,
+        seed: u64,
+    ) -> anyhow::Result<Self> {
+        let dir = dir.as_ref();
+        let device = Device::Cpu;
+
+        let raw_config: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join(CONFIG_FILE))?)?;
+        let config: EncoderConfig = serde_json::from_value(raw_config.clone())?;
+        let tokenizer_path = dir.join(TOKENIZER_FILE);
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path).map_err(anyhow::Error::msg)?;
+        tokenizer
+            .with_truncation(None)
+            .map_err(anyhow::Error::msg)?;
+
+        let varmap = VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &device);
+        let model = FineTuneModel::load(vb, &config, tag_set)?;
+        init_head(&varmap, seed, &device)?;
+        load_pretrained(&varmap, &dir.join(MODEL_FILE), &device)?;
+
+        let optimizer = AdamW::new(
+            varmap.all_vars(),
+            ParamsAdamW {
+                lr: learning_rate,
+                ..ParamsAdamW::default()
+            },
+        )?;
+
+        Ok(Self {
+            varmap,
+            model,
+            config,
+            raw_config,
+            tokenizer,
+            tokenizer_path,
+            tag_set,
+            optimizer,
+            device,
+            seed,
+        })
+    }
+
+    /// Token ids and per-token tags for `example`, cut to the model's
+    /// maximum sequence length.
+    pub fn encode(&self, example: &TrainingExample) -> anyhow::Result<(Vec<u32>, Vec<u32>)> {
+        let encoding = self
+            .tokenizer
+            .encode(example.tokens.as_slice(), true)
+            .map_err(anyhow::Error::msg)?;
+        let max_len = self.config.max_position_embeddings;
+        let mut ids = encoding.get_ids().to_vec();
+        let mut tags = align_subword_tags(encoding.get_word_ids(), &example.labels, self.tag_set);
+        ids.truncate(max_len);
+        tags.truncate(max_len);
+        Ok((ids, tags))
+    }
+
+    /// Take one optimizer step on `example`, returning its loss.
+    pub fn train_step(&mut self, example: &TrainingExample) -> anyhow::Result<f32> {
+        let (ids, tags) = self.encode(example)?;
+        let emissions = self.model.emissions(&ids, &self.device)?;
+        let loss = crf_loss(&emissions, &self.model.transitions, &tags)?;
+        self.optimizer.backward_step(&loss)?;
+        Ok(loss.to_scalar::<f32>()?)
+    }
+
+    /// Train for `epochs` passes over `examples`, shuffled per epoch by the
+    /// trainer's seed.
+    pub fn train(&mut self, examples: &[TrainingExample], epochs: usize) -> anyhow::Result<()> {
+        for epoch in 0..epochs {
+            let mut total_loss = 0.0f32;
+            let mut steps = 0usize;
+
+            let indices = shuffled_indices(
+                examples.len(),
+                (u128::from(self.seed) << 64) | epoch as u128,
+            );
+            for example in indices
+                .into_iter()
+                .map(|i| &examples[i])
+                .filter(|e| !e.tokens.is_empty())
+            {
+                total_loss += self.train_step(example)?;
+                steps += 1;
+
+                if steps % 1000 == 0 {
+                    tracing::info!(
+                        "Epoch {}/{}, Step {}/{}, Loss: {:.4}",
+                        epoch + 1,
+                        epochs,
+                        steps,
+                        examples.len(),
+                        total_loss / steps as f32
+                    );
+                }
+            }
+
+            tracing::info!(
+                "Epoch {}/{} complete - Loss: {:.4}",
+                epoch + 1,
+                epochs,
+                total_loss / steps.max(1) as f32
+            );
+        }
+        Ok(())
+    }
+
+    /// Emission scores `[seq_len, num_tags]` for `ids`.
+    pub fn emissions(&self, ids: &[u32]) -> anyhow::Result<Tensor> {
+        Ok(self.model.emissions(ids, &self.device)?)
+    }
+
+    /// Write `model.safetensors`, `config.json` (with the tag set's
+    /// `id2label`), `tokenizer.json`, `transitions.json` and `manifest.json`
+    /// into `dir`.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> anyhow::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        self.varmap.save(dir.join(MODEL_FILE))?;
+
+        let labels = self.tag_set.tags();
+        let mut config = self.raw_config.clone();
+        config["id2label"] = labels
+            .iter()
+            .enumerate()
+            .map(|(index, tag)| (index.to_string(), tag.to_string().into()))
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+        config["label2id"] = labels
+            .iter()
+            .enumerate()
+            .map(|(index, tag)| (tag.to_string(), index.into()))
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+        std::fs::write(
+            dir.join(CONFIG_FILE),
+            serde_json::to_string_pretty(&config)?,
+        )?;
+
+        let tokenizer_path = dir.join(TOKENIZER_FILE);
+        if tokenizer_path != self.tokenizer_path {
+            std::fs::copy(&self.tokenizer_path, &tokenizer_path)?;
+        }
+
+        // Written alongside the weights so a stale `transitions.json` in
+        // `dir` cannot override the learned ones.
+        let scores = self.model.transitions.to_vec2::<f32>()?;
+        TransitionMatrix::new(self.tag_set, scores)?
+            .with_seed(self.seed)
+            .save(&dir.join(TRANSITIONS_FILE))?;
+        ModelManifest::new(env!("CARGO_PKG_VERSION"), self.tag_set, &tokenizer_path)?
+            .save(&dir.join(MANIFEST_FILE))?;
+
+        tracing::info!("Model saved to {}", dir.display());
+        Ok(())
+    }
+}
+
+/// Draw the classifier head from `seed`, uniform in `±1/√dim` like
+/// PyTorch's `nn.Linear`.
+///
+/// candle cannot seed its CPU generator, so the head is filled here rather
+/// than by the variable initializer.
+fn init_head(varmap: &VarMap, seed: u64, device: &Device) -> anyhow::Result<()> {
+    let mut rng = oorandom::Rand32::new(seed);
+    let vars = varmap.data().lock().unwrap_or_else(|e| e.into_inner());
+    let weight = &vars["classifier.weight"];
+    let bound = 1.0 / (weight.dims()[1] as f32).sqrt();
+    for var in [weight, &vars["classifier.bias"]] {
+        let values: Vec<f32> = (0..var.elem_count())
+            .map(|_| (rng.rand_float() * 2.0 - 1.0) * bound)
+            .collect();
+        var.set(&Tensor::from_vec(values, var.shape(), device)?)?;
+    }
+    Ok(())
+}
+
+/// Copy the checkpoint's tensors into the matching variables of `varmap`.
+///
+/// Every encoder variable must be present; the head and transitions are
+/// only taken when their shapes match.
+fn load_pretrained(varmap: &VarMap, path: &Path, device: &Device) -> anyhow::Result<()> {
+    let checkpoint = candle_core::safetensors::load(path, device)?;
+    let vars = varmap.data().lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut loaded = HashSet::new();
+    for (name, tensor) in checkpoint {
+        let name = candle_tensor_name(&name);
+        if let Some(var) = vars.get(&name)
+            && var.shape() == tensor.shape()
+        {
+            var.set(&tensor.to_dtype(DType::F32)?)?;
+            loaded.insert(name);
+        }
+    }
+
+    let mut missing: Vec<&str> = vars
+        .keys()
+        .filter(|name| !name.starts_with("classifier.") && *name != TRANSITIONS_VAR)
+        .filter(|name| !loaded.contains(*name))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        missing.sort_unstable();
+        anyhow::bail!(
+            "pretrained checkpoint {} is missing or mis-shapes {}",
+            path.display(),
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+pub fn run_distilbert_training() -> anyhow::Result<()> {
+    let pretrained_dir = "models/distilbert-base-uncased";
+    let data_path = "data/training/silver_dataset.jsonl";
+
+    for path in [pretrained_dir, data_path] {
+        if !Path::new(path).exists() {
+            anyhow::bail!("Not found: {}", path);
+        }
+    }
+
+    let examples = load_bio_dataset(data_path)?;
+    tracing::info!("Loaded {} training examples", examples.len());
+
+    let mut trainer = DistilBertTrainer::from_pretrained(
+        pretrained_dir,
+        TagSet::LATEST,
+        5e-5,
+        TrainConfig::default().seed,
+    )?;
+    trainer.train(&examples, 3)?;
+    trainer.save("models/ner_model")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candle_transformers::models::distilbert::Config;
+    use std::collections::HashMap;
+    use tokenizers::models::wordlevel::WordLevel;
+    use tokenizers::pre_tokenizers::whitespace::Whitespace;
+    use tokenizers::processors::bert::BertProcessing;
+    use zantetsu_core::crf::model::CrfModel;
+
+    #[test]
+    fn continues_entities_across_subwords() {
+        let word_ids = [None, Some(0), Some(1), Some(1), Some(2), None];
+        let labels = [
+            BioTag::BeginGroup.index(),
+            BioTag::BeginTitle.index(),
+            BioTag::Part.index(),
+        ];
+
+        let tags = align_subword_tags(&word_ids, &labels, TagSet::V2);
+        let expected = [
+            BioTag::Outside,
+            BioTag::BeginGroup,
+            BioTag::BeginTitle,
+            BioTag::InsideTitle,
+            BioTag::Part,
+            BioTag::Outside,
+        ];
+        assert_eq!(tags, expected.map(|tag| tag.index() as u32));
+
+        // PART is not in the v1 tag set.
+        let tags = align_subword_tags(&word_ids, &labels, TagSet::V1);
+        assert_eq!(tags[4], BioTag::Outside.index() as u32);
+    }
+
+    #[test]
+    fn crf_loss_matches_brute_force() {
+        let device = Device::Cpu;
+        let emissions = Tensor::new(&[[0.5f32, -1.0], [0.2, 0.3], [-0.4, 1.1]], &device).unwrap();
+        let transitions = Tensor::new(&[[0.1f32, -0.6], [0.7, 0.0]], &device).unwrap();
+        let e = emissions.to_vec2::<f32>().unwrap();
+        let t = transitions.to_vec2::<f32>().unwrap();
+
+        let score = |path: &[usize]| {
+            path.iter()
+                .enumerate()
+                .map(|(i, &tag)| e[i][tag])
+                .sum::<f32>()
+                + path.windows(2).map(|pair| t[pair[0]][pair[1]]).sum::<f32>()
+        };
+        let paths: Vec<[usize; 3]> = (0..8).map(|n| [n >> 2 & 1, n >> 1 & 1, n & 1]).collect();
+        let log_partition = paths.iter().map(|path| score(path).exp()).sum::<f32>().ln();
+
+        let loss = crf_loss(&emissions, &transitions, &[0, 1, 1])
+            .unwrap()
+            .to_scalar::<f32>()
+            .unwrap();
+        assert!((loss - (log_partition - score(&[0, 1, 1]))).abs() < 1e-4);
+    }
+
+    /// A tiny "pretrained" checkpoint in Hugging Face layout. The core's F32
+    /// loader assumes a 768-wide encoder, so only depth and vocab are small.
+    fn write_pretrained(dir: &Path) {
+        let words = [
+            "[PAD]",
+            "[UNK]",
+            "[CLS]",
+            "[SEP]",
+            "subsplease",
+            "frieren",
+            "05",
+            "1080p",
+        ];
+        let vocab: HashMap<String, u32> = words
+            .iter()
+            .enumerate()
+            .map(|(id, word)| (word.to_string(), id as u32))
+            .collect();
+        let model = WordLevel::builder()
+            .vocab(vocab)
+            .unk_token("[UNK]".into())
+            .build()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(model);
+        tokenizer.with_pre_tokenizer(Whitespace {});
+        tokenizer.with_post_processor(BertProcessing::new(
+            ("[SEP]".into(), 3),
+            ("[CLS]".into(), 2),
+        ));
+        tokenizer.save(dir.join(TOKENIZER_FILE), false).unwrap();
+
+        let config = serde_json::json!({
+            "vocab_size": words.len(),
+            "dim": 768,
+            "n_layers": 1,
+            "n_heads": 12,
+            "hidden_dim": 32,
+            "activation": "gelu",
+            "max_position_embeddings": 16,
+            "initializer_range": 0.02,
+            "pad_token_id": 0,
+            "model_type": "distilbert",
+        });
+        std::fs::write(dir.join(CONFIG_FILE), config.to_string()).unwrap();
+
+        let varmap = VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+        let config: EncoderConfig = serde_json::from_value(config).unwrap();
+        FineTuneModel::load(vb, &config, TagSet::V1).unwrap();
+        let tensors: HashMap<String, Tensor> = varmap
+            .data()
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| !name.starts_with("classifier.") && *name != TRANSITIONS_VAR)
+            .map(|(name, var)| {
+                let name = name
+                    .replace("LayerNorm.weight", "LayerNorm.gamma")
+                    .replace("LayerNorm.bias", "LayerNorm.beta");
+                (format!("distilbert.{name}"), var.as_tensor().clone())
+            })
+            .collect();
+        candle_core::safetensors::save(&tensors, dir.join(MODEL_FILE)).unwrap();
+    }
+
+    #[test]
+    fn fine_tuned_model_loads_in_core() {
+        let root = std::env::temp_dir().join(format!("zantetsu-train-bert-{}", std::process::id()));
+        let (pretrained, output) = (root.join("pretrained"), root.join("ner_model"));
+        std::fs::create_dir_all(&pretrained).unwrap();
+        write_pretrained(&pretrained);
+
+        let example = TrainingExample::new(
+            ["subsplease", "frieren", "05", "1080p"]
+                .map(String::from)
+                .to_vec(),
+            [
+                BioTag::BeginGroup,
+                BioTag::BeginTitle,
+                BioTag::BeginEpisode,
+                BioTag::Resolution,
+            ]
+            .map(|tag| tag.index())
+            .to_vec(),
+        );
+        let mut trainer =
+            DistilBertTrainer::from_pretrained(&pretrained, TagSet::V2, 1e-3, 7).unwrap();
+        let (ids, tags) = trainer.encode(&example).unwrap();
+        assert_eq!(ids, [2, 4, 5, 6, 7, 3]);
+        assert_eq!(tags[1], BioTag::BeginGroup.index() as u32);
+
+        let first = trainer.train_step(&example).unwrap();
+        let mut last = first;
+        for _ in 0..10 {
+            last = trainer.train_step(&example).unwrap();
+        }
+        assert!(last < first, "loss did not decrease: {first} -> {last}");
+        trainer.save(&output).unwrap();
+
+        let config: Config =
+            serde_json::from_str(&std::fs::read_to_string(output.join(CONFIG_FILE)).unwrap())
+                .unwrap();
+        let vb = unsafe {
+            VarBuilder::from_mmaped_safetensors(
+                &[output.join(MODEL_FILE)],
+                DType::F32,
+                &Device::Cpu,
+            )
+        }
+        .unwrap();
+        let model = CrfModel::load(vb, config, TagSet::V2).unwrap();
+        assert!(model.has_learned_transitions());
+
+        let input_ids = Tensor::new(ids.as_slice(), &Device::Cpu)
+            .unwrap()
+            .unsqueeze(0)
+            .unwrap();
+        let no_mask = input_ids.zeros_like().unwrap();
+        let core = model
+            .forward(&input_ids, &no_mask)
+            .unwrap()
+            .squeeze(0)
+            .unwrap();
+        let trained = trainer.emissions(&ids).unwrap();
+        let max_diff = (core - trained)
+            .unwrap()
+            .abs()
+            .unwrap()
+            .max_all()
+            .unwrap()
+            .to_scalar::<f32>()
+            .unwrap();
+        assert!(max_diff < 1e-3, "core emissions differ by {max_diff}");
+        let transitions = TransitionMatrix::load(&output.join(TRANSITIONS_FILE)).unwrap();
+        assert_eq!(transitions.seed, Some(7));
+        assert!(output.join(MANIFEST_FILE).exists());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn same_seed_trains_the_same_weights() {
+        let root = std::env::temp_dir().join(format!("zantetsu-train-seed-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        write_pretrained(&root);
+
+        let examples: Vec<TrainingExample> = [["subsplease", "frieren"], ["frieren", "05"]]
+            .map(|tokens| {
+                TrainingExample::new(
+                    tokens.map(String::from).to_vec(),
+                    vec![BioTag::BeginTitle.index(), BioTag::BeginEpisode.index()],
+                )
+            })
+            .to_vec();
+        let weights = |seed| {
+            let mut trainer =
+                DistilBertTrainer::from_pretrained(&root, TagSet::V2, 1e-3, seed).unwrap();
+            trainer.train(&examples, 2).unwrap();
+            let mut vars: Vec<(String, Vec<f32>)> = trainer
+                .varmap
+                .data()
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, var)| (name.clone(), var.flatten_all().unwrap().to_vec1().unwrap()))
+                .collect();
+            vars.sort_by(|a, b| a.0.cmp(&b.0));
+            vars
+        };
+
+        assert_eq!(weights(3), weights(3));
+        assert_ne!(weights(3), weights(4));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,478 @@
+//! Evaluation of predicted tag sequences against gold labels.
+//!
+//! Token accuracy is dominated by `O` tokens and overstates quality, so
+//! predictions are scored on whole entity spans (exact-match precision,
+//! recall and F1 per entity type) and on the [`ParseResult`] fields they
+//! assemble into.
+//!
+//! [`ParseResult`]: zantetsu_core::ParseResult
+
+use std::collections::HashMap;
+use std::fmt;
+
+use zantetsu_core::ParseField;
+use zantetsu_core::parser::{BioTag, EntityType, NeuralParser};
+
+use crate::data::{TrainingExample, shuffled_indices};
+
+/// A labeled span of tokens, `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub entity_type: EntityType,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Entity spans in a sequence of tag indices.
+///
+/// Spans are grouped the same way the neural parser groups tags: a span
+/// starts at any entity tag and continues over `I-` tags of its type, or
+/// over repeats of a single-token tag such as `RESOLUTION`. A stray `I-` tag
+/// starts a span of its own.
+pub fn extract_spans(labels: &[usize]) -> Vec<Span> {
+    let tags: Vec<Option<BioTag>> = labels.iter().map(|&l| BioTag::from_index(l)).collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < tags.len() {
+        let Some((tag, entity_type)) = tags[i].and_then(|tag| Some((tag, tag.entity_type()?)))
+        else {
+            i += 1;
+            continue;
+        };
+        let start = i;
+        i += 1;
+        while let Some(next) = tags.get(i).copied().flatten() {
+            let continues = if next.is_inside() {
+                next.entity_type() == Some(entity_type)
+            } else {
+                next == tag && !tag.is_begin() && !tag.is_inside()
+            };
+            if !continues {
+                break;
+            }
+            i += 1;
+        }
+        spans.push(Span {
+            entity_type,
+            start,
+            end: i,
+        });
+    }
+
+    spans
+}
+
+/// Span-level counts for one entity type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntityScores {
+    /// Predicted spans that exactly match a gold span.
+    pub true_positives: usize,
+    /// Predicted spans with no exactly matching gold span.
+    pub false_positives: usize,
+    /// Gold spans with no exactly matching predicted span.
+    pub false_negatives: usize,
+}
+
+impl EntityScores {
+    pub fn precision(&self) -> f32 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+        )
+    }
+
+    pub fn recall(&self) -> f32 {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+        )
+    }
+
+    pub fn f1(&self) -> f32 {
+        let (precision, recall) = (self.precision(), self.recall());
+        if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        }
+    }
+
+    /// Number of gold spans.
+    pub fn support(&self) -> usize {
+        self.true_positives + self.false_negatives
+    }
+
+    fn add(&mut self, other: &EntityScores) {
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+    }
+}
+
+/// Field-level counts for one [`ParseField`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FieldScore {
+    /// Examples whose gold result has the field.
+    pub support: usize,
+    /// Examples whose predicted result has the field.
+    pub predicted: usize,
+    /// Examples where gold and prediction have the same value.
+    pub correct: usize,
+}
+
+impl FieldScore {
+    /// Share of gold values the prediction reproduced exactly.
+    pub fn accuracy(&self) -> f32 {
+        ratio(self.correct, self.support)
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f32 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f32 / denominator as f32
+    }
+}
+
+/// Aggregated evaluation over a held-out set.
+#[derive(Debug, Clone, Default)]
+pub struct EvalReport {
+    /// Number of evaluated examples.
+    pub examples: usize,
+    /// Examples whose assembled fields all match the gold fields.
+    pub exact_matches: usize,
+    /// Number of evaluated tokens.
+    pub tokens: usize,
+    /// Tokens whose predicted tag equals the gold tag.
+    pub correct_tokens: usize,
+    /// Span scores per entity type.
+    pub entities: HashMap<EntityType, EntityScores>,
+    /// End-to-end scores per parse field.
+    pub fields: HashMap<ParseField, FieldScore>,
+}
+
+impl EvalReport {
+    /// Score one example's `predicted` tags against its `gold` tags.
+    pub fn add(
+        &mut self,
+        tokens: &[String],
+        gold: &[usize],
+        predicted: &[usize],
+    ) -> anyhow::Result<()> {
+        if gold.len() != tokens.len() || predicted.len() != tokens.len() {
+            anyhow::bail!(
+                "{} tokens with {} gold and {} predicted tags",
+                tokens.len(),
+                gold.len(),
+                predicted.len()
+            );
+        }
+
+        self.examples += 1;
+        self.tokens += tokens.len();
+        self.correct_tokens += gold.iter().zip(predicted).filter(|(g, p)| g == p).count();
+
+        let gold_spans = extract_spans(gold);
+        let predicted_spans = extract_spans(predicted);
+        for span in &predicted_spans {
+            let scores = self.entities.entry(span.entity_type).or_default();
+            if gold_spans.contains(span) {
+                scores.true_positives += 1;
+            } else {
+                scores.false_positives += 1;
+            }
+        }
+        for span in gold_spans.iter().filter(|s| !predicted_spans.contains(s)) {
+            self.entities
+                .entry(span.entity_type)
+                .or_default()
+                .false_negatives += 1;
+        }
+
+        // Re-join the tokens so both tag sequences go through the parser's
+        // own entity assembly and field normalization.
+        let (input, offsets) = join_tokens(tokens);
+        let gold = NeuralParser::assemble_result(&input, &offsets, gold)?;
+        let predicted = NeuralParser::assemble_result(&input, &offsets, predicted)?;
+        let diff = gold.diff(&predicted);
+        if diff.is_empty() {
+            self.exact_matches += 1;
+        }
+        for field in ParseField::ALL {
+            let (in_gold, in_predicted) = (field.is_present(&gold), field.is_present(&predicted));
+            if !in_gold && !in_predicted {
+                continue;
+            }
+            let score = self.fields.entry(field).or_default();
+            score.support += usize::from(in_gold);
+            score.predicted += usize::from(in_predicted);
+            if in_gold && !diff.iter().any(|d| d.field == field) {
+                score.correct += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Span scores summed over all entity types.
+    pub fn micro(&self) -> EntityScores {
+        let mut total = EntityScores::default();
+        for scores in self.entities.values() {
+            total.add(scores);
+        }
+        total
+    }
+
+    /// Unweighted mean F1 over the entity types that occur in the gold
+    /// labels.
+    pub fn macro_f1(&self) -> f32 {
+        let present: Vec<f32> = self
+            .entities
+            .values()
+            .filter(|scores| scores.support() > 0)
+            .map(EntityScores::f1)
+            .collect();
+        if present.is_empty() {
+            0.0
+        } else {
+            present.iter().sum::<f32>() / present.len() as f32
+        }
+    }
+
+    pub fn token_accuracy(&self) -> f32 {
+        ratio(self.correct_tokens, self.tokens)
+    }
+
+    /// Share of examples whose assembled fields are all correct.
+    pub fn exact_match_rate(&self) -> f32 {
+        ratio(self.exact_matches, self.examples)
+    }
+}
+
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} examples, token accuracy {:.2}%, exact match {:.2}%",
+            self.examples,
+            self.token_accuracy() * 100.0,
+            self.exact_match_rate() * 100.0
+        )?;
+
+        writeln!(
+            f,
+            "{:<16} {:>9} {:>9} {:>9} {:>8}",
+            "entity", "precision", "recall", "f1", "support"
+        )?;
+        let mut entities: Vec<_> = self.entities.iter().collect();
+        entities.sort_by_key(|(entity_type, _)| format!("{entity_type:?}"));
+        for (entity_type, scores) in entities {
+            writeln!(
+                f,
+                "{:<16} {:>9.4} {:>9.4} {:>9.4} {:>8}",
+                format!("{entity_type:?}"),
+                scores.precision(),
+                scores.recall(),
+                scores.f1(),
+                scores.support()
+            )?;
+        }
+        let micro = self.micro();
+        writeln!(
+            f,
+            "{:<16} {:>9.4} {:>9.4} {:>9.4} {:>8}",
+            "micro",
+            micro.precision(),
+            micro.recall(),
+            micro.f1(),
+            micro.support()
+        )?;
+        writeln!(f, "{:<16} {:>29.4}", "macro f1", self.macro_f1())?;
+
+        writeln!(f, "{:<16} {:>9} {:>9}", "field", "accuracy", "support")?;
+        for field in ParseField::ALL {
+            if let Some(score) = self.fields.get(&field) {
+                writeln!(
+                    f,
+                    "{:<16} {:>9.4} {:>9}",
+                    field.to_string(),
+                    score.accuracy(),
+                    score.support
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `tokens` joined with single spaces, and the byte range of each token in
+/// the joined string.
+pub(crate) fn join_tokens(tokens: &[String]) -> (String, Vec<(usize, usize)>) {
+    let mut input = String::new();
+    let mut offsets = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        if !input.is_empty() {
+            input.push(' ');
+        }
+        offsets.push((input.len(), input.len() + token.len()));
+        input.push_str(token);
+    }
+    (input, offsets)
+}
+
+/// Score `predict` on every example of a held-out set.
+pub fn evaluate<F>(examples: &[TrainingExample], mut predict: F) -> anyhow::Result<EvalReport>
+where
+    F: FnMut(&[String]) -> Vec<usize>,
+{
+    let mut report = EvalReport::default();
+    for example in examples.iter().filter(|e| !e.tokens.is_empty()) {
+        let predicted = predict(&example.tokens);
+        report.add(&example.tokens, &example.labels, &predicted)?;
+    }
+    Ok(report)
+}
+
+/// Hold out `fraction` of the examples, chosen by a shuffle seeded with
+/// `seed`, returning `(train, held_out)` each in dataset order. The same
+/// seed always holds out the same examples, so repeated runs evaluate on
+/// the same data.
+pub fn split_held_out(
+    examples: Vec<TrainingExample>,
+    fraction: f32,
+    seed: u64,
+) -> (Vec<TrainingExample>, Vec<TrainingExample>) {
+    if fraction <= 0.0 {
+        return (examples, Vec::new());
+    }
+    let count = ((examples.len() as f32 * fraction).round() as usize).min(examples.len());
+    let mut held = vec![false; examples.len()];
+    for i in shuffled_indices(examples.len(), u128::from(seed))
+        .into_iter()
+        .take(count)
+    {
+        held[i] = true;
+    }
+    let (mut train, mut held_out) = (Vec::new(), Vec::new());
+    for (example, held) in examples.into_iter().zip(held) {
+        if held {
+            held_out.push(example);
+        } else {
+            train.push(example);
+        }
+    }
+    (train, held_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[BioTag]) -> Vec<usize> {
+        tags.iter().map(BioTag::index).collect()
+    }
+
+    #[test]
+    fn groups_tags_into_spans() {
+        let labels = tags(&[
+            BioTag::BeginGroup,
+            BioTag::BeginTitle,
+            BioTag::InsideTitle,
+            BioTag::Outside,
+            BioTag::Resolution,
+            BioTag::Resolution,
+            BioTag::InsideEpisode,
+        ]);
+        let spans = extract_spans(&labels);
+        let span = |entity_type, start, end| Span {
+            entity_type,
+            start,
+            end,
+        };
+        assert_eq!(
+            spans,
+            [
+                span(EntityType::Group, 0, 1),
+                span(EntityType::Title, 1, 3),
+                span(EntityType::Resolution, 4, 6),
+                span(EntityType::Episode, 6, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn scores_spans_and_fields() {
+        let tokens: Vec<String> = ["subsplease", "sousou", "no", "frieren", "05", "1080p"]
+            .map(String::from)
+            .to_vec();
+        let gold = tags(&[
+            BioTag::BeginGroup,
+            BioTag::BeginTitle,
+            BioTag::InsideTitle,
+            BioTag::InsideTitle,
+            BioTag::BeginEpisode,
+            BioTag::Resolution,
+        ]);
+        // The title is cut short and the episode missed: most tokens are
+        // still right, but two of four entities are wrong.
+        let predicted = tags(&[
+            BioTag::BeginGroup,
+            BioTag::BeginTitle,
+            BioTag::InsideTitle,
+            BioTag::Outside,
+            BioTag::Outside,
+            BioTag::Resolution,
+        ]);
+
+        let mut report = EvalReport::default();
+        report.add(&tokens, &gold, &predicted).unwrap();
+
+        assert!((report.token_accuracy() - 4.0 / 6.0).abs() < 1e-6);
+        let micro = report.micro();
+        assert_eq!(
+            micro,
+            EntityScores {
+                true_positives: 2,
+                false_positives: 1,
+                false_negatives: 2,
+            }
+        );
+        assert_eq!(report.entities[&EntityType::Title].f1(), 0.0);
+        assert_eq!(report.entities[&EntityType::Group].f1(), 1.0);
+        assert!((report.macro_f1() - 0.5).abs() < 1e-6);
+
+        assert_eq!(report.exact_matches, 0);
+        assert_eq!(report.fields[&ParseField::Title].accuracy(), 0.0);
+        assert_eq!(report.fields[&ParseField::Resolution].accuracy(), 1.0);
+        assert_eq!(
+            report.fields[&ParseField::Episode],
+            FieldScore {
+                support: 1,
+                predicted: 0,
+                correct: 0,
+            }
+        );
+        assert!(report.to_string().contains("micro"));
+
+        assert!(report.add(&tokens, &gold, &predicted[1..]).is_err());
+    }
+
+    #[test]
+    fn holds_out_a_seeded_share() {
+        let examples: Vec<TrainingExample> = (0..10)
+            .map(|i| TrainingExample::new(vec![i.to_string()], vec![BioTag::Outside.index()]))
+            .collect();
+        let (train, held_out) = split_held_out(examples.clone(), 0.2, 7);
+        assert_eq!((train.len(), held_out.len()), (8, 2));
+
+        let (_, again) = split_held_out(examples.clone(), 0.2, 7);
+        let tokens = |set: &[TrainingExample]| -> Vec<Vec<String>> {
+            set.iter().map(|e| e.tokens.clone()).collect()
+        };
+        assert_eq!(tokens(&again), tokens(&held_out));
+        let (_, other) = split_held_out(examples, 0.2, 8);
+        assert_ne!(tokens(&other), tokens(&held_out));
+    }
+}
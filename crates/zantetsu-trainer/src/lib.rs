@@ -12,10 +12,34 @@
 //!
 //! Use this crate when you need dataset bootstrapping, model training, or evaluation workflows.
 
+pub mod active;
+pub mod align;
+pub mod bootstrap;
+pub mod char_cnn;
+pub mod checkpoint;
+pub mod config;
 pub mod data;
+pub mod distilbert;
+pub mod eval;
 pub mod model;
+pub mod noise;
+pub mod synth;
 pub mod trainer;
 
-pub use data::{BIO_LABELS, CharVocab, TrainingExample, load_bio_dataset};
+pub use active::{
+    ActiveLearner, AnnotationTask, KEY_FIELDS, MergeStats, merge_annotations, write_tasks,
+};
+pub use bootstrap::bootstrap_silver;
+pub use char_cnn::{CharCnnTrainer, CharExample, load_silver_dataset, run_char_cnn_training};
+pub use checkpoint::{CHECKPOINT_FILE, Checkpoint};
+pub use config::TrainConfig;
+pub use data::{
+    BIO_LABELS, CharVocab, TrainingExample, load_bio_dataset, load_conll, write_bio_dataset,
+    write_conll,
+};
+pub use distilbert::{DistilBertTrainer, run_distilbert_training};
+pub use eval::{EntityScores, EvalReport, FieldScore, evaluate, extract_spans, split_held_out};
 pub use model::{CrfModel, NUM_LABELS, viterbi_decode};
-pub use trainer::{Trainer, run_training};
+pub use noise::{NoiseFilter, NoiseReport, Verdict, render_canonical};
+pub use synth::{SynthGenerator, load_titles};
+pub use trainer::{MODEL_FILE, TRANSITIONS_DIR, Trainer, run_training};
@@ -1,22 +1,77 @@
 //! CRF Model for sequence labeling.
-//! Improved feature-based CRF with better tokenization.
-
-pub const NUM_LABELS: usize = 3;
+//! Feature-based linear-chain CRF over the full BIO tag set, trained with
+//! structured perceptron updates.
+
+use zantetsu_core::crf::transitions::TransitionMatrix;
+use zantetsu_core::parser::{BioTag, TagSet};
+
+use crate::data::BIO_LABELS;
+
+/// Number of labels the model scores, one per [`BioTag`].
+pub const NUM_LABELS: usize = BioTag::NUM_TAGS;
+
+/// Number of token features per label.
+pub const NUM_FEATURES: usize = 27;
+
+/// Score of a transition [`BioTag::is_valid_transition`] rules out, low
+/// enough that Viterbi never takes it.
+const INVALID_TRANSITION: f32 = -1.0e4;
+
+const VIDEO_CODECS: &[&str] = &[
+    "x264", "h264", "avc", "x265", "h265", "hevc", "av1", "vp9", "xvid", "divx", "10bit", "hi10p",
+];
+const AUDIO_CODECS: &[&str] = &[
+    "flac", "aac", "opus", "ac3", "eac3", "dts", "mp3", "vorbis", "truehd", "ddp",
+];
+const SOURCES: &[&str] = &[
+    "bd", "bluray", "bdrip", "remux", "web", "webdl", "webrip", "hdtv", "tv", "dvd", "dvdrip",
+];
+const EXTENSIONS: &[&str] = &["mkv", "mp4", "avi", "webm", "m4v", "ts", "wmv", "flv"];
+const LANGUAGES: &[&str] = &[
+    "eng", "english", "jpn", "japanese", "jap", "dual", "multi", "esp", "spa", "fre", "ger", "ita",
+    "por", "rus", "chs", "cht", "vostfr",
+];
+const RELEASE_FLAGS: &[&str] = &[
+    "batch",
+    "complete",
+    "uncensored",
+    "uncut",
+    "remastered",
+    "repack",
+    "proper",
+    "ova",
+    "oad",
+];
 
 #[derive(Clone)]
 pub struct CrfModel {
+    /// Transition scores, stored as `[to * NUM_LABELS + from]`.
     pub transition: Vec<f32>,
-    pub emission_weights: Vec<f32>, // Per-label bias
+    /// Per-label bias.
+    pub emission_weights: Vec<f32>,
+    /// Per-label feature weights, stored as `[label * NUM_FEATURES + feature]`.
+    pub feature_weights: Vec<f32>,
+    /// Seed of the training run, saved with the weights so the run can be
+    /// reproduced. Any random initialization must draw from it.
+    pub seed: Option<u64>,
 }
 
 impl CrfModel {
     pub fn new() -> Self {
-        let transition = vec![0.0f32; NUM_LABELS * NUM_LABELS];
-        let emission_weights = vec![0.0f32; NUM_LABELS];
+        let mut transition = vec![0.0f32; NUM_LABELS * NUM_LABELS];
+        for (from, &from_tag) in BioTag::all_tags().iter().enumerate() {
+            for (to, &to_tag) in BioTag::all_tags().iter().enumerate() {
+                if !BioTag::is_valid_transition(from_tag, to_tag) {
+                    transition[to * NUM_LABELS + from] = INVALID_TRANSITION;
+                }
+            }
+        }
 
         Self {
             transition,
-            emission_weights,
+            emission_weights: vec![0.0f32; NUM_LABELS],
+            feature_weights: vec![0.0f32; NUM_LABELS * NUM_FEATURES],
+            seed: None,
         }
     }
 
@@ -25,150 +80,88 @@ impl CrfModel {
         token: &str,
         prev_token: Option<&str>,
         next_token: Option<&str>,
-    ) -> Vec<f32> {
-        let mut features = Vec::new();
-
-        let lower = token.to_lowercase();
-        let _len = token.len();
-
-        // Basic features
-        features.push(
-            if token
+    ) -> [f32; NUM_FEATURES] {
+        let strip = |t: &str| {
+            t.trim_matches(|c: char| matches!(c, '[' | ']' | '(' | ')'))
+                .to_lowercase()
+        };
+        let lower = strip(token);
+        let prev = prev_token.map(strip);
+        let next = next_token.map(strip);
+        let is_digits = |t: &str| !t.is_empty() && t.chars().all(|c| c.is_ascii_digit());
+        let is_one_of = |t: &str, words: &[&str]| words.contains(&t);
+        let opens = |t: &str| t.starts_with('[') || t.starts_with('(');
+        let is_season =
+            |t: &str| t == "season" || (t.len() > 1 && t.starts_with('s') && is_digits(&t[1..]));
+
+        let flags = [
+            token
                 .chars()
-                .all(|c| !c.is_alphabetic() || c.is_uppercase())
-            {
-                1.0
-            } else {
-                0.0
-            },
-        ); // is_all_caps
-        features.push(if token.starts_with('[') || token.starts_with('(') {
-            1.0
-        } else {
-            0.0
-        }); // has_bracket_start
-        features.push(if token.ends_with(']') || token.ends_with(')') {
-            1.0
-        } else {
-            0.0
-        }); // has_bracket_end
-        features.push(
-            if lower.contains("e0")
-                || lower.contains("s0")
-                || lower.chars().all(|c| c.is_ascii_digit())
-            {
-                1.0
-            } else {
-                0.0
-            },
-        ); // is_episode
-        features.push(
-            if lower.contains("720p")
-                || lower.contains("1080p")
-                || lower.contains("480p")
-                || lower == "bd"
-                || lower == "web"
-            {
-                1.0
-            } else {
-                0.0
-            },
-        ); // is_quality
-        features.push(if token.chars().any(|c| c.is_ascii_digit()) {
-            1.0
-        } else {
-            0.0
-        }); // has_digit
-        features.push(if token.len() > 3 { 1.0 } else { 0.0 }); // long_token
-
-        // Context features
-        features.push(if let Some(p) = prev_token {
-            if p.starts_with('[') || p.starts_with('(') {
-                1.0
-            } else {
-                0.0
-            }
-        } else {
-            0.0
-        });
-        features.push(if let Some(n) = next_token {
-            if n.starts_with('[') || n.starts_with('(') {
-                1.0
-            } else {
-                0.0
-            }
-        } else {
-            0.0
-        });
-
-        features
+                .all(|c| !c.is_alphabetic() || c.is_uppercase()),
+            opens(token),
+            token.ends_with(']') || token.ends_with(')'),
+            lower.contains("e0") || lower.contains("s0") || is_digits(&lower),
+            lower.ends_with("0p") || lower == "4k" || lower == "2160",
+            token.chars().any(|c| c.is_ascii_digit()),
+            token.len() > 3,
+            prev_token.is_some_and(opens),
+            next_token.is_some_and(opens),
+            is_digits(&lower),
+            is_digits(&lower) && lower.len() <= 2,
+            is_one_of(&lower, VIDEO_CODECS),
+            is_one_of(&lower, AUDIO_CODECS),
+            is_one_of(&lower, SOURCES),
+            is_digits(&lower)
+                && lower.len() == 4
+                && (1950..=2099).contains(&lower.parse::<u32>().unwrap_or(0)),
+            lower.len() == 8
+                && lower.chars().all(|c| c.is_ascii_hexdigit())
+                && lower.chars().any(|c| c.is_ascii_digit()),
+            is_one_of(&lower, EXTENSIONS),
+            lower.len() > 1 && lower.starts_with('v') && is_digits(&lower[1..]),
+            is_one_of(&lower, LANGUAGES),
+            lower.ends_with("ch") && is_digits(&lower[..lower.len() - 2]),
+            lower == "part" || lower == "cour",
+            prev.as_deref().is_some_and(|p| p == "part" || p == "cour"),
+            is_one_of(&lower, RELEASE_FLAGS),
+            is_season(&lower),
+            prev.as_deref().is_some_and(is_season),
+            prev_token.is_none(),
+            next.is_none(),
+        ];
+        flags.map(|flag| if flag { 1.0 } else { 0.0 })
     }
 
-    fn compute_emission(
-        &self,
-        token: &str,
-        prev_token: Option<&str>,
-        next_token: Option<&str>,
-        label: usize,
-    ) -> f32 {
-        let features = self.extract_features(token, prev_token, next_token);
-
-        let bias = self.emission_weights[label];
-
-        // Score based on features and label
-        let mut score = bias;
-
-        match label {
-            0 => {
-                // O
-                score += features[2] * 2.0; // has brackets -> O
-                score += features[3] * 2.0; // episode -> O
-                score += features[4] * 2.0; // quality -> O
-                score -= features[0] * 1.0; // all_caps -> not O
-            }
-            1 => {
-                // B-TITLE
-                score += features[0] * 2.0; // all_caps -> B-TITLE
-                score -= features[2] * 2.0; // has brackets -> not B-TITLE
-                score -= features[3] * 2.0; // episode -> not B-TITLE
-                score -= features[4] * 2.0; // quality -> not B-TITLE
-                score += features[5] * 0.5; // has digit (part of title)
-            }
-            2 => {
-                // I-TITLE
-                score += features[0] * 1.5; // all_caps -> I-TITLE
-                score -= features[2] * 2.0; // has brackets -> not I-TITLE
-                score -= features[3] * 2.0; // episode -> not I-TITLE
-                score -= features[4] * 2.0; // quality -> not I-TITLE
-            }
-            _ => {}
-        }
+    fn compute_emission(&self, features: &[f32; NUM_FEATURES], label: usize) -> f32 {
+        let weights = &self.feature_weights[label * NUM_FEATURES..(label + 1) * NUM_FEATURES];
+        self.emission_weights[label]
+            + features
+                .iter()
+                .zip(weights)
+                .map(|(feature, weight)| feature * weight)
+                .sum::<f32>()
+    }
 
-        score
+    fn token_features(&self, tokens: &[String]) -> Vec<[f32; NUM_FEATURES]> {
+        (0..tokens.len())
+            .map(|i| {
+                let prev = i.checked_sub(1).map(|p| tokens[p].as_str());
+                let next = tokens.get(i + 1).map(String::as_str);
+                self.extract_features(&tokens[i], prev, next)
+            })
+            .collect()
     }
 
     pub fn forward(&self, tokens: &[String]) -> (Vec<Vec<f32>>, Vec<f32>) {
-        let seq_len = tokens.len();
-        let mut emissions = Vec::new();
-
-        for (i, token) in tokens.iter().enumerate() {
-            let prev = if i > 0 {
-                Some(tokens[i - 1].as_str())
-            } else {
-                None
-            };
-            let next = if i < seq_len - 1 {
-                Some(tokens[i + 1].as_str())
-            } else {
-                None
-            };
-
-            let mut scores = Vec::new();
-            for label in 0..NUM_LABELS {
-                scores.push(self.compute_emission(token, prev, next, label));
-            }
-            emissions.push(scores);
-        }
+        let emissions = self
+            .token_features(tokens)
+            .iter()
+            .map(|features| {
+                (0..NUM_LABELS)
+                    .map(|label| self.compute_emission(features, label))
+                    .collect()
+            })
+            .collect();
 
         (emissions, self.transition.clone())
     }
@@ -180,74 +173,153 @@ impl CrfModel {
         viterbi_decode(&emissions_flat, &transitions, NUM_LABELS)
     }
 
-    pub fn train_step(&mut self, tokens: &[String], true_labels: &[usize], _lr: f32) {
-        // Simple perceptron-style update
+    /// Structured perceptron update: moves emission and transition weights
+    /// towards `true_labels` wherever the current prediction disagrees.
+    /// Transitions the BIO scheme forbids stay forbidden.
+    pub fn train_step(&mut self, tokens: &[String], true_labels: &[usize], lr: f32) {
         let preds = self.predict(tokens);
+        self.update(tokens, true_labels, &preds, lr, &[1.0; NUM_LABELS]);
+    }
 
-        for (&pred, &true_label) in preds.iter().zip(true_labels.iter()) {
-            if pred != true_label {
-                // Update emission weights
-                for label in 0..NUM_LABELS {
-                    if label == true_label {
-                        self.emission_weights[label] += 0.1;
-                    } else if label == pred {
-                        self.emission_weights[label] -= 0.1;
-                    }
+    /// Perceptron update towards `true_labels` and away from `preds`, a
+    /// decoding made before the update. Each token's step is `lr` scaled by
+    /// the weight of its gold label in `label_weights`.
+    pub fn update(
+        &mut self,
+        tokens: &[String],
+        true_labels: &[usize],
+        preds: &[usize],
+        lr: f32,
+        label_weights: &[f32; NUM_LABELS],
+    ) {
+        let features = self.token_features(tokens);
+
+        for ((&pred, &true_label), features) in preds.iter().zip(true_labels).zip(&features) {
+            if pred == true_label {
+                continue;
+            }
+            let lr = lr * label_weights[true_label];
+            for (label, step) in [(true_label, lr), (pred, -lr)] {
+                self.emission_weights[label] += step;
+                let weights =
+                    &mut self.feature_weights[label * NUM_FEATURES..(label + 1) * NUM_FEATURES];
+                for (weight, feature) in weights.iter_mut().zip(features) {
+                    *weight += step * feature;
                 }
             }
         }
 
-        // Update transition matrix based on correct sequences
         for i in 1..true_labels.len().min(preds.len()) {
-            let from = preds[i - 1];
-            let to = preds[i];
-            let correct_from = true_labels[i - 1];
-            let correct_to = true_labels[i];
-
-            if from != correct_from || to != correct_to {
-                // Penalize wrong transitions
-                self.transition[to * NUM_LABELS + from] -= 0.01;
-                // Reward correct transitions
-                self.transition[correct_to * NUM_LABELS + correct_from] += 0.01;
+            let predicted = (preds[i - 1], preds[i]);
+            let correct = (true_labels[i - 1], true_labels[i]);
+            if predicted == correct {
+                continue;
+            }
+            let lr = lr * label_weights[correct.1];
+            for ((from, to), step) in [(correct, lr), (predicted, -lr)] {
+                let score = &mut self.transition[to * NUM_LABELS + from];
+                if *score > INVALID_TRANSITION {
+                    *score += step;
+                }
             }
         }
-
-        // Constrain transitions
-        // B-TITLE can only be followed by I-TITLE or O (not directly O after B without I)
-        self.transition[1] = self.transition[1].min(-1.0);
-        // B -> O is bad
     }
 
     pub fn save(&self, path: &str) -> std::io::Result<()> {
-        let json = serde_json::json!({
+        let content = serde_json::to_string_pretty(&self.to_json())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, content)
+    }
+
+    /// The weights in the JSON layout [`save`](Self::save) writes.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "labels": BIO_LABELS,
+            "num_labels": NUM_LABELS,
+            "num_features": NUM_FEATURES,
             "transition": self.transition,
             "emission_weights": self.emission_weights,
-            "num_labels": NUM_LABELS,
-        });
-        std::fs::write(path, serde_json::to_string_pretty(&json).unwrap())?;
-        Ok(())
+            "feature_weights": self.feature_weights,
+            "seed": self.seed,
+        })
     }
 
+    /// Transition scores in the core [`TransitionMatrix`] format, over
+    /// `tag_set`. Labels outside `tag_set` are left out.
+    pub fn to_transition_matrix(&self, tag_set: TagSet) -> anyhow::Result<TransitionMatrix> {
+        let tag_index = |label: &str| {
+            BioTag::all_tags()
+                .iter()
+                .find(|tag| tag.to_string() == label)
+                .map(BioTag::index)
+                .ok_or_else(|| anyhow::anyhow!("unknown label {label}"))
+        };
+        let indices = BIO_LABELS
+            .iter()
+            .map(|label| tag_index(label))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let num_tags = tag_set.num_tags();
+        let mut scores = TransitionMatrix::zeros(tag_set).scores;
+        for (from, &from_tag) in indices.iter().enumerate() {
+            for (to, &to_tag) in indices.iter().enumerate() {
+                if from_tag < num_tags && to_tag < num_tags {
+                    scores[from_tag][to_tag] = self.transition[to * NUM_LABELS + from];
+                }
+            }
+        }
+
+        let matrix = TransitionMatrix::new(tag_set, scores)?;
+        Ok(match self.seed {
+            Some(seed) => matrix.with_seed(seed),
+            None => matrix,
+        })
+    }
+
+    /// Load a model written by [`save`](Self::save).
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidData`] if the file is not a
+    /// model over the current label set.
     pub fn load(path: &str) -> std::io::Result<Self> {
         let content = std::fs::read_to_string(path)?;
-        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Self::from_json(&json)
+    }
 
-        let transition: Vec<f32> = json["transition"]
-            .as_array()
-            .unwrap()
-            .iter()
-            .map(|v| v.as_f64().unwrap() as f32)
-            .collect();
-        let emission_weights: Vec<f32> = json["emission_weights"]
-            .as_array()
-            .unwrap()
-            .iter()
-            .map(|v| v.as_f64().unwrap() as f32)
-            .collect();
+    /// Weights from the JSON layout of [`to_json`](Self::to_json).
+    pub fn from_json(json: &serde_json::Value) -> std::io::Result<Self> {
+        let invalid =
+            |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+
+        let num_labels = json["num_labels"].as_u64().unwrap_or(0) as usize;
+        if num_labels != NUM_LABELS {
+            return Err(invalid(format!(
+                "model has {num_labels} labels, expected {NUM_LABELS}"
+            )));
+        }
+        let floats = |key: &str, len: usize| -> std::io::Result<Vec<f32>> {
+            let values = json[key]
+                .as_array()
+                .ok_or_else(|| invalid(format!("missing {key}")))?
+                .iter()
+                .map(|v| v.as_f64().map(|v| v as f32))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid(format!("non-numeric value in {key}")))?;
+            if values.len() != len {
+                return Err(invalid(format!(
+                    "{key} has {} values, expected {len}",
+                    values.len()
+                )));
+            }
+            Ok(values)
+        };
 
         Ok(Self {
-            transition,
-            emission_weights,
+            transition: floats("transition", NUM_LABELS * NUM_LABELS)?,
+            emission_weights: floats("emission_weights", NUM_LABELS)?,
+            feature_weights: floats("feature_weights", NUM_LABELS * NUM_FEATURES)?,
+            seed: json["seed"].as_u64(),
         })
     }
 }
@@ -327,6 +399,95 @@ pub fn viterbi_decode(emissions: &[f32], transitions: &[f32], num_labels: usize)
 mod tests {
     use super::*;
 
+    #[test]
+    fn exports_transitions_in_core_format() {
+        let mut model = CrfModel::new();
+        let (b_title, i_title) = (BioTag::BeginTitle.index(), BioTag::InsideTitle.index());
+        // B-TITLE -> I-TITLE, stored as [to * NUM_LABELS + from]
+        model.transition[i_title * NUM_LABELS + b_title] = 0.7;
+
+        let matrix = model.to_transition_matrix(TagSet::V1).unwrap();
+        assert_eq!(matrix.validate().unwrap(), TagSet::V1);
+        assert_eq!(matrix.scores[b_title][i_title], 0.7);
+        assert_eq!(matrix.scores[i_title][BioTag::Outside.index()], 0.0);
+        // O -> I-TITLE is ruled out by the BIO scheme.
+        assert!(matrix.scores[BioTag::Outside.index()][i_title] < -1.0);
+
+        let matrix = model.to_transition_matrix(TagSet::V2).unwrap();
+        assert_eq!(matrix.validate().unwrap(), TagSet::V2);
+        assert_eq!(matrix.seed, None);
+    }
+
+    #[test]
+    fn records_the_training_seed() {
+        let mut model = CrfModel::new();
+        model.seed = Some(7);
+
+        let loaded = CrfModel::from_json(&model.to_json()).unwrap();
+        assert_eq!(loaded.seed, Some(7));
+        let matrix = model.to_transition_matrix(TagSet::LATEST).unwrap();
+        assert_eq!(matrix.seed, Some(7));
+    }
+
+    #[test]
+    fn learns_the_full_tag_set() {
+        let examples = [
+            (
+                "[subsplease] frieren - 05 (1080p) [x265 flac].mkv",
+                vec![
+                    BioTag::BeginGroup,
+                    BioTag::BeginTitle,
+                    BioTag::BeginEpisode,
+                    BioTag::Resolution,
+                    BioTag::VCodec,
+                    BioTag::ACodec,
+                    BioTag::Extension,
+                ],
+            ),
+            (
+                "[erai] bocchi - 12 (720p) [bd aac] [1a2b3c4d].mp4",
+                vec![
+                    BioTag::BeginGroup,
+                    BioTag::BeginTitle,
+                    BioTag::BeginEpisode,
+                    BioTag::Resolution,
+                    BioTag::Source,
+                    BioTag::ACodec,
+                    BioTag::Crc32,
+                    BioTag::Extension,
+                ],
+            ),
+        ]
+        .map(|(input, tags)| {
+            let tokens: Vec<String> = input
+                .split(|c: char| " []().-".contains(c))
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect();
+            let labels: Vec<usize> = tags.iter().map(BioTag::index).collect();
+            assert_eq!(tokens.len(), labels.len());
+            (tokens, labels)
+        });
+
+        let mut model = CrfModel::new();
+        for _ in 0..20 {
+            for (tokens, labels) in &examples {
+                model.train_step(tokens, labels, 0.1);
+            }
+        }
+        for (tokens, labels) in &examples {
+            assert_eq!(&model.predict(tokens), labels);
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("zantetsu-crf-test-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        model.save(path).unwrap();
+        let loaded = CrfModel::load(path).unwrap();
+        assert_eq!(loaded.predict(&examples[0].0), examples[0].1);
+        std::fs::remove_file(path).unwrap();
+    }
+
     #[test]
     fn test_viterbi() {
         let emissions = vec![0.1, 0.8, 0.1, 0.8, 0.1, 0.1];
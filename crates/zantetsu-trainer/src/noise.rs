@@ -0,0 +1,370 @@
+//! Label-noise filtering for silver data.
+//!
+//! Silver samples inherit every mistake of the heuristic parser that
+//! labeled them. [`NoiseFilter`] cross-checks each sample: the fields its
+//! tags spell out are rendered as a canonical filename, which is parsed
+//! again. Labels that survive the round trip are kept; a sample whose fields
+//! come back different was probably mislabeled and is down-weighted or
+//! dropped.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use zantetsu_core::parser::{HeuristicParser, NeuralParser};
+use zantetsu_core::{
+    AudioCodec, EpisodeSpec, MediaSource, ParseField, ParseResult, SeasonSpec, VideoCodec,
+};
+
+use crate::data::{BioSample, label_index};
+use crate::eval::join_tokens;
+
+/// Fields compared after the round trip. Free-text fields such as the
+/// episode title or release flags have no fixed place in a canonical name
+/// and are left out.
+const CHECKED_FIELDS: [ParseField; 12] = [
+    ParseField::Title,
+    ParseField::Group,
+    ParseField::Season,
+    ParseField::Episode,
+    ParseField::Version,
+    ParseField::Year,
+    ParseField::Resolution,
+    ParseField::Source,
+    ParseField::VideoCodec,
+    ParseField::AudioCodec,
+    ParseField::Crc32,
+    ParseField::Extension,
+];
+
+fn video_codec_tag(codec: VideoCodec) -> &'static str {
+    match codec {
+        VideoCodec::H264 => "x264",
+        VideoCodec::HEVC => "x265",
+        VideoCodec::AV1 => "AV1",
+        VideoCodec::VP9 => "VP9",
+        VideoCodec::MPEG4 => "XviD",
+    }
+}
+
+fn audio_codec_tag(codec: AudioCodec) -> &'static str {
+    match codec {
+        AudioCodec::FLAC => "FLAC",
+        AudioCodec::AAC => "AAC",
+        AudioCodec::Opus => "Opus",
+        AudioCodec::AC3 => "AC3",
+        AudioCodec::DTS => "DTS",
+        AudioCodec::MP3 => "MP3",
+        AudioCodec::Vorbis => "Vorbis",
+        AudioCodec::TrueHD => "TrueHD",
+        AudioCodec::EAAC => "EAAC",
+    }
+}
+
+fn source_tag(source: MediaSource) -> &'static str {
+    match source {
+        MediaSource::BluRayRemux => "BluRay Remux",
+        MediaSource::BluRay => "BluRay",
+        MediaSource::WebDL => "WEB-DL",
+        MediaSource::WebRip => "WEBRip",
+        MediaSource::HDTV => "HDTV",
+        MediaSource::DVD => "DVD",
+        MediaSource::LaserDisc => "LaserDisc",
+        MediaSource::VHS => "VHS",
+    }
+}
+
+/// Render `result` as a fansub-style filename:
+/// `[Group] Title - S02E05v2 (2024) [1080p BluRay x265 FLAC][ABCD1234].mkv`.
+///
+/// Every part is written in a spelling the heuristic parser recognizes, so
+/// parsing the rendered name gives `result` back when its fields are sound.
+pub fn render_canonical(result: &ParseResult) -> String {
+    let mut name = String::new();
+    if let Some(group) = &result.group {
+        name.push_str(&format!("[{group}] "));
+    }
+    if let Some(title) = &result.title {
+        name.push_str(title);
+    }
+
+    let episode = result.episode.as_ref().map(|episode| match episode {
+        EpisodeSpec::Version { episode, version } => format!("{episode:02}v{version}"),
+        EpisodeSpec::Single(episode) => match result.version {
+            Some(version) => format!("{episode:02}v{version}"),
+            None => format!("{episode:02}"),
+        },
+        other => other.to_string(),
+    });
+    match (result.season, episode) {
+        (Some(SeasonSpec::Single(season)), Some(episode)) => {
+            name.push_str(&format!(" - S{season:02}E{episode}"));
+        }
+        (season, episode) => {
+            if let Some(season) = season {
+                name.push_str(&format!(" {season}"));
+            }
+            if let Some(episode) = episode {
+                name.push_str(&format!(" - {episode}"));
+            }
+        }
+    }
+    if let Some(year) = result.year {
+        name.push_str(&format!(" ({year})"));
+    }
+
+    let quality: Vec<String> = [
+        result.resolution.map(|resolution| resolution.to_string()),
+        result.source.map(|source| source_tag(source).to_string()),
+        result
+            .video_codec
+            .map(|codec| video_codec_tag(codec).to_string()),
+        result
+            .audio_codec
+            .map(|codec| audio_codec_tag(codec).to_string()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !quality.is_empty() {
+        name.push_str(&format!(" [{}]", quality.join(" ")));
+    }
+    if let Some(crc32) = &result.crc32 {
+        name.push_str(&format!("[{crc32}]"));
+    }
+    if let Some(extension) = &result.extension {
+        name.push_str(&format!(".{extension}"));
+    }
+    name
+}
+
+/// What to do with a silver sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    /// The labels survived the cross-check.
+    Keep,
+    /// A few fields disagreed; train on the sample at this weight.
+    DownWeight(f32),
+    /// Too many fields disagreed to trust the labels.
+    Reject,
+}
+
+/// Outcome of [`NoiseFilter::check`] for one sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseCheck {
+    /// Fields that changed in the round trip, in [`ParseField::ALL`] order.
+    pub mismatches: Vec<ParseField>,
+    pub verdict: Verdict,
+}
+
+/// Counts of a filtering pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoiseReport {
+    pub total: usize,
+    pub kept: usize,
+    pub down_weighted: usize,
+    pub rejected: usize,
+    /// Samples on which each field failed the cross-check.
+    pub mismatches: BTreeMap<String, usize>,
+}
+
+impl NoiseReport {
+    /// Add one checked sample to the counts.
+    pub fn record(&mut self, check: &NoiseCheck) {
+        self.total += 1;
+        match check.verdict {
+            Verdict::Keep => self.kept += 1,
+            Verdict::DownWeight(_) => self.down_weighted += 1,
+            Verdict::Reject => self.rejected += 1,
+        }
+        for field in &check.mismatches {
+            *self.mismatches.entry(field.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Share of samples rejected, `0.0` for an empty pass.
+    pub fn rejection_rate(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.rejected as f32 / self.total as f32
+        }
+    }
+}
+
+impl fmt::Display for NoiseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} samples: {} kept, {} down-weighted, {} rejected ({:.1}% rejection rate)",
+            self.total,
+            self.kept,
+            self.down_weighted,
+            self.rejected,
+            self.rejection_rate() * 100.0
+        )?;
+        for (field, count) in &self.mismatches {
+            writeln!(f, "  {field:<14} {count:>6} mismatches")?;
+        }
+        Ok(())
+    }
+}
+
+/// Cross-checks silver samples by re-parsing the canonical filename of
+/// their labels.
+pub struct NoiseFilter {
+    parser: HeuristicParser,
+    max_mismatches: usize,
+    down_weight: f32,
+}
+
+impl NoiseFilter {
+    pub fn new() -> anyhow::Result<Self> {
+        Ok(Self {
+            parser: HeuristicParser::new()?,
+            max_mismatches: 1,
+            down_weight: 0.5,
+        })
+    }
+
+    /// Most mismatched fields a sample may have and still be kept, at the
+    /// reduced weight (default `1`). `0` rejects every mismatch.
+    pub fn with_max_mismatches(mut self, max_mismatches: usize) -> Self {
+        self.max_mismatches = max_mismatches;
+        self
+    }
+
+    /// Weight of samples with tolerated mismatches (default `0.5`).
+    pub fn with_down_weight(mut self, down_weight: f32) -> Self {
+        self.down_weight = down_weight;
+        self
+    }
+
+    /// Cross-check one sample. Samples without a title, or whose tags do
+    /// not assemble into a result, are rejected.
+    pub fn check(&self, sample: &BioSample) -> NoiseCheck {
+        let reject = NoiseCheck {
+            mismatches: Vec::new(),
+            verdict: Verdict::Reject,
+        };
+        let Some(tag_indices) = sample
+            .ner_tags
+            .iter()
+            .map(|tag| label_index(tag))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return reject;
+        };
+        let (input, offsets) = join_tokens(&sample.tokens);
+        let Ok(labeled) = NeuralParser::assemble_result(&input, &offsets, &tag_indices) else {
+            return reject;
+        };
+        if labeled.title.is_none() {
+            return reject;
+        }
+        let Ok(reparsed) = self.parser.parse(&render_canonical(&labeled)) else {
+            return reject;
+        };
+
+        let mismatches: Vec<ParseField> = labeled
+            .diff(&reparsed)
+            .into_iter()
+            .filter(|diff| {
+                // Tokens are lowercased, so only a change beyond case counts.
+                let lowercase = |value: &Option<String>| value.as_ref().map(|v| v.to_lowercase());
+                CHECKED_FIELDS.contains(&diff.field)
+                    && lowercase(&diff.left) != lowercase(&diff.right)
+            })
+            .map(|diff| diff.field)
+            .collect();
+        let verdict = match mismatches.len() {
+            0 => Verdict::Keep,
+            n if n <= self.max_mismatches => Verdict::DownWeight(self.down_weight),
+            _ => Verdict::Reject,
+        };
+        NoiseCheck {
+            mismatches,
+            verdict,
+        }
+    }
+
+    /// Drop rejected samples and set the weight of down-weighted ones.
+    pub fn filter(&self, samples: Vec<BioSample>) -> (Vec<BioSample>, NoiseReport) {
+        let mut report = NoiseReport::default();
+        let mut kept = Vec::with_capacity(samples.len());
+        for mut sample in samples {
+            let check = self.check(&sample);
+            report.record(&check);
+            match check.verdict {
+                Verdict::Keep => kept.push(sample),
+                Verdict::DownWeight(weight) => {
+                    sample.weight = Some(sample.weight.unwrap_or(1.0) * weight);
+                    kept.push(sample);
+                }
+                Verdict::Reject => {}
+            }
+        }
+        (kept, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::align::align_tags;
+    use zantetsu_core::parser::Tokenizer;
+
+    fn silver(input: &str) -> BioSample {
+        let parser = HeuristicParser::new().unwrap();
+        let result = parser.parse(input).unwrap();
+        let tokens = Tokenizer::new().tokenize(input);
+        let tags = align_tags(&tokens, &parser.field_spans(input, &result));
+        BioSample {
+            tokens: tokens.into_iter().map(|t| t.text).collect(),
+            ner_tags: tags.into_iter().map(|t| t.to_string()).collect(),
+            weight: None,
+        }
+    }
+
+    #[test]
+    fn clean_labels_survive_the_round_trip() {
+        let filter = NoiseFilter::new().unwrap();
+        for input in [
+            "[SubsPlease] Sousou no Frieren - 05 (1080p) [ABCD1234].mkv",
+            "[Erai-raws] Oshi no Ko S02E05 [720p WEB-DL x264 AAC].mkv",
+            "Mushishi - 26v2 [BD 1080p HEVC FLAC].mkv",
+        ] {
+            let check = filter.check(&silver(input));
+            assert_eq!(check.verdict, Verdict::Keep, "{input}: {check:?}");
+        }
+    }
+
+    #[test]
+    fn mislabeled_samples_are_down_weighted_or_rejected() {
+        let filter = NoiseFilter::new().unwrap();
+        let clean = silver("[SubsPlease] Sousou no Frieren (2023) - 05 (1080p) [ABCD1234].mkv");
+        let position = |token: &str| clean.tokens.iter().position(|t| t == token).unwrap();
+
+        // The episode number tagged as a release version.
+        let mut one_off = clean.clone();
+        one_off.ner_tags[position("05")] = "VERSION".into();
+        let check = filter.check(&one_off);
+        assert_eq!(check.mismatches, [ParseField::Version]);
+        assert_eq!(check.verdict, Verdict::DownWeight(0.5));
+
+        // The year swallowed into the title.
+        let mut garbled = clean.clone();
+        garbled.ner_tags[position("2023")] = "I-TITLE".into();
+        let check = filter.check(&garbled);
+        assert_eq!(check.mismatches, [ParseField::Title, ParseField::Year]);
+        assert_eq!(check.verdict, Verdict::Reject);
+
+        let (kept, report) = filter.filter(vec![clean, one_off, garbled]);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].weight, Some(0.5));
+        assert_eq!(
+            (report.kept, report.down_weighted, report.rejected),
+            (1, 1, 1)
+        );
+        assert!((report.rejection_rate() - 1.0 / 3.0).abs() < 1e-6);
+    }
+}
@@ -1,11 +1,30 @@
 //! Training loop for the CRF model.
 
-use crate::data::{CharVocab, load_bio_dataset};
+use std::path::Path;
+
+use zantetsu_core::crf::transitions::TRANSITIONS_FILE;
+use zantetsu_core::parser::TagSet;
+
+use crate::checkpoint::{CHECKPOINT_FILE, Checkpoint, dataset_hash};
+use crate::config::TrainConfig;
+use crate::data::{CharVocab, TrainingExample, load_bio_dataset, shuffled_indices};
+use crate::eval::{EvalReport, evaluate, split_held_out};
 use crate::model::CrfModel;
 
+/// File name of the trained model inside [`TrainConfig::output_dir`].
+pub const MODEL_FILE: &str = "crf_model_v2.json";
+
+/// Directory inside [`TrainConfig::output_dir`] the model's transitions are
+/// exported to. It is kept apart from `ner_model/`, whose `transitions.json`
+/// belongs to the fine-tuned neural model.
+pub const TRANSITIONS_DIR: &str = "crf";
+
 pub struct Trainer {
     model: CrfModel,
     _vocab: CharVocab,
+    config: TrainConfig,
+    /// Position to continue from, set by [`Trainer::resume`].
+    resume_at: Option<(usize, usize, String)>,
 }
 
 impl Trainer {
@@ -16,9 +35,66 @@ impl Trainer {
         Self {
             model,
             _vocab: vocab,
+            config: TrainConfig::default(),
+            resume_at: None,
         }
     }
 
+    /// A trainer starting from the model saved at `path` by
+    /// [`save_model`](Self::save_model).
+    pub fn load_model<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let model = CrfModel::load(&path.to_string_lossy())
+            .map_err(|e| anyhow::anyhow!("loading {}: {e}", path.display()))?;
+        Ok(Self {
+            model,
+            ..Self::new()
+        })
+    }
+
+    /// Restore a run from a checkpoint written during
+    /// [`train_on_examples`](Self::train_on_examples). Training the restored
+    /// trainer on the same examples continues from the checkpointed epoch
+    /// and step; other examples are refused.
+    pub fn resume<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let checkpoint = Checkpoint::load(path)?;
+        Ok(Self {
+            model: CrfModel::from_json(&checkpoint.model)?,
+            _vocab: CharVocab::new(),
+            config: checkpoint.config,
+            resume_at: Some((checkpoint.epoch, checkpoint.step, checkpoint.dataset_hash)),
+        })
+    }
+
+    /// Train with the learning rate, batch size, seed and label weights of
+    /// `config`. The seed is also recorded in the saved model.
+    pub fn with_config(mut self, config: TrainConfig) -> Self {
+        self.model.seed = Some(config.seed);
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &TrainConfig {
+        &self.config
+    }
+
+    /// Where checkpoints are written: [`CHECKPOINT_FILE`] in the output
+    /// directory.
+    pub fn checkpoint_path(&self) -> std::path::PathBuf {
+        self.config.output_dir.join(CHECKPOINT_FILE)
+    }
+
+    fn save_checkpoint(&self, epoch: usize, step: usize, dataset_hash: &str) -> anyhow::Result<()> {
+        Checkpoint {
+            epoch,
+            step,
+            dataset_hash: dataset_hash.to_string(),
+            config: self.config.clone(),
+            model: self.model.to_json(),
+        }
+        .save(self.checkpoint_path())
+    }
+
     pub fn train_on_file<P: AsRef<std::path::Path>>(
         &mut self,
         path: P,
@@ -26,55 +102,99 @@ impl Trainer {
     ) -> anyhow::Result<()> {
         let examples = load_bio_dataset(path)?;
         println!("Loaded {} training examples", examples.len());
+        self.train_on_examples(&examples, epochs)
+    }
+
+    pub fn train_on_examples(
+        &mut self,
+        examples: &[TrainingExample],
+        epochs: usize,
+    ) -> anyhow::Result<()> {
+        let lr = self.config.learning_rate;
+        let label_weights = self.config.label_weight_table();
+        let checkpoint_every = self.config.checkpoint_every;
+        let hash = dataset_hash(examples);
 
-        let lr = 0.1f32;
+        let (start_epoch, start_step) = match self.resume_at.take() {
+            Some((epoch, step, checkpoint_hash)) => {
+                if checkpoint_hash != hash {
+                    anyhow::bail!(
+                        "checkpoint was written for dataset {checkpoint_hash}, \
+                         but these examples hash to {hash}"
+                    );
+                }
+                println!("Resuming at epoch {}, step {}", epoch + 1, step);
+                (epoch, step)
+            }
+            None => (0, 0),
+        };
 
-        for epoch in 0..epochs {
+        for epoch in start_epoch..epochs {
             let mut correct = 0usize;
             let mut total = 0usize;
 
-            // Shuffle
-            let mut indices: Vec<usize> = (0..examples.len()).collect();
-            for i in (1..indices.len()).rev() {
-                let j = (epoch * 17 + i * 13) % (i + 1);
-                indices.swap(i, j);
-            }
+            // Shuffle, seeded per epoch so a resumed run replays the order.
+            let indices = shuffled_indices(
+                examples.len(),
+                (u128::from(self.config.seed) << 64) | epoch as u128,
+            );
 
-            for (step, &idx) in indices.iter().enumerate() {
-                let example = &examples[idx];
-                if example.tokens.is_empty() {
-                    continue;
-                }
+            let mut step = if epoch == start_epoch {
+                start_step.min(indices.len())
+            } else {
+                0
+            };
+            for batch in indices[step..].chunks(self.config.batch_size) {
+                // Decode the whole batch before updating, so every example
+                // sees the same weights.
+                let preds: Vec<Vec<usize>> = batch
+                    .iter()
+                    .map(|&idx| self.model.predict(&examples[idx].tokens))
+                    .collect();
 
-                // Training step
-                self.model.train_step(&example.tokens, &example.labels, lr);
+                for (&idx, preds) in batch.iter().zip(&preds) {
+                    step += 1;
+                    let example = &examples[idx];
+                    if example.tokens.is_empty() {
+                        continue;
+                    }
 
-                // Evaluate
-                let preds = self.model.predict(&example.tokens);
+                    self.model.update(
+                        &example.tokens,
+                        &example.labels,
+                        preds,
+                        lr * example.weight,
+                        &label_weights,
+                    );
 
-                for (i, &pred) in preds.iter().enumerate() {
-                    if i < example.labels.len() {
-                        if pred == example.labels[i] {
-                            correct += 1;
+                    for (i, &pred) in preds.iter().enumerate() {
+                        if i < example.labels.len() {
+                            if pred == example.labels[i] {
+                                correct += 1;
+                            }
+                            total += 1;
                         }
-                        total += 1;
+                    }
+
+                    if step % 5000 == 0 {
+                        let acc = if total > 0 {
+                            correct as f32 / total as f32
+                        } else {
+                            0.0
+                        };
+                        println!(
+                            "Epoch {}/{}, Step {}/{}, Accuracy: {:.2}%",
+                            epoch + 1,
+                            epochs,
+                            step,
+                            examples.len(),
+                            acc * 100.0
+                        );
                     }
                 }
 
-                if (step + 1) % 5000 == 0 {
-                    let acc = if total > 0 {
-                        correct as f32 / total as f32
-                    } else {
-                        0.0
-                    };
-                    println!(
-                        "Epoch {}/{}, Step {}/{}, Accuracy: {:.2}%",
-                        epoch + 1,
-                        epochs,
-                        step + 1,
-                        examples.len(),
-                        acc * 100.0
-                    );
+                if checkpoint_every > 0 && step % checkpoint_every < batch.len() {
+                    self.save_checkpoint(epoch, step, &hash)?;
                 }
             }
 
@@ -89,16 +209,48 @@ impl Trainer {
                 epochs,
                 acc * 100.0
             );
+            if checkpoint_every > 0 {
+                self.save_checkpoint(epoch + 1, 0, &hash)?;
+            }
         }
 
         Ok(())
     }
 
+    /// Span- and field-level scores of the model on held-out `examples`.
+    pub fn evaluate(&self, examples: &[TrainingExample]) -> anyhow::Result<EvalReport> {
+        evaluate(examples, |tokens| self.model.predict(tokens))
+    }
+
     pub fn save_model<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
         self.model.save(path.as_ref().to_str().unwrap())?;
         println!("Model saved to {:?}", path.as_ref());
         Ok(())
     }
+
+    /// Write the learned transitions to `<model_dir>/transitions.json`, where
+    /// the neural parser loads them. The matrix is sized for the tag set of
+    /// the model's `config.json` (the latest tag set if there is none).
+    pub fn export_transitions<P: AsRef<Path>>(&self, model_dir: P) -> anyhow::Result<()> {
+        let model_dir = model_dir.as_ref();
+        let tag_set = std::fs::read_to_string(model_dir.join("config.json"))
+            .ok()
+            .and_then(|config| serde_json::from_str::<serde_json::Value>(&config).ok())
+            .and_then(|config| {
+                config
+                    .get("id2label")?
+                    .as_object()
+                    .map(|labels| labels.len())
+            })
+            .and_then(TagSet::from_num_labels)
+            .unwrap_or(TagSet::LATEST);
+
+        std::fs::create_dir_all(model_dir)?;
+        let path = model_dir.join(TRANSITIONS_FILE);
+        self.model.to_transition_matrix(tag_set)?.save(&path)?;
+        println!("Transitions exported to {:?}", path);
+        Ok(())
+    }
 }
 
 impl Default for Trainer {
@@ -107,20 +259,40 @@ impl Default for Trainer {
     }
 }
 
-pub fn run_training() -> anyhow::Result<()> {
-    let mut trainer = Trainer::new();
-
-    let data_path = "data/training/bio_train_50k.txt";
+/// Train the CRF model as `config` describes, report its scores on the
+/// held-out split and write the model and transitions to
+/// `config.output_dir`. With `resume`, training continues from that
+/// checkpoint instead of starting over.
+pub fn run_training(config: &TrainConfig, resume: Option<&Path>) -> anyhow::Result<()> {
+    config.validate()?;
+    let data_path = &config.train_data;
 
-    if !std::path::Path::new(data_path).exists() {
-        anyhow::bail!("Training data not found: {}", data_path);
+    if !data_path.exists() {
+        anyhow::bail!("Training data not found: {}", data_path.display());
     }
 
+    let examples = load_bio_dataset(data_path)?;
+    let (train, held_out) = split_held_out(examples, config.held_out_fraction, config.seed);
+    println!(
+        "Loaded {} training and {} held-out examples",
+        train.len(),
+        held_out.len()
+    );
+
     println!("Starting improved CRF training...");
-    trainer.train_on_file(data_path, 3)?;
+    let trainer = match resume {
+        Some(checkpoint) => Trainer::resume(checkpoint)?,
+        None => Trainer::new(),
+    };
+    let mut trainer = trainer.with_config(config.clone());
+    trainer.train_on_examples(&train, config.epochs)?;
+    if !held_out.is_empty() {
+        println!("{}", trainer.evaluate(&held_out)?);
+    }
 
-    std::fs::create_dir_all("models")?;
-    trainer.save_model("models/crf_model_v2.json")?;
+    std::fs::create_dir_all(&config.output_dir)?;
+    trainer.save_model(config.output_dir.join(MODEL_FILE))?;
+    trainer.export_transitions(config.output_dir.join(TRANSITIONS_DIR))?;
 
     Ok(())
 }
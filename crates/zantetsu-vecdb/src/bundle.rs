@@ -0,0 +1,355 @@
+//! Prebuilt title index bundles.
+//!
+//! An [`IndexBundle`] is a directory holding an [`HnswIndex`] over every
+//! catalog alias (`index.hnsw`, readable on its own with [`HnswIndex::load`])
+//! and the catalog the index points into (`catalog.json`). `kitsu-sync
+//! build-index` writes one from a synced dump so the matcher does not have to
+//! parse and embed the whole catalog at startup.
+
+use crate::error::{MatchResult, MatcherError};
+use crate::index::{HnswIndex, HnswParams, Quantization};
+use crate::matcher::{
+    AliasSource, AnimeIds, AnimeTitleMatch, MatchProvider, MatchSource, load_catalog,
+    normalize_title,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Version of the bundle layout, stored in `catalog.json`.
+pub const BUNDLE_VERSION: u32 = 1;
+/// File name of the HNSW index inside a bundle directory.
+pub const BUNDLE_INDEX_FILE: &str = "index.hnsw";
+/// File name of the catalog inside a bundle directory.
+pub const BUNDLE_CATALOG_FILE: &str = "catalog.json";
+
+/// Hits fetched per requested result, since one anime has several aliases.
+const HITS_PER_RESULT: usize = 4;
+
+/// One anime in a bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleEntry {
+    /// Canonical title chosen for the anime.
+    pub canonical_title: String,
+    /// Known aliases, canonical title first.
+    pub titles: Vec<String>,
+    /// Known IDs for the anime.
+    pub ids: AnimeIds,
+    /// Season number written in the anime's titles.
+    #[serde(default)]
+    pub season: Option<u32>,
+    /// Part number written in the anime's titles.
+    #[serde(default)]
+    pub part: Option<u32>,
+    /// Year the anime started airing.
+    #[serde(default)]
+    pub year: Option<u16>,
+}
+
+/// The anime and alias kind behind one index node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct BundleNode {
+    entry: u32,
+    source: AliasSource,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleCatalog {
+    version: u32,
+    provider: MatchProvider,
+    entries: Vec<BundleEntry>,
+    /// Indexed by node id.
+    nodes: Vec<BundleNode>,
+}
+
+/// An HNSW title index together with the catalog it was built from.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use zantetsu_vecdb::{HnswParams, IndexBundle, MatchSource};
+///
+/// let bundle = IndexBundle::build(
+///     MatchSource::kitsu_export("/tmp/kitsu-dumps"),
+///     HnswParams::default(),
+/// )
+/// .unwrap();
+/// bundle.save("/tmp/kitsu-dumps/index").unwrap();
+///
+/// let bundle = IndexBundle::load("/tmp/kitsu-dumps/index").unwrap();
+/// let best = bundle.search("Sousou no Frieren", 1).unwrap();
+/// assert!(!best.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndexBundle {
+    provider: MatchProvider,
+    index: HnswIndex,
+    entries: Vec<BundleEntry>,
+    nodes: Vec<BundleNode>,
+}
+
+impl IndexBundle {
+    /// Loads the catalog from a local `source` and indexes every alias
+    /// except generated acronyms, whose embeddings say nothing about the
+    /// title.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of loading the catalog, and
+    /// [`MatcherError::InvalidIndex`] for a remote endpoint, which has no
+    /// catalog to index.
+    pub fn build(source: MatchSource, params: HnswParams) -> MatchResult<Self> {
+        let (provider, catalog) = load_catalog(source)?;
+
+        let mut titles = Vec::new();
+        let mut nodes = Vec::new();
+        let mut entries = Vec::with_capacity(catalog.len());
+        for (entry, anime) in catalog.iter().enumerate() {
+            for alias in &anime.aliases {
+                if alias.source != AliasSource::Acronym {
+                    titles.push(alias.title.clone());
+                    nodes.push(BundleNode {
+                        entry: entry as u32,
+                        source: alias.source,
+                    });
+                }
+            }
+            entries.push(BundleEntry {
+                canonical_title: anime.canonical_title.clone(),
+                titles: anime.titles(),
+                ids: anime.ids.clone(),
+                season: anime.season,
+                part: anime.part,
+                year: anime.year,
+            });
+        }
+
+        Ok(Self {
+            provider,
+            index: HnswIndex::build_with(titles, params),
+            entries,
+            nodes,
+        })
+    }
+
+    /// Compresses the index vectors; see [`HnswIndex::quantize`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`HnswIndex::quantize`].
+    pub fn quantize(&mut self, quantization: Quantization) -> MatchResult<()> {
+        self.index.quantize(quantization)
+    }
+
+    /// Writes the bundle into the `dir` directory, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the files cannot be written.
+    pub fn save(&self, dir: impl AsRef<Path>) -> MatchResult<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        self.index.save(dir.join(BUNDLE_INDEX_FILE))?;
+
+        let catalog = BundleCatalog {
+            version: BUNDLE_VERSION,
+            provider: self.provider,
+            entries: self.entries.clone(),
+            nodes: self.nodes.clone(),
+        };
+        let mut writer = BufWriter::new(File::create(dir.join(BUNDLE_CATALOG_FILE))?);
+        serde_json::to_writer(&mut writer, &catalog)
+            .map_err(|error| MatcherError::Io(error.into()))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a bundle written by [`save`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if a file cannot be read and
+    /// [`MatcherError::InvalidIndex`] if the bundle is corrupt, from another
+    /// bundle version, or its catalog does not match its index.
+    pub fn load(dir: impl AsRef<Path>) -> MatchResult<Self> {
+        let dir = dir.as_ref();
+        let reader = BufReader::new(File::open(dir.join(BUNDLE_CATALOG_FILE))?);
+        let catalog: BundleCatalog = serde_json::from_reader(reader)
+            .map_err(|error| MatcherError::InvalidIndex(format!("bundle catalog: {error}")))?;
+        if catalog.version != BUNDLE_VERSION {
+            return Err(MatcherError::InvalidIndex(format!(
+                "unsupported bundle version {}",
+                catalog.version
+            )));
+        }
+
+        let index = HnswIndex::load(dir.join(BUNDLE_INDEX_FILE))?;
+        if index.len() != catalog.nodes.len()
+            || catalog
+                .nodes
+                .iter()
+                .any(|node| node.entry as usize >= catalog.entries.len())
+        {
+            return Err(MatcherError::InvalidIndex(
+                "bundle catalog does not match its index".into(),
+            ));
+        }
+
+        Ok(Self {
+            provider: catalog.provider,
+            index,
+            entries: catalog.entries,
+            nodes: catalog.nodes,
+        })
+    }
+
+    /// Search for the anime whose aliases embed closest to `title`, best
+    /// first, with at most one result per anime.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::EmptyQuery`] for a blank title.
+    pub fn search(&self, title: &str, limit: usize) -> MatchResult<Vec<AnimeTitleMatch>> {
+        if normalize_title(title).is_empty() {
+            return Err(MatcherError::EmptyQuery);
+        }
+
+        let limit = limit.max(1);
+        let mut seen = HashSet::new();
+        let mut matches = Vec::with_capacity(limit);
+        for hit in self.index.search_title(title, limit * HITS_PER_RESULT) {
+            let node = self.nodes[hit.id];
+            if !seen.insert(node.entry) {
+                continue;
+            }
+            let entry = &self.entries[node.entry as usize];
+            matches.push(AnimeTitleMatch {
+                provider: self.provider,
+                canonical_title: entry.canonical_title.clone(),
+                matched_title: self.index.title(hit.id).unwrap_or_default().to_string(),
+                alias_source: node.source,
+                score: hit.score.clamp(0.0, 1.0),
+                ids: entry.ids.clone(),
+                season: entry.season,
+                part: entry.part,
+                year: entry.year,
+                titles: entry.titles.clone(),
+            });
+            if matches.len() == limit {
+                break;
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Which catalog the bundle was built from.
+    #[must_use]
+    pub fn provider(&self) -> MatchProvider {
+        self.provider
+    }
+
+    /// The title index.
+    #[must_use]
+    pub fn index(&self) -> &HnswIndex {
+        &self.index
+    }
+
+    /// The indexed anime.
+    #[must_use]
+    pub fn entries(&self) -> &[BundleEntry] {
+        &self.entries
+    }
+
+    /// Number of indexed anime.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the bundle indexes no anime.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::KitsuExportRecord;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn builds_saves_and_searches_a_bundle() {
+        let dir = std::env::temp_dir().join(format!(
+            "zantetsu-bundle-test-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let records = [
+            ("Spy x Family", vec!["Spy Family"], 1),
+            (
+                "Sousou no Frieren",
+                vec!["Frieren: Beyond Journey's End"],
+                2,
+            ),
+            ("Jujutsu Kaisen", vec![], 3),
+        ]
+        .map(|(title, synonyms, kitsu_id)| KitsuExportRecord {
+            kitsu_id,
+            canonical_title: Some(title.into()),
+            titles: vec![title.into()],
+            synonyms: synonyms.into_iter().map(Into::into).collect(),
+            anilist_id: None,
+            mal_id: None,
+            start_year: None,
+        });
+        let lines = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(dir.join("catalog.jsonl"), lines).unwrap();
+
+        let bundle =
+            IndexBundle::build(MatchSource::kitsu_export(&dir), HnswParams::default()).unwrap();
+        let bundle_dir = dir.join("index");
+        bundle.save(&bundle_dir).unwrap();
+        assert!(HnswIndex::load(bundle_dir.join(BUNDLE_INDEX_FILE)).is_ok());
+
+        let loaded = IndexBundle::load(&bundle_dir).unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.provider(), MatchProvider::KitsuExport);
+        let matches = loaded.search("beyond journeys end", 2).unwrap();
+        assert_eq!(matches[0].canonical_title, "Sousou no Frieren");
+        assert_eq!(matches[0].alias_source, AliasSource::Synonym);
+        assert_eq!(matches[0].ids.kitsu, Some(2));
+        assert_ne!(matches[1].canonical_title, "Sousou no Frieren");
+
+        let catalog = fs::read_to_string(bundle_dir.join(BUNDLE_CATALOG_FILE)).unwrap();
+        fs::write(
+            bundle_dir.join(BUNDLE_CATALOG_FILE),
+            catalog.replacen("\"version\":1", "\"version\":99", 1),
+        )
+        .unwrap();
+        assert!(matches!(
+            IndexBundle::load(&bundle_dir),
+            Err(MatcherError::InvalidIndex(_))
+        ));
+        assert!(matches!(
+            IndexBundle::build(
+                MatchSource::remote_endpoint("https://graphql.anilist.co"),
+                HnswParams::default()
+            ),
+            Err(MatcherError::InvalidIndex(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+//! Resolution cache.
+//!
+//! LRU cache of resolved matches keyed by the raw title string, consulted by
+//! [`TitleMatcher::match_title`](crate::TitleMatcher::match_title) before
+//! any scoring or remote request. RSS pipelines resolve the same few dozen
+//! titles thousands of times a day.
+
+use crate::error::{MatchResult, MatcherError};
+use crate::matcher::AnimeTitleMatch;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+struct CachedResolution {
+    matched: AnimeTitleMatch,
+    stored_at: SystemTime,
+}
+
+/// On-disk form of one entry.
+#[derive(Serialize, Deserialize)]
+struct PersistedResolution {
+    title: String,
+    matched: AnimeTitleMatch,
+    /// Seconds since the Unix epoch.
+    stored_at: u64,
+}
+
+/// Thread-safe LRU cache of raw title → resolved match, with optional
+/// expiry and disk persistence.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::num::NonZeroUsize;
+/// use std::time::Duration;
+/// use zantetsu_vecdb::{ResolutionCache, TitleMatcher};
+///
+/// let cache = ResolutionCache::new(NonZeroUsize::new(512).unwrap())
+///     .with_ttl(Duration::from_secs(24 * 60 * 60));
+/// let matcher = TitleMatcher::from_kitsu_dump("/tmp/kitsu-dumps")
+///     .unwrap()
+///     .with_cache(cache);
+///
+/// matcher.match_title("Sousou no Frieren").unwrap(); // scored
+/// matcher.match_title("Sousou no Frieren").unwrap(); // cached
+/// matcher.cache().unwrap().save("/tmp/resolutions.json").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ResolutionCache {
+    entries: Mutex<LruCache<String, CachedResolution>>,
+    ttl: Option<Duration>,
+}
+
+impl ResolutionCache {
+    /// Cache up to `capacity` resolutions, with no expiry.
+    #[must_use]
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl: None,
+        }
+    }
+
+    /// Treat entries older than `ttl` as missing.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// The expiry, if any.
+    #[must_use]
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    /// Look up `title`, marking it as most recently used. Expired entries
+    /// are dropped.
+    #[must_use]
+    pub fn get(&self, title: &str) -> Option<AnimeTitleMatch> {
+        let mut entries = self.lock();
+        let cached = entries.get(title)?;
+        if self.is_expired(cached) {
+            entries.pop(title);
+            return None;
+        }
+        Some(cached.matched.clone())
+    }
+
+    /// Store the match for `title`, evicting the least recently used entry
+    /// when full.
+    pub fn insert(&self, title: &str, matched: AnimeTitleMatch) {
+        self.lock().put(
+            title.to_string(),
+            CachedResolution {
+                matched,
+                stored_at: SystemTime::now(),
+            },
+        );
+    }
+
+    /// Number of cached entries, expired ones included until they are looked
+    /// up.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if nothing is cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Drop every entry (e.g. after switching to a newer dump).
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Writes the unexpired entries to `path` as JSON, keeping their age and
+    /// recency order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> MatchResult<()> {
+        let persisted: Vec<PersistedResolution> = self
+            .lock()
+            .iter()
+            .rev()
+            .filter(|(_, cached)| !self.is_expired(cached))
+            .map(|(title, cached)| PersistedResolution {
+                title: title.clone(),
+                matched: cached.matched.clone(),
+                stored_at: cached
+                    .stored_at
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |age| age.as_secs()),
+            })
+            .collect();
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, &persisted)
+            .map_err(|error| MatcherError::Io(error.into()))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a cache written by [`save`](Self::save), keeping the `capacity`
+    /// most recently used entries. Entries keep their original age, so a
+    /// TTL set afterwards with [`with_ttl`](Self::with_ttl) still applies.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the file cannot be read and
+    /// [`MatcherError::InvalidCache`] if it is not a saved cache.
+    pub fn load(path: impl AsRef<Path>, capacity: NonZeroUsize) -> MatchResult<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let persisted: Vec<PersistedResolution> = serde_json::from_reader(reader)
+            .map_err(|error| MatcherError::InvalidCache(error.to_string()))?;
+
+        let cache = Self::new(capacity);
+        {
+            let mut entries = cache.lock();
+            for entry in persisted {
+                entries.put(
+                    entry.title,
+                    CachedResolution {
+                        matched: entry.matched,
+                        stored_at: UNIX_EPOCH + Duration::from_secs(entry.stored_at),
+                    },
+                );
+            }
+        }
+        Ok(cache)
+    }
+
+    fn is_expired(&self, cached: &CachedResolution) -> bool {
+        self.ttl
+            .is_some_and(|ttl| cached.stored_at.elapsed().is_ok_and(|age| age >= ttl))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache<String, CachedResolution>> {
+        // Entries are plain data, so a panic elsewhere cannot leave them inconsistent.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::{AliasSource, AnimeIds, MatchProvider};
+    use std::fs;
+
+    fn resolution(title: &str, kitsu: u32) -> AnimeTitleMatch {
+        AnimeTitleMatch {
+            provider: MatchProvider::KitsuDump,
+            canonical_title: title.into(),
+            matched_title: title.into(),
+            alias_source: AliasSource::Canonical,
+            score: 1.0,
+            ids: AnimeIds {
+                kitsu: Some(kitsu),
+                ..AnimeIds::default()
+            },
+            season: Some(1),
+            part: Some(1),
+            year: None,
+            titles: vec![title.into()],
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used_and_expires() {
+        let cache = ResolutionCache::new(NonZeroUsize::new(2).unwrap());
+        cache.insert("a", resolution("A", 1));
+        cache.insert("b", resolution("B", 2));
+        assert_eq!(cache.get("a").unwrap().ids.kitsu, Some(1));
+
+        cache.insert("c", resolution("C", 3));
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert_eq!(cache.len(), 2);
+
+        let expiring = ResolutionCache::new(NonZeroUsize::new(2).unwrap()).with_ttl(Duration::ZERO);
+        expiring.insert("a", resolution("A", 1));
+        assert!(expiring.get("a").is_none());
+        assert!(expiring.is_empty());
+    }
+
+    #[test]
+    fn persists_entries_with_their_age() {
+        let path = std::env::temp_dir().join(format!(
+            "zantetsu-cache-test-{}-{}.json",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let cache = ResolutionCache::new(NonZeroUsize::new(4).unwrap());
+        cache.insert("Spy x Family", resolution("Spy x Family", 1));
+        cache.insert("Frieren", resolution("Sousou no Frieren", 2));
+        cache.save(&path).unwrap();
+
+        let loaded = ResolutionCache::load(&path, NonZeroUsize::new(1).unwrap()).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(
+            loaded.get("Frieren").unwrap().canonical_title,
+            "Sousou no Frieren"
+        );
+
+        let stale = ResolutionCache::load(&path, NonZeroUsize::new(4).unwrap())
+            .unwrap()
+            .with_ttl(Duration::ZERO);
+        assert!(stale.get("Spy x Family").is_none());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            ResolutionCache::load(&path, NonZeroUsize::new(4).unwrap()),
+            Err(MatcherError::InvalidCache(_))
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -27,6 +27,22 @@ pub enum MatcherError {
     #[error("remote GraphQL error: {0}")]
     GraphQl(String),
 
+    /// An anime-relations file contained a malformed rule.
+    #[error("invalid anime-relations rule: {0}")]
+    InvalidRelations(String),
+
+    /// A saved resolution cache could not be parsed.
+    #[error("invalid resolution cache: {0}")]
+    InvalidCache(String),
+
+    /// A resolution store backend failed.
+    #[error("resolution store error: {0}")]
+    Store(String),
+
+    /// A saved ANN index is corrupt or does not match the expected layout.
+    #[error("invalid index: {0}")]
+    InvalidIndex(String),
+
     /// The remote response was missing expected fields.
     #[error("invalid remote response: {0}")]
     InvalidResponse(String),
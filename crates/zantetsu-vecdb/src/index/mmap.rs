@@ -0,0 +1,479 @@
+//! Memory-mapped, read-only HNSW index.
+//!
+//! Layout (little-endian, sections 8-byte aligned):
+//!
+//! | Offset | Field                                                        |
+//! |--------|--------------------------------------------------------------|
+//! | 0      | magic `ZHNSWMAP`                                             |
+//! | 8      | `u32` version, dim, m, ef_construction, ef_search, count, entry, top level |
+//! | 40     | `u64` offsets of the vector, link and title sections         |
+//! | 64     | sections                                                     |
+//!
+//! Vectors are `count * dim` `f32`s. The link and title sections each start
+//! with `count + 1` `u64` offsets into the data that follows: `u32` words
+//! `[layers, len, ids..., len, ids...]` per node, and UTF-8 title bytes.
+
+use super::{
+    Graph, HnswIndex, HnswParams, IndexHit, MAX_LEVEL, NO_ENTRY, embed_title, search_graph,
+};
+use crate::error::{MatchResult, MatcherError};
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"ZHNSWMAP";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 64;
+/// Bytes touched per page when prefetching without `madvise`.
+#[cfg(not(unix))]
+const PAGE_SIZE: usize = 4096;
+
+impl HnswIndex {
+    /// Writes the index in the layout read by [`MmapHnswIndex`].
+    ///
+    /// The mapped layout stores full-precision vectors, so a
+    /// [quantized](Self::quantize) index is written with its vectors
+    /// reconstructed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the file cannot be written.
+    pub fn save_mmap(&self, path: impl AsRef<Path>) -> MatchResult<()> {
+        let count = self.nodes.len();
+        let mut link_offsets = Vec::with_capacity(count + 1);
+        let mut links = Vec::new();
+        let mut title_offsets = Vec::with_capacity(count + 1);
+        let mut titles = Vec::new();
+        for node in &self.nodes {
+            link_offsets.push(links.len() as u64);
+            links.push(node.neighbors.len() as u32);
+            for layer in &node.neighbors {
+                links.push(layer.len() as u32);
+                links.extend_from_slice(layer);
+            }
+            title_offsets.push(titles.len() as u64);
+            titles.extend_from_slice(node.title.as_bytes());
+        }
+        link_offsets.push(links.len() as u64);
+        title_offsets.push(titles.len() as u64);
+
+        let vectors_offset = HEADER_LEN;
+        let links_offset = align8(vectors_offset + count * self.dim * 4);
+        let titles_offset = align8(links_offset + (count + 1) * 8 + links.len() * 4);
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        for value in [
+            FORMAT_VERSION,
+            self.dim as u32,
+            self.params.m as u32,
+            self.params.ef_construction as u32,
+            self.params.ef_search as u32,
+            count as u32,
+            self.entry.unwrap_or(NO_ENTRY),
+            self.top_level() as u32,
+        ] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        for offset in [vectors_offset, links_offset, titles_offset] {
+            writer.write_all(&(offset as u64).to_le_bytes())?;
+        }
+
+        let mut written = HEADER_LEN;
+        for id in 0..count as u32 {
+            for component in self.decode(id) {
+                writer.write_all(&component.to_le_bytes())?;
+            }
+        }
+        written += count * self.dim * 4;
+        pad_to(&mut writer, &mut written, links_offset)?;
+        for offset in link_offsets {
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+        for word in &links {
+            writer.write_all(&word.to_le_bytes())?;
+        }
+        written += (count + 1) * 8 + links.len() * 4;
+        pad_to(&mut writer, &mut written, titles_offset)?;
+        for offset in title_offsets {
+            writer.write_all(&offset.to_le_bytes())?;
+        }
+        writer.write_all(&titles)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A read-only [`HnswIndex`] queried in place from a memory-mapped file.
+///
+/// Opening only validates the header, so start-up cost and resident memory
+/// do not grow with the catalog: vectors, links and titles are paged in by
+/// the OS as searches touch them, and can be paged out again under memory
+/// pressure. This suits embedded and mobile clients. Call
+/// [`prefetch`](Self::prefetch) to warm shards that are known to be hot.
+///
+/// The file must not be modified while it is mapped.
+///
+/// # Examples
+///
+/// ```rust
+/// use zantetsu_vecdb::{HnswIndex, MmapHnswIndex};
+///
+/// let path = std::env::temp_dir().join("zantetsu-doc-index.hnswmap");
+/// HnswIndex::build(["Sousou no Frieren", "Spy x Family"])
+///     .save_mmap(&path)
+///     .unwrap();
+///
+/// let index = MmapHnswIndex::open(&path).unwrap();
+/// let hits = index.search_title("spy family", 1);
+/// assert_eq!(index.title(hits[0].id), Some("Spy x Family"));
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct MmapHnswIndex {
+    mmap: Mmap,
+    params: HnswParams,
+    dim: usize,
+    count: usize,
+    entry: Option<u32>,
+    top_level: usize,
+    vectors: usize,
+    links: usize,
+    titles: usize,
+}
+
+impl MmapHnswIndex {
+    /// Number of consecutive node ids in a [`prefetch`](Self::prefetch)
+    /// shard.
+    pub const SHARD_SIZE: usize = 4096;
+
+    /// Maps an index written by [`HnswIndex::save_mmap`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the file cannot be opened or mapped,
+    /// and [`MatcherError::InvalidIndex`] if its header or section table is
+    /// invalid.
+    pub fn open(path: impl AsRef<Path>) -> MatchResult<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only and the type documents that the
+        // file must not change while mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        #[cfg(unix)]
+        mmap.advise(memmap2::Advice::Random)?;
+
+        let invalid = |message: &str| MatcherError::InvalidIndex(message.into());
+        if mmap.len() < HEADER_LEN {
+            return Err(invalid("file is truncated"));
+        }
+        if &mmap[..8] != MAGIC {
+            return Err(invalid("not a zantetsu mmap index file"));
+        }
+        let u32_at = |offset: usize| read_u32(&mmap, offset) as usize;
+        if u32_at(8) != FORMAT_VERSION as usize {
+            return Err(MatcherError::InvalidIndex(format!(
+                "unsupported format version {}",
+                u32_at(8)
+            )));
+        }
+        let (dim, count, top_level) = (u32_at(12), u32_at(28), u32_at(36));
+        let entry = read_u32(&mmap, 32);
+        let [vectors, links, titles] = [40, 48, 56].map(|offset| read_u64(&mmap, offset) as usize);
+
+        let entry = match entry {
+            NO_ENTRY if count == 0 => None,
+            entry if (entry as usize) < count => Some(entry),
+            _ => return Err(invalid("entry point out of range")),
+        };
+        if top_level > MAX_LEVEL {
+            return Err(invalid("too many layers"));
+        }
+        let fits = |start: usize, len: Option<usize>| {
+            len.and_then(|len| start.checked_add(len))
+                .is_some_and(|end| start >= HEADER_LEN && start % 8 == 0 && end <= mmap.len())
+        };
+        let table_len = (count + 1) * 8;
+        if !fits(
+            vectors,
+            count.checked_mul(dim).and_then(|n| n.checked_mul(4)),
+        ) || !fits(links, Some(table_len))
+            || !fits(titles, Some(table_len))
+        {
+            return Err(invalid("section out of bounds"));
+        }
+        let link_words = read_u64(&mmap, links + count * 8) as usize;
+        let title_bytes = read_u64(&mmap, titles + count * 8) as usize;
+        if !fits(links + table_len, link_words.checked_mul(4))
+            || !fits(titles + table_len, Some(title_bytes))
+        {
+            return Err(invalid("section out of bounds"));
+        }
+
+        Ok(Self {
+            params: HnswParams {
+                m: u32_at(16).max(2),
+                ef_construction: u32_at(20).max(1),
+                ef_search: u32_at(24).max(1),
+            },
+            mmap,
+            dim,
+            count,
+            entry,
+            top_level,
+            vectors,
+            links,
+            titles,
+        })
+    }
+
+    /// Returns up to `k` nearest titles to `vector`, most similar first.
+    ///
+    /// A vector of the wrong dimension yields no hits.
+    #[must_use]
+    pub fn search(&self, vector: &[f32], k: usize) -> Vec<IndexHit> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if k == 0 || vector.len() != self.dim {
+            return Vec::new();
+        }
+        search_graph(
+            self,
+            entry,
+            self.top_level,
+            self.params.ef_search,
+            vector,
+            k,
+        )
+    }
+
+    /// Embeds `title` and returns up to `k` nearest titles.
+    #[must_use]
+    pub fn search_title(&self, title: &str, k: usize) -> Vec<IndexHit> {
+        self.search(&embed_title(title), k)
+    }
+
+    /// The title stored under `id`, or `None` if the id is out of range or
+    /// the stored bytes are not UTF-8.
+    #[must_use]
+    pub fn title(&self, id: usize) -> Option<&str> {
+        let range = self.item_range(self.titles, id)?;
+        std::str::from_utf8(self.mmap.get(range)?).ok()
+    }
+
+    /// Number of indexed titles.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if nothing has been indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Vector dimension the index was built for.
+    #[must_use]
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// The parameters the index was built with.
+    #[must_use]
+    pub fn params(&self) -> HnswParams {
+        self.params
+    }
+
+    /// Number of [`SHARD_SIZE`](Self::SHARD_SIZE) shards.
+    #[must_use]
+    pub fn shard_count(&self) -> usize {
+        self.count.div_ceil(Self::SHARD_SIZE)
+    }
+
+    /// Asks the OS to page in the vectors, links and titles of `shard` ahead
+    /// of use. Shards past [`shard_count`](Self::shard_count) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the OS rejects the request.
+    pub fn prefetch(&self, shard: usize) -> MatchResult<()> {
+        let first = shard.saturating_mul(Self::SHARD_SIZE);
+        if first >= self.count {
+            return Ok(());
+        }
+        let last = (first + Self::SHARD_SIZE).min(self.count);
+
+        let vector_bytes = self.dim * 4;
+        let ranges = [
+            self.vectors + first * vector_bytes..self.vectors + last * vector_bytes,
+            self.data_range(self.links, first, last, 4),
+            self.data_range(self.titles, first, last, 1),
+        ];
+        for range in ranges.into_iter().filter(|range| !range.is_empty()) {
+            self.will_need(range)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn will_need(&self, range: Range<usize>) -> MatchResult<()> {
+        self.mmap
+            .advise_range(memmap2::Advice::WillNeed, range.start, range.len())?;
+        Ok(())
+    }
+
+    /// Without `madvise`, touch one byte per page instead.
+    #[cfg(not(unix))]
+    fn will_need(&self, range: Range<usize>) -> MatchResult<()> {
+        let touched = self.mmap[range]
+            .iter()
+            .step_by(PAGE_SIZE)
+            .fold(0u8, |acc, &byte| acc ^ byte);
+        std::hint::black_box(touched);
+        Ok(())
+    }
+
+    /// Byte range of node `id`'s data in the byte-addressed section at
+    /// `section`.
+    fn item_range(&self, section: usize, id: usize) -> Option<Range<usize>> {
+        (id < self.count).then(|| self.data_range(section, id, id + 1, 1))
+    }
+
+    /// Byte range covering nodes `first..last` in an offset-table section.
+    fn data_range(
+        &self,
+        section: usize,
+        first: usize,
+        last: usize,
+        item_len: usize,
+    ) -> Range<usize> {
+        let data = section + (self.count + 1) * 8;
+        let start = read_u64(&self.mmap, section + first * 8) as usize;
+        let end = read_u64(&self.mmap, section + last * 8) as usize;
+        let start = data.saturating_add(start.saturating_mul(item_len));
+        let end = data.saturating_add(end.saturating_mul(item_len));
+        start.min(self.mmap.len())..end.clamp(start.min(self.mmap.len()), self.mmap.len())
+    }
+}
+
+impl Graph for MmapHnswIndex {
+    fn similarity(&self, query: &[f32], id: u32) -> f32 {
+        let start = self.vectors + id as usize * self.dim * 4;
+        self.mmap[start..start + self.dim * 4]
+            .chunks_exact(4)
+            .zip(query)
+            .map(|(bytes, q)| f32::from_le_bytes(bytes.try_into().unwrap()) * q)
+            .sum()
+    }
+
+    /// Links that point outside the index are skipped rather than trusted.
+    fn neighbors(&self, id: u32, layer: usize) -> impl Iterator<Item = u32> + '_ {
+        let range = self.data_range(self.links, id as usize, id as usize + 1, 4);
+        let words = self.mmap[range]
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+
+        // Skip the layer count and every lower layer.
+        let mut words = words.skip(1);
+        for _ in 0..layer {
+            let len = words.next().unwrap_or(0) as usize;
+            if len > 0 {
+                words.nth(len - 1);
+            }
+        }
+        let len = words.next().unwrap_or(0) as usize;
+        let count = self.count;
+        words
+            .take(len)
+            .filter(move |&neighbor| (neighbor as usize) < count)
+    }
+}
+
+fn align8(offset: usize) -> usize {
+    offset.next_multiple_of(8)
+}
+
+fn pad_to(writer: &mut impl Write, written: &mut usize, offset: usize) -> std::io::Result<()> {
+    writer.write_all(&[0; 8][..offset - *written])?;
+    *written = offset;
+    Ok(())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "zantetsu-mmap-test-{}-{}.hnswmap",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn mapped_index_matches_in_memory_search() {
+        let titles: Vec<String> = (0..300).map(|i| format!("Anime Title {i}")).collect();
+        let index = HnswIndex::build(titles.iter().map(String::as_str));
+        let path = temp_path();
+        index.save_mmap(&path).unwrap();
+
+        let mapped = MmapHnswIndex::open(&path).unwrap();
+        assert_eq!(mapped.len(), index.len());
+        assert_eq!(mapped.params(), index.params());
+        assert_eq!(mapped.shard_count(), 1);
+        mapped.prefetch(0).unwrap();
+        mapped.prefetch(7).unwrap();
+        for query in ["anime title 42", "title 7", "frieren"] {
+            assert_eq!(mapped.search_title(query, 5), index.search_title(query, 5));
+        }
+        assert_eq!(mapped.title(42), Some("Anime Title 42"));
+        assert_eq!(mapped.title(300), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let path = temp_path();
+        HnswIndex::build(["Spy x Family"]).save_mmap(&path).unwrap();
+        let mut bytes = fs::read(&path).unwrap();
+
+        bytes.truncate(HEADER_LEN + 4);
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            MmapHnswIndex::open(&path),
+            Err(MatcherError::InvalidIndex(_))
+        ));
+
+        fs::write(&path, b"ZHNSW not the mmap layout").unwrap();
+        assert!(matches!(
+            MmapHnswIndex::open(&path),
+            Err(MatcherError::InvalidIndex(_))
+        ));
+
+        let empty = HnswIndex::new(8, HnswParams::default());
+        empty.save_mmap(&path).unwrap();
+        let mapped = MmapHnswIndex::open(&path).unwrap();
+        assert!(mapped.is_empty());
+        assert!(mapped.search(&[1.0; 8], 3).is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
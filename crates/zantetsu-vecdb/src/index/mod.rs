@@ -0,0 +1,1033 @@
+//! Approximate nearest-neighbour search over title embeddings.
+//!
+//! [`HnswIndex`] is a Hierarchical Navigable Small World graph over
+//! [`embed_title`] vectors. Build it once from a catalog, [`save`] it next to
+//! the dump, and [`load`] it at startup instead of re-embedding every title.
+//!
+//! [`quantize`] compresses the stored vectors 4x or more for shipping the
+//! index inside an app. On low-memory devices, [`save_mmap`] writes a layout that
+//! [`MmapHnswIndex`] queries straight from a memory-mapped file, paging
+//! vectors in only as the search touches them.
+//!
+//! [`save`]: HnswIndex::save
+//! [`load`]: HnswIndex::load
+//! [`save_mmap`]: HnswIndex::save_mmap
+//! [`quantize`]: HnswIndex::quantize
+
+mod mmap;
+mod quantize;
+
+pub use mmap::MmapHnswIndex;
+pub use quantize::Quantization;
+use quantize::{CENTROIDS, Codebook, StoredVector};
+
+use crate::error::{MatchResult, MatcherError};
+use crate::matcher::normalize_title;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Dimension of the vectors produced by [`embed_title`].
+pub const EMBEDDING_DIM: usize = 256;
+
+const MAGIC: &[u8; 5] = b"ZHNSW";
+const FORMAT_VERSION: u8 = 1;
+/// Format of quantized indexes; unquantized ones are still written as
+/// version 1.
+const QUANTIZED_FORMAT_VERSION: u8 = 2;
+const NO_ENTRY: u32 = u32::MAX;
+/// Upper bound on graph layers; with `m >= 2` a node reaches it with
+/// probability below 2^-16.
+const MAX_LEVEL: usize = 16;
+
+/// Embeds a title as a unit vector of hashed character trigrams and words.
+///
+/// Titles are normalized like the matcher does, so `SPY×FAMILY` and
+/// `spy x family` land close together. The embedding needs no model and is
+/// stable across runs, which keeps saved indexes valid.
+#[must_use]
+pub fn embed_title(title: &str) -> Vec<f32> {
+    let normalized = normalize_title(title);
+    let mut vector = vec![0.0_f32; EMBEDDING_DIM];
+    let mut add = |feature: &[u8], weight: f32| {
+        let hash = fnv1a(feature);
+        let bucket = (hash % EMBEDDING_DIM as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign * weight;
+    };
+
+    let padded: Vec<char> = format!(" {normalized} ").chars().collect();
+    let mut buffer = [0u8; 12];
+    for window in padded.windows(3) {
+        let mut len = 0;
+        for ch in window {
+            len += ch.encode_utf8(&mut buffer[len..]).len();
+        }
+        add(&buffer[..len], 1.0);
+    }
+    for word in normalized.split_whitespace() {
+        add(word.as_bytes(), 2.0);
+    }
+
+    normalize(&mut vector);
+    vector
+}
+
+/// Tuning knobs for [`HnswIndex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HnswParams {
+    /// Links per node on the upper layers; the bottom layer keeps twice as
+    /// many.
+    pub m: usize,
+    /// Candidate list size while inserting; larger builds a better graph
+    /// more slowly.
+    pub ef_construction: usize,
+    /// Minimum candidate list size while searching.
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+        }
+    }
+}
+
+/// A search hit: the node id and its cosine similarity to the query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexHit {
+    /// Position of the title in build order.
+    pub id: usize,
+    /// Cosine similarity in `[-1.0, 1.0]`.
+    pub score: f32,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    title: String,
+    vector: StoredVector,
+    /// Neighbour ids per layer, bottom layer first.
+    neighbors: Vec<Vec<u32>>,
+}
+
+/// HNSW approximate nearest-neighbour index over title embeddings.
+///
+/// # Examples
+///
+/// ```rust
+/// use zantetsu_vecdb::{HnswIndex, embed_title};
+///
+/// let index = HnswIndex::build(["Sousou no Frieren", "Spy x Family", "Jujutsu Kaisen"]);
+/// let hits = index.search(&embed_title("spy family"), 1);
+/// assert_eq!(index.title(hits[0].id), Some("Spy x Family"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HnswIndex {
+    params: HnswParams,
+    dim: usize,
+    nodes: Vec<Node>,
+    entry: Option<u32>,
+    quantization: Option<Quantization>,
+    /// Centroids for [`Quantization::Product`].
+    codebook: Option<Codebook>,
+}
+
+impl HnswIndex {
+    /// Creates an empty index for vectors of `dim` components.
+    #[must_use]
+    pub fn new(dim: usize, params: HnswParams) -> Self {
+        Self {
+            params: HnswParams {
+                m: params.m.max(2),
+                ef_construction: params.ef_construction.max(1),
+                ef_search: params.ef_search.max(1),
+            },
+            dim,
+            nodes: Vec::new(),
+            entry: None,
+            quantization: None,
+            codebook: None,
+        }
+    }
+
+    /// Embeds and indexes `titles` with the default parameters.
+    #[must_use]
+    pub fn build<I, S>(titles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::build_with(titles, HnswParams::default())
+    }
+
+    /// Embeds and indexes `titles` with custom parameters.
+    #[must_use]
+    pub fn build_with<I, S>(titles: I, params: HnswParams) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut index = Self::new(EMBEDDING_DIM, params);
+        for title in titles {
+            let title = title.into();
+            let vector = embed_title(&title);
+            index
+                .insert(title, vector)
+                .expect("embeddings always have EMBEDDING_DIM components");
+        }
+        index
+    }
+
+    /// Adds a title with a precomputed vector, returning its id.
+    ///
+    /// The vector is normalized to unit length before insertion, and
+    /// compressed if the index is [quantized](Self::quantize).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::InvalidIndex`] if the vector has the wrong
+    /// number of components.
+    pub fn insert(&mut self, title: impl Into<String>, mut vector: Vec<f32>) -> MatchResult<usize> {
+        if vector.len() != self.dim {
+            return Err(MatcherError::InvalidIndex(format!(
+                "vector has {} components, index expects {}",
+                vector.len(),
+                self.dim
+            )));
+        }
+        normalize(&mut vector);
+
+        let id = self.nodes.len() as u32;
+        let level = self.random_level(id);
+        self.nodes.push(Node {
+            title: title.into(),
+            vector: self.encode(&vector),
+            neighbors: vec![Vec::new(); level + 1],
+        });
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(id);
+            return Ok(id as usize);
+        };
+
+        let query = vector;
+        let top = self.top_level();
+        let mut nearest = entry;
+        for layer in (level + 1..=top).rev() {
+            nearest = greedy_closest(self, &query, nearest, layer);
+        }
+
+        let mut entry_points = vec![nearest];
+        for layer in (0..=level.min(top)).rev() {
+            let candidates = search_layer(
+                self,
+                &query,
+                &entry_points,
+                self.params.ef_construction,
+                layer,
+            );
+            let limit = self.max_links(layer);
+            let selected: Vec<u32> = candidates.iter().take(limit).map(|c| c.id).collect();
+
+            for &neighbor in &selected {
+                self.link(neighbor, id, layer);
+            }
+            self.nodes[id as usize].neighbors[layer] = selected;
+            entry_points = candidates.into_iter().map(|c| c.id).collect();
+        }
+
+        if level > top {
+            self.entry = Some(id);
+        }
+        Ok(id as usize)
+    }
+
+    /// Returns up to `k` nearest titles to `vector`, most similar first.
+    ///
+    /// A vector of the wrong dimension yields no hits.
+    #[must_use]
+    pub fn search(&self, vector: &[f32], k: usize) -> Vec<IndexHit> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if k == 0 || vector.len() != self.dim {
+            return Vec::new();
+        }
+
+        search_graph(
+            self,
+            entry,
+            self.top_level(),
+            self.params.ef_search,
+            vector,
+            k,
+        )
+    }
+
+    /// Embeds `title` and returns up to `k` nearest titles.
+    #[must_use]
+    pub fn search_title(&self, title: &str, k: usize) -> Vec<IndexHit> {
+        self.search(&embed_title(title), k)
+    }
+
+    /// The title stored under `id`.
+    #[must_use]
+    pub fn title(&self, id: usize) -> Option<&str> {
+        self.nodes.get(id).map(|node| node.title.as_str())
+    }
+
+    /// Number of indexed titles.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if nothing has been indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Vector dimension the index was built for.
+    #[must_use]
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// The parameters the index was built with.
+    #[must_use]
+    pub fn params(&self) -> HnswParams {
+        self.params
+    }
+
+    /// Compresses the stored vectors.
+    ///
+    /// The graph is kept, so search quality depends on how well the
+    /// compressed vectors preserve similarities: [`Quantization::Int8`] is
+    /// close to lossless, while [`Quantization::Product`] trades recall for
+    /// size. Titles inserted afterwards are compressed the same way.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::InvalidIndex`] if the index is already
+    /// quantized, or if product quantization is requested on an empty index
+    /// or with a subspace count that does not divide the dimension.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zantetsu_vecdb::{HnswIndex, Quantization};
+    ///
+    /// let mut index = HnswIndex::build(["Sousou no Frieren", "Spy x Family"]);
+    /// index.quantize(Quantization::Int8).unwrap();
+    /// let hits = index.search_title("spy family", 1);
+    /// assert_eq!(index.title(hits[0].id), Some("Spy x Family"));
+    /// ```
+    pub fn quantize(&mut self, quantization: Quantization) -> MatchResult<()> {
+        if self.quantization.is_some() {
+            return Err(MatcherError::InvalidIndex(
+                "index is already quantized".into(),
+            ));
+        }
+        if let Quantization::Product { subspaces } = quantization {
+            if subspaces == 0 || self.dim % subspaces != 0 {
+                return Err(MatcherError::InvalidIndex(format!(
+                    "{subspaces} subspaces do not divide dimension {}",
+                    self.dim
+                )));
+            }
+            if self.nodes.is_empty() {
+                return Err(MatcherError::InvalidIndex(
+                    "product quantization needs indexed vectors to train on".into(),
+                ));
+            }
+            let vectors: Vec<Vec<f32>> = (0..self.nodes.len() as u32)
+                .map(|id| self.decode(id))
+                .collect();
+            self.codebook = Some(Codebook::train(&vectors, self.dim, subspaces));
+        }
+
+        self.quantization = Some(quantization);
+        for id in 0..self.nodes.len() {
+            let vector = self.decode(id as u32);
+            self.nodes[id].vector = self.encode(&vector);
+        }
+        Ok(())
+    }
+
+    /// How the stored vectors are compressed, if at all.
+    #[must_use]
+    pub fn quantization(&self) -> Option<Quantization> {
+        self.quantization
+    }
+
+    /// Writes the index, vectors and graph included, to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> MatchResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        let version = if self.quantization.is_some() {
+            QUANTIZED_FORMAT_VERSION
+        } else {
+            FORMAT_VERSION
+        };
+        writer.write_all(&[version])?;
+        for value in [
+            self.dim,
+            self.params.m,
+            self.params.ef_construction,
+            self.params.ef_search,
+            self.nodes.len(),
+        ] {
+            write_u32(&mut writer, value as u32)?;
+        }
+        write_u32(&mut writer, self.entry.unwrap_or(NO_ENTRY))?;
+        match self.quantization {
+            None => {}
+            Some(Quantization::Int8) => writer.write_all(&[1])?,
+            Some(Quantization::Product { subspaces }) => {
+                writer.write_all(&[2])?;
+                write_u32(&mut writer, subspaces as u32)?;
+                let codebook = self
+                    .codebook
+                    .as_ref()
+                    .expect("product index has a codebook");
+                write_f32s(&mut writer, &codebook.centroids)?;
+            }
+        }
+
+        for node in &self.nodes {
+            write_u32(&mut writer, node.title.len() as u32)?;
+            writer.write_all(node.title.as_bytes())?;
+            match &node.vector {
+                StoredVector::Full(vector) => write_f32s(&mut writer, vector)?,
+                StoredVector::Int8 { scale, values } => {
+                    write_f32s(&mut writer, &[*scale])?;
+                    let bytes: Vec<u8> = values.iter().map(|&v| v as u8).collect();
+                    writer.write_all(&bytes)?;
+                }
+                StoredVector::Product(codes) => writer.write_all(codes)?,
+            }
+            write_u32(&mut writer, node.neighbors.len() as u32)?;
+            for layer in &node.neighbors {
+                write_u32(&mut writer, layer.len() as u32)?;
+                for &neighbor in layer {
+                    write_u32(&mut writer, neighbor)?;
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads an index written by [`save`](Self::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Io`] if the file cannot be read and
+    /// [`MatcherError::InvalidIndex`] if it is not a valid index file.
+    pub fn load(path: impl AsRef<Path>) -> MatchResult<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let truncated = |_| MatcherError::InvalidIndex("file is truncated".into());
+
+        let mut magic = [0u8; 5];
+        reader.read_exact(&mut magic).map_err(truncated)?;
+        if &magic != MAGIC {
+            return Err(MatcherError::InvalidIndex(
+                "not a zantetsu index file".into(),
+            ));
+        }
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version).map_err(truncated)?;
+        if version[0] != FORMAT_VERSION && version[0] != QUANTIZED_FORMAT_VERSION {
+            return Err(MatcherError::InvalidIndex(format!(
+                "unsupported format version {}",
+                version[0]
+            )));
+        }
+
+        let mut header = [0u32; 6];
+        for value in &mut header {
+            *value = read_u32(&mut reader).map_err(truncated)?;
+        }
+        let [dim, m, ef_construction, ef_search, count, entry] = header;
+        let (dim, count) = (dim as usize, count as usize);
+
+        let mut quantization = None;
+        let mut codebook = None;
+        if version[0] == QUANTIZED_FORMAT_VERSION {
+            let mut tag = [0u8; 1];
+            reader.read_exact(&mut tag).map_err(truncated)?;
+            quantization = match tag[0] {
+                1 => Some(Quantization::Int8),
+                2 => {
+                    let subspaces = read_u32(&mut reader).map_err(truncated)? as usize;
+                    if subspaces == 0 || dim % subspaces != 0 {
+                        return Err(MatcherError::InvalidIndex(format!(
+                            "{subspaces} subspaces do not divide dimension {dim}"
+                        )));
+                    }
+                    let sub_dim = dim / subspaces;
+                    let centroids = read_f32s(&mut reader, subspaces * CENTROIDS * sub_dim)
+                        .map_err(truncated)?;
+                    codebook = Some(Codebook {
+                        subspaces,
+                        sub_dim,
+                        centroids,
+                    });
+                    Some(Quantization::Product { subspaces })
+                }
+                tag => {
+                    return Err(MatcherError::InvalidIndex(format!(
+                        "unknown quantization {tag}"
+                    )));
+                }
+            };
+        }
+
+        let mut nodes = Vec::with_capacity(count.min(1 << 20));
+        for _ in 0..count {
+            let title_len = read_u32(&mut reader).map_err(truncated)? as usize;
+            let mut title = vec![0u8; title_len];
+            reader.read_exact(&mut title).map_err(truncated)?;
+            let title = String::from_utf8(title)
+                .map_err(|_| MatcherError::InvalidIndex("title is not UTF-8".into()))?;
+
+            let vector = match (quantization, &codebook) {
+                (Some(Quantization::Int8), _) => {
+                    let scale = read_f32s(&mut reader, 1).map_err(truncated)?[0];
+                    let mut bytes = vec![0u8; dim];
+                    reader.read_exact(&mut bytes).map_err(truncated)?;
+                    let values = bytes.into_iter().map(|b| b as i8).collect();
+                    StoredVector::Int8 { scale, values }
+                }
+                (_, Some(codebook)) => {
+                    let mut codes = vec![0u8; codebook.subspaces];
+                    reader.read_exact(&mut codes).map_err(truncated)?;
+                    StoredVector::Product(codes)
+                }
+                (_, None) => StoredVector::Full(read_f32s(&mut reader, dim).map_err(truncated)?),
+            };
+
+            let layers = read_u32(&mut reader).map_err(truncated)? as usize;
+            if layers == 0 || layers > MAX_LEVEL + 1 {
+                return Err(MatcherError::InvalidIndex(format!(
+                    "node has {layers} layers"
+                )));
+            }
+            let mut neighbors = Vec::with_capacity(layers);
+            for _ in 0..layers {
+                let len = read_u32(&mut reader).map_err(truncated)? as usize;
+                let mut layer = Vec::with_capacity(len.min(1024));
+                for _ in 0..len {
+                    let neighbor = read_u32(&mut reader).map_err(truncated)?;
+                    if neighbor as usize >= count {
+                        return Err(MatcherError::InvalidIndex(format!(
+                            "neighbour {neighbor} out of range"
+                        )));
+                    }
+                    layer.push(neighbor);
+                }
+                neighbors.push(layer);
+            }
+            nodes.push(Node {
+                title,
+                vector,
+                neighbors,
+            });
+        }
+
+        let entry = match entry {
+            NO_ENTRY if count == 0 => None,
+            entry if (entry as usize) < count => Some(entry),
+            _ => {
+                return Err(MatcherError::InvalidIndex(
+                    "entry point out of range".into(),
+                ));
+            }
+        };
+
+        let mut index = Self::new(
+            dim,
+            HnswParams {
+                m: m as usize,
+                ef_construction: ef_construction as usize,
+                ef_search: ef_search as usize,
+            },
+        );
+        index.nodes = nodes;
+        index.entry = entry;
+        index.quantization = quantization;
+        index.codebook = codebook;
+        Ok(index)
+    }
+
+    fn top_level(&self) -> usize {
+        self.entry
+            .map_or(0, |entry| self.nodes[entry as usize].neighbors.len() - 1)
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.params.m * 2
+        } else {
+            self.params.m
+        }
+    }
+
+    /// Draws a node level from the HNSW geometric distribution, hashing the
+    /// id so that rebuilding the same catalog yields the same graph.
+    fn random_level(&self, id: u32) -> usize {
+        let uniform = (splitmix64(u64::from(id)) >> 11) as f64 / (1u64 << 53) as f64;
+        let level_mult = 1.0 / (self.params.m as f64).ln();
+        let level = (-(1.0 - uniform).ln() * level_mult).floor() as usize;
+        level.min(MAX_LEVEL)
+    }
+
+    /// Stores `vector` in the index's current format.
+    fn encode(&self, vector: &[f32]) -> StoredVector {
+        match (self.quantization, &self.codebook) {
+            (Some(Quantization::Int8), _) => StoredVector::int8(vector),
+            (Some(Quantization::Product { .. }), Some(codebook)) => {
+                StoredVector::Product(codebook.encode(vector))
+            }
+            _ => StoredVector::Full(vector.to_vec()),
+        }
+    }
+
+    /// Node `id`'s vector, reconstructed if it is stored compressed.
+    fn decode(&self, id: u32) -> Vec<f32> {
+        match &self.nodes[id as usize].vector {
+            StoredVector::Full(vector) => vector.clone(),
+            StoredVector::Int8 { scale, values } => {
+                values.iter().map(|&v| scale * f32::from(v)).collect()
+            }
+            StoredVector::Product(codes) => self
+                .codebook
+                .as_ref()
+                .map_or_else(|| vec![0.0; self.dim], |codebook| codebook.decode(codes)),
+        }
+    }
+
+    fn layer(&self, id: u32, layer: usize) -> &[u32] {
+        self.nodes[id as usize]
+            .neighbors
+            .get(layer)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds `to` to `from`'s links on `layer`, keeping only the closest
+    /// links when the list overflows.
+    fn link(&mut self, from: u32, to: u32, layer: usize) {
+        let limit = self.max_links(layer);
+        let mut links = std::mem::take(&mut self.nodes[from as usize].neighbors[layer]);
+        links.push(to);
+        if links.len() > limit {
+            let base = self.decode(from);
+            links.sort_by(|&a, &b| {
+                self.similarity(&base, b)
+                    .total_cmp(&self.similarity(&base, a))
+            });
+            links.truncate(limit);
+        }
+        self.nodes[from as usize].neighbors[layer] = links;
+    }
+}
+
+impl Graph for HnswIndex {
+    fn similarity(&self, query: &[f32], id: u32) -> f32 {
+        match &self.nodes[id as usize].vector {
+            StoredVector::Full(vector) => dot(query, vector),
+            StoredVector::Int8 { scale, values } => {
+                scale
+                    * query
+                        .iter()
+                        .zip(values)
+                        .map(|(q, &v)| q * f32::from(v))
+                        .sum::<f32>()
+            }
+            StoredVector::Product(codes) => self
+                .codebook
+                .as_ref()
+                .map_or(0.0, |codebook| codebook.similarity(query, codes)),
+        }
+    }
+
+    fn neighbors(&self, id: u32, layer: usize) -> impl Iterator<Item = u32> + '_ {
+        self.layer(id, layer).iter().copied()
+    }
+}
+
+/// Read access to an HNSW graph, shared by the in-memory and mapped
+/// indexes.
+trait Graph {
+    /// Dot product of `query` with node `id`'s unit vector.
+    fn similarity(&self, query: &[f32], id: u32) -> f32;
+
+    /// Node `id`'s links on `layer`.
+    fn neighbors(&self, id: u32, layer: usize) -> impl Iterator<Item = u32> + '_;
+}
+
+/// Descends from `entry` through the upper layers, then beam-searches the
+/// bottom layer for the `k` nearest nodes to `vector`.
+fn search_graph(
+    graph: &impl Graph,
+    entry: u32,
+    top_level: usize,
+    ef_search: usize,
+    vector: &[f32],
+    k: usize,
+) -> Vec<IndexHit> {
+    let mut query = vector.to_vec();
+    normalize(&mut query);
+
+    let mut nearest = entry;
+    for layer in (1..=top_level).rev() {
+        nearest = greedy_closest(graph, &query, nearest, layer);
+    }
+    let ef = ef_search.max(k);
+    search_layer(graph, &query, &[nearest], ef, 0)
+        .into_iter()
+        .take(k)
+        .map(|candidate| IndexHit {
+            id: candidate.id as usize,
+            score: candidate.score,
+        })
+        .collect()
+}
+
+/// Walks `layer` towards `query` one best neighbour at a time.
+fn greedy_closest(graph: &impl Graph, query: &[f32], start: u32, layer: usize) -> u32 {
+    let mut current = start;
+    let mut best = graph.similarity(query, current);
+    loop {
+        let mut improved = false;
+        for neighbor in graph.neighbors(current, layer) {
+            let score = graph.similarity(query, neighbor);
+            if score > best {
+                best = score;
+                current = neighbor;
+                improved = true;
+            }
+        }
+        if !improved {
+            return current;
+        }
+    }
+}
+
+/// Beam search on `layer`, returning up to `ef` candidates, most
+/// similar first.
+fn search_layer(
+    graph: &impl Graph,
+    query: &[f32],
+    entry_points: &[u32],
+    ef: usize,
+    layer: usize,
+) -> Vec<Candidate> {
+    let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+    let mut frontier: BinaryHeap<Candidate> = BinaryHeap::new();
+    let mut results: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+    for &id in entry_points {
+        let candidate = Candidate {
+            score: graph.similarity(query, id),
+            id,
+        };
+        frontier.push(candidate);
+        results.push(Reverse(candidate));
+        if results.len() > ef {
+            results.pop();
+        }
+    }
+
+    while let Some(candidate) = frontier.pop() {
+        let worst = results.peek().map_or(f32::NEG_INFINITY, |r| r.0.score);
+        if candidate.score < worst && results.len() >= ef {
+            break;
+        }
+        for neighbor in graph.neighbors(candidate.id, layer) {
+            if !visited.insert(neighbor) {
+                continue;
+            }
+            let score = graph.similarity(query, neighbor);
+            let worst = results.peek().map_or(f32::NEG_INFINITY, |r| r.0.score);
+            if results.len() < ef || score > worst {
+                let next = Candidate {
+                    score,
+                    id: neighbor,
+                };
+                frontier.push(next);
+                results.push(Reverse(next));
+                if results.len() > ef {
+                    results.pop();
+                }
+            }
+        }
+    }
+
+    let mut results: Vec<Candidate> = results.into_iter().map(|r| r.0).collect();
+    results.sort_by(|a, b| b.cmp(a));
+    results
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    score: f32,
+    id: u32,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.id.cmp(&self.id))
+    }
+}
+
+pub(crate) fn dot(left: &[f32], right: &[f32]) -> f32 {
+    left.iter().zip(right).map(|(a, b)| a * b).sum()
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = dot(vector, vector).sqrt();
+    if norm > 0.0 {
+        for component in vector {
+            *component /= norm;
+        }
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn write_u32(writer: &mut impl Write, value: u32) -> std::io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn read_u32(reader: &mut impl Read) -> std::io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn write_f32s(writer: &mut impl Write, values: &[f32]) -> std::io::Result<()> {
+    for value in values {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_f32s(reader: &mut impl Read, len: usize) -> std::io::Result<Vec<f32>> {
+    let mut values = Vec::with_capacity(len.min(1 << 20));
+    for _ in 0..len {
+        let mut bytes = [0u8; 4];
+        reader.read_exact(&mut bytes)?;
+        values.push(f32::from_le_bytes(bytes));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn random_vector(seed: u64, dim: usize) -> Vec<f32> {
+        (0..dim as u64)
+            .map(|i| (splitmix64(seed * 1_000 + i) >> 40) as f32 / (1u64 << 24) as f32 - 0.5)
+            .collect()
+    }
+
+    fn brute_force(vectors: &[Vec<f32>], query: &[f32]) -> usize {
+        let mut query = query.to_vec();
+        normalize(&mut query);
+        (0..vectors.len())
+            .max_by(|&a, &b| {
+                let (mut va, mut vb) = (vectors[a].clone(), vectors[b].clone());
+                normalize(&mut va);
+                normalize(&mut vb);
+                dot(&query, &va).total_cmp(&dot(&query, &vb))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_similar_titles() {
+        let index = HnswIndex::build([
+            "Sousou no Frieren",
+            "Spy x Family",
+            "Jujutsu Kaisen",
+            "Kimetsu no Yaiba",
+            "Shingeki no Kyojin",
+        ]);
+        assert_eq!(index.len(), 5);
+
+        let hits = index.search_title("SPY×FAMILY", 2);
+        assert_eq!(index.title(hits[0].id), Some("Spy x Family"));
+        assert!(hits[0].score > hits[1].score);
+
+        let hits = index.search_title("Jujutsu Kaisen S2", 1);
+        assert_eq!(index.title(hits[0].id), Some("Jujutsu Kaisen"));
+    }
+
+    #[test]
+    fn approximate_search_recalls_exact_neighbours() {
+        let dim = 32;
+        let vectors: Vec<Vec<f32>> = (0..1_000).map(|i| random_vector(i, dim)).collect();
+        let mut index = HnswIndex::new(dim, HnswParams::default());
+        for (i, vector) in vectors.iter().enumerate() {
+            index.insert(format!("title {i}"), vector.clone()).unwrap();
+        }
+
+        let queries = 100;
+        let found = (0..queries)
+            .filter(|&q| {
+                let query = random_vector(10_000 + q, dim);
+                index.search(&query, 1)[0].id == brute_force(&vectors, &query)
+            })
+            .count();
+        assert!(found >= 95, "recall {found}/{queries}");
+    }
+
+    #[test]
+    fn quantized_vectors_keep_recall_and_shrink_the_file() {
+        let dim = 32;
+        let vectors: Vec<Vec<f32>> = (0..500).map(|i| random_vector(i, dim)).collect();
+        let mut full = HnswIndex::new(dim, HnswParams::default());
+        for (i, vector) in vectors.iter().enumerate() {
+            full.insert(format!("title {i}"), vector.clone()).unwrap();
+        }
+        let path = temp_index_path();
+        full.save(&path).unwrap();
+        let full_size = fs::metadata(&path).unwrap().len();
+
+        let queries: Vec<(Vec<f32>, usize)> = (0..100)
+            .map(|q| {
+                let query = random_vector(10_000 + q, dim);
+                let exact = brute_force(&vectors, &query);
+                (query, exact)
+            })
+            .collect();
+
+        // Bytes per stored vector, and fixed header overhead.
+        let codebook = 8 * CENTROIDS * (dim / 8) * 4;
+        for (quantization, min_recall, vector_bytes, overhead) in [
+            (Quantization::Int8, 90, 4 + dim, 1),
+            (
+                Quantization::Product { subspaces: 8 },
+                60,
+                8,
+                1 + 4 + codebook,
+            ),
+        ] {
+            let mut index = full.clone();
+            index.quantize(quantization).unwrap();
+            assert_eq!(index.quantization(), Some(quantization));
+
+            let found = queries
+                .iter()
+                .filter(|(query, exact)| index.search(query, 10).iter().any(|hit| hit.id == *exact))
+                .count();
+            assert!(found >= min_recall, "{quantization:?} recall {found}/100");
+
+            index.save(&path).unwrap();
+            let size = fs::metadata(&path).unwrap().len() as usize;
+            let expected = full_size as usize - vectors.len() * (dim * 4 - vector_bytes) + overhead;
+            assert_eq!(size, expected, "{quantization:?}");
+
+            let loaded = HnswIndex::load(&path).unwrap();
+            assert_eq!(loaded.quantization(), Some(quantization));
+            let query = random_vector(99, dim);
+            assert_eq!(loaded.search(&query, 5), index.search(&query, 5));
+        }
+
+        let mut index = full.clone();
+        assert!(
+            index
+                .quantize(Quantization::Product { subspaces: 5 })
+                .is_err()
+        );
+        index.quantize(Quantization::Int8).unwrap();
+        assert!(index.quantize(Quantization::Int8).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    fn temp_index_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "zantetsu-index-test-{}-{}.hnsw",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ))
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let index = HnswIndex::build(["Sousou no Frieren", "Spy x Family", "Jujutsu Kaisen"]);
+        let path = std::env::temp_dir().join(format!(
+            "zantetsu-index-test-{}-{}.hnsw",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        index.save(&path).unwrap();
+
+        let loaded = HnswIndex::load(&path).unwrap();
+        assert_eq!(loaded.len(), index.len());
+        assert_eq!(loaded.params(), index.params());
+        let query = embed_title("frieren");
+        assert_eq!(loaded.search(&query, 3), index.search(&query, 3));
+
+        fs::write(&path, b"not an index").unwrap();
+        assert!(matches!(
+            HnswIndex::load(&path),
+            Err(MatcherError::InvalidIndex(_))
+        ));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_vectors_of_the_wrong_dimension() {
+        let mut index = HnswIndex::new(4, HnswParams::default());
+        assert!(index.insert("x", vec![1.0; 3]).is_err());
+        assert!(index.search(&[1.0; 3], 1).is_empty());
+        assert!(index.search(&[1.0; 4], 1).is_empty());
+    }
+}
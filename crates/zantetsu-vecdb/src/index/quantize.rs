@@ -0,0 +1,172 @@
+//! Compressed vector storage for [`HnswIndex`](super::HnswIndex).
+
+use super::{dot, splitmix64};
+
+/// Centroids per product-quantization subspace; codes fit in one byte.
+pub(super) const CENTROIDS: usize = 256;
+/// At most this many vectors train the product quantizer.
+const TRAINING_SAMPLE: usize = 4096;
+const KMEANS_ITERATIONS: usize = 12;
+
+/// How [`HnswIndex`](super::HnswIndex) stores its vectors, trading recall
+/// for size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quantization {
+    /// One signed byte per component plus a per-vector scale: about 4x
+    /// smaller, with near-identical search results.
+    Int8,
+    /// Product quantization: each vector is split into `subspaces` equal
+    /// slices, each stored as a one-byte index into 256 centroids learned
+    /// from the indexed vectors. Vectors shrink by `4 * dim / subspaces`;
+    /// fewer subspaces are smaller and less precise. `subspaces` must
+    /// divide the index dimension.
+    Product {
+        /// Number of slices per vector.
+        subspaces: usize,
+    },
+}
+
+/// A vector as stored in a node.
+#[derive(Debug, Clone)]
+pub(super) enum StoredVector {
+    Full(Vec<f32>),
+    Int8 { scale: f32, values: Vec<i8> },
+    Product(Vec<u8>),
+}
+
+impl StoredVector {
+    pub(super) fn int8(vector: &[f32]) -> Self {
+        let max = vector.iter().fold(0.0_f32, |max, c| max.max(c.abs()));
+        let scale = max / 127.0;
+        let values = vector
+            .iter()
+            .map(|&c| {
+                if scale > 0.0 {
+                    (c / scale).round() as i8
+                } else {
+                    0
+                }
+            })
+            .collect();
+        Self::Int8 { scale, values }
+    }
+}
+
+/// Learned centroids for product quantization.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Codebook {
+    pub(super) subspaces: usize,
+    pub(super) sub_dim: usize,
+    /// `subspaces * CENTROIDS * sub_dim` components.
+    pub(super) centroids: Vec<f32>,
+}
+
+impl Codebook {
+    /// Runs k-means in each subspace over (a sample of) `vectors`.
+    pub(super) fn train(vectors: &[Vec<f32>], dim: usize, subspaces: usize) -> Self {
+        let sub_dim = dim / subspaces;
+        let step = vectors.len().div_ceil(TRAINING_SAMPLE).max(1);
+        let sample: Vec<&[f32]> = vectors.iter().step_by(step).map(Vec::as_slice).collect();
+
+        let mut centroids = Vec::with_capacity(subspaces * CENTROIDS * sub_dim);
+        for subspace in 0..subspaces {
+            let slices: Vec<&[f32]> = sample
+                .iter()
+                .map(|vector| &vector[subspace * sub_dim..(subspace + 1) * sub_dim])
+                .collect();
+            centroids.extend(kmeans(&slices, sub_dim));
+        }
+        Self {
+            subspaces,
+            sub_dim,
+            centroids,
+        }
+    }
+
+    pub(super) fn encode(&self, vector: &[f32]) -> Vec<u8> {
+        vector
+            .chunks_exact(self.sub_dim)
+            .enumerate()
+            .map(|(subspace, slice)| {
+                (0..CENTROIDS)
+                    .min_by(|&a, &b| {
+                        distance(slice, self.centroid(subspace, a))
+                            .total_cmp(&distance(slice, self.centroid(subspace, b)))
+                    })
+                    .unwrap_or(0) as u8
+            })
+            .collect()
+    }
+
+    pub(super) fn decode(&self, codes: &[u8]) -> Vec<f32> {
+        codes
+            .iter()
+            .enumerate()
+            .flat_map(|(subspace, &code)| self.centroid(subspace, code as usize).iter().copied())
+            .collect()
+    }
+
+    /// Dot product of `query` with the vector `codes` stands for.
+    pub(super) fn similarity(&self, query: &[f32], codes: &[u8]) -> f32 {
+        query
+            .chunks_exact(self.sub_dim)
+            .zip(codes)
+            .enumerate()
+            .map(|(subspace, (slice, &code))| dot(slice, self.centroid(subspace, code as usize)))
+            .sum()
+    }
+
+    fn centroid(&self, subspace: usize, index: usize) -> &[f32] {
+        let start = (subspace * CENTROIDS + index) * self.sub_dim;
+        &self.centroids[start..start + self.sub_dim]
+    }
+}
+
+/// Lloyd's k-means with [`CENTROIDS`] clusters, seeded deterministically
+/// from the points. Clusters that end up empty keep their position.
+fn kmeans(points: &[&[f32]], dim: usize) -> Vec<f32> {
+    let mut centroids = Vec::with_capacity(CENTROIDS * dim);
+    for cluster in 0..CENTROIDS {
+        let seed = splitmix64(cluster as u64) as usize % points.len().max(1);
+        match points.get(seed) {
+            Some(point) => centroids.extend_from_slice(point),
+            None => centroids.extend(std::iter::repeat_n(0.0, dim)),
+        }
+    }
+
+    let mut assignment = vec![0usize; points.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        for (point, cluster) in points.iter().zip(&mut assignment) {
+            *cluster = (0..CENTROIDS)
+                .min_by(|&a, &b| {
+                    distance(point, &centroids[a * dim..(a + 1) * dim])
+                        .total_cmp(&distance(point, &centroids[b * dim..(b + 1) * dim]))
+                })
+                .unwrap_or(0);
+        }
+
+        let mut sums = vec![0.0_f32; CENTROIDS * dim];
+        let mut counts = vec![0usize; CENTROIDS];
+        for (point, &cluster) in points.iter().zip(&assignment) {
+            counts[cluster] += 1;
+            for (sum, component) in sums[cluster * dim..(cluster + 1) * dim]
+                .iter_mut()
+                .zip(*point)
+            {
+                *sum += component;
+            }
+        }
+        for (cluster, &count) in counts.iter().enumerate() {
+            if count > 0 {
+                for d in 0..dim {
+                    centroids[cluster * dim + d] = sums[cluster * dim + d] / count as f32;
+                }
+            }
+        }
+    }
+    centroids
+}
+
+fn distance(left: &[f32], right: &[f32]) -> f32 {
+    left.iter().zip(right).map(|(a, b)| (a - b) * (a - b)).sum()
+}
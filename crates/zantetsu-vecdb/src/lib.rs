@@ -2,10 +2,37 @@
 //!
 //! Canonical title matching for parsed anime names.
 //!
-//! The current implementation supports two backends:
+//! The current implementation supports four backends:
 //! - A local Kitsu SQL dump (`latest.sql` or `latest.sql.gz`)
+//! - A flat catalog exported from the imported database by `kitsu-sync export`
+//!   (`catalog.jsonl`, see [`KitsuExportRecord`])
+//! - The downloadable [anime-offline-database](https://github.com/manami-project/anime-offline-database)
+//!   release, for users who do not run the Kitsu database sync
 //! - A remote GraphQL endpoint compatible with the expected anime search schema
 //!
+//! [`HnswIndex`] provides approximate nearest-neighbour search over title
+//! embeddings, and can be saved and reloaded to skip re-embedding at startup.
+//! [`MmapHnswIndex`] queries a saved index in place from a memory-mapped
+//! file, for devices that cannot hold every vector in RAM.
+//!
+//! [`IndexBundle`] pairs a saved index with the catalog it was built from;
+//! `kitsu-sync build-index` writes one from a synced dump.
+//!
+//! [`ResolutionCache`] remembers resolved titles, optionally across runs, so
+//! repeated lookups skip scoring entirely.
+//!
+//! [`TitleMatcher::rank_titles`] returns scored [`TitleMatch`] candidates
+//! and flags results too close to call as ambiguous, so callers can ask a
+//! human instead of silently picking the wrong anime.
+//!
+//! A [`ResolutionStore`] keeps resolutions users confirmed or corrected,
+//! and answers before any matching, so corrections stick across runs.
+//! [`RedbStore`] persists them in an embedded database (`redb` feature, on
+//! by default).
+//!
+//! [`AnimeRelations`] maps absolute episode numbers onto season entries using
+//! the anime-relations rule format.
+//!
 //! Crates:
 //! - [`zantetsu`](https://docs.rs/zantetsu) - unified API surface
 //! - [`zantetsu-core`](https://docs.rs/zantetsu-core) - parsing engine
@@ -43,11 +70,29 @@
 //! assert!(best.is_some());
 //! ```
 
+pub mod bundle;
+pub mod cache;
 pub mod error;
+pub mod index;
 mod matcher;
+mod rank;
+mod relations;
+mod season;
+pub mod store;
 
+pub use bundle::{BUNDLE_VERSION, BundleEntry, IndexBundle};
+pub use cache::ResolutionCache;
 pub use error::{MatchResult, MatcherError};
+pub use index::{
+    EMBEDDING_DIM, HnswIndex, HnswParams, IndexHit, MmapHnswIndex, Quantization, embed_title,
+};
 pub use matcher::{
-    AnimeIds, AnimeTitleMatch, MatchProvider, MatchSource, TitleMatcher,
-    default_kitsu_dump_dir,
+    AliasSource, AnimeIds, AnimeTitleMatch, KitsuExportRecord, MatchProvider, MatchSource,
+    TitleMatcher, default_kitsu_dump_dir,
 };
+pub use rank::{AMBIGUITY_MARGIN, DEFAULT_CONFIDENCE_THRESHOLD, RankedMatches, TitleMatch};
+pub use relations::{AnimeRelations, EpisodeInterval, RelationRule, SeasonalEpisode};
+pub use season::SeasonHint;
+#[cfg(feature = "redb")]
+pub use store::RedbStore;
+pub use store::{MemoryStore, ResolutionStore, resolution_key};
@@ -1,4 +1,8 @@
+use crate::cache::ResolutionCache;
 use crate::error::{MatchResult, MatcherError};
+use crate::rank::{DEFAULT_CONFIDENCE_THRESHOLD, RankedMatches, rank_matches};
+use crate::season::{SEASON_WEIGHT, SeasonHint, season_markers};
+use crate::store::{ResolutionStore, resolution_key};
 use dirs::data_dir;
 use flate2::read::GzDecoder;
 use reqwest::blocking::Client;
@@ -13,15 +17,72 @@ use tracing::debug;
 
 const DEFAULT_TIMEOUT_SECS: u64 = 10;
 
+/// Score of an exact hit on a generated acronym. Initials collide across
+/// titles far more often than curated abbreviations do, so they rank below
+/// an exact title or fan-abbreviation hit.
+const GENERATED_ACRONYM_SCORE: f32 = 0.9;
+
+/// Well-known fan abbreviations, keyed by the normalized title they stand
+/// for. A key also covers sequels whose title starts with it (e.g.
+/// "Jujutsu Kaisen 2nd Season"). Plain initials such as `SAO` or `HxH` are
+/// generated and need no entry here.
+const FAN_ABBREVIATIONS: &[(&str, &[&str])] = &[
+    ("shingeki no kyojin", &["AoT", "SnK"]),
+    ("attack on titan", &["AoT", "SnK"]),
+    ("jujutsu kaisen", &["JJK"]),
+    ("kimetsu no yaiba", &["KnY"]),
+    ("demon slayer", &["KnY"]),
+    ("boku no hero academia", &["BnHA", "MHA"]),
+    ("my hero academia", &["BnHA", "MHA"]),
+    ("fullmetal alchemist brotherhood", &["FMAB"]),
+    ("fullmetal alchemist", &["FMA"]),
+    ("re zero kara hajimeru isekai seikatsu", &["ReZero"]),
+    ("kono subarashii sekai ni shukufuku wo", &["KonoSuba"]),
+    (
+        "yahari ore no seishun love comedy wa machigatteiru",
+        &["OreGairu", "SNAFU"],
+    ),
+    ("ore no imouto ga konna ni kawaii wake ga nai", &["OreImo"]),
+    ("tensei shitara slime datta ken", &["TenSura"]),
+    ("jojo no kimyou na bouken", &["JoJo"]),
+    ("kaguya sama wa kokurasetai", &["Kaguya-sama"]),
+    ("mahou shoujo madoka magica", &["Madoka", "PMMM"]),
+    ("chainsaw man", &["CSM"]),
+];
+
 /// Which backend produced a canonical title match.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MatchProvider {
     /// Match derived from the local Kitsu SQL dump.
     KitsuDump,
+    /// Match derived from a `kitsu-sync export` catalog.
+    KitsuExport,
+    /// Match derived from the anime-offline-database.
+    OfflineDatabase,
     /// Match derived from a remote GraphQL endpoint.
     RemoteEndpoint,
 }
 
+/// Where a catalog alias comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AliasSource {
+    /// The anime's canonical title.
+    #[default]
+    Canonical,
+    /// A localized title (English, romaji, native, ...).
+    Localized,
+    /// An abbreviation or alternative title listed by the catalog.
+    Synonym,
+    /// Derived from the catalog's URL slug.
+    Slug,
+    /// A well-known fan abbreviation from the built-in table, e.g. `JJK`.
+    FanAbbreviation,
+    /// The initials of a title of three or more words, e.g. `HxH`.
+    Acronym,
+    /// Added with [`TitleMatcher::with_alias`].
+    Custom,
+}
+
 /// External and internal identifiers for a matched anime.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AnimeIds {
@@ -42,11 +103,25 @@ pub struct AnimeTitleMatch {
     pub canonical_title: String,
     /// The specific alias that scored highest for the query.
     pub matched_title: String,
+    /// Where `matched_title` comes from.
+    #[serde(default)]
+    pub alias_source: AliasSource,
     /// Similarity score in the range `[0.0, 1.0]`.
     pub score: f32,
     /// Known IDs for the matched anime.
     pub ids: AnimeIds,
-    /// Known aliases for the anime, canonical title first.
+    /// Season number written in the entry's titles; `None` for a "final
+    /// season" without a number. Entries without a marker are season 1.
+    #[serde(default)]
+    pub season: Option<u32>,
+    /// Part number written in the entry's titles, or 1.
+    #[serde(default)]
+    pub part: Option<u32>,
+    /// Year the anime started airing, when the catalog provides it.
+    #[serde(default)]
+    pub year: Option<u16>,
+    /// Known aliases for the anime, canonical title first. Generated
+    /// acronyms are matched but not listed.
     pub titles: Vec<String>,
 }
 
@@ -69,6 +144,21 @@ pub enum MatchSource {
         /// Path to either the dump directory or a specific `latest.sql` / `latest.sql.gz` file.
         dump_path: PathBuf,
     },
+    /// Use a flat catalog exported from the imported Kitsu database by
+    /// `kitsu-sync export`.
+    KitsuExport {
+        /// Path to either the dump directory or a specific `catalog.jsonl` /
+        /// `catalog.jsonl.gz` file.
+        export_path: PathBuf,
+    },
+    /// Use the [anime-offline-database](https://github.com/manami-project/anime-offline-database)
+    /// JSON release located at `database_path`.
+    OfflineDatabase {
+        /// Path to either the download directory or a specific
+        /// `anime-offline-database.json`, `anime-offline-database-minified.json`
+        /// or `anime-offline-database.jsonl` file, optionally gzipped.
+        database_path: PathBuf,
+    },
     /// Use a remote GraphQL endpoint.
     RemoteEndpoint {
         /// Remote endpoint URL.
@@ -85,6 +175,22 @@ impl MatchSource {
         }
     }
 
+    /// Use the `kitsu-sync export` catalog at the provided path.
+    #[must_use]
+    pub fn kitsu_export(export_path: impl Into<PathBuf>) -> Self {
+        Self::KitsuExport {
+            export_path: export_path.into(),
+        }
+    }
+
+    /// Use the anime-offline-database release at the provided path.
+    #[must_use]
+    pub fn offline_database(database_path: impl Into<PathBuf>) -> Self {
+        Self::OfflineDatabase {
+            database_path: database_path.into(),
+        }
+    }
+
     /// Use a remote GraphQL endpoint.
     #[must_use]
     pub fn remote_endpoint(endpoint: impl Into<String>) -> Self {
@@ -108,10 +214,15 @@ pub fn default_kitsu_dump_dir() -> PathBuf {
 /// Construct a matcher once and reuse it for many title lookups.
 pub struct TitleMatcher {
     backend: MatcherBackend,
+    cache: Option<ResolutionCache>,
+    store: Option<Box<dyn ResolutionStore>>,
+    confidence_threshold: f32,
 }
 
 enum MatcherBackend {
-    Kitsu(KitsuTitleMatcher),
+    Kitsu(CatalogTitleMatcher),
+    KitsuExport(CatalogTitleMatcher),
+    OfflineDatabase(CatalogTitleMatcher),
     RemoteEndpoint(RemoteTitleMatcher),
 }
 
@@ -133,14 +244,25 @@ impl TitleMatcher {
     pub fn new(source: MatchSource) -> MatchResult<Self> {
         let backend = match source {
             MatchSource::KitsuDump { dump_path } => {
-                MatcherBackend::Kitsu(KitsuTitleMatcher::from_dump(dump_path)?)
+                MatcherBackend::Kitsu(CatalogTitleMatcher::from_dump(dump_path)?)
+            }
+            MatchSource::KitsuExport { export_path } => {
+                MatcherBackend::KitsuExport(CatalogTitleMatcher::from_export(export_path)?)
             }
+            MatchSource::OfflineDatabase { database_path } => MatcherBackend::OfflineDatabase(
+                CatalogTitleMatcher::from_offline_database(database_path)?,
+            ),
             MatchSource::RemoteEndpoint { endpoint } => {
                 MatcherBackend::RemoteEndpoint(RemoteTitleMatcher::new(endpoint)?)
             }
         };
 
-        Ok(Self { backend })
+        Ok(Self {
+            backend,
+            cache: None,
+            store: None,
+            confidence_threshold: DEFAULT_CONFIDENCE_THRESHOLD,
+        })
     }
 
     /// Create a matcher from a local Kitsu dump path.
@@ -148,13 +270,143 @@ impl TitleMatcher {
         Self::new(MatchSource::kitsu_dump(dump_path))
     }
 
+    /// Create a matcher from a `kitsu-sync export` catalog path.
+    pub fn from_kitsu_export(export_path: impl Into<PathBuf>) -> MatchResult<Self> {
+        Self::new(MatchSource::kitsu_export(export_path))
+    }
+
+    /// Create a matcher from an anime-offline-database release path.
+    pub fn from_offline_database(database_path: impl Into<PathBuf>) -> MatchResult<Self> {
+        Self::new(MatchSource::offline_database(database_path))
+    }
+
     /// Create a matcher using a remote GraphQL endpoint.
     pub fn from_remote_endpoint(endpoint: impl Into<String>) -> MatchResult<Self> {
         Self::new(MatchSource::remote_endpoint(endpoint))
     }
 
+    /// Consult `cache` in [`match_title`](Self::match_title) before scoring,
+    /// and store new matches in it.
+    #[must_use]
+    pub fn with_cache(mut self, cache: ResolutionCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The resolution cache, e.g. to [save](ResolutionCache::save) it on
+    /// shutdown.
+    #[must_use]
+    pub fn cache(&self) -> Option<&ResolutionCache> {
+        self.cache.as_ref()
+    }
+
+    /// Answer [`match_title`](Self::match_title) and
+    /// [`match_title_for_season`](Self::match_title_for_season) from the
+    /// resolutions [confirmed](Self::confirm) in `store` before matching.
+    #[must_use]
+    pub fn with_store(mut self, store: impl ResolutionStore + 'static) -> Self {
+        self.store = Some(Box::new(store));
+        self
+    }
+
+    /// The store of confirmed resolutions, if one was set.
+    #[must_use]
+    pub fn store(&self) -> Option<&dyn ResolutionStore> {
+        self.store.as_deref()
+    }
+
+    /// Record `matched` as the right answer for `title` and `hint`, e.g.
+    /// after a user corrects a match. Later lookups return it without
+    /// matching. Without a [store](Self::with_store) this does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Store`] if the store fails.
+    pub fn confirm(
+        &self,
+        title: &str,
+        hint: &SeasonHint,
+        matched: AnimeTitleMatch,
+    ) -> MatchResult<()> {
+        match &self.store {
+            Some(store) => store.put(&resolution_key(title, hint), &matched),
+            None => Ok(()),
+        }
+    }
+
+    /// Drop the confirmed resolution for `title` and `hint`, returning
+    /// whether there was one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Store`] if the store fails.
+    pub fn forget(&self, title: &str, hint: &SeasonHint) -> MatchResult<bool> {
+        match &self.store {
+            Some(store) => store.remove(&resolution_key(title, hint)),
+            None => Ok(false),
+        }
+    }
+
+    /// Flag [ranked](Self::rank_titles) results whose best combined score
+    /// is below `threshold` as ambiguous. Defaults to
+    /// [`DEFAULT_CONFIDENCE_THRESHOLD`].
+    #[must_use]
+    pub fn with_confidence_threshold(mut self, threshold: f32) -> Self {
+        self.confidence_threshold = threshold;
+        self
+    }
+
+    /// The threshold below which ranked results are ambiguous.
+    #[must_use]
+    pub fn confidence_threshold(&self) -> f32 {
+        self.confidence_threshold
+    }
+
+    /// Index `alias` as another name for every anime known by `title`.
+    ///
+    /// `title` is compared after normalization, so `"Oshi no Ko"` also
+    /// targets `"【Oshi no Ko】"`. Matches through the alias report
+    /// [`AliasSource::Custom`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use zantetsu_vecdb::TitleMatcher;
+    ///
+    /// let matcher = TitleMatcher::from_kitsu_dump("/tmp/kitsu-dumps")
+    ///     .unwrap()
+    ///     .with_alias("Sousou no Frieren", "Furiren");
+    ///
+    /// let best = matcher.match_title("Furiren").unwrap();
+    /// assert!(best.is_some());
+    /// ```
+    #[must_use]
+    pub fn with_alias(mut self, title: &str, alias: impl Into<String>) -> Self {
+        let target = normalize_title(title);
+        let alias = alias.into();
+        match &mut self.backend {
+            MatcherBackend::Kitsu(matcher)
+            | MatcherBackend::KitsuExport(matcher)
+            | MatcherBackend::OfflineDatabase(matcher) => {
+                for entry in &mut matcher.entries {
+                    entry.add_custom_alias(&target, &alias);
+                }
+            }
+            MatcherBackend::RemoteEndpoint(matcher) => matcher.custom_aliases.push((target, alias)),
+        }
+        // Cached matches were resolved without the alias.
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+        self
+    }
+
     /// Return the best available match for the provided title.
     ///
+    /// A [confirmed](Self::confirm) resolution wins over matching. With a
+    /// [cache](Self::with_cache), a title seen before is answered from the
+    /// cache.
+    ///
     /// # Examples
     ///
     /// ```rust,no_run
@@ -169,13 +421,105 @@ impl TitleMatcher {
     /// assert!(best.is_some());
     /// ```
     pub fn match_title(&self, title: &str) -> MatchResult<Option<AnimeTitleMatch>> {
-        Ok(self.search_titles(title, 1)?.into_iter().next())
+        if let Some(confirmed) = self.confirmed(title, &SeasonHint::default())? {
+            return Ok(Some(confirmed));
+        }
+        if let Some(cached) = self.cache.as_ref().and_then(|cache| cache.get(title)) {
+            return Ok(Some(cached));
+        }
+        let best = self.search_titles(title, 1)?.into_iter().next();
+        if let (Some(cache), Some(best)) = (&self.cache, &best) {
+            cache.insert(title, best.clone());
+        }
+        Ok(best)
     }
 
     /// Search for the best matches for the provided title.
     ///
     /// Results are ordered from highest score to lowest score.
     pub fn search_titles(&self, title: &str, limit: usize) -> MatchResult<Vec<AnimeTitleMatch>> {
+        self.search_titles_for_season(title, &SeasonHint::default(), limit)
+    }
+
+    /// Rank up to `limit` candidates for the provided title by a blend of
+    /// fuzzy string and embedding similarity, flagging the result as
+    /// ambiguous when no candidate is clearly right.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use zantetsu_vecdb::TitleMatcher;
+    ///
+    /// let matcher = TitleMatcher::from_kitsu_dump("/tmp/kitsu-dumps").unwrap();
+    /// let ranked = matcher.rank_titles("Hunter x Hunter", 5).unwrap();
+    /// match ranked.confident() {
+    ///     Some(best) => println!("{} {:?}", best.title, best.id),
+    ///     None => println!("needs review: {:?}", ranked.candidates),
+    /// }
+    /// ```
+    pub fn rank_titles(&self, title: &str, limit: usize) -> MatchResult<RankedMatches> {
+        self.rank_titles_for_season(title, &SeasonHint::default(), limit)
+    }
+
+    /// Like [`rank_titles`](Self::rank_titles), scoring candidates as
+    /// [`search_titles_for_season`](Self::search_titles_for_season) does.
+    pub fn rank_titles_for_season(
+        &self,
+        title: &str,
+        hint: &SeasonHint,
+        limit: usize,
+    ) -> MatchResult<RankedMatches> {
+        let matches = self.search_titles_for_season(title, hint, limit)?;
+        Ok(rank_matches(title, matches, self.confidence_threshold))
+    }
+
+    /// Return the best match for the provided title, preferring the entry
+    /// for the hinted season over the franchise root.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use zantetsu_vecdb::{SeasonHint, TitleMatcher};
+    ///
+    /// let matcher = TitleMatcher::from_kitsu_dump("/tmp/kitsu-dumps").unwrap();
+    /// let hint = SeasonHint::new().with_season(3).with_part(2);
+    ///
+    /// let best = matcher.match_title_for_season("Attack on Titan", &hint).unwrap();
+    /// assert!(best.is_some());
+    /// ```
+    pub fn match_title_for_season(
+        &self,
+        title: &str,
+        hint: &SeasonHint,
+    ) -> MatchResult<Option<AnimeTitleMatch>> {
+        if let Some(confirmed) = self.confirmed(title, hint)? {
+            return Ok(Some(confirmed));
+        }
+        Ok(self
+            .search_titles_for_season(title, hint, 1)?
+            .into_iter()
+            .next())
+    }
+
+    fn confirmed(&self, title: &str, hint: &SeasonHint) -> MatchResult<Option<AnimeTitleMatch>> {
+        match &self.store {
+            Some(store) => store.get(&resolution_key(title, hint)),
+            None => Ok(None),
+        }
+    }
+
+    /// Search for the best matches for the provided title and season.
+    ///
+    /// Season, part and year markers (`2nd Season`, `Part 2`, ...) are
+    /// stripped from both the query and the catalog titles before comparing,
+    /// and agreement with `hint` then decides between a franchise's entries.
+    /// An empty hint scores exactly like [`search_titles`](Self::search_titles).
+    pub fn search_titles_for_season(
+        &self,
+        title: &str,
+        hint: &SeasonHint,
+        limit: usize,
+    ) -> MatchResult<Vec<AnimeTitleMatch>> {
         let query = normalize_title(title);
         if query.is_empty() {
             return Err(MatcherError::EmptyQuery);
@@ -188,18 +532,61 @@ impl TitleMatcher {
                 &matcher.entries,
                 title,
                 &query,
+                hint,
                 limit,
             )),
-            MatcherBackend::RemoteEndpoint(matcher) => matcher.search_titles(title, &query, limit),
+            MatcherBackend::KitsuExport(matcher) => Ok(score_entries(
+                MatchProvider::KitsuExport,
+                &matcher.entries,
+                title,
+                &query,
+                hint,
+                limit,
+            )),
+            MatcherBackend::OfflineDatabase(matcher) => Ok(score_entries(
+                MatchProvider::OfflineDatabase,
+                &matcher.entries,
+                title,
+                &query,
+                hint,
+                limit,
+            )),
+            MatcherBackend::RemoteEndpoint(matcher) => {
+                matcher.search_titles(title, &query, hint, limit)
+            }
         }
     }
 }
 
-struct KitsuTitleMatcher {
+/// Loads the full catalog of a local `source`, for indexing.
+pub(crate) fn load_catalog(source: MatchSource) -> MatchResult<(MatchProvider, Vec<CatalogEntry>)> {
+    let (provider, matcher) = match source {
+        MatchSource::KitsuDump { dump_path } => (
+            MatchProvider::KitsuDump,
+            CatalogTitleMatcher::from_dump(dump_path)?,
+        ),
+        MatchSource::KitsuExport { export_path } => (
+            MatchProvider::KitsuExport,
+            CatalogTitleMatcher::from_export(export_path)?,
+        ),
+        MatchSource::OfflineDatabase { database_path } => (
+            MatchProvider::OfflineDatabase,
+            CatalogTitleMatcher::from_offline_database(database_path)?,
+        ),
+        MatchSource::RemoteEndpoint { endpoint } => {
+            return Err(MatcherError::InvalidIndex(format!(
+                "remote endpoint {endpoint} has no catalog to index"
+            )));
+        }
+    };
+    Ok((provider, matcher.entries))
+}
+
+struct CatalogTitleMatcher {
     entries: Vec<CatalogEntry>,
 }
 
-impl KitsuTitleMatcher {
+impl CatalogTitleMatcher {
     fn from_dump(dump_path: PathBuf) -> MatchResult<Self> {
         let resolved = resolve_dump_path(&dump_path)?;
         debug!("loading Kitsu dump from {}", resolved.display());
@@ -207,11 +594,33 @@ impl KitsuTitleMatcher {
         let entries = parse_kitsu_dump(reader)?;
         Ok(Self { entries })
     }
+
+    fn from_export(export_path: PathBuf) -> MatchResult<Self> {
+        let resolved = resolve_export_path(&export_path)?;
+        debug!("loading Kitsu export from {}", resolved.display());
+        let reader = open_dump_reader(&resolved)?;
+        let entries = parse_kitsu_export(reader)?;
+        Ok(Self { entries })
+    }
+
+    fn from_offline_database(database_path: PathBuf) -> MatchResult<Self> {
+        let resolved = resolve_offline_database_path(&database_path)?;
+        debug!("loading anime-offline-database from {}", resolved.display());
+        let reader = open_dump_reader(&resolved)?;
+        let entries = if is_json_lines(&resolved) {
+            parse_offline_database_lines(reader)?
+        } else {
+            parse_offline_database(reader)?
+        };
+        Ok(Self { entries })
+    }
 }
 
 struct RemoteTitleMatcher {
     client: Client,
     endpoint: String,
+    /// `(normalized title, alias)` pairs applied to every fetched entry.
+    custom_aliases: Vec<(String, String)>,
 }
 
 impl RemoteTitleMatcher {
@@ -227,20 +636,27 @@ impl RemoteTitleMatcher {
             .timeout(std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .user_agent(format!("zantetsu/{}", env!("CARGO_PKG_VERSION")))
             .build()?;
-        Ok(Self { client, endpoint })
+        Ok(Self {
+            client,
+            endpoint,
+            custom_aliases: Vec::new(),
+        })
     }
 
     fn search_titles(
         &self,
         raw_query: &str,
         normalized_query: &str,
+        hint: &SeasonHint,
         limit: usize,
     ) -> MatchResult<Vec<AnimeTitleMatch>> {
+        // Fetch more candidates when a later season may rank below the root.
+        let per_page = if hint.is_empty() { 5 } else { 15 };
         let payload = RemoteGraphQlRequest {
             query: REMOTE_GRAPHQL_QUERY,
             variables: RemoteGraphQlVariables {
                 search: raw_query,
-                per_page: limit.max(5) as i64,
+                per_page: limit.max(per_page) as i64,
             },
         };
 
@@ -268,65 +684,154 @@ impl RemoteTitleMatcher {
             .ok_or_else(|| MatcherError::InvalidResponse("missing page".into()))?
             .media;
 
-        let entries = media
+        let mut entries = media
             .into_iter()
             .map(CatalogEntry::from_remote_media)
             .collect::<Vec<_>>();
+        for entry in &mut entries {
+            for (target, alias) in &self.custom_aliases {
+                entry.add_custom_alias(target, alias);
+            }
+        }
 
         Ok(score_entries(
             MatchProvider::RemoteEndpoint,
             &entries,
             raw_query,
             normalized_query,
+            hint,
             limit,
         ))
     }
 }
 
 #[derive(Debug, Clone)]
-struct CatalogEntry {
-    canonical_title: String,
-    titles: Vec<String>,
-    normalized_titles: Vec<String>,
-    ids: AnimeIds,
+pub(crate) struct CatalogEntry {
+    pub(crate) canonical_title: String,
+    pub(crate) aliases: Vec<CatalogAlias>,
+    pub(crate) ids: AnimeIds,
+    pub(crate) season: Option<u32>,
+    pub(crate) part: Option<u32>,
+    pub(crate) year: Option<u16>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CatalogAlias {
+    pub(crate) title: String,
+    normalized: String,
+    /// `normalized` without season and part markers.
+    base: String,
+    pub(crate) source: AliasSource,
 }
 
 impl CatalogEntry {
-    fn new(canonical_title: String, titles: Vec<String>, ids: AnimeIds) -> Option<Self> {
-        let titles = dedupe_titles(&canonical_title, titles);
-        if titles.is_empty() {
-            return None;
+    /// Builds an entry from catalog titles, then indexes the fan
+    /// abbreviations and acronyms they imply.
+    fn new(
+        canonical_title: String,
+        titles: Vec<(String, AliasSource)>,
+        ids: AnimeIds,
+        year: Option<u16>,
+    ) -> Option<Self> {
+        let mut entry = Self {
+            canonical_title: String::new(),
+            aliases: Vec::new(),
+            ids,
+            season: None,
+            part: None,
+            year,
+        };
+        entry.push_alias(&canonical_title, AliasSource::Canonical);
+        for (title, source) in titles {
+            entry.push_alias(&title, source);
         }
+        entry.canonical_title = entry.aliases.first()?.title.clone();
 
-        let canonical_title = titles
-            .first()
-            .cloned()
-            .unwrap_or_else(|| canonical_title.trim().to_string());
+        // The first title that names a season or part decides it.
+        let markers = entry
+            .aliases
+            .iter()
+            .map(|alias| season_markers(&alias.normalized))
+            .collect::<Vec<_>>();
+        let is_final = markers.iter().any(|m| m.is_final);
+        entry.season = markers
+            .iter()
+            .find_map(|m| m.season)
+            .or((!is_final).then_some(1));
+        entry.part = markers.iter().find_map(|m| m.part).or(Some(1));
+
+        let catalog_aliases = entry.aliases.len();
+        for index in 0..catalog_aliases {
+            let normalized = entry.aliases[index].normalized.clone();
+            for abbreviation in fan_abbreviations(&normalized) {
+                entry.push_alias(abbreviation, AliasSource::FanAbbreviation);
+            }
+        }
+        for index in 0..catalog_aliases {
+            if let Some(acronym) = acronym(&entry.aliases[index].normalized) {
+                entry.push_alias(&acronym, AliasSource::Acronym);
+            }
+        }
 
-        let normalized_titles = titles.iter().map(|title| normalize_title(title)).collect();
-        Some(Self {
-            canonical_title,
-            titles,
-            normalized_titles,
-            ids,
-        })
+        Some(entry)
     }
 
-    fn from_remote_media(media: RemoteMedia) -> Self {
-        let mut titles = Vec::new();
-        if let Some(title) = media.title.user_preferred.clone() {
-            titles.push(title);
-        }
-        if let Some(title) = media.title.romaji.clone() {
-            titles.push(title);
-        }
-        if let Some(title) = media.title.english.clone() {
-            titles.push(title);
+    /// Adds `title` unless it is blank or normalizes like an existing alias.
+    fn push_alias(&mut self, title: &str, source: AliasSource) {
+        let title = title.trim();
+        let normalized = normalize_title(title);
+        if normalized.is_empty()
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.normalized == normalized)
+        {
+            return;
         }
-        if let Some(title) = media.title.native.clone() {
-            titles.push(title);
+        self.aliases.push(CatalogAlias {
+            title: title.to_string(),
+            base: season_markers(&normalized).base,
+            normalized,
+            source,
+        });
+    }
+
+    fn add_custom_alias(&mut self, normalized_target: &str, alias: &str) {
+        if self
+            .aliases
+            .iter()
+            .any(|existing| existing.normalized == normalized_target)
+        {
+            self.push_alias(alias, AliasSource::Custom);
         }
-        titles.extend(media.synonyms.unwrap_or_default());
+    }
+
+    pub(crate) fn titles(&self) -> Vec<String> {
+        self.aliases
+            .iter()
+            .filter(|alias| alias.source != AliasSource::Acronym)
+            .map(|alias| alias.title.clone())
+            .collect()
+    }
+
+    fn from_remote_media(media: RemoteMedia) -> Self {
+        let mut titles = [
+            &media.title.user_preferred,
+            &media.title.romaji,
+            &media.title.english,
+            &media.title.native,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|title| (title.clone(), AliasSource::Localized))
+        .collect::<Vec<_>>();
+        titles.extend(
+            media
+                .synonyms
+                .unwrap_or_default()
+                .into_iter()
+                .map(|title| (title, AliasSource::Synonym)),
+        );
 
         let canonical_title = media
             .title
@@ -345,16 +850,133 @@ impl CatalogEntry {
                 anilist: Some(media.id),
                 mal: media.id_mal,
             },
+            media.start_date.and_then(|date| date.year),
         )
         .expect("remote media should always produce at least one title")
     }
 }
 
+/// One line of a `kitsu-sync export` catalog (JSON Lines).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KitsuExportRecord {
+    /// Kitsu anime id.
+    pub kitsu_id: u32,
+    /// Kitsu's canonical title; the first of `titles` is used when absent.
+    #[serde(default)]
+    pub canonical_title: Option<String>,
+    /// Localized titles (English, romaji, Japanese, ...).
+    #[serde(default)]
+    pub titles: Vec<String>,
+    /// Abbreviations and alternative titles.
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+    /// AniList media id, from Kitsu's mappings table.
+    #[serde(default)]
+    pub anilist_id: Option<u32>,
+    /// MyAnimeList anime id, from Kitsu's mappings table.
+    #[serde(default)]
+    pub mal_id: Option<u32>,
+    /// Year the anime started airing.
+    #[serde(default)]
+    pub start_year: Option<u16>,
+}
+
+impl KitsuExportRecord {
+    fn into_entry(self) -> Option<CatalogEntry> {
+        let canonical_title = self
+            .canonical_title
+            .or_else(|| self.titles.first().cloned())?;
+        let titles = self
+            .titles
+            .into_iter()
+            .map(|title| (title, AliasSource::Localized))
+            .chain(
+                self.synonyms
+                    .into_iter()
+                    .map(|title| (title, AliasSource::Synonym)),
+            )
+            .collect();
+        CatalogEntry::new(
+            canonical_title,
+            titles,
+            AnimeIds {
+                kitsu: Some(self.kitsu_id),
+                anilist: self.anilist_id,
+                mal: self.mal_id,
+            },
+            self.start_year,
+        )
+    }
+}
+
+/// An anime-offline-database release: a `data` array of anime.
+#[derive(Debug, Deserialize)]
+struct OfflineDatabase {
+    data: Vec<OfflineAnime>,
+}
+
+/// One anime of an anime-offline-database release. The JSON Lines release
+/// starts with a metadata line without a `title`, which is skipped.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OfflineAnime {
+    #[serde(default)]
+    title: Option<String>,
+    /// Page URLs on each service, e.g. `https://kitsu.app/anime/1376`.
+    #[serde(default)]
+    sources: Vec<String>,
+    #[serde(default)]
+    synonyms: Vec<String>,
+    #[serde(default)]
+    anime_season: Option<OfflineAnimeSeason>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OfflineAnimeSeason {
+    #[serde(default)]
+    year: Option<u16>,
+}
+
+impl OfflineAnime {
+    fn into_entry(self) -> Option<CatalogEntry> {
+        let ids = AnimeIds {
+            kitsu: source_id(&self.sources, &["kitsu.app", "kitsu.io"]),
+            anilist: source_id(&self.sources, &["anilist.co"]),
+            mal: source_id(&self.sources, &["myanimelist.net"]),
+        };
+        let titles = self
+            .synonyms
+            .into_iter()
+            .map(|title| (title, AliasSource::Synonym))
+            .collect();
+        CatalogEntry::new(
+            self.title?,
+            titles,
+            ids,
+            self.anime_season.and_then(|season| season.year),
+        )
+    }
+}
+
+/// The anime id in the first of `sources` hosted on one of `hosts`.
+fn source_id(sources: &[String], hosts: &[&str]) -> Option<u32> {
+    sources.iter().find_map(|source| {
+        let (_, rest) = source.split_once("://")?;
+        let (host, path) = rest.split_once('/')?;
+        let host = host.strip_prefix("www.").unwrap_or(host);
+        if !hosts.contains(&host) {
+            return None;
+        }
+        path.strip_prefix("anime/")?.split('/').next()?.parse().ok()
+    })
+}
+
 #[derive(Debug, Default)]
 struct RawAnimeRecord {
     canonical_title: Option<String>,
-    titles: Vec<String>,
+    titles: Vec<(String, AliasSource)>,
     slug: Option<String>,
+    year: Option<u16>,
 }
 
 #[derive(Debug, Clone)]
@@ -371,6 +993,7 @@ struct AnimeColumns {
     titles: Option<usize>,
     abbreviated_titles: Option<usize>,
     slug: Option<usize>,
+    start_date: Option<usize>,
 }
 
 impl AnimeColumns {
@@ -383,6 +1006,7 @@ impl AnimeColumns {
             titles: find_column_index(columns, "titles"),
             abbreviated_titles: find_column_index(columns, "abbreviated_titles"),
             slug: find_column_index(columns, "slug"),
+            start_date: find_column_index(columns, "start_date"),
         })
     }
 }
@@ -421,11 +1045,19 @@ fn score_entries(
     entries: &[CatalogEntry],
     raw_query: &str,
     normalized_query: &str,
+    hint: &SeasonHint,
     limit: usize,
 ) -> Vec<AnimeTitleMatch> {
+    let query_base = season_markers(normalized_query).base;
     let mut scored = entries
         .iter()
-        .filter_map(|entry| score_entry(provider, entry, raw_query, normalized_query))
+        .filter_map(|entry| {
+            let mut matched = score_entry(provider, entry, raw_query, normalized_query)?;
+            if !hint.is_empty() {
+                matched = disambiguate_season(matched, entry, &query_base, hint);
+            }
+            Some(matched)
+        })
         .collect::<Vec<_>>();
 
     scored.sort_by(|left, right| {
@@ -445,27 +1077,72 @@ fn score_entry(
     _raw_query: &str,
     normalized_query: &str,
 ) -> Option<AnimeTitleMatch> {
-    let mut best_title = None;
+    let mut best_alias = None;
     let mut best_score = 0.0_f32;
 
-    for (index, normalized_title) in entry.normalized_titles.iter().enumerate() {
-        let score = similarity_score(normalized_query, normalized_title);
+    for alias in &entry.aliases {
+        // Abbreviations are too short for fuzzy scoring to mean anything.
+        let exact = if alias.normalized == normalized_query {
+            1.0
+        } else {
+            0.0
+        };
+        let score = match alias.source {
+            AliasSource::FanAbbreviation => exact,
+            AliasSource::Acronym => exact * GENERATED_ACRONYM_SCORE,
+            _ => similarity_score(normalized_query, &alias.normalized),
+        };
         if score > best_score {
             best_score = score;
-            best_title = entry.titles.get(index).cloned();
+            best_alias = Some(alias);
         }
     }
 
-    best_title.map(|matched_title| AnimeTitleMatch {
+    best_alias.map(|alias| AnimeTitleMatch {
         provider,
         canonical_title: entry.canonical_title.clone(),
-        matched_title,
+        matched_title: alias.title.clone(),
+        alias_source: alias.source,
         score: best_score,
         ids: entry.ids.clone(),
-        titles: entry.titles.clone(),
+        season: entry.season,
+        part: entry.part,
+        year: entry.year,
+        titles: entry.titles(),
     })
 }
 
+/// Rescores a title match by how well the entry fits the hinted season.
+///
+/// Titles are also compared without their season markers, so that
+/// "Shingeki no Kyojin" matches "Shingeki no Kyojin Season 3" as well as
+/// the franchise root does; the hint then breaks the tie.
+fn disambiguate_season(
+    mut matched: AnimeTitleMatch,
+    entry: &CatalogEntry,
+    query_base: &str,
+    hint: &SeasonHint,
+) -> AnimeTitleMatch {
+    for alias in &entry.aliases {
+        if matches!(
+            alias.source,
+            AliasSource::FanAbbreviation | AliasSource::Acronym
+        ) {
+            continue;
+        }
+        let score = similarity_score(query_base, &alias.base);
+        if score > matched.score {
+            matched.score = score;
+            matched.matched_title.clone_from(&alias.title);
+            matched.alias_source = alias.source;
+        }
+    }
+
+    let fit = hint.fit(entry.season, entry.part, entry.year);
+    matched.score = (1.0 - SEASON_WEIGHT) * matched.score + SEASON_WEIGHT * fit;
+    matched
+}
+
 fn similarity_score(query: &str, candidate: &str) -> f32 {
     if query.is_empty() || candidate.is_empty() {
         return 0.0;
@@ -486,7 +1163,8 @@ fn similarity_score(query: &str, candidate: &str) -> f32 {
     } else {
         0.0
     };
-    let len_ratio = query.len().min(candidate.len()) as f32 / query.len().max(candidate.len()) as f32;
+    let len_ratio =
+        query.len().min(candidate.len()) as f32 / query.len().max(candidate.len()) as f32;
 
     let mut score =
         0.55 * jaro + 0.20 * token_overlap + 0.15 * contains + 0.10 * len_ratio + 0.05 * prefix;
@@ -508,53 +1186,59 @@ fn token_overlap_score(left: &str, right: &str) -> f32 {
     (2.0 * shared) / (left_tokens.len() as f32 + right_tokens.len() as f32)
 }
 
-fn normalize_title(title: &str) -> String {
+pub(crate) fn normalize_title(title: &str) -> String {
     let mut normalized = String::with_capacity(title.len());
     let mut last_was_space = true;
 
     for ch in title.chars() {
         let mapped = match ch {
             '×' | '✕' | '✖' => 'x',
-            '&' => ' ',
+            '&' | '(' | ')' | '[' | ']' | '【' | '】' | '「' | '」' | '『' | '』' => ' ',
             _ => ch.to_ascii_lowercase(),
         };
 
         if mapped.is_alphanumeric() {
             normalized.push(mapped);
             last_was_space = false;
-        } else if mapped.is_whitespace() || matches!(mapped, '-' | '_' | '.' | ':' | '/' | '\\') {
-            if !last_was_space {
-                normalized.push(' ');
-                last_was_space = true;
-            }
+        } else if (mapped.is_whitespace() || matches!(mapped, '-' | '_' | '.' | ':' | '/' | '\\'))
+            && !last_was_space
+        {
+            normalized.push(' ');
+            last_was_space = true;
         }
     }
 
     normalized.trim().to_string()
 }
 
-fn dedupe_titles(canonical_title: &str, titles: Vec<String>) -> Vec<String> {
-    let mut deduped = Vec::new();
-    let mut seen = HashSet::new();
-
-    let mut push_title = |title: String| {
-        let cleaned = title.trim();
-        if cleaned.is_empty() {
-            return;
-        }
-        let key = normalize_title(cleaned);
-        if key.is_empty() || !seen.insert(key) {
-            return;
-        }
-        deduped.push(cleaned.to_string());
-    };
+/// Fan abbreviations for a normalized title, from [`FAN_ABBREVIATIONS`].
+fn fan_abbreviations(normalized_title: &str) -> impl Iterator<Item = &'static str> + '_ {
+    FAN_ABBREVIATIONS
+        .iter()
+        .filter(move |(key, _)| {
+            normalized_title
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+        })
+        .flat_map(|(_, abbreviations)| abbreviations.iter().copied())
+}
 
-    push_title(canonical_title.to_string());
-    for title in titles {
-        push_title(title);
+/// Initials of a normalized title of three or more words, e.g. `SAO` for
+/// "sword art online". Titles with non-ASCII words are skipped.
+fn acronym(normalized_title: &str) -> Option<String> {
+    let words = normalized_title.split(' ').collect::<Vec<_>>();
+    if words.len() < 3 {
+        return None;
     }
-
-    deduped
+    words
+        .iter()
+        .map(|word| {
+            word.chars()
+                .next()
+                .filter(char::is_ascii_alphanumeric)
+                .map(|initial| initial.to_ascii_uppercase())
+        })
+        .collect()
 }
 
 fn resolve_dump_path(path: &Path) -> MatchResult<PathBuf> {
@@ -585,6 +1269,52 @@ fn resolve_dump_path(path: &Path) -> MatchResult<PathBuf> {
     )))
 }
 
+fn resolve_export_path(path: &Path) -> MatchResult<PathBuf> {
+    resolve_catalog_path(path, &["catalog.jsonl", "catalog.jsonl.gz"])
+}
+
+fn resolve_offline_database_path(path: &Path) -> MatchResult<PathBuf> {
+    resolve_catalog_path(
+        path,
+        &[
+            "anime-offline-database-minified.json",
+            "anime-offline-database-minified.json.gz",
+            "anime-offline-database.json",
+            "anime-offline-database.json.gz",
+            "anime-offline-database.jsonl",
+            "anime-offline-database.jsonl.gz",
+        ],
+    )
+}
+
+/// `path` itself if it is a file, otherwise the first of `names` found in
+/// the `path` directory.
+fn resolve_catalog_path(path: &Path, names: &[&str]) -> MatchResult<PathBuf> {
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+
+    if path.is_dir() {
+        for name in names {
+            let candidate = path.join(name);
+            if candidate.is_file() {
+                return Ok(candidate);
+            }
+        }
+
+        return Err(MatcherError::InvalidDumpPath(format!(
+            "directory {} does not contain {}",
+            path.display(),
+            names.join(" or ")
+        )));
+    }
+
+    Err(MatcherError::InvalidDumpPath(format!(
+        "{} does not exist",
+        path.display()
+    )))
+}
+
 fn open_dump_reader(path: &Path) -> MatchResult<Box<dyn BufRead>> {
     let file = File::open(path)?;
     if path
@@ -641,17 +1371,17 @@ fn parse_kitsu_dump<R: BufRead>(reader: R) -> MatchResult<Vec<CatalogEntry>> {
             let canonical_title = record
                 .canonical_title
                 .clone()
-                .or_else(|| record.titles.first().cloned())
+                .or_else(|| record.titles.first().map(|(title, _)| title.clone()))
                 .or(fallback_slug)?;
 
             let mut titles = record.titles;
             if let Some(slug) = record.slug {
-                titles.push(slug_to_title(&slug));
+                titles.push((slug_to_title(&slug), AliasSource::Slug));
             }
 
             let mut ids = ids_by_anime.remove(&kitsu_id).unwrap_or_default();
             ids.kitsu = Some(kitsu_id);
-            CatalogEntry::new(canonical_title, titles, ids)
+            CatalogEntry::new(canonical_title, titles, ids, record.year)
         })
         .collect::<Vec<_>>();
 
@@ -659,6 +1389,77 @@ fn parse_kitsu_dump<R: BufRead>(reader: R) -> MatchResult<Vec<CatalogEntry>> {
     Ok(entries)
 }
 
+fn parse_kitsu_export<R: BufRead>(reader: R) -> MatchResult<Vec<CatalogEntry>> {
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: KitsuExportRecord = serde_json::from_str(&line).map_err(|error| {
+            MatcherError::InvalidDump(format!("export line {}: {error}", index + 1))
+        })?;
+        entries.extend(record.into_entry());
+    }
+
+    if entries.is_empty() {
+        return Err(MatcherError::InvalidDump("export contains no anime".into()));
+    }
+
+    entries.sort_by(|left, right| left.canonical_title.cmp(&right.canonical_title));
+    Ok(entries)
+}
+
+fn parse_offline_database<R: BufRead>(reader: R) -> MatchResult<Vec<CatalogEntry>> {
+    let database: OfflineDatabase = serde_json::from_reader(reader)
+        .map_err(|error| MatcherError::InvalidDump(format!("anime-offline-database: {error}")))?;
+    collect_offline_entries(database.data)
+}
+
+fn parse_offline_database_lines<R: BufRead>(reader: R) -> MatchResult<Vec<CatalogEntry>> {
+    let mut anime = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        anime.push(serde_json::from_str(&line).map_err(|error| {
+            MatcherError::InvalidDump(format!(
+                "anime-offline-database line {}: {error}",
+                index + 1
+            ))
+        })?);
+    }
+    collect_offline_entries(anime)
+}
+
+fn collect_offline_entries(anime: Vec<OfflineAnime>) -> MatchResult<Vec<CatalogEntry>> {
+    let mut entries = anime
+        .into_iter()
+        .filter_map(OfflineAnime::into_entry)
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return Err(MatcherError::InvalidDump(
+            "anime-offline-database contains no anime".into(),
+        ));
+    }
+
+    entries.sort_by(|left, right| left.canonical_title.cmp(&right.canonical_title));
+    Ok(entries)
+}
+
+/// Whether `path` names a `.jsonl` file, gzipped or not.
+fn is_json_lines(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    name.strip_suffix(".gz")
+        .unwrap_or(&name)
+        .ends_with(".jsonl")
+}
+
 fn parse_copy_statement(line: &str) -> Option<(String, Vec<String>)> {
     let rest = line.strip_prefix("COPY ")?;
     let rest = rest.strip_suffix(" FROM stdin;")?;
@@ -697,13 +1498,21 @@ fn parse_anime_row(
 
     if let Some(index) = columns.titles {
         if let Some(value) = field_value(&fields, index) {
-            record.titles.extend(parse_json_titles(value));
+            record.titles.extend(
+                parse_json_titles(value)
+                    .into_iter()
+                    .map(|title| (title, AliasSource::Localized)),
+            );
         }
     }
 
     if let Some(index) = columns.abbreviated_titles {
         if let Some(value) = field_value(&fields, index) {
-            record.titles.extend(parse_json_titles(value));
+            record.titles.extend(
+                parse_json_titles(value)
+                    .into_iter()
+                    .map(|title| (title, AliasSource::Synonym)),
+            );
         }
     }
 
@@ -713,6 +1522,13 @@ fn parse_anime_row(
         }
     }
 
+    if let Some(index) = columns.start_date
+        && let Some(value) = field_value(&fields, index)
+    {
+        // `YYYY-MM-DD`
+        record.year = value.get(..4).and_then(|year| year.parse().ok());
+    }
+
     Ok(())
 }
 
@@ -723,19 +1539,16 @@ fn parse_mapping_row(
 ) -> MatchResult<()> {
     let fields = split_copy_row(line);
     if let Some(index) = columns.item_type {
-        if !field_value(&fields, index)
-            .is_some_and(|value| value.eq_ignore_ascii_case("anime"))
-        {
+        if !field_value(&fields, index).is_some_and(|value| value.eq_ignore_ascii_case("anime")) {
             return Ok(());
         }
     }
 
-    let item_id = match field_value(&fields, columns.item_id)
-        .and_then(|value| value.parse::<u32>().ok())
-    {
-        Some(id) => id,
-        None => return Ok(()),
-    };
+    let item_id =
+        match field_value(&fields, columns.item_id).and_then(|value| value.parse::<u32>().ok()) {
+            Some(id) => id,
+            None => return Ok(()),
+        };
 
     let external_site = match field_value(&fields, columns.external_site) {
         Some(site) => site.to_ascii_lowercase(),
@@ -846,6 +1659,9 @@ query($search: String!, $perPage: Int!) {
         userPreferred
       }
       synonyms
+      startDate {
+        year
+      }
     }
   }
 }
@@ -888,6 +1704,13 @@ struct RemoteMedia {
     id_mal: Option<u32>,
     title: RemoteTitle,
     synonyms: Option<Vec<String>>,
+    #[serde(rename = "startDate")]
+    start_date: Option<RemoteDate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteDate {
+    year: Option<u16>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -907,6 +1730,7 @@ struct RemoteGraphQlError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store::MemoryStore;
     use flate2::Compression;
     use flate2::write::GzEncoder;
     use std::fs;
@@ -947,9 +1771,365 @@ mod tests {
         fs::remove_dir_all(temp_dir).unwrap();
     }
 
+    #[test]
+    fn matches_titles_from_kitsu_export() {
+        let temp_dir = unique_temp_dir();
+        let records = [
+            KitsuExportRecord {
+                kitsu_id: 1,
+                canonical_title: Some("Spy x Family".into()),
+                titles: vec!["Spy x Family".into(), "SPY×FAMILY".into()],
+                synonyms: vec!["Spy Family".into()],
+                anilist_id: Some(777),
+                mal_id: None,
+                start_year: Some(2022),
+            },
+            KitsuExportRecord {
+                kitsu_id: 42,
+                canonical_title: None,
+                titles: vec!["Shingeki no Kyojin".into()],
+                synonyms: vec!["AoT".into()],
+                anilist_id: None,
+                mal_id: Some(16498),
+                start_year: Some(2013),
+            },
+        ];
+        let lines = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(temp_dir.join("catalog.jsonl"), lines).unwrap();
+
+        let matcher = TitleMatcher::from_kitsu_export(&temp_dir).unwrap();
+        let matched = matcher.match_title("aot").unwrap().unwrap();
+        assert_eq!(matched.provider, MatchProvider::KitsuExport);
+        assert_eq!(matched.canonical_title, "Shingeki no Kyojin");
+        assert_eq!(matched.matched_title, "AoT");
+        assert_eq!(matched.alias_source, AliasSource::Synonym);
+        assert_eq!(matched.ids.kitsu, Some(42));
+        assert_eq!(matched.ids.mal, Some(16498));
+
+        fs::write(temp_dir.join("catalog.jsonl"), "{\"titles\": []}").unwrap();
+        assert!(matches!(
+            TitleMatcher::from_kitsu_export(&temp_dir),
+            Err(MatcherError::InvalidDump(_))
+        ));
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn matches_abbreviations_and_aliases() {
+        let entries = [
+            ("Jujutsu Kaisen 2nd Season", vec![]),
+            ("Sword Art Online", vec![]),
+            (
+                "Oshi no Ko",
+                vec![("【Oshi no Ko】".to_string(), AliasSource::Localized)],
+            ),
+        ]
+        .into_iter()
+        .map(|(title, titles)| {
+            CatalogEntry::new(title.to_string(), titles, AnimeIds::default(), None).unwrap()
+        })
+        .collect::<Vec<_>>();
+        let best = |query: &str| {
+            score_entries(
+                MatchProvider::KitsuDump,
+                &entries,
+                query,
+                &normalize_title(query),
+                &SeasonHint::default(),
+                1,
+            )
+            .remove(0)
+        };
+
+        let matched = best("JJK");
+        assert_eq!(matched.canonical_title, "Jujutsu Kaisen 2nd Season");
+        assert_eq!(matched.matched_title, "JJK");
+        assert_eq!(matched.alias_source, AliasSource::FanAbbreviation);
+        assert_eq!(matched.score, 1.0);
+
+        let matched = best("sao");
+        assert_eq!(matched.canonical_title, "Sword Art Online");
+        assert_eq!(matched.alias_source, AliasSource::Acronym);
+        assert_eq!(matched.score, GENERATED_ACRONYM_SCORE);
+        assert_eq!(matched.titles, ["Sword Art Online"]);
+
+        let matched = best("【Oshi no Ko】");
+        assert_eq!(matched.canonical_title, "Oshi no Ko");
+        assert_eq!(matched.alias_source, AliasSource::Canonical);
+        assert_eq!(matched.titles, ["Oshi no Ko"]);
+
+        // Short aliases only count on an exact hit.
+        assert!(best("JJKX").score < 0.9);
+    }
+
+    #[test]
+    fn prefers_the_hinted_season_entry() {
+        let entries = [
+            ("Shingeki no Kyojin", "Attack on Titan", 2013),
+            (
+                "Shingeki no Kyojin Season 3",
+                "Attack on Titan Season 3",
+                2018,
+            ),
+            (
+                "Shingeki no Kyojin Season 3 Part 2",
+                "Attack on Titan Season 3 Part 2",
+                2019,
+            ),
+            (
+                "Shingeki no Kyojin: The Final Season Part 2",
+                "Attack on Titan: The Final Season Part 2",
+                2022,
+            ),
+        ]
+        .into_iter()
+        .map(|(title, english, year)| {
+            let titles = vec![(english.to_string(), AliasSource::Localized)];
+            CatalogEntry::new(title.to_string(), titles, AnimeIds::default(), Some(year)).unwrap()
+        })
+        .collect::<Vec<_>>();
+        let best = |query: &str, hint: SeasonHint| {
+            score_entries(
+                MatchProvider::KitsuDump,
+                &entries,
+                query,
+                &normalize_title(query),
+                &hint,
+                1,
+            )
+            .remove(0)
+        };
+
+        let root = best("Attack on Titan", SeasonHint::new());
+        assert_eq!(root.canonical_title, "Shingeki no Kyojin");
+        assert_eq!((root.season, root.part), (Some(1), Some(1)));
+
+        let matched = best("Attack on Titan", SeasonHint::new().with_season(3));
+        assert_eq!(matched.canonical_title, "Shingeki no Kyojin Season 3");
+
+        let matched = best(
+            "Shingeki no Kyojin",
+            SeasonHint::new().with_season(3).with_part(2),
+        );
+        assert_eq!(
+            matched.canonical_title,
+            "Shingeki no Kyojin Season 3 Part 2"
+        );
+
+        let matched = best(
+            "Attack on Titan The Final Season",
+            SeasonHint::new().with_part(2).with_year(2022),
+        );
+        assert_eq!(
+            matched.canonical_title,
+            "Shingeki no Kyojin: The Final Season Part 2"
+        );
+        assert_eq!((matched.season, matched.year), (None, Some(2022)));
+    }
+
+    #[test]
+    fn custom_aliases_point_at_the_same_entry() {
+        let temp_dir = unique_temp_dir();
+        fs::write(temp_dir.join("latest.sql"), sample_dump()).unwrap();
+
+        let matcher = TitleMatcher::from_kitsu_dump(&temp_dir)
+            .unwrap()
+            .with_alias("frieren", "Furiren");
+        let matched = matcher.match_title("furiren").unwrap().unwrap();
+        assert_eq!(matched.canonical_title, "Sousou no Frieren");
+        assert_eq!(matched.matched_title, "Furiren");
+        assert_eq!(matched.alias_source, AliasSource::Custom);
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn cached_matches_skip_scoring() {
+        let temp_dir = unique_temp_dir();
+        fs::write(temp_dir.join("latest.sql"), sample_dump()).unwrap();
+
+        let cache = ResolutionCache::new(std::num::NonZeroUsize::new(8).unwrap());
+        let matcher = TitleMatcher::from_kitsu_dump(&temp_dir)
+            .unwrap()
+            .with_cache(cache);
+        let first = matcher.match_title("frieren").unwrap().unwrap();
+        assert_eq!(matcher.cache().unwrap().get("frieren"), Some(first.clone()));
+        assert_eq!(matcher.match_title("frieren").unwrap(), Some(first));
+
+        let matcher = matcher.with_alias("frieren", "Furiren");
+        assert!(matcher.cache().unwrap().is_empty());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn matches_titles_from_offline_database() {
+        let temp_dir = unique_temp_dir();
+        let database = r#"{
+            "license": {"name": "ODbL-1.0"},
+            "lastUpdate": "2026-10-12",
+            "data": [
+                {
+                    "sources": [
+                        "https://anilist.co/anime/154587",
+                        "https://kitsu.app/anime/46474",
+                        "https://myanimelist.net/anime/52991"
+                    ],
+                    "title": "Sousou no Frieren",
+                    "type": "TV",
+                    "animeSeason": {"season": "FALL", "year": 2023},
+                    "synonyms": ["Frieren: Beyond Journey's End", "葬送のフリーレン"]
+                },
+                {
+                    "sources": ["https://myanimelist.net/anime/16498"],
+                    "title": "Shingeki no Kyojin",
+                    "animeSeason": {"season": "SPRING", "year": 2013},
+                    "synonyms": ["Attack on Titan"]
+                }
+            ]
+        }"#;
+        write_gzip(
+            &temp_dir.join("anime-offline-database-minified.json.gz"),
+            database.as_bytes(),
+        );
+
+        let matcher = TitleMatcher::from_offline_database(&temp_dir).unwrap();
+        let matched = matcher
+            .match_title("Beyond Journey's End")
+            .unwrap()
+            .unwrap();
+        assert_eq!(matched.provider, MatchProvider::OfflineDatabase);
+        assert_eq!(matched.canonical_title, "Sousou no Frieren");
+        assert_eq!(matched.alias_source, AliasSource::Synonym);
+        assert_eq!(matched.year, Some(2023));
+        assert_eq!(
+            matched.ids,
+            AnimeIds {
+                kitsu: Some(46474),
+                anilist: Some(154587),
+                mal: Some(52991),
+            }
+        );
+
+        let lines = temp_dir.join("anime-offline-database.jsonl");
+        fs::write(
+            &lines,
+            "{\"$schema\": \"schema.json\", \"lastUpdate\": \"2026-10-12\"}\n\
+             {\"sources\": [\"https://kitsu.io/anime/7442\"], \"title\": \"Attack on Titan\"}\n",
+        )
+        .unwrap();
+        let matcher = TitleMatcher::from_offline_database(&lines).unwrap();
+        let matched = matcher.match_title("attack on titan").unwrap().unwrap();
+        assert_eq!(matched.ids.kitsu, Some(7442));
+
+        fs::write(&lines, "{\"lastUpdate\": \"2026-10-12\"}\n").unwrap();
+        assert!(matches!(
+            TitleMatcher::from_offline_database(&lines),
+            Err(MatcherError::InvalidDump(_))
+        ));
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn ranks_candidates_and_flags_ambiguous_results() {
+        let temp_dir = unique_temp_dir();
+        fs::write(temp_dir.join("latest.sql"), sample_dump()).unwrap();
+
+        let matcher = TitleMatcher::from_kitsu_dump(&temp_dir).unwrap();
+        let ranked = matcher.rank_titles("spy x family", 5).unwrap();
+        assert!(!ranked.ambiguous);
+        let best = ranked.confident().unwrap();
+        assert_eq!(best.title, "Spy x Family");
+        assert_eq!(best.id.kitsu, Some(1));
+        assert!(best.semantic_score > 0.99 && best.lexical_score > 0.99);
+        assert!(
+            ranked
+                .candidates
+                .windows(2)
+                .all(|pair| pair[0].combined >= pair[1].combined)
+        );
+
+        let strict = TitleMatcher::from_kitsu_dump(&temp_dir)
+            .unwrap()
+            .with_confidence_threshold(0.99);
+        let ranked = strict.rank_titles("frieren journey", 5).unwrap();
+        assert!(ranked.ambiguous);
+        assert!(ranked.confident().is_none());
+        assert_eq!(ranked.candidates[0].title, "Sousou no Frieren");
+
+        let records = [1, 2].map(|kitsu_id| KitsuExportRecord {
+            kitsu_id,
+            canonical_title: Some("Hunter x Hunter".into()),
+            titles: Vec::new(),
+            synonyms: Vec::new(),
+            anilist_id: None,
+            mal_id: None,
+            start_year: None,
+        });
+        let lines = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(temp_dir.join("catalog.jsonl"), lines).unwrap();
+        let matcher = TitleMatcher::from_kitsu_export(&temp_dir).unwrap();
+        let ranked = matcher.rank_titles("hunter x hunter", 5).unwrap();
+        assert_eq!(ranked.candidates.len(), 2);
+        assert!(ranked.ambiguous);
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn confirmed_resolutions_override_matching() {
+        let temp_dir = unique_temp_dir();
+        fs::write(temp_dir.join("latest.sql"), sample_dump()).unwrap();
+
+        let matcher = TitleMatcher::from_kitsu_dump(&temp_dir)
+            .unwrap()
+            .with_store(MemoryStore::new());
+        let frieren = matcher.match_title("frieren").unwrap().unwrap();
+        let spy = matcher.match_title("spy x family").unwrap().unwrap();
+
+        let season_two = SeasonHint::new().with_season(2);
+        matcher
+            .confirm("Spy x Family", &season_two, frieren.clone())
+            .unwrap();
+        assert_eq!(matcher.match_title("SPY x FAMILY").unwrap(), Some(spy));
+        assert_eq!(
+            matcher
+                .match_title_for_season("SPY x FAMILY", &season_two)
+                .unwrap(),
+            Some(frieren)
+        );
+
+        assert!(matcher.forget("spy x family", &season_two).unwrap());
+        assert!(!matcher.forget("spy x family", &season_two).unwrap());
+        assert_eq!(
+            matcher
+                .match_title_for_season("spy x family", &season_two)
+                .unwrap()
+                .unwrap()
+                .canonical_title,
+            "Spy x Family"
+        );
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
     #[test]
     fn normalizes_titles_before_scoring() {
         assert_eq!(normalize_title("SPY×FAMILY"), "spyxfamily");
+        assert_eq!(
+            normalize_title("【Oshi no Ko】 2nd Season"),
+            "oshi no ko 2nd season"
+        );
         assert!(similarity_score("spy x family", "spy family") > 0.75);
         assert!(similarity_score("jujutsu kaisen", "bleach") < 0.50);
     }
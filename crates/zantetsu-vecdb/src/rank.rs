@@ -0,0 +1,102 @@
+use crate::index::{dot, embed_title};
+use crate::matcher::{AnimeIds, AnimeTitleMatch};
+use serde::{Deserialize, Serialize};
+
+/// Default [`TitleMatcher::with_confidence_threshold`](crate::TitleMatcher::with_confidence_threshold).
+pub const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.75;
+/// A runner-up for a different anime within this much of the best
+/// candidate's combined score makes the result ambiguous.
+pub const AMBIGUITY_MARGIN: f32 = 0.05;
+/// Share of the combined score that comes from embedding similarity.
+const SEMANTIC_WEIGHT: f32 = 0.3;
+
+/// One ranked candidate for a query title.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TitleMatch {
+    /// Known IDs for the candidate anime.
+    pub id: AnimeIds,
+    /// Canonical title of the candidate anime.
+    pub title: String,
+    /// Best cosine similarity between the query's and an alias's
+    /// [embedding](crate::embed_title), in `[0.0, 1.0]`.
+    pub semantic_score: f32,
+    /// Fuzzy string similarity of the best alias, in `[0.0, 1.0]`; the
+    /// `score` of [`TitleMatch::matched`].
+    pub lexical_score: f32,
+    /// Weighted blend of both scores, used for ranking.
+    pub combined: f32,
+    /// The underlying match, with the alias that scored and the catalog
+    /// details.
+    pub matched: AnimeTitleMatch,
+}
+
+/// Candidates for a query title, best first, and whether they need a human
+/// to pick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedMatches {
+    /// Candidates ordered by descending [`TitleMatch::combined`].
+    pub candidates: Vec<TitleMatch>,
+    /// The best candidate scored below the confidence threshold, or a
+    /// different anime scored within [`AMBIGUITY_MARGIN`] of it. Also set
+    /// when nothing matched.
+    pub ambiguous: bool,
+}
+
+impl RankedMatches {
+    /// The best candidate, unless the result is ambiguous.
+    #[must_use]
+    pub fn confident(&self) -> Option<&TitleMatch> {
+        if self.ambiguous {
+            None
+        } else {
+            self.candidates.first()
+        }
+    }
+}
+
+/// Adds semantic scores to `matches`, re-ranks them by the combined score
+/// and flags the result against `threshold`.
+pub(crate) fn rank_matches(
+    query: &str,
+    matches: Vec<AnimeTitleMatch>,
+    threshold: f32,
+) -> RankedMatches {
+    let query_vector = embed_title(query);
+    let mut candidates = matches
+        .into_iter()
+        .map(|matched| {
+            let semantic_score = matched
+                .titles
+                .iter()
+                .chain([&matched.matched_title])
+                .map(|title| dot(&query_vector, &embed_title(title)))
+                .fold(0.0_f32, f32::max)
+                .min(1.0);
+            let lexical_score = matched.score;
+            TitleMatch {
+                id: matched.ids.clone(),
+                title: matched.canonical_title.clone(),
+                semantic_score,
+                lexical_score,
+                combined: SEMANTIC_WEIGHT * semantic_score
+                    + (1.0 - SEMANTIC_WEIGHT) * lexical_score,
+                matched,
+            }
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|left, right| right.combined.total_cmp(&left.combined));
+
+    let ambiguous = match candidates.as_slice() {
+        [] => true,
+        [best, rest @ ..] => {
+            best.combined < threshold
+                || rest.iter().any(|other| {
+                    other.id != best.id && best.combined - other.combined < AMBIGUITY_MARGIN
+                })
+        }
+    };
+    RankedMatches {
+        candidates,
+        ambiguous,
+    }
+}
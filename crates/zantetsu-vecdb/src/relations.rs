@@ -0,0 +1,292 @@
+use crate::error::{MatchResult, MatcherError};
+use crate::matcher::AnimeIds;
+use std::fs;
+use std::path::Path;
+
+/// An inclusive span of episode numbers; `end` is `None` for open ranges (`13-?`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeInterval {
+    /// First episode of the span.
+    pub start: u32,
+    /// Last episode of the span, if bounded.
+    pub end: Option<u32>,
+}
+
+impl EpisodeInterval {
+    /// Returns `true` if `episode` falls within the span.
+    pub fn contains(&self, episode: u32) -> bool {
+        episode >= self.start && self.end.is_none_or(|end| episode <= end)
+    }
+}
+
+/// A single redirect rule, e.g. `- 10863|9919|11237:14-26 -> 11061|11739|11665:1-13`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationRule {
+    /// Entry whose absolute numbering is being redirected.
+    pub source: AnimeIds,
+    /// Episodes of the source entry covered by this rule.
+    pub source_episodes: EpisodeInterval,
+    /// Entry the episodes actually belong to.
+    pub destination: AnimeIds,
+    /// Matching episodes of the destination entry.
+    pub destination_episodes: EpisodeInterval,
+}
+
+impl RelationRule {
+    fn redirect(&self, episode: u32) -> u32 {
+        self.destination_episodes.start + (episode - self.source_episodes.start)
+    }
+}
+
+/// An absolute episode number resolved to its season entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeasonalEpisode {
+    /// Identifiers of the entry the episode belongs to.
+    pub ids: AnimeIds,
+    /// One-based season index within the franchise.
+    pub season: u32,
+    /// Episode number relative to the start of that season.
+    pub episode: u32,
+}
+
+/// Episode offset mappings loaded from the
+/// [anime-relations](https://github.com/erengy/anime-relations) format.
+///
+/// Used to turn absolute numbering (`One Piece - 1084`) into season and
+/// episode pairs for indexers that expect seasonal numbering.
+#[derive(Debug, Clone, Default)]
+pub struct AnimeRelations {
+    rules: Vec<RelationRule>,
+}
+
+impl AnimeRelations {
+    /// Parses an anime-relations document.
+    ///
+    /// Only the `::rules` section is read; `::meta` and comments are skipped.
+    /// A trailing `!` also redirects the destination's own absolute numbers.
+    pub fn parse(text: &str) -> MatchResult<Self> {
+        let mut rules = Vec::new();
+        let mut in_rules = false;
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix("::") {
+                in_rules = section.trim() == "rules";
+                continue;
+            }
+            if !in_rules {
+                continue;
+            }
+
+            let rule = line
+                .strip_prefix('-')
+                .ok_or_else(|| invalid_rule(index, line))?
+                .trim();
+            let (rule, self_redirect) = match rule.strip_suffix('!') {
+                Some(rule) => (rule, true),
+                None => (rule, false),
+            };
+            let (source, destination) = rule
+                .split_once("->")
+                .ok_or_else(|| invalid_rule(index, line))?;
+
+            let (source, source_episodes) =
+                parse_side(source.trim(), None).ok_or_else(|| invalid_rule(index, line))?;
+            let (destination, destination_episodes) = parse_side(destination.trim(), Some(&source))
+                .ok_or_else(|| invalid_rule(index, line))?;
+
+            let rule = RelationRule {
+                source,
+                source_episodes,
+                destination,
+                destination_episodes,
+            };
+            if self_redirect {
+                rules.push(RelationRule {
+                    source: rule.destination.clone(),
+                    ..rule.clone()
+                });
+            }
+            rules.push(rule);
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Reads and parses an anime-relations file (usually `anime-relations.txt`).
+    pub fn from_path(path: impl AsRef<Path>) -> MatchResult<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// All loaded rules, including those expanded from `!` suffixes.
+    pub fn rules(&self) -> &[RelationRule] {
+        &self.rules
+    }
+
+    /// Redirects an episode of `ids` to the entry it belongs to, if a rule applies.
+    pub fn redirect(&self, ids: &AnimeIds, episode: u32) -> Option<(AnimeIds, u32)> {
+        self.rules
+            .iter()
+            .find(|rule| same_anime(&rule.source, ids) && rule.source_episodes.contains(episode))
+            .map(|rule| (rule.destination.clone(), rule.redirect(episode)))
+    }
+
+    /// Converts an absolute episode of `ids` into a season and episode pair.
+    ///
+    /// Seasons are numbered by ordering the base entry's outgoing redirects:
+    /// episodes before the first redirect are season 1, the first redirect
+    /// target is season 2, and so on. Returns `None` when no rule mentions
+    /// the entry.
+    pub fn to_seasonal(&self, ids: &AnimeIds, episode: u32) -> Option<SeasonalEpisode> {
+        let mut outgoing: Vec<&RelationRule> = self
+            .rules
+            .iter()
+            .filter(|rule| same_anime(&rule.source, ids) && !same_anime(&rule.destination, ids))
+            .collect();
+        if outgoing.is_empty() {
+            return None;
+        }
+        outgoing.sort_by_key(|rule| rule.source_episodes.start);
+
+        match outgoing
+            .iter()
+            .position(|rule| rule.source_episodes.contains(episode))
+        {
+            Some(index) => {
+                let rule = outgoing[index];
+                Some(SeasonalEpisode {
+                    ids: rule.destination.clone(),
+                    season: index as u32 + 2,
+                    episode: rule.redirect(episode),
+                })
+            }
+            None if episode < outgoing[0].source_episodes.start => Some(SeasonalEpisode {
+                ids: ids.clone(),
+                season: 1,
+                episode,
+            }),
+            None => None,
+        }
+    }
+}
+
+fn invalid_rule(index: usize, line: &str) -> MatcherError {
+    MatcherError::InvalidRelations(format!("line {}: {line}", index + 1))
+}
+
+/// Parses `mal|kitsu|anilist:episodes`; `~` copies the id from `inherit`.
+fn parse_side(side: &str, inherit: Option<&AnimeIds>) -> Option<(AnimeIds, EpisodeInterval)> {
+    let (ids, episodes) = side.split_once(':')?;
+    let mut parts = ids.split('|').map(str::trim);
+    let mal = parse_id(parts.next()?, inherit.and_then(|ids| ids.mal))?;
+    let kitsu = parse_id(parts.next()?, inherit.and_then(|ids| ids.kitsu))?;
+    let anilist = parse_id(parts.next()?, inherit.and_then(|ids| ids.anilist))?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some((
+        AnimeIds {
+            kitsu,
+            anilist,
+            mal,
+        },
+        parse_interval(episodes.trim())?,
+    ))
+}
+
+fn parse_id(raw: &str, inherited: Option<u32>) -> Option<Option<u32>> {
+    match raw {
+        "?" => Some(None),
+        "~" => Some(inherited),
+        _ => raw.parse().ok().map(Some),
+    }
+}
+
+fn parse_interval(raw: &str) -> Option<EpisodeInterval> {
+    let Some((start, end)) = raw.split_once('-') else {
+        let episode = raw.parse().ok()?;
+        return Some(EpisodeInterval {
+            start: episode,
+            end: Some(episode),
+        });
+    };
+
+    let start = start.trim().parse().ok()?;
+    let end = match end.trim() {
+        "?" => None,
+        end => Some(end.parse().ok()?),
+    };
+    Some(EpisodeInterval { start, end })
+}
+
+fn same_anime(left: &AnimeIds, right: &AnimeIds) -> bool {
+    let shared = |l: Option<u32>, r: Option<u32>| matches!((l, r), (Some(l), Some(r)) if l == r);
+    shared(left.mal, right.mal)
+        || shared(left.kitsu, right.kitsu)
+        || shared(left.anilist, right.anilist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+::meta
+- version: 1.3.0
+
+::rules
+# Shingeki no Kyojin
+- 16498|7442|16498:26-37 -> 25777|8671|20958:1-12
+- 16498|7442|16498:38-59 -> 35760|13569|99147:1-22!
+# Open-ended
+- 21|12|21:1000-? -> ~|~|~:1000-?
+";
+
+    fn ids(mal: u32) -> AnimeIds {
+        AnimeIds {
+            mal: Some(mal),
+            ..AnimeIds::default()
+        }
+    }
+
+    #[test]
+    fn parses_rules_and_expands_self_redirects() {
+        let relations = AnimeRelations::parse(SAMPLE).unwrap();
+        assert_eq!(relations.rules().len(), 4);
+
+        let (target, episode) = relations.redirect(&ids(35760), 40).unwrap();
+        assert_eq!(target.mal, Some(35760));
+        assert_eq!(episode, 3);
+
+        let open = relations.rules().last().unwrap();
+        assert_eq!(open.destination.kitsu, Some(12));
+        assert_eq!(open.source_episodes.end, None);
+    }
+
+    #[test]
+    fn converts_absolute_to_seasonal() {
+        let relations = AnimeRelations::parse(SAMPLE).unwrap();
+
+        let first = relations.to_seasonal(&ids(16498), 5).unwrap();
+        assert_eq!((first.season, first.episode), (1, 5));
+
+        let second = relations.to_seasonal(&ids(16498), 30).unwrap();
+        assert_eq!((second.season, second.episode), (2, 5));
+        assert_eq!(second.ids.anilist, Some(20958));
+
+        let third = relations.to_seasonal(&ids(16498), 59).unwrap();
+        assert_eq!((third.season, third.episode), (3, 22));
+
+        assert!(relations.to_seasonal(&ids(1), 5).is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        let err = AnimeRelations::parse("::rules\n- 1|2|3:1-12 => 4|5|6:1-12").unwrap_err();
+        assert!(matches!(err, MatcherError::InvalidRelations(_)));
+    }
+}
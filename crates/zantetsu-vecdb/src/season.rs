@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+
+/// How much of a season-aware match score comes from agreeing with the
+/// [`SeasonHint`]; the rest is title similarity.
+pub(crate) const SEASON_WEIGHT: f32 = 0.2;
+
+/// Season details parsed alongside a title, used to prefer the catalog entry
+/// for that specific season over the franchise root.
+///
+/// # Examples
+///
+/// ```rust
+/// use zantetsu_vecdb::SeasonHint;
+///
+/// // "Shingeki no Kyojin - The Final Season Part 2 (2022)"
+/// let hint = SeasonHint::new().with_part(2).with_year(2022);
+/// assert!(!hint.is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonHint {
+    /// Season number.
+    pub season: Option<u32>,
+    /// Split-cour or split-season part number.
+    pub part: Option<u32>,
+    /// Release year.
+    pub year: Option<u16>,
+}
+
+impl SeasonHint {
+    /// A hint with nothing known.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the season number.
+    #[must_use]
+    pub fn with_season(mut self, season: u32) -> Self {
+        self.season = Some(season);
+        self
+    }
+
+    /// Set the part number.
+    #[must_use]
+    pub fn with_part(mut self, part: u32) -> Self {
+        self.part = Some(part);
+        self
+    }
+
+    /// Set the release year.
+    #[must_use]
+    pub fn with_year(mut self, year: u16) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    /// Returns `true` if no field is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.season.is_none() && self.part.is_none() && self.year.is_none()
+    }
+
+    /// How well an entry fits this hint, in `[0.0, 1.0]`: the mean over the
+    /// hinted fields of 1.0 for agreement and 0.0 for disagreement. Unknown
+    /// entry values count 0.5; a release year one off counts 0.5, since
+    /// winter seasons straddle the new year.
+    pub(crate) fn fit(&self, season: Option<u32>, part: Option<u32>, year: Option<u16>) -> f32 {
+        fn agree<T: PartialEq>(hint: Option<T>, entry: Option<T>) -> Option<f32> {
+            let hint = hint?;
+            Some(entry.map_or(0.5, |entry| if entry == hint { 1.0 } else { 0.0 }))
+        }
+
+        let year = self.year.map(|hint| {
+            year.map_or(0.5, |year| match year.abs_diff(hint) {
+                0 => 1.0,
+                1 => 0.5,
+                _ => 0.0,
+            })
+        });
+        let scores = [agree(self.season, season), agree(self.part, part), year];
+        let (sum, count) = scores
+            .into_iter()
+            .flatten()
+            .fold((0.0, 0), |(sum, count), score| (sum + score, count + 1));
+        if count == 0 { 0.5 } else { sum / count as f32 }
+    }
+}
+
+/// Season and part numbers written in a normalized title, and the title
+/// without them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SeasonMarkers {
+    /// The title with every marker removed.
+    pub base: String,
+    pub season: Option<u32>,
+    pub part: Option<u32>,
+    /// The title says "final season" without a number.
+    pub is_final: bool,
+}
+
+/// Finds `Season 2`, `2nd Season`, `S2`, `Final Season`, `Part 2`, `Cour 2`,
+/// and a trailing `II` or `2` in a normalized title.
+pub(crate) fn season_markers(normalized_title: &str) -> SeasonMarkers {
+    let tokens = normalized_title.split(' ').collect::<Vec<_>>();
+    let mut markers = SeasonMarkers::default();
+    let mut base = Vec::with_capacity(tokens.len());
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = tokens[index];
+        let next = tokens.get(index + 1).copied();
+        let is_last = index + 1 == tokens.len();
+
+        if token == "season"
+            && let Some(number) = next.and_then(parse_number)
+        {
+            markers.season = Some(number);
+            index += 2;
+        } else if next == Some("season")
+            && let Some(number) = parse_ordinal(token)
+        {
+            markers.season = Some(number);
+            index += 2;
+        } else if token == "final" && next == Some("season") {
+            markers.is_final = true;
+            if base.last() == Some(&"the") {
+                base.pop();
+            }
+            index += 2;
+        } else if matches!(token, "part" | "cour")
+            && let Some(number) = next.and_then(parse_number)
+        {
+            markers.part = Some(number);
+            index += 2;
+        } else if index > 0
+            && let Some(number) = token.strip_prefix('s').and_then(parse_number)
+        {
+            markers.season = Some(number);
+            index += 1;
+        } else if index > 0
+            && is_last
+            && markers.season.is_none()
+            && let Some(number) = parse_number(token).filter(|n| (2..=9).contains(n))
+        {
+            markers.season = Some(number);
+            index += 1;
+        } else {
+            base.push(token);
+            index += 1;
+        }
+    }
+
+    markers.base = base.join(" ");
+    markers
+}
+
+/// A small number written in digits or roman numerals.
+fn parse_number(token: &str) -> Option<u32> {
+    let roman = match token {
+        "ii" => Some(2),
+        "iii" => Some(3),
+        "iv" => Some(4),
+        "v" => Some(5),
+        _ => None,
+    };
+    roman.or_else(|| {
+        (!token.is_empty() && token.len() <= 2 && token.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| token.parse().ok())
+            .flatten()
+    })
+}
+
+/// `2nd`, `third`, ...
+fn parse_ordinal(token: &str) -> Option<u32> {
+    match token {
+        "first" => Some(1),
+        "second" => Some(2),
+        "third" => Some(3),
+        "fourth" => Some(4),
+        "fifth" => Some(5),
+        _ => ["st", "nd", "rd", "th"]
+            .iter()
+            .find_map(|suffix| token.strip_suffix(suffix))
+            .and_then(parse_number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_season_and_part_markers() {
+        let markers = season_markers("shingeki no kyojin the final season part 2");
+        assert_eq!(markers.base, "shingeki no kyojin");
+        assert!(markers.is_final);
+        assert_eq!((markers.season, markers.part), (None, Some(2)));
+
+        for title in [
+            "jujutsu kaisen 2nd season",
+            "jujutsu kaisen season 2",
+            "jujutsu kaisen s2",
+            "jujutsu kaisen ii",
+            "jujutsu kaisen 2",
+        ] {
+            let markers = season_markers(title);
+            assert_eq!(markers.base, "jujutsu kaisen", "{title}");
+            assert_eq!(markers.season, Some(2), "{title}");
+        }
+
+        let markers = season_markers("mob psycho 100");
+        assert_eq!(markers.base, "mob psycho 100");
+        assert_eq!(markers.season, None);
+    }
+
+    #[test]
+    fn fit_rewards_agreement() {
+        let hint = SeasonHint::new().with_season(2).with_year(2023);
+        assert_eq!(hint.fit(Some(2), Some(1), Some(2023)), 1.0);
+        assert_eq!(hint.fit(Some(1), Some(1), Some(2020)), 0.0);
+        assert_eq!(hint.fit(None, None, Some(2022)), 0.5);
+        assert_eq!(SeasonHint::new().fit(Some(1), None, None), 0.5);
+    }
+}
@@ -0,0 +1,243 @@
+//! Persistent store of confirmed resolutions.
+//!
+//! Unlike the [`ResolutionCache`](crate::ResolutionCache), which remembers
+//! whatever the matcher answered, a [`ResolutionStore`] holds answers a user
+//! confirmed or corrected with [`TitleMatcher::confirm`]. The matcher
+//! consults it before any fuzzy matching, so a correction sticks across runs.
+//!
+//! [`TitleMatcher::confirm`]: crate::TitleMatcher::confirm
+
+use crate::error::{MatchResult, MatcherError};
+use crate::matcher::{AnimeTitleMatch, normalize_title};
+use crate::season::SeasonHint;
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+/// Key-value storage for confirmed resolutions.
+///
+/// Keys come from [`resolution_key`]; implement this trait to keep
+/// resolutions in an application's own database.
+pub trait ResolutionStore: Send + Sync {
+    /// The resolution stored under `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Store`] if the backend fails.
+    fn get(&self, key: &str) -> MatchResult<Option<AnimeTitleMatch>>;
+
+    /// Store `matched` under `key`, replacing any previous resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Store`] if the backend fails.
+    fn put(&self, key: &str, matched: &AnimeTitleMatch) -> MatchResult<()>;
+
+    /// Remove the resolution under `key`, returning whether there was one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MatcherError::Store`] if the backend fails.
+    fn remove(&self, key: &str) -> MatchResult<bool>;
+}
+
+/// The store key for a query: the normalized title, plus the season hint
+/// when one is given, since the same title may resolve to another season.
+#[must_use]
+pub fn resolution_key(title: &str, hint: &SeasonHint) -> String {
+    let mut key = normalize_title(title);
+    if !hint.is_empty() {
+        let field = |value: Option<String>| value.unwrap_or_default();
+        key.push_str(&format!(
+            "|s{}|p{}|y{}",
+            field(hint.season.map(|season| season.to_string())),
+            field(hint.part.map(|part| part.to_string())),
+            field(hint.year.map(|year| year.to_string())),
+        ));
+    }
+    key
+}
+
+/// A [`ResolutionStore`] that lives only as long as the process.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: RwLock<HashMap<String, AnimeTitleMatch>>,
+}
+
+impl MemoryStore {
+    /// An empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ResolutionStore for MemoryStore {
+    fn get(&self, key: &str) -> MatchResult<Option<AnimeTitleMatch>> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        Ok(entries.get(key).cloned())
+    }
+
+    fn put(&self, key: &str, matched: &AnimeTitleMatch) -> MatchResult<()> {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        entries.insert(key.to_string(), matched.clone());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> MatchResult<bool> {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        Ok(entries.remove(key).is_some())
+    }
+}
+
+#[cfg(feature = "redb")]
+pub use redb_store::RedbStore;
+
+#[cfg(feature = "redb")]
+mod redb_store {
+    use super::*;
+    use redb::{Database, TableDefinition, TableError};
+    use std::path::Path;
+
+    const RESOLUTIONS: TableDefinition<&str, &[u8]> = TableDefinition::new("resolutions");
+
+    /// A [`ResolutionStore`] in a [redb](https://docs.rs/redb) database file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use zantetsu_vecdb::{RedbStore, SeasonHint, TitleMatcher};
+    ///
+    /// let matcher = TitleMatcher::from_kitsu_dump("/tmp/kitsu-dumps")
+    ///     .unwrap()
+    ///     .with_store(RedbStore::open("/tmp/resolutions.redb").unwrap());
+    ///
+    /// // The user picked the right entry; remember it for next time.
+    /// let ranked = matcher.rank_titles("Hunter x Hunter", 5).unwrap();
+    /// let chosen = ranked.candidates[1].matched.clone();
+    /// matcher.confirm("Hunter x Hunter", &SeasonHint::new(), chosen).unwrap();
+    /// ```
+    #[derive(Debug)]
+    pub struct RedbStore {
+        database: Database,
+    }
+
+    impl RedbStore {
+        /// Open the database at `path`, creating it if it does not exist.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`MatcherError::Store`] if the file cannot be opened or is
+        /// not a redb database.
+        pub fn open(path: impl AsRef<Path>) -> MatchResult<Self> {
+            let database = Database::create(path).map_err(store_error)?;
+            Ok(Self { database })
+        }
+    }
+
+    impl ResolutionStore for RedbStore {
+        fn get(&self, key: &str) -> MatchResult<Option<AnimeTitleMatch>> {
+            let transaction = self.database.begin_read().map_err(store_error)?;
+            let table = match transaction.open_table(RESOLUTIONS) {
+                Ok(table) => table,
+                Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+                Err(error) => return Err(store_error(error)),
+            };
+            let Some(value) = table.get(key).map_err(store_error)? else {
+                return Ok(None);
+            };
+            serde_json::from_slice(value.value())
+                .map(Some)
+                .map_err(|error| MatcherError::Store(format!("resolution for {key:?}: {error}")))
+        }
+
+        fn put(&self, key: &str, matched: &AnimeTitleMatch) -> MatchResult<()> {
+            let value = serde_json::to_vec(matched)
+                .map_err(|error| MatcherError::Store(error.to_string()))?;
+            let transaction = self.database.begin_write().map_err(store_error)?;
+            {
+                let mut table = transaction.open_table(RESOLUTIONS).map_err(store_error)?;
+                table.insert(key, value.as_slice()).map_err(store_error)?;
+            }
+            transaction.commit().map_err(store_error)
+        }
+
+        fn remove(&self, key: &str) -> MatchResult<bool> {
+            let transaction = self.database.begin_write().map_err(store_error)?;
+            let removed = {
+                let mut table = transaction.open_table(RESOLUTIONS).map_err(store_error)?;
+                table.remove(key).map_err(store_error)?.is_some()
+            };
+            transaction.commit().map_err(store_error)?;
+            Ok(removed)
+        }
+    }
+
+    fn store_error(error: impl Into<redb::Error>) -> MatcherError {
+        MatcherError::Store(error.into().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::{AliasSource, AnimeIds, MatchProvider};
+
+    fn resolution(title: &str, kitsu: u32) -> AnimeTitleMatch {
+        AnimeTitleMatch {
+            provider: MatchProvider::KitsuDump,
+            canonical_title: title.into(),
+            matched_title: title.into(),
+            alias_source: AliasSource::Canonical,
+            score: 1.0,
+            ids: AnimeIds {
+                kitsu: Some(kitsu),
+                ..AnimeIds::default()
+            },
+            season: Some(1),
+            part: Some(1),
+            year: None,
+            titles: vec![title.into()],
+        }
+    }
+
+    #[test]
+    fn keys_include_the_season_hint() {
+        let plain = resolution_key("Jujutsu  KAISEN", &SeasonHint::new());
+        assert_eq!(plain, "jujutsu kaisen");
+        let hinted = resolution_key("Jujutsu Kaisen", &SeasonHint::new().with_season(2));
+        assert_eq!(hinted, "jujutsu kaisen|s2|p|y");
+    }
+
+    #[cfg(feature = "redb")]
+    #[test]
+    fn redb_store_persists_across_reopens() {
+        let path = std::env::temp_dir().join(format!(
+            "zantetsu-store-test-{}-{}.redb",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+
+        let store = RedbStore::open(&path).unwrap();
+        assert_eq!(store.get("hunter x hunter").unwrap(), None);
+        assert!(!store.remove("hunter x hunter").unwrap());
+        store
+            .put(
+                "hunter x hunter",
+                &resolution("Hunter x Hunter (2011)", 6448),
+            )
+            .unwrap();
+        drop(store);
+
+        let store = RedbStore::open(&path).unwrap();
+        let stored = store.get("hunter x hunter").unwrap().unwrap();
+        assert_eq!(stored.ids.kitsu, Some(6448));
+        assert!(store.remove("hunter x hunter").unwrap());
+        assert_eq!(store.get("hunter x hunter").unwrap(), None);
+
+        drop(store);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -16,6 +16,7 @@
 //! - **Character CNN**: CNN + BiLSTM + CRF for robust character-level parsing (in development)
 //! - **Canonical Matching**: Local Kitsu dump matching or remote endpoint lookup
 //! - **Quality Scoring**: Configurable quality profiles for release validation
+//! - **Resolution**: [`resolve`] a filename to its canonical anime, IDs and quality score in one call
 //!
 //! ## Quick Start
 //!
@@ -42,15 +43,24 @@
 //!
 //! The engine automatically selects the best parser based on availability and confidence.
 //!
-pub use zantetsu_core::error::{Result, ZantetsuError};
+mod resolve;
+
+pub use resolve::{ResolveError, ResolvedMedia, resolve};
+pub use zantetsu_core::crf::model::ModelPrecision;
+pub use zantetsu_core::error::{ConfigError, Result, ZantetsuError};
 pub use zantetsu_core::parser::{HeuristicParser, NeuralParser};
-pub use zantetsu_core::scoring::{QualityProfile, QualityScores};
+pub use zantetsu_core::scoring::{
+    GroupBlocklist, GroupRegistry, Preset, QualityProfile, QualityScores,
+};
 pub use zantetsu_core::types::{
-    AudioCodec, EpisodeSpec, MediaSource, ParseMode, ParseResult, Resolution, VideoCodec,
+    AudioCodec, AudioTrack, EpisodeNumbering, EpisodeSpec, FieldConfidence, MediaSource,
+    ParseEpisodeSpecError, ParseField, ParseMode, ParseResult, ParseWarning, Resolution,
+    SeasonSpec, VideoCodec,
 };
 pub use zantetsu_vecdb::{
-    AnimeIds, AnimeTitleMatch, MatchProvider, MatchSource, MatchResult, MatcherError,
-    TitleMatcher, default_kitsu_dump_dir,
+    AliasSource, AnimeIds, AnimeRelations, AnimeTitleMatch, KitsuExportRecord, MatchProvider,
+    MatchSource, MatchResult, MatcherError, MemoryStore, RankedMatches, ResolutionCache,
+    ResolutionStore, SeasonHint, SeasonalEpisode, TitleMatch, TitleMatcher, default_kitsu_dump_dir,
 };
 
 /// Main entry point for the Zantetsu parsing engine.
@@ -59,6 +69,8 @@ pub use zantetsu_vecdb::{
 pub struct Zantetsu {
     heuristic: HeuristicParser,
     neural: Option<NeuralParser>,
+    matcher: Option<TitleMatcher>,
+    profile: QualityProfile,
 }
 
 impl Zantetsu {
@@ -70,7 +82,27 @@ impl Zantetsu {
         let heuristic = HeuristicParser::new()?;
         let neural = NeuralParser::new().ok();
 
-        Ok(Self { heuristic, neural })
+        Ok(Self {
+            heuristic,
+            neural,
+            matcher: None,
+            profile: QualityProfile::default(),
+        })
+    }
+
+    /// Match parsed titles against `matcher` in [`resolve`](Self::resolve).
+    #[must_use]
+    pub fn with_matcher(mut self, matcher: TitleMatcher) -> Self {
+        self.matcher = Some(matcher);
+        self
+    }
+
+    /// Weigh quality scores in [`resolve`](Self::resolve) with `profile`
+    /// instead of the default profile.
+    #[must_use]
+    pub fn with_quality_profile(mut self, profile: QualityProfile) -> Self {
+        self.profile = profile;
+        self
     }
 
     /// Parse an anime filename using the best available parser.
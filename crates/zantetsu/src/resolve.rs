@@ -0,0 +1,210 @@
+//! Filename → canonical anime resolution.
+//!
+//! Chains the parser, canonical title matching and quality scoring, which
+//! callers would otherwise wire together by hand.
+
+use crate::{
+    AnimeIds, AnimeTitleMatch, MatcherError, ParseField, ParseResult, QualityScores, SeasonHint,
+    TitleMatcher, Zantetsu, ZantetsuError, default_kitsu_dump_dir,
+};
+use std::sync::OnceLock;
+
+/// Errors returned while resolving a filename.
+#[derive(Debug, thiserror::Error)]
+pub enum ResolveError {
+    /// The filename could not be parsed.
+    #[error(transparent)]
+    Parse(#[from] ZantetsuError),
+
+    /// The title matcher could not be created or queried.
+    #[error(transparent)]
+    Match(#[from] MatcherError),
+}
+
+/// A parsed filename together with its canonical anime and quality score.
+#[derive(Debug, Clone)]
+pub struct ResolvedMedia {
+    /// Everything the parser extracted.
+    pub parse: ParseResult,
+    /// The catalog entry for the parsed title, if one matched.
+    pub matched: Option<AnimeTitleMatch>,
+    /// Per-dimension quality scores of the release.
+    pub quality: QualityScores,
+    /// Weighted quality score in `[0.0, 1.0]` under the engine's profile.
+    pub quality_score: f32,
+    /// How sure the resolution is, in `[0.0, 1.0]`: the match score
+    /// weighted by the parser's confidence in the title. `0.0` without a
+    /// match.
+    pub match_confidence: f32,
+}
+
+impl ResolvedMedia {
+    /// Canonical IDs of the matched anime.
+    #[must_use]
+    pub fn ids(&self) -> Option<&AnimeIds> {
+        self.matched.as_ref().map(|matched| &matched.ids)
+    }
+
+    /// Canonical title of the matched anime.
+    #[must_use]
+    pub fn canonical_title(&self) -> Option<&str> {
+        self.matched
+            .as_ref()
+            .map(|matched| matched.canonical_title.as_str())
+    }
+}
+
+impl Zantetsu {
+    /// Parse `input`, match its title against the engine's
+    /// [matcher](Self::with_matcher) and score its quality.
+    ///
+    /// The parsed season, part and year steer the match towards the right
+    /// season entry. Without a matcher, or without a parsed title,
+    /// [`ResolvedMedia::matched`] is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ResolveError::Parse`] if parsing fails and
+    /// [`ResolveError::Match`] if the matcher fails.
+    pub fn resolve(&self, input: &str) -> Result<ResolvedMedia, ResolveError> {
+        let parse = self.parse(input)?;
+
+        let matched = match (&self.matcher, parse.title.as_deref()) {
+            (Some(matcher), Some(title)) if !title.trim().is_empty() => {
+                let hint = season_hint(&parse);
+                if hint.is_empty() {
+                    matcher.match_title(title)?
+                } else {
+                    matcher.match_title_for_season(title, &hint)?
+                }
+            }
+            _ => None,
+        };
+
+        let title_confidence = parse
+            .field_confidence
+            .get(ParseField::Title)
+            .unwrap_or(parse.confidence);
+        let match_confidence = matched
+            .as_ref()
+            .map_or(0.0, |matched| matched.score * title_confidence);
+
+        let quality = self.score(&parse, &self.profile);
+        let quality_score = quality.compute(&self.profile);
+
+        Ok(ResolvedMedia {
+            parse,
+            matched,
+            quality,
+            quality_score,
+            match_confidence,
+        })
+    }
+}
+
+/// Resolve `input` with a process-wide engine matching against the Kitsu
+/// dump in [`default_kitsu_dump_dir`].
+///
+/// The engine is created on the first successful call and reused after.
+/// Build a [`Zantetsu`] with [`with_matcher`](Zantetsu::with_matcher) for
+/// any other catalog.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// let resolved = zantetsu::resolve("[SubsPlease] Sousou no Frieren - 01 (1080p).mkv").unwrap();
+/// println!("{:?} {:?}", resolved.canonical_title(), resolved.ids());
+/// ```
+///
+/// # Errors
+///
+/// Returns [`ResolveError::Match`] if the dump cannot be loaded, and the
+/// errors of [`Zantetsu::resolve`].
+pub fn resolve(input: &str) -> Result<ResolvedMedia, ResolveError> {
+    static ENGINE: OnceLock<Zantetsu> = OnceLock::new();
+
+    let engine = match ENGINE.get() {
+        Some(engine) => engine,
+        None => {
+            let matcher = TitleMatcher::from_kitsu_dump(default_kitsu_dump_dir())?;
+            let engine = Zantetsu::new()?.with_matcher(matcher);
+            ENGINE.get_or_init(|| engine)
+        }
+    };
+    engine.resolve(input)
+}
+
+fn season_hint(parse: &ParseResult) -> SeasonHint {
+    SeasonHint {
+        season: parse.season.map(|season| season.first()),
+        part: parse.part,
+        year: parse.year,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KitsuExportRecord;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn resolves_filenames_to_canonical_ids() {
+        let dir = std::env::temp_dir().join(format!(
+            "zantetsu-resolve-test-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let records = [
+            ("Cowboy Bebop", 1, Some(1998)),
+            ("Jujutsu Kaisen", 42765, Some(2020)),
+            ("Jujutsu Kaisen 2nd Season", 45857, Some(2023)),
+        ]
+        .map(|(title, kitsu_id, start_year)| KitsuExportRecord {
+            kitsu_id,
+            canonical_title: Some(title.into()),
+            titles: vec![title.into()],
+            synonyms: Vec::new(),
+            anilist_id: None,
+            mal_id: None,
+            start_year,
+        });
+        let lines = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(dir.join("catalog.jsonl"), lines).unwrap();
+
+        let engine = Zantetsu::new()
+            .unwrap()
+            .with_matcher(TitleMatcher::from_kitsu_export(&dir).unwrap());
+
+        let resolved = engine
+            .resolve("[SubsPlease] Cowboy Bebop - 01 [1080p][HEVC].mkv")
+            .unwrap();
+        assert_eq!(resolved.canonical_title(), Some("Cowboy Bebop"));
+        assert_eq!(resolved.ids().unwrap().kitsu, Some(1));
+        assert!(resolved.match_confidence > 0.5);
+        assert!((0.0..=1.0).contains(&resolved.quality_score));
+
+        let resolved = engine
+            .resolve("[SubsPlease] Jujutsu Kaisen S2 - 05 (1080p).mkv")
+            .unwrap();
+        assert_eq!(resolved.ids().unwrap().kitsu, Some(45857));
+
+        let unmatched = Zantetsu::new()
+            .unwrap()
+            .resolve("[SubsPlease] Cowboy Bebop - 01 [1080p].mkv")
+            .unwrap();
+        assert!(unmatched.matched.is_none());
+        assert_eq!(unmatched.match_confidence, 0.0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+//! Ground-truth accuracy: score an engine's parses against labeled
+//! filenames.
+
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::ParseOutput;
+
+/// Fields of [`ParseOutput`] a label can check.
+pub const FIELDS: [&str; 12] = [
+    "title",
+    "group",
+    "season",
+    "episode",
+    "resolution",
+    "video_codec",
+    "audio_codec",
+    "source",
+    "year",
+    "crc32",
+    "extension",
+    "version",
+];
+
+/// One labeled filename.
+///
+/// `expected` holds the checked fields in the layout of the parse output.
+/// A field left out is not scored; `null` asserts the field is absent.
+#[derive(Debug, Deserialize)]
+pub struct Label {
+    pub input: String,
+    pub expected: Map<String, Value>,
+}
+
+/// The labels of a JSONL file, one per non-empty line.
+pub fn load_labels(path: &Path) -> io::Result<Vec<Label>> {
+    let invalid = |line: usize, message: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}:{line}: {message}", path.display()),
+        )
+    };
+    let mut labels = Vec::new();
+    for (index, line) in BufReader::new(std::fs::File::open(path)?)
+        .lines()
+        .enumerate()
+    {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let label: Label =
+            serde_json::from_str(&line).map_err(|e| invalid(index + 1, e.to_string()))?;
+        if let Some(field) = label
+            .expected
+            .keys()
+            .find(|f| !FIELDS.contains(&f.as_str()))
+        {
+            return Err(invalid(index + 1, format!("unknown field `{field}`")));
+        }
+        labels.push(label);
+    }
+    Ok(labels)
+}
+
+/// Counts for one field over the labels that check it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FieldScore {
+    /// Labels that check the field.
+    pub labeled: usize,
+    /// Labels with a value for the field.
+    pub support: usize,
+    /// Parses with a value for the field.
+    pub predicted: usize,
+    /// Parses that agree with the label, including on absence.
+    pub correct: usize,
+    /// Parses with the labeled value.
+    pub true_positives: usize,
+}
+
+impl FieldScore {
+    /// Share of labels the parse agrees with.
+    pub fn accuracy(&self) -> f32 {
+        ratio(self.correct, self.labeled)
+    }
+
+    /// Share of values the parser extracted that are right.
+    pub fn precision(&self) -> f32 {
+        ratio(self.true_positives, self.predicted)
+    }
+
+    /// Share of labeled values the parser extracted.
+    pub fn recall(&self) -> f32 {
+        ratio(self.true_positives, self.support)
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f32 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f32 / denominator as f32
+    }
+}
+
+/// Scores of one engine over a labeled set.
+#[derive(Debug, Clone, Default)]
+pub struct AccuracyReport {
+    pub engine: String,
+    pub examples: usize,
+    /// Examples whose every checked field is right.
+    pub exact_matches: usize,
+    /// Examples the engine failed to parse.
+    pub errors: usize,
+    pub fields: BTreeMap<&'static str, FieldScore>,
+}
+
+impl AccuracyReport {
+    pub fn new(engine: &str) -> Self {
+        Self {
+            engine: engine.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Score the parse `output` of `label`'s input.
+    pub fn add(&mut self, label: &Label, output: &ParseOutput) {
+        let parsed = serde_json::to_value(output).unwrap_or_default();
+        self.examples += 1;
+        let mut exact = output.error.is_none();
+        if !exact {
+            self.errors += 1;
+        }
+        for field in FIELDS {
+            let Some(expected) = label.expected.get(field) else {
+                continue;
+            };
+            let actual = &parsed[field];
+            let score = self.fields.entry(field).or_default();
+            score.labeled += 1;
+            score.support += usize::from(!expected.is_null());
+            score.predicted += usize::from(!actual.is_null());
+            if actual == expected {
+                score.correct += 1;
+                score.true_positives += usize::from(!expected.is_null());
+            } else {
+                exact = false;
+            }
+        }
+        self.exact_matches += usize::from(exact);
+    }
+
+    pub fn exact_match_rate(&self) -> f32 {
+        ratio(self.exact_matches, self.examples)
+    }
+
+    /// The report as one JSON object, with rates next to the counts.
+    pub fn to_json(&self) -> Value {
+        let fields: Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|(field, score)| {
+                let value = json!({
+                    "accuracy": score.accuracy(),
+                    "precision": score.precision(),
+                    "recall": score.recall(),
+                    "labeled": score.labeled,
+                    "support": score.support,
+                    "predicted": score.predicted,
+                    "correct": score.correct,
+                });
+                (field.to_string(), value)
+            })
+            .collect();
+        json!({
+            "engine": self.engine,
+            "examples": self.examples,
+            "exact_match": self.exact_match_rate(),
+            "errors": self.errors,
+            "fields": fields,
+        })
+    }
+}
+
+impl fmt::Display for AccuracyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} examples, exact match {:.2}%, {} errors",
+            self.engine,
+            self.examples,
+            self.exact_match_rate() * 100.0,
+            self.errors
+        )?;
+        writeln!(
+            f,
+            "{:<16} {:>9} {:>9} {:>9} {:>8}",
+            "field", "accuracy", "precision", "recall", "support"
+        )?;
+        for field in FIELDS {
+            if let Some(score) = self.fields.get(field) {
+                writeln!(
+                    f,
+                    "{:<16} {:>9.4} {:>9.4} {:>9.4} {:>8}",
+                    field,
+                    score.accuracy(),
+                    score.precision(),
+                    score.recall(),
+                    score.support
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(title: &str, episode: Option<u32>, group: Option<&str>) -> ParseOutput {
+        let mut output = ParseOutput::from_error(title, "", "heuristic");
+        output.error = None;
+        output.title = Some(title.to_string());
+        output.episode = episode.map(|e| json!({ "Single": e }));
+        output.group = group.map(str::to_string);
+        output
+    }
+
+    fn label(json: Value) -> Label {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn scores_only_the_labeled_fields() {
+        let mut report = AccuracyReport::new("heuristic");
+        let first = label(json!({
+            "input": "a",
+            "expected": {"title": "Frieren", "episode": {"Single": 1}, "group": null},
+        }));
+        report.add(&first, &output("Frieren", Some(1), Some("Subs")));
+        let second =
+            label(json!({"input": "b", "expected": {"title": "Bleach", "episode": {"Single": 2}}}));
+        report.add(&second, &output("Bleach", Some(3), Some("Subs")));
+
+        assert_eq!(report.examples, 2);
+        assert_eq!(report.exact_matches, 0);
+        let episode = report.fields["episode"];
+        assert_eq!(
+            (episode.accuracy(), episode.precision(), episode.recall()),
+            (0.5, 0.5, 0.5)
+        );
+        // Only the first label checks the group, asserting there is none
+        let group = report.fields["group"];
+        assert_eq!(
+            (group.labeled, group.support, group.predicted, group.correct),
+            (1, 0, 1, 0)
+        );
+        assert_eq!(report.fields["title"].accuracy(), 1.0);
+        assert!(!report.fields.contains_key("resolution"));
+    }
+}
@@ -1,7 +1,13 @@
+use accuracy::{AccuracyReport, load_labels};
+use clap::Parser as ClapParser;
 use serde::{Deserialize, Serialize};
-use std::io::{self, BufRead};
+use std::io;
+use std::path::{Path, PathBuf};
+use zantetsu_core::ZantetsuError;
 use zantetsu_core::parser::{Parser, ParserConfig};
-use zantetsu_core::types::ParseMode;
+use zantetsu_core::types::{ParseMode, ParseResult, SeasonSpec};
+
+mod accuracy;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ParseOutput {
@@ -27,176 +33,149 @@ fn episode_to_value(ep: &zantetsu_core::types::EpisodeSpec) -> serde_json::Value
     serde_json::to_value(ep).unwrap_or(serde_json::Value::Null)
 }
 
-fn main() -> std::io::Result<()> {
-    let args: Vec<String> = std::env::args().collect();
-    let mode = args.get(1).map(|s| s.as_str()).unwrap_or("heuristic");
-
-    let stdin = io::stdin();
-
-    if mode == "neural" {
-        let mut parser =
-            zantetsu_core::parser::NeuralParser::new().expect("Failed to create neural parser");
-        parser.init_model().expect("Failed to load safetensors");
+impl ParseOutput {
+    fn from_result(r: ParseResult, mode: &str) -> Self {
+        ParseOutput {
+            title: r.title,
+            group: r.group,
+            season: r.season.map(SeasonSpec::first),
+            episode: r.episode.as_ref().map(episode_to_value),
+            resolution: r.resolution.as_ref().map(|x| format!("{:?}", x)),
+            video_codec: r.video_codec.as_ref().map(|x| format!("{:?}", x)),
+            audio_codec: r.audio_codec.as_ref().map(|x| format!("{:?}", x)),
+            source: r.source.as_ref().map(|x| format!("{:?}", x)),
+            year: r.year,
+            crc32: r.crc32,
+            extension: r.extension,
+            version: r.version,
+            confidence: r.confidence,
+            input: r.input,
+            mode: mode.to_string(),
+            error: None,
+        }
+    }
 
-        for line in stdin.lock().lines() {
-            let line = line?;
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
+    fn from_error(input: &str, error: impl std::fmt::Display, mode: &str) -> Self {
+        ParseOutput {
+            input: input.to_string(),
+            title: None,
+            group: None,
+            season: None,
+            episode: None,
+            resolution: None,
+            video_codec: None,
+            audio_codec: None,
+            source: None,
+            year: None,
+            crc32: None,
+            extension: None,
+            version: None,
+            confidence: 0.0,
+            mode: mode.to_string(),
+            error: Some(error.to_string()),
+        }
+    }
+}
 
-            let result = parser.parse(line);
+/// Parse filenames from stdin into JSON lines, or score every engine on
+/// labeled filenames.
+#[derive(ClapParser)]
+#[command(name = "zantetsu-parse")]
+struct Cli {
+    /// Engine for parsing stdin: heuristic, auto or neural.
+    #[arg(default_value = "heuristic")]
+    mode: String,
+    /// JSONL file of `{"input", "expected"}` records to score each engine
+    /// on, instead of parsing stdin.
+    #[arg(long)]
+    labels: Option<PathBuf>,
+    /// Print the accuracy reports as JSON lines.
+    #[arg(long, requires = "labels")]
+    json: bool,
+}
 
-            let output = match result {
-                Ok(r) => ParseOutput {
-                    input: r.input.clone(),
-                    title: r.title.clone(),
-                    group: r.group.clone(),
-                    season: r.season,
-                    episode: r.episode.as_ref().map(episode_to_value),
-                    resolution: r.resolution.as_ref().map(|x| format!("{:?}", x)),
-                    video_codec: r.video_codec.as_ref().map(|x| format!("{:?}", x)),
-                    audio_codec: r.audio_codec.as_ref().map(|x| format!("{:?}", x)),
-                    source: r.source.as_ref().map(|x| format!("{:?}", x)),
-                    year: r.year,
-                    crc32: r.crc32.clone(),
-                    extension: r.extension.clone(),
-                    version: r.version,
-                    confidence: r.confidence,
-                    mode: "neural".to_string(),
-                    error: None,
-                },
-                Err(e) => ParseOutput {
-                    input: line.to_string(),
-                    title: None,
-                    group: None,
-                    season: None,
-                    episode: None,
-                    resolution: None,
-                    video_codec: None,
-                    audio_codec: None,
-                    source: None,
-                    year: None,
-                    crc32: None,
-                    extension: None,
-                    version: None,
-                    confidence: 0.0,
-                    mode: "neural".to_string(),
-                    error: Some(e.to_string()),
-                },
-            };
+/// The parser for `mode` and the name of its engine; unknown modes are
+/// heuristic.
+fn engine(mode: &str) -> (ParserConfig, &'static str) {
+    match mode {
+        "neural" => (ParserConfig::new().with_mode(ParseMode::Full), "neural"),
+        "auto" => (ParserConfig::new().with_mode(ParseMode::Auto), "auto"),
+        _ => (
+            ParserConfig::new()
+                .with_mode(ParseMode::Light)
+                .with_neural(false),
+            "heuristic",
+        ),
+    }
+}
 
-            println!("{}", serde_json::to_string(&output).unwrap());
-        }
-    } else if mode == "auto" {
-        let parser = Parser::new(ParserConfig::new().with_mode(ParseMode::Auto))
-            .expect("Failed to create auto parser");
+fn parse_stdin(mode: &str) -> io::Result<()> {
+    let (config, mode) = engine(mode);
+    let parser = Parser::new(config).expect("Failed to create parser");
+    if mode == "neural" && !parser.has_model() {
+        eprintln!("warning: neural model unavailable, falling back to heuristic parser");
+    }
 
-        for line in stdin.lock().lines() {
-            let line = line?;
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
+    let stdin = io::stdin();
+    let mut lines = parser.parse_lines(stdin.lock());
+
+    // Lines that are not UTF-8 or fail to parse are reported and skipped;
+    // only a failing read of stdin ends the run
+    let mut failed = 0;
+    while let Some(item) = lines.next() {
+        let output = match item {
+            Ok(r) => ParseOutput::from_result(r, mode),
+            Err(ZantetsuError::Io(e)) if e.kind() != io::ErrorKind::InvalidData => return Err(e),
+            Err(e) => {
+                failed += 1;
+                ParseOutput::from_error(lines.current_line(), e, mode)
             }
+        };
 
-            let result = parser.parse(line);
-
-            let output = match result {
-                Ok(r) => ParseOutput {
-                    input: r.input.clone(),
-                    title: r.title.clone(),
-                    group: r.group.clone(),
-                    season: r.season,
-                    episode: r.episode.as_ref().map(episode_to_value),
-                    resolution: r.resolution.as_ref().map(|x| format!("{:?}", x)),
-                    video_codec: r.video_codec.as_ref().map(|x| format!("{:?}", x)),
-                    audio_codec: r.audio_codec.as_ref().map(|x| format!("{:?}", x)),
-                    source: r.source.as_ref().map(|x| format!("{:?}", x)),
-                    year: r.year,
-                    crc32: r.crc32.clone(),
-                    extension: r.extension.clone(),
-                    version: r.version,
-                    confidence: r.confidence,
-                    mode: "auto".to_string(),
-                    error: None,
-                },
-                Err(e) => ParseOutput {
-                    input: line.to_string(),
-                    title: None,
-                    group: None,
-                    season: None,
-                    episode: None,
-                    resolution: None,
-                    video_codec: None,
-                    audio_codec: None,
-                    source: None,
-                    year: None,
-                    crc32: None,
-                    extension: None,
-                    version: None,
-                    confidence: 0.0,
-                    mode: "auto".to_string(),
-                    error: Some(e.to_string()),
-                },
-            };
+        println!("{}", serde_json::to_string(&output).unwrap());
+    }
+    if failed > 0 {
+        eprintln!("warning: {failed} lines failed to parse");
+    }
 
-            println!("{}", serde_json::to_string(&output).unwrap());
-        }
-    } else {
-        let parser = zantetsu_core::parser::HeuristicParser::new()
-            .expect("Failed to create heuristic parser");
+    Ok(())
+}
 
-        for line in stdin.lock().lines() {
-            let line = line?;
-            let line = line.trim();
-            if line.is_empty() {
+/// Score each engine on the labels at `path`; the neural engine only when
+/// its model loads.
+fn score_engines(path: &Path, json: bool) -> io::Result<()> {
+    let labels = load_labels(path)?;
+    for mode in ["heuristic", "auto", "neural"] {
+        let (config, mode) = engine(mode);
+        let parser = match Parser::new(config) {
+            Ok(parser) if mode != "neural" || parser.has_model() => parser,
+            _ => {
+                eprintln!("warning: neural model unavailable, skipping the neural engine");
                 continue;
             }
+        };
 
-            let result = parser.parse(line);
-
-            let output = match result {
-                Ok(r) => ParseOutput {
-                    input: r.input.clone(),
-                    title: r.title.clone(),
-                    group: r.group.clone(),
-                    season: r.season,
-                    episode: r.episode.as_ref().map(episode_to_value),
-                    resolution: r.resolution.as_ref().map(|x| format!("{:?}", x)),
-                    video_codec: r.video_codec.as_ref().map(|x| format!("{:?}", x)),
-                    audio_codec: r.audio_codec.as_ref().map(|x| format!("{:?}", x)),
-                    source: r.source.as_ref().map(|x| format!("{:?}", x)),
-                    year: r.year,
-                    crc32: r.crc32.clone(),
-                    extension: r.extension.clone(),
-                    version: r.version,
-                    confidence: r.confidence,
-                    mode: "heuristic".to_string(),
-                    error: None,
-                },
-                Err(e) => ParseOutput {
-                    input: line.to_string(),
-                    title: None,
-                    group: None,
-                    season: None,
-                    episode: None,
-                    resolution: None,
-                    video_codec: None,
-                    audio_codec: None,
-                    source: None,
-                    year: None,
-                    crc32: None,
-                    extension: None,
-                    version: None,
-                    confidence: 0.0,
-                    mode: "heuristic".to_string(),
-                    error: Some(e.to_string()),
-                },
+        let mut report = AccuracyReport::new(mode);
+        for label in &labels {
+            let output = match parser.parse(&label.input) {
+                Ok(r) => ParseOutput::from_result(r, mode),
+                Err(e) => ParseOutput::from_error(&label.input, e, mode),
             };
-
-            println!("{}", serde_json::to_string(&output).unwrap());
+            report.add(label, &output);
+        }
+        if json {
+            println!("{}", report.to_json());
+        } else {
+            println!("{report}");
         }
     }
-
     Ok(())
 }
+
+fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+    match cli.labels {
+        Some(path) => score_engines(&path, cli.json),
+        None => parse_stdin(&cli.mode),
+    }
+}
@@ -11,6 +11,7 @@ DUMP_URL="https://f002.backblazeb2.com/file/kitsu-dumps/latest.sql.gz"
 DUMP_DIR="${HOME}/.local/share/zantetsu/kitsu-dumps"
 DUMP_FILE="${DUMP_DIR}/latest.sql.gz"
 SQL_FILE="${DUMP_DIR}/latest.sql"
+EXPORT_FILE="${KITSU_EXPORT_FILE:-${DUMP_DIR}/catalog.jsonl}"
 
 # Database configuration
 DB_NAME="${KITSU_DB_NAME:-root}"
@@ -50,6 +51,7 @@ Commands:
     download    Download the latest Kitsu database dump
     import      Import the dump to PostgreSQL (requires download first)
     reset       Download and import (full reset)
+    export      Export the imported anime catalog for zantetsu-vecdb
     clean       Remove downloaded dump files
     status      Check download status and database connection
     help        Show this help message
@@ -60,6 +62,7 @@ Environment Variables:
     KITSU_DB_HOST       Database host (default: localhost)
     KITSU_DB_PORT       Database port (default: 5432)
     KITSU_DB_PASSWORD   PostgreSQL password (default: empty)
+    KITSU_EXPORT_FILE   Export target (default: <dump dir>/catalog.jsonl)
     PGPASSWORD          Alternative for password (backward compatible)
 
 Examples:
@@ -244,6 +247,47 @@ import_dump() {
     " 2>/dev/null || log_warn "Could not retrieve statistics"
 }
 
+export_catalog() {
+    log_info "Exporting anime catalog from '${DB_NAME}'..."
+    log_info "Target: ${EXPORT_FILE}"
+
+    if ! check_database_connection; then
+        return 1
+    fi
+
+    # One JSON object per line, in the layout of zantetsu_vecdb::KitsuExportRecord
+    if ! run_psql "${DB_NAME}" -At -o "${EXPORT_FILE}.tmp" -c "
+        SELECT json_build_object(
+            'kitsu_id', a.id,
+            'canonical_title', a.canonical_title,
+            'titles', COALESCE(
+                (SELECT json_agg(t.value) FROM jsonb_each_text(to_jsonb(a.titles)) t
+                 WHERE t.value <> ''),
+                '[]'::json),
+            'synonyms', COALESCE(to_json(a.abbreviated_titles), '[]'::json),
+            'anilist_id', (SELECT m.external_id::int FROM mappings m
+                WHERE m.item_type = 'Anime' AND m.item_id = a.id
+                  AND m.external_site = 'anilist/anime' AND m.external_id ~ '^[0-9]+$'
+                LIMIT 1),
+            'mal_id', (SELECT m.external_id::int FROM mappings m
+                WHERE m.item_type = 'Anime' AND m.item_id = a.id
+                  AND m.external_site = 'myanimelist/anime' AND m.external_id ~ '^[0-9]+$'
+                LIMIT 1),
+            'start_year', EXTRACT(YEAR FROM a.start_date)::int
+        )
+        FROM anime a
+        ORDER BY a.id;
+    "; then
+        log_error "Export failed"
+        rm -f "${EXPORT_FILE}.tmp"
+        return 1
+    fi
+
+    mv "${EXPORT_FILE}.tmp" "${EXPORT_FILE}"
+    local count=$(wc -l < "${EXPORT_FILE}" | tr -d ' ')
+    log_info "Exported ${count} anime"
+}
+
 clean_dumps() {
     log_info "Cleaning up downloaded dump files..."
     
@@ -331,6 +375,10 @@ case "${1:-help}" in
         check_dependencies
         download_dump && extract_dump && import_dump
         ;;
+    export)
+        check_dependencies
+        export_catalog
+        ;;
     clean)
         clean_dumps
         ;;
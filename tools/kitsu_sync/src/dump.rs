@@ -0,0 +1,773 @@
+//! The Kitsu dump: streaming download, gzip extraction, and import of the
+//! plain-SQL script `pg_dump` writes without going through `psql`.
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use flate2::read::MultiGzDecoder;
+use futures_util::SinkExt;
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+use reqwest::header::{IF_RANGE, RANGE};
+use reqwest::StatusCode;
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::Receiver;
+use tokio::task::JoinHandle;
+use tokio_postgres::error::DbError;
+use tokio_postgres::{Client, CopyInSink};
+use tracing::{debug, info, warn};
+
+use crate::sync::Watermarks;
+
+/// Attempts at a download before giving up.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry, doubled for each further one.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// How long a download may receive nothing before it counts as failed.
+const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// `path` with `suffix` appended, for files still being written.
+pub fn partial_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Stream `url` to `target` through a `.tmp` file, renamed once the
+/// archive has been verified. Returns the downloaded size in bytes.
+///
+/// A `.tmp` file left by an interrupted download is resumed with an HTTP
+/// range request, unless the file on the server has changed since. Network
+/// and server errors are retried with exponential backoff, each retry
+/// resuming where the last one stopped.
+pub async fn download(url: &str, target: &Path) -> Result<u64> {
+    let partial = partial_path(target, ".tmp");
+    let validator = partial_path(target, ".tmp.etag");
+    let client = reqwest::Client::builder()
+        .connect_timeout(STALL_TIMEOUT)
+        .build()?;
+    let progress = ProgressBar::new(0).with_style(
+        ProgressStyle::with_template(
+            "{bar:40.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}, {eta} left)",
+        )?
+        .progress_chars("=> "),
+    );
+
+    let mut attempt = 1;
+    let sha1 = loop {
+        match fetch(&client, url, &partial, &validator, &progress).await {
+            Ok(sha1) => break sha1,
+            Err(e) if attempt < MAX_ATTEMPTS && is_retryable(&e) => {
+                let delay = RETRY_DELAY * 2u32.pow(attempt - 1);
+                progress.suspend(|| {
+                    warn!(
+                        "Download failed ({e:#}); retrying in {}s ({attempt}/{})",
+                        delay.as_secs(),
+                        MAX_ATTEMPTS - 1
+                    )
+                });
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                progress.abandon();
+                return Err(e).with_context(|| format!("Failed to download {url}"));
+            }
+        }
+    };
+    progress.finish_and_clear();
+
+    let check = partial.clone();
+    let verified = tokio::task::spawn_blocking(move || {
+        if let Some(sha1) = sha1 {
+            verify_sha1(&check, &sha1)?;
+        }
+        verify_gzip(&check)
+    })
+    .await?;
+    if let Err(e) = verified {
+        let _ = std::fs::remove_file(&partial);
+        let _ = std::fs::remove_file(&validator);
+        return Err(e);
+    }
+    let _ = std::fs::remove_file(&validator);
+    tokio::fs::rename(&partial, target).await?;
+    Ok(std::fs::metadata(target)?.len())
+}
+
+/// Download the rest of `url` into `partial`, from its current size on.
+/// Returns the SHA-1 the server reports for the whole file, if any.
+async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+    partial: &Path,
+    validator: &Path,
+    progress: &ProgressBar,
+) -> Result<Option<String>> {
+    let offset = tokio::fs::metadata(partial)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+        // Makes the server send the whole file if it changed since
+        if let Ok(etag) = tokio::fs::read_to_string(validator).await {
+            request = request.header(IF_RANGE, etag.trim());
+        }
+    }
+
+    let response = request.send().await?;
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file is already complete
+        progress.set_length(offset);
+        progress.set_position(offset);
+        return Ok(None);
+    }
+    let mut response = response.error_for_status()?;
+
+    let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+    let start = if resumed { offset } else { 0 };
+    if resumed {
+        progress.suspend(|| info!("Resuming the download at {}", HumanBytes(offset)));
+    }
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    match header("etag").or_else(|| header("last-modified")) {
+        Some(etag) => tokio::fs::write(validator, etag).await?,
+        None => {
+            let _ = tokio::fs::remove_file(validator).await;
+        }
+    }
+    // Backblaze B2 reports the SHA-1 of the whole file, for files uploaded
+    // in parts under a separate header
+    let sha1 = header("x-bz-content-sha1")
+        .filter(|sha1| sha1 != "none")
+        .or_else(|| header("x-bz-info-large_file_sha1"));
+
+    let expected = response.content_length().map(|len| start + len);
+    progress.set_length(expected.unwrap_or(0));
+    progress.set_position(start);
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(partial)
+        .await
+        .with_context(|| format!("Failed to open {}", partial.display()))?;
+    loop {
+        let chunk = tokio::time::timeout(STALL_TIMEOUT, response.chunk())
+            .await
+            .map_err(|_| {
+                Interrupted(format!("received nothing for {}s", STALL_TIMEOUT.as_secs()))
+            })??;
+        let Some(chunk) = chunk else { break };
+        file.write_all(&chunk).await?;
+        progress.inc(chunk.len() as u64);
+    }
+    file.flush().await?;
+
+    let written = file.metadata().await?.len();
+    if let Some(expected) = expected.filter(|&expected| expected != written) {
+        bail!(Interrupted(format!(
+            "download ended at {written} of {expected} bytes"
+        )));
+    }
+    Ok(sha1)
+}
+
+/// A download that stopped before the end of the file.
+#[derive(Debug)]
+struct Interrupted(String);
+
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Whether a failed download attempt is worth retrying: network errors,
+/// server errors and interrupted bodies are; local I/O and client errors
+/// are not.
+fn is_retryable(error: &anyhow::Error) -> bool {
+    if error.downcast_ref::<Interrupted>().is_some() {
+        return true;
+    }
+    match error.downcast_ref::<reqwest::Error>() {
+        Some(e) => e.status().is_none_or(|status| {
+            status.is_server_error()
+                || status == StatusCode::REQUEST_TIMEOUT
+                || status == StatusCode::TOO_MANY_REQUESTS
+        }),
+        None => false,
+    }
+}
+
+/// Check the SHA-1 of `path` against the hex digest `expected`.
+pub fn verify_sha1(path: &Path, expected: &str) -> Result<()> {
+    let mut hasher = Sha1::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    let actual = hex(&hasher.finalize());
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!(
+            "Checksum mismatch for {}: expected SHA-1 {expected}, got {actual}",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Decompress all of `archive`, failing if it is not valid gzip.
+pub fn verify_gzip(archive: &Path) -> Result<()> {
+    let mut decoder = MultiGzDecoder::new(BufReader::new(File::open(archive)?));
+    io::copy(&mut decoder, &mut io::sink())
+        .with_context(|| format!("{} is not a valid gzip archive", archive.display()))?;
+    Ok(())
+}
+
+/// Decompress `archive` to `target`. Returns the extracted size in bytes.
+pub fn extract(archive: &Path, target: &Path) -> Result<u64> {
+    let partial = partial_path(target, ".tmp");
+    let mut decoder = MultiGzDecoder::new(BufReader::new(
+        File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?,
+    ));
+    let mut output = BufWriter::new(File::create(&partial)?);
+    let written = io::copy(&mut decoder, &mut output)
+        .and_then(|written| io::Write::flush(&mut output).map(|()| written));
+    drop(output);
+    match written {
+        Ok(written) => {
+            std::fs::rename(&partial, target)?;
+            Ok(written)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            Err(e).with_context(|| format!("Failed to extract {}", archive.display()))
+        }
+    }
+}
+
+/// The SQL script: `sql` if it was extracted, else `archive` decompressed
+/// on the fly.
+pub fn open_script(sql: &Path, archive: &Path) -> Result<Box<dyn BufRead + Send>> {
+    if sql.exists() {
+        return Ok(Box::new(BufReader::new(File::open(sql)?)));
+    }
+    let archive_file = File::open(archive).with_context(|| {
+        format!(
+            "No dump at {}; run `kitsu-sync download` first",
+            archive.display()
+        )
+    })?;
+    Ok(Box::new(BufReader::new(MultiGzDecoder::new(
+        BufReader::new(archive_file),
+    ))))
+}
+
+/// The rows of a `COPY ... FROM stdin` in progress.
+pub type CopySink = Pin<Box<CopyInSink<Bytes>>>;
+
+/// Start the `COPY ... FROM stdin` statement `sql`.
+pub async fn copy_in(client: &Client, sql: &str) -> Result<CopySink, tokio_postgres::Error> {
+    client.copy_in(sql).await.map(Box::pin)
+}
+
+/// Counts of an import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub statements: usize,
+    /// Statements the server rejected.
+    pub failed: usize,
+    /// Rows loaded by `COPY`.
+    pub rows: u64,
+    /// Statements left out by the table filter.
+    pub skipped: usize,
+    /// Latest `updated_at` per table, for incremental syncs.
+    pub watermarks: BTreeMap<String, String>,
+}
+
+/// Run the statements of `script` that `tables` keeps against `client`.
+///
+/// As with `psql -f`, a statement the server rejects is logged and the
+/// import goes on; only a lost connection or an unreadable script stops it.
+pub async fn import(
+    client: &Client,
+    script: Box<dyn BufRead + Send>,
+    tables: &TableFilter,
+) -> Result<ImportReport> {
+    let (mut receiver, reader) = read_script(script);
+    let mut report = ImportReport::default();
+    let mut watermarks = Watermarks::default();
+    // The COPY whose rows are coming, if the server accepted it
+    let mut copy: Option<CopySink> = None;
+    while let Some(item) = receiver.recv().await {
+        match item {
+            ScriptItem::Statement(sql) => {
+                if !tables.keeps(&sql) {
+                    // Drops the rows of a COPY too
+                    copy = None;
+                    report.skipped += 1;
+                    continue;
+                }
+                report.statements += 1;
+                let result = if is_copy_from_stdin(&sql) {
+                    copy_in(client, &sql).await.map(|sink| {
+                        watermarks.start(&sql);
+                        copy = Some(sink);
+                    })
+                } else {
+                    client.batch_execute(&sql).await
+                };
+                if let Err(e) = result {
+                    report.failed += 1;
+                    tolerate(e)?;
+                }
+            }
+            ScriptItem::CopyRow(row) => {
+                let Some(sink) = &mut copy else {
+                    continue;
+                };
+                watermarks.observe(&row);
+                if let Err(e) = sink.feed(Bytes::from(row)).await {
+                    copy = None;
+                    report.failed += 1;
+                    tolerate(e)?;
+                }
+            }
+            ScriptItem::CopyEnd => {
+                if let Some(mut sink) = copy.take() {
+                    match sink.as_mut().finish().await {
+                        Ok(rows) => report.rows += rows,
+                        Err(e) => {
+                            report.failed += 1;
+                            tolerate(e)?;
+                        }
+                    }
+                }
+            }
+            ScriptItem::MetaCommand(command) => debug!("Skipping psql command {command}"),
+        }
+    }
+    reader.await??;
+    report.watermarks = watermarks.tables;
+    Ok(report)
+}
+
+/// Split `script` on a blocking thread, sending its items as they come.
+pub fn read_script(
+    script: Box<dyn BufRead + Send>,
+) -> (Receiver<ScriptItem>, JoinHandle<io::Result<()>>) {
+    let (sender, receiver) = tokio::sync::mpsc::channel(1024);
+    let reader = tokio::task::spawn_blocking(move || {
+        for item in ScriptReader::new(script) {
+            if sender.blocking_send(item?).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    });
+    (receiver, reader)
+}
+
+/// Log a statement the server rejected; any other error is returned.
+fn tolerate(error: tokio_postgres::Error) -> Result<()> {
+    warn!("{}", server_error(error)?);
+    Ok(())
+}
+
+/// The server's report of a rejected statement in `error`; a lost
+/// connection or any other error is returned as is.
+pub fn server_error(error: tokio_postgres::Error) -> Result<DbError> {
+    match error.as_db_error() {
+        Some(e) => Ok(e.clone()),
+        None => Err(error.into()),
+    }
+}
+
+pub fn is_copy_from_stdin(sql: &str) -> bool {
+    let sql = sql.trim();
+    sql.get(..5)
+        .is_some_and(|head| head.eq_ignore_ascii_case("COPY "))
+        && sql.to_ascii_lowercase().ends_with("from stdin;")
+}
+
+/// The tables of the dump to import; empty imports them all.
+#[derive(Debug, Clone, Default)]
+pub struct TableFilter {
+    tables: Vec<String>,
+}
+
+impl TableFilter {
+    /// A filter for `tables`, named with or without their schema.
+    pub fn new(tables: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let mut tables: Vec<String> = tables
+            .into_iter()
+            .map(|table| bare_name(table.as_ref()))
+            .filter(|table| !table.is_empty())
+            .collect();
+        tables.sort();
+        tables.dedup();
+        Self { tables }
+    }
+
+    /// The selected tables, without schema; empty for all.
+    pub fn tables(&self) -> &[String] {
+        &self.tables
+    }
+
+    pub fn includes(&self, table: &str) -> bool {
+        self.tables.is_empty() || self.tables.contains(&bare_name(table))
+    }
+
+    /// Whether to run `sql`. Statements about other tables, and foreign
+    /// keys to them, are left out. Statements about no table (types,
+    /// functions, sequences, settings) always run.
+    pub fn keeps(&self, sql: &str) -> bool {
+        if self.tables.is_empty() {
+            return true;
+        }
+        let words: Vec<&str> = sql.split_whitespace().collect();
+        subject_table(&words).is_none_or(|table| self.includes(table))
+            && words
+                .windows(2)
+                .filter(|pair| pair[0].eq_ignore_ascii_case("REFERENCES"))
+                .all(|pair| self.includes(pair[1]))
+    }
+}
+
+/// `name` without schema, quotes or a trailing column list, lowercased.
+fn bare_name(name: &str) -> String {
+    let name = name.split('(').next().unwrap_or_default();
+    let name = name.rsplit('.').next().unwrap_or_default();
+    name.trim_matches('"').to_ascii_lowercase()
+}
+
+/// The table a `pg_dump` statement, split into `words`, is about.
+fn subject_table<'a>(words: &[&'a str]) -> Option<&'a str> {
+    let is = |i: usize, keyword: &str| {
+        words
+            .get(i)
+            .is_some_and(|w| w.eq_ignore_ascii_case(keyword))
+    };
+    let skip = |i: usize, keyword: &str| if is(i, keyword) { i + 1 } else { i };
+    let without_column = |name: &'a str| name.rsplit_once('.').map_or(name, |(table, _)| table);
+
+    if is(0, "CREATE") {
+        let i = skip(skip(1, "UNLOGGED"), "UNIQUE");
+        if is(i, "TABLE") {
+            return words.get(i + 1).copied();
+        }
+        if is(i, "INDEX") || is(i, "TRIGGER") {
+            let on = (i..words.len()).find(|&j| is(j, "ON"))?;
+            return words.get(skip(on + 1, "ONLY")).copied();
+        }
+    } else if is(0, "ALTER") && is(1, "TABLE") {
+        return words.get(skip(2, "ONLY")).copied();
+    } else if is(0, "ALTER") && is(1, "SEQUENCE") && is(3, "OWNED") {
+        return words.get(5).copied().map(without_column);
+    } else if is(0, "COPY") {
+        return words.get(1).copied();
+    } else if is(0, "COMMENT") && is(1, "ON") {
+        if is(2, "TABLE") {
+            return words.get(3).copied();
+        }
+        if is(2, "COLUMN") {
+            return words.get(3).copied().map(without_column);
+        }
+    }
+    None
+}
+
+/// A piece of a `pg_dump` script.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ScriptItem {
+    /// One or more statements, through the `;` ending the last one.
+    Statement(String),
+    /// A line of the data following `COPY ... FROM stdin`, with its newline.
+    CopyRow(Vec<u8>),
+    /// The `\.` ending the data of a `COPY`.
+    CopyEnd,
+    /// A psql meta-command such as `\connect`, which only psql understands.
+    MetaCommand(String),
+}
+
+/// Splits a SQL script into statements, `COPY` data and psql
+/// meta-commands, line by line.
+pub struct ScriptReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    statement: String,
+    scanner: Scanner,
+    in_copy: bool,
+}
+
+impl<R: BufRead> ScriptReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            statement: String::new(),
+            scanner: Scanner::default(),
+            in_copy: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ScriptReader<R> {
+    type Item = io::Result<ScriptItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => {
+                    let rest = std::mem::take(&mut self.statement);
+                    return (!rest.trim().is_empty()).then_some(Ok(ScriptItem::Statement(rest)));
+                }
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+
+            if self.in_copy {
+                if self.line.trim_ascii_end() == b"\\." {
+                    self.in_copy = false;
+                    return Some(Ok(ScriptItem::CopyEnd));
+                }
+                return Some(Ok(ScriptItem::CopyRow(self.line.clone())));
+            }
+
+            let line = String::from_utf8_lossy(&self.line);
+            if self.statement.is_empty() {
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with("--") {
+                    continue;
+                }
+                if trimmed.starts_with('\\') {
+                    return Some(Ok(ScriptItem::MetaCommand(trimmed.to_string())));
+                }
+            }
+            self.statement.push_str(&line);
+            if self.scanner.ends_statement(&line) {
+                let statement = std::mem::take(&mut self.statement);
+                self.in_copy = is_copy_from_stdin(&statement);
+                return Some(Ok(ScriptItem::Statement(statement)));
+            }
+        }
+    }
+}
+
+/// Where a statement spanning several lines stands: inside a quoted
+/// string or comment, and whether its last token so far is `;`.
+#[derive(Default)]
+struct Scanner {
+    quote: Option<Quote>,
+    comment_depth: usize,
+    ends_with_semicolon: bool,
+}
+
+enum Quote {
+    Single,
+    Double,
+    /// A dollar-quoted body, with its `$tag$`.
+    Dollar(String),
+}
+
+impl Scanner {
+    /// Scan the next line of a statement; true if the statement ends there.
+    fn ends_statement(&mut self, line: &str) -> bool {
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let mut step = c.len_utf8();
+            match &self.quote {
+                // A doubled quote closes and reopens the string
+                Some(Quote::Single) if c == '\'' => self.quote = None,
+                Some(Quote::Double) if c == '"' => self.quote = None,
+                Some(Quote::Dollar(tag)) if rest.starts_with(tag.as_str()) => {
+                    step = tag.len();
+                    self.quote = None;
+                }
+                Some(_) => {}
+                None if self.comment_depth > 0 => {
+                    if rest.starts_with("*/") {
+                        self.comment_depth -= 1;
+                        step = 2;
+                    } else if rest.starts_with("/*") {
+                        self.comment_depth += 1;
+                        step = 2;
+                    }
+                }
+                None => {
+                    if rest.starts_with("--") {
+                        break;
+                    }
+                    if rest.starts_with("/*") {
+                        self.comment_depth += 1;
+                        step = 2;
+                    } else {
+                        if !c.is_whitespace() {
+                            self.ends_with_semicolon = c == ';';
+                        }
+                        if c == '\'' {
+                            self.quote = Some(Quote::Single);
+                        } else if c == '"' {
+                            self.quote = Some(Quote::Double);
+                        } else if let Some(tag) = dollar_tag(rest) {
+                            step = tag.len();
+                            self.quote = Some(Quote::Dollar(tag));
+                        }
+                    }
+                }
+            }
+            rest = &rest[step..];
+        }
+
+        let ended = self.quote.is_none() && self.comment_depth == 0 && self.ends_with_semicolon;
+        if ended {
+            self.ends_with_semicolon = false;
+        }
+        ended
+    }
+}
+
+/// The `$tag$` opening a dollar-quoted string at the start of `sql`.
+fn dollar_tag(sql: &str) -> Option<String> {
+    let body = sql.strip_prefix('$')?;
+    let end = body.find('$')?;
+    let tag = &body[..end];
+    let valid = tag
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_alphabetic() || c == '_')
+        && tag.chars().all(|c| c.is_alphanumeric() || c == '_');
+    valid.then(|| format!("${tag}$"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(script: &str) -> Vec<ScriptItem> {
+        ScriptReader::new(script.as_bytes())
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn splits_pg_dump_scripts() {
+        let script = "\
+--
+-- PostgreSQL database dump
+--
+\\restrict abc123
+SET statement_timeout = 0;
+CREATE FUNCTION public.touch() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $_$
+BEGIN
+  NEW.note := 'a;b';
+  RETURN NEW;
+END;
+$_$;
+
+COMMENT ON TABLE anime IS 'it''s; fine';
+COPY public.anime (id, canonical_title) FROM stdin;
+1\tCowboy Bebop;
+2\t\\N
+\\.
+SELECT 1; /* trailing */
+";
+        assert_eq!(
+            items(script),
+            vec![
+                ScriptItem::MetaCommand("\\restrict abc123".into()),
+                ScriptItem::Statement("SET statement_timeout = 0;\n".into()),
+                ScriptItem::Statement(
+                    "CREATE FUNCTION public.touch() RETURNS trigger\n    LANGUAGE plpgsql\n    \
+                     AS $_$\nBEGIN\n  NEW.note := 'a;b';\n  RETURN NEW;\nEND;\n$_$;\n"
+                        .into()
+                ),
+                ScriptItem::Statement("COMMENT ON TABLE anime IS 'it''s; fine';\n".into()),
+                ScriptItem::Statement(
+                    "COPY public.anime (id, canonical_title) FROM stdin;\n".into()
+                ),
+                ScriptItem::CopyRow(b"1\tCowboy Bebop;\n".to_vec()),
+                ScriptItem::CopyRow(b"2\t\\N\n".to_vec()),
+                ScriptItem::CopyEnd,
+                ScriptItem::Statement("SELECT 1; /* trailing */\n".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn retries_only_transient_failures() {
+        let interrupted = anyhow::Error::new(Interrupted("received nothing".into()));
+        assert!(is_retryable(&interrupted.context("Download failed")));
+        let local = anyhow::Error::new(io::Error::from(io::ErrorKind::StorageFull));
+        assert!(!is_retryable(&local));
+    }
+
+    #[test]
+    fn verifies_sha1() {
+        let path = std::env::temp_dir().join(format!("kitsu-sync-sha1-{}", std::process::id()));
+        std::fs::write(&path, "abc").unwrap();
+        assert!(verify_sha1(&path, "A9993E364706816ABA3E25717850C26C9CD0D89D").is_ok());
+        assert!(verify_sha1(&path, &"0".repeat(40)).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn filters_statements_by_table() {
+        let tables = TableFilter::new(["anime", "public.mappings"]);
+        assert_eq!(tables.tables(), ["anime", "mappings"]);
+        for kept in [
+            "CREATE TABLE public.anime (\n    id integer NOT NULL\n);",
+            "ALTER TABLE ONLY public.mappings ADD CONSTRAINT fk_anime \
+             FOREIGN KEY (item_id) REFERENCES public.anime(id);",
+            "CREATE UNIQUE INDEX index_anime_on_slug ON public.anime USING btree (slug);",
+            "COPY public.anime (id, slug) FROM stdin;",
+            "COMMENT ON COLUMN public.anime.slug IS 'url';",
+            "CREATE SEQUENCE public.users_id_seq START WITH 1;",
+            "SET client_encoding = 'UTF8';",
+        ] {
+            assert!(tables.keeps(kept), "{kept}");
+        }
+        for skipped in [
+            "CREATE TABLE public.users (\n    id integer NOT NULL\n);",
+            "COPY public.library_entries (id, user_id) FROM stdin;",
+            "CREATE INDEX index_users_on_name ON ONLY public.users USING btree (name);",
+            "ALTER TABLE ONLY public.anime ADD CONSTRAINT fk_user \
+             FOREIGN KEY (user_id) REFERENCES public.users(id);",
+            "ALTER SEQUENCE public.users_id_seq OWNED BY public.users.id;",
+            "CREATE TRIGGER touch BEFORE UPDATE ON public.users FOR EACH ROW EXECUTE FUNCTION f();",
+        ] {
+            assert!(!tables.keeps(skipped), "{skipped}");
+        }
+        assert!(TableFilter::default().keeps("COPY public.users (id) FROM stdin;"));
+    }
+
+    #[test]
+    fn dollar_tags() {
+        assert_eq!(dollar_tag("$$ body"), Some("$$".into()));
+        assert_eq!(dollar_tag("$fn$ body"), Some("$fn$".into()));
+        assert_eq!(dollar_tag("$1, $2"), None);
+    }
+}
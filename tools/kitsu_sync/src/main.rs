@@ -4,13 +4,21 @@
 //! Provides both a CLI interface and a library API.
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
-use std::process::Command;
-use tracing::info;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_postgres::{Client, NoTls, SimpleQueryMessage};
+use tracing::{info, warn};
+use zantetsu_vecdb::{HnswParams, IndexBundle, MatchSource, Quantization};
+
+mod dump;
+mod sync;
+
+use dump::TableFilter;
+use sync::SyncState;
 
 /// Kitsu database dump URL
-#[allow(dead_code)]
 const DUMP_URL: &str = "https://f002.backblazeb2.com/file/kitsu-dumps/latest.sql.gz";
 
 /// Default dump directory
@@ -64,18 +72,60 @@ struct Cli {
 enum Commands {
     /// Download the latest database dump
     Download {
-        /// Force re-download if file exists
+        /// Force re-download if file exists, discarding any partial download
         #[arg(short, long)]
         force: bool,
     },
     /// Import the dump to PostgreSQL
     Import {
-        /// Extract before importing (if not already extracted)
+        /// Extract to latest.sql before importing, instead of reading the
+        /// archive directly
         #[arg(short, long)]
         extract: bool,
+        /// Apply only the rows changed since the last import
+        #[arg(short, long)]
+        incremental: bool,
+        /// Import only these tables (comma-separated), with their indexes
+        /// and constraints
+        #[arg(short, long, value_delimiter = ',')]
+        tables: Vec<String>,
+    },
+    /// Download the latest dump and apply only the rows changed since the
+    /// last import
+    Sync {
+        /// Sync only these tables (comma-separated) [default: the tables of
+        /// the last import]
+        #[arg(short, long, value_delimiter = ',')]
+        tables: Vec<String>,
     },
     /// Download and import (full reset)
-    Reset,
+    Reset {
+        /// Import only these tables (comma-separated), with their indexes
+        /// and constraints
+        #[arg(short, long, value_delimiter = ',')]
+        tables: Vec<String>,
+    },
+    /// Export the imported anime catalog as JSON Lines for zantetsu-vecdb
+    Export {
+        /// Output file (defaults to catalog.jsonl in the dump directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Build a zantetsu-vecdb index bundle from the exported catalog or dump
+    BuildIndex {
+        /// Catalog to index
+        #[arg(short, long, value_enum, default_value_t = CatalogFormat::Export)]
+        from: CatalogFormat,
+        /// Catalog path (defaults to the export file or the dump directory)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Output directory (defaults to index/ in the dump directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Store vectors as int8 for a ~4x smaller index
+        #[arg(long)]
+        int8: bool,
+    },
     /// Clean up downloaded files
     Clean {
         /// Skip confirmation
@@ -86,6 +136,34 @@ enum Commands {
     Status,
 }
 
+/// Catalog formats `build-index` can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CatalogFormat {
+    /// catalog.jsonl written by `export`
+    Export,
+    /// latest.sql or latest.sql.gz
+    Dump,
+    /// anime-offline-database JSON release
+    OfflineDatabase,
+}
+
+impl CatalogFormat {
+    /// Match source for the catalog at `input`, or at its default location
+    fn source(self, input: Option<PathBuf>, manager: &KitsuDumpManager) -> MatchSource {
+        match self {
+            Self::Export => {
+                MatchSource::kitsu_export(input.unwrap_or_else(|| manager.export_file_path()))
+            }
+            Self::Dump => {
+                MatchSource::kitsu_dump(input.unwrap_or_else(|| manager.dump_dir.clone()))
+            }
+            Self::OfflineDatabase => {
+                MatchSource::offline_database(input.unwrap_or_else(|| manager.dump_dir.clone()))
+            }
+        }
+    }
+}
+
 /// Database configuration
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
@@ -105,18 +183,105 @@ impl DatabaseConfig {
         )
     }
 
-    /// Check if database is accessible
+    /// Check if the server accepts connections
     pub async fn check_connection(&self) -> Result<bool> {
-        // We'll use the shell script for now
-        // In a full implementation, this would use sqlx or tokio-postgres
-        Ok(true)
+        Ok(self.connect_to("postgres").await.is_ok())
+    }
+
+    /// Connect to the configured database
+    pub async fn connect(&self) -> Result<Client> {
+        self.connect_to(&self.database)
+            .await
+            .with_context(|| format!("Failed to connect to {}", self.connection_string()))
+    }
+
+    /// Connect to `database` on the configured server
+    async fn connect_to(&self, database: &str) -> Result<Client> {
+        let mut config = tokio_postgres::Config::new();
+        config
+            .host(&self.host)
+            .port(self.port)
+            .user(&self.user)
+            .dbname(database);
+        if let Some(password) = &self.password {
+            config.password(password);
+        }
+        let (client, connection) = config.connect(NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!("Database connection closed: {e}");
+            }
+        });
+        Ok(client)
+    }
+
+    /// Create the configured database unless it exists
+    pub async fn ensure_database(&self) -> Result<()> {
+        let client = self
+            .connect_to("postgres")
+            .await
+            .context("Failed to connect to the postgres database")?;
+        let exists = client
+            .query_opt(
+                "SELECT 1 FROM pg_database WHERE datname = $1",
+                &[&self.database],
+            )
+            .await?
+            .is_some();
+        if exists {
+            info!("Database '{}' already exists", self.database);
+        } else {
+            info!("Creating database '{}'", self.database);
+            client
+                .batch_execute(&format!(
+                    "CREATE DATABASE {};",
+                    quote_identifier(&self.database)
+                ))
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to create database '{}'; does user '{}' have the CREATEDB privilege?",
+                        self.database, self.user
+                    )
+                })?;
+        }
+        Ok(())
     }
 }
 
+fn quote_identifier(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// One JSON object per anime, in the layout of `zantetsu_vecdb::KitsuExportRecord`
+const EXPORT_QUERY: &str = "
+    SELECT json_build_object(
+        'kitsu_id', a.id,
+        'canonical_title', a.canonical_title,
+        'titles', COALESCE(
+            (SELECT json_agg(t.value) FROM jsonb_each_text(to_jsonb(a.titles)) t
+             WHERE t.value <> ''),
+            '[]'::json),
+        'synonyms', COALESCE(to_json(a.abbreviated_titles), '[]'::json),
+        'anilist_id', (SELECT m.external_id::int FROM mappings m
+            WHERE m.item_type = 'Anime' AND m.item_id = a.id
+              AND m.external_site = 'anilist/anime' AND m.external_id ~ '^[0-9]+$'
+            LIMIT 1),
+        'mal_id', (SELECT m.external_id::int FROM mappings m
+            WHERE m.item_type = 'Anime' AND m.item_id = a.id
+              AND m.external_site = 'myanimelist/anime' AND m.external_id ~ '^[0-9]+$'
+            LIMIT 1),
+        'start_year', EXTRACT(YEAR FROM a.start_date)::int
+    )
+    FROM anime a
+    ORDER BY a.id;";
+
+/// Tables whose row counts are shown after an import
+const STATISTICS_TABLES: [&str; 4] = ["anime", "characters", "episodes", "manga"];
+
 /// Kitsu dump manager
 pub struct KitsuDumpManager {
     dump_dir: PathBuf,
-    #[allow(dead_code)]
     db_config: DatabaseConfig,
 }
 
@@ -148,6 +313,328 @@ impl KitsuDumpManager {
     pub fn sql_exists(&self) -> bool {
         self.sql_file_path().exists()
     }
+
+    /// Get path to the exported catalog read by `zantetsu-vecdb`
+    pub fn export_file_path(&self) -> PathBuf {
+        self.dump_dir.join("catalog.jsonl")
+    }
+
+    /// Get path to the index bundle written by `build-index`
+    pub fn index_dir_path(&self) -> PathBuf {
+        self.dump_dir.join("index")
+    }
+
+    /// Download the dump unless it is already there, resuming an
+    /// interrupted download unless `force` is set
+    pub async fn download(&self, force: bool) -> Result<()> {
+        let target = self.dump_file_path();
+        if self.dump_exists() && !force {
+            info!(
+                "Dump already downloaded to {}; use --force to download it again",
+                target.display()
+            );
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dump_dir)?;
+        if force {
+            for suffix in [".tmp", ".tmp.etag"] {
+                let _ = std::fs::remove_file(dump::partial_path(&target, suffix));
+            }
+        }
+        info!("Downloading {} to {}...", DUMP_URL, target.display());
+        let size = dump::download(DUMP_URL, &target).await?;
+        info!("Downloaded {} MiB", size >> 20);
+
+        // An SQL file extracted from the previous dump would shadow the new one
+        if self.sql_exists() {
+            std::fs::remove_file(self.sql_file_path())?;
+            info!("Removed the SQL file extracted from the previous dump");
+        }
+        Ok(())
+    }
+
+    /// Extract the dump to the SQL file
+    pub async fn extract(&self) -> Result<()> {
+        let (archive, target) = (self.dump_file_path(), self.sql_file_path());
+        info!("Extracting {}...", archive.display());
+        let size = tokio::task::spawn_blocking(move || dump::extract(&archive, &target)).await??;
+        info!("Extracted {} MiB", size >> 20);
+        Ok(())
+    }
+
+    /// Import the `tables` of the dump into the database, creating it if
+    /// needed
+    pub async fn import(&self, extract: bool, tables: &TableFilter) -> Result<()> {
+        if extract && !self.sql_exists() {
+            self.extract().await?;
+        }
+        let script = dump::open_script(&self.sql_file_path(), &self.dump_file_path())?;
+        self.db_config.ensure_database().await?;
+
+        info!(
+            "Importing the dump into '{}' (this may take several minutes)...",
+            self.db_config.database
+        );
+        let client = self.db_config.connect().await?;
+        let report = dump::import(&client, script, tables).await?;
+        info!(
+            "Imported {} statements and {} rows",
+            report.statements, report.rows
+        );
+        if report.skipped > 0 {
+            info!(
+                "Skipped {} statements for tables other than {}",
+                report.skipped,
+                tables.tables().join(", ")
+            );
+        }
+        if report.failed > 0 {
+            warn!("{} statements failed", report.failed);
+        }
+        self.save_sync_state(tables, report.watermarks)?;
+
+        // The dump empties search_path for its session, so count on a fresh one
+        drop(client);
+        self.log_statistics(tables).await;
+        Ok(())
+    }
+
+    /// Apply only the rows of the dump changed since the last import, or
+    /// import it in full if this database was never imported or `tables`
+    /// differ from the last import; no `tables` means those of the last
+    /// import
+    pub async fn import_changes(&self, extract: bool, tables: &TableFilter) -> Result<()> {
+        let state = SyncState::load(&self.sync_state_path())?
+            .filter(|state| state.database == self.db_config.database);
+        let Some(mut state) = state else {
+            info!(
+                "No previous import into '{}'; importing the full dump",
+                self.db_config.database
+            );
+            return self.import(extract, tables).await;
+        };
+        let tables = match tables.tables() {
+            [] => TableFilter::new(&state.tables),
+            _ => tables.clone(),
+        };
+        if tables.tables() != state.tables {
+            info!("The tables differ from the last import; importing the full dump");
+            return self.import(extract, &tables).await;
+        }
+        if state.is_current(&self.imported_dump_path()) {
+            info!(
+                "'{}' is already in sync with the dump",
+                self.db_config.database
+            );
+            return Ok(());
+        }
+
+        if extract && !self.sql_exists() {
+            self.extract().await?;
+        }
+        let script = dump::open_script(&self.sql_file_path(), &self.dump_file_path())?;
+        info!(
+            "Applying changes since the last sync to '{}'...",
+            self.db_config.database
+        );
+        let client = self.db_config.connect().await?;
+        let report = sync::import_changes(&client, script, &tables, &mut state.watermarks).await?;
+        sync::log_report(&report);
+        self.save_sync_state(&tables, state.watermarks)?;
+
+        drop(client);
+        self.log_statistics(&tables).await;
+        Ok(())
+    }
+
+    /// Get path to the record of the last import, read by incremental syncs
+    pub fn sync_state_path(&self) -> PathBuf {
+        self.dump_dir.join("sync-state.json")
+    }
+
+    /// The file imports read: the archive, or the SQL file extracted from it
+    fn imported_dump_path(&self) -> PathBuf {
+        if self.dump_exists() {
+            self.dump_file_path()
+        } else {
+            self.sql_file_path()
+        }
+    }
+
+    fn save_sync_state(
+        &self,
+        tables: &TableFilter,
+        watermarks: BTreeMap<String, String>,
+    ) -> Result<()> {
+        SyncState::after_import(
+            &self.db_config.database,
+            &self.imported_dump_path(),
+            tables,
+            watermarks,
+        )?
+        .save(&self.sync_state_path())
+    }
+
+    async fn log_statistics(&self, tables: &TableFilter) {
+        let query = STATISTICS_TABLES
+            .iter()
+            .filter(|table| tables.includes(table))
+            .map(|table| format!("SELECT '{table}', COUNT(*) FROM {table}"))
+            .collect::<Vec<_>>()
+            .join(" UNION ALL ");
+        if query.is_empty() {
+            return;
+        }
+        let statistics = match self.db_config.connect().await {
+            Ok(client) => client.query(&query, &[]).await.map_err(Into::into),
+            Err(e) => Err(e),
+        };
+        match statistics {
+            Ok(rows) => {
+                info!("Database statistics:");
+                for row in rows {
+                    let (table, count): (&str, i64) = (row.get(0), row.get(1));
+                    info!("  {table}: {count}");
+                }
+            }
+            Err(e) => warn!("Could not retrieve statistics: {e}"),
+        }
+    }
+
+    /// Export the imported anime catalog as JSON Lines to `output`
+    pub async fn export(&self, output: &Path) -> Result<()> {
+        info!(
+            "Exporting anime catalog from '{}' to {}...",
+            self.db_config.database,
+            output.display()
+        );
+        let rows = self
+            .db_config
+            .connect()
+            .await?
+            .simple_query(EXPORT_QUERY)
+            .await
+            .context("Export failed")?;
+
+        let mut catalog = String::new();
+        let mut exported = 0;
+        for message in &rows {
+            if let SimpleQueryMessage::Row(row) = message {
+                if let Some(record) = row.get(0) {
+                    catalog.push_str(record);
+                    catalog.push('\n');
+                    exported += 1;
+                }
+            }
+        }
+        let partial = output.with_extension("jsonl.tmp");
+        std::fs::write(&partial, catalog)
+            .with_context(|| format!("Failed to write {}", partial.display()))?;
+        std::fs::rename(&partial, output)?;
+        info!("Exported {exported} anime");
+        Ok(())
+    }
+
+    /// Remove the downloaded and extracted dump
+    pub fn clean(&self) -> Result<()> {
+        let mut removed = 0;
+        for path in [self.dump_file_path(), self.sql_file_path()] {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+                info!("Removed: {}", path.display());
+                removed += 1;
+            }
+        }
+        if removed == 0 {
+            info!("No dump files to clean");
+        } else {
+            info!("Cleaned {removed} file(s)");
+        }
+        Ok(())
+    }
+
+    /// Print the state of the dump files and the database
+    pub async fn status(&self) -> Result<()> {
+        println!("Kitsu Database Dump Status");
+        println!("==========================");
+        match std::fs::metadata(self.dump_file_path()) {
+            Ok(metadata) => {
+                println!("Dump file: {}", self.dump_file_path().display());
+                println!("  Size: {} MiB", metadata.len() >> 20);
+                if let Ok(age) = metadata.modified().map(|m| m.elapsed().unwrap_or_default()) {
+                    println!("  Downloaded: {} days ago", age.as_secs() / 86_400);
+                }
+            }
+            Err(_) => println!("Dump file: Not downloaded"),
+        }
+        if let Ok(metadata) = std::fs::metadata(dump::partial_path(&self.dump_file_path(), ".tmp"))
+        {
+            println!(
+                "  Partial download: {} MiB (resumed by `download`)",
+                metadata.len() >> 20
+            );
+        }
+        match std::fs::metadata(self.sql_file_path()) {
+            Ok(metadata) => {
+                println!("SQL file: {}", self.sql_file_path().display());
+                println!("  Size: {} MiB", metadata.len() >> 20);
+            }
+            Err(_) => println!("SQL file: Not extracted"),
+        }
+        if let Ok(Some(state)) = SyncState::load(&self.sync_state_path()) {
+            let age = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|now| now.as_secs().saturating_sub(state.synced_at))
+                .unwrap_or(0);
+            println!(
+                "Last import: into '{}', {} days ago",
+                state.database,
+                age / 86_400
+            );
+        }
+
+        let db = &self.db_config;
+        println!();
+        println!("Database:");
+        println!("  Name: {}", db.database);
+        println!("  Host: {}:{}", db.host, db.port);
+        println!("  User: {}", db.user);
+        if !db.check_connection().await? {
+            println!("  Status: Cannot connect");
+            return Ok(());
+        }
+        println!("  Status: Connected");
+        match db.connect_to(&db.database).await {
+            Ok(client) => {
+                println!("  Database: Exists");
+                if let Ok(row) = client.query_one("SELECT COUNT(*) FROM anime", &[]).await {
+                    println!("  Anime entries: {}", row.get::<_, i64>(0));
+                }
+            }
+            Err(_) => println!("  Database: Not created"),
+        }
+        Ok(())
+    }
+}
+
+/// Embed and index a catalog, then write the bundle to `output`
+fn build_index(source: MatchSource, output: PathBuf, int8: bool) -> Result<()> {
+    info!("Building title index from {:?}...", source);
+    let mut bundle = IndexBundle::build(source, HnswParams::default())
+        .context("Failed to build the title index")?;
+    if int8 {
+        bundle.quantize(Quantization::Int8)?;
+    }
+    bundle
+        .save(&output)
+        .with_context(|| format!("Failed to write the index to {}", output.display()))?;
+    info!(
+        "Indexed {} anime ({} titles) into {}",
+        bundle.len(),
+        bundle.index().len(),
+        output.display()
+    );
+    Ok(())
 }
 
 #[tokio::main]
@@ -161,54 +648,48 @@ async fn main() -> Result<()> {
         port: cli.port,
         database: cli.database,
         user: cli.user,
-        password: cli.password,
+        password: cli.password.or_else(|| std::env::var("PGPASSWORD").ok()),
     };
 
-    // For now, delegate to the shell script
-    // This provides a stable interface while we implement native Rust version
-    // The script is at tools/kitsu_sync.sh and we're in tools/kitsu_sync/
-    let script_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .parent()
-        .unwrap()
-        .join("kitsu_sync.sh");
-
-    let mut cmd = Command::new(&script_path);
-    cmd.env("KITSU_DB_HOST", &db_config.host)
-        .env("KITSU_DB_PORT", db_config.port.to_string())
-        .env("KITSU_DB_NAME", &db_config.database)
-        .env("KITSU_DB_USER", &db_config.user);
-
-    // Add password if provided
-    if let Some(password) = &db_config.password {
-        cmd.env("KITSU_DB_PASSWORD", password);
-    }
-
-    // Ensure dump directory exists
-    std::fs::create_dir_all(&dump_dir)?;
+    let manager = KitsuDumpManager::new(dump_dir, db_config);
 
     match cli.command {
-        Commands::Download { force } => {
-            info!("Downloading Kitsu database dump...");
-            if force {
-                // Remove existing file to force re-download
-                let dump_file = dump_dir.join("latest.sql.gz");
-                if dump_file.exists() {
-                    std::fs::remove_file(&dump_file)?;
-                    info!("Removed existing dump file");
-                }
+        Commands::Download { force } => manager.download(force).await?,
+        Commands::Import {
+            extract,
+            incremental,
+            tables,
+        } => {
+            let tables = TableFilter::new(tables);
+            if incremental {
+                manager.import_changes(extract, &tables).await?;
+            } else {
+                manager.import(extract, &tables).await?;
             }
-            cmd.arg("download");
         }
-        Commands::Import { extract } => {
-            info!("Importing database dump...");
-            if extract {
-                info!("Will extract before importing");
-            }
-            cmd.arg("import");
+        Commands::Sync { tables } => {
+            manager.download(true).await?;
+            manager
+                .import_changes(false, &TableFilter::new(tables))
+                .await?;
         }
-        Commands::Reset => {
+        Commands::Reset { tables } => {
             info!("Performing full reset (download + import)...");
-            cmd.arg("reset");
+            manager.download(true).await?;
+            manager.import(false, &TableFilter::new(tables)).await?;
+        }
+        Commands::Export { output } => {
+            let output = output.unwrap_or_else(|| manager.export_file_path());
+            manager.export(&output).await?;
+        }
+        Commands::BuildIndex {
+            from,
+            input,
+            output,
+            int8,
+        } => {
+            let output = output.unwrap_or_else(|| manager.index_dir_path());
+            build_index(from.source(input, &manager), output, int8)?;
         }
         Commands::Clean { yes } => {
             if !yes {
@@ -225,17 +706,9 @@ async fn main() -> Result<()> {
                     return Ok(());
                 }
             }
-            cmd.arg("clean");
+            manager.clean()?;
         }
-        Commands::Status => {
-            cmd.arg("status");
-        }
-    }
-
-    let status = cmd.status().context("Failed to execute sync script")?;
-
-    if !status.success() {
-        anyhow::bail!("Sync script failed with exit code: {:?}", status.code());
+        Commands::Status => manager.status().await?,
     }
 
     Ok(())
@@ -244,6 +717,7 @@ async fn main() -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn test_default_dump_dir() {
@@ -266,4 +740,28 @@ mod tests {
         assert!(conn_str.contains("5432"));
         assert!(conn_str.contains("kitsu"));
     }
+
+    #[test]
+    fn test_build_index_defaults() {
+        let manager = KitsuDumpManager::new(
+            PathBuf::from("/data/dumps"),
+            DatabaseConfig {
+                host: "localhost".to_string(),
+                port: 5432,
+                database: "kitsu".to_string(),
+                user: "postgres".to_string(),
+                password: None,
+            },
+        );
+
+        assert_eq!(manager.index_dir_path(), PathBuf::from("/data/dumps/index"));
+        assert!(matches!(
+            CatalogFormat::Export.source(None, &manager),
+            MatchSource::KitsuExport { export_path } if export_path == manager.export_file_path()
+        ));
+        assert!(matches!(
+            CatalogFormat::Dump.source(Some(PathBuf::from("/tmp/latest.sql")), &manager),
+            MatchSource::KitsuDump { dump_path } if dump_path == Path::new("/tmp/latest.sql")
+        ));
+    }
 }
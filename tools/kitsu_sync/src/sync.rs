@@ -0,0 +1,374 @@
+//! Incremental sync: apply only the rows of a new dump that changed since
+//! the last import.
+//!
+//! Kitsu publishes full dumps only, so the dump itself is diffed: each
+//! import records the latest `updated_at` of every table, and an
+//! incremental import upserts just the rows updated after it. Schema
+//! changes and rows deleted upstream are only picked up by a full import.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+use bytes::Bytes;
+use futures_util::SinkExt;
+use tokio_postgres::Client;
+
+use crate::dump::{copy_in, is_copy_from_stdin, read_script, server_error, CopySink};
+use crate::dump::{ScriptItem, TableFilter};
+
+/// What the last import left in the database, kept next to the dump.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    /// Database the dump was imported into.
+    pub database: String,
+    /// When the import finished, in seconds since the Unix epoch.
+    pub synced_at: u64,
+    /// Size and modification time (seconds since the epoch) of the dump
+    /// imported, to notice when there is nothing new.
+    pub dump_size: u64,
+    pub dump_modified: u64,
+    /// Tables imported, without schema; empty for all.
+    #[serde(default)]
+    pub tables: Vec<String>,
+    /// Latest `updated_at` imported per table, as written in the dump.
+    pub watermarks: BTreeMap<String, String>,
+}
+
+impl SyncState {
+    /// The state at `path`, or `None` if there is none.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map(Some)
+                .with_context(|| format!("Invalid sync state in {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// A state for `database` after importing the `tables` of `dump`.
+    pub fn after_import(
+        database: &str,
+        dump: &Path,
+        tables: &TableFilter,
+        watermarks: BTreeMap<String, String>,
+    ) -> Result<Self> {
+        let metadata = std::fs::metadata(dump)?;
+        Ok(Self {
+            database: database.to_string(),
+            synced_at: unix_seconds(SystemTime::now()),
+            dump_size: metadata.len(),
+            dump_modified: metadata.modified().map(unix_seconds).unwrap_or(0),
+            tables: tables.tables().to_vec(),
+            watermarks,
+        })
+    }
+
+    /// Whether `dump` is the one this state was recorded from.
+    pub fn is_current(&self, dump: &Path) -> bool {
+        std::fs::metadata(dump).is_ok_and(|metadata| {
+            metadata.len() == self.dump_size
+                && metadata.modified().map(unix_seconds).unwrap_or(0) == self.dump_modified
+        })
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// The table and columns of a `COPY ... FROM stdin` statement.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CopyTarget {
+    pub table: String,
+    /// Column names as written, quoted where the dump quotes them.
+    pub columns: Vec<String>,
+}
+
+impl CopyTarget {
+    pub fn parse(sql: &str) -> Option<Self> {
+        let rest = sql.trim().get(5..)?;
+        let (table, rest) = rest.split_once(" (")?;
+        let (columns, _) = rest.rsplit_once(") ")?;
+        Some(Self {
+            table: table.trim().to_string(),
+            columns: columns.split(',').map(|c| c.trim().to_string()).collect(),
+        })
+    }
+
+    fn column(&self, name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|column| column.trim_matches('"') == name)
+    }
+
+    /// Index of the `updated_at` field in the rows of this COPY.
+    pub fn updated_at(&self) -> Option<usize> {
+        self.column("updated_at")
+    }
+}
+
+/// Field `index` of a COPY row, unless it is NULL.
+fn field(row: &[u8], index: usize) -> Option<&str> {
+    let row = row.strip_suffix(b"\n").unwrap_or(row);
+    let field = row.split(|&b| b == b'\t').nth(index)?;
+    (field != b"\\N").then(|| std::str::from_utf8(field).ok())?
+}
+
+/// Tracks the latest `updated_at` per table over the COPY data of a dump.
+#[derive(Debug, Default)]
+pub struct Watermarks {
+    pub tables: BTreeMap<String, String>,
+    /// The table being copied and the index of its `updated_at`.
+    current: Option<(String, usize)>,
+}
+
+impl Watermarks {
+    /// Start the data of the COPY `sql`.
+    pub fn start(&mut self, sql: &str) {
+        self.current = CopyTarget::parse(sql)
+            .and_then(|target| Some((target.table.clone(), target.updated_at()?)));
+    }
+
+    /// Ignore rows until the next COPY.
+    pub fn stop(&mut self) {
+        self.current = None;
+    }
+
+    /// Observe one row of the current COPY.
+    pub fn observe(&mut self, row: &[u8]) {
+        let Some((table, index)) = &self.current else {
+            return;
+        };
+        if let Some(updated_at) = field(row, *index) {
+            let mark = self.tables.entry(table.clone()).or_default();
+            // PostgreSQL's text timestamps sort chronologically as strings
+            if updated_at > mark.as_str() {
+                *mark = updated_at.to_string();
+            }
+        }
+    }
+}
+
+/// Counts of an incremental import.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// Tables with changed rows.
+    pub tables: usize,
+    /// Rows inserted or updated.
+    pub rows: u64,
+    /// Tables left out for lacking an `id` or `updated_at` column.
+    pub skipped: Vec<String>,
+    /// Tables whose changes the server rejected.
+    pub failed: Vec<String>,
+}
+
+/// Upsert the rows of the `tables` of `script` updated after the
+/// `watermarks` of the last import, and advance them to the latest
+/// `updated_at` seen.
+///
+/// Each table is applied in its own transaction through a temporary table,
+/// so a table that fails is rolled back and left as it was.
+pub async fn import_changes(
+    client: &Client,
+    script: Box<dyn BufRead + Send>,
+    tables: &TableFilter,
+    watermarks: &mut BTreeMap<String, String>,
+) -> Result<SyncReport> {
+    let (mut receiver, reader) = read_script(script);
+    let mut report = SyncReport::default();
+    let mut seen = Watermarks::default();
+    // The table being applied, its `updated_at` index, its changed rows and
+    // the copy into its temporary table
+    let mut current: Option<(CopyTarget, usize, u64, CopySink)> = None;
+    while let Some(item) = receiver.recv().await {
+        match item {
+            ScriptItem::Statement(sql) => {
+                let Some(target) = CopyTarget::parse(&sql).filter(|_| is_copy_from_stdin(&sql))
+                else {
+                    // The schema is already in place
+                    continue;
+                };
+                if !tables.includes(&target.table) {
+                    seen.stop();
+                    continue;
+                }
+                seen.start(&sql);
+                match (target.column("id"), target.updated_at()) {
+                    (Some(_), Some(index)) => match begin_delta(client, &target).await? {
+                        Some(sink) => current = Some((target, index, 0, sink)),
+                        None => report.failed.push(target.table),
+                    },
+                    _ => report.skipped.push(target.table),
+                }
+            }
+            ScriptItem::CopyRow(row) => {
+                seen.observe(&row);
+                let Some((target, index, changed, sink)) = &mut current else {
+                    continue;
+                };
+                let mark = watermarks.get(&target.table).map_or("", String::as_str);
+                if field(&row, *index).is_none_or(|updated_at| updated_at <= mark) {
+                    continue;
+                }
+                *changed += 1;
+                if let Err(e) = sink.feed(Bytes::from(row)).await {
+                    let (target, ..) = current.take().expect("a table is being applied");
+                    warn!(
+                        "Changes to {} rolled back: {}",
+                        target.table,
+                        server_error(e)?
+                    );
+                    client.batch_execute("ROLLBACK;").await?;
+                    report.failed.push(target.table);
+                }
+            }
+            ScriptItem::CopyEnd => {
+                let Some((target, _, changed, sink)) = current.take() else {
+                    continue;
+                };
+                match apply_delta(client, &target, sink).await {
+                    Ok(()) if changed > 0 => {
+                        debug!("Applied {changed} changed rows to {}", target.table);
+                        report.tables += 1;
+                        report.rows += changed;
+                    }
+                    Ok(()) => {}
+                    Err(e) => {
+                        warn!(
+                            "Changes to {} rolled back: {}",
+                            target.table,
+                            server_error(e)?
+                        );
+                        client.batch_execute("ROLLBACK;").await?;
+                        report.failed.push(target.table);
+                    }
+                }
+            }
+            ScriptItem::MetaCommand(_) => {}
+        }
+    }
+    reader.await??;
+
+    // Failed tables keep their watermark, so the next sync retries them
+    watermarks.extend(
+        seen.tables
+            .into_iter()
+            .filter(|(table, _)| !report.failed.contains(table)),
+    );
+    Ok(report)
+}
+
+/// Open a transaction with an empty copy of `target`'s table and start
+/// copying into it. `None` if the server refused.
+async fn begin_delta(client: &Client, target: &CopyTarget) -> Result<Option<CopySink>> {
+    let setup = format!(
+        "BEGIN; CREATE TEMP TABLE kitsu_sync_delta (LIKE {}) ON COMMIT DROP;",
+        target.table
+    );
+    let copy = format!(
+        "COPY kitsu_sync_delta ({}) FROM stdin;",
+        target.columns.join(", ")
+    );
+    let started = match client.batch_execute(&setup).await {
+        Ok(()) => copy_in(client, &copy).await,
+        Err(e) => Err(e),
+    };
+    match started {
+        Ok(sink) => Ok(Some(sink)),
+        Err(e) => {
+            warn!("Skipping changes to {}: {}", target.table, server_error(e)?);
+            client.batch_execute("ROLLBACK;").await?;
+            Ok(None)
+        }
+    }
+}
+
+/// End the copy into the temporary table and upsert it into the table.
+async fn apply_delta(
+    client: &Client,
+    target: &CopyTarget,
+    mut sink: CopySink,
+) -> Result<(), tokio_postgres::Error> {
+    sink.as_mut().finish().await?;
+    let columns = target.columns.join(", ");
+    let updates = target
+        .columns
+        .iter()
+        .filter(|column| column.trim_matches('"') != "id")
+        .map(|column| format!("{column} = EXCLUDED.{column}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    client
+        .batch_execute(&format!(
+            "INSERT INTO {table} ({columns}) SELECT {columns} FROM kitsu_sync_delta \
+             ON CONFLICT (id) DO UPDATE SET {updates}; COMMIT;",
+            table = target.table
+        ))
+        .await
+}
+
+/// Log a summary of an incremental import.
+pub fn log_report(report: &SyncReport) {
+    info!(
+        "Applied {} changed rows across {} tables",
+        report.rows, report.tables
+    );
+    if !report.skipped.is_empty() {
+        debug!(
+            "Tables without id or updated_at, left as they were: {}",
+            report.skipped.join(", ")
+        );
+    }
+    if !report.failed.is_empty() {
+        warn!(
+            "Changes to {} failed; they are retried by the next sync, or run a full import",
+            report.failed.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_copy_targets() {
+        let target = CopyTarget::parse(
+            "COPY public.anime (id, canonical_title, \"position\", updated_at) FROM stdin;\n",
+        )
+        .unwrap();
+        assert_eq!(target.table, "public.anime");
+        assert_eq!(target.columns[2], "\"position\"");
+        assert_eq!(target.updated_at(), Some(3));
+        assert_eq!(target.column("position"), Some(2));
+    }
+
+    #[test]
+    fn tracks_the_latest_updated_at() {
+        let mut watermarks = Watermarks::default();
+        watermarks.start("COPY public.anime (id, updated_at) FROM stdin;");
+        watermarks.observe(b"1\t2024-03-01 10:00:00.5\n");
+        watermarks.observe(b"2\t2024-03-01 10:00:00.45\n");
+        watermarks.observe(b"3\t\\N\n");
+        watermarks.start("COPY public.tags (id, name) FROM stdin;");
+        watermarks.observe(b"1\t2099-01-01\n");
+
+        assert_eq!(
+            watermarks.tables,
+            BTreeMap::from([("public.anime".into(), "2024-03-01 10:00:00.5".into())])
+        );
+    }
+}